@@ -0,0 +1,248 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Example of the mastering chain of mastering.rs, to a loudness and
+//!              a true peak target, one block at a time.
+//!              Each block of the chain is a function of this example, that runs it
+//!              on the whole file and prints the loudness and the true peak after
+//!              it, so each step can be heard and measured on its own:
+//!
+//!                 detection --> EQ --> gain --> multiband compression --> limiter
+//!                           --> dither
+//!
+//!              One pass of the chain doesn't meet the targets, the compressors and
+//!              the limiter lower the loudness. master_audio() of mastering.rs runs
+//!              the same chain again with the gain and the ceiling corrected, and
+//!              the example writes its output, with the dither of the last block.
+//!              The target is a loudness, not the loudest possible master, the
+//!              platforms that normalize to -14 LUFS turn a louder master down.
+//!
+//!              How to run:
+//!
+//!                 cargo run --release --example master -- <input.wav> <output.wav> [<LUFS>] [<dBTP>]
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use std::env;
+use std::process;
+
+use audio_filters_in_rust::iir_filter::ProcessingBlock; // Trait
+use audio_filters_in_rust::audio_buffer::AudioBuffer;
+use audio_filters_in_rust::audio_io::SampleFormat;
+use audio_filters_in_rust::wav_metadata::read_wav_with_metadata;
+use audio_filters_in_rust::wav_metadata::write_wav_with_metadata;
+use audio_filters_in_rust::diagnostics::analyze_audio;
+use audio_filters_in_rust::diagnostics::make_dc_blocker;
+use audio_filters_in_rust::diagnostics::Correction;
+use audio_filters_in_rust::crossover::CrossoverNetwork;
+use audio_filters_in_rust::dynamics::Compressor;
+use audio_filters_in_rust::dynamics::Limiter;
+use audio_filters_in_rust::dither::TpdfDither;
+use audio_filters_in_rust::loudness::integrated_loudness;
+use audio_filters_in_rust::loudness::true_peak;
+use audio_filters_in_rust::mastering::MasteringSettings;
+use audio_filters_in_rust::mastering::master_audio;
+
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(error) = run(& args) {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
+
+fn run(args: & [String]) -> Result<(), String> {
+    if args.len() < 2 || args.len() > 4 {
+        return Err("Usage: cargo run --release --example master -- <input.wav> <output.wav> [<LUFS>] [<dBTP>]"
+                   .to_string());
+    }
+    let parse = |index: usize, default: f64| match args.get(index) {
+        None => Ok(default),
+        Some(value) => value.parse::<f64>().map_err(|_| format!("Error: invalid number \"{}\"", value)),
+    };
+    let settings = MasteringSettings::new(parse(2, -14.0)?, parse(3, -1.0)?);
+    let (audio, sample_format, metadata) = read_wav_with_metadata(& args[0])?;
+    println!("Mastering {} to {} LUFS and {} dBTP\n", args[0], settings.target_lufs, settings.true_peak_db);
+    print_levels("input", & audio);
+
+    let dc_blocker_cutoff = detection(& audio, & settings)?;
+    let equalized = equalize(& audio, & settings, dc_blocker_cutoff)?;
+    print_levels("EQ", & equalized);
+    let gain_db = settings.target_lufs - integrated_loudness(& equalized);
+    let louder = apply_gain(& equalized, gain_db);
+    print_levels(& format!("gain {:+.1} dB", gain_db), & louder);
+    let compressed = compress_bands(& louder, & settings)?;
+    print_levels("multiband compression", & compressed);
+    let limited = limit(& compressed, & settings, settings.true_peak_db);
+    print_levels("limiter", & limited);
+
+    // The chain again, until both targets are met.
+    let (mut mastered, report) = master_audio(& audio, & settings)?;
+    println!("\nAfter {} passes, gain {:+.1} dB and ceiling {:.2} dB:", report.passes, report.gain_db,
+             report.limiter_ceiling_db);
+    print_levels("master", & mastered);
+
+    dither(& mut mastered, sample_format.get_bits_per_sample() as u32, sample_format.is_float());
+    write_wav_with_metadata(& args[1], & mastered, sample_format, & metadata)?;
+    println!("\nWritten to {}", args[1]);
+    Ok(())
+}
+
+fn print_levels(stage: & str, audio: & AudioBuffer) {
+    let true_peak_db = audio.channels.iter()
+        .map(|channel| 20.0 * f64::log10(true_peak(channel)))
+        .fold(f64::NEG_INFINITY, f64::max);
+    println!("  {:<28} {:>7.2} LUFS  {:>7.2} dBTP", stage, integrated_loudness(audio), true_peak_db);
+}
+
+/// Block 1, detection: the diagnostics of the input, its loudness, its true peak and
+/// its DC offset. A DC offset wastes headroom and makes the limiter work on one
+/// side of the waveform, the chain removes it with the DC blocker of the
+/// corrections, a first order high-pass of a few Hz. Silence, or audio shorter
+/// than a gating block of 400 ms, has no loudness and can't be mastered.
+fn detection(audio: & AudioBuffer, settings: & MasteringSettings) -> Result<Option<f64>, String> {
+    // The samples in memory are f64, not the integer format of the file.
+    let diagnostics = analyze_audio(audio, SampleFormat::Float64);
+    println!("  detection: {:.2} LUFS, {:.2} dBTP, {} clipped samples", diagnostics.loudness_lufs,
+             diagnostics.get_true_peak_db(), diagnostics.get_clipped_samples());
+    if !diagnostics.loudness_lufs.is_finite() {
+        return Err("Error: can't master silence, or audio shorter than 400 ms".to_string());
+    }
+    let cutoff = diagnostics.corrections.iter().find_map(|correction| match correction {
+        Correction::DcBlocker { cutoff_hz } => Some(*cutoff_hz),
+        _ => None,
+    });
+    if let Some(cutoff_hz) = cutoff {
+        println!("  detection: DC offset, DC blocker at {} Hz", cutoff_hz);
+    }
+    // The gain to the target, before the blocks that change the loudness.
+    println!("  detection: {:+.1} dB to the target of {} LUFS", settings.target_lufs - diagnostics.loudness_lufs,
+             settings.target_lufs);
+    Ok(cutoff)
+}
+
+/// Block 2, EQ: the DC blocker of the detection, and the parametric equalizer of the
+/// settings. The default EQ is a 25 Hz high-pass, the subsonic content isn't
+/// heard on most speakers but takes the headroom of the limiter. Each channel
+/// has its own filters, with their own state.
+fn equalize(audio: & AudioBuffer, settings: & MasteringSettings, dc_blocker_cutoff: Option<f64>)
+        -> Result<AudioBuffer, String> {
+    let channels = audio.channels.iter()
+        .map(|channel| {
+            let mut eq = settings.eq.to_parametric_eq(audio.sample_rate)?;
+            let mut dc_blocker = dc_blocker_cutoff.map(|cutoff| make_dc_blocker(cutoff, audio.sample_rate));
+            Ok(channel.iter()
+                .map(|x| {
+                    let x = match & mut dc_blocker {
+                        Some(filter) => filter.process(*x),
+                        None => *x,
+                    };
+                    eq.process(x)
+                })
+                .collect())
+        })
+        .collect::<Result<Vec<Vec<f64>>, String>>()?;
+    Ok(audio.with_channels(audio.sample_rate, channels))
+}
+
+/// Block 3, gain: the gain from the loudness after the EQ to the target. The peaks go
+/// above full scale, the next blocks bring them down.
+fn apply_gain(audio: & AudioBuffer, gain_db: f64) -> AudioBuffer {
+    let gain = f64::powf(10.0, gain_db / 20.0);
+    let channels = audio.channels.iter()
+        .map(|channel| channel.iter().map(|x| x * gain).collect())
+        .collect();
+    audio.with_channels(audio.sample_rate, channels)
+}
+
+/// Block 4, multiband compression: a Linkwitz-Riley crossover splits each channel in
+/// three bands, at 200 Hz and 3 kHz by default, whose sum is flat. Each band has
+/// a gentle 2:1 soft knee compressor a little below the target loudness, linked
+/// across the channels so the stereo image doesn't move, and a loud kick drum
+/// only compresses the low band, it doesn't pump the vocals.
+fn compress_bands(audio: & AudioBuffer, settings: & MasteringSettings) -> Result<AudioBuffer, String> {
+    let num_channels = audio.get_num_channels();
+    let mut crossovers = (0..num_channels)
+        .map(|_| CrossoverNetwork::new(audio.sample_rate, & settings.crossover_frequencies))
+        .collect::<Result<Vec<CrossoverNetwork>, String>>()?;
+    let num_bands = crossovers[0].get_num_bands();
+    let mut compressors: Vec<Compressor> = (0..num_bands)
+        .map(|_| {
+            let mut compressor = Compressor::new(audio.sample_rate, settings.target_lufs + settings.band_threshold_db,
+                                                 settings.band_ratio, 10.0, 150.0);
+            compressor.set_knee_db(6.0);
+            compressor
+        })
+        .collect();
+    // bands[band][channel]
+    let mut bands = vec![vec![0.0; num_channels]; num_bands];
+    let mut split = vec![0.0; num_bands];
+    let mut frame = vec![0.0; num_channels];
+    let mut output = vec![Vec::with_capacity(audio.get_num_frames()); num_channels];
+    for n in 0..audio.get_num_frames() {
+        for (channel, crossover) in crossovers.iter_mut().enumerate() {
+            crossover.split(audio.channels[channel][n], & mut split);
+            for (band, value) in split.iter().enumerate() {
+                bands[band][channel] = *value;
+            }
+        }
+        // The sum of the compressed bands.
+        frame.iter_mut().for_each(|x| *x = 0.0);
+        for (band, compressor) in bands.iter_mut().zip(& mut compressors) {
+            compressor.process_frame(band);
+            for (x, y) in frame.iter_mut().zip(band.iter()) {
+                *x += y;
+            }
+        }
+        for (channel, x) in output.iter_mut().zip(& frame) {
+            channel.push(*x);
+        }
+    }
+    Ok(audio.with_channels(audio.sample_rate, output))
+}
+
+/// Block 5, limiter: a look-ahead limiter at the ceiling, linked across the channels.
+/// It sees the peaks before they arrive, so it lowers the gain smoothly instead
+/// of clipping them. Its look-ahead is a latency, removed from the output. It
+/// limits the samples, the peaks between them, the true peak, can still be a
+/// little above the ceiling, master_audio() lowers the ceiling by the excess.
+fn limit(audio: & AudioBuffer, settings: & MasteringSettings, ceiling_db: f64) -> AudioBuffer {
+    let num_channels = audio.get_num_channels();
+    let mut limiter = Limiter::new(audio.sample_rate, num_channels, ceiling_db, settings.limiter_lookahead_ms,
+                                   settings.limiter_release_ms);
+    let latency = limiter.get_latency_samples();
+    let mut output = vec![Vec::with_capacity(audio.get_num_frames()); num_channels];
+    let mut frame = vec![0.0; num_channels];
+    for n in 0..audio.get_num_frames() + latency {
+        for (x, channel) in frame.iter_mut().zip(& audio.channels) {
+            *x = channel.get(n).copied().unwrap_or(0.0);
+        }
+        limiter.process_frame(& mut frame);
+        if n >= latency {
+            for (channel, x) in output.iter_mut().zip(& frame) {
+                channel.push(*x);
+            }
+        }
+    }
+    audio.with_channels(audio.sample_rate, output)
+}
+
+/// Block 6, dither: TPDF dither of the bit depth of the output, for an integer format.
+/// The quiet fades of a 16 bit master then end in a constant hiss, not in the
+/// distortion of the rounding. Each channel has its own seed, the noise of the
+/// channels isn't correlated. A float output isn't dithered.
+fn dither(audio: & mut AudioBuffer, bits: u32, is_float: bool) {
+    if is_float {
+        return;
+    }
+    for (index, channel) in audio.channels.iter_mut().enumerate() {
+        let mut dither = TpdfDither::new(bits, index as u32 + 1);
+        channel.iter_mut().for_each(|x| *x = dither.process(*x));
+    }
+}
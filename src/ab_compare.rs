@@ -0,0 +1,239 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: A/B comparison of two settings, with gain-matched switching.
+//!              The comparator holds two processors, A and B, with two settings of
+//!              the same effect. Both process the input all the time, so their filter
+//!              states are always warm, and the switch is a short linear crossfade
+//!              between the two outputs, without a click.
+//!
+//!              The louder setting nearly always sounds better, so an honest
+//!              comparison needs the same loudness. The loudness match measures the
+//!              integrated loudness (LUFS) of the two outputs on a program and
+//!              applies the difference as a trim gain to B.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::audio_buffer::AudioBuffer;
+use crate::loudness::integrated_loudness;
+use crate::snapshot::Snapshot;
+
+
+/// Default crossfade time of a switch.
+pub const DEFAULT_CROSSFADE_MS: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbSlot {
+    A,
+    B,
+}
+
+/// Two processors with a click-free switch between their outputs.
+pub struct AbComparator<P: ProcessingBlock> {
+    sample_rate:        u32,
+    processor_a:        P,
+    processor_b:        P,
+    selected:           AbSlot,
+    // 0.0 is all A and 1.0 is all B.
+    mix:                f64,
+    mix_step:           f64,
+    match_gain_db:      f64,
+    match_gain:         f64,
+    loudness_matching:  bool,
+}
+
+impl<P: ProcessingBlock> AbComparator<P> {
+    /// Starts with A selected and the loudness matching off.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let mut ab = AbComparator::new(eq_setting_a, eq_setting_b, 48_000, DEFAULT_CROSSFADE_MS);
+    ///    >>> ab.match_loudness(& program)?;
+    ///    >>> ab.set_loudness_matching(true);
+    ///    >>> ab.toggle();
+    ///    ```
+    ///
+    pub fn new(processor_a: P, processor_b: P, sample_rate: u32, crossfade_ms: f64) -> Self {
+        assert!(crossfade_ms >= 0.0);
+        let crossfade_samples = (crossfade_ms * sample_rate as f64 / 1_000.0).max(1.0);
+        AbComparator {
+            sample_rate,
+            processor_a,
+            processor_b,
+            selected: AbSlot::A,
+            mix: 0.0,
+            mix_step: 1.0 / crossfade_samples,
+            match_gain_db: 0.0,
+            match_gain: 1.0,
+            loudness_matching: false,
+        }
+    }
+
+    pub fn get_selected(& self) -> AbSlot {
+        self.selected
+    }
+
+    /// Switches to the slot, with a crossfade.
+    pub fn select(& mut self, slot: AbSlot) {
+        self.selected = slot;
+    }
+
+    pub fn toggle(& mut self) {
+        self.selected = match self.selected {
+            AbSlot::A => AbSlot::B,
+            AbSlot::B => AbSlot::A,
+        };
+    }
+
+    pub fn get_processor(& self, slot: AbSlot) -> & P {
+        match slot {
+            AbSlot::A => & self.processor_a,
+            AbSlot::B => & self.processor_b,
+        }
+    }
+
+    pub fn get_processor_mut(& mut self, slot: AbSlot) -> & mut P {
+        match slot {
+            AbSlot::A => & mut self.processor_a,
+            AbSlot::B => & mut self.processor_b,
+        }
+    }
+
+    /// Trim gain of B, in dB.
+    pub fn get_match_gain_db(& self) -> f64 {
+        self.match_gain_db
+    }
+
+    pub fn set_match_gain_db(& mut self, gain_db: f64) {
+        self.match_gain_db = gain_db;
+        self.match_gain = f64::powf(10.0, gain_db / 20.0);
+    }
+
+    /// Turns on or off the trim gain of B.
+    pub fn set_loudness_matching(& mut self, enabled: bool) {
+        self.loudness_matching = enabled;
+    }
+
+    /// Measures the loudness of the outputs of A and B with the program (its mixdown),
+    /// sets the trim gain of B to the difference and returns it in dB.
+    /// The program must be longer than 400 ms, and not silent.
+    pub fn match_loudness(& mut self, program: & AudioBuffer) -> Result<f64, String> {
+        if program.sample_rate != self.sample_rate {
+            return Err(format!("Error: the program is at {} Hz, the comparator at {} Hz",
+                               program.sample_rate, self.sample_rate));
+        }
+        let input = program.mixdown();
+        let output_a: Vec<f64> = input.iter().map(|x| self.processor_a.process(*x)).collect();
+        let output_b: Vec<f64> = input.iter().map(|x| self.processor_b.process(*x)).collect();
+        let loudness_a = integrated_loudness(& AudioBuffer::new(self.sample_rate, vec![output_a]));
+        let loudness_b = integrated_loudness(& AudioBuffer::new(self.sample_rate, vec![output_b]));
+        if !loudness_a.is_finite() || !loudness_b.is_finite() {
+            return Err("Error: can't measure the loudness, the program is too short or silent".to_string());
+        }
+        self.set_match_gain_db(loudness_a - loudness_b);
+        Ok(self.match_gain_db)
+    }
+}
+
+impl<P: ProcessingBlock + Snapshot> AbComparator<P> {
+    /// The settings of a slot.
+    pub fn get_snapshot(& self, slot: AbSlot) -> P::State {
+        self.get_processor(slot).capture()
+    }
+
+    /// Changes the settings of a slot.
+    pub fn set_snapshot(& mut self, slot: AbSlot, state: & P::State) -> Result<(), String> {
+        self.get_processor_mut(slot).restore(state)
+    }
+}
+
+impl<P: ProcessingBlock> ProcessingBlock for AbComparator<P> {
+    fn process(& mut self, sample: f64) -> f64 {
+        let output_a = self.processor_a.process(sample);
+        let mut output_b = self.processor_b.process(sample);
+        if self.loudness_matching {
+            output_b *= self.match_gain;
+        }
+        let target = match self.selected {
+            AbSlot::A => 0.0,
+            AbSlot::B => 1.0,
+        };
+        // Linear ramp of the mix, the two outputs are correlated.
+        if self.mix < target {
+            self.mix = f64::min(self.mix + self.mix_step, target);
+        } else if self.mix > target {
+            self.mix = f64::max(self.mix - self.mix_step, target);
+        }
+        output_a * (1.0 - self.mix) + output_b * self.mix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::TAU;
+    use crate::parametric_eq::ParametricEq;
+    use crate::parametric_eq::ParametricBand;
+    use crate::parametric_eq::BandType;
+    use crate::analysis::peak;
+
+    fn make_sine(length: usize) -> Vec<f64> {
+        (0..length).map(|n| 0.25 * f64::sin(TAU * 440.0 * n as f64 / 48_000.0)).collect()
+    }
+
+    #[test]
+    fn test_switch_is_click_free() {
+        let a = ParametricEq::new(48_000);
+        let mut b = ParametricEq::new(48_000);
+        b.set_preamp_db(-12.0);
+        let mut ab = AbComparator::new(a, b, 48_000, DEFAULT_CROSSFADE_MS);
+        let input = make_sine(9_600);
+        let mut output = Vec::new();
+        for (n, x) in input.iter().enumerate() {
+            if n == 4_800 {
+                ab.toggle();
+            }
+            output.push(ab.process(*x));
+        }
+        assert_eq!(ab.get_selected(), AbSlot::B);
+        // The largest step of a 440 Hz sine of 0.25 is 0.0144, no jump is added.
+        let max_step = output.windows(2).map(|w| (w[1] - w[0]).abs()).fold(0.0, f64::max);
+        assert!(max_step < 0.015);
+        // After the 20 ms crossfade, only B.
+        assert!((output[9_000] - input[9_000] * f64::powf(10.0, -12.0 / 20.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_loudness_matching() {
+        let a = ParametricEq::from_bands(48_000, 0.0, & [ParametricBand::new(BandType::Peak, 440.0, 6.0, 1.0)]).unwrap();
+        let b = ParametricEq::new(48_000);
+        let mut ab = AbComparator::new(a, b, 48_000, DEFAULT_CROSSFADE_MS);
+        let program = AudioBuffer::new(48_000, vec![make_sine(48_000)]);
+        let gain_db = ab.match_loudness(& program).unwrap();
+        assert!((gain_db - 6.0).abs() < 0.1);
+
+        ab.set_loudness_matching(true);
+        ab.select(AbSlot::B);
+        let output: Vec<f64> = make_sine(9_600).iter().map(|x| ab.process(*x)).collect();
+        assert!((peak(& output[4_800..]) - 0.5).abs() < 0.01);
+
+        assert!(ab.match_loudness(& AudioBuffer::new(48_000, vec![vec![0.0; 48_000]])).is_err());
+        assert!(ab.match_loudness(& AudioBuffer::new(44_100, vec![make_sine(48_000)])).is_err());
+    }
+
+    #[test]
+    fn test_snapshots_of_slots() {
+        let mut ab = AbComparator::new(ParametricEq::new(48_000), ParametricEq::new(48_000), 48_000, DEFAULT_CROSSFADE_MS);
+        let mut eq = ParametricEq::new(48_000);
+        eq.add_band(ParametricBand::new(BandType::HighShelf, 8_000.0, 2.0, 0.7)).unwrap();
+        ab.set_snapshot(AbSlot::B, & eq.capture()).unwrap();
+        assert_eq!(ab.get_snapshot(AbSlot::B), eq.capture());
+        assert_eq!(ab.get_snapshot(AbSlot::A).bands.len(), 0);
+    }
+}
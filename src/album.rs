@@ -0,0 +1,214 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Gapless rendering of an album, the tracks as one continuous stream.
+//!              The tracks of a live album or of a DJ mix are played back to back,
+//!              without silence, the end of a track is the start of the next. A file
+//!              processed alone starts its filters from a zero state, a transient
+//!              at the start of each track, and the state at the end, the tail of
+//!              the previous track, is lost. At the boundary it is a click or a
+//!              thump that isn't in the music.
+//!              The album rendering processes the tracks in order through the same
+//!              processors, one for each channel, without resetting their state
+//!              between the tracks, and splits the output at the same boundaries.
+//!              Each output track has the length of its input, and the tracks played
+//!              gapless are the continuous render of the whole album:
+//!
+//!                 [ track 1 | track 2 | track 3 ]  ->  chain  ->  [ out 1 | out 2 | out 3 ]
+//!
+//!              The _with_control() versions report the progress in frames of the
+//!              album, every block of PROGRESS_BLOCK_FRAMES frames, and stop when
+//!              the job is cancelled, see progress.rs .
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::audio_buffer::AudioBuffer;
+use crate::multichannel_filter::MultiChannelFilter;
+use crate::wav_metadata::read_wav_with_metadata;
+use crate::wav_metadata::write_wav_with_metadata;
+use crate::progress::JobControl;
+
+
+/// The frames processed between two progress updates.
+pub const PROGRESS_BLOCK_FRAMES: usize = 65_536;
+
+/// Processes the tracks as one continuous stream, with a processor for each
+/// channel from make_processor(), called with the index of the channel. The
+/// tracks must have the same sample rate and number of channels.
+///
+/// In Rust:
+///    ```text
+///    >>> let rendered = process_album(& tracks, |_| profile.to_parametric_eq(48_000).unwrap())?;
+///    ```
+///
+pub fn process_album<B, M>(tracks: & [AudioBuffer], make_processor: M) -> Result<Vec<AudioBuffer>, String>
+        where B: ProcessingBlock, M: FnMut(usize) -> B {
+    process_album_with_control(tracks, make_processor, & mut JobControl::new())
+}
+
+/// Processes the tracks like process_album(), with the progress in frames of the
+/// album, and returns an error when the job is cancelled.
+pub fn process_album_with_control<B, M>(tracks: & [AudioBuffer], make_processor: M, control: & mut JobControl)
+        -> Result<Vec<AudioBuffer>, String>
+        where B: ProcessingBlock, M: FnMut(usize) -> B {
+    let first = tracks.first().ok_or("Error: the album has no tracks".to_string())?;
+    for (index, track) in tracks.iter().enumerate() {
+        if track.sample_rate != first.sample_rate || track.get_num_channels() != first.get_num_channels() {
+            return Err(format!("Error: the track {} has {} channels at {} Hz, the first track has {} channels at {} Hz",
+                               index + 1, track.get_num_channels(), track.sample_rate,
+                               first.get_num_channels(), first.sample_rate));
+        }
+    }
+    let _span = trace_span!("process_album", tracks = tracks.len(), channels = first.get_num_channels());
+    let mut processors = MultiChannelFilter::from_factory(first.get_num_channels(), make_processor);
+    let total_frames = tracks.iter().map(|track| track.get_num_frames()).sum();
+    let mut done_frames = 0;
+    control.update(done_frames, total_frames)?;
+    let mut rendered = Vec::with_capacity(tracks.len());
+    for track in tracks {
+        let mut output = track.clone();
+        for start in (0..output.get_num_frames()).step_by(PROGRESS_BLOCK_FRAMES) {
+            let end = usize::min(start + PROGRESS_BLOCK_FRAMES, output.get_num_frames());
+            let mut block: Vec<& mut [f64]> = output.channels.iter_mut().map(|channel| & mut channel[start..end]).collect();
+            processors.process_planar(& mut block)?;
+            done_frames += end - start;
+            control.update(done_frames, total_frames)?;
+        }
+        rendered.push(output);
+    }
+    Ok(rendered)
+}
+
+/// Renders the WAV files of an album gapless, see process_album(), each output
+/// file with the sample format and the metadata of its input. All the tracks are
+/// read and checked before the first one is written.
+pub fn process_album_files<B, M>(input_paths: & [String], output_paths: & [String], make_processor: M)
+        -> Result<(), String>
+        where B: ProcessingBlock, M: FnMut(usize) -> B {
+    process_album_files_with_control(input_paths, output_paths, make_processor, & mut JobControl::new())
+}
+
+/// Renders the WAV files like process_album_files(), with the progress of
+/// process_album_with_control(). When the job is cancelled no file is written.
+pub fn process_album_files_with_control<B, M>(input_paths: & [String], output_paths: & [String], make_processor: M,
+                                              control: & mut JobControl) -> Result<(), String>
+        where B: ProcessingBlock, M: FnMut(usize) -> B {
+    if input_paths.len() != output_paths.len() {
+        return Err(format!("Error: {} input files and {} output files", input_paths.len(), output_paths.len()));
+    }
+    let mut tracks = Vec::with_capacity(input_paths.len());
+    let mut formats = Vec::with_capacity(input_paths.len());
+    for path in input_paths {
+        let (audio, sample_format, metadata) = read_wav_with_metadata(path)?;
+        tracks.push(audio);
+        formats.push((sample_format.get_writable(), metadata));
+    }
+    let rendered = process_album_with_control(& tracks, make_processor, control)?;
+    for ((path, audio), (sample_format, metadata)) in output_paths.iter().zip(& rendered).zip(& formats) {
+        write_wav_with_metadata(path, audio, *sample_format, metadata)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::audio_io::SampleFormat;
+    use crate::audio_io::read_wav;
+    use crate::audio_io::write_wav;
+    use crate::progress::CancellationToken;
+    use crate::progress::CANCELLED_ERROR;
+    use crate::fixtures::temp_test_path;
+
+    // A tone that continues across the tracks, cut at the frames.
+    fn make_tracks(lengths: & [usize]) -> Vec<AudioBuffer> {
+        let mut start = 0;
+        lengths.iter().map(|length| {
+            let channel: Vec<f64> = (start..start + length).map(|n| 0.5 * f64::sin(n as f64 * 0.02)).collect();
+            start += length;
+            AudioBuffer::new(48_000, vec![channel.clone(), channel.iter().map(|x| -x).collect()])
+        }).collect()
+    }
+
+    #[test]
+    fn test_album_is_continuous() {
+        let tracks = make_tracks(& [10_000, 7_777, 12_345]);
+        let make = |_| make_lowpass(200.0, 48_000, None);
+        let rendered = process_album(& tracks, make).unwrap();
+
+        let mut album = tracks[0].clone();
+        for track in & tracks[1..] {
+            album.append(track).unwrap();
+        }
+        let continuous = & process_album(& [album], make).unwrap()[0];
+        let mut joined = rendered[0].clone();
+        for track in & rendered[1..] {
+            joined.append(track).unwrap();
+        }
+        assert_eq!(& joined, continuous);
+        assert!(rendered.iter().zip(& tracks).all(|(output, input)| output.get_num_frames() == input.get_num_frames()));
+
+        // Alone, the second track starts from a zero state, far from the album.
+        let alone = & process_album(& tracks[1..2], make).unwrap()[0];
+        assert!((alone.channels[0][0] - rendered[1].channels[0][0]).abs() > 0.01);
+
+        assert!(process_album(& [], make).is_err());
+        let mut other_rate = tracks.clone();
+        other_rate[2].sample_rate = 44_100;
+        assert!(process_album(& other_rate, make).is_err());
+
+        // The progress in frames of the album, in blocks, to the end.
+        let long_tracks = make_tracks(& [PROGRESS_BLOCK_FRAMES + 10, 100]);
+        let mut reports = Vec::new();
+        let mut control = JobControl::new();
+        control.set_progress_callback(|progress| reports.push((progress.done, progress.total)));
+        let with_control = process_album_with_control(& long_tracks, make, & mut control).unwrap();
+        drop(control);
+        let total = PROGRESS_BLOCK_FRAMES + 110;
+        assert_eq!(reports, vec![(0, total), (PROGRESS_BLOCK_FRAMES, total), (PROGRESS_BLOCK_FRAMES + 10, total),
+                                 (total, total)]);
+        assert_eq!(with_control, process_album(& long_tracks, make).unwrap());
+    }
+
+    #[test]
+    fn test_album_files() {
+        let path = |name: & str| temp_test_path(& format!("album_{}.wav", name));
+        let input_paths = vec![path("input_1"), path("input_2")];
+        let output_paths = vec![path("output_1"), path("output_2")];
+        let tracks = make_tracks(& [4_800, 4_800]);
+        write_wav(& input_paths[0], & tracks[0], SampleFormat::Float32).unwrap();
+        write_wav(& input_paths[1], & tracks[1], SampleFormat::Int24).unwrap();
+
+        process_album_files(& input_paths, & output_paths, |_| make_lowpass(500.0, 48_000, None)).unwrap();
+        let (first, first_format) = read_wav(& output_paths[0]).unwrap();
+        let (second, second_format) = read_wav(& output_paths[1]).unwrap();
+        assert_eq!((first_format, second_format), (SampleFormat::Float32, SampleFormat::Int24));
+        let read_tracks: Vec<AudioBuffer> = input_paths.iter().map(|p| read_wav(p).unwrap().0).collect();
+        let rendered = process_album(& read_tracks, |_| make_lowpass(500.0, 48_000, None)).unwrap();
+        assert!(second.channels[1].iter().zip(& rendered[1].channels[1]).all(|(x, y)| (x - y).abs() < 1e-6));
+        assert_eq!(first.get_num_frames(), 4_800);
+        assert!(process_album_files(& input_paths, & output_paths[..1], |_| make_lowpass(500.0, 48_000, None)).is_err());
+
+        // Cancelled at the first update, no file is written.
+        let token = CancellationToken::new();
+        token.cancel();
+        let mut control = JobControl::new();
+        control.set_cancellation_token(Some(token));
+        let cancelled_paths = vec![path("cancelled_1"), path("cancelled_2")];
+        let result = process_album_files_with_control(& input_paths, & cancelled_paths,
+                                                      |_| make_lowpass(500.0, 48_000, None), & mut control);
+        assert_eq!(result, Err(CANCELLED_ERROR.to_string()));
+        assert!(cancelled_paths.iter().all(|path| !std::path::Path::new(path).exists()));
+
+        for path in input_paths.iter().chain(& output_paths) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,401 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Signal analysis helpers.
+//!              Levels (peak, RMS, dBFS), the delay between two signals by
+//!              cross-correlation, the short time spectrum (spectrogram) and the
+//!              amplitude of a single frequency (Goertzel).
+//!
+//!              The delay estimator computes the cross-correlation with the FFT,
+//!              and returns the lag of its maximum. The spectrogram uses a Hann
+//!              window, and is scaled so that a full scale sine is at 0 dB.
+//!
+//!              The Goertzel algorithm is the DFT at one frequency w, with a second
+//!              order recursion and one real multiplication per sample:
+//!
+//!                 s[n] = x[n] + 2 cos(w) s[n-1] - s[n-2]
+//!                 |X(w)|^2 = s[N-1]^2 + s[N-2]^2 - 2 cos(w) s[N-1] s[N-2]
+//!
+//!              It's cheaper than a FFT for a few frequencies, and the frequency
+//!              doesn't need to be on a bin.
+//!
+//!              For sensor signals, like the rate of a gyroscope or the frequency
+//!              error of a clock, the mean and the linear trend (the drift) are
+//!              removed by least squares, and the overlapping Allan deviation shows
+//!              the noise at each averaging time tau = m / fs. With the integral of
+//!              the rate theta[k] = sum_{i<k} y[i] / fs, of N + 1 points:
+//!
+//!                 sigma^2(tau) = sum_{k=0}^{N-2m} (theta[k+2m] - 2 theta[k+m] + theta[k])^2
+//!                                / (2 tau^2 (N - 2m + 1))
+//!
+//!              On a log-log plot, white noise of the rate (angle random walk) has a
+//!              slope of -1/2, with sigma(1 s) = N, the bias instability is the flat
+//!              bottom, at 0.664 B, and a rate ramp R has a slope of +1, R tau / sqrt(2).
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Cross-correlation - Wikipedia
+//!       https://en.wikipedia.org/wiki/Cross-correlation
+//!
+//!    2. Short-time Fourier transform - Wikipedia
+//!       https://en.wikipedia.org/wiki/Short-time_Fourier_transform
+//!
+//!    3. Goertzel algorithm - Wikipedia
+//!       https://en.wikipedia.org/wiki/Goertzel_algorithm
+//!
+//!    4. IEEE Std 952-1997 - IEEE Standard Specification Format Guide and Test
+//!       Procedure for Single-Axis Interferometric Fiber Optic Gyros, Annex C
+//!
+//!    5. Allan variance - Wikipedia
+//!       https://en.wikipedia.org/wiki/Allan_variance
+//!
+
+
+use std::f64::consts::TAU;
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+
+
+/// Largest absolute value of the signal.
+pub fn peak(signal: & [f64]) -> f64 {
+    signal.iter().fold(0.0, |max, x| f64::max(max, x.abs()))
+}
+
+/// Root mean square of the signal, 0 for an empty signal.
+pub fn rms(signal: & [f64]) -> f64 {
+    if signal.is_empty() {
+        return 0.0;
+    }
+    f64::sqrt(signal.iter().map(|x| x * x).sum::<f64>() / signal.len() as f64)
+}
+
+/// Amplitude in dB relative to full scale (1.0), -inf for 0.
+pub fn amplitude_to_db(amplitude: f64) -> f64 {
+    20.0 * f64::log10(amplitude)
+}
+
+/// Delay in samples of the signal relative to the reference, in the interval
+/// [-max_delay, max_delay]. Positive when the signal comes after the reference.
+///
+/// In Rust:
+///    ```text
+///    >>> let delay = estimate_delay(& original, & recorded, 48_000);
+///    >>> // recorded[n + delay] lines up with original[n] .
+///    ```
+///
+pub fn estimate_delay(reference: & [f64], signal: & [f64], max_delay: usize) -> isize {
+    if reference.is_empty() || signal.is_empty() {
+        return 0;
+    }
+    let fft_size = (reference.len() + signal.len()).next_power_of_two();
+    let mut planner = FftPlanner::<f64>::new();
+    let fft = planner.plan_fft_forward(fft_size);
+    let ifft = planner.plan_fft_inverse(fft_size);
+
+    let mut reference_spectrum = vec![Complex::new(0.0, 0.0); fft_size];
+    let mut signal_spectrum = vec![Complex::new(0.0, 0.0); fft_size];
+    for (c, x) in reference_spectrum.iter_mut().zip(reference) {
+        c.re = *x;
+    }
+    for (c, x) in signal_spectrum.iter_mut().zip(signal) {
+        c.re = *x;
+    }
+    fft.process(& mut reference_spectrum);
+    fft.process(& mut signal_spectrum);
+    // correlation[k] = sum_n reference[n] * signal[n + k], negative lags wrap around.
+    let mut correlation: Vec<Complex<f64>> = signal_spectrum.iter().zip(& reference_spectrum)
+        .map(|(s, r)| s * r.conj())
+        .collect();
+    ifft.process(& mut correlation);
+
+    let max_positive = max_delay.min(signal.len() - 1);
+    let max_negative = max_delay.min(reference.len() - 1);
+    let mut best_lag = 0_isize;
+    let mut best_value = f64::MIN;
+    for lag in -(max_negative as isize)..=(max_positive as isize) {
+        let index = lag.rem_euclid(fft_size as isize) as usize;
+        if correlation[index].re > best_value {
+            best_value = correlation[index].re;
+            best_lag = lag;
+        }
+    }
+    best_lag
+}
+
+/// Magnitude spectrogram in dB, one Vec of fft_size / 2 + 1 bins per frame, the
+/// frames start every hop_size samples. The last incomplete frame is zero padded.
+pub fn spectrogram(signal: & [f64], fft_size: usize, hop_size: usize) -> Vec<Vec<f64>> {
+    assert!(fft_size >= 2 && hop_size >= 1);
+    let window: Vec<f64> = (0..fft_size)
+        .map(|n| 0.5 - 0.5 * f64::cos(TAU * n as f64 / fft_size as f64))
+        .collect();
+    // A sine of amplitude 1 has a peak of sum(window) / 2 in the FFT.
+    let scale = 2.0 / window.iter().sum::<f64>();
+    let mut planner = FftPlanner::<f64>::new();
+    let fft = planner.plan_fft_forward(fft_size);
+
+    let mut frames = Vec::new();
+    let mut buffer = vec![Complex::new(0.0, 0.0); fft_size];
+    let mut start = 0;
+    while start < signal.len() {
+        for (n, c) in buffer.iter_mut().enumerate() {
+            let x = signal.get(start + n).copied().unwrap_or(0.0);
+            *c = Complex::new(x * window[n], 0.0);
+        }
+        fft.process(& mut buffer);
+        frames.push(buffer[..fft_size / 2 + 1].iter()
+            .map(|c| amplitude_to_db(f64::max(c.norm() * scale, 1e-12)))
+            .collect());
+        start += hop_size;
+    }
+    frames
+}
+
+/// Amplitude of the sine at the frequency in the signal, with the Goertzel
+/// algorithm. It's exact for a whole number of periods of the sine, otherwise
+/// the sines at other frequencies leak into it like in a DFT bin.
+///
+/// In Rust:
+///    ```text
+///    >>> let amplitude = goertzel_amplitude(& block, 697.0, 8_000);
+///    ```
+///
+pub fn goertzel_amplitude(signal: & [f64], frequency: f64, sample_rate: u32) -> f64 {
+    if signal.is_empty() {
+        return 0.0;
+    }
+    let coefficient = 2.0 * f64::cos(TAU * frequency / sample_rate as f64);
+    let (mut s1, mut s2) = (0.0, 0.0);
+    for x in signal {
+        let s0 = x + coefficient * s1 - s2;
+        s2 = s1;
+        s1 = s0;
+    }
+    let power = f64::max(s1 * s1 + s2 * s2 - coefficient * s1 * s2, 0.0);
+    2.0 * f64::sqrt(power) / signal.len() as f64
+}
+
+/// Removes the mean of the signal, and returns it.
+pub fn remove_mean(signal: & mut [f64]) -> f64 {
+    if signal.is_empty() {
+        return 0.0;
+    }
+    let mean = signal.iter().sum::<f64>() / signal.len() as f64;
+    signal.iter_mut().for_each(|x| *x -= mean);
+    mean
+}
+
+/// The least squares line of the signal, as (offset, slope), the value at the
+/// first sample and the change per sample. The drift per second is slope * fs.
+pub fn linear_trend(signal: & [f64]) -> (f64, f64) {
+    if signal.len() < 2 {
+        return (signal.first().copied().unwrap_or(0.0), 0.0);
+    }
+    // The line mean + slope (n - center), with the center of the samples.
+    let length = signal.len() as f64;
+    let center = (length - 1.0) / 2.0;
+    let mean = signal.iter().sum::<f64>() / length;
+    let (covariance, variance) = signal.iter().enumerate()
+        .fold((0.0, 0.0), |(c, v), (n, x)| (c + (n as f64 - center) * x, v + (n as f64 - center).powi(2)));
+    let slope = covariance / variance;
+    (mean - slope * center, slope)
+}
+
+/// Removes the least squares line of the signal, and returns it as (offset, slope)
+/// like linear_trend().
+pub fn remove_linear_trend(signal: & mut [f64]) -> (f64, f64) {
+    let (offset, slope) = linear_trend(signal);
+    signal.iter_mut().enumerate().for_each(|(n, x)| *x -= offset + slope * n as f64);
+    (offset, slope)
+}
+
+/// The Allan deviation of a signal, at increasing averaging times.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllanDeviation {
+    /// The averaging times, in s.
+    pub taus:       Vec<f64>,
+    /// In the units of the signal.
+    pub deviations: Vec<f64>,
+}
+
+impl AllanDeviation {
+    /// The bias instability, the minimum of the deviation / 0.664, and its tau.
+    pub fn get_bias_instability(& self) -> (f64, f64) {
+        let index = (0..self.deviations.len())
+            .fold(0, |best, i| if self.deviations[i] < self.deviations[best] { i } else { best });
+        (self.taus[index], self.deviations[index] / 0.664)
+    }
+
+    /// The random walk coefficient N, of the white noise of the signal, in
+    /// units times sqrt(s): the line N / sqrt(tau) fitted to the points with a
+    /// log-log slope near -1/2. None if the curve has no such part.
+    pub fn get_random_walk(& self) -> Option<f64> {
+        let mut sum = 0.0;
+        let mut count = 0;
+        for i in 0..self.taus.len().saturating_sub(1) {
+            let slope = f64::ln(self.deviations[i + 1] / self.deviations[i]) / f64::ln(self.taus[i + 1] / self.taus[i]);
+            if (slope + 0.5).abs() < 0.1 {
+                sum += f64::ln(self.deviations[i] * self.taus[i].sqrt());
+                count += 1;
+            }
+        }
+        (count > 0).then(|| f64::exp(sum / count as f64))
+    }
+}
+
+/// Cluster sizes m from 1 to num_samples / 2, about points_per_decade per decade of
+/// tau, for allan_deviation().
+pub fn allan_cluster_sizes(num_samples: usize, points_per_decade: usize) -> Vec<usize> {
+    let max_size = num_samples / 2;
+    let mut sizes: Vec<usize> = Vec::new();
+    let mut exponent = 0;
+    loop {
+        let size = f64::powf(10.0, exponent as f64 / points_per_decade.max(1) as f64).round() as usize;
+        if size > max_size {
+            break;
+        }
+        if sizes.last() != Some(& size) {
+            sizes.push(size);
+        }
+        exponent += 1;
+    }
+    sizes
+}
+
+/// The overlapping Allan deviation of a rate signal (gyroscope rate, fractional
+/// frequency of a clock) at the averaging times m / fs of the cluster sizes. The
+/// sample rate is a f64, the data of a clock can have a sample every 10 s.
+///
+/// In Rust:
+///    ```text
+///    >>> // 1 hour of a gyroscope at 100 Hz, in deg/s.
+///    >>> let sizes = allan_cluster_sizes(rate.len(), 10);
+///    >>> let adev = allan_deviation(& rate, 100.0, & sizes)?;
+///    >>> let (tau, bias_instability) = adev.get_bias_instability();
+///    >>> let angle_random_walk = adev.get_random_walk();   // deg / sqrt(s)
+///    ```
+///
+pub fn allan_deviation(signal: & [f64], sample_rate: f64, cluster_sizes: & [usize]) -> Result<AllanDeviation, String> {
+    if sample_rate.is_nan() || sample_rate <= 0.0 {
+        return Err(format!("Error: invalid sample rate {}", sample_rate));
+    }
+    if let Some(size) = cluster_sizes.iter().find(|m| **m == 0 || 2 * **m > signal.len()) {
+        return Err(format!("Error: cluster size {} out of 1 to {}, half of the {} samples",
+                           size, signal.len() / 2, signal.len()));
+    }
+    let _span = trace_span!("allan_deviation", samples = signal.len(), taus = cluster_sizes.len());
+    // The integral of the rate, in units * s, from 0.
+    let theta: Vec<f64> = std::iter::once(0.0)
+        .chain(signal.iter().scan(0.0, |sum, y| { *sum += y / sample_rate; Some(*sum) }))
+        .collect();
+    let mut taus = Vec::with_capacity(cluster_sizes.len());
+    let mut deviations = Vec::with_capacity(cluster_sizes.len());
+    for & m in cluster_sizes {
+        let tau = m as f64 / sample_rate;
+        let terms = theta.len() - 2 * m;
+        let sum: f64 = (0..terms)
+            .map(|k| (theta[k + 2 * m] - 2.0 * theta[k + m] + theta[k]).powi(2))
+            .sum();
+        taus.push(tau);
+        deviations.push(f64::sqrt(sum / (2.0 * tau * tau * terms as f64)));
+    }
+    Ok(AllanDeviation { taus, deviations })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::make_white_noise;
+
+    #[test]
+    fn test_levels() {
+        let signal = [0.5, -1.0, 0.5, 0.0];
+        assert_eq!(peak(& signal), 1.0);
+        assert!((rms(& signal) - f64::sqrt(1.5 / 4.0)).abs() < 1e-12);
+        assert!((amplitude_to_db(0.5) + 6.0206).abs() < 1e-4);
+        assert_eq!(amplitude_to_db(0.0), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_estimate_delay() {
+        // Pseudo random noise, delayed by 37 samples.
+        let mut state: u32 = 12345;
+        let reference: Vec<f64> = (0..2_000).map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (state >> 16) as f64 / 32_768.0 - 1.0
+        }).collect();
+        let mut delayed = vec![0.0; 37];
+        delayed.extend(& reference);
+        assert_eq!(estimate_delay(& reference, & delayed, 100), 37);
+        assert_eq!(estimate_delay(& delayed, & reference, 100), -37);
+        assert!(estimate_delay(& reference, & delayed, 10).abs() <= 10);
+    }
+
+    #[test]
+    fn test_spectrogram_of_sine() {
+        let sample_rate = 8_000.0;
+        let fft_size = 256;
+        // 1000 Hz is exactly the bin 32.
+        let signal: Vec<f64> = (0..1_024).map(|n| f64::sin(TAU * 1_000.0 * n as f64 / sample_rate)).collect();
+        let frames = spectrogram(& signal, fft_size, 128);
+        assert_eq!(frames.len(), 8);
+        assert_eq!(frames[0].len(), 129);
+        assert!(frames[0][32].abs() < 0.01);
+        assert!(frames[0][64] < -100.0);
+    }
+
+    #[test]
+    fn test_goertzel_amplitude() {
+        // 1 s of 697 Hz and of 1209 Hz at 8 kHz, whole periods of both.
+        let signal: Vec<f64> = (0..8_000)
+            .map(|n| 0.3 * f64::sin(TAU * 697.0 * n as f64 / 8_000.0) + 0.1 * f64::cos(TAU * 1_209.0 * n as f64 / 8_000.0))
+            .collect();
+        assert!((goertzel_amplitude(& signal, 697.0, 8_000) - 0.3).abs() < 1e-9);
+        assert!((goertzel_amplitude(& signal, 1_209.0, 8_000) - 0.1).abs() < 1e-9);
+        assert!(goertzel_amplitude(& signal, 941.0, 8_000) < 1e-9);
+        assert_eq!(goertzel_amplitude(& [], 941.0, 8_000), 0.0);
+    }
+
+    #[test]
+    fn test_detrend() {
+        let mut signal: Vec<f64> = (0..100).map(|n| 3.0 + 0.25 * n as f64 + f64::sin(n as f64 * 0.7)).collect();
+        let (offset, slope) = linear_trend(& signal);
+        assert!((offset - 3.0).abs() < 0.1 && (slope - 0.25).abs() < 0.01, "{} {}", offset, slope);
+        remove_linear_trend(& mut signal);
+        assert!(linear_trend(& signal).1.abs() < 1e-12);
+        assert!(remove_mean(& mut signal).abs() < 1e-12);
+        let mut constant = vec![2.0; 10];
+        assert_eq!(remove_mean(& mut constant), 2.0);
+        assert_eq!(constant, vec![0.0; 10]);
+    }
+
+    #[test]
+    fn test_allan_deviation() {
+        // White noise of the rate, uniform of standard deviation 1 at 100 Hz, has
+        // sigma(tau) = 1 / sqrt(tau fs), a random walk of 0.1 / sqrt(s).
+        let noise = make_white_noise(200_000, f64::sqrt(3.0), 1);
+        let sizes = allan_cluster_sizes(noise.len(), 5);
+        assert_eq!(& sizes[..6], & [1, 2, 3, 4, 6, 10]);
+        assert!(* sizes.last().unwrap() <= 100_000);
+        let adev = allan_deviation(& noise, 100.0, & sizes).unwrap();
+        assert!((adev.deviations[0] - 1.0).abs() < 0.02);
+        let random_walk = adev.get_random_walk().unwrap();
+        assert!((random_walk - 0.1).abs() < 0.005, "{}", random_walk);
+
+        // A rate ramp R has sigma(tau) = R tau / sqrt(2), and its trend is the ramp.
+        let ramp: Vec<f64> = (0..1_000).map(|n| 0.5 * n as f64 / 10.0).collect();
+        let adev = allan_deviation(& ramp, 10.0, & [1, 10, 100]).unwrap();
+        for (tau, deviation) in adev.taus.iter().zip(& adev.deviations) {
+            assert!((deviation - 0.5 * tau / f64::sqrt(2.0)).abs() < 1e-9, "{} {}", tau, deviation);
+        }
+        assert_eq!(adev.get_bias_instability().0, 0.1);
+        assert!(adev.get_random_walk().is_none());
+        assert!((linear_trend(& ramp).1 * 10.0 - 0.5).abs() < 1e-12);
+        assert!(allan_deviation(& ramp, 10.0, & [501]).is_err());
+        assert!(allan_deviation(& ramp, 0.0, & [1]).is_err());
+    }
+}
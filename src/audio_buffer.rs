@@ -0,0 +1,258 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: The audio buffer, channels x frames, with the sample rate.
+//!              The WAV files, the multichannel chains, the analysis and the
+//!              processing of a file all pass audio around as an AudioBuffer, one
+//!              Vec of samples per channel (planar), all of the same length, with the
+//!              sample rate of the samples:
+//!
+//!                 channels[0]  L  x[0] x[1] x[2] ...
+//!                 channels[1]  R  x[0] x[1] x[2] ...
+//!
+//!              A frame is the sample of each channel at the same time. The
+//!              interleaved order of a WAV file or of an audio driver, L R L R ...,
+//!              is converted with from_interleaved() and to_interleaved().
+//!              An audio driver gives the samples in blocks of a fixed size, 64 to
+//!              2048 frames, and for_each_block() processes a buffer in blocks of a
+//!              configurable size, the last one shorter, so an offline render goes
+//!              through the same code path and the same block boundaries as a real
+//!              time one. The processing blocks of this crate are sample by sample,
+//!              and the output doesn't depend on the block size.
+//!
+//!              The buffer has the channel layout of its channels, see
+//!              channel_layout.rs, from the channel mask of a WAV file or by default
+//!              from the number of channels, so that a multichannel filter knows
+//!              the LFE channel of a 5.1 signal.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::channel_layout::ChannelLayout;
+
+
+/// The block size of the processing, when the caller doesn't choose one, about
+/// 10 ms at 48 kHz.
+pub const DEFAULT_BLOCK_SIZE: usize = 512;
+
+/// Multichannel audio, one Vec of samples per channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioBuffer {
+    pub sample_rate: u32,
+    pub channels:    Vec<Vec<f64>>,
+    pub layout:      ChannelLayout,
+}
+
+impl AudioBuffer {
+    /// All the channels must have the same number of samples. The layout is the
+    /// default one of the number of channels, see ChannelLayout::from_channel_count().
+    pub fn new(sample_rate: u32, channels: Vec<Vec<f64>>) -> Self {
+        assert!(sample_rate > 0 && !channels.is_empty());
+        assert!(channels.iter().all(|c| c.len() == channels[0].len()));
+        let layout = ChannelLayout::from_channel_count(channels.len());
+        AudioBuffer { sample_rate, channels, layout }
+    }
+
+    /// The same buffer with another layout, of the same number of channels.
+    pub fn with_layout(mut self, layout: ChannelLayout) -> Result<Self, String> {
+        self.set_layout(layout)?;
+        Ok(self)
+    }
+
+    pub fn set_layout(& mut self, layout: ChannelLayout) -> Result<(), String> {
+        if layout.channel_count() != self.get_num_channels() {
+            return Err(format!("Error: the layout {:?} has {} channels, the audio has {}",
+                               layout, layout.channel_count(), self.get_num_channels()));
+        }
+        self.layout = layout;
+        Ok(())
+    }
+
+    /// A buffer of the same layout, for the output of a processing of each channel
+    /// of this one, at the sample rate.
+    pub fn with_channels(& self, sample_rate: u32, channels: Vec<Vec<f64>>) -> AudioBuffer {
+        assert_eq!(channels.len(), self.get_num_channels());
+        AudioBuffer { layout: self.layout, ..AudioBuffer::new(sample_rate, channels) }
+    }
+
+    /// A buffer of silence.
+    pub fn zeros(sample_rate: u32, num_channels: usize, num_frames: usize) -> Self {
+        AudioBuffer::new(sample_rate, vec![vec![0.0; num_frames]; num_channels])
+    }
+
+    /// The buffer of the samples in the interleaved order, frame by frame.
+    pub fn from_interleaved(sample_rate: u32, num_channels: usize, samples: & [f64]) -> Result<Self, String> {
+        if num_channels == 0 || !samples.len().is_multiple_of(num_channels) {
+            return Err(format!("Error: {} interleaved samples aren't whole frames of {} channels",
+                               samples.len(), num_channels));
+        }
+        let channels = (0..num_channels)
+            .map(|channel| samples.iter().skip(channel).step_by(num_channels).copied().collect())
+            .collect();
+        Ok(AudioBuffer::new(sample_rate, channels))
+    }
+
+    /// The samples in the interleaved order, frame by frame.
+    pub fn to_interleaved(& self) -> Vec<f64> {
+        (0..self.get_num_frames())
+            .flat_map(|n| self.channels.iter().map(move |channel| channel[n]))
+            .collect()
+    }
+
+    pub fn get_num_channels(& self) -> usize {
+        self.channels.len()
+    }
+
+    /// Number of samples in each channel.
+    pub fn get_num_frames(& self) -> usize {
+        self.channels[0].len()
+    }
+
+    pub fn get_duration_seconds(& self) -> f64 {
+        self.get_num_frames() as f64 / self.sample_rate as f64
+    }
+
+    pub fn get_channel(& self, channel: usize) -> & [f64] {
+        assert!(channel < self.channels.len());
+        & self.channels[channel]
+    }
+
+    pub fn get_channel_mut(& mut self, channel: usize) -> & mut [f64] {
+        assert!(channel < self.channels.len());
+        & mut self.channels[channel]
+    }
+
+    /// The sample of each channel at the frame.
+    pub fn get_frame(& self, frame: usize) -> Vec<f64> {
+        self.channels.iter().map(|channel| channel[frame]).collect()
+    }
+
+    /// A copy of the frames from start to end, not included.
+    pub fn get_frames(& self, start: usize, end: usize) -> AudioBuffer {
+        assert!(start <= end && end <= self.get_num_frames());
+        AudioBuffer {
+            sample_rate: self.sample_rate,
+            channels: self.channels.iter().map(|channel| channel[start..end].to_vec()).collect(),
+            layout: self.layout,
+        }
+    }
+
+    /// Adds the frames of the other buffer at the end, with the same sample rate
+    /// and number of channels.
+    pub fn append(& mut self, other: & AudioBuffer) -> Result<(), String> {
+        if other.sample_rate != self.sample_rate || other.get_num_channels() != self.get_num_channels() {
+            return Err(format!("Error: can't append {} channels at {} Hz to {} channels at {} Hz",
+                               other.get_num_channels(), other.sample_rate, self.get_num_channels(),
+                               self.sample_rate));
+        }
+        for (channel, other_channel) in self.channels.iter_mut().zip(& other.channels) {
+            channel.extend_from_slice(other_channel);
+        }
+        Ok(())
+    }
+
+    /// The average of all the channels.
+    pub fn mixdown(& self) -> Vec<f64> {
+        let num_channels = self.get_num_channels() as f64;
+        (0..self.get_num_frames())
+            .map(|n| self.channels.iter().map(|c| c[n]).sum::<f64>() / num_channels)
+            .collect()
+    }
+
+    /// Processes each channel in place with its processor, one for each channel.
+    pub fn process_channels<B: ProcessingBlock>(& mut self, processors: & mut [B]) {
+        assert_eq!(processors.len(), self.channels.len());
+        for (channel, processor) in self.channels.iter_mut().zip(processors.iter_mut()) {
+            processor.process_inplace(channel);
+        }
+    }
+
+    /// Processes the buffer in place, in blocks of block_size frames, the last one
+    /// shorter, like the callback of an audio driver. The function gets each block
+    /// and the index of its first frame.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let mut filters: Vec<IIRFilter> = (0..2).map(|_| make_lowpass(1_000.0, 48_000, None)).collect();
+    ///    >>> audio.for_each_block(256, |block, _| block.process_channels(& mut filters));
+    ///    ```
+    ///
+    pub fn for_each_block<F>(& mut self, block_size: usize, mut process_block: F)
+            where F: FnMut(& mut AudioBuffer, usize) {
+        assert!(block_size > 0);
+        let num_frames = self.get_num_frames();
+        let _span = trace_span!("audio_buffer_blocks", block_size, frames = num_frames);
+        let mut start = 0;
+        while start < num_frames {
+            let end = usize::min(start + block_size, num_frames);
+            let mut block = self.get_frames(start, end);
+            process_block(& mut block, start);
+            for (channel, block_channel) in self.channels.iter_mut().zip(& block.channels) {
+                channel[start..end].copy_from_slice(block_channel);
+            }
+            start = end;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::iir_filter::IIRFilter;
+
+    #[test]
+    fn test_interleaved_and_frames() {
+        let samples = [1.0, -1.0, 2.0, -2.0, 3.0, -3.0];
+        let mut audio = AudioBuffer::from_interleaved(44_100, 2, & samples).unwrap();
+        assert_eq!(audio.channels, vec![vec![1.0, 2.0, 3.0], vec![-1.0, -2.0, -3.0]]);
+        assert_eq!(audio.to_interleaved(), samples.to_vec());
+        assert_eq!(audio.get_frame(1), vec![2.0, -2.0]);
+        assert_eq!(audio.get_frames(1, 3).channels, vec![vec![2.0, 3.0], vec![-2.0, -3.0]]);
+        assert!(AudioBuffer::from_interleaved(44_100, 2, & samples[..5]).is_err());
+
+        audio.append(& AudioBuffer::zeros(44_100, 2, 2)).unwrap();
+        assert_eq!(audio.get_num_frames(), 5);
+        assert_eq!(audio.get_channel(1), & [-1.0, -2.0, -3.0, 0.0, 0.0]);
+        assert!(audio.append(& AudioBuffer::zeros(48_000, 2, 1)).is_err());
+        assert!(audio.append(& AudioBuffer::zeros(44_100, 1, 1)).is_err());
+    }
+
+    #[test]
+    fn test_layout() {
+        let audio = AudioBuffer::zeros(48_000, 6, 10);
+        assert_eq!(audio.layout, ChannelLayout::Surround51);
+        assert_eq!(audio.get_frames(2, 4).layout, ChannelLayout::Surround51);
+        assert_eq!(audio.with_channels(96_000, vec![vec![0.0; 20]; 6]).layout, ChannelLayout::Surround51);
+        let audio = AudioBuffer::zeros(48_000, 4, 10).with_layout(ChannelLayout::AmbisonicsFirstOrder).unwrap();
+        assert_eq!(audio.layout, ChannelLayout::AmbisonicsFirstOrder);
+        assert!(audio.with_layout(ChannelLayout::Stereo).is_err());
+    }
+
+    #[test]
+    fn test_block_size_independence() {
+        let channel: Vec<f64> = (0..1_000).map(|n| f64::sin(n as f64 * 0.3)).collect();
+        let input = AudioBuffer::new(48_000, vec![channel.clone(), channel.iter().map(|x| -x).collect()]);
+        let make_filters = || -> Vec<IIRFilter> { (0..2).map(|_| make_lowpass(2_000.0, 48_000, None)).collect() };
+        let mut reference = input.clone();
+        reference.process_channels(& mut make_filters());
+
+        for block_size in [1, 64, 333, DEFAULT_BLOCK_SIZE, 4_096] {
+            let mut output = input.clone();
+            let mut filters = make_filters();
+            let mut starts = Vec::new();
+            output.for_each_block(block_size, |block, start| {
+                assert!(block.get_num_frames() <= block_size);
+                starts.push(start);
+                block.process_channels(& mut filters);
+            });
+            assert_eq!(output, reference);
+            assert_eq!(starts.len(), 1_000_usize.div_ceil(block_size));
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Audio diff, the null test between two files.
+//!              To verify that a new implementation of a filter (other structure,
+//!              f32, SIMD) sounds the same as the old one, render the same input with
+//!              both, time-align the outputs, subtract them and look at what is left.
+//!              If the residual is far below the audible level, the change is
+//!              transparent. The difference spectrogram shows at which frequencies
+//!              and times the two files differ.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use crate::audio_buffer::AudioBuffer;
+use crate::analysis::estimate_delay;
+use crate::analysis::peak;
+use crate::analysis::rms;
+use crate::analysis::amplitude_to_db;
+use crate::analysis::spectrogram;
+
+
+/// Result of the null test of a candidate against a reference.
+#[derive(Debug, Clone)]
+pub struct NullTestReport {
+    /// Delay of the candidate relative to the reference, in samples.
+    pub delay_samples:    isize,
+    /// Per channel residual levels in dBFS, -inf when the files null completely.
+    pub residual_rms_db:  Vec<f64>,
+    pub residual_peak_db: Vec<f64>,
+    /// Per channel RMS of the reference in dBFS, over the compared samples.
+    pub reference_rms_db: Vec<f64>,
+    /// Candidate minus reference, over the overlapping samples after the alignment.
+    pub difference:       AudioBuffer,
+}
+
+impl NullTestReport {
+    /// Highest residual peak of all the channels, in dBFS.
+    pub fn max_residual_peak_db(& self) -> f64 {
+        self.residual_peak_db.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// True when the residual peak of all the channels is below the threshold.
+    pub fn is_transparent(& self, threshold_db: f64) -> bool {
+        self.max_residual_peak_db() < threshold_db
+    }
+
+    /// Spectrogram in dB of the mixdown of the difference.
+    pub fn difference_spectrogram(& self, fft_size: usize, hop_size: usize) -> Vec<Vec<f64>> {
+        spectrogram(& self.difference.mixdown(), fft_size, hop_size)
+    }
+}
+
+/// Time-aligns the candidate to the reference, searching delays up to max_delay
+/// samples, subtracts them and measures the residual.
+pub fn null_test(reference: & AudioBuffer, candidate: & AudioBuffer, max_delay: usize) -> Result<NullTestReport, String> {
+    let _span = trace_span!("null_test", max_delay);
+    if reference.sample_rate != candidate.sample_rate {
+        return Err(format!("Error: different sample rates, {} Hz and {} Hz",
+                           reference.sample_rate, candidate.sample_rate));
+    }
+    if reference.get_num_channels() != candidate.get_num_channels() {
+        return Err(format!("Error: different number of channels, {} and {}",
+                           reference.get_num_channels(), candidate.get_num_channels()));
+    }
+
+    let delay = estimate_delay(& reference.mixdown(), & candidate.mixdown(), max_delay);
+    // Overlap of reference[n] with candidate[n + delay].
+    let reference_start = (-delay).max(0) as usize;
+    let candidate_start = delay.max(0) as usize;
+    let length = reference.get_num_frames().saturating_sub(reference_start)
+        .min(candidate.get_num_frames().saturating_sub(candidate_start));
+    if length == 0 {
+        return Err("Error: the files don't overlap after the alignment".to_string());
+    }
+
+    let differences: Vec<Vec<f64>> = reference.channels.iter().zip(& candidate.channels)
+        .map(|(r, c)| {
+            let r = & r[reference_start..reference_start + length];
+            let c = & c[candidate_start..candidate_start + length];
+            c.iter().zip(r).map(|(c, r)| c - r).collect()
+        })
+        .collect();
+
+    Ok(NullTestReport {
+        delay_samples: delay,
+        residual_rms_db: differences.iter().map(|d| amplitude_to_db(rms(d))).collect(),
+        residual_peak_db: differences.iter().map(|d| amplitude_to_db(peak(d))).collect(),
+        reference_rms_db: reference.channels.iter()
+            .map(|r| amplitude_to_db(rms(& r[reference_start..reference_start + length])))
+            .collect(),
+        difference: reference.with_channels(reference.sample_rate, differences),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iir_filter::ProcessingBlock; // Trait
+    use crate::butterworth_filter::make_peak;
+    use std::f64::consts::TAU;
+
+    fn make_music_like(sample_rate: u32, length: usize) -> Vec<f64> {
+        (0..length).map(|n| {
+            let t = n as f64 / sample_rate as f64;
+            0.3 * f64::sin(TAU * 220.0 * t) + 0.2 * f64::sin(TAU * 1_375.0 * t)
+                + 0.1 * f64::sin(TAU * 5_100.0 * t * (1.0 + t))
+        }).collect()
+    }
+
+    #[test]
+    fn test_null_test_of_delayed_copy_is_silent() {
+        let sample_rate = 48_000;
+        let signal = make_music_like(sample_rate, 9_600);
+        let reference = AudioBuffer::new(sample_rate, vec![signal.clone(), signal.clone()]);
+        let mut delayed = vec![0.0; 25];
+        delayed.extend(& signal);
+        let candidate = AudioBuffer::new(sample_rate, vec![delayed.clone(), delayed]);
+
+        let report = null_test(& reference, & candidate, 1_000).unwrap();
+        assert_eq!(report.delay_samples, 25);
+        assert_eq!(report.max_residual_peak_db(), f64::NEG_INFINITY);
+        assert!(report.is_transparent(-120.0));
+        assert_eq!(report.difference.get_num_frames(), 9_600);
+    }
+
+    #[test]
+    fn test_null_test_finds_a_small_eq_change() {
+        let sample_rate = 48_000;
+        let signal = make_music_like(sample_rate, 9_600);
+        let mut filter = make_peak(1_375.0, sample_rate, 1.0, Some(2.0));
+        let processed: Vec<f64> = signal.iter().map(|x| filter.process(*x)).collect();
+        let reference = AudioBuffer::new(sample_rate, vec![signal]);
+        let candidate = AudioBuffer::new(sample_rate, vec![processed]);
+
+        let report = null_test(& reference, & candidate, 100).unwrap();
+        assert_eq!(report.delay_samples, 0);
+        assert!(!report.is_transparent(-40.0));
+        assert!(report.residual_rms_db[0] < report.reference_rms_db[0] - 10.0);
+        // The residual is at the frequency of the peak filter, 1375 Hz is near bin 29
+        // of a 1024 point FFT at 48 kHz.
+        let frames = report.difference_spectrogram(1_024, 512);
+        let middle = & frames[frames.len() / 2];
+        let loudest_bin = (0..middle.len()).max_by(|a, b| middle[*a].total_cmp(& middle[*b])).unwrap();
+        assert!((28..=30).contains(& loudest_bin));
+
+        let other_rate = AudioBuffer::new(44_100, vec![vec![0.0; 10]]);
+        assert!(null_test(& reference, & other_rate, 100).is_err());
+    }
+}
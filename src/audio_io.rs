@@ -0,0 +1,426 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Audio file input and output.
+//!              A small built-in reader and writer of WAV files, so that the filters
+//!              can be applied to real audio. It reads PCM 8, 16, 24 and 32 bit and
+//!              IEEE float 32 and 64 bit, also in the WAVE_FORMAT_EXTENSIBLE header,
+//!              and writes PCM 16, 24, 32 bit and float 32 bit.
+//!              filter_wav_file() and filter_wav_file_channels() filter a file to
+//!              another, to listen to a filter or to the 10 band equalizer on real
+//!              audio, and not only to look at its plots.
+//!
+//!              The samples are f64 normalized on [-1, 1], in an AudioBuffer, see
+//!              audio_buffer.rs, with the conversions of convert.rs . The other
+//!              chunks of the file, the metadata, are read and written by
+//!              wav_metadata.rs .
+//!              The dwChannelMask of a WAVE_FORMAT_EXTENSIBLE header gives the
+//!              channel layout of the buffer, and the files of more than 2 channels
+//!              are written in WAVE_FORMAT_EXTENSIBLE with the mask of the layout.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. WAVE PCM soundfile format
+//!       http://soundfile.sapp.org/doc/WaveFormat/
+//!
+//!    2. Multiple channel audio data and WAVE files - Microsoft
+//!       https://learn.microsoft.com/en-us/windows-hardware/drivers/audio/multiple-channel-audio-data-and-wave-files
+//!
+
+
+use crate::convert::Sample; // Trait
+use crate::convert::I24;
+use crate::audio_buffer::AudioBuffer;
+use crate::channel_layout::ChannelLayout;
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::multichannel_filter::MultiChannelFilter;
+
+
+const WAVE_FORMAT_PCM:        u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+// The bytes of the sub format GUID after its format tag, 0000-0010-8000-00aa00389b71 .
+const KSDATAFORMAT_SUBTYPE_SUFFIX: [u8; 14] = [0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00,
+                                               0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71];
+
+/// Sample format of a WAV file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleFormat {
+    Int8,
+    Int16,
+    Int24,
+    Int32,
+    Float32,
+    Float64,
+}
+
+impl SampleFormat {
+    pub fn get_bits_per_sample(& self) -> u16 {
+        match self {
+            SampleFormat::Int8    => 8,
+            SampleFormat::Int16   => 16,
+            SampleFormat::Int24   => 24,
+            SampleFormat::Int32   => 32,
+            SampleFormat::Float32 => 32,
+            SampleFormat::Float64 => 64,
+        }
+    }
+
+    pub fn is_float(& self) -> bool {
+        matches!(self, SampleFormat::Float32 | SampleFormat::Float64)
+    }
+
+    /// The nearest format that write_wav() writes, Int8 as Int16 and Float64 as
+    /// Float32.
+    pub fn get_writable(& self) -> SampleFormat {
+        match self {
+            SampleFormat::Int8    => SampleFormat::Int16,
+            SampleFormat::Float64 => SampleFormat::Float32,
+            other                 => *other,
+        }
+    }
+}
+
+/// Reads a WAV file, returns the audio and the sample format of the file.
+pub fn read_wav(path: & str) -> Result<(AudioBuffer, SampleFormat), String> {
+    let _span = trace_span!("read_wav", path);
+    let bytes = std::fs::read(path).map_err(|e| format!("Error: reading WAV file {}: {}", path, e))?;
+    decode_wav(& bytes).map_err(|e| format!("{} ({})", e, path))
+}
+
+/// Writes a WAV file. The samples are clamped to [-1, 1] in the integer formats.
+pub fn write_wav(path: & str, audio: & AudioBuffer, sample_format: SampleFormat) -> Result<(), String> {
+    let _span = trace_span!("write_wav", path, frames = audio.get_num_frames(), channels = audio.get_num_channels());
+    let bytes = encode_wav(audio, sample_format)?;
+    std::fs::write(path, bytes).map_err(|e| format!("Error: writing WAV file {}: {}", path, e))
+}
+
+/// Filters a mono WAV file with the block, and writes the output in the sample
+/// format of the input, or the nearest one that write_wav() writes. A block has
+/// the state of a single channel, the files of more channels are filtered with
+/// filter_wav_file_channels().
+///
+/// In Rust:
+///    ```text
+///    >>> let mut lowpass = make_lowpass(1_000.0, 48_000, None);
+///    >>> filter_wav_file("voice.wav", "voice_lowpass.wav", & mut lowpass)?;
+///    ```
+///
+pub fn filter_wav_file(input_path: & str, output_path: & str, block: & mut dyn ProcessingBlock) -> Result<(), String> {
+    let (mut audio, sample_format) = read_wav(input_path)?;
+    if audio.get_num_channels() != 1 {
+        return Err(format!("Error: {} has {} channels, filter it with a block for each channel, \
+                            see filter_wav_file_channels()", input_path, audio.get_num_channels()));
+    }
+    block.process_inplace(audio.get_channel_mut(0));
+    write_wav(output_path, & audio, sample_format.get_writable())
+}
+
+/// Filters each channel of a WAV file with a block of its own, from make_block()
+/// called with the index of the channel and the sample rate of the file, and
+/// writes the output like filter_wav_file(). The LFE channel of a 5.1 file is
+/// passed through, to filter it process the buffer of read_wav() with a
+/// MultiChannelFilter and set_channel_processed().
+///
+/// In Rust:
+///    ```text
+///    >>> filter_wav_file_channels("music.wav", "music_eq.wav", |_, sample_rate| {
+///    >>>     let mut equalizer = Equalizer::make_equalizer_10_band(sample_rate);
+///    >>>     equalizer.set_band_gain(0, 6.0).unwrap();
+///    >>>     equalizer
+///    >>> })?;
+///    ```
+///
+pub fn filter_wav_file_channels<B, M>(input_path: & str, output_path: & str, mut make_block: M) -> Result<(), String>
+        where B: ProcessingBlock, M: FnMut(usize, u32) -> B {
+    let (mut audio, sample_format) = read_wav(input_path)?;
+    let sample_rate = audio.sample_rate;
+    let mut filter = MultiChannelFilter::from_factory(audio.get_num_channels(),
+                                                      |channel| make_block(channel, sample_rate));
+    filter.process_buffer(& mut audio)?;
+    write_wav(output_path, & audio, sample_format.get_writable())
+}
+
+/// The id and the bytes of a chunk of a RIFF file.
+pub type ChunkSlice<'a> = ([u8; 4], & 'a [u8]);
+
+/// The chunks of a RIFF WAVE file, in the order of the file.
+pub fn riff_chunks(bytes: & [u8]) -> Result<Vec<ChunkSlice<'_>>, String> {
+    if bytes.len() < 12 || & bytes[0..4] != b"RIFF" || & bytes[8..12] != b"WAVE" {
+        return Err("Error: not a RIFF WAVE file".to_string());
+    }
+    let mut chunks = Vec::new();
+    let mut position = 12;
+    while position + 8 <= bytes.len() {
+        let chunk_id = [bytes[position], bytes[position + 1], bytes[position + 2], bytes[position + 3]];
+        let chunk_size = read_u32(bytes, position + 4) as usize;
+        let start = position + 8;
+        // Some writers leave the data size at 0 or too big when streaming.
+        let end = usize::min(start + chunk_size, bytes.len());
+        chunks.push((chunk_id, & bytes[start..end]));
+        // Chunks are padded to an even size.
+        position = start + chunk_size + (chunk_size & 1);
+    }
+    Ok(chunks)
+}
+
+/// Decodes the bytes of a WAV file.
+pub fn decode_wav(bytes: & [u8]) -> Result<(AudioBuffer, SampleFormat), String> {
+    let mut format: Option<(u16, u16, u32, u16)> = None;
+    let mut channel_mask = 0;
+    let mut data: Option<& [u8]> = None;
+    for (chunk_id, chunk) in riff_chunks(bytes)? {
+        match & chunk_id {
+            b"fmt " => {
+                if chunk.len() < 16 {
+                    return Err("Error: WAV fmt chunk too short".to_string());
+                }
+                let mut format_tag = read_u16(chunk, 0);
+                let num_channels = read_u16(chunk, 2);
+                let sample_rate = read_u32(chunk, 4);
+                let bits_per_sample = read_u16(chunk, 14);
+                if format_tag == WAVE_FORMAT_EXTENSIBLE {
+                    if chunk.len() < 40 {
+                        return Err("Error: WAV extensible fmt chunk too short".to_string());
+                    }
+                    channel_mask = read_u32(chunk, 20);
+                    // The first 2 bytes of the sub format GUID are the format tag.
+                    format_tag = read_u16(chunk, 24);
+                }
+                format = Some((format_tag, num_channels, sample_rate, bits_per_sample));
+            }
+            b"data" => data = Some(chunk),
+            _ => {}
+        }
+    }
+
+    let (format_tag, num_channels, sample_rate, bits_per_sample) = format.ok_or("Error: WAV file without fmt chunk")?;
+    let data = data.ok_or("Error: WAV file without data chunk")?;
+    let sample_format = match (format_tag, bits_per_sample) {
+        (WAVE_FORMAT_PCM, 8)         => SampleFormat::Int8,
+        (WAVE_FORMAT_PCM, 16)        => SampleFormat::Int16,
+        (WAVE_FORMAT_PCM, 24)        => SampleFormat::Int24,
+        (WAVE_FORMAT_PCM, 32)        => SampleFormat::Int32,
+        (WAVE_FORMAT_IEEE_FLOAT, 32) => SampleFormat::Float32,
+        (WAVE_FORMAT_IEEE_FLOAT, 64) => SampleFormat::Float64,
+        _ => return Err(format!("Error: unsupported WAV format {} with {} bits per sample",
+                                format_tag, bits_per_sample)),
+    };
+    if num_channels == 0 || sample_rate == 0 {
+        return Err("Error: WAV file with 0 channels or 0 sample rate".to_string());
+    }
+
+    let num_channels = num_channels as usize;
+    let bytes_per_sample = bits_per_sample as usize / 8;
+    let num_frames = data.len() / (bytes_per_sample * num_channels);
+    let mut channels = vec![Vec::with_capacity(num_frames); num_channels];
+    for frame in data.chunks_exact(bytes_per_sample * num_channels) {
+        for (channel, sample_bytes) in frame.chunks_exact(bytes_per_sample).enumerate() {
+            channels[channel].push(decode_sample(sample_bytes, sample_format));
+        }
+    }
+
+    let layout = ChannelLayout::from_wav_channel_mask(channel_mask, num_channels);
+    Ok((AudioBuffer::new(sample_rate, channels).with_layout(layout)?, sample_format))
+}
+
+/// Encodes the audio as the bytes of a WAV file.
+pub fn encode_wav(audio: & AudioBuffer, sample_format: SampleFormat) -> Result<Vec<u8>, String> {
+    if sample_format == SampleFormat::Int8 || sample_format == SampleFormat::Float64 {
+        return Err(format!("Error: writing WAV files in the {:?} format isn't supported", sample_format));
+    }
+    let num_channels = audio.get_num_channels();
+    let bits_per_sample = sample_format.get_bits_per_sample();
+    let bytes_per_sample = bits_per_sample as usize / 8;
+    let block_align = num_channels * bytes_per_sample;
+    let data_size = audio.get_num_frames() * block_align;
+    // More than 2 channels need the WAVE_FORMAT_EXTENSIBLE header, for the channel mask.
+    let extensible = num_channels > 2;
+    let fmt_size: usize = if extensible { 40 } else { 16 };
+    if num_channels > u16::MAX as usize || 20 + fmt_size + data_size > u32::MAX as usize {
+        return Err("Error: the audio is too big for a WAV file".to_string());
+    }
+    let format_tag = if sample_format.is_float() { WAVE_FORMAT_IEEE_FLOAT } else { WAVE_FORMAT_PCM };
+
+    let mut bytes = Vec::with_capacity(28 + fmt_size + data_size);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(& ((20 + fmt_size + data_size) as u32).to_le_bytes());
+    bytes.extend_from_slice(b"WAVE");
+    bytes.extend_from_slice(b"fmt ");
+    bytes.extend_from_slice(& (fmt_size as u32).to_le_bytes());
+    bytes.extend_from_slice(& (if extensible { WAVE_FORMAT_EXTENSIBLE } else { format_tag }).to_le_bytes());
+    bytes.extend_from_slice(& (num_channels as u16).to_le_bytes());
+    bytes.extend_from_slice(& audio.sample_rate.to_le_bytes());
+    bytes.extend_from_slice(& (audio.sample_rate * block_align as u32).to_le_bytes());
+    bytes.extend_from_slice(& (block_align as u16).to_le_bytes());
+    bytes.extend_from_slice(& bits_per_sample.to_le_bytes());
+    if extensible {
+        // cbSize, wValidBitsPerSample, dwChannelMask and the sub format GUID,
+        // the format tag followed by the KSDATAFORMAT_SUBTYPE suffix.
+        bytes.extend_from_slice(& 22_u16.to_le_bytes());
+        bytes.extend_from_slice(& bits_per_sample.to_le_bytes());
+        bytes.extend_from_slice(& audio.layout.wav_channel_mask().to_le_bytes());
+        bytes.extend_from_slice(& format_tag.to_le_bytes());
+        bytes.extend_from_slice(& KSDATAFORMAT_SUBTYPE_SUFFIX);
+    }
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(& (data_size as u32).to_le_bytes());
+    for n in 0..audio.get_num_frames() {
+        for channel in & audio.channels {
+            encode_sample(channel[n], sample_format, & mut bytes);
+        }
+    }
+
+    Ok(bytes)
+}
+
+pub fn read_u16(bytes: & [u8], position: usize) -> u16 {
+    u16::from_le_bytes([bytes[position], bytes[position + 1]])
+}
+
+pub fn read_u32(bytes: & [u8], position: usize) -> u32 {
+    u32::from_le_bytes([bytes[position], bytes[position + 1], bytes[position + 2], bytes[position + 3]])
+}
+
+fn decode_sample(bytes: & [u8], sample_format: SampleFormat) -> f64 {
+    match sample_format {
+        // 8 bit PCM is unsigned.
+        SampleFormat::Int8    => (bytes[0] as f64 - 128.0) / 128.0,
+        SampleFormat::Int16   => i16::from_le_bytes([bytes[0], bytes[1]]).to_f64(),
+        SampleFormat::Int24   => I24::from_le_bytes([bytes[0], bytes[1], bytes[2]]).to_f64(),
+        SampleFormat::Int32   => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]).to_f64(),
+        SampleFormat::Float32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]).to_f64(),
+        SampleFormat::Float64 => f64::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3],
+                                                     bytes[4], bytes[5], bytes[6], bytes[7]]),
+    }
+}
+
+fn encode_sample(sample: f64, sample_format: SampleFormat, bytes: & mut Vec<u8>) {
+    match sample_format {
+        SampleFormat::Int16   => bytes.extend_from_slice(& i16::from_f64(sample).to_le_bytes()),
+        SampleFormat::Int24   => bytes.extend_from_slice(& I24::from_f64(sample).to_le_bytes()),
+        SampleFormat::Int32   => bytes.extend_from_slice(& i32::from_f64(sample).to_le_bytes()),
+        SampleFormat::Float32 => bytes.extend_from_slice(& f32::from_f64(sample).to_le_bytes()),
+        SampleFormat::Int8 | SampleFormat::Float64 => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::temp_test_path;
+
+    fn make_test_audio() -> AudioBuffer {
+        let left: Vec<f64> = (0..100).map(|n| f64::sin(n as f64 * 0.1) * 0.9).collect();
+        let right: Vec<f64> = (0..100).map(|n| -0.5 + n as f64 * 0.01).collect();
+        AudioBuffer::new(44_100, vec![left, right])
+    }
+
+    #[test]
+    fn test_wav_round_trip() {
+        let audio = make_test_audio();
+        for (sample_format, tolerance) in [(SampleFormat::Int16, 1.0 / 32_768.0),
+                                           (SampleFormat::Int24, 1.0 / 8_388_608.0),
+                                           (SampleFormat::Int32, 1e-9),
+                                           (SampleFormat::Float32, 1e-7)] {
+            let bytes = encode_wav(& audio, sample_format).unwrap();
+            let (decoded, decoded_format) = decode_wav(& bytes).unwrap();
+            assert_eq!(decoded_format, sample_format);
+            assert_eq!(decoded.sample_rate, 44_100);
+            assert_eq!(decoded.get_num_channels(), 2);
+            assert_eq!(decoded.get_num_frames(), 100);
+            for (channel, decoded_channel) in audio.channels.iter().zip(& decoded.channels) {
+                for (x, y) in channel.iter().zip(decoded_channel) {
+                    assert!((x - y).abs() <= tolerance);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_wav_decode_extensible_and_errors() {
+        // Mono 16 bit WAVE_FORMAT_EXTENSIBLE, with an odd sized chunk before the data.
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(& 0_u32.to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(& 40_u32.to_le_bytes());
+        bytes.extend_from_slice(& [0xFE, 0xFF, 1, 0]);
+        bytes.extend_from_slice(& 8_000_u32.to_le_bytes());
+        bytes.extend_from_slice(& 16_000_u32.to_le_bytes());
+        bytes.extend_from_slice(& [2, 0, 16, 0, 22, 0, 16, 0, 4, 0, 0, 0, 1, 0]);
+        bytes.extend_from_slice(& [0; 14]);
+        bytes.extend_from_slice(b"LIST");
+        bytes.extend_from_slice(& 3_u32.to_le_bytes());
+        bytes.extend_from_slice(& [1, 2, 3, 0]);
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(& 4_u32.to_le_bytes());
+        bytes.extend_from_slice(& 16_384_i16.to_le_bytes());
+        bytes.extend_from_slice(& (-32_768_i16).to_le_bytes());
+        let (audio, sample_format) = decode_wav(& bytes).unwrap();
+        assert_eq!(sample_format, SampleFormat::Int16);
+        assert_eq!(audio.channels, vec![vec![0.5, -1.0]]);
+
+        assert_eq!(audio.layout, ChannelLayout::Mono);
+
+        assert!(decode_wav(b"RIFF0000WAVX").is_err());
+        assert!(encode_wav(& audio, SampleFormat::Int8).is_err());
+    }
+
+    #[test]
+    fn test_wav_channel_layout_round_trip() {
+        for layout in [ChannelLayout::Surround51, ChannelLayout::AmbisonicsFirstOrder, ChannelLayout::Discrete(3)] {
+            let channels = (0..layout.channel_count()).map(|c| vec![c as f64 * 0.125; 10]).collect();
+            let audio = AudioBuffer::new(48_000, channels).with_layout(layout).unwrap();
+            let bytes = encode_wav(& audio, SampleFormat::Float32).unwrap();
+            assert_eq!(read_u16(& bytes, 20), WAVE_FORMAT_EXTENSIBLE);
+            assert_eq!(read_u32(& bytes, 40), layout.wav_channel_mask());
+            assert_eq!(read_u32(& bytes, 4) as usize, bytes.len() - 8);
+            let (decoded, sample_format) = decode_wav(& bytes).unwrap();
+            assert_eq!(sample_format, SampleFormat::Float32);
+            // The ambisonics have no speaker positions, they are read back as discrete.
+            let expected = if layout == ChannelLayout::AmbisonicsFirstOrder { ChannelLayout::Discrete(4) } else { layout };
+            assert_eq!(decoded.layout, expected);
+            assert_eq!(decoded.channels, audio.channels);
+        }
+    }
+
+    #[test]
+    fn test_filter_wav_file() {
+        use crate::butterworth_filter::make_lowpass;
+        use crate::equalizer::Equalizer;
+
+        let (input_path, output_path) = (& temp_test_path("filter_input.wav"), & temp_test_path("filter_output.wav"));
+        let audio = make_test_audio();
+        let mono = AudioBuffer::new(44_100, vec![audio.channels[0].clone()]);
+        std::fs::write(input_path, encode_wav(& mono, SampleFormat::Float32).unwrap()).unwrap();
+        filter_wav_file(input_path, output_path, & mut make_lowpass(1_000.0, 44_100, None)).unwrap();
+        let (output, sample_format) = read_wav(output_path).unwrap();
+        let (input, _) = read_wav(input_path).unwrap();
+        let mut lowpass = make_lowpass(1_000.0, 44_100, None);
+        assert_eq!(sample_format, SampleFormat::Float32);
+        for (x, y) in input.channels[0].iter().zip(& output.channels[0]) {
+            assert!((lowpass.process(*x) - y).abs() < 1e-7);
+        }
+
+        // Stereo, an equalizer for each channel.
+        write_wav(input_path, & audio, SampleFormat::Int16).unwrap();
+        assert!(filter_wav_file(input_path, output_path, & mut make_lowpass(1_000.0, 44_100, None)).is_err());
+        let mut channels = Vec::new();
+        filter_wav_file_channels(input_path, output_path, |channel, sample_rate| {
+            channels.push(channel);
+            let mut equalizer = Equalizer::make_equalizer_10_band(sample_rate);
+            equalizer.set_band_gain(9, -12.0).unwrap();
+            equalizer
+        }).unwrap();
+        let (output, sample_format) = read_wav(output_path).unwrap();
+        assert_eq!((channels, sample_format, output.get_num_frames()), (vec![0, 1], SampleFormat::Int16, 100));
+        assert_eq!(SampleFormat::Float64.get_writable(), SampleFormat::Float32);
+        let _ = std::fs::remove_file(input_path);
+        let _ = std::fs::remove_file(output_path);
+    }
+}
@@ -0,0 +1,281 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Offline rendering with a timeline of parameter changes.
+//!              A timeline is a list of "time parameter value" changes, like the
+//!              automation lane of a DAW, that is applied at the exact sample of
+//!              each time while a file is rendered. The rendering is block based,
+//!              with the ScheduledProcessor of event_scheduler.rs, so the output is
+//!              the same for any block size, and for the same input and timeline it
+//!              is always the same, for the golden tests and the bounce-downs.
+//!
+//!              The processors expose their parameters by name with the trait
+//!              Automatable:
+//!
+//!                 ParametricEq:  preamp_db, band<i>.frequency, band<i>.gain_db,
+//!                                band<i>.q
+//!                 Equalizer:     band<i>.gain_db
+//!
+//!              The text format of a timeline has one change per line, the time in
+//!              seconds, the parameter and the value, and comments after a '#':
+//!
+//!                 # Fade the bass boost in and out.
+//!                 0.0   band0.gain_db   0.0
+//!                 1.5   band0.gain_db   6.0
+//!                 3.0   band0.gain_db   0.0
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::audio_buffer::AudioBuffer;
+use crate::wav_metadata::read_wav_with_metadata;
+use crate::wav_metadata::write_wav_with_metadata;
+use crate::event_scheduler::ScheduledProcessor;
+use crate::parametric_eq::ParametricEq;
+use crate::equalizer::Equalizer;
+
+
+/// A processor with parameters that can be changed by name.
+pub trait Automatable {
+    /// Changes the parameter, on error the processor is unchanged.
+    fn set_parameter(& mut self, name: & str, value: f64) -> Result<(), String>;
+}
+
+// Splits "band<i>.<name>" in the band index and the name.
+fn parse_band_parameter(name: & str) -> Option<(usize, & str)> {
+    let (band, parameter) = name.strip_prefix("band")?.split_once('.')?;
+    Some((band.parse().ok()?, parameter))
+}
+
+fn unknown_parameter(name: & str) -> String {
+    format!("Error: unknown parameter \"{}\"", name)
+}
+
+impl Automatable for ParametricEq {
+    fn set_parameter(& mut self, name: & str, value: f64) -> Result<(), String> {
+        if name == "preamp_db" {
+            self.set_preamp_db(value);
+            return Ok(());
+        }
+        let (index, parameter) = parse_band_parameter(name).ok_or_else(|| unknown_parameter(name))?;
+        if index >= self.get_num_bands() {
+            return Err(format!("Error: parameter \"{}\" of the band {}, the EQ has {} bands",
+                               name, index, self.get_num_bands()));
+        }
+        let mut band = self.get_band(index);
+        match parameter {
+            "frequency" => band.frequency = value,
+            "gain_db"   => band.gain_db = value,
+            "q"         => band.q_factor = value,
+            _ => return Err(unknown_parameter(name)),
+        }
+        self.set_band(index, band)
+    }
+}
+
+impl Automatable for Equalizer {
+    fn set_parameter(& mut self, name: & str, value: f64) -> Result<(), String> {
+        match parse_band_parameter(name) {
+            Some((index, "gain_db")) if index < self.get_num_bands() => self.set_band_gain(index, value),
+            _ => Err(unknown_parameter(name)),
+        }
+    }
+}
+
+/// One change of the timeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutomationPoint {
+    pub time_seconds: f64,
+    pub parameter:    String,
+    pub value:        f64,
+}
+
+/// The parameter changes, sorted by time. Changes at the same time keep the order
+/// in which they were added.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AutomationTimeline {
+    points: Vec<AutomationPoint>,
+}
+
+impl AutomationTimeline {
+    pub fn new() -> Self {
+        AutomationTimeline { points: Vec::new() }
+    }
+
+    pub fn get_points(& self) -> & [AutomationPoint] {
+        & self.points
+    }
+
+    pub fn add(& mut self, time_seconds: f64, parameter: & str, value: f64) -> Result<(), String> {
+        if !(time_seconds >= 0.0 && time_seconds.is_finite() && value.is_finite()) {
+            return Err(format!("Error: invalid automation of \"{}\", time {} s, value {}",
+                               parameter, time_seconds, value));
+        }
+        let position = self.points.partition_point(|point| point.time_seconds <= time_seconds);
+        self.points.insert(position, AutomationPoint { time_seconds, parameter: parameter.to_string(), value });
+        Ok(())
+    }
+
+    /// Parses the text format of the module description.
+    pub fn parse(text: & str) -> Result<Self, String> {
+        let mut timeline = AutomationTimeline::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let fields: Vec<& str> = line.split_whitespace().collect();
+            let parsed = match fields.as_slice() {
+                [time, parameter, value] => time.parse::<f64>().ok().zip(value.parse::<f64>().ok())
+                    .map(|(time, value)| (time, *parameter, value)),
+                _ => None,
+            };
+            let (time, parameter, value) = parsed.ok_or_else(||
+                format!("Error: line {} of the timeline isn't \"time parameter value\": {}", index + 1, line))?;
+            timeline.add(time, parameter, value)?;
+        }
+        Ok(timeline)
+    }
+
+    /// Writes the timeline in the text format.
+    pub fn write(& self) -> String {
+        self.points.iter()
+            .map(|point| format!("{} {} {}\n", point.time_seconds, point.parameter, point.value))
+            .collect()
+    }
+}
+
+/// Renders each channel of the audio through a new processor, with the changes of
+/// the timeline at their exact sample, processing blocks of block_size samples.
+/// The timeline is checked on a processor before the rendering.
+///
+/// In Rust:
+///    ```text
+///    >>> let timeline = AutomationTimeline::parse(& std::fs::read_to_string("sweep.txt")?)?;
+///    >>> let rendered = render_automation(& audio, & timeline, 512,
+///    >>>     || ParametricEq::from_bands(48_000, 0.0, & bands).unwrap())?;
+///    ```
+///
+pub fn render_automation<B, M>(audio: & AudioBuffer, timeline: & AutomationTimeline, block_size: usize,
+                               mut make_processor: M) -> Result<AudioBuffer, String>
+        where B: ProcessingBlock + Automatable, M: FnMut() -> B {
+    assert!(block_size > 0);
+    let _span = trace_span!("render_automation", frames = audio.get_num_frames(), points = timeline.points.len());
+    let mut check = make_processor();
+    for point in & timeline.points {
+        check.set_parameter(& point.parameter, point.value)
+            .map_err(|error| format!("{} at {} s", error, point.time_seconds))?;
+    }
+
+    let mut channels = Vec::with_capacity(audio.get_num_channels());
+    for input in & audio.channels {
+        let mut error = None;
+        let mut output = vec![0.0; input.len()];
+        {
+            let mut scheduled = ScheduledProcessor::new(make_processor(), |processor: & mut B, point: & AutomationPoint| {
+                if let Err(message) = processor.set_parameter(& point.parameter, point.value) {
+                    error.get_or_insert(message);
+                }
+            });
+            for point in & timeline.points {
+                let frame = (point.time_seconds * audio.sample_rate as f64).round() as u64;
+                scheduled.schedule(frame, point);
+            }
+            for (x, y) in input.chunks(block_size).zip(output.chunks_mut(block_size)) {
+                scheduled.process_block(x, y);
+            }
+        }
+        if let Some(message) = error {
+            return Err(message);
+        }
+        channels.push(output);
+    }
+    Ok(audio.with_channels(audio.sample_rate, channels))
+}
+
+/// Renders a WAV file with the timeline, the output has the sample format and the
+/// metadata of the input.
+pub fn render_automation_file<B, M>(input_path: & str, output_path: & str, timeline: & AutomationTimeline,
+                                    make_processor: M) -> Result<(), String>
+        where B: ProcessingBlock + Automatable, M: FnMut() -> B {
+    let (audio, sample_format, metadata) = read_wav_with_metadata(input_path)?;
+    let rendered = render_automation(& audio, timeline, 1_024, make_processor)?;
+    write_wav_with_metadata(output_path, & rendered, sample_format, & metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_io::SampleFormat;
+    use crate::audio_io::read_wav;
+    use crate::audio_io::write_wav;
+    use crate::parametric_eq::ParametricBand;
+    use crate::parametric_eq::BandType;
+    use crate::fixtures::temp_test_path;
+
+    fn make_eq() -> ParametricEq {
+        ParametricEq::from_bands(48_000, 0.0, & [ParametricBand::new(BandType::Peak, 1_000.0, 0.0, 1.0)]).unwrap()
+    }
+
+    fn make_audio() -> AudioBuffer {
+        let channel = |phase: f64| (0..48_000).map(|n| f64::sin(n as f64 * 0.05 + phase)).collect();
+        AudioBuffer::new(48_000, vec![channel(0.0), channel(1.0)])
+    }
+
+    #[test]
+    fn test_sample_accurate_rendering() {
+        let timeline = AutomationTimeline::parse("\
+            # Half the level at exactly 0.5 s.
+            0.5 preamp_db -6.020599913279624
+            0.25 band0.gain_db 6.0   # Before the preamp change.
+            0.75 band0.gain_db 0.0
+            0.75 band0.frequency 2000.0
+            ").unwrap();
+        assert_eq!(timeline.get_points()[0].time_seconds, 0.25);
+        assert_eq!(AutomationTimeline::parse(& timeline.write()).unwrap(), timeline);
+
+        let audio = make_audio();
+        let rendered = render_automation(& audio, & timeline, 1, make_eq).unwrap();
+        for block_size in [64, 1_000, 48_000] {
+            assert_eq!(render_automation(& audio, & timeline, block_size, make_eq).unwrap(), rendered);
+        }
+        // A 0 dB peak is transparent, the preamp changes at the sample 24000.
+        let (x, y) = (& audio.channels[1], & rendered.channels[1]);
+        assert!((y[11_999] - x[11_999]).abs() < 1e-9);
+        assert!((y[12_000] - x[12_000]).abs() > 1e-6);
+        assert!((y[40_000] - 0.5 * x[40_000]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_invalid_timelines() {
+        assert!(AutomationTimeline::parse("0.5 preamp_db").is_err());
+        assert!(AutomationTimeline::parse("-1.0 preamp_db 0.0").is_err());
+        assert!(AutomationTimeline::parse("soon preamp_db 0.0").is_err());
+        let audio = make_audio();
+        for text in ["0.1 band1.gain_db 3.0", "0.1 volume 3.0", "0.1 band0.frequency 30000.0"] {
+            let timeline = AutomationTimeline::parse(text).unwrap();
+            assert!(render_automation(& audio, & timeline, 256, make_eq).is_err(), "{}", text);
+        }
+        let timeline = AutomationTimeline::parse("0.1 band2.gain_db 30.0").unwrap();
+        assert!(render_automation(& audio, & timeline, 256, || Equalizer::make_equalizer_10_band(48_000)).is_err());
+    }
+
+    #[test]
+    fn test_render_file() {
+        let (input_path, output_path) = (& temp_test_path("automation_input.wav"), & temp_test_path("automation_output.wav"));
+        write_wav(input_path, & make_audio(), SampleFormat::Float32).unwrap();
+        let timeline = AutomationTimeline::parse("0.5 band3.gain_db -12.0").unwrap();
+        render_automation_file(input_path, output_path, & timeline, || Equalizer::make_equalizer_10_band(48_000))
+            .unwrap();
+        let (output, sample_format) = read_wav(output_path).unwrap();
+        assert_eq!(sample_format, SampleFormat::Float32);
+        assert_eq!(output.get_num_channels(), 2);
+        let _ = std::fs::remove_file(input_path);
+        let _ = std::fs::remove_file(output_path);
+    }
+}
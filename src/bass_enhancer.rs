@@ -0,0 +1,149 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Psychoacoustic bass enhancement.
+//!              Small speakers can't reproduce the low frequencies, but the ear
+//!              perceives the pitch of a missing fundamental from its harmonics
+//!              ("missing fundamental" effect). This block takes the bass below
+//!              the cut frequency, generates harmonics of it with a full-wave
+//!              rectifier, keeps only the harmonics that the speaker can reproduce
+//!              with a band-pass, and mixes them back with the original signal.
+//!
+//!                 input ──┬───────────────────── [high-pass] ──────────┬── output
+//!                         └─ [low-pass] ─ |x| ─ [band-pass] ─ gain ────┘
+//!
+//!              The high-pass on the direct path is optional, it removes the bass
+//!              that the speaker can't play, and that only wastes excursion.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Missing fundamental - Wikipedia
+//!       https://en.wikipedia.org/wiki/Missing_fundamental
+//!
+//!    2. Larsen, E. and Aarts, R. M. - Reproducing Low-Pitched Signals through
+//!       Small Loudspeakers. Journal of the Audio Engineering Society, 2002
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::iir_filter::IIRFilter;
+use crate::butterworth_filter::make_lowpass;
+use crate::butterworth_filter::make_highpass;
+
+
+pub struct BassEnhancer {
+    cut_frequency:      f64,
+    harmonics_gain_db:  f64,
+    harmonics_gain:     f64,
+    remove_sub_bass:    bool,
+    // Two cascaded 2nd-order low-passes, a 4th order slope for the bass extraction.
+    bass_lowpass:       [IIRFilter; 2],
+    // Band-pass of the harmonics, from the cut frequency to 4 times the cut frequency.
+    harmonics_highpass: IIRFilter,
+    harmonics_lowpass:  IIRFilter,
+    direct_highpass:    IIRFilter,
+}
+
+impl BassEnhancer {
+
+    /// Creates a bass enhancer.
+    ///
+    /// cut_frequency:     Lowest frequency that the speaker reproduces well, in Hz.
+    /// harmonics_gain_db: Level of the generated harmonics, 0 dB is the level of the
+    ///                    rectified bass.
+    /// remove_sub_bass:   High-pass the direct signal at the cut frequency.
+    pub fn new(sample_rate: u32, cut_frequency: f64, harmonics_gain_db: f64, remove_sub_bass: bool) -> Self {
+        assert!(cut_frequency > 0.0 && 4.0 * cut_frequency < sample_rate as f64 / 2.0);
+        BassEnhancer {
+            cut_frequency,
+            harmonics_gain_db,
+            harmonics_gain: 10.0_f64.powf(harmonics_gain_db / 20.0),
+            remove_sub_bass,
+            bass_lowpass: [make_lowpass(cut_frequency, sample_rate, None),
+                           make_lowpass(cut_frequency, sample_rate, None)],
+            harmonics_highpass: make_highpass(cut_frequency, sample_rate, None),
+            harmonics_lowpass: make_lowpass(4.0 * cut_frequency, sample_rate, None),
+            direct_highpass: make_highpass(cut_frequency, sample_rate, None),
+        }
+    }
+
+    /// Setting for small laptop or phone speakers.
+    pub fn make_small_speaker(sample_rate: u32) -> Self {
+        BassEnhancer::new(sample_rate, 150.0, 6.0, true)
+    }
+
+    pub fn get_cut_frequency(& self) -> f64 {
+        self.cut_frequency
+    }
+
+    pub fn get_harmonics_gain_db(& self) -> f64 {
+        self.harmonics_gain_db
+    }
+
+    pub fn set_harmonics_gain_db(& mut self, harmonics_gain_db: f64) {
+        self.harmonics_gain_db = harmonics_gain_db;
+        self.harmonics_gain = 10.0_f64.powf(harmonics_gain_db / 20.0);
+    }
+
+    pub fn get_remove_sub_bass(& self) -> bool {
+        self.remove_sub_bass
+    }
+}
+
+impl ProcessingBlock for BassEnhancer {
+    fn process(& mut self, sample: f64) -> f64 {
+        let mut bass = sample;
+        for filter in & mut self.bass_lowpass {
+            bass = filter.process(bass);
+        }
+        // The full-wave rectifier generates the even harmonics of the bass, the
+        // high-pass of the band-pass also removes its DC component.
+        let harmonics = self.harmonics_lowpass.process(self.harmonics_highpass.process(bass.abs()));
+        // Always run the direct high-pass, so that it has no transient when enabled.
+        let direct_highpassed = self.direct_highpass.process(sample);
+        let direct = if self.remove_sub_bass { direct_highpassed } else { sample };
+
+        direct + self.harmonics_gain * harmonics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::goertzel_amplitude;
+    use std::f64::consts::TAU;
+
+    fn process_sine(enhancer: & mut BassEnhancer, frequency: f64, sample_rate: u32) -> Vec<f64> {
+        // One second, only the last half is returned, after the transient.
+        let outputs: Vec<f64> = (0..sample_rate as usize)
+            .map(|n| enhancer.process(0.5 * f64::sin(TAU * frequency * n as f64 / sample_rate as f64)))
+            .collect();
+        outputs[outputs.len() / 2..].to_vec()
+    }
+
+    #[test]
+    fn test_bass_enhancer_generates_harmonics_of_bass() {
+        let sample_rate = 48_000;
+        let mut enhancer = BassEnhancer::new(sample_rate, 150.0, 6.0, true);
+        let output = process_sine(& mut enhancer, 60.0, sample_rate);
+        let fundamental = goertzel_amplitude(& output, 60.0, sample_rate);
+        let second_harmonic = goertzel_amplitude(& output, 120.0, sample_rate);
+        let fourth_harmonic = goertzel_amplitude(& output, 240.0, sample_rate);
+        assert!(fundamental < 0.1);
+        assert!(fourth_harmonic > 0.05);
+        assert!(second_harmonic > 0.05);
+    }
+
+    #[test]
+    fn test_bass_enhancer_passes_mid_frequencies() {
+        let sample_rate = 48_000;
+        let mut enhancer = BassEnhancer::make_small_speaker(sample_rate);
+        let output = process_sine(& mut enhancer, 2_000.0, sample_rate);
+        let amplitude = goertzel_amplitude(& output, 2_000.0, sample_rate);
+        assert!((20.0 * f64::log10(amplitude / 0.5)).abs() < 0.5);
+    }
+}
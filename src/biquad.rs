@@ -0,0 +1,301 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: A biquad of fixed size, with the designs of the cookbook as methods
+//!              that change its coefficients in place, set_lowpass(), set_peak(),
+//!              set_highshelf()..., and keep its state, the past inputs and outputs.
+//!              A make_* function allocates a new IIRFilter for each design, where
+//!              a sweep of the cutoff in an audio callback changes the design at
+//!              each block or each sample, without the allocation:
+//!
+//!                 >>> for block in samples.chunks_mut(64) {
+//!                 >>>     biquad.set_lowpass(lfo.next_frequency(), 48_000, Some(2.0));
+//!                 >>>     biquad.process_inplace(block);
+//!                 >>> }
+//!
+//!              The coefficients are the ones of the make_* function of the same
+//!              name, and the processing is the direct form I of IIRFilter, so the
+//!              output is bit identical to the one of the IIRFilter. The other
+//!              designs, like the constant Q peak, are set with set_coefficients().
+//!              The state of the direct form I is the one of the signal, not of the
+//!              coefficients, so a change doesn't click for a small step, but a large
+//!              step of a high Q design at each sample can still blow up, see
+//!              svf.rs and time_varying.rs for the fast modulation.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. WebAudio - Cookbook formulae for audio equalizer biquad filter coefficients
+//!       https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html
+//!
+
+
+use std::f64::consts::TAU;
+use rustfft::num_complex::Complex;
+
+use crate::iir_filter::Float; // Trait
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::iir_filter::IIRFilter;
+use crate::exact_math;
+
+
+/// A biquad with the designs in place, of f64 when the type isn't given.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut biquad = Biquad::new();
+///    >>> biquad.set_peak(1_000.0, 48_000, 6.0, Some(2.0));
+///    >>> biquad.process_inplace(& mut samples);
+///    ```
+///
+#[derive(Debug, Clone)]
+pub struct Biquad<T: Float = f64> {
+    // a_{0} a_{1} a_{2}
+    a_coeffs:       [T; 3],
+    // b_{0} b_{1} b_{2}
+    b_coeffs:       [T; 3],
+    // x[n-1] x[n-2]
+    input_history:  [T; 2],
+    // y[n-1] y[n-2]
+    output_history: [T; 2],
+}
+
+impl<T: Float> Default for Biquad<T> {
+    fn default() -> Self {
+        Biquad::new()
+    }
+}
+
+impl<T: Float> Biquad<T> {
+    /// The biquad that passes the input, until a design is set.
+    pub fn new() -> Self {
+        Biquad { a_coeffs: [T::ONE, T::ZERO, T::ZERO], b_coeffs: [T::ONE, T::ZERO, T::ZERO],
+                 input_history: [T::ZERO; 2], output_history: [T::ZERO; 2] }
+    }
+
+    /// The biquad of an IIRFilter of order 2, with its coefficients, from the zero
+    /// state.
+    pub fn from_filter(filter: & IIRFilter<T>) -> Result<Self, String> {
+        if filter.order != 2 {
+            return Err(format!("Error: expected a biquad, got a filter of order {}", filter.order));
+        }
+        let mut biquad = Biquad::new();
+        biquad.set_coefficients(& [filter.a_coeffs[0], filter.a_coeffs[1], filter.a_coeffs[2]],
+                                & [filter.b_coeffs[0], filter.b_coeffs[1], filter.b_coeffs[2]])?;
+        Ok(biquad)
+    }
+
+    /// Changes the coefficients and keeps the state. Gives an error for a_0 = 0 .
+    pub fn set_coefficients(& mut self, a_coeffs: & [T; 3], b_coeffs: & [T; 3]) -> Result<(), String> {
+        if a_coeffs[0] == T::ZERO {
+            return Err("Error: the coefficient a_0 of the biquad is 0".to_string());
+        }
+        self.a_coeffs = * a_coeffs;
+        self.b_coeffs = * b_coeffs;
+        trace_event!(a_coeffs = ?self.a_coeffs, b_coeffs = ?self.b_coeffs, "biquad coefficients updated");
+        Ok(())
+    }
+
+    /// The coefficients a_0, a_1, a_2 and b_0, b_1, b_2, not normalized, like the
+    /// ones of IIRFilter.
+    pub fn get_coefficients(& self) -> ([T; 3], [T; 3]) {
+        (self.a_coeffs, self.b_coeffs)
+    }
+
+    pub fn reset(& mut self) {
+        self.input_history = [T::ZERO; 2];
+        self.output_history = [T::ZERO; 2];
+    }
+
+    /// Frequency response H(e^jw) of the filter at a frequency, like the one of
+    /// IIRFilter. It doesn't change the filter state.
+    pub fn frequency_response(& self, frequency: f64, sample_rate: u32) -> Complex<f64> {
+        let z_inv = Complex::from_polar(1.0, -TAU * frequency / sample_rate as f64);
+        let polynomial = |coeffs: [T; 3]| coeffs.iter().rev().fold(Complex::new(0.0, 0.0), |sum, c| sum * z_inv + c.to_f64());
+        polynomial(self.b_coeffs) / polynomial(self.a_coeffs)
+    }
+
+    /// The design of make_lowpass().
+    pub fn set_lowpass(& mut self, frequency: f64, sample_rate: u32, q_factor: Option<f64>) {
+        let (_sin, _cos, alpha) = cookbook_terms(frequency, sample_rate, q_factor);
+        let b0 = (1.0 - _cos) / 2.0;
+        self.set_design([1.0 + alpha, -2.0 * _cos, 1.0 - alpha], [b0, 1.0 - _cos, b0]);
+    }
+
+    /// The design of make_highpass().
+    pub fn set_highpass(& mut self, frequency: f64, sample_rate: u32, q_factor: Option<f64>) {
+        let (_sin, _cos, alpha) = cookbook_terms(frequency, sample_rate, q_factor);
+        let b0 = (1.0 + _cos) / 2.0;
+        self.set_design([1.0 + alpha, -2.0 * _cos, 1.0 - alpha], [b0, -1.0 - _cos, b0]);
+    }
+
+    /// The design of make_bandpass(), of a peak gain of Q.
+    pub fn set_bandpass(& mut self, frequency: f64, sample_rate: u32, q_factor: Option<f64>) {
+        let (_sin, _cos, alpha) = cookbook_terms(frequency, sample_rate, q_factor);
+        let b0 = _sin / 2.0;
+        self.set_design([1.0 + alpha, -2.0 * _cos, 1.0 - alpha], [b0, 0.0, -b0]);
+    }
+
+    /// The design of make_allpass().
+    pub fn set_allpass(& mut self, frequency: f64, sample_rate: u32, q_factor: Option<f64>) {
+        let (_sin, _cos, alpha) = cookbook_terms(frequency, sample_rate, q_factor);
+        let (b0, b1, b2) = (1.0 - alpha, -2.0 * _cos, 1.0 + alpha);
+        self.set_design([b2, b1, b0], [b0, b1, b2]);
+    }
+
+    /// The design of make_peak().
+    pub fn set_peak(& mut self, frequency: f64, sample_rate: u32, gain_db: f64, q_factor: Option<f64>) {
+        let (_sin, _cos, alpha) = cookbook_terms(frequency, sample_rate, q_factor);
+        let big_a = exact_math::powf(10.0, gain_db / 40.0);
+        self.set_design([1.0 + alpha / big_a, -2.0 * _cos, 1.0 - alpha / big_a],
+                        [1.0 + alpha * big_a, -2.0 * _cos, 1.0 - alpha * big_a]);
+    }
+
+    /// The design of make_lowshelf().
+    pub fn set_lowshelf(& mut self, frequency: f64, sample_rate: u32, gain_db: f64, q_factor: Option<f64>) {
+        let (pmc, ppmc, mpc, pmpc, aa2, big_a) = shelf_terms(frequency, sample_rate, gain_db, q_factor);
+        self.set_design([ppmc + aa2, -2.0 * pmpc, ppmc - aa2],
+                        [big_a * (pmc + aa2), 2.0 * big_a * mpc, big_a * (pmc - aa2)]);
+    }
+
+    /// The design of make_highshelf().
+    pub fn set_highshelf(& mut self, frequency: f64, sample_rate: u32, gain_db: f64, q_factor: Option<f64>) {
+        let (pmc, ppmc, mpc, pmpc, aa2, big_a) = shelf_terms(frequency, sample_rate, gain_db, q_factor);
+        self.set_design([pmc + aa2, 2.0 * mpc, pmc - aa2],
+                        [big_a * (ppmc + aa2), -2.0 * big_a * pmpc, big_a * (ppmc - aa2)]);
+    }
+
+    /// The design of make_bandstop(), of the bandwidth in octaves.
+    pub fn set_bandstop(& mut self, frequency: f64, sample_rate: u32, bandwidth_octaves: f64) {
+        let (_cos, alpha) = bandwidth_terms(frequency, sample_rate, bandwidth_octaves);
+        self.set_design([1.0 + alpha, -2.0 * _cos, 1.0 - alpha], [1.0, -2.0 * _cos, 1.0]);
+    }
+
+    /// The design of make_bandpass_bw(), of the bandwidth in octaves and a gain of
+    /// 0 dB.
+    pub fn set_bandpass_bw(& mut self, frequency: f64, sample_rate: u32, bandwidth_octaves: f64) {
+        let (_cos, alpha) = bandwidth_terms(frequency, sample_rate, bandwidth_octaves);
+        self.set_design([1.0 + alpha, -2.0 * _cos, 1.0 - alpha], [alpha, 0.0, -alpha]);
+    }
+
+    // A design of the cookbook, in f64, to the type of the biquad.
+    fn set_design(& mut self, a_coeffs: [f64; 3], b_coeffs: [f64; 3]) {
+        self.a_coeffs = a_coeffs.map(T::from_f64);
+        self.b_coeffs = b_coeffs.map(T::from_f64);
+        trace_event!(a_coeffs = ?self.a_coeffs, b_coeffs = ?self.b_coeffs, "biquad coefficients updated");
+    }
+}
+
+impl<T: Float> ProcessingBlock<T> for Biquad<T> {
+    /// The same operations as the process() of IIRFilter.
+    fn process(& mut self, sample: T) -> T {
+        let mut result: T = T::ZERO;
+        result += self.b_coeffs[1] * self.input_history[0] - self.a_coeffs[1] * self.output_history[0];
+        result += self.b_coeffs[2] * self.input_history[1] - self.a_coeffs[2] * self.output_history[1];
+        result = (result + self.b_coeffs[0] * sample) / self.a_coeffs[0];
+
+        self.input_history = [sample, self.input_history[0]];
+        self.output_history = [result, self.output_history[0]];
+        result
+    }
+}
+
+// sin(w0), cos(w0) and alpha of the designs with a Q, of 1 / sqrt(2) by default.
+fn cookbook_terms(frequency: f64, sample_rate: u32, q_factor: Option<f64>) -> (f64, f64, f64) {
+    let q_factor: f64 = q_factor.unwrap_or(1.0 / f64::sqrt(2.0));
+    let w0 = TAU * frequency / sample_rate as f64;
+    let _sin = exact_math::sin(w0);
+    (_sin, exact_math::cos(w0), _sin / (2.0 * q_factor))
+}
+
+// cos(w0) and alpha of the designs with a bandwidth in octaves.
+fn bandwidth_terms(frequency: f64, sample_rate: u32, bandwidth_octaves: f64) -> (f64, f64) {
+    let w0 = TAU * frequency / sample_rate as f64;
+    let _sin = exact_math::sin(w0);
+    (exact_math::cos(w0), _sin * exact_math::sinh((exact_math::ln(2.0) / 2.0) * bandwidth_octaves * (w0 / _sin)))
+}
+
+// (A+1)-(A-1)cos(w0), (A+1)+(A-1)cos(w0), (A-1)-(A+1)cos(w0), (A-1)+(A+1)cos(w0),
+// 2 sqrt(A) alpha and A of the shelves.
+fn shelf_terms(frequency: f64, sample_rate: u32, gain_db: f64, q_factor: Option<f64>) -> (f64, f64, f64, f64, f64, f64) {
+    let (_sin, _cos, alpha) = cookbook_terms(frequency, sample_rate, q_factor);
+    let big_a = exact_math::powf(10.0, gain_db / 40.0);
+    ((big_a + 1.0) - (big_a - 1.0) * _cos, (big_a + 1.0) + (big_a - 1.0) * _cos,
+     (big_a - 1.0) - (big_a + 1.0) * _cos, (big_a - 1.0) + (big_a + 1.0) * _cos,
+     2.0 * f64::sqrt(big_a) * alpha, big_a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_allpass;
+    use crate::butterworth_filter::make_bandpass;
+    use crate::butterworth_filter::make_bandpass_bw;
+    use crate::butterworth_filter::make_bandstop;
+    use crate::butterworth_filter::make_highpass;
+    use crate::butterworth_filter::make_highshelf;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::butterworth_filter::make_lowshelf;
+    use crate::butterworth_filter::make_peak;
+
+    // A make_* function and the set_* method of the same design.
+    type DesignPair = (IIRFilter, fn(& mut Biquad));
+
+    #[test]
+    fn test_designs_are_the_make_functions() {
+        let signal: Vec<f64> = (0..500).map(|n| f64::sin(n as f64 * 0.07) + 0.3 * f64::sin(n as f64 * 2.3)).collect();
+        let designs: Vec<DesignPair> = vec![
+            (make_lowpass(1_000.0, 48_000, None),           |b| b.set_lowpass(1_000.0, 48_000, None)),
+            (make_highpass(200.0, 44_100, Some(2.0)),       |b| b.set_highpass(200.0, 44_100, Some(2.0))),
+            (make_bandpass(3_000.0, 48_000, Some(4.0)),     |b| b.set_bandpass(3_000.0, 48_000, Some(4.0))),
+            (make_allpass(500.0, 48_000, None),             |b| b.set_allpass(500.0, 48_000, None)),
+            (make_peak(1_000.0, 48_000, -6.0, Some(2.0)),   |b| b.set_peak(1_000.0, 48_000, -6.0, Some(2.0))),
+            (make_lowshelf(100.0, 48_000, 4.0, None),       |b| b.set_lowshelf(100.0, 48_000, 4.0, None)),
+            (make_highshelf(8_000.0, 96_000, -3.0, None),   |b| b.set_highshelf(8_000.0, 96_000, -3.0, None)),
+            (make_bandstop(50.0, 48_000, 1.0 / 3.0),        |b| b.set_bandstop(50.0, 48_000, 1.0 / 3.0)),
+            (make_bandpass_bw(2_000.0, 48_000, 1.0),        |b| b.set_bandpass_bw(2_000.0, 48_000, 1.0)),
+        ];
+        for (mut filter, set_design) in designs {
+            let mut biquad = Biquad::new();
+            set_design(& mut biquad);
+            let (a_coeffs, b_coeffs) = biquad.get_coefficients();
+            assert_eq!((a_coeffs.to_vec(), b_coeffs.to_vec()), (filter.a_coeffs.clone(), filter.b_coeffs.clone()));
+            assert!((biquad.frequency_response(700.0, 48_000) - filter.frequency_response(700.0, 48_000)).norm() < 1e-12);
+            assert!(signal.iter().all(|x| biquad.process(*x) == filter.process(*x)));
+        }
+
+        let mut biquad = Biquad::from_filter(& make_peak(1_000.0, 48_000, 6.0, None)).unwrap();
+        assert_eq!(biquad.get_coefficients().0.to_vec(), make_peak(1_000.0, 48_000, 6.0, None).a_coeffs);
+        assert!(biquad.set_coefficients(& [0.0, 1.0, 0.0], & [1.0, 0.0, 0.0]).is_err());
+        assert!(Biquad::from_filter(& IIRFilter::new(4)).is_err());
+        assert_eq!(Biquad::<f32>::default().process(0.5), 0.5);
+    }
+
+    #[test]
+    fn test_sweep_keeps_the_state() {
+        // A cutoff swept at each block of 32 samples, against an IIRFilter with the
+        // coefficients of a new design set at each block.
+        let signal: Vec<f64> = (0..4_800).map(|n| f64::sin(n as f64 * 0.05)).collect();
+        let mut biquad = Biquad::new();
+        let mut filter = IIRFilter::new(2);
+        for (index, block) in signal.chunks(32).enumerate() {
+            let frequency = 500.0 + 20.0 * index as f64;
+            biquad.set_lowpass(frequency, 48_000, Some(2.0));
+            let design = make_lowpass(frequency, 48_000, Some(2.0));
+            filter.set_coefficients(& design.a_coeffs, & design.b_coeffs).unwrap();
+            let mut output = block.to_vec();
+            biquad.process_inplace(& mut output);
+            assert!(block.iter().zip(& output).all(|(x, y)| filter.process(*x) == * y));
+        }
+        // Without the state, a new filter starts from zero.
+        let mut fresh = Biquad::new();
+        fresh.set_lowpass(500.0, 48_000, Some(2.0));
+        let (mut copy, x) = (biquad.clone(), signal[100]);
+        assert_ne!(copy.process(x), fresh.process(x));
+        biquad.reset();
+        assert_eq!(biquad.process(0.0), 0.0);
+    }
+}
@@ -0,0 +1,283 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Biquad calculator, the coefficients, poles and zeros and the
+//!              magnitude response of a design, like the online biquad calculators
+//!              but scriptable from the coeffs subcommand of cli.rs .
+//!              The design goes through the default DesignPolicy of design_policy.rs,
+//!              that rejects a frequency above Nyquist or a Q that makes the poles
+//!              too near the unit circle. The coefficients are normalized by a0, in
+//!              the order {b0, b1, b2, a1, a2} of coefficient_export.rs, for
+//!
+//!                 y = b0*x + b1*x1 + b2*x2 - a1*y1 - a2*y2
+//!
+//!              The zeros and the poles are the roots of b0 z^2 + b1 z + b2 and of
+//!              z^2 + a1 z + a2. Each one is shown with its radius and the frequency
+//!              of its angle, f = arg(z) / (2 pi) * sample_rate. The report is text,
+//!              with a mini-plot of the magnitude for the terminal, or JSON:
+//!
+//!                 {"type": "peak", "sample_rate": 48000, "frequency": 1000, ...,
+//!                  "coefficients": {"b0": ..., "b1": ..., "b2": ..., "a1": ..., "a2": ...},
+//!                  "zeros": [{"re": ..., "im": ...}, ...], "poles": [...]}
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Biquad calculator v3 - EarLevel Engineering
+//!       https://www.earlevel.com/main/2021/09/02/biquad-calculator-v3/
+//!
+
+
+use std::f64::consts::FRAC_1_SQRT_2;
+use std::f64::consts::TAU;
+use rustfft::num_complex::Complex;
+
+use crate::design_policy::DesignPolicy;
+use crate::design_policy::FilterKind;
+use crate::coefficient_export::normalized_biquad_coefficients;
+use crate::show_response::render_magnitude_ascii;
+use crate::design_report::DesignReport;
+use crate::note_names::frequency_to_note;
+
+
+/// Names of the filter types of parse_filter_kind().
+pub const FILTER_KIND_NAMES: [&str; 18] = ["lowpass", "highpass", "bandpass", "allpass", "peak", "peak_constant_q",
+                                           "lowshelf", "highshelf", "notch", "bandstop", "bandpass_bw",
+                                           "butterworth_lowpass", "butterworth_highpass", "linkwitz_riley_lowpass",
+                                           "linkwitz_riley_highpass", "bessel_lowpass", "bessel_highpass",
+                                           "linkwitz_transform"];
+pub const DEFAULT_Q_FACTOR: f64 = FRAC_1_SQRT_2;
+/// Order of the cascades of parse_filter_kind().
+pub const DEFAULT_ORDER: usize = 4;
+/// Size of the magnitude plot, in characters.
+pub const DEFAULT_PLOT_WIDTH: usize = 64;
+pub const DEFAULT_PLOT_HEIGHT: usize = 13;
+
+/// The filter type of a name of FILTER_KIND_NAMES. The cascades are of
+/// DEFAULT_ORDER, see FilterKind::with_order(), and the Linkwitz transform has
+/// no target, the target frequency is 0 Hz.
+pub fn parse_filter_kind(name: & str) -> Result<FilterKind, String> {
+    let kinds = [FilterKind::LowPass, FilterKind::HighPass, FilterKind::BandPass, FilterKind::AllPass,
+                 FilterKind::Peak, FilterKind::PeakConstantQ, FilterKind::LowShelf, FilterKind::HighShelf,
+                 FilterKind::Notch, FilterKind::BandStop, FilterKind::BandPassBw,
+                 FilterKind::ButterworthLowPass(DEFAULT_ORDER), FilterKind::ButterworthHighPass(DEFAULT_ORDER),
+                 FilterKind::LinkwitzRileyLowPass(DEFAULT_ORDER), FilterKind::LinkwitzRileyHighPass(DEFAULT_ORDER),
+                 FilterKind::BesselLowPass(DEFAULT_ORDER), FilterKind::BesselHighPass(DEFAULT_ORDER),
+                 FilterKind::LinkwitzTransform { target_frequency: 0.0, target_q: DEFAULT_Q_FACTOR }];
+    FILTER_KIND_NAMES.iter().position(|n| n.eq_ignore_ascii_case(name))
+        .map(|index| kinds[index])
+        .ok_or(format!("Error: unknown filter type \"{}\", the types are {}", name, FILTER_KIND_NAMES.join(", ")))
+}
+
+fn kind_name(kind: FilterKind) -> & 'static str {
+    let index = match kind {
+        FilterKind::LowPass       => 0,
+        FilterKind::HighPass      => 1,
+        FilterKind::BandPass      => 2,
+        FilterKind::AllPass       => 3,
+        FilterKind::Peak          => 4,
+        FilterKind::PeakConstantQ => 5,
+        FilterKind::LowShelf      => 6,
+        FilterKind::HighShelf     => 7,
+        FilterKind::Notch         => 8,
+        FilterKind::BandStop      => 9,
+        FilterKind::BandPassBw    => 10,
+        FilterKind::ButterworthLowPass(_)    => 11,
+        FilterKind::ButterworthHighPass(_)   => 12,
+        FilterKind::LinkwitzRileyLowPass(_)  => 13,
+        FilterKind::LinkwitzRileyHighPass(_) => 14,
+        FilterKind::BesselLowPass(_)         => 15,
+        FilterKind::BesselHighPass(_)        => 16,
+        FilterKind::LinkwitzTransform { .. } => 17,
+    };
+    FILTER_KIND_NAMES[index]
+}
+
+/// The roots of c0 z^2 + c1 z + c2, one root when c0 is 0 and none when both
+/// c0 and c1 are 0.
+pub fn quadratic_roots(c0: f64, c1: f64, c2: f64) -> Vec<Complex<f64>> {
+    if c0 == 0.0 {
+        return if c1 == 0.0 { Vec::new() } else { vec![Complex::new(-c2 / c1, 0.0)] };
+    }
+    let discriminant = c1 * c1 - 4.0 * c0 * c2;
+    let root = Complex::new(discriminant, 0.0).sqrt();
+    vec![(-c1 + root) / (2.0 * c0), (-c1 - root) / (2.0 * c0)]
+}
+
+/// The design of a biquad and its coefficients, zeros and poles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiquadCalculation {
+    pub kind:         FilterKind,
+    pub sample_rate:  u32,
+    pub frequency:    f64,
+    pub gain_db:      f64,
+    pub q_factor:     f64,
+    /// {b0, b1, b2, a1, a2}, normalized by a0.
+    pub coefficients: [f64; 5],
+    pub zeros:        Vec<Complex<f64>>,
+    pub poles:        Vec<Complex<f64>>,
+}
+
+impl BiquadCalculation {
+    /// Designs the biquad with the default DesignPolicy, the gain is ignored by the
+    /// types without one. A cascade is an error, but for a Butterworth of order 2,
+    /// of one biquad.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let calculation = BiquadCalculation::new(FilterKind::Peak, 1_000.0, 48_000, 6.0, 2.0)?;
+    ///    >>> println!("{}", calculation.format_text(true));
+    ///    ```
+    ///
+    pub fn new(kind: FilterKind, frequency: f64, sample_rate: u32, gain_db: f64, q_factor: f64) -> Result<Self, String> {
+        let design = DesignPolicy::default().make(kind, frequency, sample_rate, gain_db, q_factor)?;
+        let (kind, frequency, gain_db, q_factor) = (design.kind, design.frequency, design.gain_db, design.q_factor);
+        let coefficients = normalized_biquad_coefficients(& design.into_biquad()?)?;
+        let [b0, b1, b2, a1, a2] = coefficients;
+        Ok(BiquadCalculation {
+            kind,
+            sample_rate,
+            frequency,
+            gain_db: if kind.has_gain() { gain_db } else { 0.0 },
+            q_factor,
+            coefficients,
+            zeros: quadratic_roots(b0, b1, b2),
+            poles: quadratic_roots(1.0, a1, a2),
+        })
+    }
+
+    /// Magnitude response at the frequency, in dB.
+    pub fn magnitude_db(& self, frequency: f64) -> f64 {
+        let [b0, b1, b2, a1, a2] = self.coefficients;
+        let z1 = Complex::from_polar(1.0, -TAU * frequency / self.sample_rate as f64);
+        let z2 = z1 * z1;
+        let response = (b0 + b1 * z1 + b2 * z2) / (1.0 + a1 * z1 + a2 * z2);
+        20.0 * f64::log10(response.norm())
+    }
+
+    /// The report for the terminal, with the magnitude plot or without it.
+    pub fn format_text(& self, with_plot: bool) -> String {
+        let [b0, b1, b2, a1, a2] = self.coefficients;
+        let mut text = format!("{} {} Hz", kind_name(self.kind), self.frequency);
+        if self.kind.has_gain() {
+            text += & format!(", gain {} dB", self.gain_db);
+        }
+        text += & format!(", Q {}", self.q_factor);
+        if let FilterKind::LinkwitzTransform { target_frequency, target_q } = self.kind {
+            text += & format!(", target {} Hz, Q {}", target_frequency, target_q);
+        }
+        text += & format!(", at {} Hz\n", self.sample_rate);
+        text += & format!("Nearest note {}\n\n", frequency_to_note(self.frequency));
+        text += "Coefficients, normalized by a0:\n";
+        for (name, value) in [("b0", b0), ("b1", b1), ("b2", b2), ("a1", a1), ("a2", a2)] {
+            text += & format!("    {} = {:.17}\n", name, value);
+        }
+        for (title, roots) in [("Zeros", & self.zeros), ("Poles", & self.poles)] {
+            text += & format!("\n{}:\n", title);
+            for root in roots {
+                text += & format!("    {:+.9} {:+.9}j    radius {:.9}, {:.2} Hz\n", root.re, root.im, root.norm(),
+                                  root.arg().abs() / TAU * self.sample_rate as f64);
+            }
+        }
+        if with_plot {
+            text += "\nMagnitude (dB):\n";
+            text += & self.plot_magnitude(DEFAULT_PLOT_WIDTH, DEFAULT_PLOT_HEIGHT);
+        }
+        text
+    }
+
+    /// The report as a JSON object.
+    pub fn format_json(& self) -> String {
+        let [b0, b1, b2, a1, a2] = self.coefficients;
+        let roots = |roots: & [Complex<f64>]| roots.iter()
+            .map(|root| format!("{{\"re\": {}, \"im\": {}}}", root.re, root.im))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let target = match self.kind {
+            FilterKind::LinkwitzTransform { target_frequency, target_q } =>
+                format!("\"target_frequency\": {}, \"target_q\": {}, ", target_frequency, target_q),
+            _ => String::new(),
+        };
+        format!("{{\"type\": \"{}\", \"sample_rate\": {}, \"frequency\": {}, \"gain_db\": {}, \"q\": {}, {}\
+                 \"coefficients\": {{\"b0\": {}, \"b1\": {}, \"b2\": {}, \"a1\": {}, \"a2\": {}}}, \
+                 \"zeros\": [{}], \"poles\": [{}]}}",
+                kind_name(self.kind), self.sample_rate, self.frequency, self.gain_db, self.q_factor, target,
+                b0, b1, b2, a1, a2, roots(& self.zeros), roots(& self.poles))
+    }
+
+    /// The intermediate values of the design, w0, alpha, A and the others, see
+    /// design_report.rs , an error for the types without a report, like the
+    /// Linkwitz transform.
+    pub fn design_report(& self) -> Result<DesignReport, String> {
+        Ok(self.kind.make_with_report(self.frequency, self.sample_rate, self.gain_db, self.q_factor)?.1)
+    }
+
+    /// Magnitude plot with characters, see render_magnitude_ascii().
+    pub fn plot_magnitude(& self, width: usize, height: usize) -> String {
+        render_magnitude_ascii(|frequency| self.magnitude_db(frequency), self.sample_rate, width, height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::design_policy::max_chain_pole_radius;
+
+    #[test]
+    fn test_lowpass_poles_and_zeros() {
+        let calculation = BiquadCalculation::new(FilterKind::LowPass, 1_000.0, 48_000, 0.0, DEFAULT_Q_FACTOR).unwrap();
+        // The double zero at Nyquist.
+        assert_eq!(calculation.zeros.len(), 2);
+        assert!(calculation.zeros.iter().all(|zero| (zero - Complex::new(-1.0, 0.0)).norm() < 1e-6));
+        let filter = FilterKind::LowPass.make(1_000.0, 48_000, 0.0, DEFAULT_Q_FACTOR).unwrap();
+        assert!(calculation.poles.iter().all(|pole| (pole.norm() - max_chain_pole_radius(& filter)).abs() < 1e-12));
+        assert!((calculation.poles[0] - calculation.poles[1].conj()).norm() < 1e-12);
+        assert!((calculation.magnitude_db(1_000.0) + 3.0103).abs() < 1e-3);
+
+        let roots = quadratic_roots(1.0, -3.0, 2.0);
+        assert_eq!(roots, vec![Complex::new(2.0, 0.0), Complex::new(1.0, 0.0)]);
+        assert_eq!(quadratic_roots(0.0, 2.0, 1.0), vec![Complex::new(-0.5, 0.0)]);
+    }
+
+    #[test]
+    fn test_reports() {
+        let kind = parse_filter_kind("Peak").unwrap();
+        let calculation = BiquadCalculation::new(kind, 1_000.0, 48_000, 6.0, 2.0).unwrap();
+        let text = calculation.format_text(true);
+        assert!(text.starts_with("peak 1000 Hz, gain 6 dB, Q 2, at 48000 Hz"));
+        assert!(text.contains("\nNearest note B5+21c\n"));
+        assert!(text.contains("b0 = ") && text.contains("Poles:"));
+        assert!(text.contains('*') && text.ends_with("10k Hz\n"));
+        let report = calculation.design_report().unwrap();
+        assert_eq!(report.get_value("A"), Some(f64::powf(10.0, 6.0 / 40.0)));
+        assert!(report.poles.iter().zip(& calculation.poles).all(|(a, b)| (a - b).norm() < 1e-12));
+
+        let json = calculation.format_json();
+        assert!(json.starts_with("{\"type\": \"peak\", \"sample_rate\": 48000, \"frequency\": 1000, \"gain_db\": 6,"));
+        assert_eq!(json.matches("\"re\"").count(), 4);
+        assert_eq!(json.matches('{').count(), json.matches('}').count());
+        assert!(!json.contains("NaN") && !json.contains("inf"));
+
+        let plot = calculation.plot_magnitude(40, 9);
+        assert_eq!(plot.lines().count(), 11);
+        assert!(plot.lines().take(9).all(|line| line.chars().count() <= 47));
+
+        assert!(parse_filter_kind("comb").is_err());
+        assert_eq!(parse_filter_kind("bandpass_bw").unwrap(), FilterKind::BandPassBw);
+        assert_eq!(kind_name(parse_filter_kind("BandStop").unwrap()), "bandstop");
+        assert!(BiquadCalculation::new(FilterKind::LowPass, 30_000.0, 48_000, 0.0, 1.0).is_err());
+
+        assert_eq!(parse_filter_kind("bessel_highpass").unwrap(), FilterKind::BesselHighPass(DEFAULT_ORDER));
+        assert!(BiquadCalculation::new(FilterKind::ButterworthLowPass(4), 1_000.0, 48_000, 0.0, 1.0).is_err());
+        let calculation = BiquadCalculation::new(FilterKind::ButterworthLowPass(2), 1_000.0, 48_000, 0.0, 1.0).unwrap();
+        assert!((calculation.magnitude_db(1_000.0) + 3.0103).abs() < 1e-3);
+        let transform = FilterKind::LinkwitzTransform { target_frequency: 30.0, target_q: 0.707 };
+        let calculation = BiquadCalculation::new(transform, 60.0, 48_000, 0.0, 0.9).unwrap();
+        assert!(calculation.format_text(false).starts_with("linkwitz_transform 60 Hz, Q 0.9, target 30 Hz, Q 0.707,"));
+        assert!(calculation.format_json().contains("\"target_frequency\": 30, \"target_q\": 0.707, \"coefficients\""));
+        assert!(calculation.design_report().is_err());
+    }
+}
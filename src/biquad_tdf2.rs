@@ -0,0 +1,225 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: A biquad in the transposed direct form II, TDF-II, the same transfer
+//!              function as the biquad of IIRFilter, in direct form I, with two
+//!              values of state instead of four:
+//!
+//!                 y  = b0 x + s1
+//!                 s1 = b1 x - a1 y + s2
+//!                 s2 = b2 x - a2 y
+//!
+//!              with the coefficients normalized by a0 once, when they are set. The
+//!              state s1 and s2 is the sum of the past terms, of the size of the
+//!              output, instead of the past inputs and outputs, and in floating
+//!              point the sums of the terms of the inputs and of the outputs, that
+//!              cancel each other at a low frequency or a high Q, are rounded each
+//!              step, see the test, where the DF1 rounds a single sum of five large
+//!              terms. It is the form of lfilter() of scipy and of the state of
+//!              process_with_state() of iir_filter.rs .
+//!              The coefficients are changed with set_coefficients() keeping the
+//!              state, for the modulated filters, the state of the TDF-II is the
+//!              one of the new coefficients from the next sample. A BiquadTDF2 is made
+//!              of any IIRFilter of order 1 or 2, like each section of a FilterChain,
+//!              and the cascade of the sections of a design is a ProcessingChain.
+//!              For the round-off noise of the TDF-II in fixed point see
+//!              roundoff_noise.rs .
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Digital biquad filter - Transposed direct forms - Wikipedia
+//!       https://en.wikipedia.org/wiki/Digital_biquad_filter#Transposed_direct_forms
+//!
+//!    2. Smith, J. O. - Introduction to Digital Filters, Transposed Direct-Forms
+//!       https://ccrma.stanford.edu/~jos/fp/Transposed_Direct_Forms.html
+//!
+
+
+use rustfft::num_complex::Complex;
+
+use crate::iir_filter::Float; // Trait
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::iir_filter::IIRFilter;
+use crate::iir_filter::FilterChain;
+
+
+/// A biquad in the transposed direct form II, of f64 when the type isn't given.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut filter = BiquadTDF2::from_filter(& make_lowpass(20.0, 48_000, None))?;
+///    >>> filter.process_inplace(& mut samples);
+///    ```
+///
+#[derive(Debug, Clone)]
+pub struct BiquadTDF2<T: Float = f64> {
+    // b_{0} b_{1} b_{2}, normalized by a_{0}
+    b_coeffs: [T; 3],
+    // a_{1} a_{2}, normalized by a_{0}
+    a_coeffs: [T; 2],
+    // s_{1} s_{2}
+    state:    [T; 2],
+}
+
+impl<T: Float> BiquadTDF2<T> {
+    /// The biquad of the coefficients a_0, a_1, a_2 and b_0, b_1, b_2, like the
+    /// ones of IIRFilter::set_coefficients().
+    pub fn new(a_coeffs: & [T; 3], b_coeffs: & [T; 3]) -> Result<Self, String> {
+        let mut filter = BiquadTDF2 { b_coeffs: [T::ZERO; 3], a_coeffs: [T::ZERO; 2], state: [T::ZERO; 2] };
+        filter.set_coefficients(a_coeffs, b_coeffs)?;
+        Ok(filter)
+    }
+
+    /// The TDF-II of an IIRFilter of order 2, or of order 1 with a_2 = b_2 = 0,
+    /// from the zero state.
+    pub fn from_filter(filter: & IIRFilter<T>) -> Result<Self, String> {
+        if !(1..=2).contains(& filter.order) {
+            return Err(format!("Error: a BiquadTDF2 is of order 1 or 2, the filter is of order {}", filter.order));
+        }
+        let coefficients = |coeffs: & [T]| [coeffs[0], coeffs[1], coeffs.get(2).copied().unwrap_or(T::ZERO)];
+        BiquadTDF2::new(& coefficients(& filter.a_coeffs), & coefficients(& filter.b_coeffs))
+    }
+
+    /// The TDF-II of each section of the chain.
+    pub fn from_chain(chain: & FilterChain<T>) -> Result<Vec<Self>, String> {
+        chain.get_filters().iter().map(BiquadTDF2::from_filter).collect()
+    }
+
+    /// Changes the coefficients and keeps the state. Gives an error for a_0 = 0 .
+    pub fn set_coefficients(& mut self, a_coeffs: & [T; 3], b_coeffs: & [T; 3]) -> Result<(), String> {
+        let a_0 = a_coeffs[0];
+        if a_0 == T::ZERO {
+            return Err("Error: the coefficient a_0 of the biquad is 0".to_string());
+        }
+        self.b_coeffs = [b_coeffs[0] / a_0, b_coeffs[1] / a_0, b_coeffs[2] / a_0];
+        self.a_coeffs = [a_coeffs[1] / a_0, a_coeffs[2] / a_0];
+        trace_event!(a_coeffs = ?self.a_coeffs, b_coeffs = ?self.b_coeffs, "TDF-II coefficients updated");
+        Ok(())
+    }
+
+    /// The normalized coefficients, a_0 = 1, a_1, a_2 and b_0, b_1, b_2 .
+    pub fn get_coefficients(& self) -> ([T; 3], [T; 3]) {
+        ([T::ONE, self.a_coeffs[0], self.a_coeffs[1]], self.b_coeffs)
+    }
+
+    /// The state s_1, s_2 .
+    pub fn get_state(& self) -> [T; 2] {
+        self.state
+    }
+
+    /// Sets the state s_1, s_2, like the one of get_state().
+    pub fn set_state(& mut self, state: [T; 2]) {
+        self.state = state;
+    }
+
+    pub fn reset(& mut self) {
+        self.state = [T::ZERO; 2];
+    }
+
+    /// Frequency response H(e^jw) of the filter at a frequency, like the one of
+    /// IIRFilter. It doesn't change the filter state.
+    pub fn frequency_response(& self, frequency: f64, sample_rate: u32) -> Complex<f64> {
+        let (a_coeffs, b_coeffs) = self.get_coefficients();
+        let z_inv = Complex::from_polar(1.0, -std::f64::consts::TAU * frequency / sample_rate as f64);
+        let polynomial = |coeffs: [T; 3]| coeffs.iter().rev().fold(Complex::new(0.0, 0.0), |sum, c| sum * z_inv + c.to_f64());
+        polynomial(b_coeffs) / polynomial(a_coeffs)
+    }
+}
+
+impl<T: Float> ProcessingBlock<T> for BiquadTDF2<T> {
+    fn process(& mut self, sample: T) -> T {
+        let [b0, b1, b2] = self.b_coeffs;
+        let [a1, a2] = self.a_coeffs;
+        let [s1, s2] = self.state;
+        let y = b0 * sample + s1;
+        self.state = [b1 * sample - a1 * y + s2, b2 * sample - a2 * y];
+        y
+    }
+
+    fn process_buffer(& mut self, input: & [T], output: & mut [T]) {
+        assert_eq!(input.len(), output.len());
+        output.copy_from_slice(input);
+        self.process_inplace(output);
+    }
+
+    /// The coefficients and the state in local variables, the same operations as
+    /// process(), so the output is bit identical.
+    fn process_inplace(& mut self, samples: & mut [T]) {
+        let [b0, b1, b2] = self.b_coeffs;
+        let [a1, a2] = self.a_coeffs;
+        let [mut s1, mut s2] = self.state;
+        for sample in samples.iter_mut() {
+            let x = *sample;
+            let y = b0 * x + s1;
+            (s1, s2) = (b1 * x - a1 * y + s2, b2 * x - a2 * y);
+            *sample = y;
+        }
+        self.state = [s1, s2];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_butterworth_lowpass;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::butterworth_filter::make_peak;
+    use crate::processing_chain::ProcessingChain;
+    use crate::generators::make_white_noise;
+
+    #[test]
+    fn test_same_filter_as_direct_form_1() {
+        let signal: Vec<f64> = (0..3_000).map(|n| f64::sin(n as f64 * 0.05) + 0.3 * f64::sin(n as f64 * 2.1)).collect();
+        let mut direct_form_1 = make_peak(1_000.0, 48_000, 6.0, Some(2.0));
+        let mut tdf2 = BiquadTDF2::from_filter(& direct_form_1).unwrap();
+        let mut by_block = signal.clone();
+        tdf2.clone().process_inplace(& mut by_block);
+        for (x, y) in signal.iter().zip(& by_block) {
+            let expected = direct_form_1.process(*x);
+            assert!((tdf2.process(*x) - expected).abs() < 1e-12);
+            assert!((y - expected).abs() < 1e-12);
+        }
+        assert!((tdf2.frequency_response(1_000.0, 48_000) - direct_form_1.frequency_response(1_000.0, 48_000)).norm() < 1e-12);
+
+        // The sections of a design of order 5, the last one of order 1.
+        let chain = make_butterworth_lowpass(3_000.0, 48_000, 5).unwrap();
+        let mut cascade = ProcessingChain::from_stages(BiquadTDF2::from_chain(& chain).unwrap().into_iter()
+            .map(|section| Box::new(section) as Box<dyn ProcessingBlock>).collect());
+        let mut reference = chain.clone();
+        assert!(signal.iter().all(|x| (cascade.process(*x) - reference.process(*x)).abs() < 1e-12));
+
+        // The coefficients are normalized, and changed without a reset.
+        let mut filter = BiquadTDF2::new(& [2.0, -1.0, 0.5], & [1.0, 0.0, 0.0]).unwrap();
+        assert_eq!(filter.get_coefficients(), ([1.0, -0.5, 0.25], [0.5, 0.0, 0.0]));
+        filter.process(1.0);
+        filter.set_coefficients(& [1.0, 0.0, 0.0], & [1.0, 0.0, 0.0]).unwrap();
+        assert_eq!(filter.get_state(), [0.25, -0.125]);
+        filter.reset();
+        assert_eq!(filter.get_state(), [0.0, 0.0]);
+        assert!(filter.set_coefficients(& [0.0, 1.0, 0.0], & [1.0, 0.0, 0.0]).is_err());
+        assert!(BiquadTDF2::from_filter(& IIRFilter::new(4)).is_err());
+    }
+
+    #[test]
+    fn test_f32_error_at_low_frequency() {
+        // White noise through a low-pass at 20 Hz, the poles near z = 1, in f32
+        // in both forms, against the f64 filter.
+        let noise = make_white_noise(1 << 16, 0.5, 1);
+        let design = make_lowpass(20.0, 48_000, None);
+        let mut reference = design.clone();
+        let mut direct_form_1: IIRFilter<f32> = design.cast();
+        let mut tdf2 = BiquadTDF2::<f32>::from_filter(& design.cast()).unwrap();
+        let (mut error_df1, mut error_tdf2) = (0.0, 0.0);
+        for x in & noise {
+            let expected = reference.process(*x);
+            error_df1 += (direct_form_1.process(*x as f32) as f64 - expected).powi(2);
+            error_tdf2 += (tdf2.process(*x as f32) as f64 - expected).powi(2);
+        }
+        // About 16 times less error power.
+        assert!(4.0 * error_tdf2 < error_df1, "TDF-II {} DF1 {}", error_tdf2, error_df1);
+    }
+}
@@ -1,140 +1,151 @@
-/// Project: Audio filters in Rust
-/// Date:    2021.12.05
-/// Author of the port: João Nuno Carvalho
-///
-/// Description: Audio or DSP filters, allow you to attenuate or accentuate some frequencies
-///              or range of frequencies in a signal. The signal can be of any kind, but in
-///              here, we will focus on 1D signals. Like audio signals.
-///              There can also occur differences in the signal phases, that vary with the
-///              filter and the frequency components of the signal.  
-///              This is a port of Audio filters, from Python to Rust,
-///              from the Audio filter from TheAlgorithms GitHub in Python. That is by it
-///              self a port from WebAudio API implementation of the same common
-///              filters in the browsers.
-/// 
-///              The following filters are implemented over a BiQuad IIR filter:
-///                 -low-pass
-///                 -high-pass
-///                 -band-pass
-///                 -all-pass
-///                 -peak
-///                 -low-shelf
-///                 -high-shelf 
-///                 -notch
-///                 -10 band equalizer
-///  
-/// License: MIT Open Source License, like the original license from
-///    GitHub - TheAlgorithms / Python / audio_filters
-///    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
-///
-/// How to run the code. 
-/// 
-/// To make a project for this files do:
-///     -Install Rust your computer (Linux, Win, Mac, Raspberry Pi).
-///     
-///     cargo new audio_filters_in_rust
-///     cd audio_filters_in_rust
-///     
-///     -Copy the repository files to this directory and overlap them.
-/// 
-/// To compile do:
-///     cargo build --release
-/// 
-/// To run do:
-///     cargo run --release
-/// 
-/// to run the tests do:
-///     cargo test
-/// 
-/// References:
-///    1. GitHub - TheAlgorithms / Python / audio_filters
-///       https://github.com/TheAlgorithms/Python/tree/master/audio_filters
-///
-///    2. WebAudio - Cookbook formulae for audio equalizer biquad filter coefficients
-///       https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html 
-/// 
-///    3. Good resources on DSP – Digital Signal Programming
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_electronics#dsp--digital-signal-programming
-///
-///    4. Biquads - EarLevel
-///       http://www.earlevel.com/main/2003/02/28/biquads/
-///
-///    5. Biquad C++ source code - EarLevel
-///       https://www.earlevel.com/main/2012/11/26/biquad-c-source-code/
-///
-///    6. A biquad calculator V3 - EarLevel
-///       https://www.earlevel.com/main/2021/09/02/biquad-calculator-v3/
-/// 
-///    7. WebAudio API - Mozilla Docs
-///       https://developer.mozilla.org/en-US/docs/Web/API/Web_Audio_API
-/// 
-///    8. Audio Filters - Theory and Practice
-///       by Ethan Winer
-///       http://ethanwiner.com/filters.html
-/// 
-///    9. Audio filter - Wikipedia
-///       https://en.wikipedia.org/wiki/Audio_filter
-/// 
-///   10. Electronic filter - Wikipedia
-///       https://en.wikipedia.org/wiki/Electronic_filter
-///
-///   11. How to learn modern Rust
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
-///
-/// 
-/// 10 Band Equalizer
-/// 
-///   12. Making an EQ from cascading filters
-///       https://dsp.stackexchange.com/questions/10309/making-an-eq-from-cascading-filters
-/// 
-///   13. PEAK/NOTCH FILTER DESIGN
-///       https://www.dsprelated.com/showcode/169.php
-/// 
-///   14. The Equivalence of Various Methods of Computing
-///       Biquad Coefficients for Audio Parametric Equalizers
-///       http://www.thesounddesign.com/MIO/EQ-Coefficients.pdf
-///
-///   15. How to learn modern Rust
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
-///
+//! Project: Audio filters in Rust
+//! Date:    2021.12.05
+//! Author of the port: João Nuno Carvalho
+//!
+//! Description: Audio or DSP filters, allow you to attenuate or accentuate some frequencies
+//!              or range of frequencies in a signal. The signal can be of any kind, but in
+//!              here, we will focus on 1D signals. Like audio signals.
+//!              There can also occur differences in the signal phases, that vary with the
+//!              filter and the frequency components of the signal.  
+//!              This is a port of Audio filters, from Python to Rust,
+//!              from the Audio filter from TheAlgorithms GitHub in Python. That is by it
+//!              self a port from WebAudio API implementation of the same common
+//!              filters in the browsers.
+//! 
+//!              The following filters are implemented over a BiQuad IIR filter:
+//!                 -low-pass
+//!                 -high-pass
+//!                 -band-pass
+//!                 -all-pass
+//!                 -peak
+//!                 -low-shelf
+//!                 -high-shelf 
+//!                 -notch
+//!                 -band-stop and band-pass of a bandwidth in octaves
+//!                 -10 band equalizer
+//!  
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! How to run the code. 
+//! 
+//! To make a project for this files do:
+//!     -Install Rust your computer (Linux, Win, Mac, Raspberry Pi).
+//!     
+//!     cargo new audio_filters_in_rust
+//!     cd audio_filters_in_rust
+//!     
+//!     -Copy the repository files to this directory and overlap them.
+//! 
+//! To compile do:
+//!     cargo build --release
+//! 
+//! To run do:
+//!     cargo run --release
+//! 
+//! to run the tests do:
+//!     cargo test
+//! 
+//! References:
+//!    1. GitHub - TheAlgorithms / Python / audio_filters
+//!       https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//!    2. WebAudio - Cookbook formulae for audio equalizer biquad filter coefficients
+//!       https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html 
+//! 
+//!    3. Good resources on DSP – Digital Signal Programming
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_electronics#dsp--digital-signal-programming
+//!
+//!    4. Biquads - EarLevel
+//!       http://www.earlevel.com/main/2003/02/28/biquads/
+//!
+//!    5. Biquad C++ source code - EarLevel
+//!       https://www.earlevel.com/main/2012/11/26/biquad-c-source-code/
+//!
+//!    6. A biquad calculator V3 - EarLevel
+//!       https://www.earlevel.com/main/2021/09/02/biquad-calculator-v3/
+//! 
+//!    7. WebAudio API - Mozilla Docs
+//!       https://developer.mozilla.org/en-US/docs/Web/API/Web_Audio_API
+//! 
+//!    8. Audio Filters - Theory and Practice
+//!       by Ethan Winer
+//!       http://ethanwiner.com/filters.html
+//! 
+//!    9. Audio filter - Wikipedia
+//!       https://en.wikipedia.org/wiki/Audio_filter
+//! 
+//!   10. Electronic filter - Wikipedia
+//!       https://en.wikipedia.org/wiki/Electronic_filter
+//!
+//!   11. How to learn modern Rust
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
+//!
+//! 
+//! 10 Band Equalizer
+//! 
+//!   12. Making an EQ from cascading filters
+//!       https://dsp.stackexchange.com/questions/10309/making-an-eq-from-cascading-filters
+//! 
+//!   13. PEAK/NOTCH FILTER DESIGN
+//!       https://www.dsprelated.com/showcode/169.php
+//! 
+//!   14. The Equivalence of Various Methods of Computing
+//!       Biquad Coefficients for Audio Parametric Equalizers
+//!       http://www.thesounddesign.com/MIO/EQ-Coefficients.pdf
+//!
+//!   15. How to learn modern Rust
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
+//!
 
 
 use crate::iir_filter::IIRFilter;
+use crate::iir_filter::FilterChain;
+use crate::exact_math;
+use crate::design_report::DesignReport;
+use crate::sensitivity::polynomial_roots;
+use rustfft::num_complex::Complex;
 use std::f64::consts::TAU;
 use std::f64::consts::PI;
 
-/// Create 2nd-order IIR filters with Butterworth design.
-/// 
-///  Code based on https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html
-///  Alternatively you can use scipy.signal.butter, which should yield the same results.
-/// 
+// Create 2nd-order IIR filters with Butterworth design.
+// 
+//  Code based on https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html
+//  Alternatively you can use scipy.signal.butter, which should yield the same results.
+// 
 
 
 /// Creates a low-pass filter
 ///
 /// In Python: 
+///    ```text
 ///    >>> filter = make_lowpass(1000, 48000)
 ///    >>> filter.a_coeffs + filter.b_coeffs  # doctest: +NORMALIZE_WHITESPACE
 ///    [1.0922959556412573, -1.9828897227476208, 0.9077040443587427, 0.004277569313094809,
 ///    0.008555138626189618, 0.004277569313094809]
+///    ```
 /// 
 /// In Rust:
+///    ```text
 ///    >>> let filter = make_lowpass(1000, 48000);
 ///    >>> let res_coeffs: Vec<f64> = filter.a_coeffs.iter.extends(filter.b_coeffs).collect();
 ///    >>> println!("{}", res_coeffs);
 ///    [1.0922959556412573, -1.9828897227476208, 0.9077040443587427, 0.004277569313094809,
 ///    0.008555138626189618, 0.004277569313094809]
+///    ```
 ///
 pub fn make_lowpass(frequency: f64, sample_rate: u32, q_factor: Option<f64>) -> IIRFilter {
-    let q_factor: f64 = if q_factor.is_none() {
-                                1.0 / f64::sqrt(2.0)
-                        } else {
-                            q_factor.unwrap()
-                        };
+    make_lowpass_with_report(frequency, sample_rate, q_factor).0
+}
+
+/// make_lowpass() and the intermediate values of its design, see design_report.rs .
+pub fn make_lowpass_with_report(frequency: f64, sample_rate: u32, q_factor: Option<f64>) -> (IIRFilter, DesignReport) {
+    let q_factor: f64 = q_factor.unwrap_or(1.0 / f64::sqrt(2.0));
 
         let w0 = TAU * frequency / sample_rate as f64;
-        let _sin = f64::sin(w0);
-        let _cos = f64::cos(w0);
+        let _sin = exact_math::sin(w0);
+        let _cos = exact_math::cos(w0);
         let alpha = _sin / (2.0 * q_factor);
     
         let b0 = (1.0 - _cos) / 2.0;
@@ -148,27 +159,33 @@ pub fn make_lowpass(frequency: f64, sample_rate: u32, q_factor: Option<f64>) ->
         let mut filter = IIRFilter::new(filter_order);
         let _ = filter.set_coefficients(& [a0, a1, a2], & [b0, b1, b0]);
         
-        filter
+        let report = DesignReport::new("lowpass", frequency, sample_rate, None, q_factor,
+                                       vec![("w0", w0), ("sin(w0)", _sin), ("cos(w0)", _cos), ("alpha", alpha)],
+                                       & filter);
+        (filter, report)
 }
 
 /// Creates a high-pass filter
 /// 
 /// In Python:
+///    ```text
 ///    >>> filter = make_highpass(1000, 48000)
 ///    >>> filter.a_coeffs + filter.b_coeffs  # doctest: +NORMALIZE_WHITESPACE
 ///    [1.0922959556412573, -1.9828897227476208, 0.9077040443587427, 0.9957224306869052,
 ///    -1.9914448613738105, 0.9957224306869052]
+///    ```
 /// 
 pub fn make_highpass(frequency: f64, sample_rate: u32, q_factor: Option<f64>) -> IIRFilter {
-    let q_factor: f64 = if q_factor.is_none() {
-                                1.0 / f64::sqrt(2.0)
-                        } else {
-                            q_factor.unwrap()
-                        };
+    make_highpass_with_report(frequency, sample_rate, q_factor).0
+}
+
+/// make_highpass() and the intermediate values of its design, see design_report.rs .
+pub fn make_highpass_with_report(frequency: f64, sample_rate: u32, q_factor: Option<f64>) -> (IIRFilter, DesignReport) {
+    let q_factor: f64 = q_factor.unwrap_or(1.0 / f64::sqrt(2.0));
 
     let w0 = TAU * frequency / sample_rate as f64; 
-    let _sin = f64::sin(w0);
-    let _cos = f64::cos(w0);
+    let _sin = exact_math::sin(w0);
+    let _cos = exact_math::cos(w0);
     let alpha = _sin / (2.0 * q_factor);
 
     let b0 = (1.0 + _cos) / 2.0;
@@ -182,27 +199,33 @@ pub fn make_highpass(frequency: f64, sample_rate: u32, q_factor: Option<f64>) ->
     let mut filter = IIRFilter::new(filter_order);
     let _ = filter.set_coefficients(& [a0, a1, a2], & [b0, b1, b0]);
     
-    filter
+    let report = DesignReport::new("highpass", frequency, sample_rate, None, q_factor,
+                                   vec![("w0", w0), ("sin(w0)", _sin), ("cos(w0)", _cos), ("alpha", alpha)],
+                                   & filter);
+    (filter, report)
 }
 
 /// Creates a band-pass filter
 /// 
 /// In Python:
+///     ```text
 ///     >>> filter = make_bandpass(1000, 48000)
 ///     >>> filter.a_coeffs + filter.b_coeffs  # doctest: +NORMALIZE_WHITESPACE
 ///     [1.0922959556412573, -1.9828897227476208, 0.9077040443587427, 0.06526309611002579,
 ///     0, -0.06526309611002579]
+///     ```
 /// 
 pub fn make_bandpass(frequency: f64, sample_rate: u32, q_factor: Option<f64>) -> IIRFilter {
-    let q_factor: f64 = if q_factor.is_none() {
-                                1.0 / f64::sqrt(2.0)
-                        } else {
-                            q_factor.unwrap()
-                        };
+    make_bandpass_with_report(frequency, sample_rate, q_factor).0
+}
+
+/// make_bandpass() and the intermediate values of its design, see design_report.rs .
+pub fn make_bandpass_with_report(frequency: f64, sample_rate: u32, q_factor: Option<f64>) -> (IIRFilter, DesignReport) {
+    let q_factor: f64 = q_factor.unwrap_or(1.0 / f64::sqrt(2.0));
 
     let w0 = TAU * frequency / sample_rate as f64;
-    let _sin = f64::sin(w0);
-    let _cos = f64::cos(w0);
+    let _sin = exact_math::sin(w0);
+    let _cos = exact_math::cos(w0);
     let alpha = _sin / (2.0 * q_factor);
 
     let b0 = _sin / 2.0;
@@ -217,27 +240,33 @@ pub fn make_bandpass(frequency: f64, sample_rate: u32, q_factor: Option<f64>) ->
     let mut filter = IIRFilter::new(filter_order);
     let _ = filter.set_coefficients(& [a0, a1, a2], & [b0, b1, b2]);
     
-    filter
+    let report = DesignReport::new("bandpass", frequency, sample_rate, None, q_factor,
+                                   vec![("w0", w0), ("sin(w0)", _sin), ("cos(w0)", _cos), ("alpha", alpha)],
+                                   & filter);
+    (filter, report)
 }
 
 /// Creates an all-pass filter
 /// 
 /// In Python:
+///     ```text
 ///     >>> filter = make_allpass(1000, 48000)
 ///     >>> filter.a_coeffs + filter.b_coeffs  # doctest: +NORMALIZE_WHITESPACE
 ///     [1.0922959556412573, -1.9828897227476208, 0.9077040443587427, 0.9077040443587427,
 ///     -1.9828897227476208, 1.0922959556412573]
+///     ```
 ///
 pub fn make_allpass(frequency: f64, sample_rate: u32, q_factor: Option<f64>) -> IIRFilter {
-    let q_factor: f64 = if q_factor.is_none() {
-                                1.0 / f64::sqrt(2.0)
-                        } else {
-                            q_factor.unwrap()
-                        };
+    make_allpass_with_report(frequency, sample_rate, q_factor).0
+}
+
+/// make_allpass() and the intermediate values of its design, see design_report.rs .
+pub fn make_allpass_with_report(frequency: f64, sample_rate: u32, q_factor: Option<f64>) -> (IIRFilter, DesignReport) {
+    let q_factor: f64 = q_factor.unwrap_or(1.0 / f64::sqrt(2.0));
 
     let w0 = TAU * frequency / sample_rate as f64;
-    let _sin = f64::sin(w0);
-    let _cos = f64::cos(w0);
+    let _sin = exact_math::sin(w0);
+    let _cos = exact_math::cos(w0);
     let alpha = _sin / (2.0 * q_factor);
 
     let b0 =  1.0 - alpha;
@@ -248,29 +277,35 @@ pub fn make_allpass(frequency: f64, sample_rate: u32, q_factor: Option<f64>) ->
     let mut filter = IIRFilter::new(filter_order);
     let _ = filter.set_coefficients(& [b2, b1, b0], & [b0, b1, b2]);
     
-    filter
+    let report = DesignReport::new("allpass", frequency, sample_rate, None, q_factor,
+                                   vec![("w0", w0), ("sin(w0)", _sin), ("cos(w0)", _cos), ("alpha", alpha)],
+                                   & filter);
+    (filter, report)
 }
 
 /// Creates a peak filter
 ///
 /// In Python: 
+///     ```text
 ///     >>> filter = make_peak(1000, 48000, 6)
 ///     >>> filter.a_coeffs + filter.b_coeffs  # doctest: +NORMALIZE_WHITESPACE
 ///     [1.0653405327119334, -1.9828897227476208, 0.9346594672880666, 1.1303715025601122,
 ///     -1.9828897227476208, 0.8696284974398878]
+///     ```
 ///
 pub fn make_peak(frequency: f64, sample_rate: u32, gain_db: f64, q_factor: Option<f64>) -> IIRFilter {
-    let q_factor: f64 = if q_factor.is_none() {
-                                1.0 / f64::sqrt(2.0)
-                        } else {
-                            q_factor.unwrap()
-                        };
+    make_peak_with_report(frequency, sample_rate, gain_db, q_factor).0
+}
+
+/// make_peak() and the intermediate values of its design, see design_report.rs .
+pub fn make_peak_with_report(frequency: f64, sample_rate: u32, gain_db: f64, q_factor: Option<f64>) -> (IIRFilter, DesignReport) {
+    let q_factor: f64 = q_factor.unwrap_or(1.0 / f64::sqrt(2.0));
 
     let w0 = TAU * frequency / sample_rate as f64;
-    let _sin = f64::sin(w0);
-    let _cos = f64::cos(w0);
+    let _sin = exact_math::sin(w0);
+    let _cos = exact_math::cos(w0);
     let alpha = _sin / (2.0 * q_factor);
-    let big_a = 10.0_f64.powf(gain_db / 40.0);
+    let big_a = exact_math::powf(10.0, gain_db / 40.0);
 
     let b0 =  1.0 + alpha * big_a;
     let b1 = -2.0 * _cos;
@@ -283,8 +318,10 @@ pub fn make_peak(frequency: f64, sample_rate: u32, gain_db: f64, q_factor: Optio
     let mut filter = IIRFilter::new(filter_order);
     let _ = filter.set_coefficients(& [a0, a1, a2], & [b0, b1, b2]);
     
-    filter
-
+    let report = DesignReport::new("peak", frequency, sample_rate, Some(gain_db), q_factor,
+                                   vec![("w0", w0), ("sin(w0)", _sin), ("cos(w0)", _cos), ("alpha", alpha), ("A", big_a)],
+                                   & filter);
+    (filter, report)
 }
 
 // This is a peak_eq filter similar to the above peak filter but with constant Q and the gain
@@ -307,6 +344,11 @@ pub fn make_peak(frequency: f64, sample_rate: u32, gain_db: f64, q_factor: Optio
 //         http://www.thesounddesign.com/MIO/EQ-Coefficients.pdf
 //
 pub fn make_peak_eq_constant_q(frequency_center: f64, sample_rate: u32, gain_db: f64, q_factor: Option<f64>) -> IIRFilter {
+    make_peak_eq_constant_q_with_report(frequency_center, sample_rate, gain_db, q_factor).0
+}
+
+/// make_peak_eq_constant_q() and the intermediate values of its design, see design_report.rs .
+pub fn make_peak_eq_constant_q_with_report(frequency_center: f64, sample_rate: u32, gain_db: f64, q_factor: Option<f64>) -> (IIRFilter, DesignReport) {
     // This specific filter is a port to Rust with modifications from the following example code:
     //    PEAK/NOTCH FILTER DESIGN
     //    https://www.dsprelated.com/showcode/169.php#commax_container
@@ -319,15 +361,11 @@ pub fn make_peak_eq_constant_q(frequency_center: f64, sample_rate: u32, gain_db:
     // Original Author:    sparafucile17 08/22/05
     //
     
-    let q_factor: f64 = if q_factor.is_none() {
-                                1.0 / f64::sqrt(2.0)
-                        } else {
-                            q_factor.unwrap()
-                        };
+    let q_factor: f64 = q_factor.unwrap_or(1.0 / f64::sqrt(2.0));
 
     let q = q_factor;
-    let k = f64::tan((PI * frequency_center) / sample_rate as f64);
-    let mut v0 = 10.0_f64.powf(gain_db / 20.0);
+    let k = exact_math::tan((PI * frequency_center) / sample_rate as f64);
+    let mut v0 = exact_math::powf(10.0, gain_db / 20.0);
     
     // Invert gain if a cut
     if v0 < 1.0  {
@@ -340,7 +378,7 @@ pub fn make_peak_eq_constant_q(frequency_center: f64, sample_rate: u32, gain_db:
     let a1: f64;
     let a2: f64;
 
-    let _k_sqr = k.powf(2.0);
+    let _k_sqr = exact_math::powf(k, 2.0);
     //***********
     //   BOOST
     //***********
@@ -367,29 +405,35 @@ pub fn make_peak_eq_constant_q(frequency_center: f64, sample_rate: u32, gain_db:
     // Note: The BiQuad filter fill's in the a0 with i.0 automatically.
     let _ = filter.set_coefficients(& [a1, a2], & [b0, b1, b2]);
     
-    filter
+    let report = DesignReport::new("peak_constant_q", frequency_center, sample_rate, Some(gain_db), q_factor,
+                                   vec![("K", k), ("V0", v0), ("K^2", _k_sqr)],
+                                   & filter);
+    (filter, report)
 }
 
 /// Creates a low-shelf filter
 /// 
 /// In Python:
+///     ```text
 ///     >>> filter = make_lowshelf(1000, 48000, 6)
 ///     >>> filter.a_coeffs + filter.b_coeffs  # doctest: +NORMALIZE_WHITESPACE
 ///     [3.0409336710888786, -5.608870992220748, 2.602157875636628, 3.139954022810743,
 ///      -5.591841778072785, 2.5201667380627257]
+///     ```
 /// 
 pub fn make_lowshelf(frequency: f64, sample_rate: u32, gain_db: f64, q_factor: Option<f64>) -> IIRFilter {
-    let q_factor: f64 = if q_factor.is_none() {
-                                1.0 / f64::sqrt(2.0)
-                        } else {
-                            q_factor.unwrap()
-                        };
+    make_lowshelf_with_report(frequency, sample_rate, gain_db, q_factor).0
+}
+
+/// make_lowshelf() and the intermediate values of its design, see design_report.rs .
+pub fn make_lowshelf_with_report(frequency: f64, sample_rate: u32, gain_db: f64, q_factor: Option<f64>) -> (IIRFilter, DesignReport) {
+    let q_factor: f64 = q_factor.unwrap_or(1.0 / f64::sqrt(2.0));
 
     let w0 = TAU * frequency / sample_rate as f64;
-    let _sin = f64::sin(w0);
-    let _cos = f64::cos(w0);
+    let _sin = exact_math::sin(w0);
+    let _cos = exact_math::cos(w0);
     let alpha = _sin / (2.0 * q_factor);
-    let big_a = 10.0_f64.powf(gain_db / 40.0);
+    let big_a = exact_math::powf(10.0, gain_db / 40.0);
     let pmc = (big_a + 1.0) - (big_a - 1.0) * _cos;
     let ppmc = (big_a + 1.0) + (big_a - 1.0) * _cos;
     let mpc = (big_a - 1.0) - (big_a + 1.0) * _cos;
@@ -407,29 +451,37 @@ pub fn make_lowshelf(frequency: f64, sample_rate: u32, gain_db: f64, q_factor: O
     let  mut filter = IIRFilter::new(filter_order);
     let _ = filter.set_coefficients(& [a0, a1, a2], & [b0, b1, b2]);
     
-    filter
+    let report = DesignReport::new("lowshelf", frequency, sample_rate, Some(gain_db), q_factor,
+                                   vec![("w0", w0), ("sin(w0)", _sin), ("cos(w0)", _cos), ("alpha", alpha), ("A", big_a),
+                                        ("(A+1)-(A-1)cos(w0)", pmc), ("(A+1)+(A-1)cos(w0)", ppmc), ("(A-1)-(A+1)cos(w0)", mpc),
+                                        ("(A-1)+(A+1)cos(w0)", pmpc), ("2 sqrt(A) alpha", aa2)],
+                                   & filter);
+    (filter, report)
 }
 
 /// Creates a high-shelf filter
 ///
 /// In Python: 
+///     ```text
 ///     >>> filter = make_highshelf(1000, 48000, 6)
 ///     >>> filter.a_coeffs + filter.b_coeffs  # doctest: +NORMALIZE_WHITESPACE
 ///     [2.2229172136088806, -3.9587208137297303, 1.7841414181566304, 4.295432981120543,
 ///      -7.922740859457287, 3.6756456963725253]
+///     ```
 ///
 pub fn make_highshelf(frequency: f64, sample_rate: u32, gain_db: f64, q_factor: Option<f64>) -> IIRFilter {
-    let q_factor: f64 = if q_factor.is_none() {
-                                1.0 / f64::sqrt(2.0)
-                        } else {
-                            q_factor.unwrap()
-                        };
+    make_highshelf_with_report(frequency, sample_rate, gain_db, q_factor).0
+}
+
+/// make_highshelf() and the intermediate values of its design, see design_report.rs .
+pub fn make_highshelf_with_report(frequency: f64, sample_rate: u32, gain_db: f64, q_factor: Option<f64>) -> (IIRFilter, DesignReport) {
+    let q_factor: f64 = q_factor.unwrap_or(1.0 / f64::sqrt(2.0));
 
     let w0 = TAU * frequency / sample_rate as f64;
-    let _sin = f64::sin(w0);
-    let _cos = f64::cos(w0);
+    let _sin = exact_math::sin(w0);
+    let _cos = exact_math::cos(w0);
     let alpha = _sin / (2.0 * q_factor);
-    let big_a = 10.0_f64.powf(gain_db / 40.0);
+    let big_a = exact_math::powf(10.0, gain_db / 40.0);
     let pmc = (big_a + 1.0) - (big_a - 1.0) * _cos;
     let ppmc = (big_a + 1.0) + (big_a - 1.0) * _cos;
     let mpc = (big_a - 1.0) - (big_a + 1.0) * _cos;
@@ -447,43 +499,374 @@ pub fn make_highshelf(frequency: f64, sample_rate: u32, gain_db: f64, q_factor:
     let mut filter = IIRFilter::new(filter_order);
     let _ = filter.set_coefficients(& [a0, a1, a2], & [b0, b1, b2]);
     
-    filter
+    let report = DesignReport::new("highshelf", frequency, sample_rate, Some(gain_db), q_factor,
+                                   vec![("w0", w0), ("sin(w0)", _sin), ("cos(w0)", _cos), ("alpha", alpha), ("A", big_a),
+                                        ("(A+1)-(A-1)cos(w0)", pmc), ("(A+1)+(A-1)cos(w0)", ppmc), ("(A-1)-(A+1)cos(w0)", mpc),
+                                        ("(A-1)+(A+1)cos(w0)", pmpc), ("2 sqrt(A) alpha", aa2)],
+                                   & filter);
+    (filter, report)
 }
 
 
+/// The bandwidth in octaves of make_notch() when its q_factor is None, a tenth of
+/// an octave, narrow enough to remove a tone or a hum harmonic and leave the
+/// music around it.
+pub const DEFAULT_NOTCH_BANDWIDTH_OCTAVES: f64 = 0.1;
+
 /// Creates a notch filter
 ///
+/// The q_factor of the notch is its bandwidth in octaves, the BW of the cookbook,
+/// not a Q, DEFAULT_NOTCH_BANDWIDTH_OCTAVES when it's None. It's the band-stop of
+/// make_bandstop().
+///
 /// In Python: 
+///    ```text
 ///    >>> filter = make_notch(1000, 48000, 10)
 ///    >>> filter.a_coeffs + filter.b_coeffs  # doctest: +NORMALIZE_WHITESPACE
 ///    [, , , ,
 ///    , ]
+///    ```
 /// 
 pub fn make_notch(frequency: f64, sample_rate: u32, q_factor: Option<f64>) -> IIRFilter {
-    let q_factor: f64 = if q_factor.is_none() {
-                                1.0 / f64::sqrt(2.0)
-                        } else {
-                            q_factor.unwrap()
-                        };
+    make_notch_with_report(frequency, sample_rate, q_factor).0
+}
 
-        let w0 = TAU * frequency / sample_rate as f64;
-        let _sin = f64::sin(w0);
-        let _cos = f64::cos(w0);
-        use std::f64::consts::E;
-        let alpha = _sin * f64::sinh((f64::log(2.0,E) / 2.0) * q_factor * (w0 /_sin ));
-    
-        let b0 =  1.0;
-        let b1 = -2.0 * _cos;
+/// make_notch() and the intermediate values of its design, see design_report.rs .
+pub fn make_notch_with_report(frequency: f64, sample_rate: u32, q_factor: Option<f64>) -> (IIRFilter, DesignReport) {
+    let bandwidth_octaves = q_factor.unwrap_or(DEFAULT_NOTCH_BANDWIDTH_OCTAVES);
+    let (filter, mut report) = make_bandstop_with_report(frequency, sample_rate, bandwidth_octaves);
+    report.filter_type = "notch";
+    (filter, report)
+}
 
-        let a0 =  1.0 + alpha;
-        let a1 = -2.0 * _cos;
-        let a2 =  1.0 - alpha;
-    
-        let filter_order = 2;
-        let mut filter = IIRFilter::new(filter_order);
-        let _ = filter.set_coefficients(& [a0, a1, a2], & [b0, b1, b0]);
-        
-        filter
+/// Creates a band-stop filter, of the bandwidth in octaves between the -3 dB
+/// frequencies, the BW of the cookbook, with its zero at the frequency. It's the
+/// design of make_notch(), with a bandwidth that isn't optional.
+///
+/// In Rust:
+///    ```text
+///    >>> // A third of an octave around the 50 Hz hum.
+///    >>> let filter = make_bandstop(50.0, 48_000, 1.0 / 3.0);
+///    ```
+///
+pub fn make_bandstop(frequency: f64, sample_rate: u32, bandwidth_octaves: f64) -> IIRFilter {
+    make_bandstop_with_report(frequency, sample_rate, bandwidth_octaves).0
+}
+
+/// make_bandstop() and the intermediate values of its design, see design_report.rs .
+pub fn make_bandstop_with_report(frequency: f64, sample_rate: u32, bandwidth_octaves: f64) -> (IIRFilter, DesignReport) {
+    let w0 = TAU * frequency / sample_rate as f64;
+    let _sin = exact_math::sin(w0);
+    let _cos = exact_math::cos(w0);
+    let alpha = _sin * exact_math::sinh((exact_math::ln(2.0) / 2.0) * bandwidth_octaves * (w0 / _sin));
+
+    let b0 =  1.0;
+    let b1 = -2.0 * _cos;
+
+    let a0 =  1.0 + alpha;
+    let a1 = -2.0 * _cos;
+    let a2 =  1.0 - alpha;
+
+    let filter_order = 2;
+    let mut filter = IIRFilter::new(filter_order);
+    let _ = filter.set_coefficients(& [a0, a1, a2], & [b0, b1, b0]);
+
+    // The Q of the same alpha, for the report.
+    let report = DesignReport::new("bandstop", frequency, sample_rate, None, _sin / (2.0 * alpha),
+                                   vec![("BW", bandwidth_octaves), ("w0", w0), ("sin(w0)", _sin), ("cos(w0)", _cos),
+                                        ("alpha", alpha)],
+                                   & filter);
+    (filter, report)
+}
+
+/// Creates a band-pass filter of the bandwidth in octaves between the -3 dB
+/// frequencies, with a gain of 0 dB at the frequency, the complement of
+/// make_bandstop(), the two sum to the input. make_bandpass() has a gain of Q.
+///
+/// In Rust:
+///    ```text
+///    >>> // An octave band around 1 kHz.
+///    >>> let filter = make_bandpass_bw(1_000.0, 48_000, 1.0);
+///    ```
+///
+pub fn make_bandpass_bw(frequency: f64, sample_rate: u32, bandwidth_octaves: f64) -> IIRFilter {
+    make_bandpass_bw_with_report(frequency, sample_rate, bandwidth_octaves).0
+}
+
+/// make_bandpass_bw() and the intermediate values of its design, see design_report.rs .
+pub fn make_bandpass_bw_with_report(frequency: f64, sample_rate: u32, bandwidth_octaves: f64) -> (IIRFilter, DesignReport) {
+    let w0 = TAU * frequency / sample_rate as f64;
+    let _sin = exact_math::sin(w0);
+    let _cos = exact_math::cos(w0);
+    let alpha = _sin * exact_math::sinh((exact_math::ln(2.0) / 2.0) * bandwidth_octaves * (w0 / _sin));
+
+    let b0 = alpha;
+    let b1 = 0.0;
+    let b2 = -alpha;
+
+    let a0 =  1.0 + alpha;
+    let a1 = -2.0 * _cos;
+    let a2 =  1.0 - alpha;
+
+    let filter_order = 2;
+    let mut filter = IIRFilter::new(filter_order);
+    let _ = filter.set_coefficients(& [a0, a1, a2], & [b0, b1, b2]);
+
+    let report = DesignReport::new("bandpass_bw", frequency, sample_rate, None, _sin / (2.0 * alpha),
+                                   vec![("BW", bandwidth_octaves), ("w0", w0), ("sin(w0)", _sin), ("cos(w0)", _cos),
+                                        ("alpha", alpha)],
+                                   & filter);
+    (filter, report)
+}
+
+/// The highest order of make_butterworth_lowpass() and make_butterworth_highpass().
+pub const BUTTERWORTH_MAX_ORDER: usize = 16;
+
+/// The Q factors of the biquads of a Butterworth filter of the order, from the
+/// lowest to the highest, with the poles on the circle at the angles
+///
+///    Q_k = 1 / (2 sin((2k + 1) pi / (2 order))),   k = 0 ... order / 2 - 1
+///
+/// An odd order has also a first order section, without a Q.
+pub fn butterworth_q_factors(order: usize) -> Vec<f64> {
+    (0..order / 2).rev()
+        .map(|k| 1.0 / (2.0 * exact_math::sin((2 * k + 1) as f64 * PI / (2 * order) as f64)))
+        .collect()
+}
+
+/// Creates a Butterworth low-pass filter of the order, 2 to 16, as a cascade of
+/// biquads of make_lowpass(), with the Q factors of butterworth_q_factors(), and
+/// a first order section for an odd order. The response is -3 dB at the
+/// frequency, and the same as the transfer function of scipy.signal.butter() .
+///
+/// In Python:
+///    ```text
+///    >>> b, a = scipy.signal.butter(4, 1000, btype='lowpass', fs=48000)
+///    ```
+///
+/// In Rust:
+///    ```text
+///    >>> let mut filter = make_butterworth_lowpass(1_000.0, 48_000, 4)?;
+///    >>> let (b, a) = filter.to_transfer_function();
+///    ```
+///
+pub fn make_butterworth_lowpass(frequency: f64, sample_rate: u32, order: usize) -> Result<FilterChain, String> {
+    make_butterworth(frequency, sample_rate, order, false)
+}
+
+/// Creates a Butterworth high-pass filter of the order, 2 to 16, like
+/// make_butterworth_lowpass() with the biquads of make_highpass().
+pub fn make_butterworth_highpass(frequency: f64, sample_rate: u32, order: usize) -> Result<FilterChain, String> {
+    make_butterworth(frequency, sample_rate, order, true)
+}
+
+fn make_butterworth(frequency: f64, sample_rate: u32, order: usize, is_highpass: bool) -> Result<FilterChain, String> {
+    if !(2..=BUTTERWORTH_MAX_ORDER).contains(& order) {
+        return Err(format!("Error: the order of a Butterworth filter must be 2 to {}, got {}",
+                           BUTTERWORTH_MAX_ORDER, order));
+    }
+    let nyquist = sample_rate as f64 / 2.0;
+    if !(frequency > 0.0 && frequency < nyquist) {
+        return Err(format!("Error: the frequency {} Hz must be between 0 and {} Hz", frequency, nyquist));
+    }
+    Ok(FilterChain::new(butterworth_sections(frequency, sample_rate, order, is_highpass)))
+}
+
+// The sections of the Butterworth of the order, 1 or more, the first order section
+// of an odd order and the biquads from the lowest Q.
+fn butterworth_sections(frequency: f64, sample_rate: u32, order: usize, is_highpass: bool) -> Vec<IIRFilter> {
+    let mut filters = Vec::with_capacity(order / 2 + 1);
+    if order % 2 == 1 {
+        filters.push(make_first_order(frequency, sample_rate, is_highpass));
+    }
+    for q_factor in butterworth_q_factors(order) {
+        filters.push(match is_highpass {
+            true  => make_highpass(frequency, sample_rate, Some(q_factor)),
+            false => make_lowpass(frequency, sample_rate, Some(q_factor)),
+        });
+    }
+    filters
+}
+
+// The first order low-pass or high-pass of the bilinear transform, with the
+// frequency prewarped, K = tan(w0 / 2).
+fn make_first_order(frequency: f64, sample_rate: u32, is_highpass: bool) -> IIRFilter {
+    let k = exact_math::tan(PI * frequency / sample_rate as f64);
+    let b = if is_highpass { [1.0, -1.0] } else { [k, k] };
+    let mut filter = IIRFilter::new(1);
+    let _ = filter.set_coefficients(& [1.0 + k, k - 1.0], & b);
+    filter
+}
+
+/// The orders of the Linkwitz-Riley filters, LR2, LR4 and LR8.
+pub const LINKWITZ_RILEY_ORDERS: [usize; 3] = [2, 4, 8];
+
+/// Creates a Linkwitz-Riley low-pass filter of the order, 2, 4 or 8, two cascaded
+/// Butterworth low-pass of half the order. The response is -6 dB at the frequency,
+/// and with make_linkwitz_riley_highpass() of the same frequency and order the sum
+/// of the two outputs has a flat magnitude, it is the all-pass of
+/// make_linkwitz_riley_allpass(), for the crossover of a speaker or of a multiband
+/// effect, see crossover.rs .
+///
+/// In Rust:
+///    ```text
+///    >>> let mut woofer = make_linkwitz_riley_lowpass(2_000.0, 48_000, 4)?;
+///    >>> let mut tweeter = make_linkwitz_riley_highpass(2_000.0, 48_000, 4)?;
+///    ```
+///
+pub fn make_linkwitz_riley_lowpass(frequency: f64, sample_rate: u32, order: usize) -> Result<FilterChain, String> {
+    make_linkwitz_riley(frequency, sample_rate, order, false)
+}
+
+/// Creates a Linkwitz-Riley high-pass filter of the order, 2, 4 or 8, like
+/// make_linkwitz_riley_lowpass(). The high-pass of LR2 is 180 degrees from the
+/// low-pass, it is inverted, as it's wired in a LR2 crossover, so the sum is
+/// the all-pass for all the orders.
+pub fn make_linkwitz_riley_highpass(frequency: f64, sample_rate: u32, order: usize) -> Result<FilterChain, String> {
+    make_linkwitz_riley(frequency, sample_rate, order, true)
+}
+
+/// Creates the all-pass of the sum of the Linkwitz-Riley low-pass and high-pass of
+/// the frequency and order, the phase of a crossover point, for the bands of a
+/// crossover that don't go through that point.
+///
+///    LP + HP = B(-s) / B(s),   B the Butterworth polynomial of half the order
+///
+pub fn make_linkwitz_riley_allpass(frequency: f64, sample_rate: u32, order: usize) -> Result<FilterChain, String> {
+    check_linkwitz_riley(frequency, sample_rate, order)?;
+    let butterworth_order = order / 2;
+    let mut filters = Vec::with_capacity(butterworth_order / 2 + 1);
+    if butterworth_order % 2 == 1 {
+        // (1 - s) / (1 + s), with the bilinear transform of K = tan(w0 / 2).
+        let k = exact_math::tan(PI * frequency / sample_rate as f64);
+        let mut filter = IIRFilter::new(1);
+        let _ = filter.set_coefficients(& [k + 1.0, k - 1.0], & [k - 1.0, k + 1.0]);
+        filters.push(filter);
+    }
+    for q_factor in butterworth_q_factors(butterworth_order) {
+        filters.push(make_allpass(frequency, sample_rate, Some(q_factor)));
+    }
+    Ok(FilterChain::new(filters))
+}
+
+fn make_linkwitz_riley(frequency: f64, sample_rate: u32, order: usize, is_highpass: bool) -> Result<FilterChain, String> {
+    check_linkwitz_riley(frequency, sample_rate, order)?;
+    let mut filters = butterworth_sections(frequency, sample_rate, order / 2, is_highpass);
+    filters.extend(filters.clone());
+    if is_highpass && (order / 2) % 2 == 1 {
+        filters[0].b_coeffs.iter_mut().for_each(|b| *b = -*b);
+    }
+    Ok(FilterChain::new(filters))
+}
+
+fn check_linkwitz_riley(frequency: f64, sample_rate: u32, order: usize) -> Result<(), String> {
+    if !LINKWITZ_RILEY_ORDERS.contains(& order) {
+        return Err(format!("Error: the order of a Linkwitz-Riley filter must be one of {:?}, got {}",
+                           LINKWITZ_RILEY_ORDERS, order));
+    }
+    let nyquist = sample_rate as f64 / 2.0;
+    if !(frequency > 0.0 && frequency < nyquist) {
+        return Err(format!("Error: the frequency {} Hz must be between 0 and {} Hz", frequency, nyquist));
+    }
+    Ok(())
+}
+
+/// The highest order of make_bessel_lowpass() and make_bessel_highpass().
+pub const BESSEL_MAX_ORDER: usize = 10;
+
+// The imaginary part below this is a real pole, of the first order section.
+const REAL_POLE_TOLERANCE: f64 = 1e-9;
+const BESSEL_BISECTION_ITERATIONS: usize = 200;
+
+/// The poles of the analog Bessel low-pass of the order, the roots of the reverse
+/// Bessel polynomial
+///
+///    theta_n(s) = sum_k (2n - k)! / (2^(n - k) k! (n - k)!) s^k,   k = 0 ... n
+///
+/// scaled for -3 dB at 1 rad/s, like scipy.signal.besselap(order, norm='mag') .
+pub fn bessel_poles(order: usize) -> Vec<Complex<f64>> {
+    let factorial = |m: usize| (1..=m).fold(1.0, |product, i| product * i as f64);
+    // Monic, from the coefficient of s^n, that is 1, to the one of s^0.
+    let coefficients: Vec<f64> = (0..=order).rev()
+        .map(|k| factorial(2 * order - k) / (f64::powi(2.0, (order - k) as i32) * factorial(k) * factorial(order - k)))
+        .collect();
+    let squared_magnitude = |w: f64| coefficients.iter()
+        .fold(Complex::new(0.0, 0.0), |sum, c| sum * Complex::new(0.0, w) + c)
+        .norm_sqr();
+    // The magnitude of theta_n(jw) grows with w, the -3 dB frequency is where it
+    // is sqrt(2) times the one at DC.
+    let target = 2.0 * coefficients[order] * coefficients[order];
+    let (mut low, mut high) = (0.0, order as f64 + 1.0);
+    for _ in 0..BESSEL_BISECTION_ITERATIONS {
+        let middle = 0.5 * (low + high);
+        if squared_magnitude(middle) < target { low = middle; } else { high = middle; }
+    }
+    let cutoff = 0.5 * (low + high);
+    polynomial_roots(& coefficients).iter().map(|p| p / cutoff).collect()
+}
+
+/// Creates a Bessel low-pass filter of the order, 2 to 10, as a cascade of biquads
+/// of make_lowpass(), and a first order section for an odd order, with the poles of
+/// bessel_poles(). The group delay is maximally flat, the phase is almost linear in
+/// the passband and the step response has almost no overshoot, for crossovers and
+/// measurements, at the cost of a slower roll-off than a Butterworth of the same
+/// order. The response is -3 dB at the frequency, and the same as the transfer
+/// function of scipy.signal.bessel(norm='mag') .
+///
+/// In Python:
+///    ```text
+///    >>> b, a = scipy.signal.bessel(4, 1000, btype='lowpass', norm='mag', fs=48000)
+///    ```
+///
+/// In Rust:
+///    ```text
+///    >>> let mut filter = make_bessel_lowpass(1_000.0, 48_000, 4)?;
+///    >>> let (b, a) = filter.to_transfer_function();
+///    ```
+///
+pub fn make_bessel_lowpass(frequency: f64, sample_rate: u32, order: usize) -> Result<FilterChain, String> {
+    make_bessel(frequency, sample_rate, order, false)
+}
+
+/// Creates a Bessel high-pass filter of the order, 2 to 10, like
+/// make_bessel_lowpass() with the biquads of make_highpass().
+pub fn make_bessel_highpass(frequency: f64, sample_rate: u32, order: usize) -> Result<FilterChain, String> {
+    make_bessel(frequency, sample_rate, order, true)
+}
+
+fn make_bessel(frequency: f64, sample_rate: u32, order: usize, is_highpass: bool) -> Result<FilterChain, String> {
+    if !(2..=BESSEL_MAX_ORDER).contains(& order) {
+        return Err(format!("Error: the order of a Bessel filter must be 2 to {}, got {}", BESSEL_MAX_ORDER, order));
+    }
+    let nyquist = sample_rate as f64 / 2.0;
+    if !(frequency > 0.0 && frequency < nyquist) {
+        return Err(format!("Error: the frequency {} Hz must be between 0 and {} Hz", frequency, nyquist));
+    }
+    // A pole of magnitude r is a section at r times the frequency, before the
+    // bilinear transform prewarped at the frequency. The high-pass poles are 1 / r.
+    let k = exact_math::tan(PI * frequency / sample_rate as f64);
+    let section_frequency = |r: f64| {
+        let r = if is_highpass { 1.0 / r } else { r };
+        sample_rate as f64 / PI * exact_math::atan(r * k)
+    };
+    let poles = bessel_poles(order);
+    let mut filters = Vec::with_capacity(order / 2 + 1);
+    if let Some(pole) = poles.iter().find(|p| p.im.abs() < REAL_POLE_TOLERANCE) {
+        filters.push(make_first_order(section_frequency(pole.norm()), sample_rate, is_highpass));
+    }
+    // The pairs of poles, from the lowest Q to the highest.
+    let mut sections: Vec<(f64, f64)> = poles.iter()
+        .filter(|p| p.im >= REAL_POLE_TOLERANCE)
+        .map(|p| (p.norm(), p.norm() / (-2.0 * p.re)))
+        .collect();
+    sections.sort_by(|a, b| a.1.total_cmp(& b.1));
+    for (r, q_factor) in sections {
+        filters.push(match is_highpass {
+            true  => make_highpass(section_frequency(r), sample_rate, Some(q_factor)),
+            false => make_lowpass(section_frequency(r), sample_rate, Some(q_factor)),
+        });
+    }
+    Ok(FilterChain::new(filters))
 }
 
 
@@ -491,8 +874,9 @@ pub fn make_notch(frequency: f64, sample_rate: u32, q_factor: Option<f64>) -> II
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::iir_filter::ProcessingBlock; // Trait
 
-    fn print_values(target_vec: & Vec<f64>, res_coeffs: & Vec<&f64>) {
+    fn print_values(target_vec: & [f64], res_coeffs: & [&f64]) {
         println!("\n >>>> target_coefficents");
         for str_t in target_vec {
             print!("{}, ", str_t);    
@@ -501,7 +885,7 @@ mod tests {
         for str_t in res_coeffs {
             print!("{}, ", str_t);    
         }
-        println!("");
+        println!();
     }
 
     #[test]
@@ -573,6 +957,37 @@ mod tests {
         // assert_eq!(true, false);
     }
 
+    #[test]
+    fn test_make_bandstop_and_bandpass_bw() {
+        let frequency = 1_000.0;  // Hz
+        let sample_rate = 48_000; // Samples
+        let bandstop = make_bandstop(frequency, sample_rate, 1.0);
+        let bandpass = make_bandpass_bw(frequency, sample_rate, 1.0);
+
+        // The zero of the band-stop and the 0 dB of the band-pass at the frequency,
+        // and -3 dB at the edges of the octave.
+        assert!(bandstop.frequency_response(frequency, sample_rate).norm() < 1e-12);
+        assert!((bandpass.frequency_response(frequency, sample_rate).norm() - 1.0).abs() < 1e-12);
+        for edge in [frequency / f64::sqrt(2.0), frequency * f64::sqrt(2.0)] {
+            let gain_db = |filter: & IIRFilter| 20.0 * f64::log10(filter.frequency_response(edge, sample_rate).norm());
+            assert!((gain_db(& bandstop) + 3.01).abs() < 0.02);
+            assert!((gain_db(& bandpass) + 3.01).abs() < 0.02);
+        }
+        // The two sum to the input, and the band-stop is the notch of the same BW.
+        for (x, y) in bandpass.b_coeffs.iter().zip(& bandstop.b_coeffs).zip(& bandstop.a_coeffs)
+                .map(|((p, s), a)| (p + s, *a)) {
+            assert!((x - y).abs() < 1e-15);
+        }
+        assert_eq!(bandstop.b_coeffs, make_notch(frequency, sample_rate, Some(1.0)).b_coeffs);
+        assert_eq!(bandstop.a_coeffs, make_notch(frequency, sample_rate, Some(1.0)).a_coeffs);
+        assert_eq!(bandpass.a_coeffs, bandstop.a_coeffs);
+        assert_eq!(make_notch(frequency, sample_rate, None).a_coeffs,
+                   make_bandstop(frequency, sample_rate, DEFAULT_NOTCH_BANDWIDTH_OCTAVES).a_coeffs);
+        assert_eq!(make_notch_with_report(frequency, sample_rate, None).1.filter_type, "notch");
+        let (_, report) = make_bandstop_with_report(frequency, sample_rate, 1.0);
+        assert_eq!(report.get_value("BW"), Some(1.0));
+    }
+
     #[test]
     fn test_make_allpass() {
         // >>> filter = make_allpass(1000, 48000)
@@ -668,5 +1083,140 @@ mod tests {
         // assert_eq!(true, false);
     }
 
+    #[test]
+    fn test_make_butterworth() {
+        // >>> b, a = scipy.signal.butter(order, frequency, btype, fs=48000)
+        // The order, the frequency, is it a high-pass, b and a.
+        type Case = (usize, f64, bool, Vec<f64>, Vec<f64>);
+        let cases: [Case; 4] = [
+            (4, 12_000.0, false,
+             vec![0.09398085143379444, 0.37592340573517774, 0.5638851086027666, 0.37592340573517774,
+                  0.09398085143379444],
+             vec![1.0, 0.0, 0.4860288220682694, 0.0, 0.01766480087244189]),
+            (3, 1_000.0, false,
+             vec![0.00024700081539115486, 0.0007410024461734646, 0.0007410024461734646, 0.00024700081539115486],
+             vec![1.0, -2.738384907524865, 2.5098818584941567, -0.7695209444461623]),
+            (5, 1_000.0, true,
+             vec![0.8089749484507495, -4.044874742253747, 8.089749484507495, -8.089749484507495,
+                  4.044874742253747, -0.8089749484507495],
+             vec![1.0, -4.576487473337743, 8.394248760033474, -7.712655577891664, 3.549366071941171,
+                  -0.6544404672199354]),
+            (8, 2_400.0, false,
+             vec![1.7625537291912854e-07, 1.4100429833530283e-06, 4.935150441735599e-06, 9.870300883471198e-06,
+                  1.2337876104338998e-05, 9.870300883471198e-06, 4.935150441735599e-06, 1.4100429833530283e-06,
+                  1.7625537291912854e-07],
+             vec![1.0, -6.390364563108544, 18.00033833573991, -29.17109937488287, 29.731375438327483,
+                  -19.50563176812666, 8.040995932998943, -1.903668891132587, 0.1981000115597917]),
+        ];
+        for (order, frequency, is_highpass, target_b, target_a) in cases {
+            let filter = match is_highpass {
+                true  => make_butterworth_highpass(frequency, 48_000, order).unwrap(),
+                false => make_butterworth_lowpass(frequency, 48_000, order).unwrap(),
+            };
+            assert_eq!((filter.get_order(), filter.get_num_sections()), (order, order.div_ceil(2)));
+            let (b, a) = filter.to_transfer_function();
+            for (c, target) in b.iter().zip(& target_b).chain(a.iter().zip(& target_a)) {
+                assert!((c - target).abs() < 1e-12 * f64::max(1.0, target.abs()));
+            }
+            let gain_db = 20.0 * f64::log10(filter.frequency_response(frequency, 48_000).norm());
+            assert!((gain_db + 3.0103).abs() < 1e-3);
+        }
+
+        // The 16th order, flat in the passband and 96 dB/octave above the frequency.
+        let filter = make_butterworth_lowpass(1_000.0, 48_000, 16).unwrap();
+        let gain_db = |f: f64| 20.0 * f64::log10(filter.frequency_response(f, 48_000).norm());
+        assert!(gain_db(500.0).abs() < 1e-3);
+        assert!(gain_db(2_000.0) < -90.0);
+        let signal: Vec<f64> = (0..500).map(|n| f64::sin(n as f64 * 0.05)).collect();
+        let mut by_sample = filter.clone();
+        let mut by_block = signal.clone();
+        filter.clone().process_inplace(& mut by_block);
+        assert!(signal.iter().zip(& by_block).all(|(x, y)| by_sample.process(*x) == *y));
+        assert!(make_butterworth_lowpass(1_000.0, 48_000, 17).is_err());
+        assert!(make_butterworth_highpass(1_000.0, 48_000, 1).is_err());
+        assert!(make_butterworth_highpass(30_000.0, 48_000, 4).is_err());
+    }
+
+    #[test]
+    fn test_make_linkwitz_riley() {
+        for order in LINKWITZ_RILEY_ORDERS {
+            let lowpass = make_linkwitz_riley_lowpass(2_000.0, 48_000, order).unwrap();
+            let highpass = make_linkwitz_riley_highpass(2_000.0, 48_000, order).unwrap();
+            let allpass = make_linkwitz_riley_allpass(2_000.0, 48_000, order).unwrap();
+            assert_eq!((lowpass.get_order(), highpass.get_order(), allpass.get_order()), (order, order, order / 2));
+            let db = |response: Complex<f64>| 20.0 * f64::log10(response.norm());
+            assert!((db(lowpass.frequency_response(2_000.0, 48_000)) + 6.0206).abs() < 1e-6);
+            assert!((db(highpass.frequency_response(2_000.0, 48_000)) + 6.0206).abs() < 1e-6);
+            // The sum is the all-pass, at all the frequencies.
+            for frequency in [20.0, 500.0, 1_900.0, 2_000.0, 2_100.0, 8_000.0, 20_000.0] {
+                let sum = lowpass.frequency_response(frequency, 48_000) + highpass.frequency_response(frequency, 48_000);
+                assert!((sum - allpass.frequency_response(frequency, 48_000)).norm() < 1e-9);
+                assert!((sum.norm() - 1.0).abs() < 1e-9);
+            }
+            // 12 dB/octave for each order of 2.
+            let slope = db(lowpass.frequency_response(16_000.0, 48_000)) - db(lowpass.frequency_response(8_000.0, 48_000));
+            assert!(slope < -6.0 * order as f64 * 0.9);
+        }
+        assert!(make_linkwitz_riley_lowpass(1_000.0, 48_000, 6).is_err());
+        assert!(make_linkwitz_riley_highpass(30_000.0, 48_000, 4).is_err());
+        assert!(make_linkwitz_riley_allpass(1_000.0, 48_000, 3).is_err());
+    }
+
+    #[test]
+    fn test_make_bessel() {
+        // >>> b, a = scipy.signal.bessel(order, frequency, btype, norm='mag', fs=48000)
+        // The order, the frequency, is it a high-pass, b and a.
+        type Case = (usize, f64, bool, Vec<f64>, Vec<f64>);
+        let cases: [Case; 4] = [
+            (2, 1_000.0, false,
+             vec![0.006037214151853502, 0.012074428303707004, 0.006037214151853502],
+             vec![1.0, -1.7250069791473133, 0.7491558357547272]),
+            (3, 2_000.0, false,
+             vec![0.0041054473368698965, 0.01231634201060969, 0.01231634201060969, 0.0041054473368698965],
+             vec![1.0, -2.17228273537095, 1.6128261661045367, -0.4076998520386277]),
+            (4, 500.0, true,
+             vec![0.9334569327195136, -3.7338277308780543, 5.600741596317081, -3.7338277308780543,
+                  0.9334569327195136],
+             vec![1.0, -3.8629630958200076, 5.596911123554921, -3.604690734830445, 0.8707459693068432]),
+            (5, 12_000.0, false,
+             vec![0.12083513227808626, 0.6041756613904313, 1.2083513227808627, 1.2083513227808627,
+                  0.6041756613904313, 0.12083513227808626],
+             vec![1.0, 1.354874173032451, 1.0189758049006874, 0.39832002984974013, 0.08681030832123575,
+                  0.007743916794646138]),
+        ];
+        for (order, frequency, is_highpass, target_b, target_a) in cases {
+            let filter = match is_highpass {
+                true  => make_bessel_highpass(frequency, 48_000, order).unwrap(),
+                false => make_bessel_lowpass(frequency, 48_000, order).unwrap(),
+            };
+            assert_eq!((filter.get_order(), filter.get_num_sections()), (order, order.div_ceil(2)));
+            let (b, a) = filter.to_transfer_function();
+            for (c, target) in b.iter().zip(& target_b).chain(a.iter().zip(& target_a)) {
+                assert!((c - target).abs() < 1e-10 * f64::max(1.0, target.abs()));
+            }
+            let gain_db = 20.0 * f64::log10(filter.frequency_response(frequency, 48_000).norm());
+            assert!((gain_db + 3.0103).abs() < 1e-6);
+        }
+
+        // The group delay in the passband, in samples, almost constant for the
+        // Bessel, and not for the Butterworth of the same order.
+        let group_delay = |filter: & FilterChain, f: f64| {
+            let phase = |f: f64| filter.frequency_response(f, 48_000).arg();
+            -(phase(f + 1.0) - phase(f - 1.0)) / (TAU * 2.0 / 48_000.0)
+        };
+        for order in 2..=BESSEL_MAX_ORDER {
+            let filter = make_bessel_highpass(3_000.0, 48_000, order).unwrap();
+            assert!((20.0 * f64::log10(filter.frequency_response(3_000.0, 48_000).norm()) + 3.0103).abs() < 1e-6);
+        }
+        let bessel = make_bessel_lowpass(1_000.0, 48_000, 6).unwrap();
+        let butterworth = make_butterworth_lowpass(1_000.0, 48_000, 6).unwrap();
+        let variation = |filter: & FilterChain| group_delay(filter, 500.0) / group_delay(filter, 50.0) - 1.0;
+        assert!(variation(& bessel).abs() < 0.01);
+        assert!(variation(& butterworth) > 0.1);
+        assert!(make_bessel_lowpass(1_000.0, 48_000, 11).is_err());
+        assert!(make_bessel_highpass(1_000.0, 48_000, 1).is_err());
+        assert!(make_bessel_lowpass(0.0, 48_000, 4).is_err());
+    }
+
 }
 
@@ -0,0 +1,188 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Channel layout metadata for multichannel signals.
+//!              A layout gives a role to each channel of a signal (left, right,
+//!              center, LFE, ambisonic component, ...) so that multichannel
+//!              processing can decide what to do with each channel. For example,
+//!              a full range EQ curve should not be applied by default to the
+//!              LFE channel of a 5.1 signal.
+//!
+//!              The layouts can be mapped to and from the WAVE_FORMAT_EXTENSIBLE
+//!              dwChannelMask of WAV files.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Multiple channel audio data and WAVE files - Microsoft Docs
+//!       https://learn.microsoft.com/en-us/previous-versions/windows/hardware/design/dn653308(v=vs.85)
+//!
+//!    2. Ambisonic data exchange formats - AmbiX (ACN channel order, SN3D normalization)
+//!       https://en.wikipedia.org/wiki/Ambisonic_data_exchange_formats
+//!
+
+
+// WAVE_FORMAT_EXTENSIBLE speaker position bits.
+const SPEAKER_FRONT_LEFT:    u32 = 0x1;
+const SPEAKER_FRONT_RIGHT:   u32 = 0x2;
+const SPEAKER_FRONT_CENTER:  u32 = 0x4;
+const SPEAKER_LOW_FREQUENCY: u32 = 0x8;
+const SPEAKER_BACK_LEFT:     u32 = 0x10;
+const SPEAKER_BACK_RIGHT:    u32 = 0x20;
+const SPEAKER_SIDE_LEFT:     u32 = 0x200;
+const SPEAKER_SIDE_RIGHT:    u32 = 0x400;
+
+/// The role of one channel inside a layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelRole {
+    Mono,
+    Left,
+    Right,
+    Center,
+    /// Low Frequency Effects channel, the ".1" of 5.1 .
+    Lfe,
+    SurroundLeft,
+    SurroundRight,
+    /// Ambisonic component, by its ACN (Ambisonic Channel Number) index.
+    /// For first order: 0 = W, 1 = Y, 2 = Z, 3 = X .
+    Ambisonic(usize),
+    /// A channel without a known position.
+    Discrete(usize),
+}
+
+/// The channel layout of a multichannel signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelLayout {
+    Mono,
+    Stereo,
+    /// 5.1 in the WAV / SMPTE channel order: L, R, C, LFE, Ls, Rs .
+    Surround51,
+    /// First-order ambisonics in AmbiX format (ACN order W, Y, Z, X and SN3D).
+    AmbisonicsFirstOrder,
+    /// N channels without positional information.
+    Discrete(usize),
+}
+
+impl ChannelLayout {
+
+    /// Default layout for a number of channels, when there is no other information.
+    /// 4 channels are treated as discrete, because they could be quad or ambisonics.
+    pub fn from_channel_count(channels: usize) -> ChannelLayout {
+        match channels {
+            1 => ChannelLayout::Mono,
+            2 => ChannelLayout::Stereo,
+            6 => ChannelLayout::Surround51,
+            n => ChannelLayout::Discrete(n),
+        }
+    }
+
+    pub fn channel_count(& self) -> usize {
+        match self {
+            ChannelLayout::Mono                 => 1,
+            ChannelLayout::Stereo               => 2,
+            ChannelLayout::Surround51           => 6,
+            ChannelLayout::AmbisonicsFirstOrder => 4,
+            ChannelLayout::Discrete(n)          => *n,
+        }
+    }
+
+    /// The role of each channel, in the channel (interleaving) order.
+    pub fn roles(& self) -> Vec<ChannelRole> {
+        match self {
+            ChannelLayout::Mono   => vec![ChannelRole::Mono],
+            ChannelLayout::Stereo => vec![ChannelRole::Left, ChannelRole::Right],
+            ChannelLayout::Surround51 => vec![
+                ChannelRole::Left,
+                ChannelRole::Right,
+                ChannelRole::Center,
+                ChannelRole::Lfe,
+                ChannelRole::SurroundLeft,
+                ChannelRole::SurroundRight,
+            ],
+            ChannelLayout::AmbisonicsFirstOrder => (0..4).map(ChannelRole::Ambisonic).collect(),
+            ChannelLayout::Discrete(n) => (0..*n).map(ChannelRole::Discrete).collect(),
+        }
+    }
+
+    /// Index of the first channel with the given role, if the layout has it.
+    pub fn index_of(& self, role: ChannelRole) -> Option<usize> {
+        self.roles().iter().position(|r| *r == role)
+    }
+
+    /// Which channels should receive a full range processing curve (EQ, tone
+    /// controls, ...) by default.
+    /// The LFE channel is excluded, it only carries the low band and is
+    /// band-limited and calibrated separately.
+    /// All ambisonic components receive the same processing, applying
+    /// the same filter to W, X, Y and Z keeps the sound field directions intact.
+    pub fn default_processing_mask(& self) -> Vec<bool> {
+        self.roles().iter().map(|role| *role != ChannelRole::Lfe).collect()
+    }
+
+    /// The dwChannelMask of a WAVE_FORMAT_EXTENSIBLE header for this layout.
+    /// Layouts without speaker positions (ambisonics and discrete) use 0.
+    pub fn wav_channel_mask(& self) -> u32 {
+        match self {
+            ChannelLayout::Mono   => SPEAKER_FRONT_CENTER,
+            ChannelLayout::Stereo => SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT,
+            ChannelLayout::Surround51 => SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT
+                                       | SPEAKER_FRONT_CENTER | SPEAKER_LOW_FREQUENCY
+                                       | SPEAKER_BACK_LEFT | SPEAKER_BACK_RIGHT,
+            ChannelLayout::AmbisonicsFirstOrder | ChannelLayout::Discrete(_) => 0,
+        }
+    }
+
+    /// Layout from the dwChannelMask and number of channels of a WAV file.
+    /// Both the "back" and the "side" 5.1 masks are accepted as 5.1 .
+    pub fn from_wav_channel_mask(channel_mask: u32, channels: usize) -> ChannelLayout {
+        let surround_51_side = SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT
+                             | SPEAKER_FRONT_CENTER | SPEAKER_LOW_FREQUENCY
+                             | SPEAKER_SIDE_LEFT | SPEAKER_SIDE_RIGHT;
+        match (channel_mask, channels) {
+            (0, n) => ChannelLayout::from_channel_count(n),
+            (mask, 6) if mask == ChannelLayout::Surround51.wav_channel_mask()
+                         || mask == surround_51_side => ChannelLayout::Surround51,
+            (mask, 2) if mask == ChannelLayout::Stereo.wav_channel_mask() => ChannelLayout::Stereo,
+            (_, 1) => ChannelLayout::Mono,
+            (_, n) => ChannelLayout::Discrete(n),
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_layout_roles_and_counts() {
+        let layouts = [ChannelLayout::Mono, ChannelLayout::Stereo, ChannelLayout::Surround51,
+                       ChannelLayout::AmbisonicsFirstOrder, ChannelLayout::Discrete(3)];
+        for layout in layouts {
+            assert_eq!(layout.roles().len(), layout.channel_count());
+        }
+        assert_eq!(ChannelLayout::Surround51.index_of(ChannelRole::Lfe), Some(3));
+        assert_eq!(ChannelLayout::Stereo.index_of(ChannelRole::Lfe), None);
+    }
+
+    #[test]
+    fn test_channel_layout_default_processing_mask_skips_lfe() {
+        let mask = ChannelLayout::Surround51.default_processing_mask();
+        assert_eq!(mask, vec![true, true, true, false, true, true]);
+        let mask = ChannelLayout::AmbisonicsFirstOrder.default_processing_mask();
+        assert!(mask.iter().all(|process| *process));
+    }
+
+    #[test]
+    fn test_channel_layout_wav_channel_mask_round_trip() {
+        for layout in [ChannelLayout::Mono, ChannelLayout::Stereo, ChannelLayout::Surround51] {
+            let mask = layout.wav_channel_mask();
+            assert_eq!(ChannelLayout::from_wav_channel_mask(mask, layout.channel_count()), layout);
+        }
+        assert_eq!(ChannelLayout::from_wav_channel_mask(0x60F, 6), ChannelLayout::Surround51);
+        assert_eq!(ChannelLayout::from_wav_channel_mask(0, 4), ChannelLayout::Discrete(4));
+    }
+}
@@ -0,0 +1,225 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Chirp Z-transform, for a zoomed spectrum of a narrow band.
+//!              To see the low bands of an EQ, or the hum at 50 Hz or 60 Hz and its
+//!              harmonics, with a step of 0.1 Hz, a FFT needs a size of fs / 0.1,
+//!              480000 bins at 48 kHz, and only a few hundred of them are in the band
+//!              of interest. The chirp Z-transform computes only the M points of the
+//!              band, on the spiral z_k = A W^-k of the z plane:
+//!
+//!                 X[k] = sum_{n=0}^{N-1} x[n] A^-n W^(n k),   k = 0 ... M-1
+//!
+//!              With n k = (n^2 + k^2 - (k - n)^2) / 2 the sum is a convolution with
+//!              the chirp W^(-n^2 / 2), done with FFTs of size N + M - 1 rounded up to
+//!              a power of 2, Bluestein's algorithm. On the unit circle, with
+//!              A = e^(j 2 pi f1 / fs) and W = e^(-j 2 pi (f2 - f1) / ((M - 1) fs)),
+//!              the points are the DTFT at M frequencies from f1 to f2.
+//!              The zoom samples the spectrum more densely, but the resolution, the
+//!              width of the peak of a sine, is still about fs / N, 2 / duration
+//!              with the Hann window of zoom_spectrum(). Two close tones need a
+//!              long signal, not only a small step.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Rabiner, L. R., Schafer, R. W., Rader, C. M. - The chirp z-transform algorithm.
+//!       IEEE Transactions on Audio and Electroacoustics, 1969
+//!
+//!    2. scipy.signal.czt
+//!       https://docs.scipy.org/doc/scipy/reference/generated/scipy.signal.czt.html
+//!
+
+
+use std::f64::consts::TAU;
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+
+use crate::analysis::amplitude_to_db;
+
+
+/// The chirp Z-transform of the input at num_points points, X[k] = sum x[n] a^-n w^(n k),
+/// like scipy.signal.czt(x, m, w, a).
+///
+/// In Rust:
+///    ```text
+///    >>> // The DFT of size N.
+///    >>> let w = Complex::from_polar(1.0, -TAU / input.len() as f64);
+///    >>> let spectrum = chirp_z_transform(& input, input.len(), w, Complex::new(1.0, 0.0));
+///    ```
+///
+pub fn chirp_z_transform(input: & [Complex<f64>], num_points: usize, w: Complex<f64>, a: Complex<f64>)
+        -> Vec<Complex<f64>> {
+    assert!(w.norm() > 0.0 && a.norm() > 0.0);
+    if input.is_empty() || num_points == 0 {
+        return vec![Complex::new(0.0, 0.0); num_points];
+    }
+    let n = input.len();
+    let fft_size = (n + num_points - 1).next_power_of_two();
+    // w^(k^2 / 2), in polar form, the angle of k^2 is large.
+    let (w_radius, w_angle) = w.to_polar();
+    let chirp = |k: usize, sign: f64| {
+        let half_square = 0.5 * (k as f64) * (k as f64);
+        Complex::from_polar(w_radius.powf(sign * half_square), sign * w_angle * half_square)
+    };
+    let (a_radius, a_angle) = a.to_polar();
+
+    let mut planner = FftPlanner::<f64>::new();
+    let fft = planner.plan_fft_forward(fft_size);
+    let ifft = planner.plan_fft_inverse(fft_size);
+
+    let mut y = vec![Complex::new(0.0, 0.0); fft_size];
+    for (index, x) in input.iter().enumerate() {
+        y[index] = x * Complex::from_polar(a_radius.powf(-(index as f64)), -a_angle * index as f64) * chirp(index, 1.0);
+    }
+    // The chirp w^(-k^2 / 2) at the lags 0 ... M-1 and, wrapped around, -1 ... -(N-1).
+    let mut v = vec![Complex::new(0.0, 0.0); fft_size];
+    for (k, c) in v.iter_mut().enumerate().take(num_points) {
+        *c = chirp(k, -1.0);
+    }
+    for k in 1..n {
+        v[fft_size - k] = chirp(k, -1.0);
+    }
+    fft.process(& mut y);
+    fft.process(& mut v);
+    let mut product: Vec<Complex<f64>> = y.iter().zip(& v).map(|(y, v)| y * v).collect();
+    ifft.process(& mut product);
+    (0..num_points)
+        .map(|k| product[k] * chirp(k, 1.0) / fft_size as f64)
+        .collect()
+}
+
+/// The spectrum of a band, from the chirp Z-transform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoomSpectrum {
+    /// In Hz, equally spaced from the start to the end of the band.
+    pub frequencies: Vec<f64>,
+    /// Scaled so that a sine of amplitude 1 at a frequency of the grid is 1.
+    pub bins:        Vec<Complex<f64>>,
+}
+
+impl ZoomSpectrum {
+    pub fn get_amplitudes(& self) -> Vec<f64> {
+        self.bins.iter().map(|bin| bin.norm()).collect()
+    }
+
+    /// The amplitudes in dB, a full scale sine is at 0 dB.
+    pub fn get_amplitudes_db(& self) -> Vec<f64> {
+        self.bins.iter().map(|bin| amplitude_to_db(f64::max(bin.norm(), 1e-12))).collect()
+    }
+
+    /// The frequency and the amplitude of the largest point.
+    pub fn get_peak(& self) -> (f64, f64) {
+        let amplitudes = self.get_amplitudes();
+        let index = (0..amplitudes.len())
+            .fold(0, |best, i| if amplitudes[i] > amplitudes[best] { i } else { best });
+        (self.frequencies[index], amplitudes[index])
+    }
+
+    /// The local maxima above the amplitude, like the hum and its harmonics,
+    /// as (frequency, amplitude).
+    pub fn find_peaks(& self, min_amplitude: f64) -> Vec<(f64, f64)> {
+        let amplitudes = self.get_amplitudes();
+        (1..amplitudes.len().saturating_sub(1))
+            .filter(|i| amplitudes[*i] >= min_amplitude
+                        && amplitudes[*i] > amplitudes[i - 1] && amplitudes[*i] >= amplitudes[i + 1])
+            .map(|i| (self.frequencies[i], amplitudes[i]))
+            .collect()
+    }
+}
+
+/// The spectrum of the signal, with a Hann window, at num_points frequencies from
+/// start_frequency to end_frequency.
+///
+/// In Rust:
+///    ```text
+///    >>> // The hum, from 20 Hz to 200 Hz with a step of 0.1 Hz.
+///    >>> let spectrum = zoom_spectrum(& recording, 48_000, 20.0, 200.0, 1_801)?;
+///    >>> for (frequency, amplitude) in spectrum.find_peaks(1e-4) {
+///    >>>     println!("{:.1} Hz  {:.1} dBFS", frequency, amplitude_to_db(amplitude));
+///    >>> }
+///    ```
+///
+pub fn zoom_spectrum(signal: & [f64], sample_rate: u32, start_frequency: f64, end_frequency: f64, num_points: usize)
+        -> Result<ZoomSpectrum, String> {
+    let nyquist = sample_rate as f64 / 2.0;
+    if !(0.0 <= start_frequency && start_frequency < end_frequency && end_frequency <= nyquist) {
+        return Err(format!("Error: invalid band from {} Hz to {} Hz, it must be between 0 Hz and {} Hz",
+                           start_frequency, end_frequency, nyquist));
+    }
+    if num_points < 2 {
+        return Err(format!("Error: a zoomed spectrum needs at least 2 points, got {}", num_points));
+    }
+    if signal.len() < 2 {
+        return Err("Error: the signal is too short for a spectrum".to_string());
+    }
+    let _span = trace_span!("zoom_spectrum", samples = signal.len(), num_points);
+    let length = signal.len();
+    let window: Vec<f64> = (0..length)
+        .map(|n| 0.5 - 0.5 * f64::cos(TAU * n as f64 / length as f64))
+        .collect();
+    // A sine of amplitude 1 has a peak of sum(window) / 2.
+    let scale = 2.0 / window.iter().sum::<f64>();
+    let input: Vec<Complex<f64>> = signal.iter().zip(& window).map(|(x, w)| Complex::new(x * w, 0.0)).collect();
+
+    let step = (end_frequency - start_frequency) / (num_points - 1) as f64;
+    let w = Complex::from_polar(1.0, -TAU * step / sample_rate as f64);
+    let a = Complex::from_polar(1.0, TAU * start_frequency / sample_rate as f64);
+    let bins = chirp_z_transform(& input, num_points, w, a).iter().map(|bin| bin * scale).collect();
+    Ok(ZoomSpectrum {
+        frequencies: (0..num_points).map(|k| start_frequency + k as f64 * step).collect(),
+        bins,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_czt_is_the_dft() {
+        let input: Vec<Complex<f64>> = (0..100).map(|n| Complex::new(f64::sin(n as f64 * 0.3), f64::cos(n as f64 * 0.7))).collect();
+        let mut reference = input.clone();
+        FftPlanner::<f64>::new().plan_fft_forward(100).process(& mut reference);
+        let w = Complex::from_polar(1.0, -TAU / 100.0);
+        let spectrum = chirp_z_transform(& input, 100, w, Complex::new(1.0, 0.0));
+        for (x, r) in spectrum.iter().zip(& reference) {
+            assert!((x - r).norm() < 1e-9, "{} {}", x, r);
+        }
+        // Off the unit circle, against the sum of the definition.
+        let (w, a) = (Complex::new(0.99, -0.05), Complex::new(1.02, 0.1));
+        let spectrum = chirp_z_transform(& input[..20], 7, w, a);
+        for (k, x) in spectrum.iter().enumerate() {
+            let sum = input[..20].iter().enumerate()
+                .fold(Complex::new(0.0, 0.0), |sum, (n, c)| sum + c * a.powi(-(n as i32)) * w.powi((n * k) as i32));
+            assert!((x - sum).norm() < 1e-9 * sum.norm().max(1.0), "{} {}", x, sum);
+        }
+    }
+
+    #[test]
+    fn test_hum_analysis() {
+        // Hum at 50 Hz and its harmonics, 1 s at 48 kHz, and a tone between the points.
+        let signal: Vec<f64> = (0..48_000)
+            .map(|n| n as f64 / 48_000.0)
+            .map(|t| 0.01 * f64::sin(TAU * 50.0 * t) + 0.003 * f64::sin(TAU * 100.0 * t)
+                     + 0.001 * f64::sin(TAU * 150.0 * t) + 0.002 * f64::sin(TAU * 180.37 * t))
+            .collect();
+        let spectrum = zoom_spectrum(& signal, 48_000, 20.0, 200.0, 1_801).unwrap();
+        assert!((spectrum.frequencies[1] - 20.1).abs() < 1e-12);
+        let (frequency, amplitude) = spectrum.get_peak();
+        assert!((frequency - 50.0).abs() < 1e-9 && (amplitude - 0.01).abs() < 1e-5, "{} {}", frequency, amplitude);
+
+        let peaks = spectrum.find_peaks(5e-4);
+        assert_eq!(peaks.len(), 4, "{:?}", peaks);
+        for ((frequency, amplitude), (expected_frequency, expected_amplitude)) in
+                peaks.iter().zip([(50.0, 0.01), (100.0, 0.003), (150.0, 0.001), (180.4, 0.002)]) {
+            assert!((frequency - expected_frequency).abs() < 1e-9);
+            assert!((amplitude / expected_amplitude - 1.0).abs() < 0.01, "{} {}", frequency, amplitude);
+        }
+        assert!(zoom_spectrum(& signal, 48_000, 200.0, 20.0, 100).is_err());
+        assert!(zoom_spectrum(& signal, 48_000, 20.0, 30_000.0, 100).is_err());
+    }
+}
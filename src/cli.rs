@@ -0,0 +1,864 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Command line subcommands.
+//!              Without arguments the program runs the demo that generates the
+//!              plots, with arguments the first one is a subcommand:
+//!
+//!                 audio_filters_in_rust diff <reference.wav> <candidate.wav>
+//!                     [--max-delay-ms <ms>]       Delay search range, default 1000 ms.
+//!                     [--threshold-db <dBFS>]     Fails if the residual peak is above it.
+//!                     [--spectrogram <file.svg>]  Plot of the difference spectrogram.
+//!                     [--json]                    The report in JSON.
+//!
+//!                 audio_filters_in_rust analyze <file.wav> [--json]
+//!
+//!                 audio_filters_in_rust master <input.wav> <output.wav>
+//!                     [--lufs <LUFS>]             Loudness target, default -14 LUFS.
+//!                     [--true-peak <dBTP>]        True peak ceiling, default -1 dBTP.
+//!                     [--json]                    The report in JSON.
+//!
+//!                 audio_filters_in_rust replaygain <track.wav>... [--json]
+//!
+//!                 audio_filters_in_rust eq <input.wav> <output.wav> <profile.txt>
+//!                     [--from <s>]                Start of the time range, default 0 s.
+//!                     [--to <s>]                  End of the time range, default the end.
+//!
+//!                 audio_filters_in_rust match <reference.wav> <target.wav> <profile.txt>
+//!                     [--bands <n>]               Peak bands of the profile, default 10.
+//!                     [--fir <file.wav>]          Also writes the minimum phase FIR.
+//!
+//!                 audio_filters_in_rust tilt <input.wav> [<output.wav>]
+//!                     [--target <dB/octave>]      Tilt of the output.
+//!                     [--reference <file.wav>]    Or the tilt of a reference.
+//!                     [--json]                    The report in JSON.
+//!
+//!                 audio_filters_in_rust filter <input.wav> <output.wav> <type> <frequency or note>
+//!                     [--q <Q>]                   Q, default 0.7071.
+//!                     [--gain <dB>]               Gain of the peak and shelf types, default 0 dB.
+//!
+//!                 audio_filters_in_rust coeffs <type> <frequency or note>
+//!                     [--sample-rate <Hz>]        Sample rate, default 48000 Hz.
+//!                     [--q <Q>]                   Q, default 0.7071.
+//!                     [--gain <dB>]               Gain of the peak and shelf types, default 0 dB.
+//!                     [--format <text|json|verbose>]  Output format, default text.
+//!
+//!                 audio_filters_in_rust watch <input_dir> <output_dir> --preset <name>
+//!                     [--interval <s>]            Interval of the scans, default 2 s.
+//!                     [--lufs <LUFS>]             Loudness target of master, default -14 LUFS.
+//!                     [--true-peak <dBTP>]        True peak ceiling of master, default -1 dBTP.
+//!
+//!              diff is the null test: it time-aligns the two files, subtracts them
+//!              and reports the residual RMS and peak of each channel.
+//!              analyze reports the levels, loudness, DC offset, clipped samples and
+//!              inter-sample overs of a file, and the suggested corrections.
+//!              master runs the mastering chain, EQ, multiband compression, limiter
+//!              and dither, to the loudness and true peak targets, see mastering.rs .
+//!              replaygain scans the tracks of an album and writes the ReplayGain 2.0
+//!              tags of each one in the sidecar file <track.wav>.replaygain.txt, see
+//!              replaygain.rs .
+//!              eq applies an Equalizer APO or AutoEq profile, see eq_profile.rs, to
+//!              the file or to the time range of a preview, see preview.rs . The
+//!              Channel commands of the profile select a different EQ per channel.
+//!              match compares the average spectra of a reference and a target
+//!              recording and writes the Equalizer APO profile that makes the target
+//!              sound like the reference, and optionally the correction as a FIR
+//!              impulse response for a convolver, see eq_match.rs .
+//!              tilt measures the spectral tilt of a file, in dB/octave, and with an
+//!              output file corrects it with the tilt EQ to a target tilt, or to the
+//!              tilt of a reference, like an episode of the same podcast, see
+//!              spectral_tilt.rs .
+//!              filter applies a filter of the types of coeffs to each channel of a
+//!              file, to listen to it on real audio, see filter_wav_file() of
+//!              audio_io.rs . The metadata of the file is kept, and the loops of a
+//!              sampled instrument, the smpl chunk, are filtered to wrap without a
+//!              click, see loop_region.rs . The Butterworth, Linkwitz-Riley and
+//!              Bessel cascades take an --order, and the Linkwitz transform takes the
+//!              resonance of the box as the frequency and --q, and its --target and
+//!              --target-q, see design_policy.rs .
+//!              coeffs is the biquad calculator, it prints the coefficients, the
+//!              poles and zeros and a plot of the magnitude, or JSON, of a design, see
+//!              biquad_calculator.rs . The verbose format adds the intermediate values
+//!              of the design, w0, alpha, A and the pre-warped frequency, see
+//!              design_report.rs . The frequency is in Hz or a note name, like A4 or
+//!              C#2+14c, see note_names.rs .
+//!              With --json, diff, analyze, master, replaygain and tilt print their
+//!              report as one JSON object, for the scripts of a pipeline, with the
+//!              levels in dB, null for the level of digital silence, and the errors,
+//!              like a residual above the threshold, still on the exit code.
+//!              watch, or --watch, monitors a directory and processes each new WAV
+//!              file with a preset, an effect preset or master, to the output
+//!              directory, without overwriting, until the program is stopped, for
+//!              an ingest pipeline, see watch.rs .
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::audio_io::read_wav;
+use crate::audio_diff::null_test;
+use crate::audio_diff::NullTestReport;
+use crate::show_response::show_spectrogram;
+use crate::diagnostics::analyze_file;
+use crate::mastering::MasteringSettings;
+use crate::mastering::master_file;
+use crate::replaygain::tag_files;
+use crate::replaygain::sidecar_path;
+use crate::replaygain::ReplayGain;
+use crate::eq_profile::parse_equalizer_apo_channels;
+use crate::channel_layout::ChannelLayout;
+use crate::preview::process_range;
+use crate::preview::DEFAULT_PREROLL_SECONDS;
+use crate::audio_io::write_wav;
+use crate::biquad_calculator::BiquadCalculation;
+use crate::biquad_calculator::parse_filter_kind;
+use crate::biquad_calculator::DEFAULT_Q_FACTOR;
+use crate::biquad_calculator::DEFAULT_PLOT_WIDTH;
+use crate::biquad_calculator::DEFAULT_PLOT_HEIGHT;
+use crate::note_names::parse_frequency;
+use crate::eq_match::match_audio;
+use crate::eq_match::EqMatchSettings;
+use crate::eq_match::FirPhase;
+use crate::eq_match::DEFAULT_NUM_BANDS;
+use crate::eq_profile::save_equalizer_apo_config;
+use crate::audio_buffer::AudioBuffer;
+use crate::audio_io::SampleFormat;
+use crate::spectral_tilt::measure_audio_tilt;
+use crate::spectral_tilt::correct_tilt;
+use crate::design_policy::DesignPolicy;
+use crate::design_policy::FilterKind;
+use crate::wav_metadata::read_wav_with_metadata;
+use crate::wav_metadata::write_wav_with_metadata;
+use crate::loop_region::process_loops;
+use crate::watch::WatchFolder;
+use crate::watch::WatchPreset;
+use crate::watch::DEFAULT_POLL_INTERVAL_SECONDS;
+
+
+const USAGE: &str = "Usage:
+    audio_filters_in_rust                  Runs the demo, generates the plots.
+    audio_filters_in_rust diff <reference.wav> <candidate.wav>
+        [--max-delay-ms <ms>] [--threshold-db <dBFS>] [--spectrogram <file.svg>] [--json]
+    audio_filters_in_rust analyze <file.wav> [--json]
+    audio_filters_in_rust master <input.wav> <output.wav> [--lufs <LUFS>] [--true-peak <dBTP>] [--json]
+    audio_filters_in_rust replaygain <track.wav>... [--json]
+    audio_filters_in_rust eq <input.wav> <output.wav> <profile.txt> [--from <s>] [--to <s>]
+    audio_filters_in_rust match <reference.wav> <target.wav> <profile.txt> [--bands <n>] [--fir <file.wav>]
+    audio_filters_in_rust tilt <input.wav> [<output.wav>] [--target <dB/octave>] [--reference <file.wav>]
+        [--json]
+    audio_filters_in_rust filter <input.wav> <output.wav> <type> <frequency or note> [--q <Q>] [--gain <dB>]
+        [--order <n>] [--target <frequency or note>] [--target-q <Q>]
+    audio_filters_in_rust coeffs <type> <frequency or note> [--sample-rate <Hz>] [--q <Q>] [--gain <dB>]
+        [--order <n>] [--target <frequency or note>] [--target-q <Q>] [--format <text|json|verbose>]
+    audio_filters_in_rust watch <input_dir> <output_dir> --preset <name> [--interval <s>]
+        [--lufs <LUFS>] [--true-peak <dBTP>]";
+
+/// Runs the subcommand of the command line arguments, without the program name.
+pub fn run(args: & [String]) -> Result<(), String> {
+    match args.first().map(|s| s.as_str()) {
+        Some("diff") => run_diff(& args[1..]),
+        Some("analyze") => run_analyze(& args[1..]),
+        Some("master") => run_master(& args[1..]),
+        Some("replaygain") => run_replaygain(& args[1..]),
+        Some("eq") => run_eq(& args[1..]),
+        Some("match") => run_match(& args[1..]),
+        Some("tilt") => run_tilt(& args[1..]),
+        Some("filter") => run_filter(& args[1..]),
+        Some("coeffs") => run_coeffs(& args[1..]),
+        Some("watch") | Some("--watch") => run_watch(& args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(other) => Err(format!("Error: unknown subcommand \"{}\"\n{}", other, USAGE)),
+        None => Err(USAGE.to_string()),
+    }
+}
+
+fn run_diff(args: & [String]) -> Result<(), String> {
+    let (args, json) = take_flag(args, "--json");
+    let (positional, options) = parse_options(& args, & ["--max-delay-ms", "--threshold-db", "--spectrogram"])?;
+    if positional.len() != 2 {
+        return Err(format!("Error: diff needs a reference and a candidate file\n{}", USAGE));
+    }
+    let max_delay_ms = parse_number(& options, "--max-delay-ms", 1_000.0)?;
+    let (reference, _) = read_wav(& positional[0])?;
+    let (candidate, _) = read_wav(& positional[1])?;
+    let max_delay = (max_delay_ms * reference.sample_rate as f64 / 1_000.0).round() as usize;
+    let report = null_test(& reference, & candidate, max_delay)?;
+    let delay_ms = report.delay_samples as f64 * 1_000.0 / reference.sample_rate as f64;
+    if let Some(path) = options.get("--spectrogram") {
+        let (fft_size, hop_size) = (2_048, 1_024);
+        show_spectrogram(& report.difference_spectrogram(fft_size, hop_size),
+                         reference.sample_rate as usize, hop_size, path, "difference");
+    }
+    let threshold_db = match options.contains_key("--threshold-db") {
+        true  => Some(parse_number(& options, "--threshold-db", 0.0)?),
+        false => None,
+    };
+    let transparent = threshold_db.map(|threshold_db| report.is_transparent(threshold_db));
+
+    if json {
+        let channels = (0..report.residual_rms_db.len()).map(|channel| format!(
+            "{{\"residual_rms_db\": {}, \"residual_peak_db\": {}, \"reference_rms_db\": {}}}",
+            json_number(report.residual_rms_db[channel]), json_number(report.residual_peak_db[channel]),
+            json_number(report.reference_rms_db[channel])));
+        println!("{{\"reference\": {}, \"candidate\": {}, \"delay_samples\": {}, \"delay_ms\": {}, \
+                  \"compared_samples\": {}, \"channels\": {}, \"spectrogram\": {}, \"threshold_db\": {}, \
+                  \"transparent\": {}}}",
+                 json_string(& positional[0]), json_string(& positional[1]), report.delay_samples, json_number(delay_ms),
+                 report.difference.get_num_frames(), json_array(channels),
+                 options.get("--spectrogram").map_or("null".to_string(), |path| json_string(path)),
+                 threshold_db.map_or("null".to_string(), json_number),
+                 transparent.map_or("null".to_string(), |transparent| transparent.to_string()));
+    } else {
+        print_null_test(& positional, & options, & report, delay_ms, threshold_db, transparent);
+    }
+    if let (Some(threshold_db), Some(false)) = (threshold_db, transparent) {
+        return Err(format!("Error: residual peak {:.2} dBFS is above the threshold of {} dBFS",
+                           report.max_residual_peak_db(), threshold_db));
+    }
+    Ok(())
+}
+
+fn print_null_test(positional: & [String], options: & HashMap<String, String>, report: & NullTestReport, delay_ms: f64,
+                   threshold_db: Option<f64>, transparent: Option<bool>) {
+    println!("Null test of {} against {}", positional[1], positional[0]);
+    println!("  delay: {} samples ({:.3} ms)", report.delay_samples, delay_ms);
+    println!("  compared: {} samples", report.difference.get_num_frames());
+    for channel in 0..report.residual_rms_db.len() {
+        println!("  channel {}: residual RMS {:.2} dBFS, residual peak {:.2} dBFS, reference RMS {:.2} dBFS",
+                 channel, report.residual_rms_db[channel], report.residual_peak_db[channel],
+                 report.reference_rms_db[channel]);
+    }
+
+    if let Some(path) = options.get("--spectrogram") {
+        println!("  difference spectrogram: {}", path);
+    }
+    if let (Some(threshold_db), Some(true)) = (threshold_db, transparent) {
+        println!("  transparent, residual peak below {} dBFS", threshold_db);
+    }
+}
+
+fn run_analyze(args: & [String]) -> Result<(), String> {
+    let (args, json) = take_flag(args, "--json");
+    let (positional, _) = parse_options(& args, & [])?;
+    if positional.len() != 1 {
+        return Err(format!("Error: analyze needs one file\n{}", USAGE));
+    }
+    let report = analyze_file(& positional[0])?;
+    if json {
+        let channels = report.channels.iter().map(|channel| format!(
+            "{{\"peak_db\": {}, \"true_peak_db\": {}, \"rms_db\": {}, \"dc_offset\": {}, \"clipped_samples\": {}, \
+             \"inter_sample_overs\": {}}}",
+            json_number(channel.peak_db), json_number(channel.true_peak_db), json_number(channel.rms_db),
+            json_number(channel.dc_offset), channel.clipped_samples,
+            json_array(channel.inter_sample_overs.iter().map(|n| json_number(*n as f64 / report.sample_rate as f64)))));
+        let corrections = report.corrections.iter().map(|correction| json_string(& correction.describe()));
+        println!("{{\"file\": {}, \"sample_rate\": {}, \"num_frames\": {}, \"loudness_lufs\": {}, \"channels\": {}, \
+                  \"corrections\": {}}}",
+                 json_string(& positional[0]), report.sample_rate, report.num_frames, json_number(report.loudness_lufs),
+                 json_array(channels), json_array(corrections));
+        return Ok(());
+    }
+    // Only the first inter-sample overs are listed.
+    let max_listed = 10;
+
+    println!("Analysis of {}", positional[0]);
+    println!("  duration: {:.3} s, {} samples at {} Hz", report.num_frames as f64 / report.sample_rate as f64,
+             report.num_frames, report.sample_rate);
+    println!("  integrated loudness: {:.2} LUFS", report.loudness_lufs);
+    for (index, channel) in report.channels.iter().enumerate() {
+        println!("  channel {}: peak {:.2} dBFS, true peak {:.2} dBTP, RMS {:.2} dBFS, DC offset {:.6}",
+                 index, channel.peak_db, channel.true_peak_db, channel.rms_db, channel.dc_offset);
+        println!("    clipped samples: {}, inter-sample overs: {}", channel.clipped_samples,
+                 channel.inter_sample_overs.len());
+        if !channel.inter_sample_overs.is_empty() {
+            let listed: Vec<String> = channel.inter_sample_overs.iter().take(max_listed)
+                .map(|n| format!("{:.4} s", *n as f64 / report.sample_rate as f64))
+                .collect();
+            println!("    inter-sample overs at: {}{}", listed.join(", "),
+                     if channel.inter_sample_overs.len() > max_listed { ", ..." } else { "" });
+        }
+    }
+    if report.corrections.is_empty() {
+        println!("  no corrections needed");
+    }
+    for correction in & report.corrections {
+        println!("  suggested: {}", correction.describe());
+    }
+    Ok(())
+}
+
+fn run_master(args: & [String]) -> Result<(), String> {
+    let (args, json) = take_flag(args, "--json");
+    let (positional, options) = parse_options(& args, & ["--lufs", "--true-peak"])?;
+    if positional.len() != 2 {
+        return Err(format!("Error: master needs an input and an output file\n{}", USAGE));
+    }
+    let target_lufs = parse_number(& options, "--lufs", -14.0)?;
+    let true_peak_db = parse_number(& options, "--true-peak", -1.0)?;
+    let report = master_file(& positional[0], & positional[1], & MasteringSettings::new(target_lufs, true_peak_db))?;
+    if json {
+        println!("{{\"input\": {}, \"output\": {}, \"input_lufs\": {}, \"output_lufs\": {}, \"target_lufs\": {}, \
+                  \"output_true_peak_db\": {}, \"true_peak_db\": {}, \"gain_db\": {}, \"limiter_ceiling_db\": {}, \
+                  \"dc_blocker\": {}, \"passes\": {}}}",
+                 json_string(& positional[0]), json_string(& positional[1]), json_number(report.input_lufs),
+                 json_number(report.output_lufs), json_number(target_lufs), json_number(report.output_true_peak_db),
+                 json_number(true_peak_db), json_number(report.gain_db), json_number(report.limiter_ceiling_db),
+                 report.dc_blocker, report.passes);
+        return Ok(());
+    }
+
+    println!("Mastering of {} to {}", positional[0], positional[1]);
+    if report.dc_blocker {
+        println!("  DC offset removed");
+    }
+    println!("  loudness: {:.2} LUFS to {:.2} LUFS, target {} LUFS", report.input_lufs, report.output_lufs,
+             target_lufs);
+    println!("  true peak: {:.2} dBTP, ceiling {} dBTP", report.output_true_peak_db, true_peak_db);
+    println!("  gain: {:.2} dB, limiter ceiling {:.2} dB, {} passes", report.gain_db, report.limiter_ceiling_db,
+             report.passes);
+    Ok(())
+}
+
+fn run_replaygain(args: & [String]) -> Result<(), String> {
+    let (args, json) = take_flag(args, "--json");
+    let (positional, _) = parse_options(& args, & [])?;
+    if positional.is_empty() {
+        return Err(format!("Error: replaygain needs at least one track\n{}", USAGE));
+    }
+    let scan = tag_files(& positional)?;
+    if json {
+        let gain = |gain: & ReplayGain| format!("\"loudness_lufs\": {}, \"gain_db\": {}, \"peak\": {}",
+                                                json_number(gain.loudness_lufs), json_number(gain.gain_db),
+                                                json_number(gain.peak));
+        let tracks = positional.iter().zip(& scan.tracks).map(|(path, track)| format!(
+            "{{\"file\": {}, {}, \"sidecar\": {}}}", json_string(path), gain(track), json_string(& sidecar_path(path))));
+        println!("{{\"tracks\": {}, \"album\": {{{}}}}}", json_array(tracks), gain(& scan.album));
+        return Ok(());
+    }
+
+    println!("ReplayGain 2.0 of {} tracks", positional.len());
+    for (path, track) in positional.iter().zip(& scan.tracks) {
+        println!("  {}: {:.2} LUFS, gain {:.2} dB, peak {:.6}, tags in {}", path, track.loudness_lufs,
+                 track.gain_db, track.peak, sidecar_path(path));
+    }
+    println!("  album: {:.2} LUFS, gain {:.2} dB, peak {:.6}", scan.album.loudness_lufs, scan.album.gain_db,
+             scan.album.peak);
+    Ok(())
+}
+
+fn run_eq(args: & [String]) -> Result<(), String> {
+    let (positional, options) = parse_options(args, & ["--from", "--to"])?;
+    if positional.len() != 3 {
+        return Err(format!("Error: eq needs an input, an output and a profile file\n{}", USAGE));
+    }
+    let text = std::fs::read_to_string(& positional[2])
+        .map_err(|e| format!("Error: reading profile {}: {}", positional[2], e))?;
+    let from_seconds = parse_number(& options, "--from", 0.0)?;
+    let to_seconds = match options.contains_key("--to") {
+        true => Some(parse_number(& options, "--to", 0.0)?),
+        false => None,
+    };
+    let (audio, sample_format) = read_wav(& positional[0])?;
+    let layout = ChannelLayout::from_channel_count(audio.get_num_channels());
+    let profiles = parse_equalizer_apo_channels(& text, layout)?;
+    // Checks the profiles once, before the processing.
+    for profile in & profiles {
+        profile.to_parametric_eq(audio.sample_rate)?;
+    }
+    let output = process_range(& audio, from_seconds, to_seconds, DEFAULT_PREROLL_SECONDS,
+                               |channel| profiles[channel].to_parametric_eq(audio.sample_rate).unwrap())?;
+    write_wav(& positional[1], & output, sample_format)?;
+
+    let bands: Vec<String> = profiles.iter().map(|profile| profile.bands.len().to_string()).collect();
+    println!("EQ of {} with {} bands, to {}", positional[0], bands.join(" / "), positional[1]);
+    println!("  {:.3} s from {:.3} s", output.get_duration_seconds(), from_seconds);
+    Ok(())
+}
+
+fn run_match(args: & [String]) -> Result<(), String> {
+    let (positional, options) = parse_options(args, & ["--bands", "--fir"])?;
+    if positional.len() != 3 {
+        return Err(format!("Error: match needs a reference, a target and a profile file\n{}", USAGE));
+    }
+    let num_bands = parse_number(& options, "--bands", DEFAULT_NUM_BANDS as f64)?;
+    if !(num_bands >= 1.0 && num_bands.fract() == 0.0) {
+        return Err(format!("Error: invalid number of bands {}", num_bands));
+    }
+    // The length of the FIR, 85 ms at 48 kHz.
+    let fir_length = 4_095;
+    let (reference, _) = read_wav(& positional[0])?;
+    let (target, _) = read_wav(& positional[1])?;
+    let curve = match_audio(& reference, & target, & EqMatchSettings::new())?;
+    let eq = curve.fit_parametric(num_bands as usize)?;
+    save_equalizer_apo_config(& positional[2], & eq)?;
+
+    println!("EQ match of {} to {}", positional[1], positional[0]);
+    println!("  level offset: {:.2} dB, from {:.0} Hz to {:.0} Hz", curve.level_offset_db, curve.min_frequency,
+             curve.max_frequency);
+    println!("  profile: {} bands, preamp {:.2} dB, in {}", eq.get_num_bands(), eq.get_preamp_db(), positional[2]);
+    if let Some(path) = options.get("--fir") {
+        let fir = curve.make_fir(fir_length, FirPhase::Minimum)?;
+        write_wav(path, & AudioBuffer::new(curve.sample_rate, vec![fir]), SampleFormat::Float32)?;
+        println!("  FIR: {} taps, minimum phase, in {}", fir_length, path);
+    }
+    Ok(())
+}
+
+fn run_tilt(args: & [String]) -> Result<(), String> {
+    let (args, json) = take_flag(args, "--json");
+    let (positional, options) = parse_options(& args, & ["--target", "--reference"])?;
+    if positional.is_empty() || positional.len() > 2 {
+        return Err(format!("Error: tilt needs an input file and an optional output file\n{}", USAGE));
+    }
+    let (audio, sample_format) = read_wav(& positional[0])?;
+    let target_tilt = match (options.get("--target"), options.get("--reference")) {
+        (Some(_), Some(_)) => return Err("Error: tilt takes a --target or a --reference, not both".to_string()),
+        (Some(_), None) => Some(parse_number(& options, "--target", 0.0)?),
+        (None, Some(path)) => Some(measure_audio_tilt(& read_wav(path)?.0)?.tilt_db_per_octave),
+        (None, None) => None,
+    };
+
+    if !json {
+        println!("Spectral tilt of {}", positional[0]);
+    }
+    match (positional.get(1), target_tilt) {
+        (None, _) => {
+            let tilt = measure_audio_tilt(& audio)?;
+            if json {
+                println!("{{\"file\": {}, \"tilt_db_per_octave\": {}, \"level_db\": {}, \"pivot_frequency\": {}, \
+                          \"rms_error_db\": {}}}",
+                         json_string(& positional[0]), json_number(tilt.tilt_db_per_octave), json_number(tilt.level_db),
+                         json_number(tilt.pivot_frequency), json_number(tilt.rms_error_db));
+            } else {
+                println!("  tilt: {:.2} dB/octave, level {:.2} dB at {} Hz, RMS error {:.2} dB",
+                         tilt.tilt_db_per_octave, tilt.level_db, tilt.pivot_frequency, tilt.rms_error_db);
+            }
+        }
+        (Some(_), None) => return Err("Error: tilt needs a --target or a --reference to write an output".to_string()),
+        (Some(output_path), Some(target_tilt)) => {
+            let (output, measured) = correct_tilt(& audio, target_tilt)?;
+            write_wav(output_path, & output, sample_format)?;
+            if json {
+                println!("{{\"file\": {}, \"output\": {}, \"tilt_db_per_octave\": {}, \"target_db_per_octave\": {}}}",
+                         json_string(& positional[0]), json_string(output_path), json_number(measured.tilt_db_per_octave),
+                         json_number(target_tilt));
+            } else {
+                println!("  tilt: {:.2} dB/octave, target {:.2} dB/octave, to {}", measured.tilt_db_per_octave,
+                         target_tilt, output_path);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_filter(args: & [String]) -> Result<(), String> {
+    let (positional, options) = parse_options(args, & ["--q", "--gain", "--order", "--target", "--target-q"])?;
+    if positional.len() != 4 {
+        return Err(format!("Error: filter needs an input, an output, a filter type and a frequency\n{}", USAGE));
+    }
+    let kind = parse_kind_options(parse_filter_kind(& positional[2])?, & options)?;
+    let frequency = parse_frequency(& positional[3])?;
+    let q_factor = parse_number(& options, "--q", DEFAULT_Q_FACTOR)?;
+    let gain_db = parse_number(& options, "--gain", 0.0)?;
+    let (audio, sample_format, metadata) = read_wav_with_metadata(& positional[0])?;
+    let design = DesignPolicy::default().make(kind, frequency, audio.sample_rate, gain_db, q_factor)?;
+    for warning in & design.warnings {
+        println!("  {}", warning);
+    }
+    let loops = metadata.sampler.as_ref().map(|sampler| sampler.loops.clone()).unwrap_or_default();
+    let output = process_loops(& audio, & loops, |_| design.filter.clone())?;
+    write_wav_with_metadata(& positional[1], & output, sample_format.get_writable(), & metadata)?;
+
+    println!("{} {:.2} Hz, Q {:.4}, {:.2} dB of {}, to {}", positional[2], design.frequency, design.q_factor,
+             design.gain_db, positional[0], positional[1]);
+    if !loops.is_empty() {
+        println!("  {} loops of the smpl chunk, filtered to wrap", loops.len());
+    }
+    Ok(())
+}
+
+fn run_coeffs(args: & [String]) -> Result<(), String> {
+    let (positional, options) = parse_options(args, & ["--sample-rate", "--q", "--gain", "--order", "--target",
+                                                       "--target-q", "--format"])?;
+    if positional.len() != 2 {
+        return Err(format!("Error: coeffs needs a filter type and a frequency\n{}", USAGE));
+    }
+    let kind = parse_kind_options(parse_filter_kind(& positional[0])?, & options)?;
+    let frequency = parse_frequency(& positional[1])?;
+    let sample_rate = parse_number(& options, "--sample-rate", 48_000.0)?;
+    if sample_rate < 1.0 || sample_rate.fract() != 0.0 {
+        return Err(format!("Error: invalid sample rate {}", sample_rate));
+    }
+    let q_factor = parse_number(& options, "--q", DEFAULT_Q_FACTOR)?;
+    let gain_db = parse_number(& options, "--gain", 0.0)?;
+    let calculation = BiquadCalculation::new(kind, frequency, sample_rate as u32, gain_db, q_factor)?;
+    match options.get("--format").map(|s| s.as_str()) {
+        None | Some("text") => print!("{}", calculation.format_text(true)),
+        Some("json") => println!("{}", calculation.format_json()),
+        Some("verbose") => {
+            print!("{}", calculation.design_report()?.format_text());
+            print!("\nMagnitude (dB):\n{}", calculation.plot_magnitude(DEFAULT_PLOT_WIDTH, DEFAULT_PLOT_HEIGHT));
+        },
+        Some(other) => return Err(format!("Error: unknown format \"{}\", the formats are text, json and verbose", other)),
+    }
+    Ok(())
+}
+
+fn run_watch(args: & [String]) -> Result<(), String> {
+    let (positional, options) = parse_options(args, & ["--preset", "--interval", "--lufs", "--true-peak"])?;
+    if positional.len() != 2 {
+        return Err(format!("Error: watch needs an input and an output directory\n{}", USAGE));
+    }
+    let name = options.get("--preset").ok_or(format!("Error: watch needs a --preset\n{}", USAGE))?;
+    let settings = MasteringSettings::new(parse_number(& options, "--lufs", -14.0)?,
+                                          parse_number(& options, "--true-peak", -1.0)?);
+    let preset = WatchPreset::from_name(name, settings)?;
+    let interval = parse_number(& options, "--interval", DEFAULT_POLL_INTERVAL_SECONDS)?;
+    if !(interval > 0.0 && interval.is_finite()) {
+        return Err(format!("Error: invalid interval {} s", interval));
+    }
+    let mut watch = WatchFolder::new(Path::new(& positional[0]), Path::new(& positional[1]), preset)?;
+
+    println!("Watching {} with the preset {}, to {}, every {} s", positional[0], name, positional[1], interval);
+    watch.run(Duration::from_secs_f64(interval), |event| match & event.result {
+        Ok(output_path) => println!("  {} to {}", event.input_path.display(), output_path.display()),
+        Err(error)      => println!("  {} failed, {}", event.input_path.display(), error),
+    })
+}
+
+// Splits the arguments in positional arguments and "--name value" options.
+fn parse_options(args: & [String], names: & [& str]) -> Result<(Vec<String>, HashMap<String, String>), String> {
+    let mut positional = Vec::new();
+    let mut options = HashMap::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg.starts_with("--") {
+            if !names.contains(& arg.as_str()) {
+                return Err(format!("Error: unknown option \"{}\"\n{}", arg, USAGE));
+            }
+            let value = iter.next().ok_or(format!("Error: missing value of option {}", arg))?;
+            options.insert(arg.clone(), value.clone());
+        } else {
+            positional.push(arg.clone());
+        }
+    }
+    Ok((positional, options))
+}
+
+// The --order of a cascade, and the --target and --target-q of a Linkwitz transform.
+fn parse_kind_options(kind: FilterKind, options: & HashMap<String, String>) -> Result<FilterKind, String> {
+    let mut kind = kind;
+    if options.contains_key("--order") {
+        let order = parse_number(options, "--order", 0.0)?;
+        if !(order >= 1.0 && order.fract() == 0.0) {
+            return Err(format!("Error: invalid order {}", order));
+        }
+        kind = kind.with_order(order as usize)?;
+    }
+    let is_transform = matches!(kind, FilterKind::LinkwitzTransform { .. });
+    if is_transform {
+        let target_frequency = match options.get("--target") {
+            Some(target) => parse_frequency(target)?,
+            None => return Err("Error: linkwitz_transform needs a --target frequency".to_string()),
+        };
+        let target_q = parse_number(options, "--target-q", DEFAULT_Q_FACTOR)?;
+        kind = FilterKind::LinkwitzTransform { target_frequency, target_q };
+    } else if options.contains_key("--target") || options.contains_key("--target-q") {
+        return Err("Error: --target and --target-q are options of linkwitz_transform".to_string());
+    }
+    Ok(kind)
+}
+
+fn parse_number(options: & HashMap<String, String>, name: & str, default: f64) -> Result<f64, String> {
+    match options.get(name) {
+        None => Ok(default),
+        Some(value) => value.parse::<f64>().map_err(|_| format!("Error: invalid value \"{}\" of option {}", value, name)),
+    }
+}
+
+// Removes the option without a value from the arguments, and tells if it was there.
+fn take_flag(args: & [String], name: & str) -> (Vec<String>, bool) {
+    let remaining: Vec<String> = args.iter().filter(|arg| *arg != name).cloned().collect();
+    let found = remaining.len() != args.len();
+    (remaining, found)
+}
+
+// A JSON string, quoted, with the escapes of the quote, the backslash and the
+// control characters.
+fn json_string(value: & str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"'  => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(& format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+// A JSON number, null for the -inf dB of digital silence, JSON has no infinity.
+fn json_number(value: f64) -> String {
+    if value.is_finite() { value.to_string() } else { "null".to_string() }
+}
+
+fn json_array<I: IntoIterator<Item = String>>(items: I) -> String {
+    format!("[{}]", items.into_iter().collect::<Vec<String>>().join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use crate::audio_io::write_wav;
+    use crate::wav_metadata::WavMetadata;
+    use crate::wav_metadata::SamplerChunk;
+    use crate::wav_metadata::SampleLoop;
+    use crate::generators::make_white_noise;
+    use crate::fixtures::temp_test_path;
+
+    fn to_args(args: & [& str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let args = to_args(& ["a.wav", "--max-delay-ms", "10", "b.wav"]);
+        let (positional, options) = parse_options(& args, & ["--max-delay-ms"]).unwrap();
+        assert_eq!(positional, vec!["a.wav", "b.wav"]);
+        assert_eq!(parse_number(& options, "--max-delay-ms", 0.0).unwrap(), 10.0);
+        assert!(parse_options(& to_args(& ["--unknown", "1"]), & ["--max-delay-ms"]).is_err());
+        assert!(parse_options(& to_args(& ["--max-delay-ms"]), & ["--max-delay-ms"]).is_err());
+        assert!(run(& to_args(& ["nonexistent"])).is_err());
+    }
+
+    #[test]
+    fn test_json_output() {
+        assert_eq!(json_string("a \"b\"\\c\n\u{1}"), "\"a \\\"b\\\"\\\\c\\n\\u0001\"");
+        assert_eq!((json_number(-6.5), json_number(f64::NEG_INFINITY), json_number(f64::NAN)),
+                   ("-6.5".to_string(), "null".to_string(), "null".to_string()));
+        assert_eq!(json_array(["1".to_string(), "null".to_string()]), "[1, null]");
+        assert_eq!(json_array(Vec::new()), "[]");
+        let (args, json) = take_flag(& to_args(& ["a.wav", "--json", "--lufs", "-16"]), "--json");
+        assert_eq!((args, json), (to_args(& ["a.wav", "--lufs", "-16"]), true));
+        assert!(!take_flag(& to_args(& ["a.wav"]), "--json").1);
+    }
+
+    #[test]
+    fn test_diff_subcommand() {
+        let (reference_path, candidate_path) = (& temp_test_path("diff_reference.wav"),
+                                                & temp_test_path("diff_candidate.wav"));
+
+        let signal: Vec<f64> = (0..4_800).map(|n| 0.5 * f64::sin(n as f64 * 0.05) * f64::cos(n as f64 * 0.0123)).collect();
+        let mut delayed = vec![0.0; 10];
+        delayed.extend(& signal);
+        write_wav(reference_path, & AudioBuffer::new(48_000, vec![signal]), SampleFormat::Float32).unwrap();
+        write_wav(candidate_path, & AudioBuffer::new(48_000, vec![delayed]), SampleFormat::Float32).unwrap();
+
+        let args = to_args(& ["diff", reference_path, candidate_path, "--threshold-db", "-120"]);
+        assert!(run(& args).is_ok());
+        // With a too small delay search range the files don't null.
+        let args = to_args(& ["diff", reference_path, candidate_path, "--max-delay-ms", "0.1", "--threshold-db", "-120"]);
+        assert!(run(& args).is_err());
+        // The JSON report, and the error of the threshold.
+        assert!(run(& to_args(& ["diff", "--json", reference_path, candidate_path])).is_ok());
+        let args = to_args(& ["diff", reference_path, candidate_path, "--json", "--max-delay-ms", "0.1", "--threshold-db", "-120"]);
+        assert!(run(& args).is_err());
+
+        let _ = std::fs::remove_file(reference_path);
+        let _ = std::fs::remove_file(candidate_path);
+    }
+
+    #[test]
+    fn test_analyze_subcommand() {
+        let path = & temp_test_path("analyze.wav");
+        let signal: Vec<f64> = (0..4_800).map(|n| 0.1 + 0.5 * f64::sin(n as f64 * 0.05)).collect();
+        write_wav(path, & AudioBuffer::new(48_000, vec![signal]), SampleFormat::Int16).unwrap();
+
+        assert!(run(& to_args(& ["analyze", path])).is_ok());
+        assert!(run(& to_args(& ["analyze", "--json", path])).is_ok());
+        assert!(run(& to_args(& ["analyze"])).is_err());
+        assert!(run(& to_args(& ["analyze", "nonexistent.wav"])).is_err());
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_master_subcommand() {
+        let (input_path, output_path) = (& temp_test_path("cli_master_input.wav"), & temp_test_path("cli_master_output.wav"));
+        let signal: Vec<f64> = (0..48_000).map(|n| 0.05 * f64::sin(n as f64 * 0.05)).collect();
+        write_wav(input_path, & AudioBuffer::new(48_000, vec![signal]), SampleFormat::Float32).unwrap();
+
+        assert!(run(& to_args(& ["master", input_path, output_path, "--lufs", "-18", "--json"])).is_ok());
+        let (output, sample_format) = read_wav(output_path).unwrap();
+        assert_eq!(sample_format, SampleFormat::Float32);
+        assert_eq!(output.get_num_frames(), 48_000);
+        assert!(run(& to_args(& ["master", input_path])).is_err());
+        assert!(run(& to_args(& ["master", input_path, output_path, "--lufs", "loud"])).is_err());
+
+        let _ = std::fs::remove_file(input_path);
+        let _ = std::fs::remove_file(output_path);
+    }
+
+    #[test]
+    fn test_replaygain_subcommand() {
+        let paths: Vec<String> = ["a", "b"].iter()
+            .map(|name| temp_test_path(& format!("cli_replaygain_{}.wav", name)))
+            .collect();
+        for (path, amplitude) in paths.iter().zip([0.1, 0.4]) {
+            let signal: Vec<f64> = (0..48_000).map(|n| amplitude * f64::sin(n as f64 * 0.1)).collect();
+            write_wav(path, & AudioBuffer::new(48_000, vec![signal]), SampleFormat::Int16).unwrap();
+        }
+
+        assert!(run(& to_args(& ["replaygain", & paths[0], & paths[1]])).is_ok());
+        assert!(run(& to_args(& ["replaygain", & paths[0], & paths[1], "--json"])).is_ok());
+        let tags = std::fs::read_to_string(sidecar_path(& paths[0])).unwrap();
+        assert!(tags.starts_with("REPLAYGAIN_TRACK_GAIN="));
+        assert!(tags.contains("REPLAYGAIN_REFERENCE_LOUDNESS=-18.00 LUFS"));
+        assert!(run(& to_args(& ["replaygain"])).is_err());
+
+        for path in & paths {
+            let _ = std::fs::remove_file(sidecar_path(path));
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn test_eq_subcommand() {
+        let paths: Vec<String> = ["input.wav", "output.wav", "profile.txt"].iter()
+            .map(|name| temp_test_path(& format!("cli_eq_{}", name)))
+            .collect();
+        let signal: Vec<f64> = (0..48_000).map(|n| 0.5 * f64::sin(n as f64 * 0.05)).collect();
+        write_wav(& paths[0], & AudioBuffer::new(48_000, vec![signal]), SampleFormat::Int24).unwrap();
+        std::fs::write(& paths[2], "Preamp: -3 dB\nFilter 1: ON PK Fc 1000 Hz Gain 3.0 dB Q 1.0\n").unwrap();
+
+        assert!(run(& to_args(& ["eq", & paths[0], & paths[1], & paths[2], "--from", "0.25", "--to", "0.75"])).is_ok());
+        let (output, sample_format) = read_wav(& paths[1]).unwrap();
+        assert_eq!(sample_format, SampleFormat::Int24);
+        assert_eq!(output.get_num_frames(), 24_000);
+        assert!(run(& to_args(& ["eq", & paths[0], & paths[1], & paths[2]])).is_ok());
+        assert_eq!(read_wav(& paths[1]).unwrap().0.get_num_frames(), 48_000);
+        assert!(run(& to_args(& ["eq", & paths[0], & paths[1], & paths[2], "--from", "2"])).is_err());
+
+        for path in & paths {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn test_match_subcommand() {
+        let paths: Vec<String> = ["reference.wav", "target.wav", "profile.txt", "fir.wav"].iter()
+            .map(|name| temp_test_path(& format!("cli_match_{}", name)))
+            .collect();
+        let noise = make_white_noise(48_000, 0.125, 1);
+        let target: Vec<f64> = noise.iter().map(|x| 0.5 * x).collect();
+        write_wav(& paths[0], & AudioBuffer::new(48_000, vec![noise]), SampleFormat::Float32).unwrap();
+        write_wav(& paths[1], & AudioBuffer::new(48_000, vec![target]), SampleFormat::Float32).unwrap();
+
+        assert!(run(& to_args(& ["match", & paths[0], & paths[1], & paths[2], "--bands", "4", "--fir", & paths[3]])).is_ok());
+        assert!(std::fs::read_to_string(& paths[2]).unwrap().starts_with("Preamp:"));
+        let (fir, _) = read_wav(& paths[3]).unwrap();
+        assert_eq!((fir.sample_rate, fir.get_num_frames()), (48_000, 4_095));
+        assert!(run(& to_args(& ["match", & paths[0], & paths[1], & paths[2], "--bands", "0"])).is_err());
+        assert!(run(& to_args(& ["match", & paths[0], & paths[1]])).is_err());
+
+        for path in & paths {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn test_tilt_subcommand() {
+        let paths: Vec<String> = ["input.wav", "output.wav"].iter()
+            .map(|name| temp_test_path(& format!("cli_tilt_{}", name)))
+            .collect();
+        let noise = make_white_noise(48_000, 0.125, 3);
+        write_wav(& paths[0], & AudioBuffer::new(48_000, vec![noise]), SampleFormat::Float32).unwrap();
+
+        assert!(run(& to_args(& ["tilt", & paths[0]])).is_ok());
+        assert!(run(& to_args(& ["tilt", & paths[0], & paths[1], "--target", "-3"])).is_ok());
+        assert_eq!(read_wav(& paths[1]).unwrap().0.get_num_frames(), 48_000);
+        assert!(run(& to_args(& ["tilt", & paths[0], & paths[1], "--reference", & paths[1]])).is_ok());
+        assert!(run(& to_args(& ["tilt", & paths[0], & paths[1]])).is_err());
+        assert!(run(& to_args(& ["tilt", & paths[0], "--target", "-3", "--reference", & paths[1]])).is_err());
+
+        for path in & paths {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn test_filter_subcommand() {
+        let paths: Vec<String> = ["input.wav", "output.wav"].iter()
+            .map(|name| temp_test_path(& format!("cli_filter_{}", name)))
+            .collect();
+        let tone: Vec<f64> = (0..4_800).map(|n| 0.5 * f64::sin(n as f64 * 0.9)).collect();
+        write_wav(& paths[0], & AudioBuffer::new(48_000, vec![tone.clone(), tone]), SampleFormat::Int24).unwrap();
+
+        assert!(run(& to_args(& ["filter", & paths[0], & paths[1], "lowpass", "500"])).is_ok());
+        let (output, sample_format) = read_wav(& paths[1]).unwrap();
+        assert_eq!((output.get_num_channels(), sample_format), (2, SampleFormat::Int24));
+        assert!(output.channels[0][2_400..].iter().all(|y| y.abs() < 0.01));
+        assert!(run(& to_args(& ["filter", & paths[0], & paths[1], "peak", "A4", "--gain", "6", "--q", "2"])).is_ok());
+        assert!(run(& to_args(& ["filter", & paths[0], & paths[1], "lowpass", "30000"])).is_err());
+        assert!(run(& to_args(& ["filter", & paths[0], & paths[1], "lowpass"])).is_err());
+        // The metadata and the loops of a sampled instrument are kept.
+        let mut metadata = WavMetadata::new();
+        metadata.sampler = Some(SamplerChunk { manufacturer: 0, product: 0, sample_period: 20_833, midi_unity_note: 60,
+                                               midi_pitch_fraction: 0, smpte_format: 0, smpte_offset: 0,
+                                               loops: vec![SampleLoop { cue_point_id: 0, loop_type: 0, start: 1_000,
+                                                                        end: 1_999, fraction: 0, play_count: 0 }],
+                                               sampler_data: Vec::new() });
+        write_wav_with_metadata(& paths[0], & output, SampleFormat::Int24, & metadata).unwrap();
+        assert!(run(& to_args(& ["filter", & paths[0], & paths[1], "highpass", "100"])).is_ok());
+        assert_eq!(read_wav_with_metadata(& paths[1]).unwrap().2, metadata);
+
+        for path in & paths {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn test_coeffs_subcommand() {
+        assert!(run(& to_args(& ["coeffs", "lowshelf", "200", "--gain", "-3", "--q", "0.5"])).is_ok());
+        assert!(run(& to_args(& ["coeffs", "notch", "60", "--sample-rate", "8000", "--format", "json"])).is_ok());
+        assert!(run(& to_args(& ["coeffs", "peak_constant_q", "1000", "--gain", "-4", "--format", "verbose"])).is_ok());
+        assert!(run(& to_args(& ["coeffs", "peak", "1000", "--format", "xml"])).is_err());
+        assert!(run(& to_args(& ["coeffs", "comb", "1000"])).is_err());
+        assert!(run(& to_args(& ["coeffs", "lowpass"])).is_err());
+        assert!(run(& to_args(& ["coeffs", "lowpass", "30000"])).is_err());
+        assert!(run(& to_args(& ["coeffs", "notch", "E1-12c"])).is_ok());
+        assert!(run(& to_args(& ["coeffs", "notch", "H1"])).is_err());
+    }
+
+    #[test]
+    fn test_watch_subcommand() {
+        // Only the errors, the watch itself runs until the program is stopped.
+        let dir = PathBuf::from(temp_test_path("cli_watch"));
+        let (input_dir, output_dir) = (dir.to_str().unwrap(), dir.join("output"));
+        std::fs::create_dir_all(& dir).unwrap();
+        let output_dir = output_dir.to_str().unwrap();
+        assert!(run(& to_args(& ["watch", input_dir, output_dir])).is_err());
+        assert!(run(& to_args(& ["--watch", input_dir, output_dir, "--preset", "loud"])).is_err());
+        assert!(run(& to_args(& ["watch", input_dir, output_dir, "--preset", "lofi", "--interval", "0"])).is_err());
+        assert!(run(& to_args(& ["watch", input_dir, "--preset", "master"])).is_err());
+        assert!(run(& to_args(& ["watch", "audio_filters_test_missing", output_dir, "--preset", "master"])).is_err());
+        let _ = std::fs::remove_dir_all(& dir);
+    }
+}
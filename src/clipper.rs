@@ -0,0 +1,183 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Clipper with an oversampled true peak ceiling, for the last stage
+//!              of a batch job.
+//!              Clipping at the sample rate makes harmonics above the Nyquist
+//!              frequency, that alias down, and a signal clipped at its samples
+//!              still has inter-sample peaks above the ceiling. This clipper
+//!              oversamples the signal 4 times, with the interpolation filter of the
+//!              true peak measurement, clips the oversampled signal at the ceiling,
+//!              and filters the clipping error with a low-pass at the original
+//!              Nyquist frequency before going back to the original rate:
+//!
+//!                 y = x + decimate( lowpass( clip(oversample(x)) - oversample(x) ) )
+//!
+//!              Only the error is filtered, the parts of the signal under the ceiling
+//!              are unchanged. The low-pass makes an overshoot of its own, so the
+//!              clipping is repeated on the output, at a level lowered by the
+//!              overshoot of the last pass, and when the passes end above the
+//!              ceiling a last gain of a fraction of dB puts the true peak under it.
+//!              It's a cheaper alternative to the look-ahead limiter of dynamics.rs,
+//!              but it distorts the peaks instead of lowering their gain, it's for
+//!              the few dB of the transients.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. ITU-R BS.1770-4 - Annex 2, Guidelines for accurate measurement of
+//!       "true-peak" level
+//!       https://www.itu.int/rec/R-REC-BS.1770
+//!
+
+
+use std::f64::consts::PI;
+
+use crate::audio_buffer::AudioBuffer;
+use crate::loudness::oversample_for_true_peak;
+use crate::loudness::true_peak;
+use crate::loudness::TRUE_PEAK_OVERSAMPLING;
+
+
+/// Maximum number of clipping passes.
+pub const DEFAULT_MAX_PASSES: usize = 4;
+// Zero crossings of the decimation low-pass on each side of the center.
+const DECIMATION_HALF_ZEROS: isize = 16;
+
+pub struct TruePeakClipper {
+    ceiling:    f64,
+    max_passes: usize,
+    // Low-pass at the original Nyquist frequency, at the oversampled rate.
+    lowpass:    Vec<f64>,
+}
+
+impl TruePeakClipper {
+    /// In Rust:
+    ///    ```text
+    ///    >>> let clipper = TruePeakClipper::new(-1.0);
+    ///    >>> let output = clipper.process_audio(& mastered);
+    ///    ```
+    ///
+    pub fn new(ceiling_db: f64) -> Self {
+        assert!(ceiling_db <= 0.0);
+        let half_taps = DECIMATION_HALF_ZEROS * TRUE_PEAK_OVERSAMPLING as isize;
+        let taps: Vec<f64> = (-half_taps + 1..half_taps)
+            .map(|m| {
+                let t = m as f64 / TRUE_PEAK_OVERSAMPLING as f64;
+                let window = 0.5 + 0.5 * f64::cos(PI * m as f64 / half_taps as f64);
+                let sinc = if m == 0 { 1.0 } else { f64::sin(PI * t) / (PI * t) };
+                sinc * window
+            })
+            .collect();
+        let sum: f64 = taps.iter().sum();
+        TruePeakClipper {
+            ceiling: f64::powf(10.0, ceiling_db / 20.0),
+            max_passes: DEFAULT_MAX_PASSES,
+            lowpass: taps.iter().map(|tap| tap / sum).collect(),
+        }
+    }
+
+    pub fn get_ceiling_db(& self) -> f64 {
+        20.0 * f64::log10(self.ceiling)
+    }
+
+    pub fn get_max_passes(& self) -> usize {
+        self.max_passes
+    }
+
+    pub fn set_max_passes(& mut self, max_passes: usize) {
+        assert!(max_passes >= 1);
+        self.max_passes = max_passes;
+    }
+
+    /// Clips one channel, the true peak of the output is at most the ceiling.
+    pub fn process(& self, signal: & [f64]) -> Vec<f64> {
+        let mut output = signal.to_vec();
+        let mut clip_level = self.ceiling;
+        let mut peak = true_peak(& output);
+        for _ in 0..self.max_passes {
+            if peak <= self.ceiling {
+                return output;
+            }
+            self.clip_pass(& mut output, clip_level);
+            // The next pass clips lower by the overshoot of the low-pass.
+            peak = true_peak(& output);
+            clip_level *= f64::min(1.0, self.ceiling / peak);
+        }
+        // The overshoot left after the last pass, a small fraction of dB.
+        if peak > self.ceiling {
+            let gain = self.ceiling / peak;
+            output.iter_mut().for_each(|x| *x *= gain);
+        }
+        output
+    }
+
+    /// Clips each channel of the audio.
+    pub fn process_audio(& self, audio: & AudioBuffer) -> AudioBuffer {
+        let _span = trace_span!("true_peak_clipper", frames = audio.get_num_frames());
+        let channels = audio.channels.iter().map(|channel| self.process(channel)).collect();
+        audio.with_channels(audio.sample_rate, channels)
+    }
+
+    // Adds the filtered clipping error at the level to the signal.
+    fn clip_pass(& self, signal: & mut [f64], clip_level: f64) {
+        let oversampled = oversample_for_true_peak(signal);
+        let error: Vec<f64> = oversampled.iter()
+            .map(|x| x.clamp(-clip_level, clip_level) - x)
+            .collect();
+        let half = (self.lowpass.len() / 2) as isize;
+        for (n, x) in signal.iter_mut().enumerate() {
+            let center = (n * TRUE_PEAK_OVERSAMPLING) as isize;
+            let mut sum = 0.0;
+            for (i, tap) in self.lowpass.iter().enumerate() {
+                let index = center + half - i as isize;
+                if index >= 0 {
+                    sum += tap * error.get(index as usize).copied().unwrap_or(0.0);
+                }
+            }
+            *x += sum;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::TAU;
+
+    #[test]
+    fn test_true_peak_under_the_ceiling() {
+        // A loud sine with a phase that puts its peaks between the samples.
+        let signal: Vec<f64> = (0..4_800)
+            .map(|n| 1.6 * f64::sin(TAU * 11_025.0 * n as f64 / 48_000.0 + 0.3))
+            .collect();
+        let clipper = TruePeakClipper::new(-1.0);
+        let output = clipper.process(& signal);
+        assert!(20.0 * f64::log10(true_peak(& output)) <= -1.0 + 1e-9);
+        // The clipping harmonics are filtered, the output is near the sine clipped
+        // to about the ceiling.
+        let ceiling = clipper.ceiling;
+        let peak = output[100..4_700].iter().fold(0.0, |max: f64, x| max.max(x.abs()));
+        assert!(peak > 0.8 * ceiling && peak <= ceiling, "{}", peak);
+    }
+
+    #[test]
+    fn test_quiet_parts_unchanged() {
+        let mut signal: Vec<f64> = (0..9_600).map(|n| 0.3 * f64::sin(TAU * 440.0 * n as f64 / 48_000.0)).collect();
+        let unclipped = TruePeakClipper::new(-1.0).process(& signal);
+        assert_eq!(unclipped, signal);
+
+        // A click in the middle, the start and the end are untouched.
+        signal[4_800] = 1.5;
+        let audio = AudioBuffer::new(48_000, vec![signal.clone(), signal.clone()]);
+        let output = TruePeakClipper::new(-0.5).process_audio(& audio);
+        for channel in & output.channels {
+            assert!(true_peak(channel) <= f64::powf(10.0, -0.5 / 20.0) + 1e-12);
+            assert_eq!(channel[..4_000], signal[..4_000]);
+            assert!(channel[4_800] < 1.0);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Export of biquad coefficients for embedded DSP targets.
+//!              Design the filters here and deploy them in the firmware. A cascade
+//!              of 2nd order IIRFilter's (biquads) is exported as:
+//!
+//!                 -a C header with float tables {b0, b1, b2, a1, a2} per stage,
+//!                  normalized by a0, for the usual direct form I loop:
+//!                     y = b0*x + b1*x1 + b2*x2 - a1*y1 - a2*y2
+//!
+//!                 -a CMSIS-DSP arm_biquad_casd_df1_inst_f32 initializer. CMSIS
+//!                  uses {b0, b1, b2, -a1, -a2}, the feedback coefficients negated.
+//!
+//!                 -fixed-point hex words for DSPs with Q formats, like Q1.31
+//!                  (CMSIS q31, most 32 bit DSPs), Q1.23 (24 bit DSPs, Motorola 56k)
+//!                  and Q5.23 (Analog Devices SigmaDSP). The feedback coefficients
+//!                  are near 2, so Q1.x formats need a post shift: the coefficients
+//!                  are divided by 2^post_shift, and the DSP shifts the accumulator
+//!                  left by post_shift, like in arm_biquad_cascade_df1_init_q31().
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. CMSIS-DSP - Biquad Cascade IIR Filters Using Direct Form I Structure
+//!       https://arm-software.github.io/CMSIS-DSP/latest/group__BiquadCascadeDF1.html
+//!
+//!    2. Q (number format) - Wikipedia
+//!       https://en.wikipedia.org/wiki/Q_(number_format)
+//!
+
+
+use crate::iir_filter::IIRFilter;
+
+
+/// Signed fixed-point format, with the sign bit counted in the integer bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedPointFormat {
+    pub integer_bits:    u32,
+    pub fractional_bits: u32,
+}
+
+impl FixedPointFormat {
+    pub fn new(integer_bits: u32, fractional_bits: u32) -> Self {
+        assert!(integer_bits >= 1 && integer_bits + fractional_bits <= 64);
+        FixedPointFormat { integer_bits, fractional_bits }
+    }
+
+    /// Q1.31, CMSIS q31 and most 32 bit DSPs.
+    pub fn make_q31() -> Self {
+        FixedPointFormat::new(1, 31)
+    }
+
+    /// Q1.23, 24 bit DSPs.
+    pub fn make_q23() -> Self {
+        FixedPointFormat::new(1, 23)
+    }
+
+    /// Q5.23, the 28 bit format of the Analog Devices SigmaDSP.
+    pub fn make_sigma_dsp() -> Self {
+        FixedPointFormat::new(5, 23)
+    }
+
+    pub fn get_total_bits(& self) -> u32 {
+        self.integer_bits + self.fractional_bits
+    }
+
+    /// Converts a value to the format, rounded to the nearest step.
+    /// Values out of the range of the format give an error.
+    pub fn quantize(& self, value: f64) -> Result<i64, String> {
+        let scale = 2.0_f64.powi(self.fractional_bits as i32);
+        let max = 2.0_f64.powi(self.get_total_bits() as i32 - 1);
+        let quantized = (value * scale).round();
+        if quantized >= max || quantized < -max {
+            return Err(format!("Error: coefficient {} doesn't fit in the Q{}.{} format",
+                               value, self.integer_bits, self.fractional_bits));
+        }
+        Ok(quantized as i64)
+    }
+
+    /// Two's complement hex word of the value, "0x" and as many digits as needed
+    /// for the total bits.
+    pub fn hex_word(& self, value: f64) -> Result<String, String> {
+        let total_bits = self.get_total_bits();
+        let quantized = self.quantize(value)?;
+        let mask = if total_bits == 64 { u64::MAX } else { (1_u64 << total_bits) - 1 };
+        let digits = total_bits.div_ceil(4) as usize;
+        Ok(format!("0x{:0width$X}", (quantized as u64) & mask, width = digits))
+    }
+}
+
+/// The {b0, b1, b2, a1, a2} coefficients of a biquad, normalized by a0.
+pub fn normalized_biquad_coefficients(filter: & IIRFilter) -> Result<[f64; 5], String> {
+    if filter.order != 2 {
+        return Err(format!("Error: only biquads can be exported, got a filter of order {}", filter.order));
+    }
+    let a0 = filter.a_coeffs[0];
+    if a0 == 0.0 {
+        return Err("Error: the biquad has a0 equal to 0".to_string());
+    }
+    let b = & filter.b_coeffs;
+    let a = & filter.a_coeffs;
+    Ok([b[0] / a0, b[1] / a0, b[2] / a0, a[1] / a0, a[2] / a0])
+}
+
+fn all_coefficients(filters: & [& IIRFilter]) -> Result<Vec<[f64; 5]>, String> {
+    if filters.is_empty() {
+        return Err("Error: no filters to export".to_string());
+    }
+    filters.iter().map(|filter| normalized_biquad_coefficients(filter)).collect()
+}
+
+/// C header with the float coefficients of a cascade of biquads.
+///
+/// In Rust:
+///    ```text
+///    >>> let filter = make_lowpass(1_000.0, 48_000, None);
+///    >>> let header = export_c_header("lowpass_1k", & [& filter]).unwrap();
+///    >>> std::fs::write("lowpass_1k.h", header).unwrap();
+///    ```
+///
+pub fn export_c_header(name: & str, filters: & [& IIRFilter]) -> Result<String, String> {
+    let coefficients = all_coefficients(filters)?;
+    let name_upper = name.to_uppercase();
+    let mut text = String::new();
+    text += & format!("#ifndef {}_H\n#define {}_H\n\n", name_upper, name_upper);
+    text += & format!("#define {}_NUM_STAGES {}\n\n", name_upper, coefficients.len());
+    text += "/* Per stage: b0, b1, b2, a1, a2 (a0 = 1).\n";
+    text += "   y = b0*x + b1*x1 + b2*x2 - a1*y1 - a2*y2 */\n";
+    text += & format!("static const float {}_coeffs[{}_NUM_STAGES * 5] = {{\n", name, name_upper);
+    for c in & coefficients {
+        text += & format!("    {}f, {}f, {}f, {}f, {}f,\n",
+                          c_float(c[0]), c_float(c[1]), c_float(c[2]), c_float(c[3]), c_float(c[4]));
+    }
+    text += "};\n\n";
+    text += & format!("#endif /* {}_H */\n", name_upper);
+    Ok(text)
+}
+
+/// C source with the coefficients, the state and the CMSIS-DSP
+/// arm_biquad_casd_df1_inst_f32 instance of a cascade of biquads.
+pub fn export_cmsis_biquad_df1_f32(name: & str, filters: & [& IIRFilter]) -> Result<String, String> {
+    let coefficients = all_coefficients(filters)?;
+    let num_stages = coefficients.len();
+    let mut text = String::from("#include \"arm_math.h\"\n\n");
+    text += & format!("#define {}_NUM_STAGES {}\n\n", name.to_uppercase(), num_stages);
+    text += "/* Per stage: b0, b1, b2, -a1, -a2 (CMSIS sign convention). */\n";
+    text += & format!("static const float32_t {}_coeffs[{}] = {{\n", name, 5 * num_stages);
+    for c in & coefficients {
+        text += & format!("    {}f, {}f, {}f, {}f, {}f,\n",
+                          c_float(c[0]), c_float(c[1]), c_float(c[2]), c_float(-c[3]), c_float(-c[4]));
+    }
+    text += "};\n\n";
+    text += & format!("static float32_t {}_state[{}];\n\n", name, 4 * num_stages);
+    text += & format!("arm_biquad_casd_df1_inst_f32 {}_inst = {{ {}, {}_state, (float32_t *) {}_coeffs }};\n",
+                      name, num_stages, name, name);
+    Ok(text)
+}
+
+/// Fixed-point hex coefficients of a cascade of biquads, one stage per line in
+/// the CMSIS order {b0, b1, b2, -a1, -a2}, divided by 2^post_shift.
+pub fn export_fixed_point_hex(filters: & [& IIRFilter], format: FixedPointFormat, post_shift: u32) -> Result<String, String> {
+    let coefficients = all_coefficients(filters)?;
+    let shift_scale = 2.0_f64.powi(post_shift as i32);
+    let mut text = format!("// Q{}.{}, post shift {}, per stage: b0, b1, b2, -a1, -a2\n",
+                           format.integer_bits, format.fractional_bits, post_shift);
+    for c in & coefficients {
+        let words = [c[0], c[1], c[2], -c[3], -c[4]].iter()
+            .map(|value| format.hex_word(value / shift_scale))
+            .collect::<Result<Vec<String>, String>>()?;
+        text += & words.join(", ");
+        text += ",\n";
+    }
+    Ok(text)
+}
+
+// A C float literal with the 9 significant digits needed to round trip an f32,
+// like "-1.822694898e0".
+fn c_float(value: f64) -> String {
+    format!("{:.8e}", value as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::butterworth_filter::make_peak;
+
+    #[test]
+    fn test_fixed_point_format() {
+        let q31 = FixedPointFormat::make_q31();
+        assert_eq!(q31.hex_word(0.5).unwrap(), "0x40000000");
+        assert_eq!(q31.hex_word(-1.0).unwrap(), "0x80000000");
+        assert!(q31.hex_word(1.0).is_err());
+        let sigma_dsp = FixedPointFormat::make_sigma_dsp();
+        assert_eq!(sigma_dsp.hex_word(1.0).unwrap(), "0x0800000");
+        assert_eq!(sigma_dsp.hex_word(-2.0).unwrap(), "0xF000000");
+        assert_eq!(FixedPointFormat::make_q23().quantize(0.25).unwrap(), 1 << 21);
+    }
+
+    #[test]
+    fn test_export_c_and_cmsis() {
+        let lowpass = make_lowpass(1_000.0, 48_000, None);
+        let peak = make_peak(3_000.0, 48_000, 6.0, Some(2.0));
+        let header = export_c_header("my_eq", & [& lowpass, & peak]).unwrap();
+        assert!(header.contains("#define MY_EQ_NUM_STAGES 2"));
+        assert!(header.contains("static const float my_eq_coeffs[MY_EQ_NUM_STAGES * 5]"));
+
+        let cmsis = export_cmsis_biquad_df1_f32("my_eq", & [& lowpass, & peak]).unwrap();
+        assert!(cmsis.contains("arm_biquad_casd_df1_inst_f32 my_eq_inst = { 2, my_eq_state, (float32_t *) my_eq_coeffs };"));
+        assert!(cmsis.contains("static float32_t my_eq_state[8];"));
+        // The negated a1 of the low-pass is positive.
+        let c = normalized_biquad_coefficients(& lowpass).unwrap();
+        assert!(c[3] < 0.0);
+        assert!(cmsis.contains(& format!("{}f", c_float(-c[3]))));
+    }
+
+    #[test]
+    fn test_export_fixed_point_needs_post_shift() {
+        let lowpass = make_lowpass(1_000.0, 48_000, None);
+        let q31 = FixedPointFormat::make_q31();
+        // -a1 is close to 2, it doesn't fit in Q1.31 without a post shift.
+        assert!(export_fixed_point_hex(& [& lowpass], q31, 0).is_err());
+        let text = export_fixed_point_hex(& [& lowpass], q31, 1).unwrap();
+        let words: Vec<& str> = text.lines().nth(1).unwrap().trim_end_matches(',').split(", ").collect();
+        assert_eq!(words.len(), 5);
+        let c = normalized_biquad_coefficients(& lowpass).unwrap();
+        let a1_word = u32::from_str_radix(words[3].trim_start_matches("0x"), 16).unwrap() as i32;
+        assert!((a1_word as f64 / 2.0_f64.powi(31) * 2.0 + c[3]).abs() < 1e-8);
+    }
+}
@@ -0,0 +1,224 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Conversions between the sample types and the f64 of the filters.
+//!              The integer samples of N bits are the fraction x / 2^(N-1), so the
+//!              negative full scale is -1.0 exactly and the positive one is one step
+//!              below 1.0. From f64 the value is rounded and clamped to the range of
+//!              the type, a sample above 1.0 becomes the largest integer instead of
+//!              wrapping around to a negative value. The float samples are only
+//!              converted, they keep the values above full scale.
+//!
+//!                 i16     x / 32768
+//!                 I24     x / 8388608, 24 bits in the low bytes of an i32
+//!                 i32     x / 2147483648
+//!                 f32     x
+//!                 f64     x
+//!
+//!              Rounding to an integer type makes an error that follows the signal,
+//!              the dithered conversions add the TPDF dither of dither.rs before the
+//!              rounding. The WAV reader and writer of audio_io.rs use these
+//!              conversions.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::dither::TpdfDither;
+
+
+/// A sample type, converted to and from f64 on [-1, 1].
+pub trait Sample: Copy + Default {
+    /// Bits of an integer type, 0 for a float type.
+    const BITS: u32;
+
+    fn to_f64(self) -> f64;
+
+    /// Rounds and clamps to the range of an integer type.
+    fn from_f64(x: f64) -> Self;
+}
+
+/// A 24 bit sample, in the low 24 bits of an i32 with the sign extended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct I24(pub i32);
+
+impl I24 {
+    pub const MIN: i32 = -8_388_608;
+    pub const MAX: i32 = 8_388_607;
+
+    /// The 3 bytes of a WAV file.
+    pub fn from_le_bytes(bytes: [u8; 3]) -> Self {
+        // Sign extension of the 24 bit value by placing it in the high bytes of an i32.
+        I24(i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8)
+    }
+
+    pub fn to_le_bytes(self) -> [u8; 3] {
+        let bytes = self.0.to_le_bytes();
+        [bytes[0], bytes[1], bytes[2]]
+    }
+}
+
+// The integer value of the f64, rounded and clamped to [min, max].
+fn to_integer(x: f64, bits: u32, min: f64, max: f64) -> f64 {
+    (x * f64::powi(2.0, bits as i32 - 1)).round().clamp(min, max)
+}
+
+impl Sample for i16 {
+    const BITS: u32 = 16;
+
+    fn to_f64(self) -> f64 {
+        self as f64 / 32_768.0
+    }
+
+    fn from_f64(x: f64) -> Self {
+        to_integer(x, Self::BITS, i16::MIN as f64, i16::MAX as f64) as i16
+    }
+}
+
+impl Sample for I24 {
+    const BITS: u32 = 24;
+
+    fn to_f64(self) -> f64 {
+        self.0 as f64 / 8_388_608.0
+    }
+
+    fn from_f64(x: f64) -> Self {
+        I24(to_integer(x, Self::BITS, I24::MIN as f64, I24::MAX as f64) as i32)
+    }
+}
+
+impl Sample for i32 {
+    const BITS: u32 = 32;
+
+    fn to_f64(self) -> f64 {
+        self as f64 / 2_147_483_648.0
+    }
+
+    fn from_f64(x: f64) -> Self {
+        to_integer(x, Self::BITS, i32::MIN as f64, i32::MAX as f64) as i32
+    }
+}
+
+impl Sample for f32 {
+    const BITS: u32 = 0;
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+}
+
+impl Sample for f64 {
+    const BITS: u32 = 0;
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+}
+
+/// Converts the samples to f64, the buffers have the same length.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut samples = vec![0.0; block.len()];
+///    >>> to_f64_buffer(& block, & mut samples);      // block: & [i16]
+///    ```
+///
+pub fn to_f64_buffer<T: Sample>(input: & [T], output: & mut [f64]) {
+    assert_eq!(input.len(), output.len());
+    for (x, y) in input.iter().zip(output) {
+        *y = x.to_f64();
+    }
+}
+
+/// Converts the f64 samples to the type, rounded and clamped.
+pub fn from_f64_buffer<T: Sample>(input: & [f64], output: & mut [T]) {
+    assert_eq!(input.len(), output.len());
+    for (x, y) in input.iter().zip(output) {
+        *y = T::from_f64(*x);
+    }
+}
+
+/// Converts the f64 samples to an integer type with the dither, that must have the
+/// bits of the type. The dither keeps its state from one buffer to the next, one
+/// dither per channel.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut dither = TpdfDither::new(16, 1);
+///    >>> from_f64_buffer_dithered(& processed, & mut block, & mut dither);  // block: & mut [i16]
+///    ```
+///
+pub fn from_f64_buffer_dithered<T: Sample>(input: & [f64], output: & mut [T], dither: & mut TpdfDither) {
+    assert_eq!(input.len(), output.len());
+    assert_eq!(dither.get_bits(), T::BITS, "the dither must have the bits of the sample type");
+    for (x, y) in input.iter().zip(output) {
+        // The dither output is on the grid of the type, the conversion is exact.
+        *y = T::from_f64(dither.process(*x));
+    }
+}
+
+/// Converts between two sample types, through f64.
+pub fn convert_buffer<A: Sample, B: Sample>(input: & [A], output: & mut [B]) {
+    assert_eq!(input.len(), output.len());
+    for (x, y) in input.iter().zip(output) {
+        *y = B::from_f64(x.to_f64());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaling_and_clamping() {
+        assert_eq!(i16::MIN.to_f64(), -1.0);
+        assert_eq!(i16::from_f64(-1.0), i16::MIN);
+        assert_eq!(i16::from_f64(1.0), i16::MAX);
+        assert_eq!(i16::from_f64(7.5), i16::MAX);
+        assert_eq!(i16::from_f64(0.5), 16_384);
+        assert_eq!(I24::from_f64(-2.0), I24(I24::MIN));
+        assert_eq!(I24::from_le_bytes([0xFF, 0xFF, 0xFF]), I24(-1));
+        assert_eq!(I24::from_le_bytes(I24(-123_456).to_le_bytes()), I24(-123_456));
+        assert_eq!(i32::from_f64(1.0), i32::MAX);
+        assert_eq!(f32::from_f64(1.5), 1.5);
+
+        // i16 to i32 is exact, the 16 bits go to the high bits.
+        let input = [i16::MIN, -1, 0, 1, i16::MAX];
+        let mut wide = [0_i32; 5];
+        convert_buffer(& input, & mut wide);
+        assert_eq!(wide, [i32::MIN, -65_536, 0, 65_536, i16::MAX as i32 * 65_536]);
+        let mut back = [0_i16; 5];
+        convert_buffer(& wide, & mut back);
+        assert_eq!(back, input);
+
+        let mut samples = [0.0; 5];
+        to_f64_buffer(& input, & mut samples);
+        let mut floats = [0.0_f32; 5];
+        from_f64_buffer(& samples, & mut floats);
+        assert_eq!(floats[1], -1.0 / 32_768.0);
+    }
+
+    #[test]
+    fn test_dithered_requantization() {
+        // A constant of a quarter of a 16 bit step, its mean survives the dither.
+        let input = vec![0.25 / 32_768.0; 100_000];
+        let mut output = vec![0_i16; input.len()];
+        from_f64_buffer(& input, & mut output);
+        assert!(output.iter().all(|y| *y == 0));
+        from_f64_buffer_dithered(& input, & mut output, & mut TpdfDither::new(16, 3));
+        let mean = output.iter().map(|y| *y as f64).sum::<f64>() / output.len() as f64;
+        assert!((mean - 0.25).abs() < 0.01);
+    }
+}
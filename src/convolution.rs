@@ -0,0 +1,512 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: FIR convolution engines.
+//!                 -DirectConvolver: time domain convolution, sample by sample,
+//!                  without latency. Good for short impulse responses. The sums of
+//!                  products run on NEON on AArch64, see simd_kernels.rs .
+//!                 -PartitionedConvolver: uniformly partitioned overlap-save FFT
+//!                  convolution. The impulse response is split into partitions of
+//!                  the block size, so the cost per sample grows slowly with the
+//!                  impulse response length. It has a latency of one block when
+//!                  used sample by sample.
+//!                 -Convolver: chooses the engine for the impulse response length and
+//!                  the block size, from a cost model or from a calibration run that
+//!                  times each engine on this machine, or uses the one given.
+//!                  The engines are Direct, Partitioned (partitions of the block size,
+//!                  no latency) and Fft (a single partition as long as the impulse
+//!                  response, the cheapest for long responses but with a latency).
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Uniformly partitioned convolution - Frank Wefers
+//!       Partitioned convolution algorithms for real-time auralization, 2015
+//!
+//!    2. Overlap-save method - Wikipedia
+//!       https://en.wikipedia.org/wiki/Overlap%E2%80%93save_method
+//!
+
+
+use crate::iir_filter::ProcessingBlock;
+use crate::simd_kernels::KernelBackend;
+use crate::simd_kernels::dot_product;
+use crate::generators::make_white_noise;
+
+use std::sync::Arc;
+use std::time::Instant;
+use rustfft::{Fft, FftPlanner, num_complex::Complex};
+
+
+/// Time domain FIR convolution, y[n] = sum_k h[k] x[n-k], with the dot_product()
+/// of the kernel backend of the CPU, see simd_kernels.rs .
+pub struct DirectConvolver {
+    impulse_response: Vec<f64>,
+    // h[len-1] ... h[1] h[0], in the order of the inputs from the oldest.
+    reversed_impulse_response: Vec<f64>,
+    // The last impulse_response.len() inputs, twice, so the ones from x[n-len+1] to
+    // x[n] are contiguous at history[write_index + 1..].
+    history: Vec<f64>,
+    write_index: usize,
+    backend: KernelBackend,
+}
+
+impl DirectConvolver {
+    pub fn new(impulse_response: & [f64]) -> Self {
+        assert!(!impulse_response.is_empty());
+        DirectConvolver {
+            impulse_response: impulse_response.to_vec(),
+            reversed_impulse_response: impulse_response.iter().rev().copied().collect(),
+            history: vec![0.0; 2 * impulse_response.len()],
+            write_index: 0,
+            backend: KernelBackend::detect(),
+        }
+    }
+
+    pub fn get_impulse_response(& self) -> & [f64] {
+        & self.impulse_response
+    }
+
+    pub fn get_backend(& self) -> KernelBackend {
+        self.backend
+    }
+}
+
+impl ProcessingBlock for DirectConvolver {
+    fn process(& mut self, sample: f64) -> f64 {
+        let len = self.impulse_response.len();
+        self.history[self.write_index] = sample;
+        self.history[self.write_index + len] = sample;
+        let window = & self.history[self.write_index + 1..self.write_index + 1 + len];
+        let result = dot_product(& self.reversed_impulse_response, window, self.backend);
+        self.write_index = (self.write_index + 1) % len;
+
+        result
+    }
+}
+
+/// Uniformly partitioned overlap-save FFT convolver.
+#[derive(Clone)]
+pub struct PartitionedConvolver {
+    block_size: usize,
+    fft_forward: Arc<dyn Fft<f64>>,
+    fft_inverse: Arc<dyn Fft<f64>>,
+    // Spectra of the impulse response partitions, each of size 2 * block_size.
+    partitions: Vec<Vec<Complex<f64>>>,
+    // Frequency domain delay line, spectra of the last input blocks, newest first.
+    input_spectra: Vec<Vec<Complex<f64>>>,
+    // The last 2 * block_size input samples.
+    input_window: Vec<f64>,
+    // Sample by sample interface buffers.
+    input_block: Vec<f64>,
+    output_block: Vec<f64>,
+    block_index: usize,
+    // Scratch buffers.
+    fft_buffer: Vec<Complex<f64>>,
+    accumulator: Vec<Complex<f64>>,
+}
+
+impl PartitionedConvolver {
+    pub fn new(impulse_response: & [f64], block_size: usize) -> Self {
+        assert!(!impulse_response.is_empty());
+        assert!(block_size > 0);
+        let fft_size = 2 * block_size;
+        let mut planner = FftPlanner::<f64>::new();
+        let mut convolver = PartitionedConvolver {
+            block_size,
+            fft_forward: planner.plan_fft_forward(fft_size),
+            fft_inverse: planner.plan_fft_inverse(fft_size),
+            partitions: Vec::new(),
+            input_spectra: Vec::new(),
+            input_window: vec![0.0; fft_size],
+            input_block: vec![0.0; block_size],
+            output_block: vec![0.0; block_size],
+            block_index: 0,
+            fft_buffer: vec![Complex::new(0.0, 0.0); fft_size],
+            accumulator: vec![Complex::new(0.0, 0.0); fft_size],
+        };
+        convolver.set_impulse_response(impulse_response);
+
+        convolver
+    }
+
+    pub fn get_block_size(& self) -> usize {
+        self.block_size
+    }
+
+    /// Latency in samples of the sample by sample interface (ProcessingBlock).
+    /// process_block() has no latency.
+    pub fn latency_samples(& self) -> usize {
+        self.block_size
+    }
+
+    /// The samples that process() returns before the output of the next block,
+    /// the output of the previous block and of the previous impulse response.
+    pub fn samples_to_next_block(& self) -> usize {
+        self.block_size - self.block_index
+    }
+
+    /// Copies the input history and the pending output of the other convolver,
+    /// of the same block size, into the buffers of this one, like a clone that
+    /// keeps its own impulse response until set_impulse_response().
+    pub fn copy_input_history(& mut self, other: & PartitionedConvolver) {
+        assert_eq!(self.block_size, other.block_size);
+        let fft_size = 2 * self.block_size;
+        self.input_spectra.resize(other.input_spectra.len(), vec![Complex::new(0.0, 0.0); fft_size]);
+        for (spectrum, other_spectrum) in self.input_spectra.iter_mut().zip(& other.input_spectra) {
+            spectrum.copy_from_slice(other_spectrum);
+        }
+        self.input_window.copy_from_slice(& other.input_window);
+        self.input_block.copy_from_slice(& other.input_block);
+        self.output_block.copy_from_slice(& other.output_block);
+        self.block_index = other.block_index;
+    }
+
+    /// Changes the impulse response, keeping the input history, so the
+    /// convolution continues without a gap.
+    pub fn set_impulse_response(& mut self, impulse_response: & [f64]) {
+        assert!(!impulse_response.is_empty());
+        trace_event!(length = impulse_response.len(), "partitioned convolver impulse response updated");
+        let fft_size = 2 * self.block_size;
+        self.partitions.clear();
+        for chunk in impulse_response.chunks(self.block_size) {
+            let mut spectrum = vec![Complex::new(0.0, 0.0); fft_size];
+            for (s, h) in spectrum.iter_mut().zip(chunk) {
+                s.re = *h;
+            }
+            self.fft_forward.process(& mut spectrum);
+            self.partitions.push(spectrum);
+        }
+        self.input_spectra.resize(self.partitions.len(), vec![Complex::new(0.0, 0.0); fft_size]);
+    }
+
+    /// Convolves one block of exactly block_size samples, without latency.
+    pub fn process_block(& mut self, input: & [f64], output: & mut [f64]) {
+        let block_size = self.block_size;
+        assert_eq!(input.len(), block_size);
+        assert_eq!(output.len(), block_size);
+        let _span = trace_span!("partitioned_convolver_block", block_size, partitions = self.partitions.len());
+
+        // Slide the input window by one block.
+        self.input_window.copy_within(block_size.., 0);
+        self.input_window[block_size..].copy_from_slice(input);
+
+        // Spectrum of the input window, into the front of the delay line.
+        for (c, x) in self.fft_buffer.iter_mut().zip(& self.input_window) {
+            *c = Complex::new(*x, 0.0);
+        }
+        self.fft_forward.process(& mut self.fft_buffer);
+        self.input_spectra.rotate_right(1);
+        self.input_spectra[0].copy_from_slice(& self.fft_buffer);
+
+        // Multiply and accumulate all partitions.
+        self.accumulator.iter_mut().for_each(|c| *c = Complex::new(0.0, 0.0));
+        for (x_spectrum, h_spectrum) in self.input_spectra.iter().zip(& self.partitions) {
+            for ((acc, x), h) in self.accumulator.iter_mut().zip(x_spectrum).zip(h_spectrum) {
+                *acc += x * h;
+            }
+        }
+        self.fft_inverse.process(& mut self.accumulator);
+
+        // Overlap-save, the last block of the circular convolution is the valid part.
+        let scale = 1.0 / (2 * block_size) as f64;
+        for (y, c) in output.iter_mut().zip(& self.accumulator[block_size..]) {
+            *y = c.re * scale;
+        }
+    }
+}
+
+impl ProcessingBlock for PartitionedConvolver {
+    fn process(& mut self, sample: f64) -> f64 {
+        self.input_block[self.block_index] = sample;
+        let result = self.output_block[self.block_index];
+        self.block_index += 1;
+        if self.block_index == self.block_size {
+            let input = std::mem::take(& mut self.input_block);
+            let mut output = std::mem::take(& mut self.output_block);
+            self.process_block(& input, & mut output);
+            self.input_block = input;
+            self.output_block = output;
+            self.block_index = 0;
+        }
+
+        result
+    }
+}
+
+/// The convolution engine used by a Convolver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvolutionStrategy {
+    /// DirectConvolver, no latency.
+    Direct,
+    /// PartitionedConvolver with a single partition of the impulse response length,
+    /// rounded to a power of 2. It has a latency of that length.
+    Fft,
+    /// PartitionedConvolver with partitions of the block size, no latency in blocks.
+    Partitioned,
+}
+
+// Block size of the single partition of the Fft strategy.
+fn fft_strategy_block_size(impulse_response_len: usize, block_size: usize) -> usize {
+    impulse_response_len.next_power_of_two().max(block_size)
+}
+
+// Floating point operations per output sample of a uniformly partitioned convolver,
+// with an FFT of size M costing about 5 M log2(M) and a complex multiply-add 8.
+fn partitioned_cost(impulse_response_len: usize, partition_size: usize) -> f64 {
+    let fft_size = 2.0 * partition_size as f64;
+    let num_partitions = impulse_response_len.div_ceil(partition_size) as f64;
+    (2.0 * 5.0 * fft_size * fft_size.log2() + 8.0 * fft_size * num_partitions) / partition_size as f64
+}
+
+/// Estimated cost, in floating point operations per output sample, of a strategy.
+pub fn strategy_cost(strategy: ConvolutionStrategy, impulse_response_len: usize, block_size: usize) -> f64 {
+    match strategy {
+        ConvolutionStrategy::Direct      => 2.0 * impulse_response_len as f64,
+        ConvolutionStrategy::Partitioned => partitioned_cost(impulse_response_len, block_size),
+        ConvolutionStrategy::Fft         => partitioned_cost(impulse_response_len,
+                                                fft_strategy_block_size(impulse_response_len, block_size)),
+    }
+}
+
+fn candidate_strategies(allow_latency: bool) -> Vec<ConvolutionStrategy> {
+    let mut candidates = vec![ConvolutionStrategy::Direct, ConvolutionStrategy::Partitioned];
+    if allow_latency {
+        candidates.push(ConvolutionStrategy::Fft);
+    }
+    candidates
+}
+
+/// The cheapest strategy by the cost model. Fft is only a candidate when the
+/// latency of the impulse response length is allowed.
+pub fn estimate_strategy(impulse_response_len: usize, block_size: usize, allow_latency: bool) -> ConvolutionStrategy {
+    candidate_strategies(allow_latency).into_iter()
+        .min_by(|a, b| strategy_cost(*a, impulse_response_len, block_size)
+                       .total_cmp(& strategy_cost(*b, impulse_response_len, block_size)))
+        .unwrap()
+}
+
+/// The fastest strategy on this machine, by timing each candidate on noise.
+/// It takes from milliseconds to a fraction of a second, do it once at setup.
+pub fn calibrate_strategy(impulse_response_len: usize, block_size: usize, allow_latency: bool) -> ConvolutionStrategy {
+    assert!(impulse_response_len > 0 && block_size > 0);
+    let noise = make_white_noise(impulse_response_len + block_size, 0.5, 1);
+    let (impulse_response, input) = noise.split_at(impulse_response_len);
+    let mut output = vec![0.0; block_size];
+    // Enough blocks to fill the Fft strategy a few times.
+    let num_blocks = (4 * fft_strategy_block_size(impulse_response_len, block_size) / block_size).max(32);
+
+    let mut best = (ConvolutionStrategy::Direct, f64::MAX);
+    for strategy in candidate_strategies(allow_latency) {
+        let mut convolver = Convolver::with_strategy(impulse_response, block_size, strategy);
+        let start = Instant::now();
+        for _ in 0..num_blocks {
+            convolver.process_block(input, & mut output);
+        }
+        let seconds = start.elapsed().as_secs_f64();
+        if seconds < best.1 {
+            best = (strategy, seconds);
+        }
+    }
+    best.0
+}
+
+enum ConvolutionEngine {
+    Direct(DirectConvolver),
+    Partitioned(PartitionedConvolver),
+    Fft(PartitionedConvolver),
+}
+
+/// Convolver that hides the choice of the engine.
+///
+/// In Rust:
+///    ```text
+///    >>> // Chosen by the cost model, without latency in blocks.
+///    >>> let mut convolver = Convolver::new(& impulse_response, 256);
+///    >>> convolver.process_block(& input, & mut output);
+///    >>> // Or by timing the engines on this machine, allowing latency.
+///    >>> let mut convolver = Convolver::calibrated(& impulse_response, 256, true);
+///    ```
+///
+pub struct Convolver {
+    strategy:   ConvolutionStrategy,
+    block_size: usize,
+    engine:     ConvolutionEngine,
+}
+
+impl Convolver {
+    /// Chooses the strategy by the cost model, without latency in process_block().
+    pub fn new(impulse_response: & [f64], block_size: usize) -> Self {
+        let strategy = estimate_strategy(impulse_response.len(), block_size, false);
+        Convolver::with_strategy(impulse_response, block_size, strategy)
+    }
+
+    /// Chooses the strategy by timing the engines on this machine.
+    pub fn calibrated(impulse_response: & [f64], block_size: usize, allow_latency: bool) -> Self {
+        let strategy = calibrate_strategy(impulse_response.len(), block_size, allow_latency);
+        Convolver::with_strategy(impulse_response, block_size, strategy)
+    }
+
+    /// Manual override of the strategy.
+    pub fn with_strategy(impulse_response: & [f64], block_size: usize, strategy: ConvolutionStrategy) -> Self {
+        assert!(block_size > 0);
+        let engine = match strategy {
+            ConvolutionStrategy::Direct =>
+                ConvolutionEngine::Direct(DirectConvolver::new(impulse_response)),
+            ConvolutionStrategy::Partitioned =>
+                ConvolutionEngine::Partitioned(PartitionedConvolver::new(impulse_response, block_size)),
+            ConvolutionStrategy::Fft => {
+                let fft_block_size = fft_strategy_block_size(impulse_response.len(), block_size);
+                ConvolutionEngine::Fft(PartitionedConvolver::new(impulse_response, fft_block_size))
+            }
+        };
+        Convolver { strategy, block_size, engine }
+    }
+
+    pub fn get_strategy(& self) -> ConvolutionStrategy {
+        self.strategy
+    }
+
+    pub fn get_block_size(& self) -> usize {
+        self.block_size
+    }
+
+    /// Latency in samples of the sample by sample interface (ProcessingBlock).
+    pub fn latency_samples(& self) -> usize {
+        match & self.engine {
+            ConvolutionEngine::Direct(_) => 0,
+            ConvolutionEngine::Partitioned(convolver) | ConvolutionEngine::Fft(convolver) => convolver.latency_samples(),
+        }
+    }
+
+    /// Latency in samples of process_block().
+    pub fn block_latency_samples(& self) -> usize {
+        match & self.engine {
+            ConvolutionEngine::Fft(convolver) => convolver.latency_samples(),
+            _ => 0,
+        }
+    }
+
+    /// Convolves one block of exactly block_size samples.
+    pub fn process_block(& mut self, input: & [f64], output: & mut [f64]) {
+        assert_eq!(input.len(), self.block_size);
+        assert_eq!(output.len(), self.block_size);
+        match & mut self.engine {
+            ConvolutionEngine::Partitioned(convolver) => convolver.process_block(input, output),
+            ConvolutionEngine::Direct(convolver) => {
+                for (y, x) in output.iter_mut().zip(input) {
+                    *y = convolver.process(*x);
+                }
+            }
+            ConvolutionEngine::Fft(convolver) => {
+                for (y, x) in output.iter_mut().zip(input) {
+                    *y = convolver.process(*x);
+                }
+            }
+        }
+    }
+}
+
+impl ProcessingBlock for Convolver {
+    fn process(& mut self, sample: f64) -> f64 {
+        match & mut self.engine {
+            ConvolutionEngine::Direct(convolver) => convolver.process(sample),
+            ConvolutionEngine::Partitioned(convolver) | ConvolutionEngine::Fft(convolver) => convolver.process(sample),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deterministic pseudo random signal in [-1, 1].
+    fn test_signal(len: usize, seed: u64) -> Vec<f64> {
+        let mut state = seed;
+        (0..len).map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1_u64 << 53) as f64 * 2.0 - 1.0
+        }).collect()
+    }
+
+    fn reference_convolution(x: & [f64], h: & [f64]) -> Vec<f64> {
+        (0..x.len()).map(|n| {
+            (0..h.len()).filter(|k| *k <= n).map(|k| h[k] * x[n - k]).sum()
+        }).collect()
+    }
+
+    #[test]
+    fn test_direct_convolver() {
+        let x = test_signal(200, 1);
+        let h = test_signal(17, 2);
+        let expected = reference_convolution(& x, & h);
+        let mut convolver = DirectConvolver::new(& h);
+        for (input, target) in x.iter().zip(& expected) {
+            assert!((convolver.process(*input) - target).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_partitioned_convolver_process_block() {
+        let block_size = 16;
+        let x = test_signal(block_size * 20, 3);
+        let h = test_signal(100, 4);   // Not a multiple of the block size.
+        let expected = reference_convolution(& x, & h);
+        let mut convolver = PartitionedConvolver::new(& h, block_size);
+        let mut output = vec![0.0; x.len()];
+        for (input, out) in x.chunks(block_size).zip(output.chunks_mut(block_size)) {
+            convolver.process_block(input, out);
+        }
+        for (y, target) in output.iter().zip(& expected) {
+            assert!((y - target).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_partitioned_convolver_sample_by_sample_latency() {
+        let block_size = 8;
+        let x = test_signal(200, 5);
+        let h = test_signal(30, 6);
+        let expected = reference_convolution(& x, & h);
+        let mut convolver = PartitionedConvolver::new(& h, block_size);
+        let latency = convolver.latency_samples();
+        let output: Vec<f64> = x.iter().map(|input| convolver.process(*input)).collect();
+        for n in 0..(x.len() - latency) {
+            assert!((output[n + latency] - expected[n]).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_estimate_strategy() {
+        assert_eq!(estimate_strategy(16, 64, true), ConvolutionStrategy::Direct);
+        assert_eq!(estimate_strategy(48_000, 256, false), ConvolutionStrategy::Partitioned);
+        assert_eq!(estimate_strategy(4_096, 32, true), ConvolutionStrategy::Fft);
+        assert_eq!(estimate_strategy(4_096, 32, false), ConvolutionStrategy::Partitioned);
+        assert_ne!(calibrate_strategy(2_000, 64, false), ConvolutionStrategy::Fft);
+    }
+
+    #[test]
+    fn test_convolver_strategies_match() {
+        let block_size = 16;
+        let x = test_signal(block_size * 40, 7);
+        let h = test_signal(70, 8);
+        let expected = reference_convolution(& x, & h);
+        for strategy in [ConvolutionStrategy::Direct, ConvolutionStrategy::Partitioned, ConvolutionStrategy::Fft] {
+            let mut convolver = Convolver::with_strategy(& h, block_size, strategy);
+            assert_eq!(convolver.get_strategy(), strategy);
+            let mut output = vec![0.0; x.len()];
+            for (input, out) in x.chunks(block_size).zip(output.chunks_mut(block_size)) {
+                convolver.process_block(input, out);
+            }
+            let latency = convolver.block_latency_samples();
+            for n in 0..(x.len() - latency) {
+                assert!((output[n + latency] - expected[n]).abs() < 1e-10);
+            }
+        }
+        let convolver = Convolver::new(& h, block_size);
+        assert_eq!(convolver.block_latency_samples(), 0);
+    }
+}
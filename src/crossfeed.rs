@@ -0,0 +1,195 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Crossfeed filter for headphone listening.
+//!              With speakers, each ear also hears the opposite speaker, a little
+//!              later and with the high frequencies shadowed by the head. With
+//!              headphones that doesn't happen, and hard panned recordings sound
+//!              unnatural and tiring. A crossfeed mixes into each ear a low-passed,
+//!              attenuated and delayed copy of the opposite channel.
+//!
+//!              This is a Bauer stereophonic-to-binaural (bs2b) style design:
+//!                 -crossfeed path: low-pass at the cut frequency, attenuated by
+//!                  the feed level and optionally delayed.
+//!                 -direct path: high-shelf that boosts the high frequencies by the
+//!                  same amount that the crossfeed adds in the low frequencies, so
+//!                  that a centered (mono) signal keeps a flat response.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Bauer, B. B. - Stereophonic Earphones and Binaural Loudspeakers
+//!       Journal of the Audio Engineering Society, 1961
+//!
+//!    2. Bauer stereophonic-to-binaural DSP (bs2b)
+//!       http://bs2b.sourceforge.net/
+//!
+//!    3. A Cross-feed Filter for Headphones - Chu Moy
+//!       https://headwize.com/?page_id=654
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::iir_filter::IIRFilter;
+use crate::exact_math;
+use crate::butterworth_filter::make_lowpass;
+use crate::butterworth_filter::make_highshelf;
+use crate::delay_line::DelayLine;
+use crate::delay_line::milliseconds_to_samples;
+
+
+pub struct Crossfeed {
+    cut_frequency:  f64,
+    feed_level_db:  f64,
+    delay_ms:       f64,
+    output_gain:    f64,
+    direct_left:    IIRFilter,
+    direct_right:   IIRFilter,
+    cross_left:     IIRFilter,   // Left signal that goes to the right ear.
+    cross_right:    IIRFilter,   // Right signal that goes to the left ear.
+    delay_left:     DelayLine,
+    delay_right:    DelayLine,
+}
+
+impl Crossfeed {
+
+    /// Creates a crossfeed.
+    ///
+    /// cut_frequency: Frequency in Hz where the head shadowing starts, the crossfeed
+    ///                path is low-passed at this frequency.
+    /// feed_level_db: How much lower, in dB, the crossfeed signal is than the direct
+    ///                signal in the low frequencies. Typical values are 3 to 10 dB.
+    /// delay_ms:      Extra delay of the crossfeed path. The low-pass already adds
+    ///                around 0.4 ms of group delay, so this is usually 0.0 .
+    pub fn new(sample_rate: u32, cut_frequency: f64, feed_level_db: f64, delay_ms: f64) -> Self {
+        assert!(cut_frequency > 0.0 && cut_frequency < sample_rate as f64 / 2.0);
+        assert!(feed_level_db > 0.0);
+
+        let cross_gain = exact_math::powf(10.0, -feed_level_db / 20.0);
+        // A centered signal has a gain of (1 + cross_gain) in the low frequencies
+        // and of 1 in the high frequencies, the shelf on the direct path levels it.
+        let shelf_gain_db = 20.0 * exact_math::log10(1.0 + cross_gain);
+        // Critically damped low-pass, no resonance on the crossfeed path.
+        let q_factor_lowpass = Some(0.5);
+
+        let make_cross = || {
+            let mut filter = make_lowpass(cut_frequency, sample_rate, q_factor_lowpass);
+            let b_coeffs: Vec<f64> = filter.b_coeffs.iter().map(|b| b * cross_gain).collect();
+            let a_coeffs = filter.a_coeffs.clone();
+            filter.set_coefficients(& a_coeffs, & b_coeffs)
+                .expect("the scaled coefficients have the lengths of the low-pass biquad");
+            filter
+        };
+        // The shelf is centered one octave below the cut frequency, to compensate for
+        // the phase lag of the low-pass. The centered signal then stays within 1 dB of flat.
+        let make_direct = || make_highshelf(cut_frequency / 2.0, sample_rate, shelf_gain_db, None);
+        let delay_samples = milliseconds_to_samples(delay_ms, sample_rate);
+
+        Crossfeed {
+            cut_frequency,
+            feed_level_db,
+            delay_ms,
+            // Normalize so that the centered signal keeps unity gain and doesn't clip.
+            output_gain: 1.0 / (1.0 + cross_gain),
+            direct_left: make_direct(),
+            direct_right: make_direct(),
+            cross_left: make_cross(),
+            cross_right: make_cross(),
+            delay_left: DelayLine::new(delay_samples),
+            delay_right: DelayLine::new(delay_samples),
+        }
+    }
+
+    /// Default bs2b setting, 700 Hz and 4.5 dB.
+    pub fn make_bauer(sample_rate: u32) -> Self {
+        Crossfeed::new(sample_rate, 700.0, 4.5, 0.0)
+    }
+
+    /// Chu Moy like setting, 700 Hz and 6 dB.
+    pub fn make_chu_moy(sample_rate: u32) -> Self {
+        Crossfeed::new(sample_rate, 700.0, 6.0, 0.0)
+    }
+
+    /// Jan Meier like setting, 650 Hz and 9.5 dB.
+    pub fn make_jan_meier(sample_rate: u32) -> Self {
+        Crossfeed::new(sample_rate, 650.0, 9.5, 0.0)
+    }
+
+    pub fn get_cut_frequency(& self) -> f64 {
+        self.cut_frequency
+    }
+
+    pub fn get_feed_level_db(& self) -> f64 {
+        self.feed_level_db
+    }
+
+    pub fn get_delay_ms(& self) -> f64 {
+        self.delay_ms
+    }
+
+    /// Processes one stereo frame and returns the (left, right) output.
+    pub fn process_stereo(& mut self, left: f64, right: f64) -> (f64, f64) {
+        let to_right = self.delay_left.process(self.cross_left.process(left));
+        let to_left  = self.delay_right.process(self.cross_right.process(right));
+        let out_left  = self.direct_left.process(left) + to_left;
+        let out_right = self.direct_right.process(right) + to_right;
+
+        (out_left * self.output_gain, out_right * self.output_gain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::TAU;
+
+    // Amplitude of the left and right outputs, after the transient, for a sine
+    // input in the left and / or right channel.
+    fn sine_amplitudes(crossfeed: & mut Crossfeed, frequency: f64, sample_rate: u32,
+                       left_on: bool, right_on: bool) -> (f64, f64) {
+        let num_samples = sample_rate as usize / 2;
+        let mut max_left: f64 = 0.0;
+        let mut max_right: f64 = 0.0;
+        for n in 0..num_samples {
+            let x = f64::sin(TAU * frequency * n as f64 / sample_rate as f64);
+            let left  = if left_on  { x } else { 0.0 };
+            let right = if right_on { x } else { 0.0 };
+            let (out_left, out_right) = crossfeed.process_stereo(left, right);
+            if n > num_samples / 2 {
+                max_left  = max_left.max(out_left.abs());
+                max_right = max_right.max(out_right.abs());
+            }
+        }
+        (max_left, max_right)
+    }
+
+    #[test]
+    fn test_crossfeed_centered_signal_is_flat() {
+        let sample_rate = 48_000;
+        for frequency in [50.0, 700.0, 5_000.0] {
+            let mut crossfeed = Crossfeed::make_bauer(sample_rate);
+            let (left, right) = sine_amplitudes(& mut crossfeed, frequency, sample_rate, true, true);
+            let gain_db = 20.0 * f64::log10(left);
+            assert!(gain_db.abs() < 1.0);
+            assert!((left - right).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_crossfeed_feeds_low_frequencies_to_other_ear() {
+        let sample_rate = 48_000;
+        let mut crossfeed = Crossfeed::make_bauer(sample_rate);
+        let (left, right) = sine_amplitudes(& mut crossfeed, 50.0, sample_rate, true, false);
+        let feed_db = 20.0 * f64::log10(right / left);
+        assert!((feed_db + 4.5).abs() < 0.5);
+
+        // The high frequencies are shadowed by the head.
+        let mut crossfeed = Crossfeed::make_bauer(sample_rate);
+        let (left, right) = sine_amplitudes(& mut crossfeed, 10_000.0, sample_rate, true, false);
+        let feed_db = 20.0 * f64::log10(right / left);
+        assert!(feed_db < -30.0);
+    }
+}
@@ -0,0 +1,289 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Linkwitz-Riley crossover network.
+//!              Splits a signal in bands, for multiband processing or to feed the
+//!              drivers of a loudspeaker. Each crossover point is a Linkwitz-Riley
+//!              low-pass and high-pass, of the order 2, 4 or 8, see
+//!              make_linkwitz_riley_lowpass() in butterworth_filter.rs, by default
+//!              the 4th order (LR4), two cascaded Butterworth biquads of
+//!              Q = 1 / sqrt(2). The two outputs are -6 dB and in phase at the
+//!              crossover frequency, and their sum is an all-pass, for LR4:
+//!
+//!                 LP + HP = (s^2 - sqrt(2) s + 1) / (s^2 + sqrt(2) s + 1)
+//!
+//!              The high-pass of LR2 is inverted, so the sum is also an all-pass.
+//!              With more than one crossover point, the bands below a point go
+//!              through that all-pass, so all the bands have the same phase and the
+//!              sum of the bands has a flat magnitude response.
+//!
+//!              To time-align the drivers of a speaker each band output has a delay
+//!              and a polarity. The delay is given in samples, in milliseconds, or
+//!              in centimeters of the distance between the acoustic centers of the
+//!              drivers, at the speed of sound of 343 m/s, and it's rounded to the
+//!              nearest sample, 7 mm at 48 kHz. An inverted polarity is the fix for
+//!              a driver wired backwards, or for the 180 degrees between the bands of
+//!              a 2nd order crossover. With the defaults, no delay and the normal
+//!              polarity, the sum of the bands stays flat.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Linkwitz-Riley filter - Wikipedia
+//!       https://en.wikipedia.org/wiki/Linkwitz%E2%80%93Riley_filter
+//!
+
+
+use crate::iir_filter::FilterChain;
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::butterworth_filter::make_linkwitz_riley_lowpass;
+use crate::butterworth_filter::make_linkwitz_riley_highpass;
+use crate::butterworth_filter::make_linkwitz_riley_allpass;
+use crate::delay_line::DelayLine;
+use crate::delay_line::milliseconds_to_samples;
+
+
+/// Speed of sound in air at 20 degrees C, in m/s.
+pub const SPEED_OF_SOUND: f64 = 343.0;
+/// The order of the Linkwitz-Riley filters of CrossoverNetwork::new(), LR4.
+pub const DEFAULT_CROSSOVER_ORDER: usize = 4;
+
+// One crossover point, the Linkwitz-Riley low-pass and high-pass.
+struct CrossoverPoint {
+    lowpass:  FilterChain,
+    highpass: FilterChain,
+}
+
+pub struct CrossoverNetwork {
+    frequencies: Vec<f64>,
+    order:       usize,
+    points:      Vec<CrossoverPoint>,
+    // The all-passes of the crossover points above each band.
+    allpasses:   Vec<Vec<FilterChain>>,
+    sample_rate: u32,
+    // The alignment of each band output.
+    delays:      Vec<DelayLine>,
+    inverted:    Vec<bool>,
+}
+
+impl CrossoverNetwork {
+    /// A network with one band more than the increasing crossover frequencies.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let mut crossover = CrossoverNetwork::new(48_000, & [200.0, 3_000.0])?;
+    ///    >>> let mut bands = [0.0; 3];
+    ///    >>> crossover.split(sample, & mut bands);
+    ///    ```
+    ///
+    pub fn new(sample_rate: u32, frequencies: & [f64]) -> Result<Self, String> {
+        Self::with_order(sample_rate, frequencies, DEFAULT_CROSSOVER_ORDER)
+    }
+
+    /// A network of Linkwitz-Riley filters of the order, 2, 4 or 8, at each
+    /// crossover point.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> // A 3-way speaker with LR8 crossovers.
+    ///    >>> let mut crossover = CrossoverNetwork::with_order(48_000, & [300.0, 2_500.0], 8)?;
+    ///    ```
+    ///
+    pub fn with_order(sample_rate: u32, frequencies: & [f64], order: usize) -> Result<Self, String> {
+        if frequencies.is_empty() {
+            return Err("Error: a crossover needs at least one frequency".to_string());
+        }
+        if frequencies.windows(2).any(|w| w[1] <= w[0])
+                || frequencies.iter().any(|f| *f <= 0.0 || *f >= sample_rate as f64 / 2.0) {
+            return Err(format!("Error: the crossover frequencies {:?} must be increasing and below {} Hz",
+                               frequencies, sample_rate as f64 / 2.0));
+        }
+        let points = frequencies.iter().map(|f| Ok(CrossoverPoint {
+            lowpass: make_linkwitz_riley_lowpass(*f, sample_rate, order)?,
+            highpass: make_linkwitz_riley_highpass(*f, sample_rate, order)?,
+        })).collect::<Result<Vec<_>, String>>()?;
+        let allpasses = (0..frequencies.len())
+            .map(|band| frequencies[band + 1..].iter().map(|f| make_linkwitz_riley_allpass(*f, sample_rate, order)).collect())
+            .collect::<Result<Vec<_>, String>>()?;
+        let num_bands = frequencies.len() + 1;
+        Ok(CrossoverNetwork {
+            frequencies: frequencies.to_vec(),
+            order,
+            points,
+            allpasses,
+            sample_rate,
+            delays: (0..num_bands).map(|_| DelayLine::new(0)).collect(),
+            inverted: vec![false; num_bands],
+        })
+    }
+
+    pub fn get_num_bands(& self) -> usize {
+        self.points.len() + 1
+    }
+
+    pub fn get_frequencies(& self) -> & [f64] {
+        & self.frequencies
+    }
+
+    pub fn get_order(& self) -> usize {
+        self.order
+    }
+
+    pub fn get_band_polarity_inverted(& self, band: usize) -> bool {
+        self.inverted[band]
+    }
+
+    /// Inverts the polarity of the output of the band.
+    pub fn set_band_polarity_inverted(& mut self, band: usize, inverted: bool) {
+        self.inverted[band] = inverted;
+    }
+
+    pub fn get_band_delay_samples(& self, band: usize) -> usize {
+        self.delays[band].get_delay_samples()
+    }
+
+    pub fn get_band_delay_ms(& self, band: usize) -> f64 {
+        self.get_band_delay_samples(band) as f64 * 1_000.0 / self.sample_rate as f64
+    }
+
+    /// Delays the output of the band. The delayed samples are cleared.
+    pub fn set_band_delay_samples(& mut self, band: usize, delay_samples: usize) {
+        self.delays[band].set_delay_samples(delay_samples);
+    }
+
+    /// Delays the output of the band, rounded to the nearest sample.
+    pub fn set_band_delay_ms(& mut self, band: usize, delay_ms: f64) {
+        let delay_samples = milliseconds_to_samples(delay_ms, self.sample_rate);
+        self.set_band_delay_samples(band, delay_samples);
+    }
+
+    /// Delays the output of the band by the time of the sound over the distance,
+    /// the distance that the acoustic center of its driver is in front of the one
+    /// of the driver that arrives last.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> // The tweeter is 2.5 cm in front of the woofer.
+    ///    >>> crossover.set_band_delay_cm(1, 2.5);
+    ///    ```
+    ///
+    pub fn set_band_delay_cm(& mut self, band: usize, distance_cm: f64) {
+        self.set_band_delay_ms(band, distance_cm * 10.0 / SPEED_OF_SOUND);
+    }
+
+    /// Splits the sample in the bands, from the lowest.
+    pub fn split(& mut self, sample: f64, bands: & mut [f64]) {
+        assert_eq!(bands.len(), self.get_num_bands());
+        let mut remaining = sample;
+        for (index, point) in self.points.iter_mut().enumerate() {
+            let low = point.lowpass.process(remaining);
+            bands[index] = self.allpasses[index].iter_mut().fold(low, |x, filter| filter.process(x));
+            remaining = point.highpass.process(remaining);
+        }
+        bands[self.points.len()] = remaining;
+        for ((band, delay), inverted) in bands.iter_mut().zip(self.delays.iter_mut()).zip(& self.inverted) {
+            *band = delay.process(*band);
+            if *inverted {
+                *band = -*band;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smoothing::magnitude_response_db;
+    use crate::butterworth_filter::LINKWITZ_RILEY_ORDERS;
+
+    #[test]
+    fn test_sum_of_bands_is_flat() {
+        let mut crossover = CrossoverNetwork::new(48_000, & [200.0, 2_000.0, 8_000.0]).unwrap();
+        let mut bands = [0.0; 4];
+        let mut sum_response = Vec::new();
+        let mut band_responses = vec![Vec::new(); 4];
+        // 1 Hz resolution.
+        for n in 0..48_000 {
+            crossover.split(if n == 0 { 1.0 } else { 0.0 }, & mut bands);
+            sum_response.push(bands.iter().sum::<f64>());
+            for (response, band) in band_responses.iter_mut().zip(& bands) {
+                response.push(*band);
+            }
+        }
+        let (frequencies, sum_db) = magnitude_response_db(& sum_response, 48_000);
+        assert!(sum_db[1..].iter().all(|db| db.abs() < 0.01));
+
+        // Each band is -6 dB at its crossover points.
+        let bin = |frequency: f64| frequencies.iter().position(|f| *f >= frequency).unwrap();
+        let (_, low_db) = magnitude_response_db(& band_responses[0], 48_000);
+        let (_, mid_db) = magnitude_response_db(& band_responses[1], 48_000);
+        assert!((low_db[bin(200.0)] + 6.0).abs() < 0.2);
+        assert!((mid_db[bin(2_000.0)] + 6.0).abs() < 0.2);
+        assert!(mid_db[bin(700.0)].abs() < 0.5);
+        assert!(low_db[bin(2_000.0)] < -40.0);
+    }
+
+    #[test]
+    fn test_orders() {
+        for order in LINKWITZ_RILEY_ORDERS {
+            let mut crossover = CrossoverNetwork::with_order(48_000, & [300.0, 2_500.0], order).unwrap();
+            assert_eq!((crossover.get_order(), crossover.get_num_bands()), (order, 3));
+            let mut bands = [0.0; 3];
+            let mut sum_response = Vec::new();
+            let mut low_response = Vec::new();
+            for n in 0..48_000 {
+                crossover.split(if n == 0 { 1.0 } else { 0.0 }, & mut bands);
+                sum_response.push(bands.iter().sum::<f64>());
+                low_response.push(bands[0]);
+            }
+            let (frequencies, sum_db) = magnitude_response_db(& sum_response, 48_000);
+            assert!(sum_db[1..].iter().all(|db| db.abs() < 0.01));
+            // The steeper the order, the more the low band is down an octave above.
+            let (_, low_db) = magnitude_response_db(& low_response, 48_000);
+            let bin = |frequency: f64| frequencies.iter().position(|f| *f >= frequency).unwrap();
+            assert!(low_db[bin(600.0)] < -5.0 * order as f64);
+        }
+        assert_eq!(CrossoverNetwork::new(48_000, & [1_000.0]).unwrap().get_order(), DEFAULT_CROSSOVER_ORDER);
+        assert!(CrossoverNetwork::with_order(48_000, & [1_000.0], 3).is_err());
+    }
+
+    #[test]
+    fn test_band_polarity_and_delay() {
+        let impulse_bands = |crossover: & mut CrossoverNetwork| {
+            let mut bands = [0.0; 2];
+            let mut responses = vec![Vec::new(); 2];
+            for n in 0..1_000 {
+                crossover.split(if n == 0 { 1.0 } else { 0.0 }, & mut bands);
+                responses[0].push(bands[0]);
+                responses[1].push(bands[1]);
+            }
+            responses
+        };
+        let reference = impulse_bands(& mut CrossoverNetwork::new(48_000, & [2_000.0]).unwrap());
+        let mut crossover = CrossoverNetwork::new(48_000, & [2_000.0]).unwrap();
+        crossover.set_band_polarity_inverted(0, true);
+        // 34.3 cm is 1 ms, 48 samples.
+        crossover.set_band_delay_cm(1, 34.3);
+        assert_eq!(crossover.get_band_delay_samples(1), 48);
+        assert!((crossover.get_band_delay_ms(1) - 1.0).abs() < 1e-12);
+        assert!(crossover.get_band_polarity_inverted(0));
+        let aligned = impulse_bands(& mut crossover);
+        assert!(aligned[0].iter().zip(& reference[0]).all(|(x, y)| *x == -*y));
+        assert!(aligned[1][..48].iter().all(|x| *x == 0.0));
+        assert_eq!(aligned[1][48..], reference[1][..1_000 - 48]);
+
+        crossover.set_band_delay_ms(0, 0.5);
+        assert_eq!(crossover.get_band_delay_samples(0), 24);
+    }
+
+    #[test]
+    fn test_invalid_frequencies() {
+        assert!(CrossoverNetwork::new(48_000, & []).is_err());
+        assert!(CrossoverNetwork::new(48_000, & [1_000.0, 500.0]).is_err());
+        assert!(CrossoverNetwork::new(48_000, & [30_000.0]).is_err());
+        assert_eq!(CrossoverNetwork::new(48_000, & [1_000.0]).unwrap().get_num_bands(), 2);
+    }
+}
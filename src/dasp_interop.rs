@@ -0,0 +1,224 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: The processing blocks in the audio graphs of the dasp crate, with
+//!              the feature "dasp".
+//!              dasp moves the audio as Frames, a sample of each channel, of any
+//!              sample type, i16, I24, f32..., and Signals, the iterators of the
+//!              frames. The blocks of this crate process the f64 of one channel.
+//!              A FrameProcessor has a block for each channel of the frame, made
+//!              by a factory with the index of the channel, and converts the
+//!              samples of the frame to f64 and back with the conversions of dasp:
+//!
+//!                 [L, R] of i16  ->  [block 0 (L), block 1 (R)] of f64  ->  [L, R] of i16
+//!
+//!              To f64 they are the ones of convert.rs, x / 32768 for i16, but back
+//!              to an integer type dasp truncates, where convert.rs rounds, and
+//!              takes the samples in [-1, 1[ . The output of the blocks to the
+//!              integer types is clamped to that range, the float types keep the
+//!              samples above full scale.
+//!              The frames of a slice are processed in place with process_frames(),
+//!              and a dasp Signal is filtered with filtered() of DaspSignalExt, a
+//!              Signal itself, that goes on in the graph with the adapters of dasp.
+//!              The I24 of convert.rs and the I24 of dasp are converted with From.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    dasp - Digital audio signal processing in Rust
+//!    https://github.com/RustAudio/dasp
+//!
+
+
+use std::marker::PhantomData;
+
+use dasp::Frame; // Trait
+use dasp::Sample; // Trait
+use dasp::Signal; // Trait
+use dasp::sample::Duplex; // Trait
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::convert::I24;
+
+
+/// A block for each channel of the frames of the type F.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut processor = FrameProcessor::<[i16; 2], _>::new(|_| make_lowpass(1_000.0, 48_000, None));
+///    >>> let frame = processor.process_frame([1_000, -1_000]);
+///    >>> processor.process_frames(& mut frames);
+///    ```
+///
+pub struct FrameProcessor<F: Frame, B: ProcessingBlock> {
+    blocks:     Vec<B>,
+    // The output is clamped to [-1, 1[ for the integer sample types.
+    is_integer: bool,
+    frame:      PhantomData<F>,
+}
+
+impl<F, B> FrameProcessor<F, B>
+        where F: Frame, F::Sample: Duplex<f64>, B: ProcessingBlock {
+    /// The factory is called with the index of each channel, 0 to F::CHANNELS - 1 .
+    pub fn new<M: FnMut(usize) -> B>(make_block: M) -> Self {
+        // A step of 1e-12 is lost in the integer sample types, and kept in the
+        // float ones.
+        let is_integer = F::Sample::from_sample(1e-12_f64).to_sample::<f64>() == 0.0;
+        FrameProcessor { blocks: (0..F::CHANNELS).map(make_block).collect(), is_integer, frame: PhantomData }
+    }
+
+    pub fn get_num_channels(& self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn get_block_mut(& mut self, channel: usize) -> Option<& mut B> {
+        self.blocks.get_mut(channel)
+    }
+
+    /// Each sample of the frame through the block of its channel.
+    pub fn process_frame(& mut self, frame: F) -> F {
+        let (blocks, is_integer) = (& mut self.blocks, self.is_integer);
+        F::from_fn(|channel| {
+            let x = frame.channel(channel).copied().unwrap_or(F::Sample::EQUILIBRIUM);
+            let y = blocks[channel].process(x.to_sample::<f64>());
+            match is_integer {
+                true  => F::Sample::from_sample(y.clamp(-1.0, 1.0 - f64::EPSILON)),
+                false => F::Sample::from_sample(y),
+            }
+        })
+    }
+
+    /// Processes the frames in place, like the buffer of a dasp graph.
+    pub fn process_frames(& mut self, frames: & mut [F]) {
+        for frame in frames.iter_mut() {
+            * frame = self.process_frame(* frame);
+        }
+    }
+}
+
+/// The signal S through a FrameProcessor, a dasp Signal.
+pub struct FilteredSignal<S: Signal, B: ProcessingBlock> {
+    signal:    S,
+    processor: FrameProcessor<S::Frame, B>,
+}
+
+impl<S, B> FilteredSignal<S, B>
+        where S: Signal, <S::Frame as Frame>::Sample: Duplex<f64>, B: ProcessingBlock {
+    pub fn get_processor_mut(& mut self) -> & mut FrameProcessor<S::Frame, B> {
+        & mut self.processor
+    }
+}
+
+impl<S, B> Signal for FilteredSignal<S, B>
+        where S: Signal, <S::Frame as Frame>::Sample: Duplex<f64>, B: ProcessingBlock {
+    type Frame = S::Frame;
+
+    fn next(& mut self) -> Self::Frame {
+        let frame = self.signal.next();
+        self.processor.process_frame(frame)
+    }
+
+    fn is_exhausted(& self) -> bool {
+        self.signal.is_exhausted()
+    }
+}
+
+/// filtered() for all the dasp Signals.
+///
+/// In Rust:
+///    ```text
+///    >>> let frames: Vec<[f32; 2]> = dasp::signal::from_iter(input)
+///    >>>     .filtered(|_| make_highpass(80.0, 48_000, None))
+///    >>>     .until_exhausted()
+///    >>>     .collect();
+///    ```
+///
+pub trait DaspSignalExt: Signal + Sized
+        where <Self::Frame as Frame>::Sample: Duplex<f64> {
+    /// The signal through a block for each channel, made by the factory with the
+    /// index of the channel.
+    fn filtered<B, M>(self, make_block: M) -> FilteredSignal<Self, B>
+            where B: ProcessingBlock, M: FnMut(usize) -> B {
+        FilteredSignal { signal: self, processor: FrameProcessor::new(make_block) }
+    }
+}
+
+impl<S> DaspSignalExt for S where S: Signal, <S::Frame as Frame>::Sample: Duplex<f64> {}
+
+impl From<I24> for dasp::sample::types::I24 {
+    fn from(sample: I24) -> Self {
+        // I24 is always in the range of 24 bits.
+        dasp::sample::types::I24::new_unchecked(sample.0)
+    }
+}
+
+impl From<dasp::sample::types::I24> for I24 {
+    fn from(sample: dasp::sample::types::I24) -> Self {
+        I24(sample.inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::convert::Sample as _;
+
+    struct Gain(f64);
+
+    impl ProcessingBlock for Gain {
+        fn process(& mut self, sample: f64) -> f64 {
+            self.0 * sample
+        }
+    }
+
+    #[test]
+    fn test_frame_processor() {
+        let left:  Vec<f64> = (0..400).map(|n| 0.5 * f64::sin(n as f64 * 0.3)).collect();
+        let right: Vec<f64> = (0..400).map(|n| 0.5 * f64::cos(n as f64 * 1.1)).collect();
+        let make = |channel: usize| make_lowpass(1_000.0 * (channel + 1) as f64, 48_000, None);
+        let mut expected_left = make(0);
+        let mut expected_right = make(1);
+
+        // Frames of f64, the same as the blocks, and of i16, with the conversions
+        // of dasp.
+        let mut frames: Vec<[f64; 2]> = left.iter().zip(& right).map(|(l, r)| [*l, *r]).collect();
+        let mut processor = FrameProcessor::new(make);
+        assert_eq!(processor.get_num_channels(), 2);
+        processor.process_frames(& mut frames);
+        assert!(frames.iter().zip(left.iter().zip(& right))
+            .all(|(frame, (l, r))| * frame == [expected_left.process(*l), expected_right.process(*r)]));
+
+        let frames_i16: Vec<[i16; 2]> = left.iter().zip(& right).map(|(l, r)| [i16::from_f64(*l), i16::from_f64(*r)]).collect();
+        let mut processor = FrameProcessor::<[i16; 2], _>::new(make);
+        let (mut expected_left, mut expected_right) = (make(0), make(1));
+        for frame in & frames_i16 {
+            let output = processor.process_frame(* frame);
+            assert_eq!(output, [i16::from_sample(expected_left.process(frame[0].to_f64())),
+                                i16::from_sample(expected_right.process(frame[1].to_f64()))]);
+        }
+        // Above full scale, clamped for the integer types, kept for the float ones.
+        let mut gain = FrameProcessor::<dasp::sample::types::I24, _>::new(|_| Gain(4.0));
+        assert_eq!(gain.process_frame(dasp::sample::types::I24::new(4_000_000).unwrap()).inner(), 8_388_607);
+        assert_eq!(FrameProcessor::<[f32; 1], _>::new(|_| Gain(4.0)).process_frame([0.5]), [2.0]);
+        assert_eq!(dasp::sample::types::I24::from(I24(-8_388_608)).inner(), -8_388_608);
+        assert_eq!(I24::from(dasp::sample::types::I24::new(1_234).unwrap()), I24(1_234));
+    }
+
+    #[test]
+    fn test_filtered_signal() {
+        let input: Vec<f32> = (0..500).map(|n| f32::sin(n as f32 * 0.9)).collect();
+        let output: Vec<f32> = dasp::signal::from_iter(input.iter().copied())
+            .filtered(|_| make_lowpass(500.0, 48_000, None))
+            .until_exhausted()
+            .collect();
+        let mut filter = make_lowpass(500.0, 48_000, None);
+        assert_eq!(output.len(), input.len());
+        assert!(output.iter().zip(& input).all(|(y, x)| * y == filter.process(*x as f64) as f32));
+        // The 0.9 rad/sample tone is far above the cutoff.
+        assert!(output[400..].iter().all(|y| y.abs() < 0.01));
+    }
+}
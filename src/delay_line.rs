@@ -0,0 +1,254 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Delay line processing block.
+//!              Delays the signal by an integer number of samples, using a
+//!              circular buffer. It's the building block for crossfeed, time
+//!              alignment of speaker drivers and latency compensation.
+//!
+//!              For delays with a fraction of a sample, like the time alignment of
+//!              the drivers of a multi-way speaker after the crossover, there is the
+//!              Thiran all-pass. It has a flat magnitude response and a maximally
+//!              flat group delay, equal to the fractional delay D at low frequencies.
+//!              The N order Thiran all-pass has the coefficients
+//!
+//!                 a_k = (-1)^k C(N, k) prod_{n=0}^{N} (D - N + n) / (D - N + k + n)
+//!
+//!              and b_k = a_{N-k}. It's stable for D > N - 1, and most accurate for
+//!              D near N. FractionalDelay combines a DelayLine with a Thiran all-pass.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Thiran, J.-P. - Recursive digital filters with maximally flat group delay.
+//!       IEEE Transactions on Circuit Theory, 1971
+//!
+//!    2. Thiran Allpass Interpolators - Julius O. Smith III, Physical Audio Signal Processing
+//!       https://ccrma.stanford.edu/~jos/pasp/Thiran_Allpass_Interpolators.html
+//!
+
+
+use crate::iir_filter::ProcessingBlock;
+use crate::iir_filter::IIRFilter;
+
+
+/// Integer sample delay line.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut delay = DelayLine::new(2);
+///    >>> [1.0, 0.0, 0.0].iter().map(|x| delay.process(*x)).collect()
+///    [0.0, 0.0, 1.0]
+///    ```
+///
+pub struct DelayLine {
+    // Circular buffer with delay + 1 positions.
+    buffer: Vec<f64>,
+    write_index: usize,
+}
+
+impl DelayLine {
+    pub fn new(delay_samples: usize) -> Self {
+        DelayLine {
+            buffer: vec![0.0; delay_samples + 1],
+            write_index: 0,
+        }
+    }
+
+    /// Creates a delay line from a time in milliseconds, rounded to the nearest sample.
+    pub fn from_milliseconds(delay_ms: f64, sample_rate: u32) -> Self {
+        DelayLine::new(milliseconds_to_samples(delay_ms, sample_rate))
+    }
+
+    pub fn get_delay_samples(& self) -> usize {
+        self.buffer.len() - 1
+    }
+
+    /// Changes the delay. The delayed samples are cleared.
+    pub fn set_delay_samples(& mut self, delay_samples: usize) {
+        self.buffer = vec![0.0; delay_samples + 1];
+        self.write_index = 0;
+    }
+
+    /// Clears the delayed samples.
+    pub fn reset(& mut self) {
+        self.buffer.iter_mut().for_each(|x| *x = 0.0);
+        self.write_index = 0;
+    }
+}
+
+impl ProcessingBlock for DelayLine {
+    fn process(& mut self, sample: f64) -> f64 {
+        let len = self.buffer.len();
+        self.buffer[self.write_index] = sample;
+        // The oldest sample is the one just after the write position.
+        let read_index = (self.write_index + 1) % len;
+        let result = self.buffer[read_index];
+        self.write_index = read_index;
+
+        result
+    }
+}
+
+/// Converts a time in milliseconds to the nearest number of samples.
+pub fn milliseconds_to_samples(delay_ms: f64, sample_rate: u32) -> usize {
+    assert!(delay_ms >= 0.0);
+    (delay_ms * 0.001 * sample_rate as f64).round() as usize
+}
+
+/// Thiran all-pass of order N with a delay of D samples at low frequencies.
+/// D must be greater than N - 1, for the filter to be stable.
+///
+/// In Rust:
+///    ```text
+///    >>> // Delay of 2.3 samples.
+///    >>> let mut filter = make_thiran_allpass(2.3, 2);
+///    >>> let output = filter.process(sample);
+///    ```
+///
+pub fn make_thiran_allpass(delay_samples: f64, order: usize) -> IIRFilter {
+    assert!(order >= 1);
+    assert!(delay_samples > order as f64 - 1.0);
+    let n_order = order as f64;
+    let mut a_coeffs = Vec::with_capacity(order + 1);
+    let mut binomial = 1.0;
+    for k in 0..=order {
+        let mut product = 1.0;
+        for n in 0..=order {
+            let n = n as f64;
+            product *= (delay_samples - n_order + n) / (delay_samples - n_order + k as f64 + n);
+        }
+        let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+        a_coeffs.push(sign * binomial * product);
+        // C(N, k + 1) from C(N, k).
+        binomial *= (n_order - k as f64) / (k as f64 + 1.0);
+    }
+    let b_coeffs: Vec<f64> = a_coeffs.iter().rev().cloned().collect();
+
+    let mut filter = IIRFilter::new(order);
+    filter.set_coefficients(& a_coeffs, & b_coeffs).unwrap();
+    filter
+}
+
+/// Delay of any number of samples, including a fraction, made of an integer
+/// DelayLine and a Thiran all-pass for the fractional part.
+pub struct FractionalDelay {
+    delay_samples: f64,
+    delay_line:    DelayLine,
+    allpass:       IIRFilter,
+}
+
+impl FractionalDelay {
+    /// The order of the Thiran all-pass is usually 1 to 4, higher orders have a
+    /// flat group delay up to higher frequencies. The delay must be at least
+    /// order - 0.5 samples.
+    pub fn new(delay_samples: f64, order: usize) -> Self {
+        assert!(delay_samples >= order as f64 - 0.5);
+        // The all-pass has a delay in [N - 0.5, N + 0.5], the rest is integer.
+        let integer_delay = delay_samples.round() as usize - order;
+        let allpass_delay = delay_samples - integer_delay as f64;
+        FractionalDelay {
+            delay_samples,
+            delay_line: DelayLine::new(integer_delay),
+            allpass: make_thiran_allpass(allpass_delay, order),
+        }
+    }
+
+    pub fn from_milliseconds(delay_ms: f64, sample_rate: u32, order: usize) -> Self {
+        FractionalDelay::new(delay_ms * 0.001 * sample_rate as f64, order)
+    }
+
+    pub fn get_delay_samples(& self) -> f64 {
+        self.delay_samples
+    }
+}
+
+impl ProcessingBlock for FractionalDelay {
+    fn process(& mut self, sample: f64) -> f64 {
+        self.allpass.process(self.delay_line.process(sample))
+    }
+}
+
+/// Delays that time-align channels, for example the drivers of a speaker, given
+/// the arrival delay of each one in samples. The channel that arrives last isn't
+/// delayed more than the minimum of the all-pass, order - 0.5 samples, the others
+/// are delayed to arrive with it.
+pub fn make_alignment_delays(arrival_delays_samples: & [f64], order: usize) -> Vec<FractionalDelay> {
+    let latest = arrival_delays_samples.iter().cloned().fold(f64::MIN, f64::max);
+    let minimum_delay = order as f64 - 0.5;
+    arrival_delays_samples.iter()
+        .map(|delay| FractionalDelay::new(latest - delay + minimum_delay, order))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_line_delays_impulse() {
+        let mut delay = DelayLine::new(3);
+        let outputs: Vec<f64> = [1.0, 2.0, 0.0, 0.0, 0.0, 0.0].iter().map(|x| delay.process(*x)).collect();
+        assert_eq!(outputs, vec![0.0, 0.0, 0.0, 1.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_delay_line_zero_delay_is_identity() {
+        let mut delay = DelayLine::new(0);
+        assert_eq!(delay.process(0.5), 0.5);
+        assert_eq!(delay.process(-0.25), -0.25);
+    }
+
+    #[test]
+    fn test_delay_line_from_milliseconds() {
+        let delay = DelayLine::from_milliseconds(1.0, 48_000);
+        assert_eq!(delay.get_delay_samples(), 48);
+    }
+
+    // Group delay at a low frequency, from the phase response.
+    fn low_frequency_delay(filter: & IIRFilter) -> f64 {
+        let sample_rate = 48_000;
+        let frequency = 10.0;
+        let w = std::f64::consts::TAU * frequency / sample_rate as f64;
+        -filter.frequency_response(frequency, sample_rate).arg() / w
+    }
+
+    #[test]
+    fn test_thiran_allpass() {
+        for (delay, order) in [(0.3, 1), (1.5, 1), (2.3, 2), (3.8, 4)] {
+            let filter = make_thiran_allpass(delay, order);
+            assert!((low_frequency_delay(& filter) - delay).abs() < 1e-6);
+            // All-pass, unit magnitude at all frequencies.
+            for frequency in [100.0, 5_000.0, 20_000.0] {
+                assert!((filter.frequency_response(frequency, 48_000).norm() - 1.0).abs() < 1e-12);
+            }
+        }
+        // The first order Thiran is a1 = (1 - D) / (1 + D).
+        let filter = make_thiran_allpass(0.5, 1);
+        assert!((filter.a_coeffs[1] - 1.0 / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_fractional_delay_aligns_sine() {
+        use std::f64::consts::TAU;
+        let frequency = 0.01; // Cycles per sample.
+        let delays = make_alignment_delays(& [0.0, 2.75, 10.4], 3);
+        assert!((delays[2].get_delay_samples() - 2.5).abs() < 1e-12);
+        for (arrival, mut delay) in [0.0, 2.75, 10.4].into_iter().zip(delays) {
+            let total = arrival + delay.get_delay_samples();
+            for n in 0..1_000 {
+                // The channel arrives with its arrival delay.
+                let output = delay.process(f64::sin(TAU * frequency * (n as f64 - arrival)));
+                if n > 200 {
+                    // All the channels end up with the same total delay.
+                    let expected = f64::sin(TAU * frequency * (n as f64 - total));
+                    assert!((total - 12.9).abs() < 1e-12);
+                    assert!((output - expected).abs() < 1e-3);
+                }
+            }
+        }
+    }
+}
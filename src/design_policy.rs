@@ -0,0 +1,477 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Design time safety policy of the filters.
+//!              The make_* functions of butterworth_filter.rs take any parameters,
+//!              and some of them make filters that explode or that are useless:
+//!
+//!                 - a frequency at or above Nyquist, or not positive;
+//!                 - a Q so high that the poles are almost on the unit circle, the
+//!                   filter rings for seconds and the rounding of the coefficients
+//!                   makes it unstable;
+//!                 - a gain beyond what an equalizer should do.
+//!
+//!              A DesignPolicy checks the parameters before the design, for all the
+//!              make_* functions, and depending on its action it rejects them with an
+//!              error, or clamps them to the nearest safe value with a warning.
+//!              The pole radius is computed from the designed coefficients, so the
+//!              same check works for all the filter types. A Q above the limit is
+//!              lowered, by bisection, towards the default Q of 1 / sqrt(2), until
+//!              the pole radius is below the limit. For the notch, the band-stop and
+//!              the band-pass of a bandwidth, whose q_factor is a bandwidth in
+//!              octaves, the bisection goes the other way by itself.
+//!              The Butterworth, Linkwitz-Riley and Bessel cascades and the Linkwitz
+//!              transform of loudspeaker.rs go through the same checks, of the
+//!              frequency and of the poles of all the sections, and of the target
+//!              frequency of the transform. They have no Q to lower, a pole radius
+//!              above the limit is an error with both actions.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use std::f64::consts::FRAC_1_SQRT_2;
+
+use crate::iir_filter::IIRFilter;
+use crate::iir_filter::FilterChain;
+use crate::butterworth_filter::make_lowpass;
+use crate::butterworth_filter::make_highpass;
+use crate::butterworth_filter::make_bandpass;
+use crate::butterworth_filter::make_allpass;
+use crate::butterworth_filter::make_peak;
+use crate::butterworth_filter::make_peak_eq_constant_q;
+use crate::butterworth_filter::make_lowshelf;
+use crate::butterworth_filter::make_highshelf;
+use crate::butterworth_filter::make_notch;
+use crate::butterworth_filter::make_bandstop;
+use crate::butterworth_filter::make_bandpass_bw;
+use crate::butterworth_filter::make_butterworth_lowpass;
+use crate::butterworth_filter::make_butterworth_highpass;
+use crate::butterworth_filter::make_linkwitz_riley_lowpass;
+use crate::butterworth_filter::make_linkwitz_riley_highpass;
+use crate::butterworth_filter::make_bessel_lowpass;
+use crate::butterworth_filter::make_bessel_highpass;
+use crate::butterworth_filter::make_lowpass_with_report;
+use crate::butterworth_filter::make_highpass_with_report;
+use crate::butterworth_filter::make_bandpass_with_report;
+use crate::butterworth_filter::make_allpass_with_report;
+use crate::butterworth_filter::make_peak_with_report;
+use crate::butterworth_filter::make_peak_eq_constant_q_with_report;
+use crate::butterworth_filter::make_lowshelf_with_report;
+use crate::butterworth_filter::make_highshelf_with_report;
+use crate::butterworth_filter::make_notch_with_report;
+use crate::butterworth_filter::make_bandstop_with_report;
+use crate::butterworth_filter::make_bandpass_bw_with_report;
+use crate::loudspeaker::make_linkwitz_transform;
+use crate::design_report::DesignReport;
+
+
+pub const DEFAULT_MAX_POLE_RADIUS: f64 = 0.9999;
+/// Highest frequency, as a fraction of Nyquist.
+pub const DEFAULT_MAX_FREQUENCY_RATIO: f64 = 0.98;
+pub const DEFAULT_MIN_FREQUENCY: f64 = 1.0;
+pub const DEFAULT_MAX_GAIN_DB: f64 = 24.0;
+
+// Iterations of the bisection of the Q.
+const Q_BISECTION_STEPS: usize = 50;
+
+/// The filters of butterworth_filter.rs, one for each make_* function, the biquads
+/// and the cascades of high order, and the Linkwitz transform of loudspeaker.rs .
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterKind {
+    LowPass,
+    HighPass,
+    BandPass,
+    AllPass,
+    Peak,
+    PeakConstantQ,
+    LowShelf,
+    HighShelf,
+    Notch,
+    /// The q_factor is the bandwidth in octaves, see make_bandstop().
+    BandStop,
+    /// The q_factor is the bandwidth in octaves, see make_bandpass_bw().
+    BandPassBw,
+    /// The order of the cascade, see make_butterworth_lowpass().
+    ButterworthLowPass(usize),
+    ButterworthHighPass(usize),
+    /// The order, 2, 4 or 8, see make_linkwitz_riley_lowpass().
+    LinkwitzRileyLowPass(usize),
+    LinkwitzRileyHighPass(usize),
+    /// The order, 2 to 10, see make_bessel_lowpass().
+    BesselLowPass(usize),
+    BesselHighPass(usize),
+    /// The frequency and the q_factor are the resonance f0 and Q0 of the sealed box,
+    /// see make_linkwitz_transform().
+    LinkwitzTransform { target_frequency: f64, target_q: f64 },
+}
+
+impl FilterKind {
+    /// Calls the make_* function of the kind, a biquad is a chain of one section.
+    /// The gain is ignored by the kinds without one, and the q_factor by the
+    /// cascades, their sections have the Q of the design.
+    pub fn make(& self, frequency: f64, sample_rate: u32, gain_db: f64, q_factor: f64) -> Result<FilterChain, String> {
+        let biquad = |filter: IIRFilter| -> Result<FilterChain, String> { Ok(FilterChain::new(vec![filter])) };
+        let bandwidth_octaves = q_factor;
+        let q = Some(q_factor);
+        match *self {
+            FilterKind::LowPass       => biquad(make_lowpass(frequency, sample_rate, q)),
+            FilterKind::HighPass      => biquad(make_highpass(frequency, sample_rate, q)),
+            FilterKind::BandPass      => biquad(make_bandpass(frequency, sample_rate, q)),
+            FilterKind::AllPass       => biquad(make_allpass(frequency, sample_rate, q)),
+            FilterKind::Peak          => biquad(make_peak(frequency, sample_rate, gain_db, q)),
+            FilterKind::PeakConstantQ => biquad(make_peak_eq_constant_q(frequency, sample_rate, gain_db, q)),
+            FilterKind::LowShelf      => biquad(make_lowshelf(frequency, sample_rate, gain_db, q)),
+            FilterKind::HighShelf     => biquad(make_highshelf(frequency, sample_rate, gain_db, q)),
+            FilterKind::Notch         => biquad(make_notch(frequency, sample_rate, q)),
+            FilterKind::BandStop      => biquad(make_bandstop(frequency, sample_rate, bandwidth_octaves)),
+            FilterKind::BandPassBw    => biquad(make_bandpass_bw(frequency, sample_rate, bandwidth_octaves)),
+            FilterKind::ButterworthLowPass(order)    => make_butterworth_lowpass(frequency, sample_rate, order),
+            FilterKind::ButterworthHighPass(order)   => make_butterworth_highpass(frequency, sample_rate, order),
+            FilterKind::LinkwitzRileyLowPass(order)  => make_linkwitz_riley_lowpass(frequency, sample_rate, order),
+            FilterKind::LinkwitzRileyHighPass(order) => make_linkwitz_riley_highpass(frequency, sample_rate, order),
+            FilterKind::BesselLowPass(order)         => make_bessel_lowpass(frequency, sample_rate, order),
+            FilterKind::BesselHighPass(order)        => make_bessel_highpass(frequency, sample_rate, order),
+            FilterKind::LinkwitzTransform { target_frequency, target_q } => {
+                // make_linkwitz_transform() asserts its parameters.
+                let nyquist = sample_rate as f64 / 2.0;
+                if !(frequency > 0.0 && frequency < nyquist && target_frequency > 0.0 && target_frequency < nyquist
+                     && q_factor > 0.0 && target_q > 0.0) {
+                    return Err(format!("Error: invalid Linkwitz transform from {} Hz, Q {} to {} Hz, Q {}",
+                                       frequency, q_factor, target_frequency, target_q));
+                }
+                biquad(make_linkwitz_transform(frequency, q_factor, target_frequency, target_q, sample_rate))
+            },
+        }
+    }
+
+    /// Calls the make_*_with_report function of the kind, the filter and the
+    /// intermediate values of its design, an error for the kinds that aren't a
+    /// biquad of butterworth_filter.rs .
+    pub fn make_with_report(& self, frequency: f64, sample_rate: u32, gain_db: f64, q_factor: f64)
+            -> Result<(IIRFilter, DesignReport), String> {
+        let bandwidth_octaves = q_factor;
+        let q = Some(q_factor);
+        let design = match self {
+            FilterKind::LowPass       => make_lowpass_with_report(frequency, sample_rate, q),
+            FilterKind::HighPass      => make_highpass_with_report(frequency, sample_rate, q),
+            FilterKind::BandPass      => make_bandpass_with_report(frequency, sample_rate, q),
+            FilterKind::AllPass       => make_allpass_with_report(frequency, sample_rate, q),
+            FilterKind::Peak          => make_peak_with_report(frequency, sample_rate, gain_db, q),
+            FilterKind::PeakConstantQ => make_peak_eq_constant_q_with_report(frequency, sample_rate, gain_db, q),
+            FilterKind::LowShelf      => make_lowshelf_with_report(frequency, sample_rate, gain_db, q),
+            FilterKind::HighShelf     => make_highshelf_with_report(frequency, sample_rate, gain_db, q),
+            FilterKind::Notch         => make_notch_with_report(frequency, sample_rate, q),
+            FilterKind::BandStop      => make_bandstop_with_report(frequency, sample_rate, bandwidth_octaves),
+            FilterKind::BandPassBw    => make_bandpass_bw_with_report(frequency, sample_rate, bandwidth_octaves),
+            _ => return Err(format!("Error: the {:?} has no design report, it isn't a biquad of butterworth_filter.rs",
+                                    self)),
+        };
+        Ok(design)
+    }
+
+    pub fn has_gain(& self) -> bool {
+        matches!(self, FilterKind::Peak | FilterKind::PeakConstantQ | FilterKind::LowShelf | FilterKind::HighShelf)
+    }
+
+    /// The biquads of butterworth_filter.rs, whose q_factor sets the poles.
+    pub fn has_q(& self) -> bool {
+        !matches!(self, FilterKind::ButterworthLowPass(_) | FilterKind::ButterworthHighPass(_)
+                      | FilterKind::LinkwitzRileyLowPass(_) | FilterKind::LinkwitzRileyHighPass(_)
+                      | FilterKind::BesselLowPass(_) | FilterKind::BesselHighPass(_)
+                      | FilterKind::LinkwitzTransform { .. })
+    }
+
+    /// The same cascade with another order, an error for the biquads.
+    pub fn with_order(& self, order: usize) -> Result<FilterKind, String> {
+        match self {
+            FilterKind::ButterworthLowPass(_)    => Ok(FilterKind::ButterworthLowPass(order)),
+            FilterKind::ButterworthHighPass(_)   => Ok(FilterKind::ButterworthHighPass(order)),
+            FilterKind::LinkwitzRileyLowPass(_)  => Ok(FilterKind::LinkwitzRileyLowPass(order)),
+            FilterKind::LinkwitzRileyHighPass(_) => Ok(FilterKind::LinkwitzRileyHighPass(order)),
+            FilterKind::BesselLowPass(_)         => Ok(FilterKind::BesselLowPass(order)),
+            FilterKind::BesselHighPass(_)        => Ok(FilterKind::BesselHighPass(order)),
+            _ => Err(format!("Error: the {:?} has no order, it is a biquad", self)),
+        }
+    }
+}
+
+/// What the policy does with a parameter out of its limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyAction {
+    /// Returns an error.
+    Reject,
+    /// Moves the parameter to the limit, and returns a warning.
+    Clamp,
+}
+
+/// The limits of the parameters of a design.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DesignPolicy {
+    pub action:              PolicyAction,
+    pub max_pole_radius:     f64,
+    pub max_frequency_ratio: f64,
+    pub min_frequency:       f64,
+    pub max_gain_db:         f64,
+}
+
+/// A filter designed with a policy, and the warnings of the clamped parameters.
+pub struct CheckedDesign {
+    /// The kind, with the checked target of a Linkwitz transform.
+    pub kind:      FilterKind,
+    pub filter:    FilterChain,
+    pub frequency: f64,
+    pub gain_db:   f64,
+    pub q_factor:  f64,
+    pub warnings:  Vec<String>,
+}
+
+impl CheckedDesign {
+    /// The filter of a design of one biquad, an error for a cascade.
+    pub fn into_biquad(self) -> Result<IIRFilter, String> {
+        match self.filter.get_filters() {
+            [filter] if filter.order == 2 => Ok(filter.clone()),
+            _ => Err(format!("Error: the {:?} is a cascade of order {}, not a biquad", self.kind,
+                             self.filter.get_order())),
+        }
+    }
+}
+
+impl DesignPolicy {
+    /// The default limits, with the action.
+    pub fn new(action: PolicyAction) -> Self {
+        DesignPolicy {
+            action,
+            max_pole_radius: DEFAULT_MAX_POLE_RADIUS,
+            max_frequency_ratio: DEFAULT_MAX_FREQUENCY_RATIO,
+            min_frequency: DEFAULT_MIN_FREQUENCY,
+            max_gain_db: DEFAULT_MAX_GAIN_DB,
+        }
+    }
+
+    /// Designs the filter, after the check of its parameters.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let policy = DesignPolicy::new(PolicyAction::Clamp);
+    ///    >>> let design = policy.make(FilterKind::LowPass, 30_000.0, 48_000, 0.0, 500.0)?;
+    ///    >>> for warning in & design.warnings {
+    ///    >>>     println!("{}", warning);
+    ///    >>> }
+    ///    Warning: frequency 30000 Hz clamped to 23520 Hz, the limit is 0.98 of Nyquist
+    ///    Warning: Q 500 clamped to 313.9369, the pole radius 0.999937 is above 0.9999
+    ///    ```
+    ///
+    pub fn make(& self, kind: FilterKind, frequency: f64, sample_rate: u32, gain_db: f64, q_factor: f64)
+            -> Result<CheckedDesign, String> {
+        let q_is_valid = q_factor.is_finite() && q_factor > 0.0;
+        if !frequency.is_finite() || !gain_db.is_finite() || (kind.has_q() && !q_is_valid) {
+            return Err(format!("Error: invalid parameters of the {:?}, frequency {} Hz, gain {} dB, Q {}",
+                               kind, frequency, gain_db, q_factor));
+        }
+        let mut warnings = Vec::new();
+
+        let max_frequency = self.max_frequency_ratio * sample_rate as f64 / 2.0;
+        let mut checked_frequency = frequency;
+        if frequency < self.min_frequency || frequency > max_frequency {
+            checked_frequency = frequency.clamp(self.min_frequency, max_frequency);
+            self.violation(& mut warnings, format!(
+                "frequency {} Hz {} {} Hz, the limit is {} of Nyquist", frequency, self.verb(), checked_frequency,
+                self.max_frequency_ratio))?;
+        }
+
+        let mut checked_kind = kind;
+        if let FilterKind::LinkwitzTransform { target_frequency, target_q } = kind {
+            if !(target_frequency.is_finite() && q_is_valid && target_q.is_finite() && target_q > 0.0) {
+                return Err(format!("Error: invalid Linkwitz transform from Q {} to {} Hz, Q {}",
+                                   q_factor, target_frequency, target_q));
+            }
+            if target_frequency < self.min_frequency || target_frequency > max_frequency {
+                let checked_target = target_frequency.clamp(self.min_frequency, max_frequency);
+                checked_kind = FilterKind::LinkwitzTransform { target_frequency: checked_target, target_q };
+                self.violation(& mut warnings, format!(
+                    "target frequency {} Hz {} {} Hz, the limit is {} of Nyquist", target_frequency, self.verb(),
+                    checked_target, self.max_frequency_ratio))?;
+            }
+        }
+
+        let mut checked_gain_db = gain_db;
+        if kind.has_gain() && gain_db.abs() > self.max_gain_db {
+            checked_gain_db = gain_db.clamp(-self.max_gain_db, self.max_gain_db);
+            self.violation(& mut warnings, format!(
+                "gain {} dB {} {} dB", gain_db, self.verb(), checked_gain_db))?;
+        }
+
+        let radius = |q: f64| -> Result<f64, String> {
+            Ok(max_chain_pole_radius(& checked_kind.make(checked_frequency, sample_rate, checked_gain_db, q)?))
+        };
+        let mut checked_q_factor = q_factor;
+        let pole_radius = radius(q_factor)?;
+        if pole_radius > self.max_pole_radius {
+            // The cascades and the Linkwitz transform have no Q that sets their poles.
+            if !kind.has_q() {
+                return Err(format!("Error: the {:?} at {} Hz has a pole radius {:.6} above {}",
+                                   checked_kind, checked_frequency, pole_radius, self.max_pole_radius));
+            }
+            // Bisection in the log of Q, between the safe default and the Q.
+            let (mut safe, mut unsafe_q) = (FRAC_1_SQRT_2.ln(), q_factor.ln());
+            if radius(FRAC_1_SQRT_2)? > self.max_pole_radius {
+                return Err(format!("Error: the {:?} at {} Hz has a pole radius above {} for any Q",
+                                   kind, checked_frequency, self.max_pole_radius));
+            }
+            for _ in 0..Q_BISECTION_STEPS {
+                let middle = (safe + unsafe_q) / 2.0;
+                if radius(middle.exp())? > self.max_pole_radius {
+                    unsafe_q = middle;
+                } else {
+                    safe = middle;
+                }
+            }
+            checked_q_factor = safe.exp();
+            self.violation(& mut warnings, format!(
+                "Q {} {} {:.4}, the pole radius {:.6} is above {}", q_factor, self.verb(), checked_q_factor,
+                pole_radius, self.max_pole_radius))?;
+        }
+
+        let filter = checked_kind.make(checked_frequency, sample_rate, checked_gain_db, checked_q_factor)?;
+        Ok(CheckedDesign {
+            kind: checked_kind,
+            filter,
+            frequency: checked_frequency,
+            gain_db: checked_gain_db,
+            q_factor: checked_q_factor,
+            warnings,
+        })
+    }
+
+    fn verb(& self) -> & str {
+        match self.action {
+            PolicyAction::Reject => "out of the limits, the nearest safe value is",
+            PolicyAction::Clamp  => "clamped to",
+        }
+    }
+
+    // Adds the warning, or returns the error.
+    fn violation(& self, warnings: & mut Vec<String>, message: String) -> Result<(), String> {
+        match self.action {
+            PolicyAction::Reject => Err(format!("Error: {}", message)),
+            PolicyAction::Clamp  => {
+                trace_event!(message = message.as_str(), "design parameter clamped");
+                warnings.push(format!("Warning: {}", message));
+                Ok(())
+            },
+        }
+    }
+}
+
+impl Default for DesignPolicy {
+    fn default() -> Self {
+        DesignPolicy::new(PolicyAction::Reject)
+    }
+}
+
+/// Largest magnitude of the poles of a biquad, the roots of a0 z^2 + a1 z + a2 ,
+/// or of the pole -a1 / a0 of a first order section.
+pub fn max_pole_radius(filter: & IIRFilter) -> f64 {
+    let a0 = filter.a_coeffs[0];
+    if filter.order == 1 {
+        return (filter.a_coeffs[1] / a0).abs();
+    }
+    let (a1, a2) = (filter.a_coeffs[1] / a0, filter.a_coeffs[2] / a0);
+    let discriminant = a1 * a1 - 4.0 * a2;
+    if discriminant < 0.0 {
+        // Complex conjugate poles, of radius squared a2.
+        a2.sqrt()
+    } else {
+        let root = discriminant.sqrt();
+        f64::max((-a1 + root).abs(), (-a1 - root).abs()) / 2.0
+    }
+}
+
+/// Largest magnitude of the poles of the sections of a cascade.
+pub fn max_chain_pole_radius(chain: & FilterChain) -> f64 {
+    chain.get_filters().iter().map(max_pole_radius).fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp_policy() {
+        let policy = DesignPolicy::new(PolicyAction::Clamp);
+        let design = policy.make(FilterKind::LowPass, 30_000.0, 48_000, 0.0, 500.0).unwrap();
+        assert_eq!(design.warnings.len(), 2);
+        assert_eq!(design.frequency, 0.98 * 24_000.0);
+        assert!(design.q_factor < 500.0);
+        assert!(max_chain_pole_radius(& design.filter) <= DEFAULT_MAX_POLE_RADIUS);
+        assert!(max_chain_pole_radius(& design.filter) > DEFAULT_MAX_POLE_RADIUS - 1e-6);
+
+        let design = policy.make(FilterKind::Peak, 1_000.0, 48_000, 40.0, 1.0).unwrap();
+        assert_eq!(design.gain_db, 24.0);
+        assert_eq!(design.warnings.len(), 1);
+        // The gain of a low-pass is ignored.
+        assert!(policy.make(FilterKind::LowPass, 1_000.0, 48_000, 40.0, 1.0).unwrap().warnings.is_empty());
+
+        // A narrow notch, its bandwidth is made wider.
+        let design = policy.make(FilterKind::Notch, 1_000.0, 48_000, 0.0, 1e-4).unwrap();
+        assert!(design.q_factor > 1e-4);
+        assert!(max_chain_pole_radius(& design.filter) <= DEFAULT_MAX_POLE_RADIUS);
+        for kind in [FilterKind::BandStop, FilterKind::BandPassBw] {
+            let design = policy.make(kind, 1_000.0, 48_000, 0.0, 1e-4).unwrap();
+            assert!(design.q_factor > 1e-4 && design.warnings.len() == 1);
+            assert!(max_chain_pole_radius(& design.filter) <= DEFAULT_MAX_POLE_RADIUS);
+        }
+    }
+
+    #[test]
+    fn test_reject_policy() {
+        let policy = DesignPolicy::default();
+        let design = policy.make(FilterKind::HighShelf, 8_000.0, 48_000, 6.0, 0.707).unwrap();
+        assert!(design.warnings.is_empty());
+        assert_eq!(design.into_biquad().unwrap().a_coeffs, make_highshelf(8_000.0, 48_000, 6.0, Some(0.707)).a_coeffs);
+
+        assert!(policy.make(FilterKind::LowPass, 30_000.0, 48_000, 0.0, 0.707).is_err());
+        assert!(policy.make(FilterKind::BandPass, 50.0, 48_000, 0.0, 1_000.0).is_err());
+        assert!(policy.make(FilterKind::LowShelf, 100.0, 48_000, -30.0, 0.707).is_err());
+        assert!(policy.make(FilterKind::AllPass, 1_000.0, 48_000, 0.0, -1.0).is_err());
+        assert!(policy.make(FilterKind::Peak, f64::NAN, 48_000, 0.0, 1.0).is_err());
+        let design = policy.make(FilterKind::BandStop, 50.0, 48_000, 0.0, 1.0 / 3.0).unwrap();
+        assert_eq!(design.into_biquad().unwrap().a_coeffs, make_bandstop(50.0, 48_000, 1.0 / 3.0).a_coeffs);
+        assert!(policy.make(FilterKind::BandPassBw, 24_000.0, 48_000, 0.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_cascades_and_linkwitz_transform() {
+        let policy = DesignPolicy::default();
+        let design = policy.make(FilterKind::LinkwitzRileyLowPass(4), 2_000.0, 48_000, 0.0, 0.0).unwrap();
+        assert_eq!(design.filter.get_order(), 4);
+        assert!(design.into_biquad().is_err());
+        // The first order section of an odd order.
+        let design = policy.make(FilterKind::ButterworthHighPass(3), 100.0, 48_000, 0.0, 0.0).unwrap();
+        assert_eq!(design.filter.get_num_sections(), 2);
+        assert!(max_chain_pole_radius(& design.filter) < 1.0);
+        assert!(policy.make(FilterKind::BesselLowPass(4), 30_000.0, 48_000, 0.0, 0.0).is_err());
+        assert!(policy.make(FilterKind::LinkwitzRileyHighPass(3), 1_000.0, 48_000, 0.0, 0.0).is_err());
+        assert!(FilterKind::Peak.with_order(4).is_err());
+        assert_eq!(FilterKind::BesselHighPass(4).with_order(6), Ok(FilterKind::BesselHighPass(6)));
+        assert!(FilterKind::BesselLowPass(2).make_with_report(1_000.0, 48_000, 0.0, 0.0).is_err());
+        // At 2 Hz the poles of the high order sections are too near the unit circle.
+        assert!(policy.make(FilterKind::ButterworthLowPass(8), 2.0, 48_000, 0.0, 0.0).is_err());
+
+        let transform = FilterKind::LinkwitzTransform { target_frequency: 30.0, target_q: 0.707 };
+        let design = policy.make(transform, 60.0, 48_000, 0.0, 0.9).unwrap();
+        assert_eq!(design.into_biquad().unwrap().a_coeffs,
+                   make_linkwitz_transform(60.0, 0.9, 30.0, 0.707, 48_000).a_coeffs);
+        let transform = FilterKind::LinkwitzTransform { target_frequency: 0.0, target_q: 0.707 };
+        assert!(policy.make(transform, 60.0, 48_000, 0.0, 0.9).is_err());
+        assert!(transform.make(60.0, 48_000, 0.0, 0.9).is_err());
+        let transform = FilterKind::LinkwitzTransform { target_frequency: 30_000.0, target_q: 0.707 };
+        let design = DesignPolicy::new(PolicyAction::Clamp).make(transform, 60.0, 48_000, 0.0, 0.9).unwrap();
+        assert_eq!(design.kind, FilterKind::LinkwitzTransform { target_frequency: 0.98 * 24_000.0, target_q: 0.707 });
+        assert_eq!(design.warnings.len(), 1);
+    }
+}
@@ -0,0 +1,178 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Verbose design of the biquads, the intermediate values of the
+//!              make_*_with_report functions of butterworth_filter.rs .
+//!              The coefficients of a biquad are the end of a short chain of
+//!              formulas, and when a filter doesn't do what is expected, or to learn
+//!              how the cookbook works, the values in the middle of the chain say
+//!              more than the coefficients. For the cookbook designs they are
+//!
+//!                 w0    = 2 pi f / Fs
+//!                 alpha = sin(w0) / (2 Q)       (the notch uses the bandwidth form)
+//!                 A     = 10^(gain / 40)        (peak and shelves)
+//!
+//!              and for the constant Q peak K = tan(pi f / Fs) and V0 = 10^(|gain| / 20).
+//!              The bilinear transform warps the frequency axis, the report also has
+//!              the frequency of the analog prototype that lands on f after the
+//!              transform, the pre-warped frequency
+//!
+//!                 fa = Fs / pi * tan(pi f / Fs)
+//!
+//!              and the poles and the zeros of the designed filter.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. WebAudio - Cookbook formulae for audio equalizer biquad filter coefficients
+//!       https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html
+//!
+
+
+use std::fmt;
+use std::f64::consts::PI;
+use std::f64::consts::TAU;
+use rustfft::num_complex::Complex;
+
+use crate::iir_filter::IIRFilter;
+use crate::exact_math;
+use crate::biquad_calculator::quadratic_roots;
+
+
+/// The intermediate values of the design of a biquad.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesignReport {
+    pub filter_type:         & 'static str,
+    pub frequency:           f64,
+    pub sample_rate:         u32,
+    /// None for the types without a gain.
+    pub gain_db:             Option<f64>,
+    pub q_factor:            f64,
+    /// The named values of the formulas, in the order they are computed.
+    pub values:              Vec<(& 'static str, f64)>,
+    /// Frequency of the analog prototype, pre-warped for the bilinear transform.
+    pub prewarped_frequency: f64,
+    /// {a0, a1, a2} and {b0, b1, b2}, as set in the filter, not normalized.
+    pub a_coeffs:            Vec<f64>,
+    pub b_coeffs:            Vec<f64>,
+    pub zeros:               Vec<Complex<f64>>,
+    pub poles:               Vec<Complex<f64>>,
+}
+
+impl DesignReport {
+    /// The report of a designed filter, with the values of its formulas.
+    pub fn new(filter_type: & 'static str, frequency: f64, sample_rate: u32, gain_db: Option<f64>, q_factor: f64,
+               values: Vec<(& 'static str, f64)>, filter: & IIRFilter) -> Self {
+        let (a, b) = (& filter.a_coeffs, & filter.b_coeffs);
+        DesignReport {
+            filter_type,
+            frequency,
+            sample_rate,
+            gain_db,
+            q_factor,
+            values,
+            prewarped_frequency: sample_rate as f64 / PI * exact_math::tan(PI * frequency / sample_rate as f64),
+            a_coeffs: a.clone(),
+            b_coeffs: b.clone(),
+            zeros: quadratic_roots(b[0], b[1], b[2]),
+            poles: quadratic_roots(a[0], a[1], a[2]),
+        }
+    }
+
+    /// The intermediate value with the name, like "w0" or "alpha".
+    pub fn get_value(& self, name: & str) -> Option<f64> {
+        self.values.iter().find(|(n, _)| *n == name).map(|(_, value)| *value)
+    }
+
+    /// The report for the terminal.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let (_, report) = make_peak_with_report(1_000.0, 48_000, 6.0, Some(2.0));
+    ///    >>> print!("{}", report.format_text());
+    ///    peak 1000 Hz, gain 6 dB, Q 2, at 48000 Hz
+    ///
+    ///    Design:
+    ///        w0                  = 0.13089969389957470
+    ///        sin(w0)             = 0.13052619222005157
+    ///        ...
+    ///    ```
+    ///
+    pub fn format_text(& self) -> String {
+        let mut text = format!("{} {} Hz", self.filter_type, self.frequency);
+        if let Some(gain_db) = self.gain_db {
+            text += & format!(", gain {} dB", gain_db);
+        }
+        text += & format!(", Q {}, at {} Hz\n\nDesign:\n", self.q_factor, self.sample_rate);
+        for (name, value) in & self.values {
+            text += & format!("    {:<19} = {:.17}\n", name, value);
+        }
+        text += & format!("    {:<19} = {:.6} Hz\n", "pre-warped f", self.prewarped_frequency);
+        text += "\nCoefficients, not normalized:\n";
+        for (prefix, coeffs) in [("a", & self.a_coeffs), ("b", & self.b_coeffs)] {
+            for (index, value) in coeffs.iter().enumerate() {
+                text += & format!("    {}{} = {:.17}\n", prefix, index, value);
+            }
+        }
+        for (title, roots) in [("Zeros", & self.zeros), ("Poles", & self.poles)] {
+            text += & format!("\n{}:\n", title);
+            for root in roots {
+                text += & format!("    {:+.9} {:+.9}j    radius {:.9}, {:.2} Hz\n", root.re, root.im, root.norm(),
+                                  root.arg().abs() / TAU * self.sample_rate as f64);
+            }
+        }
+        text
+    }
+}
+
+impl fmt::Display for DesignReport {
+    fn fmt(& self, f: & mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(& self.format_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::*;
+
+    #[test]
+    fn test_reports_match_the_designs() {
+        let (filter, report) = make_lowpass_with_report(1_000.0, 48_000, None);
+        let reference = make_lowpass(1_000.0, 48_000, None);
+        assert_eq!((& filter.a_coeffs, & filter.b_coeffs), (& reference.a_coeffs, & reference.b_coeffs));
+        assert_eq!(report.a_coeffs, reference.a_coeffs);
+        assert_eq!(report.get_value("w0"), Some(TAU * 1_000.0 / 48_000.0));
+        let alpha = f64::sin(TAU / 48.0) * f64::sqrt(2.0) / 2.0;
+        assert!((report.get_value("alpha").unwrap() - alpha).abs() < 1e-15);
+        assert_eq!(report.gain_db, None);
+        // The pre-warped frequency is above f, by less than 0.2 % at 1 kHz.
+        assert!(report.prewarped_frequency > 1_000.0 && report.prewarped_frequency < 1_002.0);
+        // The poles of the Butterworth low-pass are at 45 degrees in the s plane, at a
+        // low frequency their angle in the z plane is near w0 / sqrt(2).
+        assert_eq!(report.poles.len(), 2);
+        assert!((report.poles[0].arg().abs() / (TAU / 48.0 / f64::sqrt(2.0)) - 1.0).abs() < 0.01);
+
+        let (filter, report) = make_peak_eq_constant_q_with_report(2_000.0, 48_000, -6.0, Some(1.5));
+        assert_eq!(filter.a_coeffs, make_peak_eq_constant_q(2_000.0, 48_000, -6.0, Some(1.5)).a_coeffs);
+        assert_eq!(report.get_value("K"), Some(exact_math::tan(PI * 2_000.0 / 48_000.0)));
+        assert!((report.get_value("V0").unwrap() - f64::powf(10.0, 6.0 / 20.0)).abs() < 1e-12);
+        assert!((report.prewarped_frequency - report.get_value("K").unwrap() * 48_000.0 / PI).abs() < 1e-9);
+        assert_eq!(report.get_value("alpha"), None);
+    }
+
+    #[test]
+    fn test_format_text() {
+        let (_, report) = make_lowshelf_with_report(200.0, 48_000, -3.0, Some(0.5));
+        assert_eq!(report.get_value("A"), Some(exact_math::powf(10.0, -3.0 / 40.0)));
+        let text = report.format_text();
+        assert!(text.starts_with("lowshelf 200 Hz, gain -3 dB, Q 0.5, at 48000 Hz\n\nDesign:\n    w0 "));
+        assert!(text.contains("    pre-warped f        = 200.0"));
+        assert!(text.contains("    a0 = ") && text.contains("    b2 = ") && text.contains("Poles:"));
+        // The zeros of a shelf are inside the unit circle, like the poles.
+        assert!(report.zeros.iter().chain(& report.poles).all(|root| root.norm() < 1.0));
+    }
+}
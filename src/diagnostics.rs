@@ -0,0 +1,242 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Clipping and DC offset diagnostics of audio files.
+//!              Before a batch of files is processed, the report of each file says
+//!              what is wrong with it: the peak, RMS and integrated loudness, the DC
+//!              offset of each channel, the number of clipped samples and where the
+//!              inter-sample overs are, the places where the true peak goes above
+//!              full scale while the samples don't.
+//!
+//!              From the report come the suggested corrections: a DC blocker, a
+//!              first order high-pass at a few Hz, when there is a DC offset, and a
+//!              true peak limiter when the true peak is above -1 dBTP.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. ITU-R BS.1770-4 - Algorithms to measure audio programme loudness and
+//!       true-peak audio level
+//!       https://www.itu.int/rec/R-REC-BS.1770
+//!
+//!    2. DC bias - Wikipedia
+//!       https://en.wikipedia.org/wiki/DC_bias
+//!
+
+
+use std::f64::consts::TAU;
+
+use crate::iir_filter::IIRFilter;
+use crate::exact_math;
+use crate::audio_buffer::AudioBuffer;
+use crate::audio_io::SampleFormat;
+use crate::audio_io::read_wav;
+use crate::analysis::peak;
+use crate::analysis::rms;
+use crate::analysis::amplitude_to_db;
+use crate::loudness::integrated_loudness;
+use crate::loudness::oversample_for_true_peak;
+use crate::loudness::TRUE_PEAK_OVERSAMPLING;
+
+
+/// A DC offset above this level, in dBFS, is reported for correction.
+pub const DC_OFFSET_THRESHOLD_DB: f64 = -60.0;
+/// A true peak above this level, in dBTP, needs a limiter.
+pub const TRUE_PEAK_CEILING_DB: f64 = -1.0;
+const DC_BLOCKER_CUTOFF_HZ: f64 = 5.0;
+
+/// Diagnostics of one channel.
+#[derive(Debug, Clone)]
+pub struct ChannelDiagnostics {
+    pub peak_db:           f64,
+    pub rms_db:            f64,
+    pub true_peak_db:      f64,
+    /// Mean of the samples, linear.
+    pub dc_offset:         f64,
+    /// Samples at or above the full scale of the sample format.
+    pub clipped_samples:   usize,
+    /// Index of the sample before each inter-sample over.
+    pub inter_sample_overs: Vec<usize>,
+}
+
+/// A correction suggested by the diagnostics.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Correction {
+    /// First order high-pass, see make_dc_blocker().
+    DcBlocker { cutoff_hz: f64 },
+    /// True peak limiter with oversampled detection.
+    Limiter { ceiling_db: f64, lookahead_ms: f64, release_ms: f64 },
+}
+
+impl Correction {
+    pub fn describe(& self) -> String {
+        match self {
+            Correction::DcBlocker { cutoff_hz } =>
+                format!("DC blocker, first order high-pass at {} Hz", cutoff_hz),
+            Correction::Limiter { ceiling_db, lookahead_ms, release_ms } =>
+                format!("true peak limiter, ceiling {} dBTP, lookahead {} ms, release {} ms",
+                        ceiling_db, lookahead_ms, release_ms),
+        }
+    }
+}
+
+/// The diagnostics report of an audio file.
+#[derive(Debug, Clone)]
+pub struct DiagnosticsReport {
+    pub sample_rate:       u32,
+    pub num_frames:        usize,
+    pub loudness_lufs:     f64,
+    pub channels:          Vec<ChannelDiagnostics>,
+    pub corrections:       Vec<Correction>,
+}
+
+impl DiagnosticsReport {
+    pub fn get_clipped_samples(& self) -> usize {
+        self.channels.iter().map(|c| c.clipped_samples).sum()
+    }
+
+    pub fn get_inter_sample_overs(& self) -> usize {
+        self.channels.iter().map(|c| c.inter_sample_overs.len()).sum()
+    }
+
+    /// Highest true peak of all the channels, in dBTP.
+    pub fn get_true_peak_db(& self) -> f64 {
+        self.channels.iter().map(|c| c.true_peak_db).fold(f64::NEG_INFINITY, f64::max)
+    }
+}
+
+/// Largest value of the sample format, a clipped sample is at or above it.
+pub fn full_scale(sample_format: SampleFormat) -> f64 {
+    if sample_format.is_float() {
+        1.0
+    } else {
+        let steps = (1_u64 << (sample_format.get_bits_per_sample() - 1)) as f64;
+        (steps - 1.0) / steps
+    }
+}
+
+/// First order DC blocker, y[n] = g (x[n] - x[n-1]) + R y[n-1], with the -3 dB
+/// point at the cutoff frequency.
+pub fn make_dc_blocker(cutoff_hz: f64, sample_rate: u32) -> IIRFilter {
+    assert!(cutoff_hz > 0.0 && cutoff_hz < sample_rate as f64 / 2.0);
+    let r = exact_math::exp(-TAU * cutoff_hz / sample_rate as f64);
+    // Unity gain at Nyquist.
+    let gain = (1.0 + r) / 2.0;
+    let mut filter = IIRFilter::new(1);
+    filter.set_coefficients(& [1.0, -r], & [gain, -gain]).unwrap();
+    filter
+}
+
+fn analyze_channel(channel: & [f64], full_scale: f64) -> ChannelDiagnostics {
+    let oversampled = oversample_for_true_peak(channel);
+    let inter_sample_overs = (0..channel.len().saturating_sub(1))
+        .filter(|n| {
+            channel[*n].abs() < full_scale && channel[n + 1].abs() < full_scale
+                && oversampled[n * TRUE_PEAK_OVERSAMPLING + 1..(n + 1) * TRUE_PEAK_OVERSAMPLING]
+                    .iter().any(|x| x.abs() > 1.0)
+        })
+        .collect();
+    ChannelDiagnostics {
+        peak_db: amplitude_to_db(peak(channel)),
+        rms_db: amplitude_to_db(rms(channel)),
+        true_peak_db: amplitude_to_db(peak(& oversampled)),
+        dc_offset: if channel.is_empty() { 0.0 } else { channel.iter().sum::<f64>() / channel.len() as f64 },
+        clipped_samples: channel.iter().filter(|x| x.abs() >= full_scale).count(),
+        inter_sample_overs,
+    }
+}
+
+/// Diagnostics of the audio, the sample format gives the full scale level.
+pub fn analyze_audio(audio: & AudioBuffer, sample_format: SampleFormat) -> DiagnosticsReport {
+    let _span = trace_span!("analyze_audio", frames = audio.get_num_frames());
+    let full_scale = full_scale(sample_format);
+    let channels: Vec<ChannelDiagnostics> = audio.channels.iter()
+        .map(|channel| analyze_channel(channel, full_scale))
+        .collect();
+
+    let mut corrections = Vec::new();
+    if channels.iter().any(|c| amplitude_to_db(c.dc_offset.abs()) > DC_OFFSET_THRESHOLD_DB) {
+        corrections.push(Correction::DcBlocker { cutoff_hz: DC_BLOCKER_CUTOFF_HZ });
+    }
+    if channels.iter().any(|c| c.true_peak_db > TRUE_PEAK_CEILING_DB) {
+        corrections.push(Correction::Limiter { ceiling_db: TRUE_PEAK_CEILING_DB, lookahead_ms: 1.5, release_ms: 50.0 });
+    }
+
+    DiagnosticsReport {
+        sample_rate: audio.sample_rate,
+        num_frames: audio.get_num_frames(),
+        loudness_lufs: integrated_loudness(audio),
+        channels,
+        corrections,
+    }
+}
+
+/// Reads a WAV file and returns its diagnostics report.
+///
+/// In Rust:
+///    ```text
+///    >>> let report = analyze_file("take_3.wav")?;
+///    >>> for correction in & report.corrections {
+///    >>>     println!("{}", correction.describe());
+///    >>> }
+///    ```
+///
+pub fn analyze_file(path: & str) -> Result<DiagnosticsReport, String> {
+    let (audio, sample_format) = read_wav(path)?;
+    Ok(analyze_audio(& audio, sample_format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iir_filter::ProcessingBlock; // Trait
+
+    #[test]
+    fn test_clean_file_needs_no_corrections() {
+        let sample_rate = 48_000;
+        let sine: Vec<f64> = (0..48_000).map(|n| 0.5 * f64::sin(TAU * 440.0 * n as f64 / sample_rate as f64)).collect();
+        let report = analyze_audio(& AudioBuffer::new(sample_rate, vec![sine]), SampleFormat::Int16);
+        assert!(report.corrections.is_empty());
+        assert_eq!(report.get_clipped_samples(), 0);
+        assert_eq!(report.get_inter_sample_overs(), 0);
+        assert!((report.channels[0].peak_db + 6.02).abs() < 0.01);
+        assert!((report.channels[0].rms_db + 9.03).abs() < 0.01);
+        assert!(report.channels[0].dc_offset.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dc_offset_clipping_and_inter_sample_overs() {
+        let sample_rate = 48_000;
+        // A sine at fs / 4 at 45 degrees, its samples are at 0.95 but its peak is at 1.34 .
+        let overs: Vec<f64> = (0..4_800).map(|n| 0.95 * f64::sqrt(2.0) * f64::sin(TAU * n as f64 / 4.0 + TAU / 8.0)).collect();
+        // Clipped sine with a DC offset.
+        let clipped: Vec<f64> = (0..4_800).map(|n| 0.05 + (1.5 * f64::sin(TAU * 100.0 * n as f64 / sample_rate as f64)).clamp(-0.95, 0.95)).collect();
+        let report = analyze_audio(& AudioBuffer::new(sample_rate, vec![overs, clipped]), SampleFormat::Float32);
+
+        assert_eq!(report.channels[0].clipped_samples, 0);
+        // One peak or trough between every other pair of samples.
+        assert_eq!(report.channels[0].inter_sample_overs.len(), 2_400);
+        assert!(report.channels[0].true_peak_db > 2.0);
+        assert!(report.channels[1].clipped_samples > 1_000);
+        assert!((report.channels[1].dc_offset - 0.05).abs() < 1e-9);
+        assert_eq!(report.corrections[0], Correction::DcBlocker { cutoff_hz: DC_BLOCKER_CUTOFF_HZ });
+        assert!(matches!(report.corrections[1], Correction::Limiter { .. }));
+    }
+
+    #[test]
+    fn test_dc_blocker() {
+        let sample_rate = 48_000;
+        let filter = make_dc_blocker(5.0, sample_rate);
+        assert!(filter.frequency_response(0.0, sample_rate).norm() < 1e-12);
+        assert!((filter.frequency_response(5.0, sample_rate).norm() - f64::sqrt(0.5)).abs() < 0.01);
+        assert!((filter.frequency_response(1_000.0, sample_rate).norm() - 1.0).abs() < 0.001);
+
+        let mut filter = make_dc_blocker(5.0, sample_rate);
+        let output: Vec<f64> = (0..48_000).map(|_| filter.process(0.1)).collect();
+        assert!(output[47_999].abs() < 1e-6);
+        assert_eq!(full_scale(SampleFormat::Int16), 32_767.0 / 32_768.0);
+    }
+}
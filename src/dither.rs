@@ -0,0 +1,288 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Dither for the requantization to a lower bit depth.
+//!              Rounding a signal to 16 bits makes an error that follows the
+//!              signal, a distortion that is heard on the fades and the quiet parts.
+//!              Adding a triangular (TPDF) noise of +/- 1 LSB before the rounding, the
+//!              sum of two uniform noises, makes the error a constant white noise,
+//!              independent of the signal.
+//!
+//!              Noise shaping moves that noise to where the ear is less sensitive.
+//!              The error of each sample, of the dither and the rounding, is fed
+//!              back to the next samples through a filter, so the noise at the
+//!              output is the error through the noise transfer function, the NTF:
+//!
+//!                 y = x + NTF(z) e,   NTF(z) = 1 + h_1 z^-1 + h_2 z^-2 + ...
+//!
+//!              The NTF is monic and minimum phase, and the average of its gain in
+//!              dB over the band is 0 dB, the noise is lower in a band only by being
+//!              higher in another, and the total noise power is higher. The shaping
+//!              is selected with NoiseShaping:
+//!
+//!                 flat          TPDF dither alone, white noise.
+//!                 e_weighted    The 9 coefficients of the E-weighted filter of
+//!                               Lipshitz, Vanderkooy and Wannamaker, for 44.1 kHz
+//!                               and 48 kHz, the noise is 25 dB lower around 3 kHz,
+//!                               where the ear is the most sensitive, and higher
+//!                               above 15 kHz.
+//!                 shibata_like  The inverse of the threshold of hearing, like the
+//!                               curves of Shibata, designed at the sample rate with
+//!                               the peak and high-shelf biquads of the crate: cuts
+//!                               at 3.5 kHz and 12 kHz, and a high-shelf of +30 dB
+//!                               at 16 kHz, for 44.1 kHz and above.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Dither - Wikipedia
+//!       https://en.wikipedia.org/wiki/Dither
+//!
+//!    2. Lipshitz, S. P., Vanderkooy, J., Wannamaker, R. A. - Minimally Audible
+//!       Noise Shaping. J. Audio Eng. Soc., Vol. 39, No. 11, 1991.
+//!
+//!    3. Noise shaping - Wikipedia
+//!       https://en.wikipedia.org/wiki/Noise_shaping
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::iir_filter::IIRFilter;
+use crate::iir_filter::FilterChain;
+use crate::butterworth_filter::make_peak;
+use crate::butterworth_filter::make_highshelf;
+use crate::generators::WhiteNoise;
+
+
+/// Names of the noise shapings, of NoiseShaping::from_name().
+pub const NOISE_SHAPING_NAMES: [&str; 3] = ["flat", "e_weighted", "shibata_like"];
+
+// The E-weighted error filter of Lipshitz et al., NTF(z) = 1 - sum c_k z^-k .
+const E_WEIGHTED_COEFFICIENTS: [f64; 9] = [2.412, -3.370, 3.937, -4.174, 3.353, -2.205, 1.281, -0.569, 0.0847];
+// The sample rates of the E-weighted coefficients, designed at 44.1 kHz.
+const E_WEIGHTED_SAMPLE_RATES: [u32; 2] = [44_100, 48_000];
+const SHIBATA_MIN_SAMPLE_RATE: u32 = 44_100;
+// The error of the dither and the rounding is within 1.5 steps, it is limited to
+// it when the output clips, so the feedback stays bounded.
+const MAX_SHAPED_ERROR: f64 = 1.5;
+
+/// The spectrum of the requantization noise, see the description of the module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoiseShaping {
+    Flat,
+    EWeighted,
+    ShibataLike,
+}
+
+impl NoiseShaping {
+    /// The shaping of a name of NOISE_SHAPING_NAMES.
+    pub fn from_name(name: & str) -> Result<Self, String> {
+        match name {
+            "flat"         => Ok(NoiseShaping::Flat),
+            "e_weighted"   => Ok(NoiseShaping::EWeighted),
+            "shibata_like" => Ok(NoiseShaping::ShibataLike),
+            _ => Err(format!("Error: unknown noise shaping \"{}\", the noise shapings are {}", name,
+                             NOISE_SHAPING_NAMES.join(", "))),
+        }
+    }
+
+    pub fn get_name(& self) -> & 'static str {
+        match self {
+            NoiseShaping::Flat        => NOISE_SHAPING_NAMES[0],
+            NoiseShaping::EWeighted   => NOISE_SHAPING_NAMES[1],
+            NoiseShaping::ShibataLike => NOISE_SHAPING_NAMES[2],
+        }
+    }
+
+    /// The noise transfer function at the sample rate, monic, b_0 = a_0 . Its
+    /// frequency_response() is the spectrum of the noise relative to the flat
+    /// TPDF dither.
+    pub fn make_noise_transfer_function(& self, sample_rate: u32) -> Result<FilterChain, String> {
+        let sections = match self {
+            // The default coefficients of IIRFilter, 1 / 1 .
+            NoiseShaping::Flat => vec![IIRFilter::new(1)],
+            NoiseShaping::EWeighted => {
+                if !E_WEIGHTED_SAMPLE_RATES.contains(& sample_rate) {
+                    return Err(format!("Error: the e_weighted noise shaping is for the sample rates {:?}, got {} Hz",
+                                       E_WEIGHTED_SAMPLE_RATES, sample_rate));
+                }
+                let mut b_coeffs = vec![1.0];
+                b_coeffs.extend(E_WEIGHTED_COEFFICIENTS.iter().map(|c| -c));
+                let mut a_coeffs = vec![0.0; b_coeffs.len()];
+                a_coeffs[0] = 1.0;
+                let mut filter = IIRFilter::new(E_WEIGHTED_COEFFICIENTS.len());
+                filter.set_coefficients(& a_coeffs, & b_coeffs)?;
+                vec![filter]
+            },
+            NoiseShaping::ShibataLike => {
+                if sample_rate < SHIBATA_MIN_SAMPLE_RATE {
+                    return Err(format!("Error: the shibata_like noise shaping needs a sample rate of at least {} Hz, \
+                                        got {} Hz", SHIBATA_MIN_SAMPLE_RATE, sample_rate));
+                }
+                vec![make_peak(3_500.0, sample_rate, -24.0, Some(0.6)),
+                     make_peak(12_000.0, sample_rate, -12.0, Some(1.0)),
+                     make_highshelf(16_000.0, sample_rate, 30.0, Some(0.8))]
+            },
+        };
+        // Monic, the first coefficient of the numerator the same as the one of
+        // the denominator.
+        let sections = sections.into_iter().map(|mut filter| {
+            let scale = filter.a_coeffs[0] / filter.b_coeffs[0];
+            filter.b_coeffs.iter_mut().for_each(|b| *b *= scale);
+            filter
+        }).collect();
+        Ok(FilterChain::new(sections))
+    }
+}
+
+/// TPDF dither and rounding to the bit depth, the output is on the grid of the
+/// integer samples, in [-1, 1[ .
+pub struct TpdfDither {
+    // Number of steps of the positive half of the range.
+    levels:       f64,
+    random:       WhiteNoise,
+    shaping:      NoiseShaping,
+    // The filter of the feedback of the errors, (NTF(z) - 1) z, None for flat.
+    error_filter: Option<IIRFilter>,
+    // The feedback of the past errors to the next sample, in steps.
+    feedback:     f64,
+}
+
+impl TpdfDither {
+    /// The seed makes the noise repeatable.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let mut dither = TpdfDither::new(16, 1);
+    ///    >>> let output: Vec<f64> = mastered.iter().map(|x| dither.process(*x)).collect();
+    ///    ```
+    ///
+    pub fn new(bits: u32, seed: u32) -> Self {
+        assert!((2..=32).contains(& bits));
+        TpdfDither { levels: f64::powi(2.0, bits as i32 - 1), random: WhiteNoise::new(seed),
+                     shaping: NoiseShaping::Flat, error_filter: None, feedback: 0.0 }
+    }
+
+    /// TPDF dither with the noise shaping, designed at the sample rate.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let mut dither = TpdfDither::with_noise_shaping(16, 1, NoiseShaping::EWeighted, 44_100)?;
+    ///    ```
+    ///
+    pub fn with_noise_shaping(bits: u32, seed: u32, shaping: NoiseShaping, sample_rate: u32) -> Result<Self, String> {
+        let mut dither = TpdfDither::new(bits, seed);
+        dither.shaping = shaping;
+        if shaping != NoiseShaping::Flat {
+            // The NTF without its first term and one sample earlier, it takes the
+            // error of a sample and gives the feedback of the next one.
+            let (b, a) = shaping.make_noise_transfer_function(sample_rate)?.to_transfer_function();
+            let order = a.len() - 1;
+            let mut b_coeffs: Vec<f64> = (1..=order).map(|k| b[k] - a[k]).collect();
+            b_coeffs.push(0.0);
+            let mut error_filter = IIRFilter::new(order);
+            error_filter.set_coefficients(& a, & b_coeffs)?;
+            dither.error_filter = Some(error_filter);
+        }
+        Ok(dither)
+    }
+
+    pub fn get_bits(& self) -> u32 {
+        self.levels.log2() as u32 + 1
+    }
+
+    pub fn get_noise_shaping(& self) -> NoiseShaping {
+        self.shaping
+    }
+
+    // Uniform in [0, 1[ .
+    fn random(& mut self) -> f64 {
+        self.random.next_uniform()
+    }
+}
+
+impl ProcessingBlock for TpdfDither {
+    fn process(& mut self, sample: f64) -> f64 {
+        let noise = self.random() - self.random();
+        let target = sample * self.levels + self.feedback;
+        let quantized = (target + noise).round().clamp(-self.levels, self.levels - 1.0);
+        if let Some(error_filter) = self.error_filter.as_mut() {
+            let error = (quantized - target).clamp(-MAX_SHAPED_ERROR, MAX_SHAPED_ERROR);
+            self.feedback = error_filter.process(error);
+        }
+        quantized / self.levels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::welch::welch_psd;
+    use crate::welch::WelchConfig;
+    use crate::welch::WelchPsd;
+
+    #[test]
+    fn test_dither_decorrelates_the_error() {
+        let mut dither = TpdfDither::new(8, 7);
+        assert_eq!(dither.get_bits(), 8);
+        // A constant at a quarter of a step, rounded it would always be 0.
+        let input = 0.25 / 128.0;
+        let output: Vec<f64> = (0..100_000).map(|_| dither.process(input)).collect();
+        assert!(output.iter().all(|y| (y * 128.0).fract() == 0.0));
+        let mean = output.iter().sum::<f64>() / output.len() as f64;
+        assert!((mean - input).abs() < 0.01 / 128.0);
+        // The error power of TPDF dither is 1 / 4 of a step squared.
+        let error_power = output.iter().map(|y| (y - input) * (y - input)).sum::<f64>() / output.len() as f64;
+        assert!((error_power * 128.0 * 128.0 - 0.25).abs() < 0.02);
+        assert_eq!(dither.process(1.5), 127.0 / 128.0);
+    }
+
+    #[test]
+    fn test_noise_shaping_spectra() {
+        let sample_rate = 44_100;
+        let input: Vec<f64> = (0..1 << 18).map(|n| 0.001 * f64::sin(n as f64 * 0.1)).collect();
+        let error_psd = |shaping: NoiseShaping| {
+            let mut dither = TpdfDither::with_noise_shaping(16, 5, shaping, sample_rate).unwrap();
+            assert_eq!(dither.get_noise_shaping(), shaping);
+            let error: Vec<f64> = input.iter().map(|x| dither.process(*x) - x).collect();
+            welch_psd(& error, & WelchConfig::new(sample_rate, 1_024)).unwrap()
+        };
+        let band_db = |psd: & WelchPsd, frequency: f64| 10.0 * f64::log10(psd.get_band_power(frequency - 500.0,
+                                                                                           frequency + 500.0));
+        let flat = error_psd(NoiseShaping::Flat);
+        // The flat TPDF noise is white.
+        assert!((band_db(& flat, 3_000.0) - band_db(& flat, 18_000.0)).abs() < 0.5);
+        for shaping in [NoiseShaping::EWeighted, NoiseShaping::ShibataLike] {
+            let psd = error_psd(shaping);
+            let ntf = shaping.make_noise_transfer_function(sample_rate).unwrap();
+            // The measured noise is the flat noise through the NTF, from the
+            // 25 dB lower around 3 kHz to the 20 dB higher above 18 kHz.
+            for frequency in [1_000.0, 3_500.0, 6_000.0, 12_000.0, 18_000.0, 20_000.0] {
+                let expected_db = 20.0 * f64::log10(ntf.frequency_response(frequency, sample_rate).norm());
+                assert!((band_db(& psd, frequency) - band_db(& flat, frequency) - expected_db).abs() < 1.5);
+            }
+            assert!(band_db(& psd, 3_500.0) < band_db(& flat, 3_500.0) - 20.0);
+            assert!(band_db(& psd, 20_000.0) > band_db(& flat, 20_000.0) + 20.0);
+        }
+
+        // The average of the NTF in dB over the band is 0 dB.
+        for (shaping, name) in [NoiseShaping::Flat, NoiseShaping::EWeighted, NoiseShaping::ShibataLike].iter()
+                .zip(NOISE_SHAPING_NAMES) {
+            assert_eq!((NoiseShaping::from_name(name).unwrap(), shaping.get_name()), (*shaping, name));
+            let ntf = shaping.make_noise_transfer_function(48_000).unwrap();
+            let mean_db = (0..1_000).map(|i| 20.0 * f64::log10(ntf.frequency_response((i as f64 + 0.5) * 24.0, 48_000)
+                .norm())).sum::<f64>() / 1_000.0;
+            assert!(mean_db.abs() < 0.01);
+        }
+        assert!(NoiseShaping::from_name("triangular").is_err());
+        assert!(TpdfDither::with_noise_shaping(16, 1, NoiseShaping::EWeighted, 96_000).is_err());
+        assert!(TpdfDither::with_noise_shaping(16, 1, NoiseShaping::ShibataLike, 32_000).is_err());
+        // Full scale clips without a runaway of the feedback.
+        let mut dither = TpdfDither::with_noise_shaping(16, 1, NoiseShaping::ShibataLike, 96_000).unwrap();
+        let output: Vec<f64> = (0..10_000).map(|n| 1.5 * f64::sin(n as f64 * 0.01)).map(|x| dither.process(x)).collect();
+        assert!(output[5_000..].iter().zip(5_000..).all(|(y, n)| (y - f64::sin(n as f64 * 0.01).clamp(-1.0, 1.0)).abs() < 0.6));
+    }
+}
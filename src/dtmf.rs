@@ -0,0 +1,391 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: DTMF and call-progress tones of the telephone network, generation
+//!              and detection.
+//!              A DTMF (Dual Tone Multi Frequency) key is the sum of a sine of the
+//!              row and a sine of the column of the keypad:
+//!
+//!                           1209 Hz  1336 Hz  1477 Hz  1633 Hz
+//!                 697 Hz       1        2        3        A
+//!                 770 Hz       4        5        6        B
+//!                 852 Hz       7        8        9        C
+//!                 941 Hz       *        0        #        D
+//!
+//!              The decoder measures the 8 frequencies with the Goertzel algorithm
+//!              of analysis.rs, in blocks of about 25 ms, 205 samples at 8 kHz, the
+//!              classic size that separates the closest frequencies. A block has a
+//!              key when the strongest row and column are above the minimum level,
+//!              each one is 6 dB above the other frequencies of its group, the
+//!              difference of their levels (the twist) is at most 8 dB, and the two
+//!              sines are most of the power of the block, so that speech and music
+//!              don't make keys. A key is accepted after 2 blocks with it, so a tone
+//!              of at least 3 blocks, 77 ms, is always accepted, and a key is repeated
+//!              only after a block without it.
+//!
+//!              The call-progress tones are the North American ones:
+//!
+//!                 dial tone   350 Hz + 440 Hz   continuous
+//!                 ringback    440 Hz + 480 Hz   2 s on, 4 s off
+//!                 busy        480 Hz + 620 Hz   0.5 s on, 0.5 s off
+//!                 reorder     480 Hz + 620 Hz   0.25 s on, 0.25 s off
+//!
+//!              They are detected with the same method in blocks of 50 ms, and
+//!              busy and reorder are told apart by the duration of their cadence.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. ITU-T Q.23 - Technical features of push-button telephone sets
+//!       https://www.itu.int/rec/T-REC-Q.23
+//!
+//!    2. Dual-tone multi-frequency signaling - Wikipedia
+//!       https://en.wikipedia.org/wiki/Dual-tone_multi-frequency_signaling
+//!
+//!    3. Precise tone plan - Wikipedia
+//!       https://en.wikipedia.org/wiki/Precise_tone_plan
+//!
+
+
+use std::f64::consts::TAU;
+
+use crate::analysis::goertzel_amplitude;
+
+
+/// Frequencies of the rows and of the columns of the keypad, in Hz.
+pub const DTMF_ROW_FREQUENCIES: [f64; 4] = [697.0, 770.0, 852.0, 941.0];
+pub const DTMF_COLUMN_FREQUENCIES: [f64; 4] = [1_209.0, 1_336.0, 1_477.0, 1_633.0];
+/// The keys of the keypad, by row and column.
+pub const DTMF_KEYS: [[char; 4]; 4] = [
+    ['1', '2', '3', 'A'],
+    ['4', '5', '6', 'B'],
+    ['7', '8', '9', 'C'],
+    ['*', '0', '#', 'D'],
+];
+
+/// Duration of a key and of the silence after it, in ms.
+pub const DEFAULT_TONE_MS: f64 = 100.0;
+pub const DEFAULT_GAP_MS: f64 = 100.0;
+/// Peak level of each of the two sines of a tone, in dBFS.
+pub const DEFAULT_TONE_LEVEL_DBFS: f64 = -10.0;
+/// Level under which a sine of a tone isn't detected, in dBFS.
+pub const DEFAULT_MIN_LEVEL_DBFS: f64 = -40.0;
+
+// Duration of a DTMF detection block, 205 samples at 8 kHz.
+const DTMF_BLOCK_SECONDS: f64 = 205.0 / 8_000.0;
+// Duration of a call-progress detection block, a resolution of 20 Hz.
+const CALL_PROGRESS_BLOCK_SECONDS: f64 = 0.05;
+// Amplitude ratio of the strongest frequency of a group to the others, 6 dB.
+const MIN_GROUP_RATIO: f64 = 2.0;
+// Largest twist, 8 dB.
+const MAX_TWIST_RATIO: f64 = 2.511_886_431_509_58;
+// Fraction of the power of the block in the two sines.
+const MIN_TONE_POWER_FRACTION: f64 = 0.8;
+// Blocks with the same key before it's accepted.
+const MIN_KEY_BLOCKS: usize = 2;
+
+/// The row and column of a DTMF key, None for a character that isn't a key.
+pub fn dtmf_key_position(key: char) -> Option<(usize, usize)> {
+    let key = key.to_ascii_uppercase();
+    DTMF_KEYS.iter().enumerate()
+        .find_map(|(row, keys)| keys.iter().position(|k| *k == key).map(|column| (row, column)))
+}
+
+/// The DTMF tones of the keys, each one for tone_ms followed by gap_ms of silence.
+/// Each of the two sines of a tone has a peak of level_dbfs. The spaces of the
+/// text and the dashes of a phone number are ignored.
+///
+/// In Rust:
+///    ```text
+///    >>> let signal = encode_dtmf("555-0123", 8_000, DEFAULT_TONE_MS, DEFAULT_GAP_MS, DEFAULT_TONE_LEVEL_DBFS)?;
+///    ```
+///
+pub fn encode_dtmf(keys: & str, sample_rate: u32, tone_ms: f64, gap_ms: f64, level_dbfs: f64)
+        -> Result<Vec<f64>, String> {
+    assert!(tone_ms > 0.0 && gap_ms >= 0.0);
+    if level_dbfs > -6.0206 {
+        return Err(format!("Error: the DTMF level {} dBFS clips, the sum of the two sines is 6 dB higher", level_dbfs));
+    }
+    let amplitude = f64::powf(10.0, level_dbfs / 20.0);
+    let tone_samples = (tone_ms * 0.001 * sample_rate as f64).round() as usize;
+    let gap_samples = (gap_ms * 0.001 * sample_rate as f64).round() as usize;
+    let mut signal = Vec::new();
+    for key in keys.chars().filter(|key| *key != ' ' && *key != '-') {
+        let (row, column) = dtmf_key_position(key).ok_or(format!("Error: \"{}\" isn't a DTMF key", key))?;
+        let frequencies = [DTMF_ROW_FREQUENCIES[row], DTMF_COLUMN_FREQUENCIES[column]];
+        signal.extend((0..tone_samples).map(|n| dual_sine(frequencies, amplitude, n, sample_rate)));
+        signal.extend(std::iter::repeat_n(0.0, gap_samples));
+    }
+    Ok(signal)
+}
+
+// The sum of the two sines, at the sample n.
+fn dual_sine(frequencies: [f64; 2], amplitude: f64, n: usize, sample_rate: u32) -> f64 {
+    frequencies.iter().map(|f| amplitude * f64::sin(TAU * f * n as f64 / sample_rate as f64)).sum()
+}
+
+// The index of the strongest of the amplitudes, if it's above the minimum and
+// the ratio above the others.
+fn strongest(amplitudes: & [f64], min_amplitude: f64) -> Option<usize> {
+    let (index, max) = amplitudes.iter().enumerate()
+        .fold((0, 0.0), |(index, max), (i, a)| if *a > max { (i, *a) } else { (index, max) });
+    let others_below = amplitudes.iter().enumerate()
+        .all(|(i, a)| i == index || a * MIN_GROUP_RATIO <= max);
+    if max >= min_amplitude && others_below { Some(index) } else { None }
+}
+
+// Whether the two sines of the amplitudes are most of the power of the block.
+fn is_dual_tone(block: & [f64], low: f64, high: f64) -> bool {
+    let power = block.iter().map(|x| x * x).sum::<f64>() / block.len() as f64;
+    let ratio = if low > high { low / high } else { high / low };
+    ratio <= MAX_TWIST_RATIO && (low * low + high * high) / 2.0 >= MIN_TONE_POWER_FRACTION * power
+}
+
+/// Streaming DTMF decoder.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut decoder = DtmfDecoder::new(8_000);
+///    >>> for block in audio.chunks(160) {
+///    >>>     for key in decoder.process(block) { println!("{}", key); }
+///    >>> }
+///    ```
+///
+pub struct DtmfDecoder {
+    sample_rate:   u32,
+    block:         Vec<f64>,
+    block_size:    usize,
+    min_amplitude: f64,
+    // The key of the last blocks and how many blocks in a row had it.
+    last_key:      Option<char>,
+    count:         usize,
+}
+
+impl DtmfDecoder {
+    pub fn new(sample_rate: u32) -> Self {
+        let block_size = (DTMF_BLOCK_SECONDS * sample_rate as f64).round() as usize;
+        DtmfDecoder {
+            sample_rate,
+            block: Vec::with_capacity(block_size),
+            block_size,
+            min_amplitude: f64::powf(10.0, DEFAULT_MIN_LEVEL_DBFS / 20.0),
+            last_key: None,
+            count: 0,
+        }
+    }
+
+    pub fn get_min_level_dbfs(& self) -> f64 {
+        20.0 * f64::log10(self.min_amplitude)
+    }
+
+    pub fn set_min_level_dbfs(& mut self, min_level_dbfs: f64) {
+        self.min_amplitude = f64::powf(10.0, min_level_dbfs / 20.0);
+    }
+
+    /// Decodes the samples, returns the keys that were accepted in them.
+    pub fn process(& mut self, samples: & [f64]) -> Vec<char> {
+        let mut keys = Vec::new();
+        for x in samples {
+            self.block.push(*x);
+            if self.block.len() == self.block_size {
+                let key = self.detect_block();
+                self.block.clear();
+                if key.is_some() && key == self.last_key {
+                    self.count += 1;
+                } else {
+                    self.last_key = key;
+                    self.count = 1;
+                }
+                if let Some(key) = key {
+                    if self.count == MIN_KEY_BLOCKS {
+                        keys.push(key);
+                    }
+                }
+            }
+        }
+        keys
+    }
+
+    fn detect_block(& self) -> Option<char> {
+        let rows: Vec<f64> = DTMF_ROW_FREQUENCIES.iter()
+            .map(|f| goertzel_amplitude(& self.block, *f, self.sample_rate))
+            .collect();
+        let columns: Vec<f64> = DTMF_COLUMN_FREQUENCIES.iter()
+            .map(|f| goertzel_amplitude(& self.block, *f, self.sample_rate))
+            .collect();
+        let row = strongest(& rows, self.min_amplitude)?;
+        let column = strongest(& columns, self.min_amplitude)?;
+        if is_dual_tone(& self.block, rows[row], columns[column]) { Some(DTMF_KEYS[row][column]) } else { None }
+    }
+}
+
+/// The DTMF keys of a signal.
+pub fn decode_dtmf(signal: & [f64], sample_rate: u32) -> String {
+    DtmfDecoder::new(sample_rate).process(signal).iter().collect()
+}
+
+/// A call-progress tone of the North American telephone network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallProgressTone {
+    Dial,
+    Ringback,
+    Busy,
+    Reorder,
+}
+
+impl CallProgressTone {
+    /// The two frequencies, in Hz.
+    pub fn get_frequencies(& self) -> [f64; 2] {
+        match self {
+            CallProgressTone::Dial     => [350.0, 440.0],
+            CallProgressTone::Ringback => [440.0, 480.0],
+            CallProgressTone::Busy | CallProgressTone::Reorder => [480.0, 620.0],
+        }
+    }
+
+    /// The on and off durations of the cadence in seconds, None for a continuous tone.
+    pub fn get_cadence_seconds(& self) -> Option<(f64, f64)> {
+        match self {
+            CallProgressTone::Dial     => None,
+            CallProgressTone::Ringback => Some((2.0, 4.0)),
+            CallProgressTone::Busy     => Some((0.5, 0.5)),
+            CallProgressTone::Reorder  => Some((0.25, 0.25)),
+        }
+    }
+}
+
+/// The tone with its cadence, starting with the on part. Each of the two sines
+/// has a peak of level_dbfs.
+pub fn make_call_progress_tone(tone: CallProgressTone, sample_rate: u32, duration_seconds: f64, level_dbfs: f64)
+        -> Vec<f64> {
+    let amplitude = f64::powf(10.0, level_dbfs / 20.0);
+    let num_samples = (duration_seconds * sample_rate as f64).round() as usize;
+    (0..num_samples)
+        .map(|n| {
+            let on = match tone.get_cadence_seconds() {
+                None => true,
+                Some((on, off)) => (n as f64 / sample_rate as f64) % (on + off) < on,
+            };
+            if on { dual_sine(tone.get_frequencies(), amplitude, n, sample_rate) } else { 0.0 }
+        })
+        .collect()
+}
+
+/// Detects the call-progress tone of the signal. Dial tone and ringback are known
+/// from their frequencies, busy and reorder need at least a complete on or off
+/// part of their cadence, a signal of a little more than 0.5 s or 1 s.
+///
+/// In Rust:
+///    ```text
+///    >>> if detect_call_progress_tone(& line, 8_000) == Some(CallProgressTone::Busy) { hang_up(); }
+///    ```
+///
+pub fn detect_call_progress_tone(signal: & [f64], sample_rate: u32) -> Option<CallProgressTone> {
+    let block_size = (CALL_PROGRESS_BLOCK_SECONDS * sample_rate as f64).round() as usize;
+    let frequencies = [350.0, 440.0, 480.0, 620.0];
+    let min_amplitude = f64::powf(10.0, DEFAULT_MIN_LEVEL_DBFS / 20.0);
+    // The pair of frequencies of each block, by their indexes, None without a tone.
+    let pairs: Vec<Option<(usize, usize)>> = signal.chunks_exact(block_size)
+        .map(|block| {
+            let amplitudes: Vec<f64> = frequencies.iter().map(|f| goertzel_amplitude(block, *f, sample_rate)).collect();
+            let mut order: Vec<usize> = (0..4).collect();
+            order.sort_by(|a, b| amplitudes[*b].total_cmp(& amplitudes[*a]));
+            let (first, second, third) = (order[0], order[1], order[2]);
+            let found = amplitudes[second] >= min_amplitude
+                && amplitudes[third] * MIN_GROUP_RATIO <= amplitudes[second]
+                && is_dual_tone(block, amplitudes[first], amplitudes[second]);
+            if found { Some((first.min(second), first.max(second))) } else { None }
+        })
+        .collect();
+
+    let count = |pair: (usize, usize)| pairs.iter().filter(|p| **p == Some(pair)).count();
+    let (dial, ringback, busy) = (count((0, 1)), count((1, 2)), count((2, 3)));
+    if dial.max(ringback).max(busy) == 0 {
+        return None;
+    }
+    if dial >= ringback.max(busy) {
+        return Some(CallProgressTone::Dial);
+    }
+    if ringback >= busy {
+        return Some(CallProgressTone::Ringback);
+    }
+    // The runs of blocks on and off that are complete, with a change on both sides.
+    let states: Vec<bool> = pairs.iter().map(|p| *p == Some((2, 3))).collect();
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for index in 1..states.len() {
+        if states[index] != states[index - 1] {
+            if start > 0 {
+                runs.push(index - start);
+            }
+            start = index;
+        }
+    }
+    if runs.is_empty() {
+        return None;
+    }
+    let mean_seconds = runs.iter().sum::<usize>() as f64 / runs.len() as f64 * CALL_PROGRESS_BLOCK_SECONDS;
+    // Between the 0.25 s of reorder and the 0.5 s of busy.
+    if mean_seconds >= 0.375 { Some(CallProgressTone::Busy) } else { Some(CallProgressTone::Reorder) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::make_white_noise;
+
+    #[test]
+    fn test_dtmf_encode_decode() {
+        let keys = "0123456789*#ABCD";
+        for sample_rate in [8_000, 44_100] {
+            let signal = encode_dtmf(keys, sample_rate, DEFAULT_TONE_MS, DEFAULT_GAP_MS, DEFAULT_TONE_LEVEL_DBFS).unwrap();
+            assert_eq!(decode_dtmf(& signal, sample_rate), keys);
+        }
+        // The same key twice, with short tones and gaps, in noise.
+        let mut signal = encode_dtmf("55 9", 8_000, 80.0, 50.0, -20.0).unwrap();
+        let noise = make_white_noise(signal.len(), 0.005, 7);
+        for (x, noise) in signal.iter_mut().zip(noise) {
+            *x += noise;
+        }
+        assert_eq!(decode_dtmf(& signal, 8_000), "559");
+
+        assert!(encode_dtmf("12X", 8_000, 100.0, 100.0, -10.0).is_err());
+        assert!(encode_dtmf("1", 8_000, 100.0, 100.0, -3.0).is_err());
+        assert_eq!(dtmf_key_position('d'), Some((3, 3)));
+    }
+
+    #[test]
+    fn test_dtmf_rejects_other_signals() {
+        // A single sine and a chord of a row and a third frequency aren't keys.
+        let sine: Vec<f64> = (0..8_000).map(|n| 0.3 * f64::sin(TAU * 770.0 * n as f64 / 8_000.0)).collect();
+        assert_eq!(decode_dtmf(& sine, 8_000), "");
+        let chord: Vec<f64> = (0..8_000)
+            .map(|n| dual_sine([770.0, 1_336.0], 0.2, n, 8_000) + 0.2 * f64::sin(TAU * 1_100.0 * n as f64 / 8_000.0))
+            .collect();
+        assert_eq!(decode_dtmf(& chord, 8_000), "");
+        // Too much twist, 12 dB.
+        let twisted: Vec<f64> = (0..8_000)
+            .map(|n| 0.4 * f64::sin(TAU * 770.0 * n as f64 / 8_000.0) + 0.1 * f64::sin(TAU * 1_336.0 * n as f64 / 8_000.0))
+            .collect();
+        assert_eq!(decode_dtmf(& twisted, 8_000), "");
+    }
+
+    #[test]
+    fn test_call_progress_tones() {
+        let tones = [(CallProgressTone::Dial, 1.0), (CallProgressTone::Ringback, 3.0),
+                     (CallProgressTone::Busy, 2.0), (CallProgressTone::Reorder, 1.0)];
+        for (tone, seconds) in tones {
+            let signal = make_call_progress_tone(tone, 8_000, seconds, -16.0);
+            assert_eq!(detect_call_progress_tone(& signal, 8_000), Some(tone));
+        }
+        assert_eq!(detect_call_progress_tone(& vec![0.0; 8_000], 8_000), None);
+        // Shorter than a complete part of the cadence.
+        let busy = make_call_progress_tone(CallProgressTone::Busy, 8_000, 0.4, -16.0);
+        assert_eq!(detect_call_progress_tone(& busy, 8_000), None);
+        let keys = encode_dtmf("123", 8_000, 100.0, 100.0, -10.0).unwrap();
+        assert_eq!(detect_call_progress_tone(& keys, 8_000), None);
+    }
+}
@@ -0,0 +1,695 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Dynamics processors, a compressor and a look-ahead limiter.
+//!              Both take a frame of samples, one per channel, and apply the same
+//!              gain to all the channels (linked detection), so the stereo image
+//!              doesn't move when one channel is louder.
+//!
+//!              The compressor is a feedforward design with the gain computer and
+//!              the smoothing in the log domain: the level of the frame in dB goes
+//!              through the static curve (threshold, ratio and a soft knee of
+//!              knee_db around the threshold), and the gain reduction is smoothed
+//!              with the attack time when it grows and the release time when it
+//!              falls. The detector is the peak of the frame, its RMS level, or a
+//!              hybrid of both, and the release can be program dependent, with a
+//!              fast and a slow stage. The static curve is the function
+//!              gain_computer(), for custom dynamics processors, and
+//!              show_response::show_static_curve() plots it. Besides the downward
+//!              compression, the modes of upward compression (boost of the quiet
+//!              parts) and upward expansion (restoration of the peaks of a
+//!              compressed master) have a range of max_boost_db.
+//!
+//!              The limiter delays the signal by the look-ahead time L, and the gain
+//!              for each frame is the minimum of the gains needed by the frames in the
+//!              last L samples, with an exponential release, averaged over L samples.
+//!              So the gain ramps down smoothly before a peak, and reaches the needed
+//!              gain when the peak comes out of the delay: the output never exceeds
+//!              the ceiling.
+//!
+//!              Both can feed a GainReductionMeter of gain_meter.rs, for the meters of
+//!              a UI in another thread.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Giannoulis, D., Massberg, M., Reiss, J. D., "Digital Dynamic Range Compressor
+//!       Design - A Tutorial and Analysis", J. Audio Eng. Soc. 2012.
+//!
+
+
+use std::collections::VecDeque;
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::delay_line::DelayLine;
+use crate::delay_line::milliseconds_to_samples;
+use crate::gain_meter::GainReductionMeter;
+
+
+// Coefficient of a one pole smoother with the time constant.
+fn smoothing_coefficient(time_ms: f64, sample_rate: u32) -> f64 {
+    if time_ms <= 0.0 {
+        return 0.0;
+    }
+    f64::exp(-1.0 / (time_ms * 0.001 * sample_rate as f64))
+}
+
+fn db_to_gain(db: f64) -> f64 {
+    f64::powf(10.0, db / 20.0)
+}
+
+// The level in dB of the loudest sample of the frame.
+fn frame_level_db(frame: & [f64]) -> f64 {
+    let peak = frame.iter().fold(0.0, |max: f64, x| max.max(x.abs()));
+    20.0 * f64::log10(peak.max(1e-12))
+}
+
+/// The static curve of a compressor, the output level in dB of an input level in dB.
+/// Below the threshold the level is unchanged, above it grows 1 dB for each ratio dB,
+/// and in the knee of knee_db centered on the threshold the slope changes with a
+/// quadratic, the curve and its slope are continuous. A knee_db of 0 is a hard knee.
+/// The gain is gain_computer(level_db, ...) - level_db, for a custom detector and
+/// smoothing.
+///
+/// In Rust:
+///    ```text
+///    >>> let output_db = gain_computer(-10.0, -20.0, 4.0, 6.0);
+///    >>> // -17.5 dB
+///    ```
+///
+pub fn gain_computer(level_db: f64, threshold_db: f64, ratio: f64, knee_db: f64) -> f64 {
+    let overshoot = level_db - threshold_db;
+    if 2.0 * overshoot < -knee_db {
+        level_db
+    } else if 2.0 * overshoot.abs() <= knee_db {
+        let knee_position = overshoot + knee_db / 2.0;
+        level_db + (1.0 / ratio - 1.0) * knee_position * knee_position / (2.0 * knee_db)
+    } else {
+        threshold_db + overshoot / ratio
+    }
+}
+
+/// The direction of the gain change of the compressor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DynamicsMode {
+    /// Reduces the levels above the threshold, the slope is 1 / ratio.
+    DownwardCompression,
+    /// Boosts the levels below the threshold towards it, the slope is 1 / ratio,
+    /// it brings up the quiet details of a recording.
+    UpwardCompression,
+    /// Boosts the levels above the threshold, the slope is ratio, it restores the
+    /// peaks of a recording that was compressed or limited.
+    UpwardExpansion,
+}
+
+/// The static curve of the mode, the output level in dB of an input level in dB,
+/// with the same soft knee as gain_computer(). The upward compression is the
+/// downward curve mirrored about the threshold, and the upward expansion moves away
+/// from the 1:1 line ratio times the distance of the downward curve.
+pub fn static_curve(mode: DynamicsMode, level_db: f64, threshold_db: f64, ratio: f64, knee_db: f64) -> f64 {
+    match mode {
+        DynamicsMode::DownwardCompression => gain_computer(level_db, threshold_db, ratio, knee_db),
+        DynamicsMode::UpwardCompression => -gain_computer(-level_db, -threshold_db, ratio, knee_db),
+        DynamicsMode::UpwardExpansion =>
+            level_db + ratio * (level_db - gain_computer(level_db, threshold_db, ratio, knee_db)),
+    }
+}
+
+/// The level detector of the compressor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Detector {
+    /// The loudest sample of the frame, it catches every transient.
+    Peak,
+    /// The mean square over the RMS window, it follows the loudness and lets the
+    /// short transients through.
+    Rms,
+    /// The larger of the RMS level and the peak level - 3 dB. On a sine both are the
+    /// same, a steady program is compressed by its RMS and a transient with a crest
+    /// factor above 3 dB by its peak.
+    Hybrid,
+}
+
+/// Largest boost of the upward modes, without it the upward compression would
+/// boost the silence without limit.
+pub const DEFAULT_MAX_BOOST_DB: f64 = 12.0;
+
+/// Time constant of the RMS detector.
+pub const DEFAULT_RMS_WINDOW_MS: f64 = 10.0;
+
+// The crest factor of a sine, 20 log10(sqrt(2)).
+const SINE_CREST_DB: f64 = 3.010299956639812;
+
+pub struct Compressor {
+    sample_rate:          u32,
+    threshold_db:         f64,
+    ratio:                f64,
+    knee_db:              f64,
+    makeup_db:            f64,
+    makeup_gain:          f64,
+    attack_coefficient:   f64,
+    release_coefficient:  f64,
+    detector:             Detector,
+    rms_window_ms:        f64,
+    rms_coefficient:      f64,
+    // Smoothed mean square of the frames.
+    mean_square:          f64,
+    slow_release_ms:      Option<f64>,
+    slow_coefficient:     f64,
+    mode:                 DynamicsMode,
+    max_boost_db:         f64,
+    // Smoothed gain change in dB, negative for a reduction.
+    gain_reduction_db:    f64,
+    // Gain reduction of the fast stage and of the slow stage, for the dual release.
+    fast_reduction_db:    f64,
+    slow_reduction_db:    f64,
+    meter:                Option<GainReductionMeter>,
+}
+
+impl Compressor {
+    /// Downward compression with a hard knee, without makeup gain, peak detector and
+    /// a single release stage.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let mut compressor = Compressor::new(48_000, -20.0, 4.0, 5.0, 100.0);
+    ///    >>> compressor.set_knee_db(6.0);
+    ///    >>> compressor.process_frame(& mut [left, right]);
+    ///    ```
+    ///
+    pub fn new(sample_rate: u32, threshold_db: f64, ratio: f64, attack_ms: f64, release_ms: f64) -> Self {
+        assert!(ratio >= 1.0);
+        assert!(attack_ms >= 0.0 && release_ms >= 0.0);
+        Compressor {
+            sample_rate,
+            threshold_db,
+            ratio,
+            knee_db: 0.0,
+            makeup_db: 0.0,
+            makeup_gain: 1.0,
+            attack_coefficient: smoothing_coefficient(attack_ms, sample_rate),
+            release_coefficient: smoothing_coefficient(release_ms, sample_rate),
+            detector: Detector::Peak,
+            rms_window_ms: DEFAULT_RMS_WINDOW_MS,
+            rms_coefficient: smoothing_coefficient(DEFAULT_RMS_WINDOW_MS, sample_rate),
+            mean_square: 0.0,
+            slow_release_ms: None,
+            slow_coefficient: 0.0,
+            mode: DynamicsMode::DownwardCompression,
+            max_boost_db: DEFAULT_MAX_BOOST_DB,
+            gain_reduction_db: 0.0,
+            fast_reduction_db: 0.0,
+            slow_reduction_db: 0.0,
+            meter: None,
+        }
+    }
+
+    pub fn get_threshold_db(& self) -> f64 {
+        self.threshold_db
+    }
+
+    pub fn set_threshold_db(& mut self, threshold_db: f64) {
+        self.threshold_db = threshold_db;
+    }
+
+    pub fn get_ratio(& self) -> f64 {
+        self.ratio
+    }
+
+    pub fn set_ratio(& mut self, ratio: f64) {
+        assert!(ratio >= 1.0);
+        self.ratio = ratio;
+    }
+
+    pub fn get_knee_db(& self) -> f64 {
+        self.knee_db
+    }
+
+    /// Width of the soft knee, centered on the threshold.
+    pub fn set_knee_db(& mut self, knee_db: f64) {
+        assert!(knee_db >= 0.0);
+        self.knee_db = knee_db;
+    }
+
+    pub fn get_makeup_db(& self) -> f64 {
+        self.makeup_db
+    }
+
+    pub fn set_makeup_db(& mut self, makeup_db: f64) {
+        self.makeup_db = makeup_db;
+        self.makeup_gain = db_to_gain(makeup_db);
+    }
+
+    pub fn set_attack_ms(& mut self, attack_ms: f64) {
+        assert!(attack_ms >= 0.0);
+        self.attack_coefficient = smoothing_coefficient(attack_ms, self.sample_rate);
+    }
+
+    pub fn set_release_ms(& mut self, release_ms: f64) {
+        assert!(release_ms >= 0.0);
+        self.release_coefficient = smoothing_coefficient(release_ms, self.sample_rate);
+    }
+
+    pub fn get_detector(& self) -> Detector {
+        self.detector
+    }
+
+    pub fn set_detector(& mut self, detector: Detector) {
+        self.detector = detector;
+    }
+
+    pub fn get_rms_window_ms(& self) -> f64 {
+        self.rms_window_ms
+    }
+
+    /// Time constant of the mean square of the RMS and Hybrid detectors.
+    pub fn set_rms_window_ms(& mut self, rms_window_ms: f64) {
+        assert!(rms_window_ms > 0.0);
+        self.rms_window_ms = rms_window_ms;
+        self.rms_coefficient = smoothing_coefficient(rms_window_ms, self.sample_rate);
+    }
+
+    pub fn get_slow_release_ms(& self) -> Option<f64> {
+        self.slow_release_ms
+    }
+
+    /// Program-dependent release with two stages, None is a single stage. The fast
+    /// stage has the attack and release times, the slow stage follows the fast one
+    /// with slow_release_ms in both directions, and the gain reduction is the larger
+    /// of the two. A short transient barely charges the slow stage and is released
+    /// with the fast time, a long compression charges it and is released slowly,
+    /// without the pumping of a fast release on a dense mix.
+    pub fn set_slow_release_ms(& mut self, slow_release_ms: Option<f64>) {
+        if let Some(time_ms) = slow_release_ms {
+            assert!(time_ms >= 0.0);
+            self.slow_coefficient = smoothing_coefficient(time_ms, self.sample_rate);
+        }
+        self.slow_release_ms = slow_release_ms;
+        self.slow_reduction_db = self.fast_reduction_db;
+    }
+
+    pub fn get_mode(& self) -> DynamicsMode {
+        self.mode
+    }
+
+    pub fn set_mode(& mut self, mode: DynamicsMode) {
+        self.mode = mode;
+    }
+
+    pub fn get_max_boost_db(& self) -> f64 {
+        self.max_boost_db
+    }
+
+    /// Largest gain of the static curve, the range of the upward modes.
+    pub fn set_max_boost_db(& mut self, max_boost_db: f64) {
+        assert!(max_boost_db >= 0.0);
+        self.max_boost_db = max_boost_db;
+    }
+
+    /// The current gain change in dB, negative for a reduction, positive for the
+    /// boost of the upward modes.
+    pub fn get_gain_reduction_db(& self) -> f64 {
+        self.gain_reduction_db
+    }
+
+    /// The meter takes the gain change of each frame, for the GainReductionReader of
+    /// another thread. Returns the previous meter.
+    pub fn set_meter(& mut self, meter: Option<GainReductionMeter>) -> Option<GainReductionMeter> {
+        std::mem::replace(& mut self.meter, meter)
+    }
+
+    // The level of the frame in dB with the detector.
+    fn detect_level_db(& mut self, frame: & [f64]) -> f64 {
+        if self.detector == Detector::Peak {
+            return frame_level_db(frame);
+        }
+        let square = frame.iter().fold(0.0, |max: f64, x| max.max(x * x));
+        self.mean_square = square + self.rms_coefficient * (self.mean_square - square);
+        let rms_db = 10.0 * f64::log10(self.mean_square.max(1e-24));
+        match self.detector {
+            Detector::Hybrid => rms_db.max(frame_level_db(frame) - SINE_CREST_DB),
+            _ => rms_db,
+        }
+    }
+
+    // Static curve, the output level in dB of an input level in dB.
+    fn gain_computer(& self, level_db: f64) -> f64 {
+        let output_db = static_curve(self.mode, level_db, self.threshold_db, self.ratio, self.knee_db);
+        output_db.min(level_db + self.max_boost_db)
+    }
+
+    /// The static curve with the makeup gain, num_points (input dB, output dB) from
+    /// min_db to max_db, for show_response::show_static_curve().
+    pub fn get_static_curve(& self, min_db: f64, max_db: f64, num_points: usize) -> Vec<(f64, f64)> {
+        assert!(min_db < max_db && num_points >= 2);
+        (0..num_points)
+            .map(|i| {
+                let level_db = min_db + (max_db - min_db) * i as f64 / (num_points - 1) as f64;
+                (level_db, self.gain_computer(level_db) + self.makeup_db)
+            })
+            .collect()
+    }
+
+    /// Compresses a frame, one sample per channel, with the same gain.
+    pub fn process_frame(& mut self, frame: & mut [f64]) {
+        let level_db = self.detect_level_db(frame);
+        let target_db = self.gain_computer(level_db) - level_db;
+        // The smoothing is in dB, the attack and release times are the same at any
+        // amount of gain reduction. The attack follows a rising level, the gain falls
+        // in the compression modes and rises in the expansion.
+        let expansion = self.mode == DynamicsMode::UpwardExpansion;
+        let attack = if expansion { target_db > self.fast_reduction_db } else { target_db < self.fast_reduction_db };
+        let coefficient = if attack { self.attack_coefficient } else { self.release_coefficient };
+        self.fast_reduction_db = target_db + coefficient * (self.fast_reduction_db - target_db);
+        self.gain_reduction_db = match self.slow_release_ms {
+            Some(_) => {
+                self.slow_reduction_db = self.fast_reduction_db
+                    + self.slow_coefficient * (self.slow_reduction_db - self.fast_reduction_db);
+                if expansion {
+                    self.fast_reduction_db.max(self.slow_reduction_db)
+                } else {
+                    self.fast_reduction_db.min(self.slow_reduction_db)
+                }
+            },
+            None => self.fast_reduction_db,
+        };
+        if let Some(meter) = & mut self.meter {
+            meter.update(self.gain_reduction_db);
+        }
+        let gain = db_to_gain(self.gain_reduction_db) * self.makeup_gain;
+        frame.iter_mut().for_each(|x| *x *= gain);
+    }
+}
+
+impl ProcessingBlock for Compressor {
+    fn process(& mut self, sample: f64) -> f64 {
+        let mut frame = [sample];
+        self.process_frame(& mut frame);
+        frame[0]
+    }
+}
+
+pub struct Limiter {
+    ceiling:             f64,
+    release_coefficient: f64,
+    lookahead:           usize,
+    delays:              Vec<DelayLine>,
+    // Sliding minimum of the needed gains, (frame position, gain), increasing gains.
+    minimum_window:      VecDeque<(u64, f64)>,
+    held_gain:           f64,
+    // Moving average of the held gains.
+    average_buffer:      Vec<f64>,
+    average_index:       usize,
+    average_sum:         f64,
+    frame:               u64,
+    meter:               Option<GainReductionMeter>,
+}
+
+impl Limiter {
+    /// The output is delayed by the look-ahead time, get_latency_samples().
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let mut limiter = Limiter::new(48_000, 2, -1.0, 1.5, 50.0);
+    ///    >>> limiter.process_frame(& mut [left, right]);
+    ///    ```
+    ///
+    pub fn new(sample_rate: u32, num_channels: usize, ceiling_db: f64, lookahead_ms: f64, release_ms: f64) -> Self {
+        assert!(num_channels >= 1);
+        let lookahead = milliseconds_to_samples(lookahead_ms, sample_rate).max(1);
+        Limiter {
+            ceiling: db_to_gain(ceiling_db),
+            release_coefficient: smoothing_coefficient(release_ms, sample_rate),
+            lookahead,
+            delays: (0..num_channels).map(|_| DelayLine::new(lookahead - 1)).collect(),
+            minimum_window: VecDeque::with_capacity(lookahead + 1),
+            held_gain: 1.0,
+            average_buffer: vec![1.0; lookahead],
+            average_index: 0,
+            average_sum: lookahead as f64,
+            frame: 0,
+            meter: None,
+        }
+    }
+
+    pub fn get_ceiling_db(& self) -> f64 {
+        20.0 * f64::log10(self.ceiling)
+    }
+
+    pub fn set_ceiling_db(& mut self, ceiling_db: f64) {
+        self.ceiling = db_to_gain(ceiling_db);
+    }
+
+    /// Delay of the output, in samples.
+    pub fn get_latency_samples(& self) -> usize {
+        self.lookahead - 1
+    }
+
+    /// The current gain reduction in dB, 0 or negative.
+    pub fn get_gain_reduction_db(& self) -> f64 {
+        20.0 * f64::log10(self.average_sum / self.lookahead as f64)
+    }
+
+    /// The meter takes the gain change of each frame, for the GainReductionReader of
+    /// another thread. Returns the previous meter.
+    pub fn set_meter(& mut self, meter: Option<GainReductionMeter>) -> Option<GainReductionMeter> {
+        std::mem::replace(& mut self.meter, meter)
+    }
+
+    /// Limits a frame, one sample per channel, in place.
+    pub fn process_frame(& mut self, frame: & mut [f64]) {
+        assert_eq!(frame.len(), self.delays.len());
+        let peak = frame.iter().fold(0.0, |max: f64, x| max.max(x.abs()));
+        let needed_gain = if peak > self.ceiling { self.ceiling / peak } else { 1.0 };
+
+        // Minimum of the needed gains in the last lookahead frames.
+        while self.minimum_window.back().is_some_and(|(_, gain)| *gain >= needed_gain) {
+            self.minimum_window.pop_back();
+        }
+        self.minimum_window.push_back((self.frame, needed_gain));
+        while self.minimum_window.front().is_some_and(|(f, _)| f + self.lookahead as u64 <= self.frame) {
+            self.minimum_window.pop_front();
+        }
+        let minimum = self.minimum_window.front().unwrap().1;
+        self.frame += 1;
+
+        // Instant attack and exponential release, the average smooths the attack.
+        self.held_gain = if minimum < self.held_gain {
+            minimum
+        } else {
+            minimum + self.release_coefficient * (self.held_gain - minimum)
+        };
+        self.average_sum += self.held_gain - self.average_buffer[self.average_index];
+        self.average_buffer[self.average_index] = self.held_gain;
+        self.average_index = (self.average_index + 1) % self.lookahead;
+        if self.average_index == 0 {
+            // Removes the rounding drift of the running sum.
+            self.average_sum = self.average_buffer.iter().sum();
+        }
+        let gain = self.average_sum / self.lookahead as f64;
+        if let Some(meter) = & mut self.meter {
+            meter.update(20.0 * f64::log10(gain));
+        }
+
+        for (x, delay) in frame.iter_mut().zip(& mut self.delays) {
+            *x = delay.process(*x) * gain;
+        }
+    }
+}
+
+impl ProcessingBlock for Limiter {
+    /// Only for a limiter of one channel.
+    fn process(& mut self, sample: f64) -> f64 {
+        let mut frame = [sample];
+        self.process_frame(& mut frame);
+        frame[0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::TAU;
+
+    #[test]
+    fn test_compressor_static_curve() {
+        let mut compressor = Compressor::new(48_000, -20.0, 4.0, 0.0, 0.0);
+        assert_eq!(compressor.gain_computer(-30.0), -30.0);
+        assert_eq!(compressor.gain_computer(0.0), -15.0);
+        compressor.set_knee_db(10.0);
+        // Continuous at the edges of the knee, and half way in the middle.
+        assert!((compressor.gain_computer(-25.0) + 25.0).abs() < 1e-12);
+        assert!((compressor.gain_computer(-15.0) + 18.75).abs() < 1e-12);
+        assert!((compressor.gain_computer(-20.0) + 20.9375).abs() < 1e-12);
+
+        // Without smoothing, a 0 dBFS frame is at -15 dBFS.
+        compressor.set_makeup_db(3.0);
+        let mut frame = [1.0, -0.5];
+        compressor.process_frame(& mut frame);
+        assert!((20.0 * f64::log10(frame[0]) + 12.0).abs() < 1e-9);
+        assert!((frame[1] + 0.5 * db_to_gain(-15.0 + 3.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_gain_computer_slope() {
+        // The slope goes from 1 below the knee to 1 / ratio above it, without jumps.
+        let slope = |level_db: f64| (gain_computer(level_db + 1e-6, -18.0, 3.0, 8.0)
+                                     - gain_computer(level_db - 1e-6, -18.0, 3.0, 8.0)) / 2e-6;
+        assert!((slope(-40.0) - 1.0).abs() < 1e-6);
+        assert!((slope(-22.0) - 1.0).abs() < 1e-6);
+        assert!((slope(-14.0) - 1.0 / 3.0).abs() < 1e-6);
+        assert!((slope(0.0) - 1.0 / 3.0).abs() < 1e-6);
+        let mut previous = slope(-22.0);
+        for i in 1..=80 {
+            let current = slope(-22.0 + 0.1 * i as f64);
+            assert!(current <= previous + 1e-6 && previous - current < 0.01);
+            previous = current;
+        }
+
+        let mut compressor = Compressor::new(48_000, -18.0, 3.0, 0.0, 0.0);
+        compressor.set_knee_db(8.0);
+        compressor.set_makeup_db(2.0);
+        let curve = compressor.get_static_curve(-60.0, 0.0, 61);
+        assert_eq!(curve.len(), 61);
+        assert_eq!(curve[0], (-60.0, -58.0));
+        assert_eq!(curve[60].0, 0.0);
+        assert!((curve[60].1 - gain_computer(0.0, -18.0, 3.0, 8.0) - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_compressor_attack_and_release() {
+        let mut compressor = Compressor::new(48_000, -20.0, 10.0, 10.0, 100.0);
+        let loud: Vec<f64> = (0..48_000).map(|n| 0.9 * f64::sin(TAU * 1_000.0 * n as f64 / 48_000.0)).collect();
+        for x in & loud {
+            compressor.process(*x);
+        }
+        // The level follows the peaks, with about -18 dB of reduction.
+        assert!(compressor.get_gain_reduction_db() < -15.0);
+        for _ in 0..4_800 {
+            compressor.process(0.0);
+        }
+        // After one time constant of release, the reduction is about 1 / e of its value.
+        assert!(compressor.get_gain_reduction_db() > -10.0);
+    }
+
+    #[test]
+    fn test_detectors() {
+        let sine: Vec<f64> = (0..9_600).map(|n| 0.5 * f64::sin(TAU * 1_000.0 * n as f64 / 48_000.0)).collect();
+        let mut reductions = Vec::new();
+        for detector in [Detector::Peak, Detector::Rms, Detector::Hybrid] {
+            let mut compressor = Compressor::new(48_000, -30.0, 4.0, 0.0, 500.0);
+            compressor.set_detector(detector);
+            for x in & sine {
+                compressor.process(*x);
+            }
+            reductions.push(compressor.get_gain_reduction_db());
+        }
+        // Peak of -6 dB, RMS of -9 dB, and the hybrid is the RMS on a sine.
+        let reduction_db = |level_db: f64| gain_computer(level_db, -30.0, 4.0, 0.0) - level_db;
+        assert!((reductions[0] - reduction_db(-6.02)).abs() < 0.05, "{:?}", reductions);
+        assert!((reductions[1] - reduction_db(-9.03)).abs() < 0.3, "{:?}", reductions);
+        assert!((reductions[2] - reductions[1]).abs() < 0.3, "{:?}", reductions);
+
+        // A single click is caught by the peak and hybrid detectors, not by the RMS.
+        let mut click = vec![0.0; 100];
+        click[50] = 1.0;
+        for (detector, caught) in [(Detector::Rms, false), (Detector::Hybrid, true)] {
+            let mut compressor = Compressor::new(48_000, -20.0, 10.0, 0.0, 0.0);
+            compressor.set_detector(detector);
+            let output: Vec<f64> = click.iter().map(|x| compressor.process(*x)).collect();
+            assert_eq!(output[50] < 0.5, caught, "{:?}", detector);
+        }
+    }
+
+    #[test]
+    fn test_program_dependent_release() {
+        let burst = |compressor: & mut Compressor, length: usize| {
+            for n in 0..length {
+                compressor.process(0.9 * f64::sin(TAU * 1_000.0 * n as f64 / 48_000.0));
+            }
+            for _ in 0..2_400 {
+                compressor.process(0.0);
+            }
+            compressor.get_gain_reduction_db()
+        };
+        let make = || {
+            let mut compressor = Compressor::new(48_000, -20.0, 10.0, 1.0, 20.0);
+            compressor.set_slow_release_ms(Some(1_000.0));
+            compressor
+        };
+        // 50 ms after a 10 ms transient the gain is back, after 2 s of compression it
+        // is still reduced.
+        assert!(burst(& mut make(), 480) > -2.0);
+        assert!(burst(& mut make(), 96_000) < -10.0);
+        // With a single stage both are released fast.
+        let mut compressor = make();
+        compressor.set_slow_release_ms(None);
+        assert!(burst(& mut compressor, 96_000) > -2.0);
+    }
+
+    #[test]
+    fn test_upward_modes() {
+        // Upward compression, the quiet levels are boosted, up to the range.
+        assert_eq!(static_curve(DynamicsMode::UpwardCompression, -10.0, -30.0, 2.0, 0.0), -10.0);
+        assert_eq!(static_curve(DynamicsMode::UpwardCompression, -50.0, -30.0, 2.0, 0.0), -40.0);
+        // Upward expansion, above the threshold the slope is the ratio.
+        assert_eq!(static_curve(DynamicsMode::UpwardExpansion, -30.0, -20.0, 2.0, 0.0), -30.0);
+        assert_eq!(static_curve(DynamicsMode::UpwardExpansion, -10.0, -20.0, 2.0, 0.0), 0.0);
+        for mode in [DynamicsMode::UpwardCompression, DynamicsMode::UpwardExpansion] {
+            // The soft knee is continuous at its edges.
+            for edge in [-33.0, -27.0] {
+                let hard = static_curve(mode, edge, -30.0, 3.0, 0.0);
+                assert!((static_curve(mode, edge, -30.0, 3.0, 6.0) - hard).abs() < 1e-12);
+            }
+        }
+
+        let mut compressor = Compressor::new(48_000, -30.0, 2.0, 0.0, 0.0);
+        compressor.set_mode(DynamicsMode::UpwardCompression);
+        compressor.set_max_boost_db(6.0);
+        let curve = compressor.get_static_curve(-90.0, 0.0, 91);
+        assert_eq!(curve[80], (-10.0, -10.0));
+        assert_eq!(curve[55], (-35.0, -32.5));
+        assert_eq!(curve[0], (-90.0, -84.0));
+        // A quiet constant signal at -40 dBFS comes out at -35 dBFS.
+        let mut y = 0.0;
+        for _ in 0..100 {
+            y = compressor.process(0.01);
+        }
+        assert!((20.0 * f64::log10(y) + 35.0).abs() < 1e-9);
+
+        // The expansion attacks when the level rises above the threshold.
+        let mut expander = Compressor::new(48_000, -20.0, 2.0, 1.0, 1_000.0);
+        expander.set_mode(DynamicsMode::UpwardExpansion);
+        for _ in 0..480 {
+            expander.process(0.5);
+        }
+        // 14 dB above the threshold, the boost of 14 dB is limited to the range.
+        assert!((expander.get_gain_reduction_db() - DEFAULT_MAX_BOOST_DB).abs() < 0.01);
+        for _ in 0..480 {
+            expander.process(0.01);
+        }
+        assert!(expander.get_gain_reduction_db() > 10.0);
+    }
+
+    #[test]
+    fn test_limiter_never_exceeds_the_ceiling() {
+        let mut limiter = Limiter::new(48_000, 2, -3.0, 2.0, 10.0);
+        let latency = limiter.get_latency_samples();
+        let ceiling = db_to_gain(-3.0);
+        let mut output = Vec::new();
+        for n in 0..24_000 {
+            // Bursts up to +6 dBFS.
+            let envelope = if (n / 2_000) % 2 == 0 { 2.0 } else { 0.1 };
+            let x = envelope * f64::sin(TAU * 440.0 * n as f64 / 48_000.0);
+            let mut frame = [x, -0.5 * x];
+            limiter.process_frame(& mut frame);
+            assert!(frame[0].abs() <= ceiling + 1e-12 && frame[1].abs() <= ceiling + 1e-12);
+            output.push(frame[0]);
+        }
+        // The quiet parts are unchanged, once the gain is released.
+        let n = 4_000 * 3 + 2_000 + 1_900 + latency;
+        assert!((output[n] - 0.1 * f64::sin(TAU * 440.0 * (n - latency) as f64 / 48_000.0)).abs() < 0.01);
+    }
+}
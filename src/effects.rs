@@ -0,0 +1,15 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Effects made of the filters of this crate.
+//!              The presets module has ready-made chains, like the telephone and the
+//!              AM radio sounds, constructible by name.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+pub mod presets;
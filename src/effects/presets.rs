@@ -0,0 +1,237 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Ready-made effect chains, constructible by name.
+//!              Each preset is a chain of the filters of this crate, processed in
+//!              series:
+//!
+//!                 - telephone: the 300 Hz to 3400 Hz band of a telephone line, with
+//!                   4th order high-pass and low-pass edges.
+//!                 - am_radio: the 100 Hz to 4.5 kHz band of an AM receiver, with a
+//!                   presence peak and a soft saturation.
+//!                 - lofi: a dull band, 8 bit samples and a 4 times lower sample
+//!                   rate, without the anti-alias filter.
+//!                 - podcast: a 4th order rumble filter at 80 Hz and a de-esser, that
+//!                   lowers the band above 5 kHz when the sibilants are loud.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use crate::iir_filter::IIRFilter;
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::processing_chain::ProcessingChain;
+use crate::butterworth_filter::make_lowpass;
+use crate::butterworth_filter::make_highpass;
+use crate::butterworth_filter::make_peak;
+
+
+/// Names of the presets of make_preset().
+pub const PRESET_NAMES: [&str; 4] = ["telephone", "am_radio", "lofi", "podcast"];
+
+// Q of the two biquads of a 4th order Butterworth filter.
+const BUTTERWORTH_4_Q: [f64; 2] = [0.541_196_100_146_197, 1.306_562_964_876_376_7];
+
+/// A named chain of processing blocks.
+pub struct Preset {
+    name:   &'static str,
+    stages: ProcessingChain,
+}
+
+impl Preset {
+    pub fn get_name(& self) -> & str {
+        self.name
+    }
+
+    pub fn get_num_stages(& self) -> usize {
+        self.stages.get_num_stages()
+    }
+
+    /// The chain of the preset, to add or change its stages.
+    pub fn into_chain(self) -> ProcessingChain {
+        self.stages
+    }
+}
+
+impl ProcessingBlock for Preset {
+    fn process(& mut self, sample: f64) -> f64 {
+        self.stages.process(sample)
+    }
+
+    fn process_inplace(& mut self, samples: & mut [f64]) {
+        self.stages.process_inplace(samples)
+    }
+}
+
+/// Makes the preset with the name, one of PRESET_NAMES.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut telephone = make_preset("telephone", 48_000)?;
+///    >>> let output: Vec<f64> = voice.iter().map(|x| telephone.process(*x)).collect();
+///    ```
+///
+pub fn make_preset(name: & str, sample_rate: u32) -> Result<Preset, String> {
+    let mut stages: Vec<Box<dyn ProcessingBlock>> = Vec::new();
+    let name = match name {
+        "telephone" => {
+            stages.extend(make_butterworth_4(make_highpass, 300.0, sample_rate));
+            stages.extend(make_butterworth_4(make_lowpass, 3_400.0, sample_rate));
+            "telephone"
+        },
+        "am_radio" => {
+            stages.push(Box::new(make_highpass(100.0, sample_rate, None)));
+            stages.extend(make_butterworth_4(make_lowpass, 4_500.0, sample_rate));
+            stages.push(Box::new(make_peak(1_500.0, sample_rate, 4.0, Some(0.8))));
+            stages.push(Box::new(Saturator { drive: 2.0 }));
+            "am_radio"
+        },
+        "lofi" => {
+            stages.push(Box::new(make_highpass(150.0, sample_rate, None)));
+            stages.push(Box::new(make_lowpass(5_000.0, sample_rate, None)));
+            stages.push(Box::new(BitCrusher::new(8, 4)));
+            "lofi"
+        },
+        "podcast" => {
+            stages.extend(make_butterworth_4(make_highpass, 80.0, sample_rate));
+            stages.push(Box::new(DeEsser::new(sample_rate, 5_000.0, -30.0)));
+            "podcast"
+        },
+        _ => return Err(format!("Error: unknown preset \"{}\", the presets are {}", name, PRESET_NAMES.join(", "))),
+    };
+    Ok(Preset { name, stages: ProcessingChain::from_stages(stages) })
+}
+
+// The two biquads of a 4th order Butterworth low-pass or high-pass.
+fn make_butterworth_4(make_filter: fn(f64, u32, Option<f64>) -> IIRFilter, frequency: f64, sample_rate: u32)
+        -> Vec<Box<dyn ProcessingBlock>> {
+    BUTTERWORTH_4_Q.iter()
+        .map(|q| Box::new(make_filter(frequency, sample_rate, Some(*q))) as Box<dyn ProcessingBlock>)
+        .collect()
+}
+
+// Soft saturation, normalized so that a full scale sample stays at full scale.
+struct Saturator {
+    drive: f64,
+}
+
+impl ProcessingBlock for Saturator {
+    fn process(& mut self, sample: f64) -> f64 {
+        f64::tanh(self.drive * sample) / f64::tanh(self.drive)
+    }
+}
+
+// Lower bit depth and sample rate, by holding each sample.
+struct BitCrusher {
+    levels:  f64,
+    hold:    usize,
+    counter: usize,
+    held:    f64,
+}
+
+impl BitCrusher {
+    fn new(bits: u32, hold: usize) -> Self {
+        BitCrusher { levels: f64::powi(2.0, bits as i32 - 1), hold, counter: 0, held: 0.0 }
+    }
+}
+
+impl ProcessingBlock for BitCrusher {
+    fn process(& mut self, sample: f64) -> f64 {
+        if self.counter == 0 {
+            self.held = (sample * self.levels).round() / self.levels;
+        }
+        self.counter = (self.counter + 1) % self.hold;
+        self.held
+    }
+}
+
+// Splits the signal at the frequency, with the high band as the complement of the
+// low-pass, and lowers the high band when its envelope is above the threshold.
+struct DeEsser {
+    lowpass:         IIRFilter,
+    threshold:       f64,
+    envelope:        f64,
+    attack:          f64,
+    release:         f64,
+}
+
+impl DeEsser {
+    fn new(sample_rate: u32, frequency: f64, threshold_db: f64) -> Self {
+        let coefficient = |time_ms: f64| f64::exp(-1.0 / (time_ms / 1_000.0 * sample_rate as f64));
+        DeEsser {
+            lowpass: make_lowpass(frequency, sample_rate, None),
+            threshold: f64::powf(10.0, threshold_db / 20.0),
+            envelope: 0.0,
+            attack: coefficient(1.0),
+            release: coefficient(60.0),
+        }
+    }
+}
+
+impl ProcessingBlock for DeEsser {
+    fn process(& mut self, sample: f64) -> f64 {
+        let high = sample - self.lowpass.process(sample);
+        let level = high.abs();
+        let coefficient = if level > self.envelope { self.attack } else { self.release };
+        self.envelope = level + coefficient * (self.envelope - level);
+        // Limits the high band to the threshold.
+        let gain = if self.envelope > self.threshold { self.threshold / self.envelope } else { 1.0 };
+        sample - (1.0 - gain) * high
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::TAU;
+
+    // Amplitude of the output of a sine, after the transient.
+    fn sine_gain(preset: & mut Preset, frequency: f64, amplitude: f64) -> f64 {
+        let output: Vec<f64> = (0..24_000)
+            .map(|n| preset.process(amplitude * f64::sin(TAU * frequency * n as f64 / 48_000.0)))
+            .collect();
+        output[12_000..].iter().fold(0.0, |max: f64, y| max.max(y.abs())) / amplitude
+    }
+
+    #[test]
+    fn test_telephone_band() {
+        let gain_db = |frequency| {
+            let mut telephone = make_preset("telephone", 48_000).unwrap();
+            20.0 * f64::log10(sine_gain(& mut telephone, frequency, 0.5))
+        };
+        assert!(gain_db(1_000.0).abs() < 0.5);
+        assert!(gain_db(100.0) < -30.0);
+        assert!(gain_db(10_000.0) < -30.0);
+        assert!((gain_db(3_400.0) + 3.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_podcast_de_esser_and_rumble() {
+        let gain = |frequency, amplitude| sine_gain(& mut make_preset("podcast", 48_000).unwrap(), frequency, amplitude);
+        assert!((gain(1_000.0, 0.5) - 1.0).abs() < 0.02);
+        // A loud sibilant is lowered, a quiet one isn't.
+        // The output is then about the low band, the low-pass at 7 kHz.
+        assert!(gain(7_000.0, 0.5) < 0.6);
+        assert!((gain(7_000.0, 0.01) - 1.0).abs() < 0.02);
+        assert!(gain(30.0, 0.5) < 0.05);
+    }
+
+    #[test]
+    fn test_presets_by_name() {
+        for name in PRESET_NAMES {
+            let mut preset = make_preset(name, 44_100).unwrap();
+            assert_eq!(preset.get_name(), name);
+            assert!(preset.get_num_stages() >= 2);
+            assert!(preset.process(0.0).abs() < 1e-12);
+        }
+        let mut lofi = make_preset("lofi", 48_000).unwrap();
+        let output: Vec<f64> = (0..1_000).map(|n| lofi.process(0.5 * f64::sin(n as f64 * 0.01))).collect();
+        // 8 bit samples, each one held for 4 samples.
+        assert!(output.iter().all(|y| (y * 128.0).fract() == 0.0));
+        assert!(output.chunks(4).all(|c| c.iter().all(|y| *y == c[0])));
+        assert!(make_preset("radio", 48_000).is_err());
+    }
+}
@@ -0,0 +1,331 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: EQ matching, the correction that gives a target recording the
+//!              tonal balance of a reference recording, like the match EQ of the
+//!              mastering tools.
+//!              The average spectrum of each recording is its Welch PSD, see
+//!              welch.rs, and the correction is their difference, in dB, smoothed to
+//!              a fraction of an octave, see smoothing.rs:
+//!
+//!                 correction(f) = smooth(10 log10(PSD_reference(f) / PSD_target(f)))
+//!
+//!              The median of the correction from the lowest to the highest
+//!              frequency of the match, with the same weight for each octave, is the
+//!              level difference of the two recordings, not their tone, it is
+//!              removed and returned as the level offset. The median isn't moved by
+//!              a narrow peak or dip, like the mean would be. The correction is limited
+//!              to the maximum gain, and held at the values of the edges outside of
+//!              the frequencies of the match.
+//!              The correction is then realized as:
+//!
+//!                 - a FIR filter, by frequency sampling, the inverse FFT of the
+//!                   correction, with a Hann window, of linear phase or of minimum
+//!                   phase, see impulse_response.rs, for a convolver;
+//!                 - a ParametricEq of peak bands, placed one by one at the largest
+//!                   error of the correction still left, with the Q of the width of
+//!                   the error, and with the gains refined at the end, like the
+//!                   fit of the loudness compensation. The preamp takes the largest
+//!                   boost away. It can be saved as an Equalizer APO profile.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Frequency sampling method for FIR filter design - Wikipedia
+//!       https://en.wikipedia.org/wiki/Finite_impulse_response#Frequency_sampling_method
+//!
+
+
+use std::f64::consts::PI;
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+
+use crate::audio_buffer::AudioBuffer;
+use crate::welch::WelchConfig;
+use crate::welch::welch_psd;
+use crate::smoothing::Smoothing;
+use crate::smoothing::smooth_response;
+use crate::impulse_response::minimum_phase;
+use crate::parametric_eq::ParametricEq;
+use crate::parametric_eq::ParametricBand;
+use crate::parametric_eq::BandType;
+
+
+pub const DEFAULT_FFT_SIZE: usize = 8_192;
+pub const DEFAULT_MAX_GAIN_DB: f64 = 12.0;
+pub const DEFAULT_NUM_BANDS: usize = 10;
+
+// Points of the log frequency grid of the parametric fit.
+const FIT_GRID_POINTS: usize = 256;
+// The fit stops when the largest error left is below this gain.
+const MIN_BAND_GAIN_DB: f64 = 0.5;
+const MIN_BAND_Q: f64 = 0.3;
+const MAX_BAND_Q: f64 = 10.0;
+const REFINE_ITERATIONS: usize = 20;
+// The gain of a band after the refinement, the limit of the default DesignPolicy.
+const MAX_BAND_GAIN_DB: f64 = crate::design_policy::DEFAULT_MAX_GAIN_DB;
+// Floor of the PSDs, against the log of 0 in the silent bins.
+const MIN_POWER: f64 = 1e-30;
+
+/// The settings of the spectral analysis of the match.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EqMatchSettings {
+    /// The segment length of the Welch PSD, the frequency resolution.
+    pub fft_size:      usize,
+    pub smoothing:     Smoothing,
+    pub min_frequency: f64,
+    /// Limited to 0.95 of the Nyquist frequency.
+    pub max_frequency: f64,
+    pub max_gain_db:   f64,
+}
+
+impl EqMatchSettings {
+    /// 1/6 octave smoothing from 20 Hz to 20 kHz, at most 12 dB.
+    pub fn new() -> Self {
+        EqMatchSettings {
+            fft_size: DEFAULT_FFT_SIZE,
+            smoothing: Smoothing::Octave(6.0),
+            min_frequency: 20.0,
+            max_frequency: 20_000.0,
+            max_gain_db: DEFAULT_MAX_GAIN_DB,
+        }
+    }
+}
+
+impl Default for EqMatchSettings {
+    fn default() -> Self {
+        EqMatchSettings::new()
+    }
+}
+
+/// The correction of the target, at the frequencies of the FFT bins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EqMatchCurve {
+    pub sample_rate:     u32,
+    pub frequencies:     Vec<f64>,
+    pub gains_db:        Vec<f64>,
+    /// The level of the reference above the target, removed from the gains.
+    pub level_offset_db: f64,
+    pub min_frequency:   f64,
+    pub max_frequency:   f64,
+}
+
+/// The correction that matches the target signal to the reference signal, both at
+/// the sample rate.
+///
+/// In Rust:
+///    ```text
+///    >>> let curve = match_spectra(& reference, & target, 44_100, & EqMatchSettings::new())?;
+///    >>> let mut eq = curve.fit_parametric(DEFAULT_NUM_BANDS)?;
+///    ```
+///
+pub fn match_spectra(reference: & [f64], target: & [f64], sample_rate: u32, settings: & EqMatchSettings)
+        -> Result<EqMatchCurve, String> {
+    let max_frequency = f64::min(settings.max_frequency, 0.95 * sample_rate as f64 / 2.0);
+    if !(settings.min_frequency > 0.0 && settings.min_frequency < max_frequency) {
+        return Err(format!("Error: invalid match band from {} Hz to {} Hz", settings.min_frequency, max_frequency));
+    }
+    if settings.max_gain_db.is_nan() || settings.max_gain_db <= 0.0 {
+        return Err(format!("Error: invalid maximum gain {} dB", settings.max_gain_db));
+    }
+    let _span = trace_span!("match_spectra", reference = reference.len(), target = target.len());
+    let config = WelchConfig::new(sample_rate, settings.fft_size);
+    let reference_psd = welch_psd(reference, & config)?;
+    let target_psd = welch_psd(target, & config)?;
+    let frequencies = reference_psd.frequencies;
+    let difference_db: Vec<f64> = reference_psd.psd.iter().zip(& target_psd.psd)
+        .map(|(r, t)| 10.0 * f64::log10(f64::max(*r, MIN_POWER) / f64::max(*t, MIN_POWER)))
+        .collect();
+    let smoothed = smooth_response(& frequencies, & difference_db, settings.smoothing)?;
+
+    // The bins of the match, each one with the weight 1 / f, the same for each octave.
+    let in_band: Vec<usize> = (0..frequencies.len())
+        .filter(|k| frequencies[*k] >= settings.min_frequency && frequencies[*k] <= max_frequency)
+        .collect();
+    if in_band.is_empty() {
+        return Err(format!("Error: no frequency of the FFT of size {} from {} Hz to {} Hz",
+                           settings.fft_size, settings.min_frequency, max_frequency));
+    }
+    let mut sorted = in_band.clone();
+    sorted.sort_by(|a, b| smoothed[*a].total_cmp(& smoothed[*b]));
+    let half_weight = in_band.iter().map(|k| 1.0 / frequencies[*k]).sum::<f64>() / 2.0;
+    let mut weight = 0.0;
+    let median = sorted.iter()
+        .find(|k| { weight += 1.0 / frequencies[**k]; weight >= half_weight })
+        .unwrap_or(& sorted[sorted.len() - 1]);
+    let level_offset_db = smoothed[*median];
+
+    let (first, last) = (in_band[0], in_band[in_band.len() - 1]);
+    let gains_db = (0..frequencies.len())
+        .map(|k| (smoothed[k.clamp(first, last)] - level_offset_db).clamp(-settings.max_gain_db, settings.max_gain_db))
+        .collect();
+    Ok(EqMatchCurve {
+        sample_rate,
+        frequencies,
+        gains_db,
+        level_offset_db,
+        min_frequency: settings.min_frequency,
+        max_frequency,
+    })
+}
+
+/// The correction that matches the mixdown of the target to the mixdown of the
+/// reference.
+pub fn match_audio(reference: & AudioBuffer, target: & AudioBuffer, settings: & EqMatchSettings)
+        -> Result<EqMatchCurve, String> {
+    if reference.sample_rate != target.sample_rate {
+        return Err(format!("Error: the reference is at {} Hz and the target at {} Hz, resample one of them",
+                           reference.sample_rate, target.sample_rate));
+    }
+    match_spectra(& reference.mixdown(), & target.mixdown(), reference.sample_rate, settings)
+}
+
+/// The phase of the FIR filter of the correction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FirPhase {
+    /// Symmetric, with a delay of (length - 1) / 2 samples.
+    Linear,
+    /// Without the delay, with the phase of a minimum phase filter.
+    Minimum,
+}
+
+impl EqMatchCurve {
+    /// The correction at the frequency, interpolated between the bins.
+    pub fn get_gain_db(& self, frequency: f64) -> f64 {
+        let step = self.frequencies[1] - self.frequencies[0];
+        let position = (frequency / step).clamp(0.0, (self.frequencies.len() - 1) as f64);
+        let index = usize::min(position as usize, self.frequencies.len() - 2);
+        let t = position - index as f64;
+        self.gains_db[index] * (1.0 - t) + self.gains_db[index + 1] * t
+    }
+
+    /// The FIR filter of the correction, of odd length.
+    pub fn make_fir(& self, length: usize, phase: FirPhase) -> Result<Vec<f64>, String> {
+        if length < 3 || length.is_multiple_of(2) {
+            return Err(format!("Error: the FIR length must be odd and at least 3, got {}", length));
+        }
+        // The zero phase response on a grid finer than the filter.
+        let fft_size = 4 * length.next_power_of_two();
+        let mut spectrum: Vec<Complex<f64>> = (0..fft_size)
+            .map(|k| {
+                let bin = usize::min(k, fft_size - k);
+                let frequency = bin as f64 * self.sample_rate as f64 / fft_size as f64;
+                Complex::new(f64::powf(10.0, self.get_gain_db(frequency) / 20.0), 0.0)
+            })
+            .collect();
+        FftPlanner::<f64>::new().plan_fft_inverse(fft_size).process(& mut spectrum);
+        let half = (length / 2) as isize;
+        let fir: Vec<f64> = (-half..=half)
+            .map(|n| {
+                let window = 0.5 + 0.5 * f64::cos(PI * n as f64 / (half + 1) as f64);
+                spectrum[n.rem_euclid(fft_size as isize) as usize].re / fft_size as f64 * window
+            })
+            .collect();
+        match phase {
+            FirPhase::Linear => Ok(fir),
+            FirPhase::Minimum => minimum_phase(& fir, 8 * fft_size),
+        }
+    }
+
+    /// A ParametricEq of up to num_bands peak bands that follows the correction.
+    pub fn fit_parametric(& self, num_bands: usize) -> Result<ParametricEq, String> {
+        let ratio = self.max_frequency / self.min_frequency;
+        let grid: Vec<f64> = (0..FIT_GRID_POINTS)
+            .map(|i| self.min_frequency * f64::powf(ratio, i as f64 / (FIT_GRID_POINTS - 1) as f64))
+            .collect();
+        let target: Vec<f64> = grid.iter().map(|f| self.get_gain_db(*f)).collect();
+        let mut eq = ParametricEq::new(self.sample_rate);
+        for _ in 0..num_bands {
+            let residual: Vec<f64> = grid.iter().zip(& target).map(|(f, t)| t - eq.magnitude_db(*f)).collect();
+            let peak = (0..grid.len())
+                .fold(0, |best, i| if residual[i].abs() > residual[best].abs() { i } else { best });
+            if residual[peak].abs() < MIN_BAND_GAIN_DB {
+                break;
+            }
+            // The width where the error is above half of its peak, in dB.
+            let above_half = |i: usize| residual[i] * residual[peak].signum() > residual[peak].abs() / 2.0;
+            let (mut low, mut high) = (peak, peak);
+            while low > 0 && above_half(low - 1) {
+                low -= 1;
+            }
+            while high + 1 < grid.len() && above_half(high + 1) {
+                high += 1;
+            }
+            let octaves = f64::max(f64::log2(grid[high] / grid[low]), 1.0 / 12.0);
+            let q_factor = (f64::powf(2.0, octaves / 2.0) / (f64::powf(2.0, octaves) - 1.0)).clamp(MIN_BAND_Q, MAX_BAND_Q);
+            eq.add_band(ParametricBand::new(BandType::Peak, grid[peak], residual[peak], q_factor))?;
+        }
+
+        // The bands overlap, the gains are refined at the centers of the bands.
+        for _ in 0..REFINE_ITERATIONS {
+            for index in 0..eq.get_num_bands() {
+                let mut band = eq.get_band(index);
+                let error = self.get_gain_db(band.frequency) - eq.magnitude_db(band.frequency);
+                band.gain_db = (band.gain_db + error).clamp(-MAX_BAND_GAIN_DB, MAX_BAND_GAIN_DB);
+                eq.set_band(index, band)?;
+            }
+        }
+        let max_boost_db = grid.iter().fold(0.0, |max: f64, f| max.max(eq.magnitude_db(*f)));
+        eq.set_preamp_db(-max_boost_db);
+        Ok(eq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iir_filter::ProcessingBlock; // Trait
+    use crate::butterworth_filter::make_peak;
+    use crate::generators::make_white_noise;
+
+    // The magnitude in dB of the FIR filter at the frequency.
+    fn fir_gain_db(fir: & [f64], frequency: f64, sample_rate: u32) -> f64 {
+        let w = 2.0 * PI * frequency / sample_rate as f64;
+        let response: Complex<f64> = fir.iter().enumerate().map(|(n, h)| Complex::from_polar(*h, -w * n as f64)).sum();
+        20.0 * response.norm().log10()
+    }
+
+    #[test]
+    fn test_match_a_peak() {
+        // The target is the noise with a 6 dB peak at 1 kHz and 3 dB louder.
+        let reference = make_white_noise(240_000, 0.5, 1);
+        let mut peak = make_peak(1_000.0, 48_000, 6.0, Some(1.0));
+        let target: Vec<f64> = make_white_noise(240_000, 0.5, 2).iter().map(|x| 1.413 * peak.process(*x)).collect();
+        let curve = match_spectra(& reference, & target, 48_000, & EqMatchSettings::new()).unwrap();
+        assert!((curve.get_gain_db(1_000.0) + 6.0).abs() < 1.0, "{}", curve.get_gain_db(1_000.0));
+        assert!(curve.get_gain_db(100.0).abs() < 1.0 && curve.get_gain_db(10_000.0).abs() < 1.0);
+        assert!((curve.level_offset_db + 3.0).abs() < 1.0, "{}", curve.level_offset_db);
+
+        let eq = curve.fit_parametric(DEFAULT_NUM_BANDS).unwrap();
+        assert!(eq.get_num_bands() >= 1 && eq.get_num_bands() <= DEFAULT_NUM_BANDS);
+        let relative = |f: f64| eq.magnitude_db(f) - eq.get_preamp_db();
+        assert!((relative(1_000.0) + 6.0).abs() < 1.0, "{}", relative(1_000.0));
+        assert!(relative(100.0).abs() < 1.0 && relative(10_000.0).abs() < 1.0);
+
+        for phase in [FirPhase::Linear, FirPhase::Minimum] {
+            let fir = curve.make_fir(2_047, phase).unwrap();
+            assert_eq!(fir.len(), 2_047);
+            assert!((fir_gain_db(& fir, 1_000.0, 48_000) + 6.0).abs() < 1.0);
+            assert!(fir_gain_db(& fir, 10_000.0, 48_000).abs() < 1.0);
+        }
+        assert!(curve.make_fir(2_048, FirPhase::Linear).is_err());
+    }
+
+    #[test]
+    fn test_match_errors() {
+        let settings = EqMatchSettings::new();
+        let reference = AudioBuffer::new(48_000, vec![make_white_noise(20_000, 0.5, 1)]);
+        let target = AudioBuffer::new(44_100, vec![make_white_noise(20_000, 0.5, 2)]);
+        assert!(match_audio(& reference, & target, & settings).is_err());
+        // Identical spectra need no correction.
+        let curve = match_audio(& reference, & reference, & settings).unwrap();
+        assert!(curve.gains_db.iter().all(|g| g.abs() < 1e-9) && curve.level_offset_db.abs() < 1e-9);
+        assert_eq!(curve.fit_parametric(5).unwrap().get_num_bands(), 0);
+        let mut narrow = settings;
+        narrow.min_frequency = 30_000.0;
+        assert!(match_audio(& reference, & reference, & narrow).is_err());
+    }
+}
@@ -0,0 +1,477 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Parametric EQ profile files, import and export of a ParametricEq.
+//!
+//!              AutoEq publishes parametric correction profiles for thousands of
+//!              headphones, in a text format (ParametricEQ.txt) like:
+//!
+//!                 Preamp: -6.2 dB
+//!                 Filter 1: ON LSC Fc 105 Hz Gain 6.0 dB Q 0.70
+//!                 Filter 2: ON PK Fc 2404 Hz Gain -3.1 dB Q 1.41
+//!                 Filter 3: ON HSC Fc 10000 Hz Gain -2.5 dB Q 0.70
+//!
+//!              That is a subset of the Equalizer APO configuration syntax, that also
+//!              accepts filters without a number ("Filter: ON PK ...") and the
+//!              low-pass / high-pass types (LP, LPQ, HP, HPQ).
+//!
+//!              Room EQ Wizard (REW) exports the filters of a correction with a text
+//!              header and a line per filter, in the same token format:
+//!
+//!                 Filter  1: ON  PK       Fc   63.50 Hz  Gain  -9.30 dB  Q  4.186
+//!                 Filter  2: OFF None
+//!
+//!              REW filter files don't have a preamp.
+//!
+//!              An Equalizer APO configuration can also have a different correction
+//!              for each channel, to match the drivers of a pair of headphones or
+//!              two speakers. A Channel command selects the channels of the next
+//!              commands, by name or by number starting at 1, until the next Channel
+//!              command:
+//!
+//!                 Preamp: -4 dB
+//!                 Channel: L
+//!                 Filter 1: ON PK Fc 3100 Hz Gain -2.0 dB Q 2.0
+//!                 Channel: R
+//!                 Filter 2: ON PK Fc 2900 Hz Gain -3.5 dB Q 1.8
+//!                 Channel: all
+//!                 Filter 3: ON HSC Fc 8000 Hz Gain 1.5 dB Q 0.7
+//!
+//!              The names are L, R, C, SUB, RL, RR, SL and SR, of the channels of
+//!              the layout of the audio, see channel_layout.rs .
+//!
+//!              The filter types are PK (peak), LSC / LS (low-shelf), HSC / HS
+//!              (high-shelf), LP / LPQ (low-pass) and HP / HPQ (high-pass). When a
+//!              shelf or pass filter has no Q, Q = 1 / sqrt(2) is used.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. AutoEq - Automatic headphone equalization
+//!       https://github.com/jaakkopasanen/AutoEq
+//!
+//!    2. Equalizer APO - Configuration reference
+//!       https://sourceforge.net/p/equalizerapo/wiki/Configuration%20reference/
+//!
+//!    3. Room EQ Wizard - EQ filters
+//!       https://www.roomeqwizard.com/help/help_en-GB/html/eqfilters.html
+//!
+
+
+use crate::parametric_eq::ParametricEq;
+use crate::parametric_eq::ParametricBand;
+use crate::parametric_eq::BandType;
+use crate::channel_layout::ChannelLayout;
+use crate::channel_layout::ChannelRole;
+
+
+/// The content of a parametric EQ profile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EqProfile {
+    pub preamp_db: f64,
+    pub bands:     Vec<ParametricBand>,
+}
+
+impl EqProfile {
+    /// Creates a ParametricEq with the profile, at a sample rate.
+    pub fn to_parametric_eq(& self, sample_rate: u32) -> Result<ParametricEq, String> {
+        ParametricEq::from_bands(sample_rate, self.preamp_db, & self.bands)
+    }
+
+    /// The profile of the current settings of a ParametricEq.
+    pub fn from_parametric_eq(eq: & ParametricEq) -> Self {
+        EqProfile { preamp_db: eq.get_preamp_db(), bands: eq.get_bands().to_vec() }
+    }
+}
+
+/// Parses an AutoEq ParametricEQ.txt profile.
+pub fn parse_autoeq_profile(text: & str) -> Result<EqProfile, String> {
+    // AutoEq profiles are valid Equalizer APO configurations.
+    parse_equalizer_apo_config(text)
+}
+
+/// Reads an AutoEq ParametricEQ.txt file into a ParametricEq.
+pub fn load_autoeq_profile(path: & str, sample_rate: u32) -> Result<ParametricEq, String> {
+    parse_autoeq_profile(& read_file(path)?)?.to_parametric_eq(sample_rate)
+}
+
+/// Parses an Equalizer APO configuration with Preamp and Filter commands.
+/// Device selection lines are ignored, other commands (Channel, Include,
+/// Convolution, ...) are not supported and give an error. The configurations
+/// with Channel commands are read with parse_equalizer_apo_channels().
+pub fn parse_equalizer_apo_config(text: & str) -> Result<EqProfile, String> {
+    let mut profile = EqProfile { preamp_db: 0.0, bands: Vec::new() };
+    for (line_index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        let line_number = line_index + 1;
+        if line.is_empty() || line.starts_with('#') || line.starts_with("Device:") {
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("Preamp:") {
+            profile.preamp_db = parse_value_with_unit(value, "dB")
+                .map_err(|e| format!("Error: line {}: {}", line_number, e))?;
+        } else if line.starts_with("Filter") {
+            let band = parse_filter_line(line)
+                .map_err(|e| format!("Error: line {}: {}", line_number, e))?;
+            if let Some(band) = band {
+                profile.bands.push(band);
+            }
+        } else {
+            return Err(format!("Error: line {}: unsupported line \"{}\"", line_number, line));
+        }
+    }
+    Ok(profile)
+}
+
+/// Parses an Equalizer APO configuration with Channel commands into a profile per
+/// channel of the layout. The commands before the first Channel command are for
+/// all the channels, and the Preamp commands add up, as in Equalizer APO.
+///
+/// In Rust:
+///    ```text
+///    >>> let profiles = parse_equalizer_apo_channels(& text, ChannelLayout::Stereo)?;
+///    >>> let mut left_eq = profiles[0].to_parametric_eq(48_000)?;
+///    ```
+///
+pub fn parse_equalizer_apo_channels(text: & str, layout: ChannelLayout) -> Result<Vec<EqProfile>, String> {
+    let roles = layout.roles();
+    let mut profiles = vec![EqProfile { preamp_db: 0.0, bands: Vec::new() }; roles.len()];
+    let mut selected = vec![true; roles.len()];
+    for (line_index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        let line_number = line_index + 1;
+        if line.is_empty() || line.starts_with('#') || line.starts_with("Device:") {
+            continue;
+        }
+        if let Some(names) = line.strip_prefix("Channel:") {
+            selected = parse_channel_selection(names, & roles)
+                .map_err(|e| format!("Error: line {}: {}", line_number, e))?;
+        } else if let Some(value) = line.strip_prefix("Preamp:") {
+            let preamp_db = parse_value_with_unit(value, "dB")
+                .map_err(|e| format!("Error: line {}: {}", line_number, e))?;
+            for (profile, _) in profiles.iter_mut().zip(& selected).filter(|(_, selected)| **selected) {
+                profile.preamp_db += preamp_db;
+            }
+        } else if line.starts_with("Filter") {
+            let band = parse_filter_line(line)
+                .map_err(|e| format!("Error: line {}: {}", line_number, e))?;
+            if let Some(band) = band {
+                for (profile, _) in profiles.iter_mut().zip(& selected).filter(|(_, selected)| **selected) {
+                    profile.bands.push(band);
+                }
+            }
+        } else {
+            return Err(format!("Error: line {}: unsupported line \"{}\"", line_number, line));
+        }
+    }
+    Ok(profiles)
+}
+
+/// Writes a profile in the Equalizer APO configuration syntax, it's also a valid
+/// AutoEq ParametricEQ.txt profile.
+pub fn write_equalizer_apo_config(profile: & EqProfile) -> String {
+    let mut text = format!("Preamp: {} dB\n", profile.preamp_db);
+    for (index, band) in profile.bands.iter().enumerate() {
+        text += & format!("Filter {}: {} {}\n", index + 1, on_off(band), filter_definition(band));
+    }
+    text
+}
+
+/// Reads an Equalizer APO configuration file into a ParametricEq.
+pub fn load_equalizer_apo_config(path: & str, sample_rate: u32) -> Result<ParametricEq, String> {
+    parse_equalizer_apo_config(& read_file(path)?)?.to_parametric_eq(sample_rate)
+}
+
+/// Saves the settings of a ParametricEq as an Equalizer APO configuration file.
+pub fn save_equalizer_apo_config(path: & str, eq: & ParametricEq) -> Result<(), String> {
+    write_file(path, & write_equalizer_apo_config(& EqProfile::from_parametric_eq(eq)))
+}
+
+/// Parses a Room EQ Wizard filter settings text export.
+/// The header lines are skipped, as are the unused ("None") filters.
+pub fn parse_rew_filters(text: & str) -> Result<EqProfile, String> {
+    let mut profile = EqProfile { preamp_db: 0.0, bands: Vec::new() };
+    for (line_index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if !line.starts_with("Filter") || !line.contains(':') {
+            continue;
+        }
+        let band = parse_filter_line(line)
+            .map_err(|e| format!("Error: line {}: {}", line_index + 1, e))?;
+        if let Some(band) = band {
+            profile.bands.push(band);
+        }
+    }
+    Ok(profile)
+}
+
+/// Writes a profile as a Room EQ Wizard filter settings text file.
+/// REW has no preamp, so the preamp of the profile is lost.
+pub fn write_rew_filters(profile: & EqProfile) -> String {
+    let mut text = String::from("Filter Settings file\n\nEqualiser: Generic\n");
+    for (index, band) in profile.bands.iter().enumerate() {
+        text += & format!("Filter {:2}: {:<3} {}\n", index + 1, on_off(band), filter_definition(band));
+    }
+    text
+}
+
+/// Reads a Room EQ Wizard filter settings file into a ParametricEq.
+pub fn load_rew_filters(path: & str, sample_rate: u32) -> Result<ParametricEq, String> {
+    parse_rew_filters(& read_file(path)?)?.to_parametric_eq(sample_rate)
+}
+
+/// Saves the bands of a ParametricEq as a Room EQ Wizard filter settings file.
+pub fn save_rew_filters(path: & str, eq: & ParametricEq) -> Result<(), String> {
+    write_file(path, & write_rew_filters(& EqProfile::from_parametric_eq(eq)))
+}
+
+fn read_file(path: & str) -> Result<String, String> {
+    let _span = trace_span!("read_eq_profile", path);
+    std::fs::read_to_string(path).map_err(|e| format!("Error: reading profile file {}: {}", path, e))
+}
+
+fn write_file(path: & str, text: & str) -> Result<(), String> {
+    let _span = trace_span!("write_eq_profile", path);
+    std::fs::write(path, text).map_err(|e| format!("Error: writing profile file {}: {}", path, e))
+}
+
+fn on_off(band: & ParametricBand) -> & 'static str {
+    if band.enabled { "ON" } else { "OFF" }
+}
+
+// "PK Fc 2404 Hz Gain -3.1 dB Q 1.41"
+fn filter_definition(band: & ParametricBand) -> String {
+    match band.band_type {
+        BandType::Peak      => format!("PK Fc {} Hz Gain {} dB Q {}", band.frequency, band.gain_db, band.q_factor),
+        BandType::LowShelf  => format!("LSC Fc {} Hz Gain {} dB Q {}", band.frequency, band.gain_db, band.q_factor),
+        BandType::HighShelf => format!("HSC Fc {} Hz Gain {} dB Q {}", band.frequency, band.gain_db, band.q_factor),
+        BandType::LowPass   => format!("LPQ Fc {} Hz Q {}", band.frequency, band.q_factor),
+        BandType::HighPass  => format!("HPQ Fc {} Hz Q {}", band.frequency, band.q_factor),
+    }
+}
+
+// "-6.2 dB" -> -6.2
+fn parse_value_with_unit(text: & str, unit: & str) -> Result<f64, String> {
+    let text = text.trim();
+    let number = text.strip_suffix(unit).unwrap_or(text).trim();
+    number.parse::<f64>().map_err(|_| format!("invalid number \"{}\"", number))
+}
+
+// "Filter 1: ON PK Fc 2404 Hz Gain -3.1 dB Q 1.41", returns None for "None" filters.
+fn parse_filter_line(line: & str) -> Result<Option<ParametricBand>, String> {
+    let (_, definition) = line.split_once(':').ok_or("missing ':' after Filter")?;
+    let tokens: Vec<& str> = definition.split_whitespace().collect();
+    if tokens.len() < 2 {
+        return Err(format!("incomplete filter \"{}\"", line));
+    }
+    let enabled = match tokens[0] {
+        "ON"  => true,
+        "OFF" => false,
+        other => return Err(format!("expected ON or OFF, got \"{}\"", other)),
+    };
+    let (band_type, has_gain) = match tokens[1] {
+        "None"              => return Ok(None),
+        "PK" | "PEQ"        => (BandType::Peak, true),
+        "LSC" | "LS"        => (BandType::LowShelf, true),
+        "HSC" | "HS"        => (BandType::HighShelf, true),
+        "LP" | "LPQ"        => (BandType::LowPass, false),
+        "HP" | "HPQ"        => (BandType::HighPass, false),
+        other => return Err(format!("unsupported filter type \"{}\"", other)),
+    };
+    let frequency = find_parameter(& tokens, "Fc")?.ok_or("missing Fc")?;
+    let gain_db = if has_gain {
+        find_parameter(& tokens, "Gain")?.ok_or("missing Gain")?
+    } else {
+        0.0
+    };
+    let q_factor = match (band_type, find_parameter(& tokens, "Q")?) {
+        (_, Some(q_factor))  => q_factor,
+        (BandType::Peak, None) => return Err("missing Q".to_string()),
+        (_, None)            => 1.0 / f64::sqrt(2.0),
+    };
+
+    let mut band = ParametricBand::new(band_type, frequency, gain_db, q_factor);
+    band.enabled = enabled;
+    Ok(Some(band))
+}
+
+// "L R" or "1 3" or "all" -> the selected channels of the roles.
+fn parse_channel_selection(names: & str, roles: & [ChannelRole]) -> Result<Vec<bool>, String> {
+    let mut selected = vec![false; roles.len()];
+    for name in names.split_whitespace() {
+        if name.eq_ignore_ascii_case("all") {
+            selected.iter_mut().for_each(|s| *s = true);
+            continue;
+        }
+        let index = match name.parse::<usize>() {
+            Ok(number) if number >= 1 && number <= roles.len() => Some(number - 1),
+            Ok(_) => None,
+            Err(_) => {
+                let role = match name {
+                    "L"         => ChannelRole::Left,
+                    "R"         => ChannelRole::Right,
+                    "C"         => ChannelRole::Center,
+                    "SUB"       => ChannelRole::Lfe,
+                    "RL" | "SL" => ChannelRole::SurroundLeft,
+                    "RR" | "SR" => ChannelRole::SurroundRight,
+                    _ => return Err(format!("unknown channel \"{}\"", name)),
+                };
+                roles.iter().position(|r| *r == role)
+            },
+        };
+        let index = index.ok_or(format!("the audio has no channel \"{}\"", name))?;
+        selected[index] = true;
+    }
+    if !selected.contains(& true) {
+        return Err("Channel without channels".to_string());
+    }
+    Ok(selected)
+}
+
+// The number after a parameter name, "Fc 2404 Hz" -> Some(2404.0) .
+fn find_parameter(tokens: & [& str], name: & str) -> Result<Option<f64>, String> {
+    match tokens.iter().position(|t| *t == name) {
+        None => Ok(None),
+        Some(index) => {
+            let value = tokens.get(index + 1).ok_or(format!("missing value of {}", name))?;
+            let value = value.parse::<f64>().map_err(|_| format!("invalid {} value \"{}\"", name, value))?;
+            Ok(Some(value))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::temp_test_path;
+
+    const PROFILE: & str = "Preamp: -6.2 dB
+Filter 1: ON LSC Fc 105 Hz Gain 6.0 dB Q 0.70
+Filter 2: ON PK Fc 2404 Hz Gain -3.1 dB Q 1.41
+Filter 3: OFF PK Fc 5000 Hz Gain 2.0 dB Q 2.00
+Filter 4: ON HSC Fc 10000 Hz Gain -2.5 dB Q 0.70
+";
+
+    #[test]
+    fn test_parse_autoeq_profile() {
+        let profile = parse_autoeq_profile(PROFILE).unwrap();
+        assert_eq!(profile.preamp_db, -6.2);
+        assert_eq!(profile.bands.len(), 4);
+        assert_eq!(profile.bands[0], ParametricBand::new(BandType::LowShelf, 105.0, 6.0, 0.7));
+        assert_eq!(profile.bands[1].band_type, BandType::Peak);
+        assert_eq!(profile.bands[1].gain_db, -3.1);
+        assert!(!profile.bands[2].enabled);
+        assert_eq!(profile.bands[3].band_type, BandType::HighShelf);
+
+        let eq = profile.to_parametric_eq(48_000).unwrap();
+        assert_eq!(eq.get_num_bands(), 4);
+        assert_eq!(eq.get_preamp_db(), -6.2);
+    }
+
+    #[test]
+    fn test_parse_autoeq_profile_errors() {
+        assert!(parse_autoeq_profile("Preamp: loud dB").is_err());
+        assert!(parse_autoeq_profile("Filter 1: ON XX Fc 100 Hz Gain 1 dB Q 1").is_err());
+        assert!(parse_autoeq_profile("Filter 1: ON PK Fc 100 Hz Q 1").is_err());
+        let error = parse_autoeq_profile("Preamp: 0 dB\nFilter 1: MAYBE PK").unwrap_err();
+        assert!(error.contains("line 2"));
+    }
+
+    #[test]
+    fn test_load_autoeq_profile() {
+        let path = & temp_test_path("autoeq_profile.txt");
+        std::fs::write(path, PROFILE).unwrap();
+        let eq = load_autoeq_profile(path, 44_100).unwrap();
+        assert_eq!(eq.get_num_bands(), 4);
+        let _ = std::fs::remove_file(path);
+        assert!(load_autoeq_profile("/non/existent/profile.txt", 44_100).is_err());
+    }
+
+    #[test]
+    fn test_equalizer_apo_config_round_trip() {
+        let config = "Device: Speakers
+# Correction
+Preamp: -3 dB
+Filter: ON LP Fc 18000 Hz
+Filter: ON HPQ Fc 25 Hz Q 0.5
+Filter 3: ON LS Fc 100 Hz Gain 2.5 dB
+Filter 4: ON PK Fc 1000 Hz Gain -1.5 dB Q 2
+";
+        let profile = parse_equalizer_apo_config(config).unwrap();
+        assert_eq!(profile.preamp_db, -3.0);
+        assert_eq!(profile.bands.len(), 4);
+        assert_eq!(profile.bands[0].band_type, BandType::LowPass);
+        assert!((profile.bands[0].q_factor - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-12);
+        assert_eq!(profile.bands[1], ParametricBand::new(BandType::HighPass, 25.0, 0.0, 0.5));
+        assert_eq!(profile.bands[2].gain_db, 2.5);
+
+        let text = write_equalizer_apo_config(& profile);
+        assert_eq!(parse_equalizer_apo_config(& text).unwrap(), profile);
+        assert!(parse_equalizer_apo_config("Channel: L\nFilter: ON PK Fc 1 Hz Gain 1 dB Q 1").is_err());
+    }
+
+    #[test]
+    fn test_equalizer_apo_channels() {
+        let config = "Preamp: -4 dB
+Channel: L
+Filter 1: ON PK Fc 3100 Hz Gain -2.0 dB Q 2.0
+Channel: R
+Preamp: -1 dB
+Filter 2: ON PK Fc 2900 Hz Gain -3.5 dB Q 1.8
+Channel: all
+Filter 3: ON HSC Fc 8000 Hz Gain 1.5 dB Q 0.7
+";
+        let profiles = parse_equalizer_apo_channels(config, ChannelLayout::Stereo).unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].preamp_db, -4.0);
+        assert_eq!(profiles[1].preamp_db, -5.0);
+        assert_eq!(profiles[0].bands[0].frequency, 3_100.0);
+        assert_eq!(profiles[1].bands[0].frequency, 2_900.0);
+        assert!(profiles.iter().all(|profile| profile.bands.len() == 2 && profile.bands[1].band_type == BandType::HighShelf));
+        // Without Channel commands every channel has the profile.
+        let profiles = parse_equalizer_apo_channels(PROFILE, ChannelLayout::Surround51).unwrap();
+        assert!(profiles.iter().all(|profile| * profile == parse_autoeq_profile(PROFILE).unwrap()));
+
+        let profiles = parse_equalizer_apo_channels("Channel: SUB 1\nPreamp: -3 dB", ChannelLayout::Surround51).unwrap();
+        let preamps: Vec<f64> = profiles.iter().map(|profile| profile.preamp_db).collect();
+        assert_eq!(preamps, [-3.0, 0.0, 0.0, -3.0, 0.0, 0.0]);
+        assert!(parse_equalizer_apo_channels("Channel: C", ChannelLayout::Stereo).is_err());
+        assert!(parse_equalizer_apo_channels("Channel: 3", ChannelLayout::Stereo).is_err());
+        assert!(parse_equalizer_apo_channels("Channel: X", ChannelLayout::Stereo).is_err());
+    }
+
+    #[test]
+    fn test_rew_filters_round_trip() {
+        let rew = "Filter Settings file
+
+Room EQ V5.20
+Dated: Mar 1, 2021 2:46:20 PM
+
+Notes:
+
+Equaliser: Generic
+Filter  1: ON  PK       Fc   63.50 Hz  Gain  -9.30 dB  Q  4.186
+Filter  2: ON  PK       Fc   103.0 Hz  Gain  -7.00 dB  Q  5.000
+Filter  3: OFF None
+Filter  4: ON  LS       Fc   100.0 Hz  Gain   3.00 dB
+";
+        let profile = parse_rew_filters(rew).unwrap();
+        assert_eq!(profile.bands.len(), 3);
+        assert_eq!(profile.bands[0], ParametricBand::new(BandType::Peak, 63.5, -9.3, 4.186));
+        assert_eq!(profile.bands[2].band_type, BandType::LowShelf);
+
+        let text = write_rew_filters(& profile);
+        assert_eq!(parse_rew_filters(& text).unwrap(), profile);
+
+        // Through files and a ParametricEq.
+        let eq = profile.to_parametric_eq(48_000).unwrap();
+        let path = & temp_test_path("rew_filters.txt");
+        save_rew_filters(path, & eq).unwrap();
+        let eq_loaded = load_rew_filters(path, 48_000).unwrap();
+        assert_eq!(eq_loaded.get_bands(), eq.get_bands());
+        let _ = std::fs::remove_file(path);
+    }
+}
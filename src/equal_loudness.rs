@@ -0,0 +1,179 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: ISO 226:2003 equal-loudness contours and loudness compensation EQ.
+//!              The ear is less sensitive to low (and very high) frequencies at low
+//!              listening levels. A mix made at a reference level of, say, 80 phon
+//!              sounds thin when played at 50 phon. The "loudness" button of the
+//!              old hi-fi amplifiers compensates this with an EQ curve equal to the
+//!              difference between the equal-loudness contours of the two levels.
+//!
+//!              make_loudness_compensation() builds that EQ with the 10 band
+//!              octave equalizer, fitting the band gains to the contour difference.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. ISO 226:2003 Acoustics - Normal equal-loudness-level contours
+//!
+//!    2. Equal-loudness contour - Wikipedia
+//!       https://en.wikipedia.org/wiki/Equal-loudness_contour
+//!
+
+
+use crate::equalizer::Equalizer;
+use crate::exact_math;
+use crate::iso_bands::OCTAVE_NOMINAL_FREQUENCIES;
+
+
+// ISO 226:2003 table 1, frequencies and the parameters of the contour formula.
+const ISO_226_FREQUENCIES: [f64; 29] = [
+    20.0, 25.0, 31.5, 40.0, 50.0, 63.0, 80.0, 100.0, 125.0, 160.0, 200.0, 250.0, 315.0, 400.0,
+    500.0, 630.0, 800.0, 1000.0, 1250.0, 1600.0, 2000.0, 2500.0, 3150.0, 4000.0, 5000.0,
+    6300.0, 8000.0, 10000.0, 12500.0];
+
+// Exponent for loudness perception.
+const ISO_226_AF: [f64; 29] = [
+    0.532, 0.506, 0.480, 0.455, 0.432, 0.409, 0.387, 0.367, 0.349, 0.330, 0.315, 0.301, 0.288,
+    0.276, 0.267, 0.259, 0.253, 0.250, 0.246, 0.244, 0.243, 0.243, 0.243, 0.242, 0.242, 0.245,
+    0.254, 0.271, 0.301];
+
+// Magnitude of the linear transfer function normalized at 1000 Hz, in dB.
+const ISO_226_LU: [f64; 29] = [
+    -31.6, -27.2, -23.0, -19.1, -15.9, -13.0, -10.3, -8.1, -6.2, -4.5, -3.1, -2.0, -1.1, -0.4,
+    0.0, 0.3, 0.5, 0.0, -2.7, -4.1, -1.0, 1.7, 2.5, 1.2, -2.1, -7.1, -11.2, -10.7, -3.1];
+
+// Threshold of hearing, in dB SPL.
+const ISO_226_TF: [f64; 29] = [
+    78.5, 68.7, 59.5, 51.1, 44.0, 37.5, 31.5, 26.5, 22.1, 17.9, 14.4, 11.4, 8.6, 6.2, 4.4, 3.0,
+    2.2, 2.4, 3.5, 1.7, -1.3, -4.2, -6.0, -5.4, -1.5, 6.0, 12.6, 13.9, 12.3];
+
+/// Sound pressure level in dB SPL of the ISO 226:2003 table frequencies, for a
+/// loudness level in phon. The standard is defined from 20 to 90 phon (and 20 to
+/// 80 phon above 4 kHz), outside of it the values are an extrapolation.
+pub fn equal_loudness_contour(phon: f64) -> Vec<(f64, f64)> {
+    (0..ISO_226_FREQUENCIES.len()).map(|i| {
+        let af = ISO_226_AF[i];
+        let lu = ISO_226_LU[i];
+        let tf = ISO_226_TF[i];
+        let big_af = 4.47e-3 * (exact_math::powf(10.0, 0.025 * phon) - 1.15)
+                   + exact_math::powf(0.4 * exact_math::powf(10.0, (tf + lu) / 10.0 - 9.0), af);
+        let spl = (10.0 / af) * exact_math::log10(big_af) - lu + 94.0;
+        (ISO_226_FREQUENCIES[i], spl)
+    }).collect()
+}
+
+/// Sound pressure level of the equal-loudness contour at any frequency, linearly
+/// interpolated in log frequency between the table values. Outside of the table
+/// range (20 Hz to 12.5 kHz) the nearest value is used.
+pub fn equal_loudness_spl(frequency: f64, phon: f64) -> f64 {
+    let contour = equal_loudness_contour(phon);
+    let (first_freq, first_spl) = contour[0];
+    let (last_freq, last_spl) = contour[contour.len() - 1];
+    if frequency <= first_freq {
+        return first_spl;
+    }
+    if frequency >= last_freq {
+        return last_spl;
+    }
+    for pair in contour.windows(2) {
+        let (f0, spl0) = pair[0];
+        let (f1, spl1) = pair[1];
+        if frequency <= f1 {
+            let t = exact_math::ln(frequency / f0) / exact_math::ln(f1 / f0);
+            return spl0 + t * (spl1 - spl0);
+        }
+    }
+    last_spl
+}
+
+/// Gain in dB that makes audio played at the listening level sound with the tonal
+/// balance that it has at the reference level. It's 0 dB at 1 kHz.
+pub fn loudness_compensation_gain_db(frequency: f64, reference_phon: f64, listening_phon: f64) -> f64 {
+    equal_loudness_spl(frequency, listening_phon) - equal_loudness_spl(frequency, reference_phon)
+        + (reference_phon - listening_phon)
+}
+
+/// Creates a 10 band octave equalizer that approximates the loudness compensation
+/// from the reference level to the listening level, both in phon.
+///
+/// The bands of the equalizer overlap, so the band gains are fitted iteratively
+/// until the response at the band centers matches the compensation curve.
+///
+/// In Rust:
+///    ```text
+///    >>> // Mixed at 80 phon, listening at 50 phon.
+///    >>> let mut eq = make_loudness_compensation(48_000, 80.0, 50.0).unwrap();
+///    >>> let output = eq.process(sample);
+///    ```
+///
+pub fn make_loudness_compensation(sample_rate: u32, reference_phon: f64, listening_phon: f64) -> Result<Equalizer, String> {
+    if !(0.0..=100.0).contains(&reference_phon) || !(0.0..=100.0).contains(&listening_phon) {
+        return Err(format!("Error: invalid loudness levels {} and {} phon, must be in the interval [0, 100]",
+                           reference_phon, listening_phon));
+    }
+    let nyquist = sample_rate as f64 / 2.0;
+    let bands_vec: Vec<f64> = OCTAVE_NOMINAL_FREQUENCIES.iter().cloned().filter(|f| *f < 0.9 * nyquist).collect();
+
+    let gain_max_db =  30.0; // dB
+    let gain_min_db = -30.0; // dB
+    // One octave bandwidth.
+    let q_factor = f64::sqrt(2.0);
+    let mut eq = Equalizer::new(sample_rate, & bands_vec, gain_max_db, gain_min_db, q_factor);
+
+    let targets: Vec<f64> = bands_vec.iter()
+        .map(|f| loudness_compensation_gain_db(*f, reference_phon, listening_phon))
+        .collect();
+    let mut gains = targets.clone();
+    let num_iterations = 20;
+    for _ in 0..num_iterations {
+        for (index, gain_db) in gains.iter().enumerate() {
+            eq.set_band_gain(index, gain_db.clamp(gain_min_db, gain_max_db))?;
+        }
+        for (index, gain_db) in gains.iter_mut().enumerate() {
+            let error = targets[index] - eq.magnitude_db(bands_vec[index]);
+            *gain_db += 0.7 * error;
+        }
+    }
+
+    Ok(eq)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equal_loudness_contour_is_phon_at_1khz() {
+        for phon in [20.0, 40.0, 60.0, 80.0] {
+            let spl = equal_loudness_spl(1000.0, phon);
+            assert!((spl - phon).abs() < 0.1);
+        }
+        // Values from the ISO 226:2003 40 phon contour.
+        assert!((equal_loudness_spl(100.0, 40.0) - 64.4).abs() < 0.2);
+        assert!((equal_loudness_spl(20.0, 40.0) - 99.9).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_loudness_compensation_boosts_bass() {
+        let gain_100 = loudness_compensation_gain_db(100.0, 80.0, 50.0);
+        assert!(loudness_compensation_gain_db(1000.0, 80.0, 50.0).abs() < 0.1);
+        assert!(gain_100 > 5.0);
+        assert_eq!(loudness_compensation_gain_db(100.0, 60.0, 60.0), 0.0);
+    }
+
+    #[test]
+    fn test_make_loudness_compensation_matches_curve() {
+        let sample_rate = 48_000;
+        let eq = make_loudness_compensation(sample_rate, 80.0, 50.0).unwrap();
+        for frequency in [63.0, 125.0, 250.0, 1000.0, 4000.0] {
+            let target = loudness_compensation_gain_db(frequency, 80.0, 50.0);
+            let gain = eq.magnitude_db(frequency);
+            assert!((gain - target).abs() < 1.0);
+        }
+        assert!(make_loudness_compensation(sample_rate, 120.0, 50.0).is_err());
+    }
+}
@@ -1,109 +1,116 @@
-/// Project: Audio filters in Rust
-/// Date:    2021.12.05
-/// Author of the port: João Nuno Carvalho
-///
-/// Description: Audio or DSP filters, allow you to attenuate or accentuate some frequencies
-///              or range of frequencies in a signal. The signal can be of any kind, but in
-///              here, we will focus on 1D signals. Like audio signals.
-///              There can also occur differences in the signal phases, that vary with the
-///              filter and the frequency components of the signal.  
-///              This is a port of Audio filters, from Python to Rust,
-///              from the Audio filter from TheAlgorithms GitHub in Python. That is by it
-///              self a port from WebAudio API implementation of the same common
-///              filters in the browsers.
-/// 
-///              The following filters are implemented over a BiQuad IIR filter:
-///                 -low-pass
-///                 -high-pass
-///                 -band-pass
-///                 -all-pass
-///                 -peak
-///                 -low-shelf
-///                 -high-shelf 
-///                 -notch
-///                 -10 band equalizer
-///  
-/// License: MIT Open Source License, like the original license from
-///    GitHub - TheAlgorithms / Python / audio_filters
-///    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
-///
-/// How to run the code. 
-/// 
-/// To make a project for this files do:
-///     -Install Rust your computer (Linux, Win, Mac, Raspberry Pi).
-///     
-///     cargo new audio_filters_in_rust
-///     cd audio_filters_in_rust
-///     
-///     -Copy the repository files to this directory and overlap them.
-/// 
-/// To compile do:
-///     cargo build --release
-/// 
-/// To run do:
-///     cargo run --release
-/// 
-/// to run the tests do:
-///     cargo test
-/// 
-/// References:
-///    1. GitHub - TheAlgorithms / Python / audio_filters
-///       https://github.com/TheAlgorithms/Python/tree/master/audio_filters
-///
-///    2. WebAudio - Cookbook formulae for audio equalizer biquad filter coefficients
-///       https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html 
-/// 
-///    3. Good resources on DSP – Digital Signal Programming
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_electronics#dsp--digital-signal-programming
-///
-///    4. Biquads - EarLevel
-///       http://www.earlevel.com/main/2003/02/28/biquads/
-///
-///    5. Biquad C++ source code - EarLevel
-///       https://www.earlevel.com/main/2012/11/26/biquad-c-source-code/
-///
-///    6. A biquad calculator V3 - EarLevel
-///       https://www.earlevel.com/main/2021/09/02/biquad-calculator-v3/
-/// 
-///    7. WebAudio API - Mozilla Docs
-///       https://developer.mozilla.org/en-US/docs/Web/API/Web_Audio_API
-/// 
-///    8. Audio Filters - Theory and Practice
-///       by Ethan Winer
-///       http://ethanwiner.com/filters.html
-/// 
-///    9. Audio filter - Wikipedia
-///       https://en.wikipedia.org/wiki/Audio_filter
-/// 
-///   10. Electronic filter - Wikipedia
-///       https://en.wikipedia.org/wiki/Electronic_filter
-///
-///   11. How to learn modern Rust
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
-///
-/// 
-/// 10 Band Equalizer
-/// 
-///   12. Making an EQ from cascading filters
-///       https://dsp.stackexchange.com/questions/10309/making-an-eq-from-cascading-filters
-/// 
-///   13. PEAK/NOTCH FILTER DESIGN
-///       https://www.dsprelated.com/showcode/169.php
-/// 
-///   14. The Equivalence of Various Methods of Computing
-///       Biquad Coefficients for Audio Parametric Equalizers
-///       http://www.thesounddesign.com/MIO/EQ-Coefficients.pdf
-///
-///   15. How to learn modern Rust
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
-///
+//! Project: Audio filters in Rust
+//! Date:    2021.12.05
+//! Author of the port: João Nuno Carvalho
+//!
+//! Description: Audio or DSP filters, allow you to attenuate or accentuate some frequencies
+//!              or range of frequencies in a signal. The signal can be of any kind, but in
+//!              here, we will focus on 1D signals. Like audio signals.
+//!              There can also occur differences in the signal phases, that vary with the
+//!              filter and the frequency components of the signal.  
+//!              This is a port of Audio filters, from Python to Rust,
+//!              from the Audio filter from TheAlgorithms GitHub in Python. That is by it
+//!              self a port from WebAudio API implementation of the same common
+//!              filters in the browsers.
+//! 
+//!              The following filters are implemented over a BiQuad IIR filter:
+//!                 -low-pass
+//!                 -high-pass
+//!                 -band-pass
+//!                 -all-pass
+//!                 -peak
+//!                 -low-shelf
+//!                 -high-shelf 
+//!                 -notch
+//!                 -10 band equalizer
+//!  
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! How to run the code. 
+//! 
+//! To make a project for this files do:
+//!     -Install Rust your computer (Linux, Win, Mac, Raspberry Pi).
+//!     
+//!     cargo new audio_filters_in_rust
+//!     cd audio_filters_in_rust
+//!     
+//!     -Copy the repository files to this directory and overlap them.
+//! 
+//! To compile do:
+//!     cargo build --release
+//! 
+//! To run do:
+//!     cargo run --release
+//! 
+//! to run the tests do:
+//!     cargo test
+//! 
+//! References:
+//!    1. GitHub - TheAlgorithms / Python / audio_filters
+//!       https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//!    2. WebAudio - Cookbook formulae for audio equalizer biquad filter coefficients
+//!       https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html 
+//! 
+//!    3. Good resources on DSP – Digital Signal Programming
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_electronics#dsp--digital-signal-programming
+//!
+//!    4. Biquads - EarLevel
+//!       http://www.earlevel.com/main/2003/02/28/biquads/
+//!
+//!    5. Biquad C++ source code - EarLevel
+//!       https://www.earlevel.com/main/2012/11/26/biquad-c-source-code/
+//!
+//!    6. A biquad calculator V3 - EarLevel
+//!       https://www.earlevel.com/main/2021/09/02/biquad-calculator-v3/
+//! 
+//!    7. WebAudio API - Mozilla Docs
+//!       https://developer.mozilla.org/en-US/docs/Web/API/Web_Audio_API
+//! 
+//!    8. Audio Filters - Theory and Practice
+//!       by Ethan Winer
+//!       http://ethanwiner.com/filters.html
+//! 
+//!    9. Audio filter - Wikipedia
+//!       https://en.wikipedia.org/wiki/Audio_filter
+//! 
+//!   10. Electronic filter - Wikipedia
+//!       https://en.wikipedia.org/wiki/Electronic_filter
+//!
+//!   11. How to learn modern Rust
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
+//!
+//! 
+//! 10 Band Equalizer
+//! 
+//!   12. Making an EQ from cascading filters
+//!       https://dsp.stackexchange.com/questions/10309/making-an-eq-from-cascading-filters
+//! 
+//!   13. PEAK/NOTCH FILTER DESIGN
+//!       https://www.dsprelated.com/showcode/169.php
+//! 
+//!   14. The Equivalence of Various Methods of Computing
+//!       Biquad Coefficients for Audio Parametric Equalizers
+//!       http://www.thesounddesign.com/MIO/EQ-Coefficients.pdf
+//!
+//!   15. How to learn modern Rust
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
+//!
 
 
 use crate::iir_filter::ProcessingBlock; // Trait
 use crate::iir_filter::IIRFilter;
 use crate::butterworth_filter::make_peak_eq_constant_q;
+use crate::iso_bands::BandWidth;
+use crate::iso_bands::iso_bands_below;
+use crate::smoothed_biquad::RampShape;
+use crate::smoothed_biquad::SmoothedBiquad;
 
+use rustfft::num_complex::Complex;
 
+
+#[derive(Clone)]
 pub struct Equalizer {
     sample_rate:     u32,
     bands_vec:       Vec<f64>,
@@ -112,21 +119,24 @@ pub struct Equalizer {
     gain_min_db:     f64,
     q_factor:        f64,
     iir_filters_vec: Vec<IIRFilter>,
+    // The bands that play with the smoothing of the gain changes, empty without it.
+    smoothed_filters_vec: Vec<SmoothedBiquad>,
 }
 
 impl Equalizer {
-    pub fn new(sample_rate: u32, bands_vec: & Vec<f64>,
+    pub fn new(sample_rate: u32, bands_vec: & [f64],
            gain_max_db:f64, gain_min_db:f64,
            q_factor:f64
            ) -> Self {
         let mut equalizer = Equalizer{
             sample_rate,
-            bands_vec: bands_vec.clone(),
+            bands_vec: bands_vec.to_vec(),
             bands_gain_vec: vec![0.0; bands_vec.len()],
             gain_max_db,
             gain_min_db,
             q_factor,
-            iir_filters_vec: Vec::with_capacity(bands_vec.len())
+            iir_filters_vec: Vec::with_capacity(bands_vec.len()),
+            smoothed_filters_vec: Vec::new(),
         };
         equalizer.gen_chain_filters();
 
@@ -156,6 +166,18 @@ impl Equalizer {
         // now we are applying to the actual filter that is in the filter chain,
         // only changing the coefficients.
         let _ = self.iir_filters_vec[index].set_coefficients(& iir_filter_tmp.a_coeffs, & iir_filter_tmp.b_coeffs);
+        // With the smoothing, the band ramps to the new coefficients without a click.
+        if let Some(smoothed_filter) = self.smoothed_filters_vec.get_mut(index) {
+            let _ = smoothed_filter.set_target(& iir_filter_tmp);
+        }
+    }
+
+    pub fn get_sample_rate(& self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn get_num_bands(& self) -> usize {
+        self.bands_vec.len()
     }
 
     pub fn get_bands_freq(& self, index: usize) -> f64 {
@@ -175,17 +197,68 @@ impl Equalizer {
                        gain_db, self.gain_min_db, self.gain_max_db));
         }
         self.bands_gain_vec[index] = gain_db;
+        trace_event!(index, gain_db, "equalizer band gain updated");
         self.change_filter(index);
         
         Ok(())
     }
 
+    /// Smooths the changes of set_band_gain() over a ramp of ramp_ms milliseconds,
+    /// against the zipper noise of a gain knob, see smoothed_biquad.rs . A ramp of
+    /// 0 ms turns the smoothing off. The bands run as lattice filters with the
+    /// smoothing, so turn it on or off before the processing, the state of the
+    /// filters doesn't go from one form to the other.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let mut eq = Equalizer::make_equalizer_10_band(48_000);
+    ///    >>> eq.set_smoothing(20.0, RampShape::Linear)?;
+    ///    >>> eq.set_band_gain(5, -6.0)?;   // while it plays
+    ///    ```
+    ///
+    pub fn set_smoothing(& mut self, ramp_ms: f64, shape: RampShape) -> Result<(), String> {
+        if ramp_ms == 0.0 {
+            self.smoothed_filters_vec.clear();
+            return Ok(());
+        }
+        if self.smoothed_filters_vec.is_empty() {
+            self.smoothed_filters_vec = self.iir_filters_vec.iter()
+                .map(|filter| SmoothedBiquad::new(filter, self.sample_rate, ramp_ms, shape))
+                .collect::<Result<Vec<SmoothedBiquad>, String>>()?;
+            return Ok(());
+        }
+        for smoothed_filter in & mut self.smoothed_filters_vec {
+            smoothed_filter.set_ramp(ramp_ms, shape)?;
+        }
+        Ok(())
+    }
+
+    /// The ramp of the smoothing in milliseconds, 0 without the smoothing.
+    pub fn get_smoothing_ms(& self) -> f64 {
+        self.smoothed_filters_vec.first().map_or(0.0, |smoothed_filter| smoothed_filter.get_ramp_ms())
+    }
+
+    /// Frequency response of the cascade of all bands, at the end of the ramps with
+    /// the smoothing.
+    pub fn frequency_response(& self, frequency: f64) -> Complex<f64> {
+        self.iir_filters_vec.iter()
+            .map(|filter| filter.frequency_response(frequency, self.sample_rate))
+            .product()
+    }
+
+    /// Gain in dB of the equalizer at a frequency.
+    pub fn magnitude_db(& self, frequency: f64) -> f64 {
+        20.0 * f64::log10(self.frequency_response(frequency).norm())
+    }
+
     pub fn make_equalizer_10_band(sample_rate: u32) -> Equalizer {
         // Note: My Q_factor is correct for a octave, that means that the frequency between bands
         //       has to double in each band, but where can I now the standard values where to start
         //       the band_0, so that I can double after that, I got the frequencies from here:
         //          Gstreamer 10 band equalizer plugin.
         //          https://gitlab.freedesktop.org/gstreamer/gst-plugins-good/-/blob/086bad464387d61e31884ee6628846628118fbcb/gst/equalizer/gstiirequalizer10bands.c  
+        //       The standard ISO bands are in make_equalizer_iso(), these ones are kept
+        //       for the same sound as the GStreamer plugin.
         let bands_vec = vec![
             29.0,    // Hz band_0
             59.0,    // Hz band_1
@@ -207,9 +280,25 @@ impl Equalizer {
         // See: The second reference on the function make_peak_eq_constant_q.
         let q_factor = 2.0 * f64::sqrt(2.0);  // ~ 2.828
 
-        let equalizer_10_band = Equalizer::new(sample_rate, & bands_vec, gain_max_db, gain_min_db, q_factor);
+        Equalizer::new(sample_rate, & bands_vec, gain_max_db, gain_min_db, q_factor)
+    }
 
-        equalizer_10_band
+    /// Graphic equalizer with the ISO octave or third-octave bands, see iso_bands.rs,
+    /// the bands below the Nyquist frequency, at their nominal frequencies and with
+    /// the Q of their width.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let mut eq = Equalizer::make_equalizer_iso(48_000, BandWidth::ThirdOctave);
+    ///    >>> eq.set_band_gain(17, -3.0)?;   // the 1 kHz band
+    ///    ```
+    ///
+    pub fn make_equalizer_iso(sample_rate: u32, width: BandWidth) -> Equalizer {
+        let bands = iso_bands_below(width, sample_rate as f64 / 2.0);
+        let bands_vec: Vec<f64> = bands.iter().map(|band| band.nominal_frequency).collect();
+        let gain_max_db =  12.0; // dB
+        let gain_min_db = -12.0; // dB
+        Equalizer::new(sample_rate, & bands_vec, gain_max_db, gain_min_db, bands[0].get_q_factor())
     }
 
 }
@@ -217,6 +306,12 @@ impl Equalizer {
 impl ProcessingBlock for Equalizer {
     fn process(& mut self, sample: f64) -> f64 {
         let mut sample_t =  sample;
+        if !self.smoothed_filters_vec.is_empty() {
+            for smoothed_filter in & mut self.smoothed_filters_vec {
+                sample_t = smoothed_filter.process(sample_t);
+            }
+            return sample_t;
+        }
         for iir_filter in & mut self.iir_filters_vec {
             sample_t = iir_filter.process(sample_t);
         }
@@ -0,0 +1,262 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Sample-accurate event scheduling on processing chains.
+//!              The parameters of a chain of filters are changed by events (a new
+//!              gain of an equalizer band, the coefficients of a stage, a MIDI note
+//!              that moves a cutoff) scheduled at an exact sample position. The
+//!              block of samples is split at the event positions: the samples
+//!              before an event are processed with the old parameters, then the
+//!              event is applied, then the rest is processed with the new ones.
+//!              So the timing of the automation doesn't depend on the block size of
+//!              the audio driver, and it is the same offline and in real time.
+//!
+//!              The events are applied to the processor by a function given by the
+//!              user, so any chain (a struct with several filters) can be scheduled,
+//!              with its own event type.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use std::collections::VecDeque;
+
+use crate::iir_filter::ProcessingBlock; // Trait
+
+
+/// Events sorted by their sample position, events at the same position keep the
+/// order in which they were scheduled.
+pub struct EventQueue<E> {
+    events: VecDeque<(u64, E)>,
+}
+
+impl<E> EventQueue<E> {
+    pub fn new() -> Self {
+        EventQueue { events: VecDeque::new() }
+    }
+
+    /// Reserves the space for the events, so scheduling them from the audio thread
+    /// doesn't allocate.
+    pub fn with_capacity(capacity: usize) -> Self {
+        EventQueue { events: VecDeque::with_capacity(capacity) }
+    }
+
+    pub fn get_len(& self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(& self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn clear(& mut self) {
+        self.events.clear();
+    }
+
+    /// Adds the event at the absolute sample position.
+    pub fn schedule(& mut self, frame: u64, event: E) {
+        let index = self.events.partition_point(|(f, _)| *f <= frame);
+        self.events.insert(index, (frame, event));
+    }
+
+    /// Position of the next event.
+    pub fn get_next_frame(& self) -> Option<u64> {
+        self.events.front().map(|(frame, _)| *frame)
+    }
+
+    /// Removes and returns the next event if its position is at or before the frame.
+    pub fn pop_due(& mut self, frame: u64) -> Option<E> {
+        match self.events.front() {
+            Some((f, _)) if *f <= frame => self.events.pop_front().map(|(_, event)| event),
+            _ => None,
+        }
+    }
+}
+
+impl<E> Default for EventQueue<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A processor (a filter or a chain of them) with a queue of events, that are
+/// applied to it at their exact sample position.
+pub struct ScheduledProcessor<B, E, F>
+    where B: ProcessingBlock, F: FnMut(& mut B, E) {
+    processor: B,
+    queue:     EventQueue<E>,
+    apply:     F,
+    // Absolute position of the next sample.
+    frame:     u64,
+}
+
+impl<B, E, F> ScheduledProcessor<B, E, F>
+    where B: ProcessingBlock, F: FnMut(& mut B, E) {
+
+    /// The apply function changes the processor with an event.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let mut eq = ScheduledProcessor::new(Equalizer::make_equalizer_10_band(48_000),
+    ///    >>>     |eq: & mut Equalizer, (band, gain_db): (usize, f64)| { eq.set_band_gain(band, gain_db).unwrap(); });
+    ///    >>> // Boost the band 3 exactly one second after the start.
+    ///    >>> eq.schedule(48_000, (3, 6.0));
+    ///    >>> eq.process_block(& input, & mut output);
+    ///    ```
+    ///
+    pub fn new(processor: B, apply: F) -> Self {
+        ScheduledProcessor { processor, queue: EventQueue::new(), apply, frame: 0 }
+    }
+
+    pub fn get_processor(& self) -> & B {
+        & self.processor
+    }
+
+    pub fn get_processor_mut(& mut self) -> & mut B {
+        & mut self.processor
+    }
+
+    /// Absolute position of the next sample to be processed.
+    pub fn get_frame(& self) -> u64 {
+        self.frame
+    }
+
+    pub fn get_queue_mut(& mut self) -> & mut EventQueue<E> {
+        & mut self.queue
+    }
+
+    /// Schedules the event at the absolute sample position. An event in the past is
+    /// applied before the next sample.
+    pub fn schedule(& mut self, frame: u64, event: E) {
+        self.queue.schedule(frame, event);
+    }
+
+    /// Schedules the event at an offset from the next sample, like the offset of a
+    /// MIDI event inside the next audio block.
+    pub fn schedule_in_block(& mut self, offset: usize, event: E) {
+        self.queue.schedule(self.frame + offset as u64, event);
+    }
+
+    // Applies all the events due at the current position.
+    fn apply_due_events(& mut self) {
+        while let Some(event) = self.queue.pop_due(self.frame) {
+            (self.apply)(& mut self.processor, event);
+        }
+    }
+
+    /// Processes the block, split at the positions of the events inside it.
+    pub fn process_block(& mut self, input: & [f64], output: & mut [f64]) {
+        assert_eq!(input.len(), output.len());
+        let block_start = self.frame;
+        let block_end = block_start + input.len() as u64;
+        let mut start = 0;
+        while start < input.len() {
+            self.apply_due_events();
+            // The samples until the next event, or the end of the block.
+            let end = match self.queue.get_next_frame() {
+                Some(frame) if frame < block_end => (frame - block_start) as usize,
+                _ => input.len(),
+            };
+            for (x, y) in input[start..end].iter().zip(& mut output[start..end]) {
+                *y = self.processor.process(*x);
+            }
+            self.frame += (end - start) as u64;
+            start = end;
+        }
+    }
+}
+
+impl<B, E, F> ProcessingBlock for ScheduledProcessor<B, E, F>
+    where B: ProcessingBlock, F: FnMut(& mut B, E) {
+    fn process(& mut self, sample: f64) -> f64 {
+        self.apply_due_events();
+        self.frame += 1;
+        self.processor.process(sample)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iir_filter::IIRFilter;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::butterworth_filter::make_highpass;
+    use crate::equalizer::Equalizer;
+
+    // A chain of two filters, the events replace one of them.
+    struct TwoStages {
+        stages: [IIRFilter; 2],
+    }
+
+    impl ProcessingBlock for TwoStages {
+        fn process(& mut self, sample: f64) -> f64 {
+            let sample = self.stages[0].process(sample);
+            self.stages[1].process(sample)
+        }
+    }
+
+    #[test]
+    fn test_event_queue_order() {
+        let mut queue = EventQueue::new();
+        queue.schedule(10, "b");
+        queue.schedule(5, "a");
+        queue.schedule(10, "c");
+        assert_eq!(queue.get_next_frame(), Some(5));
+        assert_eq!(queue.pop_due(4), None);
+        assert_eq!(queue.pop_due(10), Some("a"));
+        assert_eq!(queue.pop_due(10), Some("b"));
+        assert_eq!(queue.pop_due(10), Some("c"));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_events_are_sample_accurate_for_any_block_size() {
+        let sample_rate = 48_000;
+        let input: Vec<f64> = (0..1_000).map(|n| f64::sin(n as f64 * 0.3) + 0.5 * f64::cos(n as f64 * 0.031)).collect();
+        let make_chain = || {
+            let chain = TwoStages { stages: [make_lowpass(5_000.0, sample_rate, None), make_highpass(50.0, sample_rate, None)] };
+            let mut scheduled = ScheduledProcessor::new(chain, |chain: & mut TwoStages, (stage, filter): (usize, IIRFilter)| {
+                chain.stages[stage].set_coefficients(& filter.a_coeffs, & filter.b_coeffs).unwrap();
+            });
+            scheduled.schedule(123, (0, make_lowpass(1_000.0, sample_rate, None)));
+            scheduled.schedule(777, (1, make_highpass(200.0, sample_rate, None)));
+            scheduled
+        };
+
+        // Sample by sample is the reference.
+        let mut reference = make_chain();
+        let expected: Vec<f64> = input.iter().map(|x| reference.process(*x)).collect();
+        for block_size in [1, 64, 100, 1_000] {
+            let mut scheduled = make_chain();
+            let mut output = vec![0.0; input.len()];
+            for (x, y) in input.chunks(block_size).zip(output.chunks_mut(block_size)) {
+                scheduled.process_block(x, y);
+            }
+            assert_eq!(output, expected, "block size {}", block_size);
+            assert_eq!(scheduled.get_frame(), 1_000);
+        }
+
+        // Before the first event the chain is unchanged.
+        let mut unchanged = TwoStages { stages: [make_lowpass(5_000.0, sample_rate, None), make_highpass(50.0, sample_rate, None)] };
+        for n in 0..123 {
+            assert_eq!(unchanged.process(input[n]), expected[n]);
+        }
+        assert_ne!(unchanged.process(input[123]), expected[123]);
+    }
+
+    #[test]
+    fn test_schedule_in_block() {
+        let mut eq = ScheduledProcessor::new(Equalizer::make_equalizer_10_band(48_000),
+            |eq: & mut Equalizer, (band, gain_db): (usize, f64)| { eq.set_band_gain(band, gain_db).unwrap(); });
+        let mut output = vec![0.0; 256];
+        eq.process_block(& vec![0.0; 256], & mut output);
+        eq.schedule_in_block(10, (3, 6.0));
+        assert_eq!(eq.get_queue_mut().get_next_frame(), Some(266));
+        eq.process_block(& vec![0.0; 256], & mut output);
+        assert!(eq.get_queue_mut().is_empty());
+        assert_eq!(eq.get_processor().get_band_gain(3), 6.0);
+    }
+}
@@ -0,0 +1,209 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: The math functions of the coefficient design.
+//!              The sin, cos, tan, pow... of std call the math library of the
+//!              platform, and the last bit of their result changes from one libm to
+//!              another, glibc, musl, the MSVC runtime, or the one of an embedded
+//!              target. The coefficients of a filter, and the output compared with a
+//!              golden file, then differ in the last bits from one machine to the
+//!              other.
+//!
+//!              With the feature bit-exact these functions call the libm crate, a
+//!              pure Rust port of the musl math library, the same code on all the
+//!              targets, and the designs are bit identical everywhere. Without the
+//!              feature they are the functions of std. The square root isn't here,
+//!              IEEE 754 requires it correctly rounded, it's the same everywhere.
+//!
+//!                 cargo test --features bit-exact
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. libm - A port of MUSL's libm to Rust
+//!       https://github.com/rust-lang/libm
+//!
+
+
+#[cfg(feature = "bit-exact")]
+pub fn sin(x: f64) -> f64 {
+    libm::sin(x)
+}
+
+#[cfg(not(feature = "bit-exact"))]
+pub fn sin(x: f64) -> f64 {
+    f64::sin(x)
+}
+
+#[cfg(feature = "bit-exact")]
+pub fn cos(x: f64) -> f64 {
+    libm::cos(x)
+}
+
+#[cfg(not(feature = "bit-exact"))]
+pub fn cos(x: f64) -> f64 {
+    f64::cos(x)
+}
+
+#[cfg(feature = "bit-exact")]
+pub fn tan(x: f64) -> f64 {
+    libm::tan(x)
+}
+
+#[cfg(not(feature = "bit-exact"))]
+pub fn tan(x: f64) -> f64 {
+    f64::tan(x)
+}
+
+#[cfg(feature = "bit-exact")]
+pub fn atan(x: f64) -> f64 {
+    libm::atan(x)
+}
+
+#[cfg(not(feature = "bit-exact"))]
+pub fn atan(x: f64) -> f64 {
+    f64::atan(x)
+}
+
+#[cfg(feature = "bit-exact")]
+pub fn sinh(x: f64) -> f64 {
+    libm::sinh(x)
+}
+
+#[cfg(not(feature = "bit-exact"))]
+pub fn sinh(x: f64) -> f64 {
+    f64::sinh(x)
+}
+
+#[cfg(feature = "bit-exact")]
+pub fn ln(x: f64) -> f64 {
+    libm::log(x)
+}
+
+#[cfg(not(feature = "bit-exact"))]
+pub fn ln(x: f64) -> f64 {
+    f64::ln(x)
+}
+
+#[cfg(feature = "bit-exact")]
+pub fn powf(x: f64, y: f64) -> f64 {
+    libm::pow(x, y)
+}
+
+#[cfg(not(feature = "bit-exact"))]
+pub fn powf(x: f64, y: f64) -> f64 {
+    f64::powf(x, y)
+}
+
+#[cfg(feature = "bit-exact")]
+pub fn exp(x: f64) -> f64 {
+    libm::exp(x)
+}
+
+#[cfg(not(feature = "bit-exact"))]
+pub fn exp(x: f64) -> f64 {
+    f64::exp(x)
+}
+
+#[cfg(feature = "bit-exact")]
+pub fn log10(x: f64) -> f64 {
+    libm::log10(x)
+}
+
+#[cfg(not(feature = "bit-exact"))]
+pub fn log10(x: f64) -> f64 {
+    f64::log10(x)
+}
+
+/// True when the crate was compiled with the feature bit-exact.
+pub fn is_bit_exact() -> bool {
+    cfg!(feature = "bit-exact")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::butterworth_filter::make_peak_eq_constant_q;
+
+    #[test]
+    fn test_functions_agree_with_std() {
+        for n in 1..100 {
+            let x = n as f64 * 0.031;
+            assert!((sin(x) - x.sin()).abs() <= 1e-15);
+            assert!((cos(x) - x.cos()).abs() <= 1e-15);
+            assert!((tan(x) - x.tan()).abs() <= 1e-14 * x.tan().abs().max(1.0));
+            assert!((sinh(x) - x.sinh()).abs() <= 1e-15 * x.sinh().abs().max(1.0));
+            assert!((ln(x) - x.ln()).abs() <= 1e-15 * x.ln().abs().max(1.0));
+            assert!((powf(10.0, x) - 10.0_f64.powf(x)).abs() <= 1e-15 * 10.0_f64.powf(x));
+            assert!((exp(x) - x.exp()).abs() <= 1e-15 * x.exp());
+            assert!((log10(x) - x.log10()).abs() <= 1e-15 * x.log10().abs().max(1.0));
+        }
+    }
+
+    // The bits of the coefficients computed with musl, on any target.
+    #[cfg(feature = "bit-exact")]
+    #[test]
+    fn test_bit_exact_coefficients() {
+        assert!(is_bit_exact());
+        let filter = make_lowpass(1_000.0, 48_000, None);
+        let bits: Vec<u64> = filter.a_coeffs.iter().chain(& filter.b_coeffs).map(|c| c.to_bits()).collect();
+        assert_eq!(bits, LOWPASS_BITS);
+        let filter = make_peak_eq_constant_q(1_000.0, 48_000, 6.0, Some(2.0));
+        let bits: Vec<u64> = filter.a_coeffs.iter().chain(& filter.b_coeffs).map(|c| c.to_bits()).collect();
+        assert_eq!(bits, PEAK_EQ_BITS);
+    }
+
+    #[cfg(feature = "bit-exact")]
+    const LOWPASS_BITS: [u64; 6] = [4607598082831451178, 13834980997443905691, 4606351090737149868,
+                                   4571581723802196288, 4576085323429566784, 4571581723802196288];
+    #[cfg(feature = "bit-exact")]
+    const PEAK_EQ_BITS: [u64; 6] = [4607182418800017408, 13834698801592617891, 4606613156983688516,
+                                   4607324060008303129, 13834698801592617891, 4606329874567117074];
+
+    #[cfg(not(feature = "bit-exact"))]
+    #[test]
+    fn test_platform_math() {
+        assert!(!is_bit_exact());
+        let filter = make_lowpass(1_000.0, 48_000, None);
+        assert!((filter.a_coeffs[1] + 1.9828897227476208).abs() < 1e-15);
+        assert!(make_peak_eq_constant_q(1_000.0, 48_000, 6.0, Some(2.0)).b_coeffs[0] > 1.0);
+    }
+
+    // The sources of the modules that design coefficients, the designs must call the
+    // functions of this module and not the ones of std.
+    const DESIGN_SOURCES: [(& str, & str); 12] = [
+        ("butterworth_filter.rs", include_str!("butterworth_filter.rs")),
+        ("biquad.rs",             include_str!("biquad.rs")),
+        ("biquad_tdf2.rs",        include_str!("biquad_tdf2.rs")),
+        ("svf.rs",                include_str!("svf.rs")),
+        ("smoothed_biquad.rs",    include_str!("smoothed_biquad.rs")),
+        ("crossover.rs",          include_str!("crossover.rs")),
+        ("loudspeaker.rs",        include_str!("loudspeaker.rs")),
+        ("diagnostics.rs",        include_str!("diagnostics.rs")),
+        ("loudness.rs",           include_str!("loudness.rs")),
+        ("crossfeed.rs",          include_str!("crossfeed.rs")),
+        ("equal_loudness.rs",     include_str!("equal_loudness.rs")),
+        ("parametric_eq.rs",      include_str!("parametric_eq.rs")),
+    ];
+
+    #[test]
+    fn test_designs_dont_call_std_math() {
+        for (file, source) in DESIGN_SOURCES {
+            // The tests of the module can use std.
+            let code = source.split("#[cfg(test)]").next().unwrap();
+            for (line_number, line) in code.lines().enumerate() {
+                let line = line.split("//").next().unwrap();
+                for function in ["sin", "cos", "tan", "exp", "powf", "sinh", "ln", "atan"] {
+                    let std_call = format!("f64::{}(", function);
+                    let method_call = format!(".{}(", function);
+                    assert!(!line.contains(& std_call) && !line.contains(& method_call),
+                            "{}:{} calls {} of std, use exact_math::{}", file, line_number + 1, function, function);
+                }
+            }
+        }
+    }
+}
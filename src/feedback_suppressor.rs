@@ -0,0 +1,276 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Automatic feedback suppressor (feedback destroyer) for live sound.
+//!              When a microphone picks up its own loudspeaker, the loop rings at
+//!              the frequency with the most gain, as a pure tone that grows. The
+//!              suppressor analyses the input with the FFT, every hop of 1024
+//!              samples, and looks for a peak that stands far above the average of
+//!              the spectrum (a tone, not music or noise) and stays at the same
+//!              frequency for several analysis frames. Then it places a narrow notch
+//!              (make_notch, 1/20 octave) on it, at the frequency refined by
+//!              parabolic interpolation of the peak.
+//!
+//!              Each notch is refreshed while its frequency keeps ringing, and is
+//!              released when it didn't ring for the release time, so the notches
+//!              follow the changes of the room and of the microphone positions. When
+//!              all the notches are in use, the one that rang longest ago is reused.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Audio feedback - Wikipedia
+//!       https://en.wikipedia.org/wiki/Audio_feedback
+//!
+
+
+use std::f64::consts::TAU;
+use std::sync::Arc;
+use rustfft::Fft;
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+
+use crate::iir_filter::IIRFilter;
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::butterworth_filter::make_notch;
+
+
+const FFT_SIZE: usize = 4_096;
+const HOP_SIZE: usize = 1_024;
+// Number of consecutive frames with the same peak to detect a feedback.
+const PERSISTENCE_FRAMES: usize = 4;
+// A quieter peak, in dBFS, is ignored.
+const MIN_PEAK_LEVEL_DB: f64 = -60.0;
+
+pub const DEFAULT_THRESHOLD_DB: f64 = 20.0;
+pub const DEFAULT_RELEASE_SECONDS: f64 = 10.0;
+pub const DEFAULT_NOTCH_BANDWIDTH_OCTAVES: f64 = 1.0 / 20.0;
+
+struct Notch {
+    frequency:      f64,
+    filter:         IIRFilter,
+    // Sample position of the last detection of the feedback.
+    last_detection: u64,
+}
+
+pub struct FeedbackSuppressor {
+    sample_rate:               u32,
+    max_notches:               usize,
+    threshold_db:              f64,
+    release_samples:           u64,
+    notch_bandwidth_octaves:   f64,
+    notches:                   Vec<Notch>,
+    fft:                       Arc<dyn Fft<f64>>,
+    window:                    Vec<f64>,
+    // The last FFT_SIZE input samples, circular.
+    history:                   Vec<f64>,
+    history_index:             usize,
+    spectrum:                  Vec<Complex<f64>>,
+    scratch:                   Vec<Complex<f64>>,
+    candidate_bin:             usize,
+    candidate_count:           usize,
+    // Absolute position of the next sample.
+    frame:                     u64,
+}
+
+impl FeedbackSuppressor {
+    /// Uses up to max_notches notches.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let mut suppressor = FeedbackSuppressor::new(48_000, 8);
+    ///    >>> suppressor.set_release_seconds(30.0);
+    ///    >>> let output: Vec<f64> = microphone.iter().map(|x| suppressor.process(*x)).collect();
+    ///    >>> println!("notches at {:?} Hz", suppressor.get_notch_frequencies());
+    ///    ```
+    ///
+    pub fn new(sample_rate: u32, max_notches: usize) -> Self {
+        assert!(max_notches >= 1);
+        let fft = FftPlanner::<f64>::new().plan_fft_forward(FFT_SIZE);
+        let scratch = vec![Complex::new(0.0, 0.0); fft.get_inplace_scratch_len()];
+        let window = (0..FFT_SIZE).map(|n| 0.5 - 0.5 * f64::cos(TAU * n as f64 / FFT_SIZE as f64)).collect();
+        FeedbackSuppressor {
+            sample_rate,
+            max_notches,
+            threshold_db: DEFAULT_THRESHOLD_DB,
+            release_samples: (DEFAULT_RELEASE_SECONDS * sample_rate as f64) as u64,
+            notch_bandwidth_octaves: DEFAULT_NOTCH_BANDWIDTH_OCTAVES,
+            notches: Vec::with_capacity(max_notches),
+            fft,
+            window,
+            history: vec![0.0; FFT_SIZE],
+            history_index: 0,
+            spectrum: vec![Complex::new(0.0, 0.0); FFT_SIZE],
+            scratch,
+            candidate_bin: 0,
+            candidate_count: 0,
+            frame: 0,
+        }
+    }
+
+    /// How far, in dB, a peak must be above the average power of the spectrum.
+    pub fn set_threshold_db(& mut self, threshold_db: f64) {
+        assert!(threshold_db > 0.0);
+        self.threshold_db = threshold_db;
+    }
+
+    /// Time without feedback after which a notch is removed.
+    pub fn set_release_seconds(& mut self, release_seconds: f64) {
+        assert!(release_seconds >= 0.0);
+        self.release_samples = (release_seconds * self.sample_rate as f64) as u64;
+    }
+
+    /// Bandwidth of the new notches.
+    pub fn set_notch_bandwidth_octaves(& mut self, bandwidth_octaves: f64) {
+        assert!(bandwidth_octaves > 0.0);
+        self.notch_bandwidth_octaves = bandwidth_octaves;
+    }
+
+    /// Frequencies of the active notches, in the order they were placed.
+    pub fn get_notch_frequencies(& self) -> Vec<f64> {
+        self.notches.iter().map(|notch| notch.frequency).collect()
+    }
+
+    /// Removes all the notches.
+    pub fn clear(& mut self) {
+        self.notches.clear();
+        self.candidate_count = 0;
+    }
+
+    // Returns the frequency of a feedback confirmed by this frame.
+    fn analyze(& mut self) -> Option<f64> {
+        // The oldest sample first.
+        for n in 0..FFT_SIZE {
+            let sample = self.history[(self.history_index + n) % FFT_SIZE];
+            self.spectrum[n] = Complex::new(sample * self.window[n], 0.0);
+        }
+        self.fft.process_with_scratch(& mut self.spectrum, & mut self.scratch);
+
+        let power = |k: usize| self.spectrum[k].norm_sqr();
+        let num_bins = FFT_SIZE / 2;
+        let mut peak_bin = 1;
+        let mut total_power = 0.0;
+        for k in 1..num_bins {
+            total_power += power(k);
+            if power(k) > power(peak_bin) {
+                peak_bin = k;
+            }
+        }
+        let average_power = total_power / (num_bins - 1) as f64;
+        // Amplitude of a sine in the Hann window is 4 |X| / N .
+        let peak_level_db = 20.0 * f64::log10(4.0 * power(peak_bin).sqrt() / FFT_SIZE as f64);
+        let is_tonal = peak_level_db > MIN_PEAK_LEVEL_DB
+            && 10.0 * f64::log10(power(peak_bin) / average_power) > self.threshold_db
+            && peak_bin + 1 < num_bins;
+        if !is_tonal {
+            self.candidate_count = 0;
+            return None;
+        }
+
+        if self.candidate_count > 0 && self.candidate_bin.abs_diff(peak_bin) <= 1 {
+            self.candidate_count += 1;
+        } else {
+            self.candidate_count = 1;
+        }
+        self.candidate_bin = peak_bin;
+        if self.candidate_count < PERSISTENCE_FRAMES {
+            return None;
+        }
+        self.candidate_count = 0;
+
+        // Parabolic interpolation of the log magnitudes of the peak.
+        let (left, center, right) = (power(peak_bin - 1).ln(), power(peak_bin).ln(), power(peak_bin + 1).ln());
+        let offset = 0.5 * (left - right) / (left - 2.0 * center + right);
+        Some((peak_bin as f64 + offset) * self.sample_rate as f64 / FFT_SIZE as f64)
+    }
+
+    fn place_notch(& mut self, frequency: f64) {
+        // A feedback inside an existing notch refreshes it.
+        let half_bandwidth = f64::powf(2.0, self.notch_bandwidth_octaves / 2.0);
+        if let Some(notch) = self.notches.iter_mut()
+                .find(|notch| frequency > notch.frequency / half_bandwidth && frequency < notch.frequency * half_bandwidth) {
+            notch.last_detection = self.frame;
+            return;
+        }
+        if self.notches.len() == self.max_notches {
+            let oldest = (0..self.notches.len()).min_by_key(|i| self.notches[*i].last_detection).unwrap();
+            self.notches.remove(oldest);
+        }
+        trace_event!(frequency, "feedback notch placed");
+        let filter = make_notch(frequency, self.sample_rate, Some(self.notch_bandwidth_octaves));
+        self.notches.push(Notch { frequency, filter, last_detection: self.frame });
+    }
+}
+
+impl ProcessingBlock for FeedbackSuppressor {
+    fn process(& mut self, sample: f64) -> f64 {
+        self.history[self.history_index] = sample;
+        self.history_index = (self.history_index + 1) % FFT_SIZE;
+        self.frame += 1;
+        if self.frame.is_multiple_of(HOP_SIZE as u64) {
+            if let Some(frequency) = self.analyze() {
+                self.place_notch(frequency);
+            }
+            let (frame, release_samples) = (self.frame, self.release_samples);
+            self.notches.retain(|notch| frame - notch.last_detection <= release_samples);
+        }
+        self.notches.iter_mut().fold(sample, |x, notch| notch.filter.process(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::make_white_noise;
+
+    fn level_at(signal: & [f64], frequency: f64) -> f64 {
+        let sum: Complex<f64> = signal.iter().enumerate()
+            .map(|(n, x)| Complex::from_polar(*x, -TAU * frequency * n as f64 / 48_000.0))
+            .sum();
+        2.0 * sum.norm() / signal.len() as f64
+    }
+
+    #[test]
+    fn test_notch_on_ringing_tone() {
+        let mut suppressor = FeedbackSuppressor::new(48_000, 4);
+        let noise = make_white_noise(96_000, 0.05, 12_345);
+        // A feedback at 2.5 kHz from 0.5 s.
+        let input: Vec<f64> = noise.iter().enumerate()
+            .map(|(n, x)| x + if n >= 24_000 { 0.3 * f64::sin(TAU * 2_500.0 * n as f64 / 48_000.0) } else { 0.0 })
+            .collect();
+        let output: Vec<f64> = input.iter().map(|x| suppressor.process(*x)).collect();
+        let notches = suppressor.get_notch_frequencies();
+        assert_eq!(notches.len(), 1);
+        assert!((notches[0] - 2_500.0).abs() < 2.0, "notch at {} Hz", notches[0]);
+        // The tone is removed from the output once the notch is placed.
+        assert!(level_at(& output[48_000..], 2_500.0) < 0.03);
+        assert!(level_at(& input[48_000..], 2_500.0) > 0.29);
+    }
+
+    #[test]
+    fn test_no_notch_on_noise_and_release() {
+        let mut suppressor = FeedbackSuppressor::new(48_000, 2);
+        for x in make_white_noise(96_000, 0.5, 12_345) {
+            suppressor.process(x);
+        }
+        assert!(suppressor.get_notch_frequencies().is_empty());
+
+        suppressor.set_release_seconds(0.5);
+        for n in 0..48_000 {
+            suppressor.process(0.3 * f64::sin(TAU * 1_000.0 * n as f64 / 48_000.0));
+        }
+        assert_eq!(suppressor.get_notch_frequencies().len(), 1);
+        // Silence, the notch is released after 0.5 s.
+        for _ in 0..12_000 {
+            suppressor.process(0.0);
+        }
+        assert_eq!(suppressor.get_notch_frequencies().len(), 1);
+        for _ in 0..24_000 {
+            suppressor.process(0.0);
+        }
+        assert!(suppressor.get_notch_frequencies().is_empty());
+    }
+}
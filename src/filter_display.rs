@@ -0,0 +1,248 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Display of the filters, for the exploratory use in a notebook.
+//!              Printing an IIRFilter shows its coefficients in a table and the key
+//!              points of its magnitude response, the gain at DC and at Nyquist, the
+//!              maximum and the minimum, and the -3 dB points, where the magnitude
+//!              crosses 3 dB below the maximum:
+//!
+//!                 IIRFilter of order 2
+//!                                          a                       b
+//!                  0     1.09229595564125725     0.00427756931309481
+//!                  1    -1.98288972274762076     0.00855513862618962
+//!                  2     0.90770404435874275     0.00427756931309481
+//!
+//!                 DC gain       0.00 dB
+//!                 Nyquist gain  -inf dB
+//!                 Maximum       0.00 dB at 0.00 Hz
+//!                 Minimum       -inf dB at 24000.00 Hz
+//!                 -3 dB points  1000.00 Hz
+//!
+//!              An IIRFilter doesn't know its sample rate, it prints the frequencies
+//!              as fractions of the sample rate, filter.display(sample_rate) prints
+//!              them in Hz.
+//!              With the evcxr feature, the value of filter.display(sample_rate) as the
+//!              last expression of a cell of an evcxr Jupyter notebook is shown as an
+//!              SVG plot of the magnitude, with the key points in text below it.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. evcxr Jupyter kernel - Custom output
+//!       https://github.com/evcxr/evcxr/blob/main/evcxr_jupyter/README.md
+//!
+
+
+use std::fmt;
+
+use crate::iir_filter::IIRFilter;
+
+
+// Points of the log frequency grid of the search of the key points, and its lowest
+// frequency, as fractions of the sample rate.
+const GRID_POINTS: usize = 4_096;
+const GRID_MIN_FREQUENCY: f64 = 1e-5;
+const BISECTION_STEPS: usize = 50;
+/// The drop from the maximum of the -3 dB points, 10 log10(2).
+pub const HALF_POWER_DB: f64 = 3.010_299_956_639_812;
+
+/// The points of the magnitude response shown by the display of a filter, the
+/// frequencies are fractions of the sample rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyResponsePoints {
+    pub dc_gain_db:        f64,
+    pub nyquist_gain_db:   f64,
+    /// (frequency, gain in dB)
+    pub maximum:           (f64, f64),
+    pub minimum:           (f64, f64),
+    /// Where the magnitude crosses 3 dB below the maximum, in increasing frequency.
+    pub half_power_points: Vec<f64>,
+}
+
+fn magnitude_db(filter: & IIRFilter, frequency: f64) -> f64 {
+    // The response at a sample rate of 1 takes the frequency as a fraction of it.
+    20.0 * f64::log10(filter.frequency_response(frequency, 1).norm())
+}
+
+/// The key points of the magnitude response of the filter.
+pub fn key_response_points(filter: & IIRFilter) -> KeyResponsePoints {
+    let grid: Vec<f64> = std::iter::once(0.0)
+        .chain((0..GRID_POINTS).map(|i| GRID_MIN_FREQUENCY * f64::powf(0.5 / GRID_MIN_FREQUENCY, i as f64 / (GRID_POINTS - 1) as f64)))
+        .collect();
+    let gains: Vec<f64> = grid.iter().map(|f| magnitude_db(filter, *f)).collect();
+    let extreme = |better: fn(f64, f64) -> bool| (0..grid.len())
+        .fold(0, |best, i| if better(gains[i], gains[best]) { i } else { best });
+    let (max_index, min_index) = (extreme(|a, b| a > b), extreme(|a, b| a < b));
+    let threshold = gains[max_index] - HALF_POWER_DB;
+
+    let mut half_power_points = Vec::new();
+    for i in 1..grid.len() {
+        if (gains[i - 1] >= threshold) != (gains[i] >= threshold) {
+            // Bisection of the crossing between the two points of the grid.
+            let (mut low, mut high) = (grid[i - 1], grid[i]);
+            let low_above = gains[i - 1] >= threshold;
+            for _ in 0..BISECTION_STEPS {
+                let middle = 0.5 * (low + high);
+                if (magnitude_db(filter, middle) >= threshold) == low_above {
+                    low = middle;
+                } else {
+                    high = middle;
+                }
+            }
+            half_power_points.push(0.5 * (low + high));
+        }
+    }
+    KeyResponsePoints {
+        dc_gain_db: gains[0],
+        nyquist_gain_db: gains[grid.len() - 1],
+        maximum: (grid[max_index], gains[max_index]),
+        minimum: (grid[min_index], gains[min_index]),
+        half_power_points,
+    }
+}
+
+/// An IIRFilter with the sample rate of its display, from IIRFilter::display().
+pub struct FilterDisplay<'a> {
+    filter:      & 'a IIRFilter,
+    sample_rate: Option<u32>,
+}
+
+impl IIRFilter {
+    /// The filter to print with the frequencies in Hz.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let filter = make_peak(1_000.0, 48_000, 6.0, Some(2.0));
+    ///    >>> println!("{}", filter.display(48_000));
+    ///    ```
+    ///
+    pub fn display(& self, sample_rate: u32) -> FilterDisplay<'_> {
+        FilterDisplay { filter: self, sample_rate: Some(sample_rate) }
+    }
+}
+
+impl FilterDisplay<'_> {
+    fn format_frequency(& self, frequency: f64) -> String {
+        match self.sample_rate {
+            Some(sample_rate) => format!("{:.2} Hz", frequency * sample_rate as f64),
+            None => format!("{:.6} fs", frequency),
+        }
+    }
+
+    /// The display of the cell of an evcxr notebook, the SVG plot of the magnitude
+    /// and the key points.
+    #[cfg(feature = "evcxr")]
+    pub fn evcxr_display(& self) {
+        println!("EVCXR_BEGIN_CONTENT text/html\n{}<pre>{}</pre>\nEVCXR_END_CONTENT",
+                 self.render_svg(640, 360), self);
+    }
+
+    /// The magnitude response plot, as an SVG document, at 48 kHz without a
+    /// sample rate.
+    #[cfg(feature = "evcxr")]
+    pub fn render_svg(& self, width: u32, height: u32) -> String {
+        use plotters::prelude::*;
+
+        let sample_rate = self.sample_rate.unwrap_or(48_000) as f64;
+        let min_frequency = f64::min(10.0, sample_rate / 4_800.0);
+        let points: Vec<(f64, f64)> = (0..512)
+            .map(|i| min_frequency * f64::powf(0.5 * sample_rate / min_frequency, i as f64 / 511.0))
+            .map(|f| (f, magnitude_db(self.filter, f / sample_rate).max(-120.0)))
+            .collect();
+        let (low, high) = points.iter().fold((0.0_f64, 0.0_f64), |(low, high), (_, y)| (low.min(*y), high.max(*y)));
+        let mut svg = String::new();
+        {
+            let root = SVGBackend::with_string(& mut svg, (width, height)).into_drawing_area();
+            root.fill(& WHITE).unwrap();
+            let mut chart = ChartBuilder::on(& root)
+                .caption("Gain(dB) vs Freq", ("sans-serif", 20).into_font())
+                .margin(10)
+                .x_label_area_size(30)
+                .y_label_area_size(40)
+                .build_cartesian_2d((min_frequency..0.5 * sample_rate).log_scale(), (low - 3.0)..(high + 3.0))
+                .unwrap();
+            chart.configure_mesh().x_desc("Hz").y_desc("dB").draw().unwrap();
+            chart.draw_series(LineSeries::new(points, & BLUE)).unwrap();
+            root.present().unwrap();
+        }
+        svg
+    }
+}
+
+impl fmt::Display for FilterDisplay<'_> {
+    fn fmt(& self, f: & mut fmt::Formatter<'_>) -> fmt::Result {
+        let filter = self.filter;
+        writeln!(f, "IIRFilter of order {}", filter.order)?;
+        writeln!(f, "    {:>22}  {:>22}", "a", "b")?;
+        for (index, (a, b)) in filter.a_coeffs.iter().zip(& filter.b_coeffs).enumerate() {
+            writeln!(f, "{:>2}  {:>22.17}  {:>22.17}", index, a, b)?;
+        }
+        let points = key_response_points(filter);
+        writeln!(f)?;
+        writeln!(f, "DC gain       {:.2} dB", points.dc_gain_db)?;
+        writeln!(f, "Nyquist gain  {:.2} dB", points.nyquist_gain_db)?;
+        writeln!(f, "Maximum       {:.2} dB at {}", points.maximum.1, self.format_frequency(points.maximum.0))?;
+        writeln!(f, "Minimum       {:.2} dB at {}", points.minimum.1, self.format_frequency(points.minimum.0))?;
+        let half_power: Vec<String> = points.half_power_points.iter().map(|p| self.format_frequency(*p)).collect();
+        write!(f, "-3 dB points  {}", if half_power.is_empty() { "none".to_string() } else { half_power.join(", ") })
+    }
+}
+
+impl fmt::Display for IIRFilter {
+    /// The coefficients and the key points of the response, the frequencies as
+    /// fractions of the sample rate.
+    fn fmt(& self, f: & mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(& FilterDisplay { filter: self, sample_rate: None }, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::butterworth_filter::make_peak;
+    use crate::butterworth_filter::make_allpass;
+
+    #[test]
+    fn test_key_response_points() {
+        // The -3 dB point of a Butterworth low-pass is its cutoff.
+        let points = key_response_points(& make_lowpass(1_000.0, 48_000, None));
+        assert_eq!(points.half_power_points.len(), 1);
+        assert!((points.half_power_points[0] * 48_000.0 - 1_000.0).abs() < 0.01);
+        assert_eq!(points.maximum.0, 0.0);
+        assert!(points.dc_gain_db.abs() < 1e-9);
+        assert!(points.nyquist_gain_db < -200.0);
+
+        let points = key_response_points(& make_peak(1_000.0, 48_000, 12.0, Some(2.0)));
+        assert!((points.maximum.1 - 12.0).abs() < 1e-3);
+        assert!((points.maximum.0 * 48_000.0 - 1_000.0).abs() < 5.0);
+        assert_eq!(points.half_power_points.len(), 2);
+        assert!(points.half_power_points[0] * 48_000.0 < 1_000.0 && points.half_power_points[1] * 48_000.0 > 1_000.0);
+        // All-pass, flat.
+        assert!(key_response_points(& make_allpass(1_000.0, 48_000, None)).half_power_points.is_empty());
+    }
+
+    #[test]
+    fn test_display() {
+        let filter = make_lowpass(1_000.0, 48_000, None);
+        let text = format!("{}", filter.display(48_000));
+        assert!(text.starts_with("IIRFilter of order 2\n"));
+        assert!(text.contains("\n 1    -1.98288972274762"));
+        assert!(text.contains("-3 dB points  1000.00 Hz"), "{}", text);
+        assert!(format!("{}", filter).contains("-3 dB points  0.020833 fs"));
+        assert!(format!("{:?}", filter).starts_with("IIRFilter { order: 2, a_coeffs: ["));
+    }
+
+    #[cfg(feature = "evcxr")]
+    #[test]
+    fn test_render_svg() {
+        let filter = make_peak(1_000.0, 48_000, 6.0, Some(2.0));
+        let svg = filter.display(48_000).render_svg(400, 300);
+        assert!(svg.starts_with("<svg") && svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains("<polyline"));
+    }
+}
@@ -0,0 +1,257 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Morphing between two biquads by pole / zero interpolation.
+//!              A crossfade of the direct form coefficients of two EQ settings
+//!              doesn't give the response in between: a peak at 1 kHz morphed to a
+//!              peak at 4 kHz dips and widens in the middle, instead of moving. Here
+//!              each biquad is factored in its gain, its two zeros and its two poles,
+//!              and the roots are interpolated in the z plane:
+//!
+//!                 - a complex pair to a complex pair, in polar form, the radius and
+//!                   the angle (the frequency) move linearly,
+//!                 - a real pair to a real pair, on the real line,
+//!                 - a complex pair to a real pair, in polar form, and the
+//!                   coefficients are the real part of the product of the roots.
+//!
+//!              The interpolated poles stay inside the unit circle, so any morph
+//!              between two stable filters is stable. The gain is interpolated in dB.
+//!
+//!              MorphFilter is a biquad with a morph parameter from 0 (filter A) to
+//!              1 (filter B). It runs as a lattice filter, so the morph can be
+//!              changed while it plays.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Pole-zero plot - Wikipedia
+//!       https://en.wikipedia.org/wiki/Pole%E2%80%93zero_plot
+//!
+
+
+use rustfft::num_complex::Complex;
+
+use crate::iir_filter::IIRFilter;
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::time_varying::LatticeBiquad;
+use crate::time_varying::LatticeCoefficients;
+
+
+// Roots with an imaginary part below this are real.
+const REAL_TOLERANCE: f64 = 1e-12;
+
+/// A biquad factored as gain * (z - z1) (z - z2) / ((z - p1) (z - p2)).
+#[derive(Debug, Clone, Copy)]
+pub struct BiquadRoots {
+    pub gain:  f64,
+    pub zeros: [Complex<f64>; 2],
+    pub poles: [Complex<f64>; 2],
+}
+
+impl BiquadRoots {
+    /// Factors a biquad, gives an error if it isn't a stable biquad with b0 != 0 .
+    pub fn from_iir_filter(filter: & IIRFilter) -> Result<Self, String> {
+        if filter.order != 2 {
+            return Err(format!("Error: expected a biquad, got a filter of order {}", filter.order));
+        }
+        let a = & filter.a_coeffs;
+        let b = & filter.b_coeffs;
+        if b[0] == 0.0 {
+            return Err("Error: can't morph a biquad with b0 = 0".to_string());
+        }
+        let poles = quadratic_roots(a[1] / a[0], a[2] / a[0]);
+        if poles.iter().any(|p| p.norm() >= 1.0) {
+            return Err("Error: can't morph an unstable biquad".to_string());
+        }
+        Ok(BiquadRoots {
+            gain: b[0] / a[0],
+            zeros: quadratic_roots(b[1] / b[0], b[2] / b[0]),
+            poles,
+        })
+    }
+
+    /// The direct form biquad of the roots.
+    pub fn to_iir_filter(self) -> IIRFilter {
+        let [z1, z2] = self.zeros;
+        let [p1, p2] = self.poles;
+        // Real part, the imaginary part is 0 except for a complex to real morph.
+        let b = [self.gain, -self.gain * (z1 + z2).re, self.gain * (z1 * z2).re];
+        let a = [1.0, -(p1 + p2).re, (p1 * p2).re];
+        let mut filter = IIRFilter::new(2);
+        filter.set_coefficients(& a, & b).unwrap();
+        filter
+    }
+
+    /// Interpolation of the roots and the gain, t = 0 is self and t = 1 is other.
+    pub fn interpolate(& self, other: & BiquadRoots, t: f64) -> BiquadRoots {
+        let gain = if self.gain.signum() == other.gain.signum() {
+            // Linear in dB.
+            self.gain.signum() * self.gain.abs().powf(1.0 - t) * other.gain.abs().powf(t)
+        } else {
+            self.gain + (other.gain - self.gain) * t
+        };
+        BiquadRoots {
+            gain,
+            zeros: interpolate_pair(& self.zeros, & other.zeros, t),
+            poles: interpolate_pair(& self.poles, & other.poles, t),
+        }
+    }
+}
+
+/// Roots of z^2 + c1 z + c2, a complex pair with the positive imaginary part first,
+/// or a real pair with the largest first.
+pub fn quadratic_roots(c1: f64, c2: f64) -> [Complex<f64>; 2] {
+    let discriminant = c1 * c1 - 4.0 * c2;
+    if discriminant >= 0.0 {
+        let root = f64::sqrt(discriminant);
+        [Complex::new((-c1 + root) / 2.0, 0.0), Complex::new((-c1 - root) / 2.0, 0.0)]
+    } else {
+        let imaginary = f64::sqrt(-discriminant) / 2.0;
+        [Complex::new(-c1 / 2.0, imaginary), Complex::new(-c1 / 2.0, -imaginary)]
+    }
+}
+
+fn is_real_pair(pair: & [Complex<f64>; 2]) -> bool {
+    pair[0].im.abs() < REAL_TOLERANCE
+}
+
+fn interpolate_pair(a: & [Complex<f64>; 2], b: & [Complex<f64>; 2], t: f64) -> [Complex<f64>; 2] {
+    if is_real_pair(a) && is_real_pair(b) {
+        return [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t];
+    }
+    // The first root is in the upper half plane, the second in the lower one, a
+    // negative real root is at the angle PI or -PI.
+    let polar = |root: & Complex<f64>, upper: bool| -> (f64, f64) {
+        let angle = root.im.atan2(root.re).abs();
+        (root.norm(), if upper { angle } else { -angle })
+    };
+    let lerp = |x: f64, y: f64| x + (y - x) * t;
+    let mut result = [Complex::new(0.0, 0.0); 2];
+    for (i, upper) in [true, false].iter().enumerate() {
+        let (radius_a, angle_a) = polar(& a[i], *upper);
+        let (radius_b, angle_b) = polar(& b[i], *upper);
+        result[i] = Complex::from_polar(lerp(radius_a, radius_b), lerp(angle_a, angle_b));
+    }
+    result
+}
+
+/// Morph between two biquads, t = 0 gives filter A and t = 1 gives filter B.
+///
+/// In Rust:
+///    ```text
+///    >>> let a = make_peak(1_000.0, 48_000, 6.0, None);
+///    >>> let b = make_peak(4_000.0, 48_000, 6.0, None);
+///    >>> // A 6 dB peak at 2.5 kHz.
+///    >>> let halfway = morph(& a, & b, 0.5)?;
+///    ```
+///
+pub fn morph(filter_a: & IIRFilter, filter_b: & IIRFilter, t: f64) -> Result<IIRFilter, String> {
+    let roots_a = BiquadRoots::from_iir_filter(filter_a)?;
+    let roots_b = BiquadRoots::from_iir_filter(filter_b)?;
+    Ok(roots_a.interpolate(& roots_b, t.clamp(0.0, 1.0)).to_iir_filter())
+}
+
+/// A biquad that morphs between two filters with the morph parameter.
+pub struct MorphFilter {
+    roots_a: BiquadRoots,
+    roots_b: BiquadRoots,
+    morph:   f64,
+    lattice: LatticeBiquad,
+}
+
+impl MorphFilter {
+    pub fn new(filter_a: & IIRFilter, filter_b: & IIRFilter, morph: f64) -> Result<Self, String> {
+        let roots_a = BiquadRoots::from_iir_filter(filter_a)?;
+        let roots_b = BiquadRoots::from_iir_filter(filter_b)?;
+        let morph = morph.clamp(0.0, 1.0);
+        let coefficients = LatticeCoefficients::from_iir_filter(& roots_a.interpolate(& roots_b, morph).to_iir_filter())?;
+        Ok(MorphFilter { roots_a, roots_b, morph, lattice: LatticeBiquad::new(coefficients) })
+    }
+
+    pub fn get_morph(& self) -> f64 {
+        self.morph
+    }
+
+    /// Changes the morph, from 0 to 1, keeping the filter state.
+    pub fn set_morph(& mut self, morph: f64) {
+        self.morph = morph.clamp(0.0, 1.0);
+        let filter = self.roots_a.interpolate(& self.roots_b, self.morph).to_iir_filter();
+        // The interpolated poles of two stable filters are inside the unit circle.
+        self.lattice.set_coefficients(LatticeCoefficients::from_iir_filter(& filter).unwrap());
+    }
+
+    /// The direct form biquad of the current morph.
+    pub fn get_filter(& self) -> IIRFilter {
+        self.roots_a.interpolate(& self.roots_b, self.morph).to_iir_filter()
+    }
+}
+
+impl ProcessingBlock for MorphFilter {
+    fn process(& mut self, sample: f64) -> f64 {
+        self.lattice.process(sample)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_peak;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::butterworth_filter::make_highpass;
+
+    fn gain_db(filter: & IIRFilter, frequency: f64) -> f64 {
+        20.0 * f64::log10(filter.frequency_response(frequency, 48_000).norm())
+    }
+
+    #[test]
+    fn test_morph_end_points() {
+        let a = make_peak(1_000.0, 48_000, 6.0, Some(2.0));
+        let b = make_highpass(300.0, 48_000, None);
+        for (t, filter) in [(0.0, & a), (1.0, & b)] {
+            let morphed = morph(& a, & b, t).unwrap();
+            // The morphed filter is normalized to a0 = 1 .
+            for frequency in [20.0, 300.0, 1_000.0, 5_000.0, 20_000.0] {
+                let difference = morphed.frequency_response(frequency, 48_000) - filter.frequency_response(frequency, 48_000);
+                assert!(difference.norm() < 1e-9);
+            }
+        }
+        assert!(morph(& a, & IIRFilter::new(1), 0.5).is_err());
+    }
+
+    #[test]
+    fn test_morph_moves_the_peak() {
+        let a = make_peak(1_000.0, 48_000, 6.0, Some(2.0));
+        let b = make_peak(4_000.0, 48_000, 6.0, Some(2.0));
+        let halfway = morph(& a, & b, 0.5).unwrap();
+        let frequencies: Vec<f64> = (100..8_000).step_by(10).map(|f| f as f64).collect();
+        let loudest = frequencies.iter().cloned().max_by(|x, y| gain_db(& halfway, *x).total_cmp(& gain_db(& halfway, *y))).unwrap();
+        assert!(loudest > 2_000.0 && loudest < 3_000.0);
+        assert!((gain_db(& halfway, loudest) - 6.0).abs() < 0.5);
+
+        // A naive crossfade of the coefficients has two smaller bumps instead.
+        let naive_b: Vec<f64> = a.b_coeffs.iter().zip(& b.b_coeffs).map(|(x, y)| (x + y) / 2.0).collect();
+        let naive_a: Vec<f64> = a.a_coeffs.iter().zip(& b.a_coeffs).map(|(x, y)| (x + y) / 2.0).collect();
+        let mut naive = IIRFilter::new(2);
+        naive.set_coefficients(& naive_a, & naive_b).unwrap();
+        assert!(gain_db(& naive, loudest) < gain_db(& halfway, loudest) - 1.0);
+    }
+
+    #[test]
+    fn test_morph_filter_is_stable() {
+        let a = make_lowpass(200.0, 48_000, Some(5.0));
+        let b = make_highpass(8_000.0, 48_000, Some(0.3));
+        let mut filter = MorphFilter::new(& a, & b, 0.0).unwrap();
+        let mut peak: f64 = 0.0;
+        for n in 0..48_000 {
+            filter.set_morph(n as f64 / 48_000.0);
+            let output = filter.process(if n % 1_000 == 0 { 1.0 } else { 0.0 });
+            peak = peak.max(output.abs());
+        }
+        assert!(peak < 10.0);
+        assert_eq!(filter.get_morph(), 1.0 - 1.0 / 48_000.0);
+        assert!((gain_db(& filter.get_filter(), 20_000.0)).abs() < 1.0);
+    }
+}
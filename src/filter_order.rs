@@ -0,0 +1,141 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Estimation of the minimum filter order for a specification, like the
+//!              buttord, cheb1ord and ellipord functions of scipy.
+//!              The specification is the edge of the passband, the edge of the
+//!              stopband, the maximum ripple (loss) in dB of the passband, and the
+//!              minimum attenuation in dB of the stopband. A lowpass has the passband
+//!              below the stopband, a highpass above.
+//!
+//!              The edges are prewarped for the bilinear transform, and the ratio of
+//!              the analog stopband and passband edges gives the order:
+//!
+//!                 Butterworth:  n >= log10((Gs - 1) / (Gp - 1)) / (2 log10(ws / wp))
+//!                 Chebyshev I:  n >= acosh(sqrt((Gs - 1) / (Gp - 1))) / acosh(ws / wp)
+//!                 Elliptic:     n >= K(k^2) K(1 - k1^2) / (K(1 - k^2) K(k1^2))
+//!
+//!              with Gp = 10^(ripple / 10), Gs = 10^(attenuation / 10), k = wp / ws,
+//!              k1 = sqrt((Gp - 1) / (Gs - 1)), and K the complete elliptic integral
+//!              of the first kind.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. scipy.signal.buttord
+//!       https://docs.scipy.org/doc/scipy/reference/generated/scipy.signal.buttord.html
+//!
+//!    2. Elliptic filter - Wikipedia
+//!       https://en.wikipedia.org/wiki/Elliptic_filter
+//!
+
+
+use std::f64::consts::PI;
+
+
+// The analog selectivity ws / wp and the discrimination (Gs - 1) / (Gp - 1).
+fn prewarped_ratios(passband: f64, stopband: f64, ripple_db: f64, attenuation_db: f64, sample_rate: u32)
+        -> Result<(f64, f64), String> {
+    let nyquist = sample_rate as f64 / 2.0;
+    for frequency in [passband, stopband] {
+        if frequency <= 0.0 || frequency >= nyquist {
+            return Err(format!("Error: the frequency {} Hz must be between 0 and {} Hz", frequency, nyquist));
+        }
+    }
+    if passband == stopband {
+        return Err("Error: the passband and the stopband edges must be different".to_string());
+    }
+    if ripple_db <= 0.0 || attenuation_db <= ripple_db {
+        return Err(format!("Error: expected 0 < ripple < attenuation, got {} dB and {} dB", ripple_db, attenuation_db));
+    }
+    let passband_analog = f64::tan(PI * passband / sample_rate as f64);
+    let stopband_analog = f64::tan(PI * stopband / sample_rate as f64);
+    // Lowpass or highpass.
+    let selectivity = if stopband > passband {
+        stopband_analog / passband_analog
+    } else {
+        passband_analog / stopband_analog
+    };
+    let discrimination = (f64::powf(10.0, attenuation_db / 10.0) - 1.0) / (f64::powf(10.0, ripple_db / 10.0) - 1.0);
+    Ok((selectivity, discrimination))
+}
+
+/// Minimum order of a Butterworth filter with at most ripple_db of loss up to the
+/// passband edge, and at least attenuation_db from the stopband edge.
+///
+/// In Rust:
+///    ```text
+///    >>> // Lowpass, -3 dB at 4.8 kHz and -40 dB at 7.2 kHz.
+///    >>> estimate_order_butterworth(4_800.0, 7_200.0, 3.0, 40.0, 48_000)
+///    Ok(11)
+///    ```
+///
+pub fn estimate_order_butterworth(passband: f64, stopband: f64, ripple_db: f64, attenuation_db: f64, sample_rate: u32)
+        -> Result<usize, String> {
+    let (selectivity, discrimination) = prewarped_ratios(passband, stopband, ripple_db, attenuation_db, sample_rate)?;
+    let order = f64::log10(discrimination) / (2.0 * f64::log10(selectivity));
+    Ok(order.ceil() as usize)
+}
+
+/// Minimum order of a Chebyshev type I filter, with an equiripple passband of
+/// ripple_db.
+pub fn estimate_order_chebyshev(passband: f64, stopband: f64, ripple_db: f64, attenuation_db: f64, sample_rate: u32)
+        -> Result<usize, String> {
+    let (selectivity, discrimination) = prewarped_ratios(passband, stopband, ripple_db, attenuation_db, sample_rate)?;
+    let order = f64::acosh(f64::sqrt(discrimination)) / f64::acosh(selectivity);
+    Ok(order.ceil() as usize)
+}
+
+/// Minimum order of an elliptic (Cauer) filter, with an equiripple passband and
+/// stopband.
+pub fn estimate_order_elliptic(passband: f64, stopband: f64, ripple_db: f64, attenuation_db: f64, sample_rate: u32)
+        -> Result<usize, String> {
+    let (selectivity, discrimination) = prewarped_ratios(passband, stopband, ripple_db, attenuation_db, sample_rate)?;
+    let k = 1.0 / selectivity;
+    let k1 = 1.0 / f64::sqrt(discrimination);
+    let order = elliptic_k(k * k) * elliptic_k(1.0 - k1 * k1)
+              / (elliptic_k(1.0 - k * k) * elliptic_k(k1 * k1));
+    Ok(order.ceil() as usize)
+}
+
+/// Complete elliptic integral of the first kind K(m), with the parameter m = k^2,
+/// by the arithmetic-geometric mean.
+fn elliptic_k(m: f64) -> f64 {
+    assert!((0.0..1.0).contains(& m));
+    let mut a = 1.0;
+    let mut b = f64::sqrt(1.0 - m);
+    while (a - b).abs() > 1e-15 * a {
+        (a, b) = ((a + b) / 2.0, f64::sqrt(a * b));
+    }
+    PI / (2.0 * a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orders_match_scipy() {
+        // scipy.signal.buttord(0.2, 0.3, 3, 40) is 11, cheb1ord is 6 and ellipord is 4.
+        assert_eq!(estimate_order_butterworth(4_800.0, 7_200.0, 3.0, 40.0, 48_000), Ok(11));
+        assert_eq!(estimate_order_chebyshev(4_800.0, 7_200.0, 3.0, 40.0, 48_000), Ok(6));
+        assert_eq!(estimate_order_elliptic(4_800.0, 7_200.0, 3.0, 40.0, 48_000), Ok(4));
+
+        // Highpass.
+        assert_eq!(estimate_order_butterworth(2_000.0, 1_000.0, 0.5, 40.0, 44_100), Ok(9));
+        assert_eq!(estimate_order_chebyshev(2_000.0, 1_000.0, 0.5, 40.0, 44_100), Ok(5));
+        assert_eq!(estimate_order_elliptic(2_000.0, 1_000.0, 0.5, 40.0, 44_100), Ok(4));
+        assert!((elliptic_k(0.0) - PI / 2.0).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_invalid_specifications() {
+        assert!(estimate_order_butterworth(1_000.0, 30_000.0, 1.0, 60.0, 48_000).is_err());
+        assert!(estimate_order_chebyshev(1_000.0, 1_000.0, 1.0, 60.0, 48_000).is_err());
+        assert!(estimate_order_elliptic(1_000.0, 2_000.0, 0.0, 60.0, 48_000).is_err());
+        assert!(estimate_order_elliptic(1_000.0, 2_000.0, 10.0, 6.0, 48_000).is_err());
+    }
+}
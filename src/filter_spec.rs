@@ -0,0 +1,230 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Sample rate agnostic filter specifications.
+//!              A make_* function of butterworth_filter.rs returns the coefficients
+//!              for one sample rate, and a chain of IIRFilters made at 48 kHz is
+//!              wrong on a 44.1 kHz stream. A FilterSpec is the design, the type, the
+//!              frequency, the Q and the gain, and realize(sample_rate) makes the
+//!              filter for a rate, a FilterChain of one biquad or the sections of a
+//!              cascade, checked by the default DesignPolicy, so a spec above the
+//!              Nyquist frequency of the stream is an error, not a filter that
+//!              explodes.
+//!              The frequency can also be set by pitch, from_note() takes a note name
+//!              like A4 or C#2+14c.
+//!              A SpecChain stores the specs, and realizes them when the sample rate
+//!              of the stream is known, once per rate: prepare() with the same rate
+//!              keeps the filters and their state, a new rate realizes them again.
+//!              Each stream, like each channel of a file, gets its own realized
+//!              chain, with its own state:
+//!
+//!                 specs --realize(44100)--> IIRFilters at 44.1 kHz
+//!                       --realize(48000)--> IIRFilters at 48 kHz
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use crate::iir_filter::FilterChain;
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::design_policy::DesignPolicy;
+use crate::design_policy::FilterKind;
+use crate::audio_buffer::AudioBuffer;
+use crate::note_names::note_to_frequency;
+use crate::note_names::frequency_to_note;
+
+
+/// The design of a biquad or of a cascade, without a sample rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FilterSpec {
+    pub kind:      FilterKind,
+    pub frequency: f64,
+    pub q_factor:  f64,
+    /// Ignored by the kinds without a gain.
+    pub gain_db:   f64,
+}
+
+impl FilterSpec {
+    pub fn new(kind: FilterKind, frequency: f64, q_factor: f64, gain_db: f64) -> Self {
+        FilterSpec { kind, frequency, q_factor, gain_db }
+    }
+
+    /// The spec at the frequency of a note name, like "E1" or "C#2+14c", see
+    /// note_names.rs .
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> // The resonance of a kick drum, a half semitone above G1.
+    ///    >>> let spec = FilterSpec::from_note(FilterKind::Peak, "G1+50c", 4.0, -6.0)?;
+    ///    ```
+    ///
+    pub fn from_note(kind: FilterKind, note: & str, q_factor: f64, gain_db: f64) -> Result<Self, String> {
+        Ok(FilterSpec::new(kind, note_to_frequency(note)?, q_factor, gain_db))
+    }
+
+    /// The nearest note of the frequency, like "B5+21c" for 1000 Hz.
+    pub fn get_note_name(& self) -> String {
+        frequency_to_note(self.frequency)
+    }
+
+    /// The filter of the spec at the sample rate, an error if the spec is out of
+    /// the limits of the default DesignPolicy at that rate.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let spec = FilterSpec::new(FilterKind::Peak, 1_000.0, 2.0, 6.0);
+    ///    >>> let filter = spec.realize(file_sample_rate)?;
+    ///    ```
+    ///
+    pub fn realize(& self, sample_rate: u32) -> Result<FilterChain, String> {
+        let design = DesignPolicy::default().make(self.kind, self.frequency, sample_rate, self.gain_db, self.q_factor)?;
+        Ok(design.filter)
+    }
+}
+
+/// A chain of filter specs, in series, realized at the sample rate of the stream.
+pub struct SpecChain {
+    specs:       Vec<FilterSpec>,
+    // The rate of the realized filters, None before prepare().
+    sample_rate: Option<u32>,
+    filters:     Vec<FilterChain>,
+}
+
+impl SpecChain {
+    pub fn new(specs: Vec<FilterSpec>) -> Self {
+        SpecChain { specs, sample_rate: None, filters: Vec::new() }
+    }
+
+    pub fn get_specs(& self) -> & [FilterSpec] {
+        & self.specs
+    }
+
+    /// The rate of the realized filters, None if the chain isn't prepared.
+    pub fn get_sample_rate(& self) -> Option<u32> {
+        self.sample_rate
+    }
+
+    /// Adds a spec at the end, the chain needs a new prepare().
+    pub fn push(& mut self, spec: FilterSpec) {
+        self.specs.push(spec);
+        self.sample_rate = None;
+        self.filters.clear();
+    }
+
+    /// The filters of the specs at the sample rate.
+    pub fn realize(& self, sample_rate: u32) -> Result<Vec<FilterChain>, String> {
+        self.specs.iter().map(|spec| spec.realize(sample_rate)).collect()
+    }
+
+    /// Realizes the specs for a stream at the sample rate, if they aren't already
+    /// realized at that rate. A new rate starts the filters from a zero state.
+    pub fn prepare(& mut self, sample_rate: u32) -> Result<(), String> {
+        if self.sample_rate != Some(sample_rate) {
+            let _span = trace_span!("spec_chain_realize", sample_rate, specs = self.specs.len());
+            self.filters = self.realize(sample_rate)?;
+            self.sample_rate = Some(sample_rate);
+        }
+        Ok(())
+    }
+
+    /// A new chain with the same specs, prepared at the sample rate, for a stream
+    /// with its own state.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let chain = SpecChain::new(vec![FilterSpec::new(FilterKind::HighPass, 80.0, 0.7071, 0.0)]);
+    ///    >>> process_file("in.wav", "out.wav", 48_000, RatePolicy::Redesign,
+    ///    >>>     |_, sample_rate| chain.prepared(sample_rate).unwrap())?;
+    ///    ```
+    ///
+    pub fn prepared(& self, sample_rate: u32) -> Result<SpecChain, String> {
+        let mut chain = SpecChain::new(self.specs.clone());
+        chain.prepare(sample_rate)?;
+        Ok(chain)
+    }
+
+    /// Processes each channel of the audio with the chain realized at its sample
+    /// rate, each channel with its own state.
+    pub fn process_audio(& self, audio: & AudioBuffer) -> Result<AudioBuffer, String> {
+        let channels = audio.channels.iter()
+            .map(|channel| {
+                let mut chain = self.prepared(audio.sample_rate)?;
+                Ok(channel.iter().map(|x| chain.process(*x)).collect())
+            })
+            .collect::<Result<Vec<Vec<f64>>, String>>()?;
+        Ok(AudioBuffer::new(audio.sample_rate, channels))
+    }
+}
+
+impl ProcessingBlock for SpecChain {
+    /// The chain must be prepared at the rate of the stream.
+    fn process(& mut self, sample: f64) -> f64 {
+        assert!(self.sample_rate.is_some(), "the SpecChain must be prepared at a sample rate before the processing");
+        self.filters.iter_mut().fold(sample, |x, filter| filter.process(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_peak;
+    use crate::butterworth_filter::make_lowpass;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    #[test]
+    fn test_realize_per_rate() {
+        let spec = FilterSpec::new(FilterKind::Peak, 1_000.0, 2.0, 6.0);
+        for sample_rate in [44_100, 48_000, 96_000] {
+            let filter = spec.realize(sample_rate).unwrap();
+            assert_eq!(filter.get_filters()[0].a_coeffs, make_peak(1_000.0, sample_rate, 6.0, Some(2.0)).a_coeffs);
+            // The peak is at 1 kHz at any rate.
+            let gain = filter.frequency_response(1_000.0, sample_rate).norm();
+            assert!((20.0 * gain.log10() - 6.0).abs() < 1e-9);
+        }
+        let spec = FilterSpec::from_note(FilterKind::Notch, "A2", 4.0, 0.0).unwrap();
+        assert_eq!((spec.frequency, spec.get_note_name().as_str()), (110.0, "A2"));
+        assert!(FilterSpec::from_note(FilterKind::Notch, "A", 4.0, 0.0).is_err());
+        // A third of an octave band-stop of the 50 Hz hum, at any rate.
+        let spec = FilterSpec::new(FilterKind::BandStop, 50.0, 1.0 / 3.0, 0.0);
+        for sample_rate in [44_100, 48_000] {
+            assert!(spec.realize(sample_rate).unwrap().frequency_response(50.0, sample_rate).norm() < 1e-9);
+        }
+        assert!(FilterSpec::new(FilterKind::BandPassBw, 1_000.0, 0.0, 0.0).realize(48_000).is_err());
+        // Above the Nyquist frequency of 32 kHz.
+        assert!(FilterSpec::new(FilterKind::LowPass, 20_000.0, FRAC_1_SQRT_2, 0.0).realize(32_000).is_err());
+        // A cascade, the Q is ignored.
+        let spec = FilterSpec::new(FilterKind::LinkwitzRileyHighPass(4), 2_000.0, 0.0, 0.0);
+        for sample_rate in [44_100, 48_000] {
+            let chain = spec.realize(sample_rate).unwrap();
+            assert_eq!(chain.get_order(), 4);
+            assert!((chain.frequency_response(2_000.0, sample_rate).norm() - 0.5).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_spec_chain() {
+        let mut chain = SpecChain::new(vec![FilterSpec::new(FilterKind::LowPass, 1_000.0, FRAC_1_SQRT_2, 0.0)]);
+        assert_eq!(chain.get_sample_rate(), None);
+        chain.prepare(44_100).unwrap();
+        let first = chain.process(1.0);
+        // The same rate keeps the state, the second sample of the impulse response.
+        chain.prepare(44_100).unwrap();
+        let mut reference = make_lowpass(1_000.0, 44_100, Some(FRAC_1_SQRT_2));
+        assert_eq!((first, chain.process(0.0)), (reference.process(1.0), reference.process(0.0)));
+        chain.prepare(48_000).unwrap();
+        assert_eq!(chain.get_sample_rate(), Some(48_000));
+        assert_eq!(chain.process(1.0), make_lowpass(1_000.0, 48_000, Some(FRAC_1_SQRT_2)).process(1.0));
+
+        chain.push(FilterSpec::new(FilterKind::HighShelf, 8_000.0, FRAC_1_SQRT_2, -3.0));
+        assert_eq!(chain.get_sample_rate(), None);
+        let audio = AudioBuffer::new(22_050, vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]]);
+        let output = chain.process_audio(& audio).unwrap();
+        assert_eq!(output.sample_rate, 22_050);
+        // Each channel starts from a zero state.
+        assert_eq!(output.channels[0][0], output.channels[1][1]);
+        assert!(chain.process_audio(& AudioBuffer::new(8_000, vec![vec![0.0]])).is_err());
+    }
+}
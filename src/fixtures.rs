@@ -0,0 +1,233 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: The standard test files, generated, for the integration tests and
+//!              the examples, so the repository doesn't need binary audio files.
+//!              Each fixture is deterministic, the same samples and the same bytes
+//!              of the WAV file on all the machines:
+//!
+//!                 sine_sweep       Logarithmic sine sweep, 20 Hz to 20 kHz in 2 s,
+//!                                  at -6 dBFS peak, with fades of 10 ms, mono.
+//!                 pink_noise       2 s of periodic pink noise, see generators.rs, at
+//!                                  -6 dBFS peak, a different seed on each channel,
+//!                                  stereo.
+//!                 silence_click    1 s of digital silence, with a single sample click
+//!                                  of full scale at 0.5 s, mono.
+//!                 clipped          1 s of a 1 kHz sine of amplitude 2.0, clipped at
+//!                                  full scale, in 16 bit, mono.
+//!
+//!              The sweep is the exponential sweep of Farina, that spends the same
+//!              time in each octave:
+//!
+//!                 x(t) = sin(2 pi f1 T / ln(f2 / f1) (e^(t / T ln(f2 / f1)) - 1))
+//!
+//!              write_fixtures() writes all of them to a directory, by default a
+//!              directory of the temp dir, fixtures_dir().
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Farina, A. - Simultaneous measurement of impulse response and distortion
+//!       with a swept-sine technique. AES 108th Convention, 2000.
+//!
+
+
+use std::f64::consts::TAU;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::audio_buffer::AudioBuffer;
+use crate::audio_io::SampleFormat;
+use crate::audio_io::write_wav;
+use crate::generators::make_periodic_pink_noise;
+
+
+/// Names of the fixtures, of Fixture::from_name().
+pub const FIXTURE_NAMES: [&str; 4] = ["sine_sweep", "pink_noise", "silence_click", "clipped"];
+pub const DEFAULT_FIXTURE_SAMPLE_RATE: u32 = 48_000;
+
+// Peak amplitude of the sweep and of the pink noise, -6 dBFS.
+const FIXTURE_AMPLITUDE: f64 = 0.5;
+const SWEEP_START_FREQUENCY: f64 = 20.0;
+const SWEEP_END_FREQUENCY: f64 = 20_000.0;
+const SWEEP_SECONDS: f64 = 2.0;
+const SWEEP_FADE_SECONDS: f64 = 0.01;
+const PINK_NOISE_SECONDS: f64 = 2.0;
+// Seeds of the left and right channels of the pink noise.
+const PINK_NOISE_SEEDS: [u32; 2] = [1, 2];
+const CLICK_SECONDS: f64 = 1.0;
+const CLIPPED_FREQUENCY: f64 = 1_000.0;
+const CLIPPED_AMPLITUDE: f64 = 2.0;
+
+/// A standard test file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fixture {
+    SineSweep,
+    PinkNoise,
+    SilenceClick,
+    Clipped,
+}
+
+impl Fixture {
+    /// All the fixtures, in the order of FIXTURE_NAMES.
+    pub fn all() -> [Fixture; 4] {
+        [Fixture::SineSweep, Fixture::PinkNoise, Fixture::SilenceClick, Fixture::Clipped]
+    }
+
+    /// The fixture of a name of FIXTURE_NAMES.
+    pub fn from_name(name: & str) -> Result<Self, String> {
+        FIXTURE_NAMES.iter().position(|n| *n == name)
+            .map(|index| Fixture::all()[index])
+            .ok_or(format!("Error: unknown fixture \"{}\", the fixtures are {}", name, FIXTURE_NAMES.join(", ")))
+    }
+
+    pub fn get_name(& self) -> & 'static str {
+        FIXTURE_NAMES[Fixture::all().iter().position(|f| f == self).unwrap()]
+    }
+
+    /// The file name of the fixture, <name>.wav .
+    pub fn get_file_name(& self) -> String {
+        format!("{}.wav", self.get_name())
+    }
+
+    /// The sample format of the file, 16 bit for the clipped material, like a
+    /// clipped recording, and float 32 for the others.
+    pub fn get_sample_format(& self) -> SampleFormat {
+        match self {
+            Fixture::Clipped => SampleFormat::Int16,
+            _                => SampleFormat::Float32,
+        }
+    }
+
+    /// The samples of the fixture at the sample rate, that must be above 40 kHz
+    /// for the sweep to 20 kHz.
+    pub fn generate(& self, sample_rate: u32) -> AudioBuffer {
+        assert!(sample_rate as f64 > 2.0 * SWEEP_END_FREQUENCY);
+        let num_samples = |seconds: f64| (seconds * sample_rate as f64).round() as usize;
+        let channels = match self {
+            Fixture::SineSweep => vec![make_log_sweep(SWEEP_START_FREQUENCY, SWEEP_END_FREQUENCY,
+                                                      SWEEP_SECONDS, sample_rate)],
+            Fixture::PinkNoise => PINK_NOISE_SEEDS.iter()
+                .map(|seed| make_periodic_pink_noise(num_samples(PINK_NOISE_SECONDS), *seed).iter()
+                    .map(|x| FIXTURE_AMPLITUDE * x)
+                    .collect())
+                .collect(),
+            Fixture::SilenceClick => {
+                let mut channel = vec![0.0; num_samples(CLICK_SECONDS)];
+                channel[num_samples(CLICK_SECONDS / 2.0)] = 1.0;
+                vec![channel]
+            },
+            Fixture::Clipped => vec![(0..sample_rate as usize)
+                .map(|n| (CLIPPED_AMPLITUDE * f64::sin(TAU * CLIPPED_FREQUENCY * n as f64 / sample_rate as f64))
+                    .clamp(-1.0, 1.0))
+                .collect()],
+        };
+        AudioBuffer::new(sample_rate, channels)
+    }
+}
+
+/// The logarithmic sine sweep from f_start to f_end in the duration, with a peak
+/// of -6 dBFS and fades in and out of 10 ms.
+pub fn make_log_sweep(f_start: f64, f_end: f64, duration_seconds: f64, sample_rate: u32) -> Vec<f64> {
+    assert!(f_start > 0.0 && f_end > f_start && f_end < sample_rate as f64 / 2.0 && duration_seconds > 0.0);
+    let num_samples = (duration_seconds * sample_rate as f64).round() as usize;
+    let k = f64::ln(f_end / f_start);
+    let fade_samples = (SWEEP_FADE_SECONDS * sample_rate as f64) as usize;
+    (0..num_samples).map(|n| {
+        let t = n as f64 / sample_rate as f64;
+        let phase = TAU * f_start * duration_seconds / k * (f64::exp(t / duration_seconds * k) - 1.0);
+        let distance_to_edge = usize::min(n, num_samples - 1 - n);
+        let fade = if distance_to_edge < fade_samples { distance_to_edge as f64 / fade_samples as f64 } else { 1.0 };
+        FIXTURE_AMPLITUDE * fade * f64::sin(phase)
+    }).collect()
+}
+
+/// The directory of the fixtures in the temp dir.
+pub fn fixtures_dir() -> PathBuf {
+    std::env::temp_dir().join("audio_filters_fixtures")
+}
+
+/// The path of a file of the tests in the temp dir, "audio_filters_test_" and the
+/// name, so the tests don't write to the repository.
+pub fn temp_test_path(name: & str) -> String {
+    std::env::temp_dir().join(format!("audio_filters_test_{}", name)).to_string_lossy().into_owned()
+}
+
+/// Writes the fixture to the directory, and returns the path of the file.
+pub fn write_fixture(fixture: Fixture, dir: & Path, sample_rate: u32) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Error: creating directory {}: {}", dir.display(), e))?;
+    let path = dir.join(fixture.get_file_name());
+    let path_str = path.to_str().ok_or(format!("Error: invalid path {}", path.display()))?;
+    write_wav(path_str, & fixture.generate(sample_rate), fixture.get_sample_format())?;
+    Ok(path)
+}
+
+/// Writes all the fixtures to the directory, and returns the paths of the files.
+///
+/// In Rust:
+///    ```text
+///    >>> let paths = write_fixtures(& fixtures_dir(), DEFAULT_FIXTURE_SAMPLE_RATE)?;
+///    >>> filter_wav_file(paths[0].to_str().unwrap(), "sweep_lowpass.wav", & mut lowpass)?;
+///    ```
+///
+pub fn write_fixtures(dir: & Path, sample_rate: u32) -> Result<Vec<PathBuf>, String> {
+    Fixture::all().iter().map(|fixture| write_fixture(*fixture, dir, sample_rate)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_io::read_wav;
+    use crate::diagnostics::analyze_audio;
+
+    #[test]
+    fn test_fixtures() {
+        let sample_rate = DEFAULT_FIXTURE_SAMPLE_RATE;
+        for (fixture, name) in Fixture::all().iter().zip(FIXTURE_NAMES) {
+            assert_eq!(Fixture::from_name(name).unwrap(), *fixture);
+            assert_eq!(fixture.get_name(), name);
+            assert_eq!(fixture.generate(sample_rate), fixture.generate(sample_rate));
+        }
+        assert!(Fixture::from_name("white_noise").is_err());
+
+        let sweep = Fixture::SineSweep.generate(sample_rate);
+        assert_eq!(sweep.get_num_frames(), 96_000);
+        let peak = sweep.channels[0].iter().fold(0.0, |max: f64, x| max.max(x.abs()));
+        assert!(peak <= FIXTURE_AMPLITUDE && peak > 0.499);
+        assert_eq!((sweep.channels[0][0], sweep.channels[0][95_999]), (0.0, 0.0));
+
+        let noise = Fixture::PinkNoise.generate(sample_rate);
+        assert_eq!(noise.get_num_channels(), 2);
+        assert_ne!(noise.channels[0], noise.channels[1]);
+
+        let click = Fixture::SilenceClick.generate(sample_rate);
+        let nonzero: Vec<usize> = (0..click.get_num_frames()).filter(|n| click.channels[0][*n] != 0.0).collect();
+        assert_eq!(nonzero, vec![24_000]);
+
+        let clipped = Fixture::Clipped.generate(sample_rate);
+        let report = analyze_audio(& clipped, Fixture::Clipped.get_sample_format());
+        assert!(report.get_clipped_samples() > sample_rate as usize / 2);
+    }
+
+    #[test]
+    fn test_write_fixtures() {
+        let dir = PathBuf::from(temp_test_path("fixtures"));
+        let paths = write_fixtures(& dir, 44_100).unwrap();
+        assert_eq!(paths.len(), FIXTURE_NAMES.len());
+        let bytes: Vec<Vec<u8>> = paths.iter().map(|path| std::fs::read(path).unwrap()).collect();
+        // The same bytes when written again.
+        let paths = write_fixtures(& dir, 44_100).unwrap();
+        for (path, first_bytes) in paths.iter().zip(& bytes) {
+            assert_eq!(& std::fs::read(path).unwrap(), first_bytes);
+        }
+        for (path, fixture) in paths.iter().zip(Fixture::all()) {
+            let (audio, sample_format) = read_wav(path.to_str().unwrap()).unwrap();
+            assert_eq!((audio.sample_rate, sample_format), (44_100, fixture.get_sample_format()));
+        }
+        assert!(fixtures_dir().ends_with("audio_filters_fixtures"));
+        let _ = std::fs::remove_dir_all(& dir);
+    }
+}
@@ -0,0 +1,210 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: The processing blocks as the AudioNodes of the fundsp crate, with
+//!              the feature "fundsp", to combine the cookbook biquads, the
+//!              Butterworth, Bessel and Linkwitz-Riley designs and the equalizers
+//!              of this crate with the oscillators, the noises and the effects of
+//!              fundsp, in the graphs of its DSL:
+//!
+//!                 >>> let mut graph = pink() >> block_node(make_lowpass(2_000.0, 48_000, None)) >> pan(0.0);
+//!
+//!              A BlockNode is a node of one input and one output, the f32 samples
+//!              of fundsp converted to the f64 of the block and back. Its
+//!              frequency response, for the analysis of a graph with response() of
+//!              fundsp, is the one of the NodeResponse of the block, at the sample
+//!              rate of the graph. The filters, FilterChain, BiquadTDF2,
+//!              ParametricEq and Equalizer implement it, the other blocks, like
+//!              the dynamics, implement it with the default of an unknown response,
+//!              a nonlinear node. The coefficients of a block are designed for a
+//!              sample rate, they aren't changed by set_sample_rate() of fundsp,
+//!              the block is designed at the sample rate of the graph.
+//!              reset() of fundsp restores the block as it was given, with its
+//!              state, the zero state of a new filter.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    FunDSP - Audio processing and synthesis library for Rust
+//!    https://github.com/SamiPerttu/fundsp
+//!
+
+
+use fundsp::prelude::An;
+use fundsp::prelude::AudioNode; // Trait
+use fundsp::prelude::Frame;
+use fundsp::prelude::SignalFrame;
+use fundsp::prelude::U1;
+use rustfft::num_complex::Complex;
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::iir_filter::IIRFilter;
+use crate::iir_filter::FilterChain;
+use crate::biquad_tdf2::BiquadTDF2;
+use crate::parametric_eq::ParametricEq;
+use crate::equalizer::Equalizer;
+
+
+/// The sample rate of a node before set_sample_rate(), the default of fundsp.
+pub const DEFAULT_NODE_SAMPLE_RATE: f64 = 44_100.0;
+
+/// The frequency response of a block in a fundsp graph.
+pub trait NodeResponse {
+    /// The response at the frequency, for the sample rate of the graph, None for
+    /// a nonlinear block.
+    fn node_response(& self, _frequency: f64, _sample_rate: f64) -> Option<Complex<f64>> {
+        None
+    }
+}
+
+impl NodeResponse for IIRFilter {
+    fn node_response(& self, frequency: f64, sample_rate: f64) -> Option<Complex<f64>> {
+        Some(self.frequency_response(frequency, sample_rate.round() as u32))
+    }
+}
+
+impl NodeResponse for FilterChain {
+    fn node_response(& self, frequency: f64, sample_rate: f64) -> Option<Complex<f64>> {
+        Some(self.frequency_response(frequency, sample_rate.round() as u32))
+    }
+}
+
+impl NodeResponse for BiquadTDF2 {
+    fn node_response(& self, frequency: f64, sample_rate: f64) -> Option<Complex<f64>> {
+        Some(self.frequency_response(frequency, sample_rate.round() as u32))
+    }
+}
+
+// The equalizers have their own sample rate, the frequency is scaled to it.
+impl NodeResponse for ParametricEq {
+    fn node_response(& self, frequency: f64, sample_rate: f64) -> Option<Complex<f64>> {
+        Some(self.frequency_response(frequency * self.get_sample_rate() as f64 / sample_rate))
+    }
+}
+
+impl NodeResponse for Equalizer {
+    fn node_response(& self, frequency: f64, sample_rate: f64) -> Option<Complex<f64>> {
+        Some(self.frequency_response(frequency * self.get_sample_rate() as f64 / sample_rate))
+    }
+}
+
+/// A processing block as a fundsp AudioNode, of one input and one output.
+#[derive(Clone)]
+pub struct BlockNode<B> {
+    block:       B,
+    // The block as it was given, of reset().
+    initial:     B,
+    sample_rate: f64,
+}
+
+impl<B> BlockNode<B>
+        where B: ProcessingBlock + NodeResponse + Clone + Send + Sync {
+    pub fn new(block: B) -> Self {
+        BlockNode { initial: block.clone(), block, sample_rate: DEFAULT_NODE_SAMPLE_RATE }
+    }
+
+    pub fn get_block_mut(& mut self) -> & mut B {
+        & mut self.block
+    }
+}
+
+impl<B> AudioNode for BlockNode<B>
+        where B: ProcessingBlock + NodeResponse + Clone + Send + Sync {
+    // Above the IDs of the nodes of fundsp.
+    const ID: u64 = 0x4146_494c;
+    type Inputs = U1;
+    type Outputs = U1;
+
+    fn reset(& mut self) {
+        self.block.clone_from(& self.initial);
+    }
+
+    fn set_sample_rate(& mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate;
+    }
+
+    fn tick(& mut self, input: & Frame<f32, U1>) -> Frame<f32, U1> {
+        [self.block.process(input[0] as f64) as f32].into()
+    }
+
+    fn route(& mut self, input: & SignalFrame, frequency: f64) -> SignalFrame {
+        let mut output = SignalFrame::new(1);
+        let signal = match self.block.node_response(frequency, self.sample_rate) {
+            Some(response) => input.at(0).filter(0.0, |r| r * response),
+            None           => input.at(0).distort(0.0),
+        };
+        output.set(0, signal);
+        output
+    }
+}
+
+/// The block as a node of the DSL of fundsp.
+///
+/// In Rust:
+///    ```text
+///    >>> let eq = ParametricEq::from_bands(48_000, -3.0, & bands)?;
+///    >>> let mut graph = (noise() >> block_node(eq) >> block_node(make_highpass(40.0, 48_000, None)))
+///    >>>     * 0.5;
+///    >>> graph.set_sample_rate(48_000.0);
+///    >>> let response = graph.response(0, 1_000.0);
+///    ```
+///
+pub fn block_node<B>(block: B) -> An<BlockNode<B>>
+        where B: ProcessingBlock + NodeResponse + Clone + Send + Sync {
+    An(BlockNode::new(block))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_highpass;
+    use crate::butterworth_filter::make_linkwitz_riley_lowpass;
+    use crate::butterworth_filter::make_peak;
+    use crate::parametric_eq::BandType;
+    use crate::parametric_eq::ParametricBand;
+    use fundsp::prelude::AudioUnit; // Trait
+    use fundsp::prelude::pass;
+
+    #[derive(Clone)]
+    struct Clipper;
+
+    impl ProcessingBlock for Clipper {
+        fn process(& mut self, sample: f64) -> f64 {
+            sample.clamp(-0.5, 0.5)
+        }
+    }
+
+    impl NodeResponse for Clipper {}
+
+    #[test]
+    fn test_block_node() {
+        let input: Vec<f32> = (0..1_000).map(|n| f32::sin(n as f32 * 0.3)).collect();
+        let mut node = block_node(make_peak(1_000.0, 48_000, 6.0, Some(2.0))) >> block_node(make_highpass(100.0, 48_000, None));
+        let (mut peak, mut highpass) = (make_peak(1_000.0, 48_000, 6.0, Some(2.0)), make_highpass(100.0, 48_000, None));
+        let output: Vec<f32> = input.iter().map(|x| node.filter_mono(*x)).collect();
+        let mut expected = |x: & f32| highpass.process(peak.process(*x as f64) as f32 as f64) as f32;
+        assert!(input.iter().zip(& output).all(|(x, y)| expected(x) == *y));
+        // reset() is the block as it was given.
+        node.reset();
+        assert_eq!(node.filter_mono(input[0]), output[0]);
+
+        // The response of the graph is the one of the blocks, at the sample rate
+        // of the graph.
+        let chain = make_linkwitz_riley_lowpass(2_000.0, 48_000, 4).unwrap();
+        let eq = ParametricEq::from_bands(48_000, 0.0, & [ParametricBand::new(BandType::Peak, 500.0, 6.0, 1.0)]).unwrap();
+        let mut graph = block_node(chain.clone()) >> block_node(eq) >> pass();
+        graph.set_sample_rate(48_000.0);
+        for frequency in [100.0, 500.0, 2_000.0, 8_000.0] {
+            let response = graph.response(0, frequency).unwrap();
+            let expected = chain.frequency_response(frequency, 48_000) * make_peak(500.0, 48_000, 6.0, Some(1.0))
+                .frequency_response(frequency, 48_000);
+            assert!((response - expected).norm() < 1e-9);
+        }
+        let mut nonlinear = block_node(make_peak(1_000.0, 48_000, 6.0, None)) >> block_node(Clipper);
+        assert!(nonlinear.response(0, 1_000.0).is_none());
+        assert_eq!(nonlinear.filter_mono(2.0), 0.5);
+    }
+}
@@ -0,0 +1,169 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Gain reduction metering of the dynamics processors, for a UI or a
+//!              meter in another thread.
+//!              The meter has two sides, like the ring buffer of ring_buffer.rs. The
+//!              GainReductionMeter is given to a Compressor or a Limiter, in the
+//!              audio thread, and takes the gain change of each frame. At the end of
+//!              each block of block_size frames it publishes the current gain change
+//!              in an atomic, and the largest reduction of the block in a lock-free
+//!              ring buffer. The GainReductionReader is read by the other thread:
+//!              the current value at any time, and the history of the last blocks,
+//!              oldest first. The audio side never blocks or allocates, when the
+//!              reader is late and the ring buffer is full the block values are
+//!              dropped.
+//!
+//!              The values are in dB, negative for a reduction, positive for the
+//!              boost of the upward modes of the compressor. The value of a block is
+//!              the one farthest from 0 dB, so a short peak of reduction is seen on
+//!              the meter.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use crate::ring_buffer::RingProducer;
+use crate::ring_buffer::RingConsumer;
+use crate::ring_buffer::make_ring_buffer;
+
+
+/// The audio thread side, updated by the dynamics processor.
+pub struct GainReductionMeter {
+    block_size:  usize,
+    count:       usize,
+    // The gain change of the block farthest from 0 dB.
+    block_db:    f64,
+    current_db:  Arc<AtomicU64>,
+    producer:    RingProducer<f64>,
+}
+
+/// The reading side, in the metering thread.
+pub struct GainReductionReader {
+    current_db:  Arc<AtomicU64>,
+    consumer:    RingConsumer<f64>,
+    history:     VecDeque<f64>,
+    history_len: usize,
+}
+
+/// Creates a meter that publishes every block_size frames, and keeps the values of
+/// the last history_len blocks. Each side can be moved to a different thread.
+///
+/// In Rust:
+///    ```text
+///    >>> let (meter, mut reader) = make_gain_reduction_meter(512, 200);
+///    >>> compressor.set_meter(Some(meter));
+///    >>> // In the UI thread.
+///    >>> draw_meter(reader.get_current_db(), & reader.get_history());
+///    ```
+///
+pub fn make_gain_reduction_meter(block_size: usize, history_len: usize) -> (GainReductionMeter, GainReductionReader) {
+    assert!(block_size > 0 && history_len > 0);
+    let (producer, consumer) = make_ring_buffer(history_len);
+    let current_db = Arc::new(AtomicU64::new(0.0_f64.to_bits()));
+    (GainReductionMeter { block_size, count: 0, block_db: 0.0, current_db: current_db.clone(), producer },
+     GainReductionReader { current_db, consumer, history: VecDeque::with_capacity(history_len), history_len })
+}
+
+impl GainReductionMeter {
+    pub fn get_block_size(& self) -> usize {
+        self.block_size
+    }
+
+    /// Takes the gain change in dB of one frame.
+    pub fn update(& mut self, gain_db: f64) {
+        if gain_db.abs() > self.block_db.abs() {
+            self.block_db = gain_db;
+        }
+        self.count += 1;
+        if self.count == self.block_size {
+            self.current_db.store(gain_db.to_bits(), Ordering::Relaxed);
+            // When the reader is late the value is dropped.
+            let _ = self.producer.push(self.block_db);
+            self.count = 0;
+            self.block_db = 0.0;
+        }
+    }
+}
+
+impl GainReductionReader {
+    /// The gain change in dB at the end of the last block.
+    pub fn get_current_db(& self) -> f64 {
+        f64::from_bits(self.current_db.load(Ordering::Relaxed))
+    }
+
+    /// The values of the last blocks, oldest first.
+    pub fn get_history(& mut self) -> Vec<f64> {
+        while let Some(value) = self.consumer.pop() {
+            if self.history.len() == self.history_len {
+                self.history.pop_front();
+            }
+            self.history.push_back(value);
+        }
+        self.history.iter().copied().collect()
+    }
+
+    /// The largest reduction of the history, 0 dB or negative.
+    pub fn get_max_reduction_db(& mut self) -> f64 {
+        self.get_history().into_iter().fold(0.0, f64::min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamics::Compressor;
+    use crate::dynamics::Limiter;
+    use crate::iir_filter::ProcessingBlock; // Trait
+
+    #[test]
+    fn test_meter_blocks() {
+        let (mut meter, mut reader) = make_gain_reduction_meter(4, 3);
+        for gain_db in [0.0, -1.0, -3.0, -2.0, 0.0, 0.0, -0.5, 0.0, 2.0, -1.0, 0.0, -0.25, -6.0] {
+            meter.update(gain_db);
+        }
+        // The last value is in an unfinished block.
+        assert_eq!(reader.get_history(), vec![-3.0, -0.5, 2.0]);
+        assert_eq!(reader.get_current_db(), -0.25);
+        assert_eq!(reader.get_max_reduction_db(), -3.0);
+        for _ in 0..8 {
+            meter.update(-4.0);
+        }
+        assert_eq!(reader.get_history(), vec![2.0, -6.0, -4.0]);
+    }
+
+    #[test]
+    fn test_meter_in_another_thread() {
+        let (meter, mut reader) = make_gain_reduction_meter(480, 100);
+        let mut compressor = Compressor::new(48_000, -20.0, 4.0, 1.0, 50.0);
+        compressor.set_meter(Some(meter));
+        let worker = std::thread::spawn(move || {
+            for n in 0..48_000 {
+                compressor.process(if n < 24_000 { 0.9 } else { 0.0 });
+            }
+        });
+        worker.join().unwrap();
+        let history = reader.get_history();
+        assert_eq!(history.len(), 100);
+        // -0.9 dBFS is 19 dB above the threshold, 14 dB of reduction.
+        assert!((history[40] + 14.3).abs() < 0.1, "{}", history[40]);
+        assert!(reader.get_current_db() > -0.01);
+
+        let (meter, mut reader) = make_gain_reduction_meter(100, 10);
+        let mut limiter = Limiter::new(48_000, 1, -6.0, 1.0, 50.0);
+        limiter.set_meter(Some(meter));
+        for _ in 0..1_000 {
+            limiter.process(1.0);
+        }
+        assert!((reader.get_max_reduction_db() + 6.0).abs() < 1e-6);
+        assert!((reader.get_current_db() - limiter.get_gain_reduction_db()).abs() < 1e-9);
+    }
+}
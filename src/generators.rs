@@ -0,0 +1,511 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Measurement signals with a known spectrum, and the recovery of
+//!              the impulse response of a system from its recording. An alternative
+//!              to the sine sweeps.
+//!
+//!              The maximum length sequence (MLS) of order N is the output of a
+//!              linear feedback shift register with N bits, it repeats every
+//!              L = 2^N - 1 samples. Its circular autocorrelation is L at lag 0 and
+//!              -1 at all the other lags, so its spectrum is flat.
+//!
+//!              The periodic pink noise is built in the frequency domain, with the
+//!              magnitude 1 / sqrt(k) in the bin k (the power falls 3 dB per octave)
+//!              and random phases, so one period has exactly the pink spectrum.
+//!
+//!              The seeded white noise is uniform, from a linear congruential
+//!              generator, the same samples on all the machines, for the tests and
+//!              the measurements that must repeat, not for cryptography.
+//!
+//!              The impulse response is recovered with the circular cross-correlation
+//!              of the recording with the excitation, normalized by the power
+//!              spectrum of the excitation:
+//!
+//!                 H(k) = Y(k) X*(k) / |X(k)|^2 ,   h = IFFT(H)
+//!
+//!              With a MLS this is the classic cross-correlation method, without
+//!              the DC error of the -1 off-peak autocorrelation. The impulse
+//!              response must be shorter than one period, or it wraps around.
+//!
+//!              The calibrated test signals have an exact level in dBFS, for the
+//!              alignment of a signal chain and the verification of meters. The
+//!              level is the AES17 one, the RMS relative to the RMS of a full scale
+//!              sine, so a sine at -20 dBFS has a peak of -20 dB and a square wave
+//!              at -20 dBFS has a peak 3 dB lower. The square and sawtooth waves are
+//!              band-limited, the sum of their Fourier series up to Nyquist:
+//!
+//!                 square(t) = 4 / pi * sum_{k odd} sin(k w t) / k
+//!                 saw(t)    = 2 / pi * sum_{k}     (-1)^(k+1) sin(k w t) / k
+//!
+//!              so they don't alias, and their RMS is the exact sqrt(sum a_k^2 / 2)
+//!              of the harmonics. The pink noise is one period of the periodic pink
+//!              noise, scaled to its RMS. The signals are made from a spec text like
+//!              "-20 dBFS 1 kHz sine" or "-18 dBFS pink noise", or from the names of
+//!              the alignment signals:
+//!
+//!                 - ebu_tone: the -18 dBFS 1 kHz sine of EBU R68.
+//!                 - smpte_tone: the -20 dBFS 1 kHz sine of SMPTE RP155.
+//!                 - pink_noise: -18 dBFS pink noise, the level of the EBU tone.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Maximum length sequence - Wikipedia
+//!       https://en.wikipedia.org/wiki/Maximum_length_sequence
+//!
+//!    2. Rife, D. D., Vanderkooy, J. - Transfer-Function Measurement with
+//!       Maximum-Length Sequences. Journal of the Audio Engineering Society, 1989
+//!
+//!    3. AES17-2020 - AES standard method for digital audio engineering -
+//!       Measurement of digital audio equipment
+//!
+//!    4. EBU R 68 - Alignment level in digital audio production equipment
+//!       https://tech.ebu.ch/publications/r068
+//!
+
+
+use std::f64::consts::PI;
+use std::f64::consts::TAU;
+
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+
+
+/// Lowest and highest order of the MLS generator.
+pub const MLS_MIN_ORDER: u32 = 2;
+pub const MLS_MAX_ORDER: u32 = 20;
+
+// Taps of a maximal length feedback polynomial of each order, from 2 to 20.
+const MLS_TAPS: [& [u32]; 19] = [
+    & [2, 1],
+    & [3, 2],
+    & [4, 3],
+    & [5, 3],
+    & [6, 5],
+    & [7, 6],
+    & [8, 6, 5, 4],
+    & [9, 5],
+    & [10, 7],
+    & [11, 9],
+    & [12, 6, 4, 1],
+    & [13, 4, 3, 1],
+    & [14, 5, 3, 1],
+    & [15, 14],
+    & [16, 15, 13, 4],
+    & [17, 14],
+    & [18, 11],
+    & [19, 6, 2, 1],
+    & [20, 17],
+];
+
+/// Maximum length sequence of the order, 2^order - 1 samples of +1.0 and -1.0 .
+///
+/// In Rust:
+///    ```text
+///    >>> // 16383 samples, 0.34 s at 48 kHz.
+///    >>> let excitation = make_mls(14);
+///    ```
+///
+pub fn make_mls(order: u32) -> Vec<f64> {
+    assert!((MLS_MIN_ORDER..=MLS_MAX_ORDER).contains(& order));
+    let taps = MLS_TAPS[(order - MLS_MIN_ORDER) as usize];
+    let length = (1_usize << order) - 1;
+    // Fibonacci shift register, any state but 0 is in the sequence.
+    let mut state: u32 = 1;
+    (0..length).map(|_| {
+        let output = state & 1;
+        let feedback = taps.iter().fold(0, |bit, tap| bit ^ (state >> (order - tap))) & 1;
+        state = (state >> 1) | (feedback << (order - 1));
+        if output == 1 { -1.0 } else { 1.0 }
+    }).collect()
+}
+
+/// One period of pink noise, with a peak of 1.0 . The spectrum is exactly 1 / f in
+/// power from the bin 1 to Nyquist, with random phases from the seed, and no DC.
+pub fn make_periodic_pink_noise(length: usize, seed: u32) -> Vec<f64> {
+    assert!(length >= 2);
+    let mut random = WhiteNoise::new(seed);
+    let mut random_phase = || std::f64::consts::TAU * random.next_uniform();
+
+    let mut spectrum = vec![Complex::new(0.0, 0.0); length];
+    for k in 1..=length / 2 {
+        let magnitude = 1.0 / f64::sqrt(k as f64);
+        // The Nyquist bin of an even length is real.
+        let value = if 2 * k == length {
+            Complex::new(magnitude, 0.0)
+        } else {
+            Complex::from_polar(magnitude, random_phase())
+        };
+        spectrum[k] = value;
+        spectrum[length - k] = value.conj();
+    }
+    let mut planner = FftPlanner::<f64>::new();
+    planner.plan_fft_inverse(length).process(& mut spectrum);
+
+    let peak = spectrum.iter().fold(0.0, |max, c| f64::max(max, c.re.abs()));
+    spectrum.iter().map(|c| c.re / peak).collect()
+}
+
+/// Seeded uniform random numbers, a linear congruential generator of 32 bits.
+#[derive(Debug, Clone)]
+pub struct WhiteNoise {
+    state: u32,
+}
+
+impl WhiteNoise {
+    pub fn new(seed: u32) -> Self {
+        WhiteNoise { state: seed }
+    }
+
+    /// Uniform in [0, 1[, from the 24 high bits of the state.
+    pub fn next_uniform(& mut self) -> f64 {
+        self.state = self.state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (self.state >> 8) as f64 / (1 << 24) as f64
+    }
+}
+
+/// Uniform white noise in [-amplitude, amplitude[, from the seed.
+///
+/// In Rust:
+///    ```text
+///    >>> // 1 s at 48 kHz, -6 dBFS peak.
+///    >>> let noise = make_white_noise(48_000, 0.5, 1);
+///    ```
+///
+pub fn make_white_noise(length: usize, amplitude: f64, seed: u32) -> Vec<f64> {
+    let mut random = WhiteNoise::new(seed);
+    (0..length).map(|_| amplitude * (2.0 * random.next_uniform() - 1.0)).collect()
+}
+
+/// Names of the alignment signals of TestSignalSpec::from_spec().
+pub const TEST_SIGNAL_NAMES: [&str; 3] = ["ebu_tone", "smpte_tone", "pink_noise"];
+
+// Seed of the pink noise of the test signals.
+const TEST_SIGNAL_SEED: u32 = 1;
+
+/// The waveform of a test signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Waveform {
+    Sine,
+    Square,
+    Sawtooth,
+    PinkNoise,
+}
+
+/// A test signal with an exact level, in AES17 dBFS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestSignalSpec {
+    pub waveform:   Waveform,
+    /// Frequency of the tones in Hz, not used by the noise.
+    pub frequency:  f64,
+    pub level_dbfs: f64,
+}
+
+impl TestSignalSpec {
+    pub fn new(waveform: Waveform, frequency: f64, level_dbfs: f64) -> Self {
+        assert!(frequency > 0.0);
+        TestSignalSpec { waveform, frequency, level_dbfs }
+    }
+
+    /// Parses a spec like "-20 dBFS 1 kHz sine", "square 100 Hz -12 dBFS" or
+    /// "-18 dBFS pink noise", in any order, or a name of TEST_SIGNAL_NAMES.
+    /// The frequency is 1 kHz when it's missing, the level is needed.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let spec = TestSignalSpec::from_spec("-20 dBFS 1 kHz sine")?;
+    ///    >>> let tone = spec.generate(48_000, 48_000)?;
+    ///    ```
+    ///
+    pub fn from_spec(spec: & str) -> Result<Self, String> {
+        match spec.trim() {
+            "ebu_tone"   => return Ok(TestSignalSpec::new(Waveform::Sine, 1_000.0, -18.0)),
+            "smpte_tone" => return Ok(TestSignalSpec::new(Waveform::Sine, 1_000.0, -20.0)),
+            "pink_noise" => return Ok(TestSignalSpec::new(Waveform::PinkNoise, 1_000.0, -18.0)),
+            _ => (),
+        }
+        let error = |message: String| format!("Error: test signal \"{}\": {}", spec, message);
+        let mut waveform = None;
+        let mut frequency = None;
+        let mut level_dbfs = None;
+        let mut number: Option<f64> = None;
+        for token in spec.split_whitespace() {
+            // "-20dBFS" is the number and the unit.
+            let split = token.find(|c: char| c.is_ascii_alphabetic()).filter(|index| *index > 0);
+            let (value, word) = match split {
+                Some(index) => (Some(& token[..index]), & token[index..]),
+                None if token.parse::<f64>().is_ok() => (Some(token), ""),
+                None => (None, token),
+            };
+            if let Some(value) = value {
+                if number.is_some() {
+                    return Err(error(format!("a number without a unit before \"{}\"", token)));
+                }
+                number = Some(value.parse::<f64>().map_err(|_| error(format!("invalid number \"{}\"", value)))?);
+            }
+            let unit = match word.to_ascii_lowercase().as_str() {
+                "" => continue,
+                "dbfs" => { level_dbfs = Some(number.take().ok_or(error("dBFS without a level".to_string()))?); continue },
+                "hz"   => Some(1.0),
+                "khz"  => Some(1_000.0),
+                "sine" | "sin"       => { waveform = Some(Waveform::Sine); None },
+                "square"             => { waveform = Some(Waveform::Square); None },
+                "saw" | "sawtooth"   => { waveform = Some(Waveform::Sawtooth); None },
+                "pink"               => { waveform = Some(Waveform::PinkNoise); None },
+                "noise" | "wave" | "tone" | "at" => None,
+                _ => return Err(error(format!("unknown word \"{}\"", word))),
+            };
+            if let Some(scale) = unit {
+                frequency = Some(scale * number.take().ok_or(error("Hz without a frequency".to_string()))?);
+            }
+        }
+        if number.is_some() {
+            return Err(error("a number without a unit".to_string()));
+        }
+        let waveform = waveform.ok_or(error("missing the waveform, sine, square, saw or pink noise".to_string()))?;
+        let level_dbfs = level_dbfs.ok_or(error("missing the level in dBFS".to_string()))?;
+        let frequency = frequency.unwrap_or(1_000.0);
+        if frequency <= 0.0 {
+            return Err(error("the frequency must be positive".to_string()));
+        }
+        Ok(TestSignalSpec::new(waveform, frequency, level_dbfs))
+    }
+
+    /// The signal, with num_samples samples. The peak can't be above full scale.
+    pub fn generate(& self, sample_rate: u32, num_samples: usize) -> Result<Vec<f64>, String> {
+        let nyquist = sample_rate as f64 / 2.0;
+        if self.waveform != Waveform::PinkNoise && self.frequency >= nyquist {
+            return Err(format!("Error: the frequency {} Hz is above Nyquist, {} Hz", self.frequency, nyquist));
+        }
+        // RMS of a full scale sine.
+        let rms = f64::powf(10.0, self.level_dbfs / 20.0) / f64::sqrt(2.0);
+        let signal = match self.waveform {
+            Waveform::PinkNoise => {
+                let noise = make_periodic_pink_noise(num_samples.max(2), TEST_SIGNAL_SEED);
+                let noise_rms = f64::sqrt(noise.iter().map(|x| x * x).sum::<f64>() / noise.len() as f64);
+                noise.iter().take(num_samples).map(|x| x * rms / noise_rms).collect()
+            },
+            waveform => {
+                let harmonics = band_limited_harmonics(waveform, self.frequency, nyquist);
+                let harmonics_rms = f64::sqrt(harmonics.iter().map(|(_, a)| a * a / 2.0).sum::<f64>());
+                let omega = TAU * self.frequency / sample_rate as f64;
+                (0..num_samples)
+                    .map(|n| harmonics.iter().map(|(k, a)| a * f64::sin(*k as f64 * omega * n as f64)).sum::<f64>()
+                             * rms / harmonics_rms)
+                    .collect::<Vec<f64>>()
+            },
+        };
+        if signal.iter().any(|x| x.abs() > 1.0) {
+            return Err(format!("Error: the {:?} at {} dBFS clips, its peak is above full scale",
+                               self.waveform, self.level_dbfs));
+        }
+        Ok(signal)
+    }
+}
+
+/// Makes the test signal of the spec, see TestSignalSpec::from_spec().
+pub fn make_test_signal(spec: & str, sample_rate: u32, duration_seconds: f64) -> Result<Vec<f64>, String> {
+    let num_samples = (duration_seconds * sample_rate as f64).round() as usize;
+    TestSignalSpec::from_spec(spec)?.generate(sample_rate, num_samples)
+}
+
+// The harmonics below Nyquist of the Fourier series of the waveform, (k, a_k).
+fn band_limited_harmonics(waveform: Waveform, frequency: f64, nyquist: f64) -> Vec<(usize, f64)> {
+    let num_harmonics = ((nyquist / frequency).ceil() as usize).saturating_sub(1).max(1);
+    (1..=num_harmonics)
+        .filter_map(|k| match waveform {
+            Waveform::Square if k % 2 == 1 => Some((k, 4.0 / (PI * k as f64))),
+            Waveform::Square => None,
+            Waveform::Sawtooth => Some((k, (if k % 2 == 1 { 2.0 } else { -2.0 }) / (PI * k as f64))),
+            _ if k == 1 => Some((1, 1.0)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Recovers the impulse response of a system from the recording of a periodic
+/// excitation (a MLS or periodic noise), with one period of samples.
+/// The first period of the recording is discarded, because it holds the transient
+/// of the system, and the other complete periods are averaged to lower the noise.
+///
+/// In Rust:
+///    ```text
+///    >>> // Play at least 2 periods of the excitation through the system.
+///    >>> let excitation = make_mls(14);
+///    >>> let recording: Vec<f64> = excitation.iter().cycle().take(3 * excitation.len())
+///    >>>     .map(|x| system.process(*x)).collect();
+///    >>> let impulse_response = recover_impulse_response(& excitation, & recording)?;
+///    ```
+///
+pub fn recover_impulse_response(excitation: & [f64], recording: & [f64]) -> Result<Vec<f64>, String> {
+    let length = excitation.len();
+    if length < 2 {
+        return Err("Error: the excitation needs at least 2 samples".to_string());
+    }
+    let num_periods = recording.len() / length;
+    if num_periods < 2 {
+        return Err(format!("Error: the recording needs at least 2 periods of {} samples, it has {} samples",
+                           length, recording.len()));
+    }
+
+    // Average of the steady state periods.
+    let mut average = vec![Complex::new(0.0, 0.0); length];
+    for period in recording[length..num_periods * length].chunks_exact(length) {
+        for (a, y) in average.iter_mut().zip(period) {
+            a.re += y / (num_periods - 1) as f64;
+        }
+    }
+
+    let mut planner = FftPlanner::<f64>::new();
+    let fft = planner.plan_fft_forward(length);
+    let ifft = planner.plan_fft_inverse(length);
+    let mut excitation_spectrum: Vec<Complex<f64>> = excitation.iter().map(|x| Complex::new(*x, 0.0)).collect();
+    fft.process(& mut excitation_spectrum);
+    fft.process(& mut average);
+
+    let max_power = excitation_spectrum.iter().fold(0.0, |max, x| f64::max(max, x.norm_sqr()));
+    // Cross-spectrum divided by the power spectrum of the excitation.
+    let mut response = Vec::with_capacity(length);
+    for (k, (y, x)) in average.iter().zip(& excitation_spectrum).enumerate() {
+        let power = x.norm_sqr();
+        if power <= 1e-20 * max_power {
+            // The DC of the pink noise has no energy, nothing can be said about it.
+            if k == 0 {
+                response.push(Complex::new(0.0, 0.0));
+                continue;
+            }
+            return Err(format!("Error: the excitation has no energy in the frequency bin {}", k));
+        }
+        response.push(y * x.conj() / power);
+    }
+    ifft.process(& mut response);
+    Ok(response.iter().map(|c| c.re / length as f64).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iir_filter::ProcessingBlock; // Trait
+    use crate::butterworth_filter::make_lowpass;
+    use crate::butterworth_filter::make_peak;
+
+    fn measure(block: & mut dyn ProcessingBlock, excitation: & [f64], num_periods: usize) -> Vec<f64> {
+        excitation.iter().cycle().take(num_periods * excitation.len()).map(|x| block.process(*x)).collect()
+    }
+
+    #[test]
+    fn test_mls_is_maximal() {
+        for order in MLS_MIN_ORDER..=16 {
+            let mls = make_mls(order);
+            assert_eq!(mls.len(), (1 << order) - 1);
+            // One more -1 than +1 per period.
+            assert_eq!(mls.iter().sum::<f64>(), -1.0, "order {}", order);
+        }
+        // Circular autocorrelation of L at lag 0 and -1 elsewhere.
+        let mls = make_mls(8);
+        let length = mls.len();
+        for lag in 0..length {
+            let correlation: f64 = (0..length).map(|n| mls[n] * mls[(n + lag) % length]).sum();
+            assert_eq!(correlation, if lag == 0 { length as f64 } else { -1.0 });
+        }
+    }
+
+    #[test]
+    fn test_recover_impulse_response_with_mls() {
+        let sample_rate = 48_000;
+        let mut filter = make_lowpass(2_000.0, sample_rate, None);
+        let mut reference = make_lowpass(2_000.0, sample_rate, None);
+        let impulse_response: Vec<f64> = (0..200).map(|n| reference.process(if n == 0 { 1.0 } else { 0.0 })).collect();
+
+        let excitation = make_mls(12);
+        let recording = measure(& mut filter, & excitation, 3);
+        let measured = recover_impulse_response(& excitation, & recording).unwrap();
+        assert_eq!(measured.len(), excitation.len());
+        for (m, h) in measured.iter().zip(& impulse_response) {
+            assert!((m - h).abs() < 1e-9);
+        }
+        assert!(recover_impulse_response(& excitation, & recording[..5_000]).is_err());
+    }
+
+    fn rms_dbfs(signal: & [f64]) -> f64 {
+        20.0 * f64::log10(f64::sqrt(2.0 * signal.iter().map(|x| x * x).sum::<f64>() / signal.len() as f64))
+    }
+
+    #[test]
+    fn test_calibrated_test_signals() {
+        let spec = TestSignalSpec::from_spec("-20 dBFS 1 kHz sine").unwrap();
+        assert_eq!(spec, TestSignalSpec::new(Waveform::Sine, 1_000.0, -20.0));
+        assert_eq!(TestSignalSpec::from_spec("square 997Hz -12.5dBFS").unwrap(),
+                   TestSignalSpec::new(Waveform::Square, 997.0, -12.5));
+        assert_eq!(TestSignalSpec::from_spec("smpte_tone").unwrap(), spec);
+        for (text, level) in [("ebu_tone", -18.0), ("-20 dBFS 1 kHz sine", -20.0), ("-6 dBFS 100 Hz square", -6.0),
+                              ("saw 440 Hz -10 dBFS", -10.0), ("pink_noise", -18.0)] {
+            // Whole periods of the tones.
+            let signal = make_test_signal(text, 48_000, 1.0).unwrap();
+            assert_eq!(signal.len(), 48_000);
+            assert!((rms_dbfs(& signal) - level).abs() < 1e-6, "{}: {}", text, rms_dbfs(& signal));
+        }
+        let sine = make_test_signal("-20 dBFS 1 kHz sine", 48_000, 0.01).unwrap();
+        assert!((sine.iter().fold(0.0, |max: f64, x| max.max(x.abs())) - 0.1).abs() < 1e-9);
+
+        // A band-limited 5 kHz square has only the 5 and 15 kHz harmonics at 48 kHz.
+        let square = make_test_signal("-10 dBFS 5 kHz square", 48_000, 1.0).unwrap();
+        let mut spectrum: Vec<Complex<f64>> = square.iter().map(|x| Complex::new(*x, 0.0)).collect();
+        FftPlanner::<f64>::new().plan_fft_forward(48_000).process(& mut spectrum);
+        let power: f64 = spectrum[1..24_000].iter().map(|c| c.norm_sqr()).sum();
+        let harmonics = spectrum[5_000].norm_sqr() + spectrum[15_000].norm_sqr();
+        assert!((harmonics / power - 1.0).abs() < 1e-9);
+        assert!((spectrum[5_000].norm() / spectrum[15_000].norm() - 3.0).abs() < 1e-6);
+
+        assert!(make_test_signal("+1 dBFS sine", 48_000, 0.1).is_err());
+        assert!(make_test_signal("-20 dBFS 30 kHz sine", 48_000, 0.1).is_err());
+        assert!(TestSignalSpec::from_spec("1 kHz sine").is_err());
+        assert!(TestSignalSpec::from_spec("-20 dBFS triangle").is_err());
+        assert!(TestSignalSpec::from_spec("-20 1000 Hz sine").is_err());
+    }
+
+    #[test]
+    fn test_white_noise() {
+        let noise = make_white_noise(100_000, 0.5, 1);
+        assert_eq!(noise, make_white_noise(100_000, 0.5, 1));
+        assert_ne!(noise, make_white_noise(100_000, 0.5, 2));
+        assert!(noise.iter().all(|x| (-0.5..0.5).contains(x)));
+        // Uniform, of variance amplitude^2 / 3 and mean 0.
+        let mean = noise.iter().sum::<f64>() / noise.len() as f64;
+        let variance = noise.iter().map(|x| x * x).sum::<f64>() / noise.len() as f64;
+        assert!(mean.abs() < 0.005 && (variance - 0.25 / 3.0).abs() < 0.001);
+        let mut random = WhiteNoise::new(1);
+        assert_eq!(noise[0], 0.5 * (2.0 * random.next_uniform() - 1.0));
+    }
+
+    #[test]
+    fn test_periodic_pink_noise() {
+        let length = 4_096;
+        let noise = make_periodic_pink_noise(length, 42);
+        assert_eq!(noise.len(), length);
+        assert!((noise.iter().fold(0.0, |max: f64, x| max.max(x.abs())) - 1.0).abs() < 1e-12);
+        assert!(noise.iter().sum::<f64>().abs() < 1e-9);
+
+        // The power halves each octave.
+        let mut spectrum: Vec<Complex<f64>> = noise.iter().map(|x| Complex::new(*x, 0.0)).collect();
+        FftPlanner::<f64>::new().plan_fft_forward(length).process(& mut spectrum);
+        let ratio = spectrum[100].norm_sqr() / spectrum[200].norm_sqr();
+        assert!((ratio - 2.0).abs() < 1e-9);
+
+        // Recovery of a peak filter. The DC of the pink noise is unknown, so the DC gain
+        // of 1 of the filter is missing, spread over the period.
+        let sample_rate = 48_000;
+        let mut filter = make_peak(1_000.0, sample_rate, 6.0, Some(2.0));
+        let mut reference = make_peak(1_000.0, sample_rate, 6.0, Some(2.0));
+        let recording = measure(& mut filter, & noise, 4);
+        let measured = recover_impulse_response(& noise, & recording).unwrap();
+        for (n, m) in measured.iter().enumerate().take(500) {
+            let h = reference.process(if n == 0 { 1.0 } else { 0.0 });
+            assert!((m - h + 1.0 / length as f64).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Golden audio regression tests.
+//!              A standard test signal (an impulse, a sine sweep and noise) is
+//!              rendered through every filter and effect, and the output is compared
+//!              with the stored golden waveform in tests/golden/<name>.txt , with a
+//!              small tolerance. A refactoring (other filter structure, SIMD, a change
+//!              in the coefficient normalization) can't change the sound silently.
+//!
+//!              When a change of the output is intended, regenerate the golden files
+//!              and review their diff:
+//!
+//!                 UPDATE_GOLDEN=1 cargo test golden
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use std::f64::consts::TAU;
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::butterworth_filter::make_lowpass;
+use crate::butterworth_filter::make_highpass;
+use crate::butterworth_filter::make_bandpass;
+use crate::butterworth_filter::make_allpass;
+use crate::butterworth_filter::make_peak;
+use crate::butterworth_filter::make_peak_eq_constant_q;
+use crate::butterworth_filter::make_lowshelf;
+use crate::butterworth_filter::make_highshelf;
+use crate::butterworth_filter::make_notch;
+use crate::butterworth_filter::make_bandstop;
+use crate::butterworth_filter::make_bandpass_bw;
+use crate::butterworth_filter::make_butterworth_lowpass;
+use crate::butterworth_filter::make_butterworth_highpass;
+use crate::butterworth_filter::make_linkwitz_riley_lowpass;
+use crate::butterworth_filter::make_linkwitz_riley_highpass;
+use crate::butterworth_filter::make_bessel_lowpass;
+use crate::butterworth_filter::make_bessel_highpass;
+use crate::svf::StateVariableFilter;
+use crate::svf::SvfMode;
+use crate::biquad_tdf2::BiquadTDF2;
+use crate::biquad::Biquad;
+use crate::equalizer::Equalizer;
+use crate::delay_line::DelayLine;
+use crate::crossfeed::Crossfeed;
+use crate::convolution::DirectConvolver;
+use crate::convolution::PartitionedConvolver;
+use crate::equal_loudness::make_loudness_compensation;
+use crate::bass_enhancer::BassEnhancer;
+use crate::parametric_eq::ParametricEq;
+use crate::parametric_eq::ParametricBand;
+use crate::parametric_eq::BandType;
+use crate::dynamics::Compressor;
+use crate::dynamics::Limiter;
+use crate::dither::TpdfDither;
+use crate::effects::presets::make_preset;
+use crate::effects::presets::PRESET_NAMES;
+use crate::generators::make_white_noise;
+
+
+const SAMPLE_RATE: u32 = 48_000;
+const SIGNAL_LENGTH: usize = 2_048;
+// Only every DECIMATION sample is stored, it's enough to catch any change.
+const DECIMATION: usize = 8;
+// Relative to the peak of the golden waveform.
+const TOLERANCE: f64 = 1e-9;
+
+/// The standard test signal: an impulse, a logarithmic sine sweep from 20 Hz to
+/// 20 kHz and a little of white noise, from a fixed seed.
+fn make_test_signal() -> Vec<f64> {
+    let sweep_start = 64;
+    let sweep_length = (SIGNAL_LENGTH - sweep_start) as f64 / SAMPLE_RATE as f64;
+    let (f_start, f_end) = (20.0_f64, 20_000.0_f64);
+    let k = f64::ln(f_end / f_start);
+    make_white_noise(SIGNAL_LENGTH, 0.05, 0x1234_5678).iter().enumerate().map(|(n, noise)| {
+        let impulse = if n == 0 { 1.0 } else { 0.0 };
+        let sweep = if n >= sweep_start {
+            let t = (n - sweep_start) as f64 / SAMPLE_RATE as f64;
+            0.5 * f64::sin(TAU * f_start * sweep_length / k * (f64::exp(t / sweep_length * k) - 1.0))
+        } else {
+            0.0
+        };
+        impulse + sweep + noise
+    }).collect()
+}
+
+fn render(block: & mut dyn ProcessingBlock) -> Vec<f64> {
+    make_test_signal().iter().map(|x| block.process(*x)).collect()
+}
+
+fn golden_path(name: & str) -> String {
+    format!("{}/tests/golden/{}.txt", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+/// Compares the output with the golden file, or writes it when UPDATE_GOLDEN is set.
+fn check_golden(name: & str, output: & [f64]) {
+    let decimated: Vec<f64> = output.iter().step_by(DECIMATION).cloned().collect();
+    let path = golden_path(name);
+    if std::env::var("UPDATE_GOLDEN").is_ok() {
+        let mut text = format!("# {}, every {} sample of the standard test signal at {} Hz\n",
+                               name, DECIMATION, SAMPLE_RATE);
+        for value in & decimated {
+            text += & format!("{:.15e}\n", value);
+        }
+        std::fs::create_dir_all(format!("{}/tests/golden", env!("CARGO_MANIFEST_DIR"))).unwrap();
+        std::fs::write(& path, text).unwrap();
+        return;
+    }
+
+    let text = std::fs::read_to_string(& path)
+        .unwrap_or_else(|_| panic!("Missing golden file {}, generate it with UPDATE_GOLDEN=1 cargo test golden", path));
+    let golden: Vec<f64> = text.lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| line.parse::<f64>().unwrap())
+        .collect();
+    assert_eq!(golden.len(), decimated.len(), "{}: golden length", name);
+    let scale = golden.iter().fold(1e-12, |max, x| f64::max(max, x.abs()));
+    for (index, (value, expected)) in decimated.iter().zip(& golden).enumerate() {
+        assert!((value - expected).abs() <= TOLERANCE * scale,
+                "{}: sample {} is {}, the golden value is {}", name, index * DECIMATION, value, expected);
+    }
+}
+
+#[test]
+fn golden_biquads() {
+    let q_factor = Some(0.9);
+    let mut blocks: Vec<(& str, Box<dyn ProcessingBlock>)> = vec![
+        ("lowpass",              Box::new(make_lowpass(2_000.0, SAMPLE_RATE, None))),
+        ("highpass",             Box::new(make_highpass(2_000.0, SAMPLE_RATE, None))),
+        ("bandpass",             Box::new(make_bandpass(2_000.0, SAMPLE_RATE, q_factor))),
+        ("allpass",              Box::new(make_allpass(2_000.0, SAMPLE_RATE, q_factor))),
+        ("peak",                 Box::new(make_peak(2_000.0, SAMPLE_RATE, 6.0, q_factor))),
+        ("peak_eq_constant_q",   Box::new(make_peak_eq_constant_q(2_000.0, SAMPLE_RATE, -6.0, q_factor))),
+        ("lowshelf",             Box::new(make_lowshelf(500.0, SAMPLE_RATE, 6.0, q_factor))),
+        ("highshelf",            Box::new(make_highshelf(5_000.0, SAMPLE_RATE, -6.0, q_factor))),
+        ("notch",                Box::new(make_notch(2_000.0, SAMPLE_RATE, Some(0.5)))),
+        ("bandstop",             Box::new(make_bandstop(2_000.0, SAMPLE_RATE, 1.0 / 3.0))),
+        ("bandpass_bw",          Box::new(make_bandpass_bw(2_000.0, SAMPLE_RATE, 1.0))),
+    ];
+    for (name, block) in & mut blocks {
+        check_golden(name, & render(block.as_mut()));
+    }
+}
+
+#[test]
+fn golden_filter_structures() {
+    let mut blocks: Vec<(& str, Box<dyn ProcessingBlock>)> = Vec::new();
+    for (name, mode) in [("svf_lowpass", SvfMode::LowPass), ("svf_highpass", SvfMode::HighPass),
+                         ("svf_bandpass", SvfMode::BandPass), ("svf_notch", SvfMode::Notch)] {
+        blocks.push((name, Box::new(StateVariableFilter::new(mode, 2_000.0, SAMPLE_RATE, 0.9).unwrap())));
+    }
+    blocks.push(("biquad_tdf2_peak",
+                 Box::new(BiquadTDF2::from_filter(& make_peak(2_000.0, SAMPLE_RATE, 6.0, Some(0.9))).unwrap())));
+    let mut biquad = Biquad::<f64>::new();
+    biquad.set_lowshelf(500.0, SAMPLE_RATE, 6.0, Some(0.9));
+    blocks.push(("biquad_lowshelf", Box::new(biquad)));
+    for (name, block) in & mut blocks {
+        check_golden(name, & render(block.as_mut()));
+    }
+}
+
+#[test]
+fn golden_cascades() {
+    let chains = [
+        ("butterworth_lowpass_5",    make_butterworth_lowpass(1_000.0, SAMPLE_RATE, 5)),
+        ("butterworth_highpass_4",   make_butterworth_highpass(1_000.0, SAMPLE_RATE, 4)),
+        ("linkwitz_riley_lowpass_4", make_linkwitz_riley_lowpass(2_000.0, SAMPLE_RATE, 4)),
+        ("linkwitz_riley_highpass_4", make_linkwitz_riley_highpass(2_000.0, SAMPLE_RATE, 4)),
+        ("bessel_lowpass_4",         make_bessel_lowpass(2_000.0, SAMPLE_RATE, 4)),
+        ("bessel_highpass_3",        make_bessel_highpass(500.0, SAMPLE_RATE, 3)),
+    ];
+    for (name, chain) in chains {
+        check_golden(name, & render(& mut chain.unwrap()));
+    }
+}
+
+#[test]
+fn golden_dynamics() {
+    let mut compressor = Compressor::new(SAMPLE_RATE, -20.0, 4.0, 1.0, 50.0);
+    compressor.set_knee_db(6.0);
+    check_golden("compressor", & render(& mut compressor));
+
+    let mut limiter = Limiter::new(SAMPLE_RATE, 1, -6.0, 2.0, 50.0);
+    check_golden("limiter", & render(& mut limiter));
+
+    // The dither of a fixed seed, at 8 bits to see it.
+    let mut dither = TpdfDither::new(8, 1);
+    check_golden("dither_tpdf_8_bits", & render(& mut dither));
+}
+
+#[test]
+fn golden_presets() {
+    for name in PRESET_NAMES {
+        let mut preset = make_preset(name, SAMPLE_RATE).unwrap();
+        check_golden(& format!("preset_{}", name), & render(& mut preset));
+    }
+}
+
+#[test]
+fn golden_equalizers() {
+    let mut eq = Equalizer::make_equalizer_10_band(SAMPLE_RATE);
+    for (index, gain_db) in [-10.0, 0.0, -5.0, 5.0, 0.0, -5.0, 0.0, 5.0, 10.0, 12.0].iter().enumerate() {
+        eq.set_band_gain(index, *gain_db).unwrap();
+    }
+    check_golden("equalizer_10_band", & render(& mut eq));
+
+    let bands = [ParametricBand::new(BandType::LowShelf, 105.0, 6.0, 0.7),
+                 ParametricBand::new(BandType::Peak, 2_404.0, -3.1, 1.41),
+                 ParametricBand::new(BandType::HighPass, 20.0, 0.0, 0.707)];
+    let mut parametric_eq = ParametricEq::from_bands(SAMPLE_RATE, -6.2, & bands).unwrap();
+    check_golden("parametric_eq", & render(& mut parametric_eq));
+
+    let mut loudness = make_loudness_compensation(SAMPLE_RATE, 80.0, 50.0).unwrap();
+    check_golden("loudness_compensation", & render(& mut loudness));
+}
+
+#[test]
+fn golden_effects() {
+    let mut delay_line = DelayLine::new(100);
+    check_golden("delay_line", & render(& mut delay_line));
+
+    let mut bass_enhancer = BassEnhancer::make_small_speaker(SAMPLE_RATE);
+    check_golden("bass_enhancer", & render(& mut bass_enhancer));
+
+    // Left input only, the right output is the crossfeed path.
+    let mut crossfeed = Crossfeed::make_bauer(SAMPLE_RATE);
+    let (left, right): (Vec<f64>, Vec<f64>) = make_test_signal().iter()
+        .map(|x| crossfeed.process_stereo(*x, 0.0))
+        .unzip();
+    check_golden("crossfeed_bauer_left", & left);
+    check_golden("crossfeed_bauer_right", & right);
+}
+
+#[test]
+fn golden_convolution() {
+    let impulse_response: Vec<f64> = (0..300)
+        .map(|n| f64::exp(-(n as f64) / 40.0) * f64::cos(n as f64 * 0.3))
+        .collect();
+    let mut direct = DirectConvolver::new(& impulse_response);
+    let direct_output = render(& mut direct);
+    check_golden("convolution", & direct_output);
+
+    // The partitioned convolver must match the direct one, after its latency of one
+    // block in process().
+    let block_size = 64;
+    let mut partitioned = PartitionedConvolver::new(& impulse_response, block_size);
+    let partitioned_output = render(& mut partitioned);
+    for (value, expected) in partitioned_output[block_size..].iter().zip(& direct_output) {
+        assert!((value - expected).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,450 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: HRTF binaural rendering.
+//!              A Head Related Impulse Response (HRIR) pair is the impulse response
+//!              from a point in space to each of the two ear drums. Convolving a mono
+//!              source with the HRIR pair of a direction makes the source appear to
+//!              come from that direction when listening with headphones.
+//!
+//!              HrirSet holds the measured HRIR pairs of a subject and selects the
+//!              nearest measurement to a direction. The pairs are given already
+//!              decoded, or loaded from stereo WAV files, one per direction, with the
+//!              left ear in the first channel, with from_wav_files(). The SOFA files
+//!              (AES69) are netCDF-4 / HDF5 containers, and reading them needs the
+//!              HDF5 C library; their loader is deferred, a SOFA set can be exported
+//!              to WAV files per direction with the SOFA tools of Matlab or Python.
+//!              BinauralRenderer convolves a mono source with the selected pair,
+//!              using the partitioned convolver. When the source moves, the outputs
+//!              of the old and the new pairs are crossfaded over one block, so the
+//!              change of the impulse response doesn't click. A move before the
+//!              new pair is heard changes the pair of the crossfade, a move in the
+//!              middle of the crossfade waits for its end.
+//!
+//!              Azimuth is in degrees, counter clockwise from the front (90 is left),
+//!              and elevation in degrees, up from the horizontal plane, as in SOFA.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Head-related transfer function - Wikipedia
+//!       https://en.wikipedia.org/wiki/Head-related_transfer_function
+//!
+//!    2. AES69 - Spatially Oriented Format for Acoustics (SOFA)
+//!       https://www.sofaconventions.org/
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::convolution::PartitionedConvolver;
+use crate::audio_io::read_wav;
+
+
+/// One measured HRIR pair.
+#[derive(Debug, Clone)]
+pub struct HrirMeasurement {
+    pub azimuth_deg:   f64,
+    pub elevation_deg: f64,
+    pub left:          Vec<f64>,
+    pub right:         Vec<f64>,
+}
+
+/// A set of HRIR measurements at one sample rate.
+pub struct HrirSet {
+    sample_rate:  u32,
+    measurements: Vec<HrirMeasurement>,
+}
+
+impl HrirSet {
+    pub fn new(sample_rate: u32, measurements: Vec<HrirMeasurement>) -> Result<Self, String> {
+        if measurements.is_empty() {
+            return Err("Error: the HRIR set has no measurements".to_string());
+        }
+        for m in & measurements {
+            if m.left.is_empty() || m.right.is_empty() {
+                return Err(format!("Error: empty HRIR at azimuth {} and elevation {}",
+                                   m.azimuth_deg, m.elevation_deg));
+            }
+        }
+        Ok(HrirSet { sample_rate, measurements })
+    }
+
+    /// Loads the set from stereo WAV files, one for each (azimuth, elevation, path),
+    /// the left ear in the first channel and the right ear in the second. All the
+    /// files must have the same sample rate.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let hrir_set = HrirSet::from_wav_files(& [(0.0, 0.0, "hrir/azi_0_ele_0.wav"),
+    ///    >>>                                           (90.0, 0.0, "hrir/azi_90_ele_0.wav")])?;
+    ///    ```
+    ///
+    pub fn from_wav_files(files: & [(f64, f64, & str)]) -> Result<Self, String> {
+        let mut sample_rate = None;
+        let mut measurements = Vec::with_capacity(files.len());
+        for (azimuth_deg, elevation_deg, path) in files {
+            let (audio, _) = read_wav(path)?;
+            if audio.get_num_channels() != 2 {
+                return Err(format!("Error: the HRIR {} has {} channels, it must have 2, left and right",
+                                   path, audio.get_num_channels()));
+            }
+            if *sample_rate.get_or_insert(audio.sample_rate) != audio.sample_rate {
+                return Err(format!("Error: the HRIR {} is at {} Hz, the others at {} Hz",
+                                   path, audio.sample_rate, sample_rate.unwrap()));
+            }
+            let mut channels = audio.channels.into_iter();
+            measurements.push(HrirMeasurement {
+                azimuth_deg:   *azimuth_deg,
+                elevation_deg: *elevation_deg,
+                left:          channels.next().unwrap(),
+                right:         channels.next().unwrap(),
+            });
+        }
+        HrirSet::new(sample_rate.unwrap_or(0), measurements)
+    }
+
+    pub fn get_sample_rate(& self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn get_measurements(& self) -> & [HrirMeasurement] {
+        & self.measurements
+    }
+
+    /// The measurement with the smallest angle (great circle distance) to the direction.
+    pub fn nearest(& self, azimuth_deg: f64, elevation_deg: f64) -> & HrirMeasurement {
+        let target = direction_vector(azimuth_deg, elevation_deg);
+        let mut best = & self.measurements[0];
+        let mut best_dot = f64::MIN;
+        for m in & self.measurements {
+            let v = direction_vector(m.azimuth_deg, m.elevation_deg);
+            // The largest dot product is the smallest angle.
+            let dot = v[0] * target[0] + v[1] * target[1] + v[2] * target[2];
+            if dot > best_dot {
+                best_dot = dot;
+                best = m;
+            }
+        }
+        best
+    }
+}
+
+// Unit vector of a direction, x to the front, y to the left and z up.
+fn direction_vector(azimuth_deg: f64, elevation_deg: f64) -> [f64; 3] {
+    let azimuth = azimuth_deg.to_radians();
+    let elevation = elevation_deg.to_radians();
+    [elevation.cos() * azimuth.cos(), elevation.cos() * azimuth.sin(), elevation.sin()]
+}
+
+/// Places a mono source at a direction, producing a binaural (left, right) signal.
+pub struct BinauralRenderer {
+    azimuth_deg:    f64,
+    elevation_deg:  f64,
+    convolver_left:  PartitionedConvolver,
+    convolver_right: PartitionedConvolver,
+    // The convolvers of the previous position, faded out over one block after a
+    // move, and reused for the next move.
+    previous_left:  PartitionedConvolver,
+    previous_right: PartitionedConvolver,
+    fading: bool,
+    fade_position: usize,
+    // The samples of process() before the output of the new position, its latency.
+    fade_delay: usize,
+    fade_left:  Vec<f64>,
+    fade_right: Vec<f64>,
+    // The HRIR pair of a move in the middle of a crossfade, started at its end.
+    pending: bool,
+    pending_left:  Vec<f64>,
+    pending_right: Vec<f64>,
+}
+
+impl BinauralRenderer {
+    pub fn new(hrir_set: & HrirSet, azimuth_deg: f64, elevation_deg: f64, block_size: usize) -> Self {
+        let m = hrir_set.nearest(azimuth_deg, elevation_deg);
+        BinauralRenderer {
+            azimuth_deg,
+            elevation_deg,
+            convolver_left:  PartitionedConvolver::new(& m.left, block_size),
+            convolver_right: PartitionedConvolver::new(& m.right, block_size),
+            previous_left:   PartitionedConvolver::new(& m.left, block_size),
+            previous_right:  PartitionedConvolver::new(& m.right, block_size),
+            fading: false,
+            fade_position: 0,
+            fade_delay: 0,
+            fade_left:  vec![0.0; block_size],
+            fade_right: vec![0.0; block_size],
+            pending: false,
+            pending_left:  Vec::new(),
+            pending_right: Vec::new(),
+        }
+    }
+
+    pub fn get_position(& self) -> (f64, f64) {
+        (self.azimuth_deg, self.elevation_deg)
+    }
+
+    /// Moves the source. The input history is kept, and the output of the old
+    /// position is crossfaded to the one of the new position over the next block
+    /// size samples, so the sound continues without a gap or a click. A move
+    /// before the output of the new position is heard, like two moves before the
+    /// next block, crossfades to the last one. A move in the middle of a
+    /// crossfade starts its own crossfade at the end of it.
+    pub fn set_position(& mut self, hrir_set: & HrirSet, azimuth_deg: f64, elevation_deg: f64) {
+        let m = hrir_set.nearest(azimuth_deg, elevation_deg);
+        if !self.fading {
+            self.start_fade(& m.left, & m.right);
+        } else if self.fade_position < self.fade_delay {
+            // No block of the new position yet, the previous one still fades out.
+            self.convolver_left.set_impulse_response(& m.left);
+            self.convolver_right.set_impulse_response(& m.right);
+        } else {
+            self.pending_left.clear();
+            self.pending_left.extend_from_slice(& m.left);
+            self.pending_right.clear();
+            self.pending_right.extend_from_slice(& m.right);
+            self.pending = true;
+        }
+        self.azimuth_deg = azimuth_deg;
+        self.elevation_deg = elevation_deg;
+    }
+
+    // The spare convolvers take the input history and the new pair, and become
+    // the current ones, the current ones fade out.
+    fn start_fade(& mut self, left: & [f64], right: & [f64]) {
+        self.previous_left.copy_input_history(& self.convolver_left);
+        self.previous_right.copy_input_history(& self.convolver_right);
+        self.previous_left.set_impulse_response(left);
+        self.previous_right.set_impulse_response(right);
+        std::mem::swap(& mut self.convolver_left, & mut self.previous_left);
+        std::mem::swap(& mut self.convolver_right, & mut self.previous_right);
+        self.fading = true;
+        self.fade_position = 0;
+        self.fade_delay = self.convolver_left.samples_to_next_block();
+    }
+
+    // At the end of a crossfade, the one of the move that waited for it.
+    fn end_fade(& mut self) {
+        self.fading = false;
+        self.fade_position = 0;
+        if self.pending {
+            self.pending = false;
+            let (left, right) = (std::mem::take(& mut self.pending_left), std::mem::take(& mut self.pending_right));
+            self.start_fade(& left, & right);
+            (self.pending_left, self.pending_right) = (left, right);
+        }
+    }
+
+    // The gain of the new position at the sample of the crossfade, a linear ramp,
+    // the outputs of the two positions are correlated.
+    fn fade_in_gain(& self, position: usize) -> f64 {
+        f64::min(1.0, (position + 1) as f64 / self.convolver_left.get_block_size() as f64)
+    }
+
+    /// Latency in samples of process(), process_block() has no latency.
+    pub fn latency_samples(& self) -> usize {
+        self.convolver_left.latency_samples()
+    }
+
+    /// Renders one block of the block size.
+    pub fn process_block(& mut self, input: & [f64], output_left: & mut [f64], output_right: & mut [f64]) {
+        let _span = trace_span!("binaural_renderer_block", azimuth_deg = self.azimuth_deg, elevation_deg = self.elevation_deg);
+        self.convolver_left.process_block(input, output_left);
+        self.convolver_right.process_block(input, output_right);
+        if self.fading {
+            self.previous_left.process_block(input, & mut self.fade_left);
+            self.previous_right.process_block(input, & mut self.fade_right);
+            for n in 0..input.len() {
+                let gain = self.fade_in_gain(self.fade_position + n);
+                output_left[n] = gain * output_left[n] + (1.0 - gain) * self.fade_left[n];
+                output_right[n] = gain * output_right[n] + (1.0 - gain) * self.fade_right[n];
+            }
+            self.end_fade();
+        }
+    }
+
+    /// Renders one sample, returns the (left, right) output. After a move, the
+    /// crossfade starts with the first output of the new position, after the
+    /// latency.
+    pub fn process(& mut self, sample: f64) -> (f64, f64) {
+        let left = self.convolver_left.process(sample);
+        let right = self.convolver_right.process(sample);
+        if !self.fading {
+            return (left, right);
+        }
+        let (old_left, old_right) = (self.previous_left.process(sample), self.previous_right.process(sample));
+        let gain = match self.fade_position.checked_sub(self.fade_delay) {
+            Some(position) => self.fade_in_gain(position),
+            None           => 0.0,
+        };
+        self.fade_position += 1;
+        if self.fade_position == self.fade_delay + self.convolver_left.get_block_size() {
+            self.end_fade();
+        }
+        (gain * left + (1.0 - gain) * old_left, gain * right + (1.0 - gain) * old_right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::temp_test_path;
+
+    // A toy HRIR set with a pure delay and gain per ear.
+    fn make_toy_hrir_set() -> HrirSet {
+        let make = |azimuth_deg: f64, left_delay: usize, right_delay: usize, left_gain: f64, right_gain: f64| {
+            let mut left = vec![0.0; 32];
+            let mut right = vec![0.0; 32];
+            left[left_delay] = left_gain;
+            right[right_delay] = right_gain;
+            HrirMeasurement { azimuth_deg, elevation_deg: 0.0, left, right }
+        };
+        let measurements = vec![
+            make(0.0,   0,  0,  1.0, 1.0),
+            make(90.0,  0,  20, 1.0, 0.5),   // Source at the left.
+            make(270.0, 20, 0,  0.5, 1.0),   // Source at the right.
+        ];
+        HrirSet::new(48_000, measurements).unwrap()
+    }
+
+    #[test]
+    fn test_hrir_set_nearest() {
+        let hrir_set = make_toy_hrir_set();
+        assert_eq!(hrir_set.nearest(80.0, 10.0).azimuth_deg, 90.0);
+        assert_eq!(hrir_set.nearest(-80.0, 0.0).azimuth_deg, 270.0);
+        assert_eq!(hrir_set.nearest(350.0, 0.0).azimuth_deg, 0.0);
+        assert!(HrirSet::new(48_000, vec![]).is_err());
+    }
+
+    #[test]
+    fn test_binaural_renderer_places_source() {
+        let hrir_set = make_toy_hrir_set();
+        let block_size = 16;
+        let mut renderer = BinauralRenderer::new(& hrir_set, 90.0, 0.0, block_size);
+        let mut input = vec![0.0; 64];
+        input[0] = 1.0;
+        let mut left = vec![0.0; 64];
+        let mut right = vec![0.0; 64];
+        for ((x, l), r) in input.chunks(block_size).zip(left.chunks_mut(block_size)).zip(right.chunks_mut(block_size)) {
+            renderer.process_block(x, l, r);
+        }
+        assert!((left[0] - 1.0).abs() < 1e-12);
+        assert!((right[20] - 0.5).abs() < 1e-12);
+        assert!(right[0].abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_binaural_renderer_crossfades_moves() {
+        let hrir_set = make_toy_hrir_set();
+        let block_size = 16;
+        // A constant input, the output of each position is constant after 20 samples.
+        let input = vec![1.0; block_size];
+        let (mut left, mut right) = (vec![0.0; block_size], vec![0.0; block_size]);
+        let mut renderer = BinauralRenderer::new(& hrir_set, 90.0, 0.0, block_size);
+        for _ in 0..4 {
+            renderer.process_block(& input, & mut left, & mut right);
+        }
+        assert!((right[0] - 0.5).abs() < 1e-12);
+
+        // From the left to the right, the right ear ramps from 0.5 to 1.0 over the block.
+        renderer.set_position(& hrir_set, 270.0, 0.0);
+        renderer.process_block(& input, & mut left, & mut right);
+        for n in 0..block_size {
+            let gain = (n + 1) as f64 / block_size as f64;
+            assert!((right[n] - (0.5 + 0.5 * gain)).abs() < 1e-12);
+            assert!((left[n] - (1.0 - 0.5 * gain)).abs() < 1e-12);
+        }
+        renderer.process_block(& input, & mut left, & mut right);
+        assert!(right.iter().all(|y| (y - 1.0).abs() < 1e-12));
+
+        // Sample by sample, the same ramp, after the latency of the rest of the block.
+        let mut renderer = BinauralRenderer::new(& hrir_set, 90.0, 0.0, block_size);
+        for _ in 0..4 * block_size + 5 {
+            renderer.process(1.0);
+        }
+        renderer.set_position(& hrir_set, 270.0, 0.0);
+        let ramp: Vec<f64> = (0..3 * block_size).map(|_| renderer.process(1.0).1).collect();
+        let delay = block_size - 5;
+        for (n, y) in ramp.iter().enumerate() {
+            let gain = if n < delay { 0.0 } else { f64::min(1.0, (n - delay + 1) as f64 / block_size as f64) };
+            assert!((y - (0.5 + 0.5 * gain)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_hrir_set_from_wav_files() {
+        use crate::audio_buffer::AudioBuffer;
+        use crate::audio_io::{write_wav, SampleFormat};
+
+        let paths: Vec<String> = (0..3).map(|i| temp_test_path(& format!("hrir_{}.wav", i))).collect();
+        let hrir = |gain: f64| vec![gain, 0.5 * gain, 0.0, 0.0];
+        write_wav(& paths[0], & AudioBuffer::new(48_000, vec![hrir(1.0), hrir(0.5)]), SampleFormat::Float32).unwrap();
+        write_wav(& paths[1], & AudioBuffer::new(48_000, vec![hrir(0.5), hrir(1.0)]), SampleFormat::Float32).unwrap();
+        write_wav(& paths[2], & AudioBuffer::new(44_100, vec![hrir(1.0), hrir(1.0)]), SampleFormat::Float32).unwrap();
+
+        let hrir_set = HrirSet::from_wav_files(& [(90.0, 0.0, & paths[0]), (270.0, 0.0, & paths[1])]).unwrap();
+        assert_eq!(hrir_set.get_sample_rate(), 48_000);
+        let left_source = hrir_set.nearest(80.0, 0.0);
+        assert_eq!((& left_source.left, & left_source.right), (& hrir(1.0), & hrir(0.5)));
+        assert!(HrirSet::from_wav_files(& [(90.0, 0.0, & paths[0]), (0.0, 0.0, & paths[2])]).is_err());
+        write_wav(& paths[2], & AudioBuffer::new(48_000, vec![hrir(1.0)]), SampleFormat::Float32).unwrap();
+        assert!(HrirSet::from_wav_files(& [(0.0, 0.0, & paths[2])]).is_err());
+        assert!(HrirSet::from_wav_files(& []).is_err());
+        for path in & paths {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    // The largest step between two samples of the output.
+    fn max_step(output: & [f64]) -> f64 {
+        output.windows(2).map(|pair| (pair[1] - pair[0]).abs()).fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_binaural_renderer_two_moves_in_one_block() {
+        // A constant input, the positions at 90, 270 and 0 degrees give the left
+        // ear 1.0, 0.5 and 1.0, and the right ear 0.5, 1.0 and 1.0 . A ramp between
+        // two of them over a block has steps of at most 0.5 / block_size.
+        let hrir_set = make_toy_hrir_set();
+        let block_size = 16;
+        let largest_step = 0.5 / block_size as f64 + 1e-12;
+        let input = vec![1.0; block_size];
+        let (mut left, mut right) = (vec![0.0; 6 * block_size], vec![0.0; 6 * block_size]);
+
+        // Two moves before the next block, one crossfade to the last position.
+        let mut renderer = BinauralRenderer::new(& hrir_set, 90.0, 0.0, block_size);
+        for (n, (l, r)) in left.chunks_mut(block_size).zip(right.chunks_mut(block_size)).enumerate() {
+            if n == 3 {
+                renderer.set_position(& hrir_set, 270.0, 0.0);
+                renderer.set_position(& hrir_set, 0.0, 0.0);
+            }
+            renderer.process_block(& input, l, r);
+        }
+        assert!(max_step(& left[2 * block_size..]) <= largest_step);
+        assert!(max_step(& right[2 * block_size..]) <= largest_step);
+        assert!((right[4 * block_size - 1] - 1.0).abs() < 1e-12);
+        assert_eq!(renderer.get_position(), (0.0, 0.0));
+
+        // Sample by sample, a move before the new position is heard, and a move in
+        // the middle of the crossfade, that waits for its end.
+        for second_move in [3, block_size - 5 + 4] {
+            let mut renderer = BinauralRenderer::new(& hrir_set, 90.0, 0.0, block_size);
+            for _ in 0..4 * block_size + 5 {
+                renderer.process(1.0);
+            }
+            renderer.set_position(& hrir_set, 270.0, 0.0);
+            let mut output = Vec::new();
+            for n in 0..4 * block_size {
+                if n == second_move {
+                    renderer.set_position(& hrir_set, 0.0, 0.0);
+                }
+                output.push(renderer.process(1.0));
+            }
+            let (left, right): (Vec<f64>, Vec<f64>) = output.into_iter().unzip();
+            assert!(max_step(& left) <= largest_step && max_step(& right) <= largest_step);
+            assert!((left[left.len() - 1] - 1.0).abs() < 1e-12 && (right[right.len() - 1] - 1.0).abs() < 1e-12);
+        }
+    }
+}
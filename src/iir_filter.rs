@@ -1,108 +1,231 @@
-/// Project: Audio filters in Rust
-/// Date:    2021.12.05
-/// Author of the port: João Nuno Carvalho
-///
-/// Description: Audio or DSP filters, allow you to attenuate or accentuate some frequencies
-///              or range of frequencies in a signal. The signal can be of any kind, but in
-///              here, we will focus on 1D signals. Like audio signals.
-///              There can also occur differences in the signal phases, that vary with the
-///              filter and the frequency components of the signal.  
-///              This is a port of Audio filters, from Python to Rust,
-///              from the Audio filter from TheAlgorithms GitHub in Python. That is by it
-///              self a port from WebAudio API implementation of the same common
-///              filters in the browsers.
-/// 
-///              The following filters are implemented over a BiQuad IIR filter:
-///                 -low-pass
-///                 -high-pass
-///                 -band-pass
-///                 -all-pass
-///                 -peak
-///                 -low-shelf
-///                 -high-shelf 
-///                 -notch
-///                 -10 band equalizer
-///  
-/// License: MIT Open Source License, like the original license from
-///    GitHub - TheAlgorithms / Python / audio_filters
-///    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
-///
-/// How to run the code. 
-/// 
-/// To make a project for this files do:
-///     -Install Rust your computer (Linux, Win, Mac, Raspberry Pi).
-///     
-///     cargo new audio_filters_in_rust
-///     cd audio_filters_in_rust
-///     
-///     -Copy the repository files to this directory and overlap them.
-/// 
-/// To compile do:
-///     cargo build --release
-/// 
-/// To run do:
-///     cargo run --release
-/// 
-/// to run the tests do:
-///     cargo test
-/// 
-/// References:
-///    1. GitHub - TheAlgorithms / Python / audio_filters
-///       https://github.com/TheAlgorithms/Python/tree/master/audio_filters
-///
-///    2. WebAudio - Cookbook formulae for audio equalizer biquad filter coefficients
-///       https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html 
-/// 
-///    3. Good resources on DSP – Digital Signal Programming
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_electronics#dsp--digital-signal-programming
-///
-///    4. Biquads - EarLevel
-///       http://www.earlevel.com/main/2003/02/28/biquads/
-///
-///    5. Biquad C++ source code - EarLevel
-///       https://www.earlevel.com/main/2012/11/26/biquad-c-source-code/
-///
-///    6. A biquad calculator V3 - EarLevel
-///       https://www.earlevel.com/main/2021/09/02/biquad-calculator-v3/
-/// 
-///    7. WebAudio API - Mozilla Docs
-///       https://developer.mozilla.org/en-US/docs/Web/API/Web_Audio_API
-/// 
-///    8. Audio Filters - Theory and Practice
-///       by Ethan Winer
-///       http://ethanwiner.com/filters.html
-/// 
-///    9. Audio filter - Wikipedia
-///       https://en.wikipedia.org/wiki/Audio_filter
-/// 
-///   10. Electronic filter - Wikipedia
-///       https://en.wikipedia.org/wiki/Electronic_filter
-///
-///   11. How to learn modern Rust
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
-///
-/// 
-/// 10 Band Equalizer
-/// 
-///   12. Making an EQ from cascading filters
-///       https://dsp.stackexchange.com/questions/10309/making-an-eq-from-cascading-filters
-/// 
-///   13. PEAK/NOTCH FILTER DESIGN
-///       https://www.dsprelated.com/showcode/169.php
-/// 
-///   14. The Equivalence of Various Methods of Computing
-///       Biquad Coefficients for Audio Parametric Equalizers
-///       http://www.thesounddesign.com/MIO/EQ-Coefficients.pdf
+//! Project: Audio filters in Rust
+//! Date:    2021.12.05
+//! Author of the port: João Nuno Carvalho
+//!
+//! Description: Audio or DSP filters, allow you to attenuate or accentuate some frequencies
+//!              or range of frequencies in a signal. The signal can be of any kind, but in
+//!              here, we will focus on 1D signals. Like audio signals.
+//!              There can also occur differences in the signal phases, that vary with the
+//!              filter and the frequency components of the signal.  
+//!              This is a port of Audio filters, from Python to Rust,
+//!              from the Audio filter from TheAlgorithms GitHub in Python. That is by it
+//!              self a port from WebAudio API implementation of the same common
+//!              filters in the browsers.
+//! 
+//!              The following filters are implemented over a BiQuad IIR filter:
+//!                 -low-pass
+//!                 -high-pass
+//!                 -band-pass
+//!                 -all-pass
+//!                 -peak
+//!                 -low-shelf
+//!                 -high-shelf 
+//!                 -notch
+//!                 -10 band equalizer
+//!  
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! How to run the code. 
+//! 
+//! To make a project for this files do:
+//!     -Install Rust your computer (Linux, Win, Mac, Raspberry Pi).
+//!     
+//!     cargo new audio_filters_in_rust
+//!     cd audio_filters_in_rust
+//!     
+//!     -Copy the repository files to this directory and overlap them.
+//! 
+//! To compile do:
+//!     cargo build --release
+//! 
+//! To run do:
+//!     cargo run --release
+//! 
+//! to run the tests do:
+//!     cargo test
+//! 
+//! References:
+//!    1. GitHub - TheAlgorithms / Python / audio_filters
+//!       https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//!    2. WebAudio - Cookbook formulae for audio equalizer biquad filter coefficients
+//!       https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html 
+//! 
+//!    3. Good resources on DSP – Digital Signal Programming
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_electronics#dsp--digital-signal-programming
+//!
+//!    4. Biquads - EarLevel
+//!       http://www.earlevel.com/main/2003/02/28/biquads/
+//!
+//!    5. Biquad C++ source code - EarLevel
+//!       https://www.earlevel.com/main/2012/11/26/biquad-c-source-code/
+//!
+//!    6. A biquad calculator V3 - EarLevel
+//!       https://www.earlevel.com/main/2021/09/02/biquad-calculator-v3/
+//! 
+//!    7. WebAudio API - Mozilla Docs
+//!       https://developer.mozilla.org/en-US/docs/Web/API/Web_Audio_API
+//! 
+//!    8. Audio Filters - Theory and Practice
+//!       by Ethan Winer
+//!       http://ethanwiner.com/filters.html
+//! 
+//!    9. Audio filter - Wikipedia
+//!       https://en.wikipedia.org/wiki/Audio_filter
+//! 
+//!   10. Electronic filter - Wikipedia
+//!       https://en.wikipedia.org/wiki/Electronic_filter
+//!
+//!   11. How to learn modern Rust
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
+//!
+//! 
+//! 10 Band Equalizer
+//! 
+//!   12. Making an EQ from cascading filters
+//!       https://dsp.stackexchange.com/questions/10309/making-an-eq-from-cascading-filters
+//! 
+//!   13. PEAK/NOTCH FILTER DESIGN
+//!       https://www.dsprelated.com/showcode/169.php
+//! 
+//!   14. The Equivalence of Various Methods of Computing
+//!       Biquad Coefficients for Audio Parametric Equalizers
+//!       http://www.thesounddesign.com/MIO/EQ-Coefficients.pdf
+//!
+//!   15. How to learn modern Rust
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
+//!
+
+use std::f64::consts::TAU;
+use std::fmt::Debug;
+use std::ops::Add;
+use std::ops::Sub;
+use std::ops::Mul;
+use std::ops::Div;
+use std::ops::AddAssign;
+use rustfft::num_complex::Complex;
+
+use crate::convert::Sample; // Trait
+
+
+/// The float type of the samples of the processing, f32 or f64.
+pub trait Float: Sample + PartialOrd + Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+        + Div<Output = Self> + AddAssign {
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+impl Float for f32 {
+    const ZERO: f32 = 0.0;
+    const ONE: f32 = 1.0;
+}
+
+impl Float for f64 {
+    const ZERO: f64 = 0.0;
+    const ONE: f64 = 1.0;
+}
+
+/// A block that processes a stream of samples, of f64 when the type isn't given.
+pub trait ProcessingBlock<T: Float = f64> {
+    fn process(& mut self, sample: T) -> T;
+
+    /// Processes a block of samples, the input and the output of the same length.
+    /// The output is the same as with process() on each sample, a block can
+    /// replace it with a faster loop without the call of each sample.
+    fn process_buffer(& mut self, input: & [T], output: & mut [T]) {
+        assert_eq!(input.len(), output.len());
+        for (x, y) in input.iter().zip(output.iter_mut()) {
+            *y = self.process(*x);
+        }
+    }
+
+    /// Processes a block of samples in place, like the f32 buffer of an audio
+    /// callback.
+    fn process_inplace(& mut self, samples: & mut [T]) {
+        for sample in samples.iter_mut() {
+            *sample = self.process(*sample);
+        }
+    }
+}
+
+impl<T: Float, B: ProcessingBlock<T> + ?Sized> ProcessingBlock<T> for & mut B {
+    fn process(& mut self, sample: T) -> T {
+        (**self).process(sample)
+    }
+
+    fn process_buffer(& mut self, input: & [T], output: & mut [T]) {
+        (**self).process_buffer(input, output)
+    }
+
+    fn process_inplace(& mut self, samples: & mut [T]) {
+        (**self).process_inplace(samples)
+    }
+}
+
+impl<T: Float, B: ProcessingBlock<T> + ?Sized> ProcessingBlock<T> for Box<B> {
+    fn process(& mut self, sample: T) -> T {
+        (**self).process(sample)
+    }
+
+    fn process_buffer(& mut self, input: & [T], output: & mut [T]) {
+        (**self).process_buffer(input, output)
+    }
+
+    fn process_inplace(& mut self, samples: & mut [T]) {
+        (**self).process_inplace(samples)
+    }
+}
+
+/// The processing blocks as adapters of the iterators of samples, for lazy
+/// pipelines over generators, decoded streams or test vectors.
 ///
-///   15. How to learn modern Rust
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
+/// In Rust:
+///    ```text
+///    >>> let mut lowpass = make_lowpass(1_000.0, 48_000, None);
+///    >>> let output: Vec<f64> = input.iter().copied()
+///    >>>     .filter_with(& mut lowpass)
+///    >>>     .filter_with(make_highpass(20.0, 48_000, None))
+///    >>>     .collect();
+///    ```
 ///
+pub trait ProcessingBlockExt<T: Float>: Iterator<Item = T> + Sized {
+    /// Each sample through the block, when the iterator is advanced.
+    fn filter_with<B: ProcessingBlock<T>>(self, block: B) -> FilterWith<Self, B> {
+        FilterWith { samples: self, block }
+    }
+}
+
+impl<T: Float, I: Iterator<Item = T>> ProcessingBlockExt<T> for I {}
+
+/// The iterator of filter_with().
+pub struct FilterWith<I, B> {
+    samples: I,
+    block:   B,
+}
 
+impl<I, B> FilterWith<I, B> {
+    /// The block, with its state after the samples already taken.
+    pub fn into_block(self) -> B {
+        self.block
+    }
+}
+
+impl<T: Float, I: Iterator<Item = T>, B: ProcessingBlock<T>> Iterator for FilterWith<I, B> {
+    type Item = T;
+
+    fn next(& mut self) -> Option<T> {
+        self.samples.next().map(|sample| self.block.process(sample))
+    }
 
-pub trait ProcessingBlock {
-    fn process(& mut self, sample: f64) -> f64;
+    fn size_hint(& self) -> (usize, Option<usize>) {
+        self.samples.size_hint()
+    }
 }
 
+impl<T: Float, I: ExactSizeIterator<Item = T>, B: ProcessingBlock<T>> ExactSizeIterator for FilterWith<I, B> {}
+
 
 /// N-Order IIR filter
 /// Assumes working with float samples normalized on [-1, 1]
@@ -117,22 +240,32 @@ pub trait ProcessingBlock {
 /// we can rewrite this to
 ///   y[n]={\frac{1}{a_{0}}}\left(\left(b_{0}x[n]+b_{1}x[n-1]+b_{2}x[n-2]+...+b_{k}x[n-k]\right)-\left(a_{1}y[n-1]+a_{2}y[n-2]+...+a_{k}y[n-k]\right)\right)
 ///
-pub struct IIRFilter {
+/// The samples, the coefficients and the state are of the type T, f64 by default.
+/// The make_* functions design the coefficients in f64, and cast() makes the f32
+/// filter of a design, with the coefficients rounded once to f32:
+///    ```text
+///    >>> let mut filter: IIRFilter<f32> = make_lowpass(1_000.0, 48_000, None).cast();
+///    >>> filter.process_inplace(& mut f32_buffer);
+///    ```
+///
+#[derive(Debug, Clone)]
+pub struct IIRFilter<T: Float = f64> {
     pub order: usize,
     // a_{0} ... a_{k}
-    pub a_coeffs: Vec<f64>,
+    pub a_coeffs: Vec<T>,
     // b_{0} ... b_{k}
-    pub b_coeffs: Vec<f64>,
+    pub b_coeffs: Vec<T>,
     // x[n-1] ... x[n-k]
-    input_history: Vec<f64>,
+    input_history: Vec<T>,
     // y[n-1] ... y[n-k]
-    output_history: Vec<f64>,
+    output_history: Vec<T>,
 }
 
 impl IIRFilter {
+    /// A filter of f64, the filters of other types are made with cast().
     pub fn new(order: usize) -> Self {
         IIRFilter {
-            order: order,
+            order,
             // a_{0} ... a_{k}
             a_coeffs: { let mut a_coeffs = vec![0.0; 1 + order];
                         a_coeffs[0] = 1.0;
@@ -148,10 +281,66 @@ impl IIRFilter {
         }
     }
 
+    /// The filter of the poles and the zeros in the z plane, and the gain,
+    ///
+    ///    H(z) = gain * (1 - z_1 z^-1) ... (1 - z_m z^-1) / ((1 - p_1 z^-1) ... (1 - p_n z^-1))
+    ///
+    /// of order max(n, m), with the coefficients a_0 = 1 and b_0 = gain. A complex
+    /// pole or zero must have its conjugate, for the real coefficients, and the
+    /// poles must be inside the unit circle, for a stable filter.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> // A resonator at 1 kHz with zeros at DC and at Nyquist.
+    ///    >>> let pole = Complex::from_polar(0.99, TAU * 1_000.0 / 48_000.0);
+    ///    >>> let zeros = [Complex::new(1.0, 0.0), Complex::new(-1.0, 0.0)];
+    ///    >>> let filter = IIRFilter::from_poles_zeros(& [pole, pole.conj()], & zeros, 0.01)?;
+    ///    >>> show_frequency_response(& mut filter, 48_000, "resonator.png")?;
+    ///    ```
+    ///
+    pub fn from_poles_zeros(poles: & [Complex<f64>], zeros: & [Complex<f64>], gain: f64) -> Result<Self, String> {
+        if poles.is_empty() && zeros.is_empty() {
+            return Err("Error: the filter needs at least a pole or a zero".to_string());
+        }
+        if !gain.is_finite() {
+            return Err(format!("Error: the gain must be finite, got {}", gain));
+        }
+        check_conjugate_pairs(poles, "pole")?;
+        check_conjugate_pairs(zeros, "zero")?;
+        if let Some(pole) = poles.iter().find(|p| p.norm() >= 1.0) {
+            return Err(format!("Error: the pole {} has a radius of {}, of 1 or more the filter is unstable",
+                               pole, pole.norm()));
+        }
+        let order = usize::max(poles.len(), zeros.len());
+        let mut a_coeffs = polynomial_from_roots(poles);
+        let mut b_coeffs: Vec<f64> = polynomial_from_roots(zeros).iter().map(|c| gain * c).collect();
+        a_coeffs.resize(order + 1, 0.0);
+        b_coeffs.resize(order + 1, 0.0);
+        let mut filter = IIRFilter::new(order);
+        filter.set_coefficients(& a_coeffs, & b_coeffs)?;
+        Ok(filter)
+    }
+}
+
+impl<T: Float> IIRFilter<T> {
+    /// The same filter with the samples of the type U, the coefficients converted
+    /// and the state cleared.
+    pub fn cast<U: Float>(& self) -> IIRFilter<U> {
+        let convert = |coeffs: & [T]| -> Vec<U> { coeffs.iter().map(|c| U::from_f64(c.to_f64())).collect() };
+        IIRFilter {
+            order: self.order,
+            a_coeffs: convert(& self.a_coeffs),
+            b_coeffs: convert(& self.b_coeffs),
+            input_history: vec![U::ZERO; self.order],
+            output_history: vec![U::ZERO; self.order],
+        }
+    }
+
     /// Set the coefficients for the IIR filter. These should both be of size order + 1.
     /// a_0 may be left out, and it will use 1.0 as default value.
     ///
     /// This method works well with scipy's filter design functions
+    ///    ```text
     ///    >>> # Make a 2nd-order 1000Hz butterworth lowpass filter
     ///    >>> import scipy.signal
     ///    >>> b_coeffs, a_coeffs = scipy.signal.butter(2, 1000,
@@ -159,15 +348,18 @@ impl IIRFilter {
     ///    ...                                          fs=48000)
     ///    >>> filt = IIRFilter(2)
     ///    >>> filt.set_coefficients(a_coeffs, b_coeffs)
+    ///    ```
     ///
     /// In Rust
+    ///    ```text
     ///    >>> let a_coeffs = [0.1,  0.2,  0.3]
     ///    >>> let b_coeffs = [0.15, 0.25, 0.35]
     ///    >>> let filter_order: u32 = 2;
     ///    >>> let iir_filter = IIR_Filter::new(filter_order);
     ///    >>> iir_filter.set_coefficients(& a_coeffs[], & b_coeffs[]);
+    ///    ```
     ///          
-    pub fn set_coefficients(& mut self, a_coeffs: &[f64], b_coeffs: &[f64]) -> Result<(), String> {
+    pub fn set_coefficients(& mut self, a_coeffs: &[T], b_coeffs: &[T]) -> Result<(), String> {
         if a_coeffs.len() != self.order + 1 && a_coeffs.len() != self.order {
             return Err(
                      r"Expected a_coeffs to have {self.order + 1} elements for {self.order} /
@@ -180,35 +372,128 @@ impl IIRFilter {
         }
         self.a_coeffs.clear();
         if a_coeffs.len() < self.order + 1 {
-            self.a_coeffs.push(1.0);
+            self.a_coeffs.push(T::ONE);
             self.a_coeffs.extend(a_coeffs);
         } else {
             self.a_coeffs.extend(a_coeffs);
         }
         self.b_coeffs.clear();
         self.b_coeffs.extend(b_coeffs);
+        trace_event!(order = self.order, a_coeffs = ?self.a_coeffs, b_coeffs = ?self.b_coeffs, "IIR filter coefficients updated");
         
         Ok(())
     }
 
+    /// Frequency response H(e^jw) of the filter at a frequency, evaluated from
+    /// the coefficients of the transfer function, with z^-1 = e^-jw .
+    /// It doesn't change the filter state.
+    pub fn frequency_response(& self, frequency: f64, sample_rate: u32) -> Complex<f64> {
+        let w = TAU * frequency / sample_rate as f64;
+        let z_inv = Complex::from_polar(1.0, -w);
+        let mut z_inv_pow = Complex::new(1.0, 0.0);
+        let mut numerator   = Complex::new(0.0, 0.0);
+        let mut denominator = Complex::new(0.0, 0.0);
+        for (b, a) in self.b_coeffs.iter().zip(& self.a_coeffs) {
+            numerator   += z_inv_pow * b.to_f64();
+            denominator += z_inv_pow * a.to_f64();
+            z_inv_pow   *= z_inv;
+        }
+
+        numerator / denominator
+    }
+
+    /// The output of the input from the explicit state zi, and the final state zf,
+    /// like y, zf = lfilter(b, a, x, zi=zi) of scipy, for the chunks of an offline
+    /// processing, the zf of a chunk is the zi of the next one. The state has order
+    /// values, of the transposed direct form II of scipy, and it is not the history
+    /// of process(), that this doesn't change. The zi of the steady state of a
+    /// step is lfilter_zi() of signal.rs .
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let zi = vec![0.0; filter.order];
+    ///    >>> let (y_0, zf) = filter.process_with_state(& x[..1024], & zi)?;
+    ///    >>> let (y_1, zf) = filter.process_with_state(& x[1024..], & zf)?;
+    ///    ```
+    ///
+    pub fn process_with_state(& self, input: & [T], zi: & [T]) -> Result<(Vec<T>, Vec<T>), String> {
+        if zi.len() != self.order {
+            return Err(format!("Error: the state zi has {} values, the filter of order {} needs {}",
+                               zi.len(), self.order, self.order));
+        }
+        let a_0 = self.a_coeffs[0];
+        let a: Vec<T> = self.a_coeffs.iter().map(|c| *c / a_0).collect();
+        let b: Vec<T> = self.b_coeffs.iter().map(|c| *c / a_0).collect();
+        let mut state = zi.to_vec();
+        let output = input.iter().map(|x| {
+            let y = b[0] * *x + state.first().copied().unwrap_or(T::ZERO);
+            for k in 0..self.order {
+                let next = if k + 1 < self.order { state[k + 1] } else { T::ZERO };
+                state[k] = b[k + 1] * *x + next - a[k + 1] * y;
+            }
+            y
+        }).collect();
+        Ok((output, state))
+    }
+
+}
+
+// Each complex root, with an imaginary part, must have its conjugate.
+fn check_conjugate_pairs(roots: & [Complex<f64>], name: & str) -> Result<(), String> {
+    let tolerance = |root: & Complex<f64>| 1e-9 * f64::max(1.0, root.norm());
+    if let Some(root) = roots.iter().find(|r| !r.re.is_finite() || !r.im.is_finite()) {
+        return Err(format!("Error: the {} {} isn't finite", name, root));
+    }
+    let mut is_paired = vec![false; roots.len()];
+    for i in 0..roots.len() {
+        if is_paired[i] || roots[i].im.abs() <= tolerance(& roots[i]) {
+            continue;
+        }
+        let conjugate = roots[i].conj();
+        let pair = (0..roots.len())
+            .find(|j| *j != i && !is_paired[*j] && (roots[*j] - conjugate).norm() <= tolerance(& roots[i]));
+        match pair {
+            Some(j) => { is_paired[i] = true; is_paired[j] = true; },
+            None    => return Err(format!("Error: the complex {} {} doesn't have its conjugate {}",
+                                          name, roots[i], conjugate)),
+        }
+    }
+    Ok(())
+}
+
+// The coefficients of (1 - r_1 z^-1) ... (1 - r_n z^-1), of z^0 to z^-n, real
+// for the roots in conjugate pairs.
+fn polynomial_from_roots(roots: & [Complex<f64>]) -> Vec<f64> {
+    let mut coeffs = vec![Complex::new(1.0, 0.0)];
+    for root in roots {
+        coeffs.push(Complex::new(0.0, 0.0));
+        for k in (1..coeffs.len()).rev() {
+            coeffs[k] = coeffs[k] - root * coeffs[k - 1];
+        }
+    }
+    coeffs.iter().map(|c| c.re).collect()
 }
 
-impl ProcessingBlock for IIRFilter {
+impl<T: Float> ProcessingBlock<T> for IIRFilter<T> {
 
     /// Calculate y[n]
     /// 
     /// In Python
+    ///     ```text
     ///     >>> filt = IIRFilter(2)
     ///     >>> filt.process(0)
     ///     0.0
+    ///     ```
     /// 
     /// In Rust
+    ///     ```text
     ///     >>> let filt = IIRFilter::new(2)
     ///     >>> filt.process(0.0)
     ///     0.0
+    ///     ```
     ///
-    fn process(& mut self, sample: f64) -> f64 {
-        let mut result: f64 = 0.0;
+    fn process(& mut self, sample: T) -> T {
+        let mut result: T = T::ZERO;
 
         // Start at index 1 and do index 0 at the end.
         for i in 1..(self.order + 1) {
@@ -229,11 +514,160 @@ impl ProcessingBlock for IIRFilter {
         result
     }
 
+    fn process_buffer(& mut self, input: & [T], output: & mut [T]) {
+        assert_eq!(input.len(), output.len());
+        output.copy_from_slice(input);
+        self.process_inplace(output);
+    }
+
+    /// The biquads, order 2, run with the coefficients and the state in local
+    /// variables, the same operations in the same order as process(), so the
+    /// output is bit identical. The other orders use process().
+    fn process_inplace(& mut self, samples: & mut [T]) {
+        if self.order != 2 {
+            for sample in samples.iter_mut() {
+                *sample = self.process(*sample);
+            }
+            return;
+        }
+        let (a0, a1, a2) = (self.a_coeffs[0], self.a_coeffs[1], self.a_coeffs[2]);
+        let (b0, b1, b2) = (self.b_coeffs[0], self.b_coeffs[1], self.b_coeffs[2]);
+        let (mut x1, mut x2) = (self.input_history[0], self.input_history[1]);
+        let (mut y1, mut y2) = (self.output_history[0], self.output_history[1]);
+        for sample in samples.iter_mut() {
+            let x = *sample;
+            let mut result = T::ZERO;
+            result += b1 * x1 - a1 * y1;
+            result += b2 * x2 - a2 * y2;
+            let y = (result + b0 * x) / a0;
+            (x2, x1) = (x1, x);
+            (y2, y1) = (y1, y);
+            *sample = y;
+        }
+        self.input_history[0]  = x1;
+        self.input_history[1]  = x2;
+        self.output_history[0] = y1;
+        self.output_history[1] = y2;
+    }
+
+}
+
+/// A cascade of IIR filters, the output of each one is the input of the next, like
+/// the second order sections of a design of high order, see
+/// make_butterworth_lowpass(). The sections are biquads, and a first order section
+/// for an odd order. The cascade is the same filter as the transfer function of
+/// the product of the sections, with a much smaller error of the coefficients.
+#[derive(Debug, Clone)]
+pub struct FilterChain<T: Float = f64> {
+    filters: Vec<IIRFilter<T>>,
+}
+
+impl<T: Float> FilterChain<T> {
+    pub fn new(filters: Vec<IIRFilter<T>>) -> Self {
+        assert!(!filters.is_empty());
+        FilterChain { filters }
+    }
+
+    pub fn get_filters(& self) -> & [IIRFilter<T>] {
+        & self.filters
+    }
+
+    pub fn get_num_sections(& self) -> usize {
+        self.filters.len()
+    }
+
+    /// The order of the cascade, the sum of the orders of the sections.
+    pub fn get_order(& self) -> usize {
+        self.filters.iter().map(|filter| filter.order).sum()
+    }
+
+    /// The same cascade with the samples of the type U, see IIRFilter::cast().
+    pub fn cast<U: Float>(& self) -> FilterChain<U> {
+        FilterChain { filters: self.filters.iter().map(|filter| filter.cast()).collect() }
+    }
+
+    /// Frequency response of the cascade, the product of the responses of the
+    /// sections.
+    pub fn frequency_response(& self, frequency: f64, sample_rate: u32) -> Complex<f64> {
+        self.filters.iter()
+            .map(|filter| filter.frequency_response(frequency, sample_rate))
+            .product()
+    }
+
+    /// The b and a coefficients of the transfer function of the cascade, of order
+    /// get_order(), with a_0 = 1, like sos2tf of scipy.
+    pub fn to_transfer_function(& self) -> (Vec<f64>, Vec<f64>) {
+        let multiply = |p: & [f64], q: & [f64]| -> Vec<f64> {
+            let mut product = vec![0.0; p.len() + q.len() - 1];
+            for (i, p_i) in p.iter().enumerate() {
+                for (j, q_j) in q.iter().enumerate() {
+                    product[i + j] += p_i * q_j;
+                }
+            }
+            product
+        };
+        let mut b = vec![1.0];
+        let mut a = vec![1.0];
+        for filter in & self.filters {
+            let a_0 = filter.a_coeffs[0].to_f64();
+            b = multiply(& b, & filter.b_coeffs.iter().map(|c| c.to_f64() / a_0).collect::<Vec<f64>>());
+            a = multiply(& a, & filter.a_coeffs.iter().map(|c| c.to_f64() / a_0).collect::<Vec<f64>>());
+        }
+        (b, a)
+    }
+}
+
+impl<T: Float> ProcessingBlock<T> for FilterChain<T> {
+    fn process(& mut self, sample: T) -> T {
+        self.filters.iter_mut().fold(sample, |x, filter| filter.process(x))
+    }
+
+    fn process_buffer(& mut self, input: & [T], output: & mut [T]) {
+        assert_eq!(input.len(), output.len());
+        output.copy_from_slice(input);
+        self.process_inplace(output);
+    }
+
+    /// Section by section, the same output as sample by sample.
+    fn process_inplace(& mut self, samples: & mut [T]) {
+        for filter in self.filters.iter_mut() {
+            filter.process_inplace(samples);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::biquad_calculator::quadratic_roots;
+
+    #[test]
+    fn test_from_poles_zeros() {
+        // The poles and the zeros of a lowpass make the same lowpass.
+        let lowpass = make_lowpass(1_000.0, 48_000, None);
+        let [b0, b1, b2] = [lowpass.b_coeffs[0], lowpass.b_coeffs[1], lowpass.b_coeffs[2]];
+        let [a0, a1, a2] = [lowpass.a_coeffs[0], lowpass.a_coeffs[1], lowpass.a_coeffs[2]];
+        let poles = quadratic_roots(1.0, a1 / a0, a2 / a0);
+        let zeros = quadratic_roots(b0, b1, b2);
+        let filter = IIRFilter::from_poles_zeros(& poles, & zeros, b0 / a0).unwrap();
+        assert_eq!(filter.order, 2);
+        for (c, expected) in filter.a_coeffs.iter().chain(& filter.b_coeffs)
+                .zip(lowpass.a_coeffs.iter().chain(& lowpass.b_coeffs).map(|c| c / a0)) {
+            assert!((c - expected).abs() < 1e-12);
+        }
+
+        // One pole, without zeros, the impulse response is 0.5^n .
+        let mut one_pole = IIRFilter::from_poles_zeros(& [Complex::new(0.5, 0.0)], & [], 1.0).unwrap();
+        let impulse: Vec<f64> = (0..4).map(|n| one_pole.process(if n == 0 { 1.0 } else { 0.0 })).collect();
+        assert_eq!(impulse, vec![1.0, 0.5, 0.25, 0.125]);
+
+        let pole = Complex::from_polar(0.9, 0.3);
+        assert!(IIRFilter::from_poles_zeros(& [pole, pole.conj()], & [], 1.0).is_ok());
+        assert!(IIRFilter::from_poles_zeros(& [pole, pole], & [], 1.0).is_err());
+        assert!(IIRFilter::from_poles_zeros(& [Complex::new(1.0, 0.0)], & [], 1.0).is_err());
+        assert!(IIRFilter::from_poles_zeros(& [], & [], 1.0).is_err());
+    }
 
     #[test]
     fn test_iir_filter_000() {
@@ -275,5 +709,99 @@ mod tests {
         // assert_eq!(true, false);
     }
 
+    #[test]
+    fn test_iir_filter_frequency_response() {
+        use crate::butterworth_filter::make_lowpass;
+
+        let frequency = 1_000.0;  // Hz
+        let sample_rate = 48_000; // Samples
+        let filter = make_lowpass(frequency, sample_rate, None);
+        let magnitude_db = |f: f64| 20.0 * f64::log10(filter.frequency_response(f, sample_rate).norm());
+        // Unity gain at DC, -3 dB at the cutoff and a zero at the Nyquist frequency.
+        assert!(magnitude_db(0.0).abs() < 1e-9);
+        assert!((magnitude_db(frequency) + 3.0103).abs() < 1e-3);
+        assert!(filter.frequency_response(24_000.0, sample_rate).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_process_buffer() {
+        use crate::butterworth_filter::make_peak;
+
+        // Bit identical to process(), across the blocks, for the biquads and an
+        // other order.
+        let input: Vec<f64> = (0..1_000).map(|n| f64::sin(n as f64 * 0.37) * 0.8).collect();
+        let mut third_order = IIRFilter::new(3);
+        third_order.set_coefficients(& [1.0, -0.5, 0.1, -0.02], & [0.3, 0.2, 0.1, 0.05]).unwrap();
+        let mut third_order_ref = IIRFilter::new(3);
+        third_order_ref.set_coefficients(& [1.0, -0.5, 0.1, -0.02], & [0.3, 0.2, 0.1, 0.05]).unwrap();
+        for (mut filter, mut reference) in [(make_peak(1_000.0, 48_000, 6.0, Some(2.0)), make_peak(1_000.0, 48_000, 6.0, Some(2.0))),
+                                            (third_order, third_order_ref)] {
+            let expected: Vec<f64> = input.iter().map(|x| reference.process(*x)).collect();
+            let mut output = vec![0.0; input.len()];
+            filter.process_buffer(& input[..300], & mut output[..300]);
+            output[300..].copy_from_slice(& input[300..]);
+            filter.process_inplace(& mut output[300..]);
+            assert_eq!(output, expected);
+            assert_eq!(filter.process(0.5), reference.process(0.5));
+        }
+        // The default of the trait, through a boxed block.
+        let mut boxed: Box<dyn ProcessingBlock> = Box::new(make_peak(1_000.0, 48_000, 6.0, Some(2.0)));
+        let mut output = input.clone();
+        boxed.process_inplace(& mut output);
+        let mut reference = make_peak(1_000.0, 48_000, 6.0, Some(2.0));
+        assert!(input.iter().zip(& output).all(|(x, y)| reference.process(*x) == *y));
+    }
+
+    #[test]
+    fn test_filter_with() {
+        use crate::butterworth_filter::make_lowpass;
+        use crate::butterworth_filter::make_highpass;
+
+        let input: Vec<f64> = (0..1_000).map(|n| f64::sin(n as f64 * 0.7) + 0.5).collect();
+        let mut lowpass = make_lowpass(1_000.0, 48_000, None);
+        let output: Vec<f64> = input.iter().copied()
+            .filter_with(& mut lowpass)
+            .filter_with(make_highpass(20.0, 48_000, None))
+            .collect();
+        let (mut lowpass_ref, mut highpass_ref) = (make_lowpass(1_000.0, 48_000, None), make_highpass(20.0, 48_000, None));
+        let reference: Vec<f64> = input.iter().map(|x| highpass_ref.process(lowpass_ref.process(*x))).collect();
+        assert_eq!(output, reference);
+        // The borrowed block keeps its state after the pipeline.
+        assert_eq!(lowpass.process(0.0), lowpass_ref.process(0.0));
+
+        // Lazy, only the samples taken are processed, from an endless generator.
+        let mut n = 0;
+        let generator = std::iter::from_fn(|| { n += 1; Some(n as f32) });
+        let boxed: Box<dyn ProcessingBlock<f32>> = Box::new(make_lowpass(1_000.0, 48_000, None).cast::<f32>());
+        let mut pipeline = generator.filter_with(boxed);
+        assert_eq!(pipeline.by_ref().take(10).count(), 10);
+        let mut block = pipeline.into_block();
+        let mut reference: IIRFilter<f32> = make_lowpass(1_000.0, 48_000, None).cast();
+        (1..=10).for_each(|x| { reference.process(x as f32); });
+        assert_eq!(block.process(11.0), reference.process(11.0));
+        assert_eq!(input.iter().copied().filter_with(IIRFilter::new(2)).len(), 1_000);
+    }
+
+    #[test]
+    fn test_iir_filter_f32() {
+        use crate::butterworth_filter::make_peak;
+
+        let mut filter_f64 = make_peak(1_000.0, 48_000, 6.0, Some(2.0));
+        let mut filter_f32: IIRFilter<f32> = filter_f64.cast();
+        assert_eq!(filter_f32.b_coeffs[1], filter_f64.b_coeffs[1] as f32);
+        assert!((filter_f32.frequency_response(1_000.0, 48_000).norm() - 1.995).abs() < 1e-3);
+
+        let input: Vec<f32> = (0..4_800).map(|n| f32::sin(n as f32 * 0.13)).collect();
+        let mut output = input.clone();
+        filter_f32.process_inplace(& mut output);
+        let max_error = input.iter().zip(& output)
+            .map(|(x, y)| (filter_f64.process(*x as f64) - *y as f64).abs())
+            .fold(0.0, f64::max);
+        assert!(max_error < 1e-4, "{}", max_error);
+        // The cast clears the state.
+        let mut again: IIRFilter<f32> = filter_f32.cast();
+        assert_eq!(again.process(input[0]), output[0]);
+    }
+
 }
 
@@ -0,0 +1,372 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Post-processing of a measured impulse response.
+//!              The impulse response recovered from a measurement, see
+//!              generators.rs, is circular: the delay of the system puts the peak
+//!              anywhere, and the samples before it wrap around to the end. It also
+//!              holds the reflections of the room and the noise. Before it is used
+//!              to design a correction filter:
+//!
+//!                 1. find_peak() finds the direct sound, the sample of largest
+//!                    magnitude.
+//!                 2. circular_shift() moves the peak near t = 0, keeping a few
+//!                    samples before it, the pre-ringing of the system.
+//!                 3. half_hann_window() fades in the samples before the peak and
+//!                    fades out before the first reflection, a frequency dependent
+//!                    window like this one keeps the direct sound only.
+//!                 4. minimum_phase() makes the minimum phase impulse response with
+//!                    the same magnitude response, without the delay and the
+//!                    excess phase, that can't be corrected. It uses the folded
+//!                    real cepstrum:
+//!
+//!                       c = IFFT(log |H|)
+//!                       h_min = IFFT(exp(FFT(c_folded)))
+//!
+//!                    c_folded keeps c[0] and c[N / 2], doubles the causal part and
+//!                    zeroes the anti-causal part.
+//!
+//!              And for the measurement of a room:
+//!
+//!                 - energy_time_curve(), the ETC, the envelope of the impulse
+//!                   response in dB, the magnitude of the analytic signal. It shows
+//!                   the direct sound and the reflections.
+//!                 - schroeder_decay(), the energy decay curve, the backward integral
+//!                   of the squared impulse response. It is the average decay of many
+//!                   measurements with interrupted noise, without their fluctuations.
+//!                 - reverberation_time(), the EDT, T20 and T30 from the line fitted to
+//!                   the decay curve, from 0 dB to -10 dB, from -5 dB to -25 dB and
+//!                   from -5 dB to -35 dB, extrapolated to a decay of 60 dB.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Oppenheim, A. V., Schafer, R. W. - Discrete-Time Signal Processing.
+//!       Chapter 13, Cepstrum analysis and homomorphic deconvolution
+//!
+//!    2. Minimum phase - Wikipedia
+//!       https://en.wikipedia.org/wiki/Minimum_phase
+//!
+//!    3. Schroeder, M. R. - New Method of Measuring Reverberation Time.
+//!       Journal of the Acoustical Society of America, 1965
+//!
+//!    4. ISO 3382-1 - Measurement of room acoustic parameters
+//!
+
+
+use std::f64::consts::PI;
+
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+
+
+// Lowest magnitude of the log in minimum_phase(), relative to the largest, -200 dB.
+const MIN_MAGNITUDE: f64 = 1e-10;
+
+/// Index of the sample of largest magnitude, the direct sound.
+pub fn find_peak(impulse_response: & [f64]) -> usize {
+    impulse_response.iter().enumerate()
+        .fold((0, 0.0), |(index, max), (n, x)| if x.abs() > max { (n, x.abs()) } else { (index, max) })
+        .0
+}
+
+/// Rotates the impulse response by the shift, the sample n goes to n + shift,
+/// modulo the length. A negative shift moves it earlier.
+///
+/// In Rust:
+///    ```text
+///    >>> // The peak at 32 samples, the samples before it were wrapped to the end.
+///    >>> let shift = 32 - find_peak(& impulse_response) as isize;
+///    >>> let aligned = circular_shift(& impulse_response, shift);
+///    ```
+///
+pub fn circular_shift(impulse_response: & [f64], shift: isize) -> Vec<f64> {
+    let length = impulse_response.len();
+    if length == 0 {
+        return Vec::new();
+    }
+    let mut output = impulse_response.to_vec();
+    output.rotate_right(shift.rem_euclid(length as isize) as usize);
+    output
+}
+
+/// Window of a half-Hann fade-in over the first fade_in samples, flat up to
+/// fade_out_start, and a half-Hann fade-out of fade_out samples. The output is
+/// truncated after the fade-out.
+///
+/// In Rust:
+///    ```text
+///    >>> // Peak at 32 samples, the first reflection at 5 ms, 1 ms fade-out at 48 kHz.
+///    >>> let windowed = half_hann_window(& aligned, 32, 32 + 240 - 48, 48)?;
+///    ```
+///
+pub fn half_hann_window(impulse_response: & [f64], fade_in: usize, fade_out_start: usize, fade_out: usize)
+        -> Result<Vec<f64>, String> {
+    let end = fade_out_start + fade_out;
+    if fade_in > fade_out_start || end > impulse_response.len() {
+        return Err(format!("Error: the window of fade-in {}, fade-out from {} to {} doesn't fit the {} samples",
+                           fade_in, fade_out_start, end, impulse_response.len()));
+    }
+    let output = impulse_response[..end].iter().enumerate().map(|(n, x)| {
+        let gain = if n < fade_in {
+            0.5 - 0.5 * f64::cos(PI * (n as f64 + 0.5) / fade_in as f64)
+        } else if n < fade_out_start {
+            1.0
+        } else {
+            0.5 + 0.5 * f64::cos(PI * (n - fade_out_start) as f64 / fade_out as f64)
+        };
+        x * gain
+    }).collect();
+    Ok(output)
+}
+
+/// Minimum phase impulse response with the magnitude response of the impulse
+/// response, of the same length. The FFT size, a power of 2 and at least 4 times
+/// the length, lowers the time aliasing of the cepstrum.
+///
+/// In Rust:
+///    ```text
+///    >>> let correction = minimum_phase(& windowed, 8 * windowed.len().next_power_of_two())?;
+///    ```
+///
+pub fn minimum_phase(impulse_response: & [f64], fft_size: usize) -> Result<Vec<f64>, String> {
+    let length = impulse_response.len();
+    if length == 0 {
+        return Err("Error: the impulse response is empty".to_string());
+    }
+    if !fft_size.is_power_of_two() || fft_size < 4 * length {
+        return Err(format!("Error: the FFT size {} must be a power of 2 and at least 4 times the length {}",
+                           fft_size, length));
+    }
+    let mut planner = FftPlanner::<f64>::new();
+    let fft = planner.plan_fft_forward(fft_size);
+    let ifft = planner.plan_fft_inverse(fft_size);
+
+    let mut spectrum: Vec<Complex<f64>> = impulse_response.iter().map(|x| Complex::new(*x, 0.0)).collect();
+    spectrum.resize(fft_size, Complex::new(0.0, 0.0));
+    fft.process(& mut spectrum);
+    let max_magnitude = spectrum.iter().fold(0.0, |max, x| f64::max(max, x.norm()));
+    if max_magnitude == 0.0 {
+        return Err("Error: the impulse response is silent".to_string());
+    }
+    let floor = MIN_MAGNITUDE * max_magnitude;
+
+    // Real cepstrum.
+    let mut cepstrum: Vec<Complex<f64>> = spectrum.iter()
+        .map(|x| Complex::new(f64::max(x.norm(), floor).ln(), 0.0))
+        .collect();
+    ifft.process(& mut cepstrum);
+    let half = fft_size / 2;
+    for (n, c) in cepstrum.iter_mut().enumerate() {
+        let fold = match n {
+            0 => 1.0,
+            n if n < half => 2.0,
+            n if n == half => 1.0,
+            _ => 0.0,
+        };
+        *c = Complex::new(c.re * fold / fft_size as f64, 0.0);
+    }
+
+    fft.process(& mut cepstrum);
+    let mut minimum_phase_spectrum: Vec<Complex<f64>> = cepstrum.iter().map(|c| c.exp()).collect();
+    ifft.process(& mut minimum_phase_spectrum);
+    Ok(minimum_phase_spectrum[..length].iter().map(|x| x.re / fft_size as f64).collect())
+}
+
+/// Energy time curve in dB, relative to its peak.
+///
+/// In Rust:
+///    ```text
+///    >>> let etc_db = energy_time_curve(& impulse_response);
+///    >>> // The reflections 20 dB below the direct sound.
+///    >>> let reflections: Vec<usize> = (1..etc_db.len()).filter(|n| etc_db[*n] > -20.0).collect();
+///    ```
+///
+pub fn energy_time_curve(impulse_response: & [f64]) -> Vec<f64> {
+    let length = impulse_response.len();
+    if length == 0 {
+        return Vec::new();
+    }
+    // The analytic signal, the spectrum without the negative frequencies. The zero
+    // padding keeps the tail of the Hilbert transform before the peak from wrapping
+    // around to the end.
+    let fft_size = 2 * length;
+    let mut planner = FftPlanner::<f64>::new();
+    let mut spectrum: Vec<Complex<f64>> = impulse_response.iter().map(|x| Complex::new(*x, 0.0)).collect();
+    spectrum.resize(fft_size, Complex::new(0.0, 0.0));
+    planner.plan_fft_forward(fft_size).process(& mut spectrum);
+    for (k, x) in spectrum.iter_mut().enumerate() {
+        if k > 0 && k < length {
+            *x *= 2.0;
+        } else if k > length {
+            *x = Complex::new(0.0, 0.0);
+        }
+    }
+    planner.plan_fft_inverse(fft_size).process(& mut spectrum);
+    let energy: Vec<f64> = spectrum[..length].iter().map(|x| x.norm_sqr()).collect();
+    let max_energy = energy.iter().fold(0.0, |max: f64, e| max.max(*e));
+    energy.iter().map(|e| 10.0 * f64::log10(e / max_energy)).collect()
+}
+
+/// Schroeder energy decay curve in dB, 0 dB at the first sample.
+pub fn schroeder_decay(impulse_response: & [f64]) -> Vec<f64> {
+    let mut decay = vec![0.0; impulse_response.len()];
+    let mut energy = 0.0;
+    for (d, x) in decay.iter_mut().zip(impulse_response).rev() {
+        energy += x * x;
+        *d = energy;
+    }
+    let total = match decay.first() {
+        Some(total) => *total,
+        None => return decay,
+    };
+    decay.iter().map(|e| 10.0 * f64::log10(e / total)).collect()
+}
+
+/// Reverberation times in seconds, None when the decay curve doesn't reach the
+/// end of the range above the noise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReverberationTime {
+    pub edt: Option<f64>,
+    pub t20: Option<f64>,
+    pub t30: Option<f64>,
+}
+
+/// Reverberation times of the impulse response of a room, from the direct sound.
+/// The impulse response must end before the noise, the decay curve of the noise
+/// is too slow.
+///
+/// In Rust:
+///    ```text
+///    >>> let rt = reverberation_time(& impulse_response[find_peak(& impulse_response)..], 48_000)?;
+///    >>> println!("T30 {:.2} s", rt.t30.unwrap());
+///    ```
+///
+pub fn reverberation_time(impulse_response: & [f64], sample_rate: u32) -> Result<ReverberationTime, String> {
+    if impulse_response.iter().all(|x| *x == 0.0) {
+        return Err("Error: the impulse response is silent".to_string());
+    }
+    let decay = schroeder_decay(impulse_response);
+    let fit = |start_db: f64, end_db: f64| -> Option<f64> {
+        let first = decay.iter().position(|d| *d <= start_db)?;
+        let last = decay.iter().position(|d| *d <= end_db)?;
+        if last < first + 2 {
+            return None;
+        }
+        let slope = regression_slope(& decay[first..=last]) * sample_rate as f64;
+        if slope >= 0.0 { None } else { Some(-60.0 / slope) }
+    };
+    Ok(ReverberationTime { edt: fit(0.0, -10.0), t20: fit(-5.0, -25.0), t30: fit(-5.0, -35.0) })
+}
+
+// Slope of the least squares line of the values, per sample.
+fn regression_slope(values: & [f64]) -> f64 {
+    let length = values.len() as f64;
+    let mean_n = (length - 1.0) / 2.0;
+    let mean_value = values.iter().sum::<f64>() / length;
+    let (covariance, variance) = values.iter().enumerate().fold((0.0, 0.0), |(c, v), (n, value)| {
+        let dn = n as f64 - mean_n;
+        (c + dn * (value - mean_value), v + dn * dn)
+    });
+    covariance / variance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iir_filter::ProcessingBlock; // Trait
+    use crate::butterworth_filter::make_lowpass;
+    use crate::butterworth_filter::make_peak;
+    use crate::smoothing::magnitude_response_db;
+    use crate::generators::make_white_noise;
+
+    fn impulse_response(block: & mut dyn ProcessingBlock, length: usize) -> Vec<f64> {
+        (0..length).map(|n| block.process(if n == 0 { 1.0 } else { 0.0 })).collect()
+    }
+
+    #[test]
+    fn test_align_and_window() {
+        let direct = impulse_response(& mut make_lowpass(4_000.0, 48_000, None), 1_024);
+        // The measured response: a delay that wraps around, and a reflection.
+        let mut measured = circular_shift(& direct, 1_000);
+        for (n, x) in direct.iter().enumerate().take(400) {
+            measured[(1_000 + 300 + n) % 1_024] += 0.5 * x;
+        }
+        let peak = find_peak(& measured);
+        assert_eq!(peak, (1_000 + find_peak(& direct)) % 1_024);
+
+        let aligned = circular_shift(& measured, 16 - peak as isize);
+        assert_eq!(find_peak(& aligned), 16);
+        assert_eq!(circular_shift(& aligned, -16 + peak as isize), measured);
+
+        // Keeps the direct sound up to the reflection at 300 samples after the start.
+        let start = 16 - find_peak(& direct);
+        let windowed = half_hann_window(& aligned, start, start + 250, 50).unwrap();
+        assert_eq!(windowed.len(), start + 300);
+        assert!(windowed[start..start + 250].iter().zip(& direct).all(|(y, x)| (y - x).abs() < 1e-12));
+        assert_eq!(windowed[start + 250], direct[250]);
+        assert!(half_hann_window(& aligned, 20, 10, 10).is_err());
+        assert!(half_hann_window(& aligned, 0, 1_000, 100).is_err());
+    }
+
+    #[test]
+    fn test_minimum_phase() {
+        // A minimum phase response, delayed, has the same minimum phase version.
+        let mut filter = make_peak(1_000.0, 48_000, 9.0, Some(2.0));
+        let original = impulse_response(& mut filter, 2_048);
+        let delayed = circular_shift(& original, 100);
+        let minimum = minimum_phase(& delayed, 16_384).unwrap();
+        assert_eq!(minimum.len(), 2_048);
+        assert_eq!(find_peak(& minimum), find_peak(& original));
+        let error = minimum.iter().zip(& original).fold(0.0, |max: f64, (y, x)| max.max((y - x).abs()));
+        assert!(error < 1e-3, "{}", error);
+
+        // The magnitude response is kept.
+        let (_, original_db) = magnitude_response_db(& original, 48_000);
+        let (_, minimum_db) = magnitude_response_db(& minimum, 48_000);
+        assert!(original_db.iter().zip(& minimum_db).all(|(a, b)| (a - b).abs() < 0.01));
+
+        assert!(minimum_phase(& delayed, 4_096).is_err());
+        assert!(minimum_phase(& [0.0; 16], 64).is_err());
+    }
+
+    #[test]
+    fn test_reverberation_time() {
+        // Exponentially decaying noise, an ideal diffuse room with an RT60 of 0.5 s.
+        let (sample_rate, rt60) = (48_000, 0.5);
+        let room: Vec<f64> = make_white_noise(48_000, 0.5, 3).iter().enumerate()
+            .map(|(n, noise)| noise * f64::powf(10.0, -3.0 * n as f64 / (rt60 * sample_rate as f64)))
+            .collect();
+        let rt = reverberation_time(& room, sample_rate).unwrap();
+        for time in [rt.edt, rt.t20, rt.t30] {
+            assert!((time.unwrap() - rt60).abs() < 0.03, "{:?}", rt);
+        }
+        // Too short for a line.
+        let short = reverberation_time(& [1.0, 0.5], sample_rate).unwrap();
+        assert_eq!(short, ReverberationTime { edt: None, t20: None, t30: None });
+        assert!(reverberation_time(& [0.0; 100], sample_rate).is_err());
+
+        let decay = schroeder_decay(& room);
+        assert_eq!(decay[0], 0.0);
+        assert!(decay.windows(2).all(|w| w[1] <= w[0]));
+    }
+
+    #[test]
+    fn test_energy_time_curve() {
+        // The direct sound and a reflection 12 dB lower.
+        let mut ir = impulse_response(& mut make_lowpass(8_000.0, 48_000, None), 1_000);
+        let direct = ir.clone();
+        for (n, x) in direct.iter().enumerate().take(500) {
+            ir[400 + n] += 0.25 * x;
+        }
+        let etc_db = energy_time_curve(& ir);
+        assert_eq!(etc_db.len(), 1_000);
+        let peak = (0..1_000).fold(0, |max, n| if etc_db[n] > etc_db[max] { n } else { max });
+        assert_eq!(etc_db[peak], 0.0);
+        let reflection = etc_db[300..].iter().fold(f64::NEG_INFINITY, |max, db| max.max(*db));
+        assert!((reflection + 12.0).abs() < 0.5, "{}", reflection);
+    }
+}
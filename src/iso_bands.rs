@@ -0,0 +1,193 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: ISO octave and third-octave bands.
+//!              The graphic equalizers, the band levels of a real time analyzer and
+//!              the loudness compensation use the standard bands of ISO 266 and
+//!              IEC 61260-1, with their nominal center frequencies, the names printed
+//!              on the faders, 31.5 Hz, 63 Hz, 125 Hz ... 16 kHz for the octaves, and
+//!              20 Hz, 25 Hz, 31.5 Hz ... 20 kHz for the third-octaves.
+//!              The exact centers, base 10, are 1000 Hz times a power of the octave
+//!              ratio G = 10^(3/10), and the band edges are at G^(-1/2b) and G^(1/2b)
+//!              of the center, for the 1/b octave bands:
+//!
+//!                 exact center  f_m = 1000 * 10^(3 x / (10 b)),   x = ... -1, 0, 1 ...
+//!                 band edges    f_1 = f_m * G^(-1 / (2 b)),   f_2 = f_m * G^(1 / (2 b))
+//!
+//!              The nominal frequency of a band is the rounded exact center, 31.5 Hz
+//!              is 31.62 Hz. The Q of a band, f_m / (f_2 - f_1), is 1.42 for the
+//!              octave and 4.33 for the third-octave.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. ISO 266:1997 - Acoustics - Preferred frequencies
+//!
+//!    2. IEC 61260-1:2014 - Electroacoustics - Octave-band and fractional-octave-band
+//!       filters - Part 1: Specifications
+//!
+//!    3. Octave band - Wikipedia
+//!       https://en.wikipedia.org/wiki/Octave_band
+//!
+
+
+/// Nominal center frequencies of the octave bands, from 31.5 Hz to 16 kHz.
+pub const OCTAVE_NOMINAL_FREQUENCIES: [f64; 10] = [
+    31.5, 63.0, 125.0, 250.0, 500.0, 1_000.0, 2_000.0, 4_000.0, 8_000.0, 16_000.0];
+
+/// Nominal center frequencies of the third-octave bands, from 20 Hz to 20 kHz.
+pub const THIRD_OCTAVE_NOMINAL_FREQUENCIES: [f64; 31] = [
+    20.0, 25.0, 31.5, 40.0, 50.0, 63.0, 80.0, 100.0, 125.0, 160.0, 200.0, 250.0, 315.0, 400.0,
+    500.0, 630.0, 800.0, 1_000.0, 1_250.0, 1_600.0, 2_000.0, 2_500.0, 3_150.0, 4_000.0, 5_000.0,
+    6_300.0, 8_000.0, 10_000.0, 12_500.0, 16_000.0, 20_000.0];
+
+// The index x of the first band, 31.5 Hz and 20 Hz, relative to the band of 1 kHz.
+const OCTAVE_FIRST_INDEX: i32 = -5;
+const THIRD_OCTAVE_FIRST_INDEX: i32 = -17;
+
+/// The width of the bands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BandWidth {
+    Octave,
+    ThirdOctave,
+}
+
+impl BandWidth {
+    /// The b of the 1/b octave bands.
+    pub fn get_bands_per_octave(& self) -> u32 {
+        match self {
+            BandWidth::Octave => 1,
+            BandWidth::ThirdOctave => 3,
+        }
+    }
+
+    pub fn get_nominal_frequencies(& self) -> & 'static [f64] {
+        match self {
+            BandWidth::Octave => & OCTAVE_NOMINAL_FREQUENCIES,
+            BandWidth::ThirdOctave => & THIRD_OCTAVE_NOMINAL_FREQUENCIES,
+        }
+    }
+
+    fn get_first_index(& self) -> i32 {
+        match self {
+            BandWidth::Octave => OCTAVE_FIRST_INDEX,
+            BandWidth::ThirdOctave => THIRD_OCTAVE_FIRST_INDEX,
+        }
+    }
+}
+
+/// A band of the table, the frequencies in Hz.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IsoBand {
+    pub nominal_frequency: f64,
+    pub center_frequency:  f64,
+    pub lower_frequency:   f64,
+    pub upper_frequency:   f64,
+}
+
+impl IsoBand {
+    /// The Q of the band, center / (upper - lower), for a peak filter of the band.
+    pub fn get_q_factor(& self) -> f64 {
+        self.center_frequency / (self.upper_frequency - self.lower_frequency)
+    }
+
+    pub fn contains(& self, frequency: f64) -> bool {
+        self.lower_frequency <= frequency && frequency < self.upper_frequency
+    }
+}
+
+// The band x, relative to the band of 1 kHz, of 1/b octave.
+fn make_band(nominal_frequency: f64, x: i32, bands_per_octave: u32) -> IsoBand {
+    let b = bands_per_octave as f64;
+    let center_frequency = 1_000.0 * f64::powf(10.0, 3.0 * x as f64 / (10.0 * b));
+    let half_band = f64::powf(10.0, 3.0 / (20.0 * b));
+    IsoBand {
+        nominal_frequency,
+        center_frequency,
+        lower_frequency: center_frequency / half_band,
+        upper_frequency: center_frequency * half_band,
+    }
+}
+
+/// All the bands of the table.
+pub fn iso_bands(width: BandWidth) -> Vec<IsoBand> {
+    width.get_nominal_frequencies().iter().enumerate()
+        .map(|(index, nominal)| make_band(*nominal, width.get_first_index() + index as i32, width.get_bands_per_octave()))
+        .collect()
+}
+
+/// The bands of the table with the upper edge below the frequency, like the
+/// Nyquist frequency.
+pub fn iso_bands_below(width: BandWidth, max_frequency: f64) -> Vec<IsoBand> {
+    iso_bands(width).into_iter().filter(|band| band.upper_frequency <= max_frequency).collect()
+}
+
+/// The band of the table nearest to the frequency, on a log scale. The band that
+/// contains it, or the first or the last band outside of the table.
+///
+/// In Rust:
+///    ```text
+///    >>> let band = nearest_iso_band(1_100.0, BandWidth::ThirdOctave);
+///    >>> println!("{} Hz", band.nominal_frequency);
+///    1000 Hz
+///    ```
+///
+pub fn nearest_iso_band(frequency: f64, width: BandWidth) -> IsoBand {
+    assert!(frequency > 0.0);
+    let bands = iso_bands(width);
+    let distance = |band: & IsoBand| f64::abs(f64::ln(frequency / band.center_frequency));
+    let index = (0..bands.len())
+        .fold(0, |best, i| if distance(& bands[i]) < distance(& bands[best]) { i } else { best });
+    bands[index]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equalizer::Equalizer;
+
+    #[test]
+    fn test_band_tables() {
+        for width in [BandWidth::Octave, BandWidth::ThirdOctave] {
+            let bands = iso_bands(width);
+            // The nominal frequencies are the exact centers within 3 %.
+            for band in & bands {
+                assert!((band.nominal_frequency / band.center_frequency - 1.0).abs() < 0.03, "{:?}", band);
+                assert!(band.contains(band.nominal_frequency));
+            }
+            // The bands are contiguous.
+            for pair in bands.windows(2) {
+                assert!((pair[0].upper_frequency - pair[1].lower_frequency).abs() < 1e-9);
+            }
+            let band_1k = bands.iter().find(|band| band.nominal_frequency == 1_000.0).unwrap();
+            assert!((band_1k.center_frequency - 1_000.0).abs() < 1e-9);
+        }
+        assert!((iso_bands(BandWidth::Octave)[0].get_q_factor() - 1.419).abs() < 1e-3);
+        assert!((iso_bands(BandWidth::ThirdOctave)[0].get_q_factor() - 4.334).abs() < 1e-3);
+        assert_eq!(iso_bands_below(BandWidth::Octave, 11_025.0).len(), 8);
+        assert_eq!(iso_bands_below(BandWidth::ThirdOctave, 24_000.0).len(), 31);
+    }
+
+    #[test]
+    fn test_nearest_iso_band() {
+        assert_eq!(nearest_iso_band(1_100.0, BandWidth::ThirdOctave).nominal_frequency, 1_000.0);
+        assert_eq!(nearest_iso_band(1_500.0, BandWidth::ThirdOctave).nominal_frequency, 1_600.0);
+        assert_eq!(nearest_iso_band(1_500.0, BandWidth::Octave).nominal_frequency, 2_000.0);
+        assert_eq!(nearest_iso_band(30.0, BandWidth::Octave).nominal_frequency, 31.5);
+        assert_eq!(nearest_iso_band(5.0, BandWidth::ThirdOctave).nominal_frequency, 20.0);
+        assert_eq!(nearest_iso_band(40_000.0, BandWidth::ThirdOctave).nominal_frequency, 20_000.0);
+    }
+
+    #[test]
+    fn test_iso_equalizer() {
+        let mut eq = Equalizer::make_equalizer_iso(48_000, BandWidth::ThirdOctave);
+        assert_eq!(eq.get_num_bands(), 31);
+        assert_eq!(eq.get_bands_freq(17), 1_000.0);
+        eq.set_band_gain(17, -6.0).unwrap();
+        assert!((eq.magnitude_db(1_000.0) + 6.0).abs() < 0.1);
+        assert_eq!(Equalizer::make_equalizer_iso(22_050, BandWidth::Octave).get_num_bands(), 8);
+    }
+}
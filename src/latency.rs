@@ -0,0 +1,248 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Measurement of the round-trip latency of an audio interface, with
+//!              a loopback cable from an output to an input, for the alignment of a
+//!              live EQ setup. The stimulus, a logarithmic sine sweep followed by a
+//!              silence as long as the largest latency, is played and recorded at
+//!              the same time, and the delay of the recording is the lag of the
+//!              maximum of the cross-correlation, estimate_delay() of analysis.rs:
+//!
+//!                 stimulus -> output -> cable -> input -> recording
+//!                 latency = lag of max |sum_n stimulus[n] recording[n + lag]|
+//!
+//!              The correlation of the sweep is a narrow peak, the latency is
+//!              refined to a fraction of a sample with the parabola through the
+//!              peak and its two neighbours. The correlation normalized by the
+//!              energies of the two signals is 1 for a clean loopback, a low one is
+//!              a bad cable or a level too low, and the measurement is an error. A
+//!              negative peak is an input or an output of inverted polarity.
+//!              The crate doesn't open the audio devices, run() calls a function of
+//!              the application that plays the stimulus and returns the recording,
+//!              with the backend of the application, and repeats the measurement to
+//!              see the jitter of the latency between the runs. The latency
+//!              includes the converters and the buffers of both directions.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Farina, A. - Simultaneous Measurement of Impulse Response and Distortion
+//!       with a Swept-Sine Technique. 108th AES Convention, 2000
+//!
+
+
+use crate::analysis::estimate_delay;
+use crate::fixtures::make_log_sweep;
+
+
+/// The length of the sweep of the stimulus.
+pub const LATENCY_SWEEP_SECONDS: f64 = 0.5;
+
+/// The lowest frequency of the sweep, the highest is 0.45 fs, at most 20 kHz.
+pub const LATENCY_SWEEP_START: f64 = 100.0;
+
+/// The lowest normalized correlation of a valid measurement.
+pub const MIN_LATENCY_CORRELATION: f64 = 0.5;
+
+/// A measurement of the latency.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyMeasurement {
+    /// The latency, with the fraction of a sample.
+    pub delay_samples: f64,
+    pub delay_ms:      f64,
+    /// The normalized correlation at the latency, in [0, 1].
+    pub correlation:   f64,
+    /// The recording has the inverted polarity of the stimulus.
+    pub is_inverted:   bool,
+}
+
+/// The measurements of the runs of run().
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyReport {
+    pub measurements: Vec<LatencyMeasurement>,
+}
+
+impl LatencyReport {
+    pub fn get_mean_ms(& self) -> f64 {
+        self.measurements.iter().map(|measurement| measurement.delay_ms).sum::<f64>() / self.measurements.len() as f64
+    }
+
+    pub fn get_min_ms(& self) -> f64 {
+        self.measurements.iter().map(|measurement| measurement.delay_ms).fold(f64::INFINITY, f64::min)
+    }
+
+    pub fn get_max_ms(& self) -> f64 {
+        self.measurements.iter().map(|measurement| measurement.delay_ms).fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// The largest difference of the latency between the runs.
+    pub fn get_jitter_ms(& self) -> f64 {
+        self.get_max_ms() - self.get_min_ms()
+    }
+}
+
+/// The latency tester of a sample rate.
+///
+/// In Rust:
+///    ```text
+///    >>> let tester = LatencyTester::new(48_000, 500.0);
+///    >>> let report = tester.run(5, |stimulus| device.play_and_record(stimulus))?;
+///    >>> println!("round trip {:.2} ms, jitter {:.3} ms", report.get_mean_ms(), report.get_jitter_ms());
+///    ```
+///
+#[derive(Debug, Clone)]
+pub struct LatencyTester {
+    sample_rate:         u32,
+    // The sweep followed by the silence.
+    stimulus:            Vec<f64>,
+    sweep_len:           usize,
+    max_latency_samples: usize,
+}
+
+impl LatencyTester {
+    /// The tester of the latencies up to max_latency_ms.
+    pub fn new(sample_rate: u32, max_latency_ms: f64) -> Self {
+        let f_end = f64::min(20_000.0, 0.45 * sample_rate as f64);
+        let mut stimulus = make_log_sweep(LATENCY_SWEEP_START, f_end, LATENCY_SWEEP_SECONDS, sample_rate);
+        let sweep_len = stimulus.len();
+        let max_latency_samples = (max_latency_ms / 1_000.0 * sample_rate as f64).ceil() as usize;
+        stimulus.resize(sweep_len + max_latency_samples, 0.0);
+        LatencyTester { sample_rate, stimulus, sweep_len, max_latency_samples }
+    }
+
+    pub fn get_sample_rate(& self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The signal to play, the sweep and the silence for the latency.
+    pub fn get_stimulus(& self) -> & [f64] {
+        & self.stimulus
+    }
+
+    /// The latency of a recording of the stimulus, from the start of its playback.
+    pub fn analyze(& self, recording: & [f64]) -> Result<LatencyMeasurement, String> {
+        let _span = trace_span!("latency_analyze", samples = recording.len());
+        let sweep = & self.stimulus[..self.sweep_len];
+        let inverted: Vec<f64> = recording.iter().map(|x| -x).collect();
+        let (lag, correlation, is_inverted) = [(recording, false), (inverted.as_slice(), true)].iter()
+            .map(|(signal, is_inverted)| {
+                let lag = estimate_delay(sweep, signal, self.max_latency_samples);
+                (lag, normalized_correlation(sweep, signal, lag), *is_inverted)
+            })
+            .fold((0, f64::NEG_INFINITY, false), |best, candidate| if candidate.1 > best.1 { candidate } else { best });
+        if correlation < MIN_LATENCY_CORRELATION {
+            return Err(format!("Error: the stimulus isn't in the recording, the correlation is {:.3}, check the \
+                               loopback and the levels", correlation));
+        }
+        let lag = lag as usize;
+        let signal = if is_inverted { inverted.as_slice() } else { recording };
+        let delay_samples = lag as f64 + peak_offset(sweep, signal, lag);
+        trace_event!(delay_samples, correlation, is_inverted, "latency measured");
+        Ok(LatencyMeasurement { delay_samples, delay_ms: delay_samples / self.sample_rate as f64 * 1_000.0,
+                                correlation, is_inverted })
+    }
+
+    /// Plays and records the stimulus with the function of the application, the
+    /// repetitions times.
+    pub fn run<F>(& self, repetitions: usize, mut play_and_record: F) -> Result<LatencyReport, String>
+            where F: FnMut(& [f64]) -> Result<Vec<f64>, String> {
+        if repetitions == 0 {
+            return Err("Error: the latency test needs at least one run".to_string());
+        }
+        let measurements = (0..repetitions)
+            .map(|_| self.analyze(& play_and_record(& self.stimulus)?))
+            .collect::<Result<Vec<LatencyMeasurement>, String>>()?;
+        Ok(LatencyReport { measurements })
+    }
+}
+
+// sum_n reference[n] signal[n + lag], over the samples of the signal.
+fn correlation_at(reference: & [f64], signal: & [f64], lag: isize) -> f64 {
+    reference.iter().enumerate()
+        .filter_map(|(n, x)| usize::try_from(n as isize + lag).ok().and_then(|m| signal.get(m)).map(|y| x * y))
+        .sum()
+}
+
+// The correlation at the lag, normalized by the energies of the reference and of
+// the signal under it, 0 for a negative lag.
+fn normalized_correlation(reference: & [f64], signal: & [f64], lag: isize) -> f64 {
+    if lag < 0 {
+        return 0.0;
+    }
+    let start = (lag as usize).min(signal.len());
+    let end = (start + reference.len()).min(signal.len());
+    let energy = |samples: & [f64]| samples.iter().map(|x| x * x).sum::<f64>();
+    let norm = f64::sqrt(energy(reference) * energy(& signal[start..end]));
+    if norm == 0.0 { 0.0 } else { correlation_at(reference, signal, lag) / norm }
+}
+
+// The fraction of a sample of the peak, of the parabola through the correlation at
+// the lag and its neighbours, in [-0.5, 0.5].
+fn peak_offset(reference: & [f64], signal: & [f64], lag: usize) -> f64 {
+    let lag = lag as isize;
+    let [before, peak, after] = [lag - 1, lag, lag + 1].map(|l| correlation_at(reference, signal, l));
+    let curvature = before - 2.0 * peak + after;
+    if curvature >= 0.0 {
+        return 0.0;
+    }
+    (0.5 * (before - after) / curvature).clamp(-0.5, 0.5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::make_white_noise;
+
+    // The loopback of a device, the stimulus late by the latency, through a gain,
+    // with noise.
+    fn make_loopback(stimulus: & [f64], latency: usize, gain: f64, seed: u32) -> Vec<f64> {
+        let mut recording = vec![0.0; latency];
+        recording.extend_from_slice(& stimulus[..stimulus.len() - latency]);
+        let noise = make_white_noise(recording.len(), 0.005, seed);
+        recording.iter().zip(noise).map(|(x, noise)| gain * x + noise).collect()
+    }
+
+    #[test]
+    fn test_loopback_latency() {
+        let tester = LatencyTester::new(48_000, 200.0);
+        assert_eq!(tester.get_stimulus().len(), 24_000 + 9_600);
+        let mut runs = [480, 481, 480].into_iter();
+        let mut seed = 0;
+        let report = tester.run(3, |stimulus| {
+            seed += 1;
+            Ok(make_loopback(stimulus, runs.next().unwrap(), 0.3, seed))
+        }).unwrap();
+        for (measurement, latency) in report.measurements.iter().zip([480.0, 481.0, 480.0]) {
+            assert!((measurement.delay_samples - latency).abs() < 0.05, "{:?}", measurement);
+            assert!(measurement.correlation > 0.9 && !measurement.is_inverted);
+        }
+        assert!((report.get_mean_ms() - 10.0 - 1.0 / 144.0).abs() < 1e-3);
+        assert!((report.get_jitter_ms() - 1.0 / 48.0).abs() < 2e-3);
+
+        // An inverted input, and no cable, only the noise.
+        let inverted = tester.analyze(& make_loopback(tester.get_stimulus(), 1_000, -0.5, 7)).unwrap();
+        assert!(inverted.is_inverted && (inverted.delay_samples - 1_000.0).abs() < 0.05);
+        assert!(tester.analyze(& make_loopback(tester.get_stimulus(), 1_000, 0.0, 7)).is_err());
+        assert!(tester.run(0, |stimulus| Ok(stimulus.to_vec())).is_err());
+    }
+
+    #[test]
+    fn test_fraction_of_a_sample() {
+        // The sweep of a sample rate 10 times higher, decimated with an offset of
+        // 4 samples, is the stimulus late by 0.4 samples.
+        let tester = LatencyTester::new(48_000, 100.0);
+        let fine = make_log_sweep(LATENCY_SWEEP_START, 20_000.0, LATENCY_SWEEP_SECONDS, 480_000);
+        let latency = 100;
+        let recording: Vec<f64> = (0..tester.get_stimulus().len())
+            .map(|m| {
+                let index = 10 * m as isize - 10 * latency - 4;
+                if index < 0 { 0.0 } else { fine.get(index as usize).copied().unwrap_or(0.0) }
+            })
+            .collect();
+        let measurement = tester.analyze(& recording).unwrap();
+        assert!((measurement.delay_samples - 100.4).abs() < 0.1, "{:?}", measurement);
+    }
+}
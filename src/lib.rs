@@ -0,0 +1,168 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: The library of the audio filters.
+//!              The filters, the equalizers, the analysis, the file I/O and the
+//!              plots are in the modules of the crate, and the core of it, the
+//!              IIRFilter, of f64 or f32, the ProcessingBlock trait, the make_*
+//!              constructors of the biquads, the Equalizer and the response plots,
+//!              is also at the root, for a crate that uses this one as a dependency:
+//!
+//!                 [dependencies]
+//!                 audio_filters_in_rust = "0.1"
+//!
+//!              In Rust:
+//!                 ```text
+//!                 >>> use audio_filters_in_rust::{make_lowpass, ProcessingBlock};
+//!                 >>> let mut filter = make_lowpass(1_000.0, 48_000, None);
+//!                 >>> let output: Vec<f64> = input.iter().map(|x| filter.process(*x)).collect();
+//!                 ```
+//!
+//!              The demo that generates the plots and the command line subcommands,
+//!              see cli.rs, are the binary of the crate, main.rs .
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+// Module definition
+#[macro_use]
+mod trace;
+pub mod iir_filter;
+pub mod butterworth_filter;
+pub mod show_response;
+pub mod equalizer;
+pub mod cli;
+pub mod channel_layout;
+pub mod delay_line;
+pub mod crossfeed;
+pub mod convolution;
+pub mod hrtf;
+pub mod equal_loudness;
+pub mod bass_enhancer;
+pub mod parametric_eq;
+pub mod eq_profile;
+pub mod coefficient_export;
+pub mod audio_buffer;
+pub mod audio_io;
+pub mod analysis;
+pub mod latency;
+pub mod audio_diff;
+pub mod time_varying;
+pub mod smoothed_biquad;
+pub mod loudspeaker;
+pub mod generators;
+pub mod loudness;
+pub mod diagnostics;
+pub mod ring_buffer;
+pub mod event_scheduler;
+pub mod filter_morph;
+pub mod snapshot;
+pub mod ab_compare;
+pub mod response_mask;
+pub mod filter_order;
+pub mod smoothing;
+pub mod feedback_suppressor;
+pub mod vad;
+pub mod effects;
+pub mod dynamics;
+pub mod crossover;
+pub mod dither;
+pub mod mastering;
+pub mod impulse_response;
+pub mod design_policy;
+pub mod exact_math;
+pub mod quantization;
+pub mod roundoff_noise;
+pub mod automation;
+pub mod gain_meter;
+pub mod clipper;
+pub mod replaygain;
+pub mod wav_metadata;
+pub mod convert;
+pub mod preview;
+pub mod multichannel;
+pub mod multichannel_filter;
+pub mod dtmf;
+pub mod biquad_calculator;
+pub mod design_report;
+pub mod filter_display;
+pub mod sensitivity;
+pub mod oscillator;
+pub mod chirp_z;
+pub mod welch;
+pub mod rate_policy;
+pub mod filter_spec;
+pub mod note_names;
+pub mod iso_bands;
+pub mod eq_match;
+pub mod spectral_tilt;
+pub mod signal;
+pub mod fixtures;
+pub mod album;
+pub mod watch;
+pub mod progress;
+pub mod processing_chain;
+pub mod precision;
+pub mod parallel_bank;
+pub mod loop_region;
+pub mod biquad_tdf2;
+pub mod simd_kernels;
+pub mod biquad;
+pub mod svf;
+#[cfg(feature = "dasp")]
+pub mod dasp_interop;
+#[cfg(feature = "fundsp")]
+pub mod fundsp_interop;
+#[cfg(feature = "symphonia")]
+pub mod symphonia_source;
+
+#[cfg(test)]
+mod golden_tests;
+#[cfg(test)]
+mod wav_round_trip_tests;
+
+// The core of the crate at the root.
+pub use crate::iir_filter::ProcessingBlock; // Trait
+pub use crate::iir_filter::IIRFilter;
+pub use crate::iir_filter::FilterChain;
+pub use crate::biquad_tdf2::BiquadTDF2;
+pub use crate::biquad::Biquad;
+pub use crate::svf::StateVariableFilter;
+pub use crate::processing_chain::ProcessingChain;
+pub use crate::parallel_bank::ParallelBank;
+pub use crate::iir_filter::Float; // Trait
+pub use crate::iir_filter::ProcessingBlockExt; // Trait
+pub use crate::signal::ProcessSignal; // Trait
+pub use crate::signal::process_signal;
+pub use crate::signal::lfilter;
+pub use crate::signal::lfilter_with_state;
+pub use crate::signal::lfilter_zi;
+pub use crate::butterworth_filter::make_lowpass;
+pub use crate::butterworth_filter::make_highpass;
+pub use crate::butterworth_filter::make_bandpass;
+pub use crate::butterworth_filter::make_allpass;
+pub use crate::butterworth_filter::make_peak;
+pub use crate::butterworth_filter::make_peak_eq_constant_q;
+pub use crate::butterworth_filter::make_lowshelf;
+pub use crate::butterworth_filter::make_highshelf;
+pub use crate::butterworth_filter::make_notch;
+pub use crate::butterworth_filter::make_bandstop;
+pub use crate::butterworth_filter::make_bandpass_bw;
+pub use crate::butterworth_filter::make_butterworth_lowpass;
+pub use crate::butterworth_filter::make_butterworth_highpass;
+pub use crate::butterworth_filter::make_bessel_lowpass;
+pub use crate::butterworth_filter::make_bessel_highpass;
+pub use crate::butterworth_filter::make_linkwitz_riley_lowpass;
+pub use crate::butterworth_filter::make_linkwitz_riley_highpass;
+pub use crate::equalizer::Equalizer;
+pub use crate::audio_buffer::AudioBuffer;
+pub use crate::multichannel_filter::MultiChannelFilter;
+pub use crate::show_response::show_frequency_response;
+pub use crate::show_response::show_phase_response;
+pub use crate::show_response::show_spectrogram;
+pub use crate::show_response::show_static_curve;
+pub use crate::show_response::render_response_ascii;
@@ -0,0 +1,217 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Processing of the sampled instruments with the loops of the WAV smpl
+//!              chunk, see wav_metadata.rs, without a click at the wrap of a loop.
+//!              A sampler plays the attack and then the loop, from the sample start
+//!              to the sample end included, again and again:
+//!
+//!                 [ attack | start ======== end | release ]
+//!                            ^-----------------'
+//!
+//!              A filter run once through the file leaves the loop with the state
+//!              of the attack, and its output at the end of the loop doesn't join
+//!              its output at the start, there is a step at each wrap, a click. The
+//!              loop is processed as the periodic signal that is played, the loop
+//!              through the processor pass after pass, from the state of the
+//!              attack, until the output of a pass is the same as the one of the
+//!              pass before, and that last pass is the loop of the output. It is
+//!              the steady state of the filter for the loop played forever, the
+//!              wrap of the output is the one of a filter running in the sampler,
+//!              sample exact. The release goes on from the state of the end of
+//!              the loop.
+//!              The change from the attack to the loop is the transient of the
+//!              state of the attack, that decays like the output of a preview
+//!              without its pre-roll, see preview.rs.
+//!              Only the forward loops are processed pass after pass, the
+//!              alternating and the backward loops play the same samples at the
+//!              turn, without a step, and are processed once. The loops are in
+//!              the order of the file and don't overlap.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Multimedia Programming Interface and Data Specifications 1.0, IBM and
+//!       Microsoft, the smpl chunk.
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::audio_buffer::AudioBuffer;
+use crate::wav_metadata::SampleLoop;
+use crate::wav_metadata::read_wav_with_metadata;
+use crate::wav_metadata::write_wav_with_metadata;
+
+
+/// The loop_type of a forward loop in the smpl chunk.
+pub const FORWARD_LOOP_TYPE: u32 = 0;
+
+/// The largest difference of the output of two passes of a loop, of the steady state.
+pub const LOOP_TOLERANCE: f64 = 1e-12;
+
+/// The most passes of a loop, for the processors that don't reach a steady state,
+/// like an LFO out of the period of the loop, the last pass is the loop of the output.
+pub const MAX_LOOP_PASSES: usize = 1_000;
+
+/// Processes each channel with a new processor, with the forward loops processed
+/// to their steady state. The factory is called with the index of the channel.
+///
+/// In Rust:
+///    ```text
+///    >>> let loops = metadata.sampler.as_ref().map(|sampler| sampler.loops.clone()).unwrap_or_default();
+///    >>> let output = process_loops(& audio, & loops, |_| make_lowpass(2_000.0, audio.sample_rate, None))?;
+///    ```
+///
+pub fn process_loops<B, M>(audio: & AudioBuffer, loops: & [SampleLoop], mut make_processor: M)
+        -> Result<AudioBuffer, String>
+        where B: ProcessingBlock, M: FnMut(usize) -> B {
+    let regions = loop_regions(loops, audio.get_num_frames())?;
+    let _span = trace_span!("process_loops", num_loops = regions.len());
+
+    let channels = audio.channels.iter()
+        .enumerate()
+        .map(|(index, channel)| {
+            let mut processor = make_processor(index);
+            let mut output = Vec::with_capacity(channel.len());
+            let mut position = 0;
+            for & (start, end) in & regions {
+                output.extend(channel[position..start].iter().map(|x| processor.process(*x)));
+                output.extend(process_steady_state(& channel[start..end], & mut processor));
+                position = end;
+            }
+            output.extend(channel[position..].iter().map(|x| processor.process(*x)));
+            output
+        })
+        .collect();
+    Ok(audio.with_channels(audio.sample_rate, channels))
+}
+
+/// Processes a WAV file with the loops of its smpl chunk, the output has the sample
+/// format and the metadata of the input. Returns the number of the forward loops.
+pub fn process_loop_file<B, M>(input_path: & str, output_path: & str, make_processor: M) -> Result<usize, String>
+        where B: ProcessingBlock, M: FnMut(usize) -> B {
+    let (audio, sample_format, metadata) = read_wav_with_metadata(input_path)?;
+    let loops = metadata.sampler.as_ref().map(|sampler| sampler.loops.clone()).unwrap_or_default();
+    let output = process_loops(& audio, & loops, make_processor)?;
+    write_wav_with_metadata(output_path, & output, sample_format.get_writable(), & metadata)?;
+    Ok(loop_regions(& loops, audio.get_num_frames())?.len())
+}
+
+// The frames of the forward loops, from the start to the end excluded, checked and
+// in order.
+fn loop_regions(loops: & [SampleLoop], num_frames: usize) -> Result<Vec<(usize, usize)>, String> {
+    let mut regions: Vec<(usize, usize)> = Vec::new();
+    for sample_loop in loops {
+        let (start, end) = (sample_loop.start as usize, sample_loop.end as usize + 1);
+        if start >= end || end > num_frames {
+            return Err(format!("Error: the loop from {} to {} is out of the {} frames of the audio",
+                               sample_loop.start, sample_loop.end, num_frames));
+        }
+        if sample_loop.loop_type == FORWARD_LOOP_TYPE {
+            regions.push((start, end));
+        }
+    }
+    regions.sort_unstable();
+    if let Some(pair) = regions.windows(2).find(|pair| pair[1].0 < pair[0].1) {
+        return Err(format!("Error: the loops from {} to {} and from {} to {} overlap", pair[0].0, pair[0].1 - 1,
+                           pair[1].0, pair[1].1 - 1));
+    }
+    Ok(regions)
+}
+
+// The loop through the processor pass after pass, to the steady state.
+fn process_steady_state<B: ProcessingBlock>(region: & [f64], processor: & mut B) -> Vec<f64> {
+    let mut output: Vec<f64> = region.iter().map(|x| processor.process(*x)).collect();
+    for _ in 1..MAX_LOOP_PASSES {
+        let mut difference: f64 = 0.0;
+        for (x, y) in region.iter().zip(output.iter_mut()) {
+            let next = processor.process(*x);
+            difference = difference.max((next - *y).abs());
+            *y = next;
+        }
+        if difference <= LOOP_TOLERANCE {
+            break;
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_io::SampleFormat;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::wav_metadata::SamplerChunk;
+    use crate::wav_metadata::WavMetadata;
+    use crate::generators::make_white_noise;
+    use crate::fixtures::temp_test_path;
+
+    fn make_loop(start: u32, end: u32, loop_type: u32) -> SampleLoop {
+        SampleLoop { cue_point_id: 0, loop_type, start, end, fraction: 0, play_count: 0 }
+    }
+
+    #[test]
+    fn test_loop_wraps_without_a_step() {
+        let channel = make_white_noise(3_000, 0.5, 7);
+        let audio = AudioBuffer::new(48_000, vec![channel.clone()]);
+        let (start, end) = (1_000, 1_299);
+        let make = |_| make_lowpass(200.0, 48_000, None);
+        let output = process_loops(& audio, & [make_loop(start, end, 0)], make).unwrap();
+        let output = & output.channels[0];
+        let region = & channel[1_000..1_300];
+
+        // The output loop, played again and again, is the filter running on the
+        // input loop played again and again.
+        let mut filter = make_lowpass(200.0, 48_000, None);
+        let played: Vec<f64> = region.iter().cycle().take(300 * 200).map(|x| filter.process(*x)).collect();
+        for (x, y) in output[1_000..1_300].iter().cycle().zip(& played[300 * 198..]) {
+            assert!((x - y).abs() < 1e-9);
+        }
+        // The release goes on from the end of the loop.
+        let mut release: Vec<f64> = channel[1_300..].to_vec();
+        filter.process_inplace(& mut release);
+        assert!(output[1_300..].iter().zip(& release).all(|(x, y)| (x - y).abs() < 1e-9));
+        // The attack is the same as a plain render, that has a step at the wrap.
+        let mut plain = channel.clone();
+        make_lowpass(200.0, 48_000, None).process_inplace(& mut plain);
+        assert_eq!(output[..1_000], plain[..1_000]);
+        let steady = & played[300 * 199..];
+        let wrap_error = |y: & [f64]| ((y[1_000] - y[1_299]) - (steady[0] - steady[299])).abs();
+        assert!(wrap_error(output) < 1e-9);
+        assert!(wrap_error(& plain) > 1e-4);
+
+        // The alternating loops are processed once.
+        let alternating = process_loops(& audio, & [make_loop(start, end, 1)], make).unwrap();
+        assert_eq!(alternating.channels[0], plain);
+        assert!(process_loops(& audio, & [make_loop(2_000, 3_000, 0)], make).is_err());
+        assert!(process_loops(& audio, & [make_loop(500, 400, 0)], make).is_err());
+        assert!(process_loops(& audio, & [make_loop(100, 200, 0), make_loop(200, 300, 0)], make).is_err());
+    }
+
+    #[test]
+    fn test_loop_file() {
+        let (input_path, output_path) = (& temp_test_path("loop_input.wav"), & temp_test_path("loop_output.wav"));
+        let audio = AudioBuffer::new(44_100, vec![make_white_noise(2_000, 0.5, 1), make_white_noise(2_000, 0.5, 2)]);
+        let mut metadata = WavMetadata::new();
+        metadata.sampler = Some(SamplerChunk { manufacturer: 0, product: 0, sample_period: 22_676, midi_unity_note: 60,
+                                               midi_pitch_fraction: 0, smpte_format: 0, smpte_offset: 0,
+                                               loops: vec![make_loop(500, 899, 0), make_loop(1_000, 1_499, 0)],
+                                               sampler_data: Vec::new() });
+        write_wav_with_metadata(input_path, & audio, SampleFormat::Float32, & metadata).unwrap();
+
+        let make = |_| make_lowpass(1_000.0, 44_100, None);
+        assert_eq!(process_loop_file(input_path, output_path, make).unwrap(), 2);
+        let (output, sample_format, output_metadata) = read_wav_with_metadata(output_path).unwrap();
+        assert_eq!((sample_format, output_metadata), (SampleFormat::Float32, metadata.clone()));
+        let (input, _, _) = read_wav_with_metadata(input_path).unwrap();
+        let expected = process_loops(& input, & metadata.sampler.unwrap().loops, make).unwrap();
+        for (channel, expected) in output.channels.iter().zip(& expected.channels) {
+            assert!(channel.iter().zip(expected).all(|(x, y)| (x - y).abs() < 1e-6));
+        }
+        let _ = std::fs::remove_file(input_path);
+        let _ = std::fs::remove_file(output_path);
+    }
+}
@@ -0,0 +1,250 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Loudness and true peak measurement, ITU-R BS.1770.
+//!              The loudness in LUFS is the gated mean square of the K-weighted
+//!              channels. The K-weighting is a cascade of two biquads, a high shelf
+//!              of +4 dB above 1.5 kHz, that models the head, and a high-pass at
+//!              38 Hz (the RLB curve). The coefficients are computed for any sample
+//!              rate from the analog prototypes of the 48 kHz filters of the norm.
+//!
+//!              The signal is cut in blocks of 400 ms with 75 % of overlap, and the
+//!              integrated loudness only uses the blocks above -70 LUFS (absolute
+//!              gate) and above the loudness of those blocks minus 10 LU (relative
+//!              gate), so the silences and the quiet parts don't lower it.
+//!
+//!                 L = -0.691 + 10 log10( sum_i G_i * z_i )
+//!
+//!              z_i is the mean square of the channel i and G_i is the channel weight,
+//!              1.0 for the front channels, 1.41 for the surrounds and 0 for the LFE.
+//!
+//!              The true peak is the peak of the signal oversampled 4 times, it finds
+//!              the inter-sample peaks that the reconstruction filter of a DAC, or a
+//!              lossy encoder, makes above the largest sample.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. ITU-R BS.1770-4 - Algorithms to measure audio programme loudness and
+//!       true-peak audio level
+//!       https://www.itu.int/rec/R-REC-BS.1770
+//!
+//!    2. EBU R 128 - Loudness normalisation and permitted maximum level of audio signals
+//!       https://tech.ebu.ch/publications/r128
+//!
+
+
+use std::f64::consts::PI;
+
+use crate::iir_filter::IIRFilter;
+use crate::exact_math;
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::audio_buffer::AudioBuffer;
+use crate::channel_layout::ChannelLayout;
+use crate::channel_layout::ChannelRole;
+
+
+const BLOCK_SECONDS: f64 = 0.4;
+// 75 % of overlap between the gating blocks.
+const BLOCK_STEP_SECONDS: f64 = 0.1;
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = -10.0;
+
+/// Oversampling factor of the true peak measurement.
+pub const TRUE_PEAK_OVERSAMPLING: usize = 4;
+// Zero crossings of the interpolation filter on each side of the sample.
+const TRUE_PEAK_HALF_TAPS: isize = 16;
+
+/// The two biquads of the K-weighting, the high shelf and the RLB high-pass.
+pub fn make_k_weighting(sample_rate: u32) -> [IIRFilter; 2] {
+    let sample_rate = sample_rate as f64;
+
+    // Stage 1, the high shelf.
+    let (gain_db, frequency, q_factor) = (3.999_843_853_973_347, 1_681.974_450_955_533, 0.707_175_236_955_419_6);
+    let k = exact_math::tan(PI * frequency / sample_rate);
+    let vh = exact_math::powf(10.0, gain_db / 20.0);
+    let vb = exact_math::powf(vh, 0.499_666_774_154_541_6);
+    let a0 = 1.0 + k / q_factor + k * k;
+    let mut shelf = IIRFilter::new(2);
+    shelf.set_coefficients(& [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q_factor + k * k) / a0],
+                           & [(vh + vb * k / q_factor + k * k) / a0,
+                              2.0 * (k * k - vh) / a0,
+                              (vh - vb * k / q_factor + k * k) / a0]).unwrap();
+
+    // Stage 2, the RLB high-pass.
+    let (frequency, q_factor) = (38.135_470_876_024_44, 0.500_327_037_323_877_3);
+    let k = exact_math::tan(PI * frequency / sample_rate);
+    let a0 = 1.0 + k / q_factor + k * k;
+    let mut highpass = IIRFilter::new(2);
+    highpass.set_coefficients(& [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q_factor + k * k) / a0],
+                              & [1.0, -2.0, 1.0]).unwrap();
+
+    [shelf, highpass]
+}
+
+/// Weight of each channel in the loudness sum, from the default layout of the
+/// number of channels.
+pub fn channel_weights(num_channels: usize) -> Vec<f64> {
+    ChannelLayout::from_channel_count(num_channels).roles().iter()
+        .map(|role| match role {
+            ChannelRole::Lfe => 0.0,
+            ChannelRole::SurroundLeft | ChannelRole::SurroundRight => 1.41,
+            _ => 1.0,
+        })
+        .collect()
+}
+
+/// Integrated loudness in LUFS, -inf when the audio is shorter than one block of
+/// 400 ms or all of it is below the absolute gate.
+///
+/// In Rust:
+///    ```text
+///    >>> let (audio, _) = read_wav("music.wav")?;
+///    >>> // EBU R 128 broadcast target is -23 LUFS.
+///    >>> let gain_db = -23.0 - integrated_loudness(& audio);
+///    ```
+///
+pub fn integrated_loudness(audio: & AudioBuffer) -> f64 {
+    gated_loudness(& gating_block_powers(audio))
+}
+
+/// The weighted sum of the mean squares of the K-weighted channels, of each gating
+/// block of 400 ms. The blocks of several files together give the loudness of the
+/// whole, like an album, with gated_loudness().
+pub fn gating_block_powers(audio: & AudioBuffer) -> Vec<f64> {
+    let sample_rate = audio.sample_rate as f64;
+    let block_length = (BLOCK_SECONDS * sample_rate).round() as usize;
+    let step = (BLOCK_STEP_SECONDS * sample_rate).round() as usize;
+    let num_frames = audio.get_num_frames();
+    if num_frames < block_length {
+        return Vec::new();
+    }
+    let num_blocks = (num_frames - block_length) / step + 1;
+
+    // Mean square of the K-weighted signal, of each block and channel.
+    let weights = channel_weights(audio.get_num_channels());
+    let mut block_powers = vec![0.0; num_blocks];
+    for (channel, weight) in audio.channels.iter().zip(& weights) {
+        let [mut shelf, mut highpass] = make_k_weighting(audio.sample_rate);
+        let weighted: Vec<f64> = channel.iter().map(|x| highpass.process(shelf.process(*x))).collect();
+        for (j, power) in block_powers.iter_mut().enumerate() {
+            *power += weight * weighted[j * step..j * step + block_length].iter().map(|x| x * x).sum::<f64>()
+                / block_length as f64;
+        }
+    }
+    block_powers
+}
+
+/// The loudness in LUFS of the gating blocks, with the absolute and the relative
+/// gates, -inf without blocks above the absolute gate.
+pub fn gated_loudness(block_powers: & [f64]) -> f64 {
+    let loudness_of = |powers: & [f64]| -> f64 {
+        if powers.is_empty() {
+            return f64::NEG_INFINITY;
+        }
+        -0.691 + 10.0 * f64::log10(powers.iter().sum::<f64>() / powers.len() as f64)
+    };
+    let above_absolute: Vec<f64> = block_powers.iter().copied()
+        .filter(|power| loudness_of(& [*power]) > ABSOLUTE_GATE_LUFS)
+        .collect();
+    let relative_gate = loudness_of(& above_absolute) + RELATIVE_GATE_LU;
+    let gated: Vec<f64> = above_absolute.into_iter()
+        .filter(|power| loudness_of(& [*power]) > relative_gate)
+        .collect();
+    loudness_of(& gated)
+}
+
+// Windowed sinc interpolation filter of the fractional position of the phase.
+fn interpolation_taps(phase: usize) -> Vec<f64> {
+    let fraction = phase as f64 / TRUE_PEAK_OVERSAMPLING as f64;
+    (-TRUE_PEAK_HALF_TAPS + 1..=TRUE_PEAK_HALF_TAPS)
+        .map(|j| {
+            let t = j as f64 - fraction;
+            let window = 0.5 + 0.5 * exact_math::cos(PI * t / TRUE_PEAK_HALF_TAPS as f64);
+            let sinc = if t == 0.0 { 1.0 } else { exact_math::sin(PI * t) / (PI * t) };
+            sinc * window
+        })
+        .collect()
+}
+
+/// The signal oversampled 4 times, the sample n of the input is the sample 4 n
+/// of the output.
+pub fn oversample_for_true_peak(signal: & [f64]) -> Vec<f64> {
+    let phases: Vec<Vec<f64>> = (1..TRUE_PEAK_OVERSAMPLING).map(interpolation_taps).collect();
+    let sample = |index: isize| -> f64 {
+        if index < 0 { 0.0 } else { signal.get(index as usize).copied().unwrap_or(0.0) }
+    };
+    let mut output = Vec::with_capacity(signal.len() * TRUE_PEAK_OVERSAMPLING);
+    for n in 0..signal.len() as isize {
+        output.push(signal[n as usize]);
+        for taps in & phases {
+            // Interpolated value between the samples n and n + 1.
+            output.push(taps.iter().enumerate()
+                .map(|(i, tap)| tap * sample(n - TRUE_PEAK_HALF_TAPS + 1 + i as isize))
+                .sum());
+        }
+    }
+    output
+}
+
+/// True peak of the signal, linear.
+pub fn true_peak(signal: & [f64]) -> f64 {
+    oversample_for_true_peak(signal).iter().fold(0.0, |max, x| f64::max(max, x.abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::TAU;
+    use crate::analysis::peak;
+
+    #[test]
+    fn test_k_weighting_coefficients_at_48_khz() {
+        // The coefficients of the table of BS.1770.
+        let [shelf, highpass] = make_k_weighting(48_000);
+        let expected_b = [1.535_124_859_586_97, -2.691_696_189_406_38, 1.198_392_810_852_85];
+        let expected_a = [1.0, -1.690_659_293_182_41, 0.732_480_774_215_85];
+        for i in 0..3 {
+            assert!((shelf.b_coeffs[i] - expected_b[i]).abs() < 1e-9);
+            assert!((shelf.a_coeffs[i] - expected_a[i]).abs() < 1e-9);
+        }
+        assert!((highpass.a_coeffs[1] + 1.990_047_454_833_98).abs() < 1e-9);
+        assert!((highpass.a_coeffs[2] - 0.990_072_250_366_21).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_integrated_loudness_of_sine() {
+        // Stereo 1 kHz sine at -23 dBFS is -23 LUFS.
+        let sample_rate = 48_000;
+        let amplitude = f64::powf(10.0, -23.0 / 20.0);
+        let sine: Vec<f64> = (0..5 * sample_rate)
+            .map(|n| amplitude * f64::sin(TAU * 1_000.0 * n as f64 / sample_rate as f64))
+            .collect();
+        let audio = AudioBuffer::new(sample_rate as u32, vec![sine.clone(), sine.clone()]);
+        assert!((integrated_loudness(& audio) + 23.0).abs() < 0.05);
+
+        // The silence after the sine is gated out, only the blocks over the end of the
+        // sine lower the loudness a little. Without the gates it would be -26 LUFS.
+        let mut with_silence = sine.clone();
+        with_silence.extend(vec![0.0; 5 * sample_rate]);
+        let audio = AudioBuffer::new(sample_rate as u32, vec![with_silence.clone(), with_silence]);
+        assert!((integrated_loudness(& audio) + 23.0).abs() < 0.2);
+
+        let short = AudioBuffer::new(sample_rate as u32, vec![vec![0.5; 1_000]]);
+        assert_eq!(integrated_loudness(& short), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_true_peak_finds_inter_sample_peak() {
+        // A sine at fs / 4 sampled at 45 degrees has its samples at 0.707 of the peak.
+        let signal: Vec<f64> = (0..400).map(|n| f64::sin(TAU * n as f64 / 4.0 + PI / 4.0)).collect();
+        assert!((peak(& signal) - f64::sqrt(0.5)).abs() < 1e-12);
+        let oversampled = oversample_for_true_peak(& signal);
+        assert_eq!(oversampled.len(), 1_600);
+        // Away from the start and the end, where the sine is cut.
+        assert!((peak(& oversampled[400..1_200]) - 1.0).abs() < 0.005);
+    }
+}
@@ -0,0 +1,143 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Loudspeaker driver EQ, the Linkwitz transform.
+//!              A driver in a sealed box has a 2nd order high-pass response, with
+//!              the resonance f0 and quality factor Q0 of the box. The Linkwitz
+//!              transform is a biquad that cancels those two poles with its zeros
+//!              and puts new poles at the target fp and Qp. So the sealed box
+//!              plays lower, with the alignment that we choose, at the cost of a
+//!              bass boost of (f0 / fp)^2 that the amplifier and the driver
+//!              excursion must handle.
+//!
+//!                          s^2 + (w0 / Q0) s + w0^2
+//!                 H(s) = ----------------------------
+//!                          s^2 + (wp / Qp) s + wp^2
+//!
+//!              The analog filter is discretized with the bilinear transform, with
+//!              the frequency pre-warped at the geometric mean of f0 and fp.
+//!
+//!              The box f0 and Q0 come from the Thiele / Small parameters of the
+//!              driver (fs, Qts and Vas) and the box volume Vb:
+//!
+//!                 alpha = Vas / Vb,  fc = fs sqrt(1 + alpha),  Qtc = Qts sqrt(1 + alpha)
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Linkwitz, S. - Linkwitz Transform
+//!       https://www.linkwitzlab.com/filters.htm
+//!
+//!    2. Small, R. H. - Closed-Box Loudspeaker Systems, Part I: Analysis.
+//!       Journal of the Audio Engineering Society, 1972
+//!
+
+
+use std::f64::consts::PI;
+use std::f64::consts::TAU;
+
+use crate::iir_filter::IIRFilter;
+use crate::exact_math;
+
+
+/// Resonance frequency and quality factor (fc, Qtc) of a driver in a sealed box.
+/// The volumes can be in any unit, liters for example, but the same for both.
+pub fn sealed_box_alignment(fs: f64, qts: f64, vas: f64, vb: f64) -> (f64, f64) {
+    assert!(fs > 0.0 && qts > 0.0 && vas > 0.0 && vb > 0.0);
+    let alpha = vas / vb;
+    let factor = f64::sqrt(1.0 + alpha);
+    (fs * factor, qts * factor)
+}
+
+/// Box volume that gives the target Qtc, the inverse of sealed_box_alignment().
+/// The target must be above Qts.
+pub fn sealed_box_volume(qts: f64, vas: f64, qtc_target: f64) -> f64 {
+    assert!(qtc_target > qts);
+    vas / ((qtc_target / qts).powi(2) - 1.0)
+}
+
+/// Magnitude in dB of the analog 2nd order high-pass response of a sealed box.
+pub fn sealed_box_response_db(frequency: f64, fc: f64, qtc: f64) -> f64 {
+    let x = frequency / fc;
+    let magnitude = x * x / f64::sqrt((1.0 - x * x).powi(2) + (x / qtc).powi(2));
+    20.0 * f64::log10(magnitude)
+}
+
+/// Boost in dB of the Linkwitz transform at low frequencies, 40 log10(f0 / fp).
+pub fn linkwitz_transform_max_boost_db(f0: f64, f_target: f64) -> f64 {
+    40.0 * f64::log10(f0 / f_target)
+}
+
+/// Creates a Linkwitz transform biquad, that changes the sealed box response with
+/// resonance f0 and Q0 to the target resonance f_target and Q q_target.
+///
+/// In Rust:
+///    ```text
+///    >>> // Extend a sealed box with fc = 60 Hz and Qtc = 0.9 to 30 Hz and Q = 0.707 .
+///    >>> let mut filter = make_linkwitz_transform(60.0, 0.9, 30.0, 0.707, 48_000);
+///    >>> let output = filter.process(sample);
+///    ```
+///
+pub fn make_linkwitz_transform(f0: f64, q0: f64, f_target: f64, q_target: f64, sample_rate: u32) -> IIRFilter {
+    assert!(f0 > 0.0 && q0 > 0.0 && f_target > 0.0 && q_target > 0.0);
+    assert!(f0 < sample_rate as f64 / 2.0 && f_target < sample_rate as f64 / 2.0);
+    let w0 = TAU * f0;
+    let wp = TAU * f_target;
+    // Bilinear transform, pre-warped at the geometric mean of the two frequencies.
+    let fc = f64::sqrt(f0 * f_target);
+    let k = TAU * fc / exact_math::tan(PI * fc / sample_rate as f64);
+    let k2 = k * k;
+
+    let b0 = k2 + w0 / q0 * k + w0 * w0;
+    let b1 = 2.0 * (w0 * w0 - k2);
+    let b2 = k2 - w0 / q0 * k + w0 * w0;
+    let a0 = k2 + wp / q_target * k + wp * wp;
+    let a1 = 2.0 * (wp * wp - k2);
+    let a2 = k2 - wp / q_target * k + wp * wp;
+
+    let mut filter = IIRFilter::new(2);
+    filter.set_coefficients(& [1.0, a1 / a0, a2 / a0], & [b0 / a0, b1 / a0, b2 / a0]).unwrap();
+    filter
+}
+
+/// Linkwitz transform for a driver in a sealed box, from the Thiele / Small
+/// parameters, to the target resonance and Q.
+pub fn make_sealed_box_linkwitz_transform(fs: f64, qts: f64, vas: f64, vb: f64,
+                                          f_target: f64, q_target: f64, sample_rate: u32) -> IIRFilter {
+    let (fc, qtc) = sealed_box_alignment(fs, qts, vas, vb);
+    make_linkwitz_transform(fc, qtc, f_target, q_target, sample_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sealed_box_alignment() {
+        // Vas = 3 Vb doubles fs and Qts.
+        let (fc, qtc) = sealed_box_alignment(30.0, 0.35, 60.0, 20.0);
+        assert!((fc - 60.0).abs() < 1e-12);
+        assert!((qtc - 0.7).abs() < 1e-12);
+        assert!((sealed_box_volume(0.35, 60.0, 0.7) - 20.0).abs() < 1e-9);
+        assert!((sealed_box_response_db(60.0, 60.0, 0.7) - 20.0 * f64::log10(0.7)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_linkwitz_transform_extends_box() {
+        let sample_rate = 48_000;
+        let (f0, q0, fp, qp) = (60.0, 0.9, 30.0, 0.707);
+        let filter = make_linkwitz_transform(f0, q0, fp, qp, sample_rate);
+        let gain_db = |f: f64| 20.0 * f64::log10(filter.frequency_response(f, sample_rate).norm());
+        // The box with the transform has the target response.
+        for frequency in [10.0, 20.0, 30.0, 60.0, 100.0, 500.0] {
+            let combined = sealed_box_response_db(frequency, f0, q0) + gain_db(frequency);
+            let target = sealed_box_response_db(frequency, fp, qp);
+            assert!((combined - target).abs() < 0.05);
+        }
+        assert!((gain_db(1.0) - linkwitz_transform_max_boost_db(f0, fp)).abs() < 0.01);
+        assert!(gain_db(10_000.0).abs() < 0.01);
+    }
+}
@@ -1,130 +1,159 @@
-/// Project: Audio filters in Rust
-/// Date:    2021.12.05
-/// Author of the port: João Nuno Carvalho
-///
-/// Description: Audio or DSP filters, allow you to attenuate or accentuate some frequencies
-///              or range of frequencies in a signal. The signal can be of any kind, but in
-///              here, we will focus on 1D signals. Like audio signals.
-///              There can also occur differences in the signal phases, that vary with the
-///              filter and the frequency components of the signal.  
-///              This is a port of Audio filters, from Python to Rust,
-///              from the Audio filter from TheAlgorithms GitHub in Python. That is by it
-///              self a port from WebAudio API implementation of the same common
-///              filters in the browsers.
-/// 
-///              The following filters are implemented over a BiQuad IIR filter:
-///                 -low-pass
-///                 -high-pass
-///                 -band-pass
-///                 -all-pass
-///                 -peak
-///                 -low-shelf
-///                 -high-shelf 
-///                 -notch
-///                 -10 band equalizer
-///  
-/// License: MIT Open Source License, like the original license from
-///    GitHub - TheAlgorithms / Python / audio_filters
-///    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
-///
-/// How to run the code. 
-/// 
-/// To make a project for this files do:
-///     -Install Rust your computer (Linux, Win, Mac, Raspberry Pi).
-///     
-///     cargo new audio_filters_in_rust
-///     cd audio_filters_in_rust
-///     
-///     -Copy the repository files to this directory and overlap them.
-/// 
-/// To compile do:
-///     cargo build --release
-/// 
-/// To run do:
-///     cargo run --release
-/// 
-/// to run the tests do:
-///     cargo test
-/// 
-/// References:
-///    1. GitHub - TheAlgorithms / Python / audio_filters
-///       https://github.com/TheAlgorithms/Python/tree/master/audio_filters
-///
-///    2. WebAudio - Cookbook formulae for audio equalizer biquad filter coefficients
-///       https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html 
-/// 
-///    3. Good resources on DSP – Digital Signal Programming
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_electronics#dsp--digital-signal-programming
-///
-///    4. Biquads - EarLevel
-///       http://www.earlevel.com/main/2003/02/28/biquads/
-///
-///    5. Biquad C++ source code - EarLevel
-///       https://www.earlevel.com/main/2012/11/26/biquad-c-source-code/
-///
-///    6. A biquad calculator V3 - EarLevel
-///       https://www.earlevel.com/main/2021/09/02/biquad-calculator-v3/
-/// 
-///    7. WebAudio API - Mozilla Docs
-///       https://developer.mozilla.org/en-US/docs/Web/API/Web_Audio_API
-/// 
-///    8. Audio Filters - Theory and Practice
-///       by Ethan Winer
-///       http://ethanwiner.com/filters.html
-/// 
-///    9. Audio filter - Wikipedia
-///       https://en.wikipedia.org/wiki/Audio_filter
-/// 
-///   10. Electronic filter - Wikipedia
-///       https://en.wikipedia.org/wiki/Electronic_filter
-///
-///   11. How to learn modern Rust
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
-///
-/// 
-/// 10 Band Equalizer
-/// 
-///   12. Making an EQ from cascading filters
-///       https://dsp.stackexchange.com/questions/10309/making-an-eq-from-cascading-filters
-/// 
-///   13. PEAK/NOTCH FILTER DESIGN
-///       https://www.dsprelated.com/showcode/169.php
-/// 
-///   14. The Equivalence of Various Methods of Computing
-///       Biquad Coefficients for Audio Parametric Equalizers
-///       http://www.thesounddesign.com/MIO/EQ-Coefficients.pdf
-///
-///   15. How to learn modern Rust
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
-///
+//! Project: Audio filters in Rust
+//! Date:    2021.12.05
+//! Author of the port: João Nuno Carvalho
+//!
+//! Description: Audio or DSP filters, allow you to attenuate or accentuate some frequencies
+//!              or range of frequencies in a signal. The signal can be of any kind, but in
+//!              here, we will focus on 1D signals. Like audio signals.
+//!              There can also occur differences in the signal phases, that vary with the
+//!              filter and the frequency components of the signal.  
+//!              This is a port of Audio filters, from Python to Rust,
+//!              from the Audio filter from TheAlgorithms GitHub in Python. That is by it
+//!              self a port from WebAudio API implementation of the same common
+//!              filters in the browsers.
+//! 
+//!              The following filters are implemented over a BiQuad IIR filter:
+//!                 -low-pass
+//!                 -high-pass
+//!                 -band-pass
+//!                 -all-pass
+//!                 -peak
+//!                 -low-shelf
+//!                 -high-shelf 
+//!                 -notch
+//!                 -10 band equalizer
+//!  
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! How to run the code. 
+//! 
+//! To make a project for this files do:
+//!     -Install Rust your computer (Linux, Win, Mac, Raspberry Pi).
+//!     
+//!     cargo new audio_filters_in_rust
+//!     cd audio_filters_in_rust
+//!     
+//!     -Copy the repository files to this directory and overlap them.
+//! 
+//! To compile do:
+//!     cargo build --release
+//! 
+//! To run do:
+//!     cargo run --release
+//!
+//! To compare two audio files with a null test do:
+//!     cargo run --release -- diff reference.wav candidate.wav
+//! 
+//! To check a file for clipping, DC offset and loudness do:
+//!     cargo run --release -- analyze file.wav
+//! 
+//! To master a file to -14 LUFS and -1 dBTP do:
+//!     cargo run --release -- master mix.wav master.wav --lufs -14 --true-peak -1
+//! 
+//! To listen to 10 s of a file with an EQ profile, from 1 minute, do:
+//!     cargo run --release -- eq song.wav preview.wav ParametricEQ.txt --from 60 --to 70
+//! 
+//! To print the coefficients, poles, zeros and response of a biquad do:
+//!     cargo run --release -- coeffs peak 1000 --gain 6 --q 2 --sample-rate 48000
+//! 
+//! To write the ReplayGain tags of the tracks of an album do:
+//!     cargo run --release -- replaygain track1.wav track2.wav track3.wav
+//! 
+//! to run the tests do:
+//!     cargo test
+//! 
+//! To use the filters in another crate, see lib.rs .
+//! 
+//! References:
+//!    1. GitHub - TheAlgorithms / Python / audio_filters
+//!       https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//!    2. WebAudio - Cookbook formulae for audio equalizer biquad filter coefficients
+//!       https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html 
+//! 
+//!    3. Good resources on DSP – Digital Signal Programming
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_electronics#dsp--digital-signal-programming
+//!
+//!    4. Biquads - EarLevel
+//!       http://www.earlevel.com/main/2003/02/28/biquads/
+//!
+//!    5. Biquad C++ source code - EarLevel
+//!       https://www.earlevel.com/main/2012/11/26/biquad-c-source-code/
+//!
+//!    6. A biquad calculator V3 - EarLevel
+//!       https://www.earlevel.com/main/2021/09/02/biquad-calculator-v3/
+//! 
+//!    7. WebAudio API - Mozilla Docs
+//!       https://developer.mozilla.org/en-US/docs/Web/API/Web_Audio_API
+//! 
+//!    8. Audio Filters - Theory and Practice
+//!       by Ethan Winer
+//!       http://ethanwiner.com/filters.html
+//! 
+//!    9. Audio filter - Wikipedia
+//!       https://en.wikipedia.org/wiki/Audio_filter
+//! 
+//!   10. Electronic filter - Wikipedia
+//!       https://en.wikipedia.org/wiki/Electronic_filter
+//!
+//!   11. How to learn modern Rust
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
+//!
+//! 
+//! 10 Band Equalizer
+//! 
+//!   12. Making an EQ from cascading filters
+//!       https://dsp.stackexchange.com/questions/10309/making-an-eq-from-cascading-filters
+//! 
+//!   13. PEAK/NOTCH FILTER DESIGN
+//!       https://www.dsprelated.com/showcode/169.php
+//! 
+//!   14. The Equivalence of Various Methods of Computing
+//!       Biquad Coefficients for Audio Parametric Equalizers
+//!       http://www.thesounddesign.com/MIO/EQ-Coefficients.pdf
+//!
+//!   15. How to learn modern Rust
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
+//!
 
 
-// Module definition
-mod iir_filter;
-mod butterworth_filter;
-mod show_response;
-mod equalizer;
-
 // Imports
-use crate::iir_filter::ProcessingBlock;  // Trait
-use crate::iir_filter::IIRFilter;
-use crate::butterworth_filter::make_lowpass;
-use crate::butterworth_filter::make_highpass;
-use crate::butterworth_filter::make_bandpass;
-use crate::butterworth_filter::make_allpass;
-use crate::butterworth_filter::make_peak;
-use crate::butterworth_filter::make_peak_eq_constant_q;
-use crate::butterworth_filter::make_lowshelf;
-use crate::butterworth_filter::make_highshelf;
-use crate::butterworth_filter::make_notch;
+use audio_filters_in_rust::ProcessingBlock;  // Trait
+use audio_filters_in_rust::IIRFilter;
+use audio_filters_in_rust::make_lowpass;
+use audio_filters_in_rust::make_highpass;
+use audio_filters_in_rust::make_bandpass;
+use audio_filters_in_rust::make_allpass;
+use audio_filters_in_rust::make_peak;
+use audio_filters_in_rust::make_peak_eq_constant_q;
+use audio_filters_in_rust::make_lowshelf;
+use audio_filters_in_rust::make_highshelf;
+use audio_filters_in_rust::make_notch;
 
-use crate::show_response::show_frequency_response;
-use crate::show_response::show_phase_response;
+use audio_filters_in_rust::show_frequency_response;
+use audio_filters_in_rust::show_phase_response;
+use audio_filters_in_rust::show_static_curve;
+use audio_filters_in_rust::render_response_ascii;
 
-use crate::equalizer::Equalizer;
+use audio_filters_in_rust::Equalizer;
+use audio_filters_in_rust::iso_bands::BandWidth;
+use audio_filters_in_rust::dynamics::Compressor;
+use audio_filters_in_rust::cli;
 
 
 fn main() {
+    // With arguments, runs a command line subcommand, see cli.rs .
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        if let Err(error) = cli::run(& args) {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     println!("***************************");
     println!("** Audio filters in Rust **");
     println!("***************************");
@@ -135,6 +164,7 @@ fn main() {
     generate_plots();
     // generate_plot_equalizer_10_bands_01();
     generate_plot_equalizer_10_bands_02();
+    // generate_plot_equalizer_iso_31_bands();
 }
 
 fn test_a() {
@@ -233,7 +263,16 @@ fn generate_plots() {
     show_frequency_response(& mut filter, sample_rate as usize, "plots/notch_gain.svg", "notch");
     show_phase_response(& mut filter, sample_rate as usize, "plots/notch_phase.svg", "notch");
 
+    // compressor static curve
+    let mut compressor = Compressor::new(48_000, -20.0, 4.0, 5.0, 100.0);
+    compressor.set_knee_db(6.0);
+    show_static_curve(& compressor.get_static_curve(-60.0, 0.0, 241), "plots/compressor_static_curve.svg", "compressor");
+
     println!("\n ... ended generating the SVG plots.");
+
+    // The same kind of plot in the terminal.
+    let mut filter = make_peak(1_000.0, 48_000, 6.0, Some(2.0));
+    println!("\npeak 1000 Hz, gain 6 dB, Q 2\n{}", render_response_ascii(& mut filter, 48_000, 64, 9));
 }
 
 #[allow(dead_code)]
@@ -282,3 +321,18 @@ fn generate_plot_equalizer_10_bands_02() {
     show_frequency_response(& mut eq, sample_rate as usize, "plots/equalizer_10_band_gain.svg", "equ_10_bands");
     show_phase_response(& mut eq, sample_rate as usize, "plots/equalizer_10_band_phase.svg", "equ_10_bands");
 }
+
+#[allow(dead_code)]
+fn generate_plot_equalizer_iso_31_bands() {
+    println!("\n31 Band ISO third-octave Equalizer\n");
+    let sample_rate = 48_000;
+    let mut eq: Equalizer = Equalizer::make_equalizer_iso(sample_rate, BandWidth::ThirdOctave);
+    // A smiley curve, bass and treble up.
+    for i in 0..eq.get_num_bands() {
+        let gain_db = f64::min(6.0, 0.08 * (i as f64 - 15.0).powi(2) - 4.0);
+        let _= eq.set_band_gain(i, gain_db);
+        println!("{} Hz :  {:.1} dB", eq.get_bands_freq(i), eq.get_band_gain(i));
+    }
+    println!("\n");
+    show_frequency_response(& mut eq, sample_rate as usize, "plots/equalizer_iso_31_band_gain.svg", "equ_iso_31_bands");
+}
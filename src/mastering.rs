@@ -0,0 +1,313 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Mastering chain to a loudness and true peak target.
+//!              The chain puts together the blocks of this crate, in the order of
+//!              a mastering engineer:
+//!
+//!                 1. Detection: the diagnostics of the input, its loudness and a DC
+//!                    blocker when it has a DC offset.
+//!                 2. EQ: a parametric equalizer, by default a 25 Hz high-pass that
+//!                    removes the subsonic content that wastes headroom.
+//!                 3. Gain to the target loudness.
+//!                 4. Multiband compression: a Linkwitz-Riley crossover in three
+//!                    bands, each with a gentle 2:1 soft knee compressor a little
+//!                    below the target loudness, so a loud band doesn't pump the
+//!                    others.
+//!                 5. Limiter: a look-ahead limiter at the true peak ceiling.
+//!                 6. Dither: TPDF dither to the bit depth of the output.
+//!
+//!              The compressors and the limiter lower the loudness, and the limiter
+//!              limits the samples, not the peaks between them. So the chain runs
+//!              again with the gain corrected by the loudness error, and the ceiling
+//!              lowered by the true peak excess, until both targets are met. The
+//!              target is a loudness, not "as loud as possible": the chain doesn't
+//!              take part in the loudness war, with the platforms that normalize
+//!              to -14 LUFS there is no gain in going louder.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::audio_buffer::AudioBuffer;
+use crate::audio_io::SampleFormat;
+use crate::wav_metadata::read_wav_with_metadata;
+use crate::wav_metadata::write_wav_with_metadata;
+use crate::diagnostics::analyze_audio;
+use crate::diagnostics::make_dc_blocker;
+use crate::diagnostics::Correction;
+use crate::eq_profile::EqProfile;
+use crate::parametric_eq::ParametricBand;
+use crate::parametric_eq::BandType;
+use crate::crossover::CrossoverNetwork;
+use crate::dynamics::Compressor;
+use crate::dynamics::Limiter;
+use crate::dither::TpdfDither;
+use crate::loudness::integrated_loudness;
+use crate::loudness::true_peak;
+
+
+// The chain runs at most this number of times to meet the targets.
+const MAX_PASSES: usize = 6;
+const LOUDNESS_TOLERANCE_LU: f64 = 0.2;
+
+/// The targets and the settings of the chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MasteringSettings {
+    pub target_lufs:           f64,
+    pub true_peak_db:          f64,
+    pub eq:                    EqProfile,
+    pub crossover_frequencies: Vec<f64>,
+    /// Threshold of the band compressors, relative to the target loudness.
+    pub band_threshold_db:     f64,
+    pub band_ratio:            f64,
+    pub limiter_lookahead_ms:  f64,
+    pub limiter_release_ms:    f64,
+}
+
+impl MasteringSettings {
+    /// The default chain for the targets, like -14 LUFS and -1 dBTP of the
+    /// streaming platforms.
+    pub fn new(target_lufs: f64, true_peak_db: f64) -> Self {
+        MasteringSettings {
+            target_lufs,
+            true_peak_db,
+            eq: EqProfile { preamp_db: 0.0, bands: vec![ParametricBand::new(BandType::HighPass, 25.0, 0.0, 0.707)] },
+            crossover_frequencies: vec![200.0, 3_000.0],
+            band_threshold_db: -4.0,
+            band_ratio: 2.0,
+            limiter_lookahead_ms: 5.0,
+            limiter_release_ms: 80.0,
+        }
+    }
+}
+
+/// What the chain did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MasteringReport {
+    pub input_lufs:        f64,
+    pub output_lufs:       f64,
+    pub output_true_peak_db: f64,
+    pub gain_db:           f64,
+    pub limiter_ceiling_db: f64,
+    pub dc_blocker:        bool,
+    pub passes:            usize,
+}
+
+/// Masters the audio to the targets, the output isn't dithered.
+///
+/// In Rust:
+///    ```text
+///    >>> let (mastered, report) = master_audio(& mix, & MasteringSettings::new(-14.0, -1.0))?;
+///    >>> println!("{:.1} LUFS, {:.1} dBTP", report.output_lufs, report.output_true_peak_db);
+///    ```
+///
+pub fn master_audio(audio: & AudioBuffer, settings: & MasteringSettings) -> Result<(AudioBuffer, MasteringReport), String> {
+    let _span = trace_span!("master_audio", frames = audio.get_num_frames(), target_lufs = settings.target_lufs);
+    // Detection.
+    let diagnostics = analyze_audio(audio, SampleFormat::Float64);
+    let input_lufs = diagnostics.loudness_lufs;
+    if !input_lufs.is_finite() {
+        return Err("Error: can't master silence, or audio shorter than 400 ms".to_string());
+    }
+    let dc_blocker_cutoff = diagnostics.corrections.iter().find_map(|correction| match correction {
+        Correction::DcBlocker { cutoff_hz } => Some(*cutoff_hz),
+        _ => None,
+    });
+    settings.eq.to_parametric_eq(audio.sample_rate)?;
+    CrossoverNetwork::new(audio.sample_rate, & settings.crossover_frequencies)?;
+
+    let mut gain_db = settings.target_lufs - input_lufs;
+    let mut ceiling_db = settings.true_peak_db;
+    let mut passes = 0;
+    loop {
+        passes += 1;
+        let output = run_chain(audio, settings, dc_blocker_cutoff, gain_db, ceiling_db);
+        let output_lufs = integrated_loudness(& output);
+        let output_true_peak_db = output.channels.iter()
+            .map(|channel| 20.0 * f64::log10(true_peak(channel)))
+            .fold(f64::NEG_INFINITY, f64::max);
+        let loudness_error = settings.target_lufs - output_lufs;
+        let true_peak_excess = output_true_peak_db - settings.true_peak_db;
+        trace_event!(passes, output_lufs, output_true_peak_db, "mastering pass");
+        if (loudness_error.abs() <= LOUDNESS_TOLERANCE_LU && true_peak_excess <= 0.0) || passes == MAX_PASSES {
+            let report = MasteringReport {
+                input_lufs,
+                output_lufs,
+                output_true_peak_db,
+                gain_db,
+                limiter_ceiling_db: ceiling_db,
+                dc_blocker: dc_blocker_cutoff.is_some(),
+                passes,
+            };
+            return Ok((output, report));
+        }
+        gain_db += loudness_error;
+        if true_peak_excess > 0.0 {
+            // A small margin, the next pass has other peaks.
+            ceiling_db -= true_peak_excess + 0.05;
+        }
+    }
+}
+
+// One pass of the chain, without the dither.
+fn run_chain(audio: & AudioBuffer, settings: & MasteringSettings, dc_blocker_cutoff: Option<f64>,
+             gain_db: f64, ceiling_db: f64) -> AudioBuffer {
+    let sample_rate = audio.sample_rate;
+    let num_channels = audio.get_num_channels();
+    let num_frames = audio.get_num_frames();
+    let gain = f64::powf(10.0, gain_db / 20.0);
+
+    // Detection, EQ and gain, each channel on its own.
+    let channels: Vec<Vec<f64>> = audio.channels.iter().map(|channel| {
+        let mut dc_blocker = dc_blocker_cutoff.map(|cutoff| make_dc_blocker(cutoff, sample_rate));
+        let mut eq = settings.eq.to_parametric_eq(sample_rate).unwrap();
+        channel.iter().map(|x| {
+            let x = match & mut dc_blocker {
+                Some(filter) => filter.process(*x),
+                None => *x,
+            };
+            eq.process(x) * gain
+        }).collect()
+    }).collect();
+
+    // Multiband compression, the compressor of each band is linked across the channels.
+    let mut crossovers: Vec<CrossoverNetwork> = (0..num_channels)
+        .map(|_| CrossoverNetwork::new(sample_rate, & settings.crossover_frequencies).unwrap())
+        .collect();
+    let num_bands = crossovers[0].get_num_bands();
+    let mut compressors: Vec<Compressor> = (0..num_bands).map(|_| {
+        let mut compressor = Compressor::new(sample_rate, settings.target_lufs + settings.band_threshold_db,
+                                             settings.band_ratio, 10.0, 150.0);
+        compressor.set_knee_db(6.0);
+        compressor
+    }).collect();
+    // bands[band][channel]
+    let mut bands = vec![vec![0.0; num_channels]; num_bands];
+    let mut split = vec![0.0; num_bands];
+
+    // Limiter, its latency is removed at the end.
+    let mut limiter = Limiter::new(sample_rate, num_channels, ceiling_db, settings.limiter_lookahead_ms,
+                                   settings.limiter_release_ms);
+    let latency = limiter.get_latency_samples();
+    let mut output = vec![Vec::with_capacity(num_frames); num_channels];
+    let mut frame = vec![0.0; num_channels];
+    for n in 0..num_frames + latency {
+        for (channel, crossover) in crossovers.iter_mut().enumerate() {
+            let x = channels[channel].get(n).copied().unwrap_or(0.0);
+            crossover.split(x, & mut split);
+            for (band, value) in split.iter().enumerate() {
+                bands[band][channel] = *value;
+            }
+        }
+        frame.iter_mut().for_each(|x| *x = 0.0);
+        for (band, compressor) in bands.iter_mut().zip(& mut compressors) {
+            compressor.process_frame(band);
+            for (x, y) in frame.iter_mut().zip(band.iter()) {
+                *x += y;
+            }
+        }
+        limiter.process_frame(& mut frame);
+        if n >= latency {
+            for (channel, x) in output.iter_mut().zip(& frame) {
+                channel.push(*x);
+            }
+        }
+    }
+    AudioBuffer::new(sample_rate, output)
+}
+
+/// Masters a WAV file, the output has the sample format of the input, with dither
+/// for an integer format. The metadata of the input is kept, with the loudness and
+/// the true peak of the bext chunk updated.
+pub fn master_file(input_path: & str, output_path: & str, settings: & MasteringSettings) -> Result<MasteringReport, String> {
+    let (audio, sample_format, mut metadata) = read_wav_with_metadata(input_path)?;
+    let (mut mastered, report) = master_audio(& audio, settings)?;
+    if !sample_format.is_float() {
+        for (index, channel) in mastered.channels.iter_mut().enumerate() {
+            let mut dither = TpdfDither::new(sample_format.get_bits_per_sample() as u32, index as u32 + 1);
+            channel.iter_mut().for_each(|x| *x = dither.process(*x));
+        }
+    }
+    if let Some(bext) = & mut metadata.bext {
+        bext.loudness_value = Some(report.output_lufs);
+        bext.max_true_peak_level = Some(report.output_true_peak_db);
+        // The other loudness values aren't measured.
+        bext.loudness_range = None;
+        bext.max_momentary = None;
+        bext.max_short_term = None;
+        bext.version = bext.version.max(2);
+    }
+    write_wav_with_metadata(output_path, & mastered, sample_format, & metadata)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::TAU;
+    use crate::audio_io::read_wav;
+    use crate::wav_metadata::BextChunk;
+    use crate::wav_metadata::CuePoint;
+    use crate::wav_metadata::WavMetadata;
+    use crate::generators::make_white_noise;
+    use crate::fixtures::temp_test_path;
+
+    // A quiet stereo mix, with a bass, a melody, bursts of noise and a DC offset.
+    fn make_mix(seconds: usize) -> AudioBuffer {
+        let sample_rate = 48_000;
+        let noise = make_white_noise(seconds * sample_rate, 0.5, 5);
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        for (n, noise) in noise.iter().enumerate() {
+            let t = n as f64 / sample_rate as f64;
+            let burst = if (n / 12_000) % 4 == 0 { 0.2 * noise } else { 0.0 };
+            let bass = 0.15 * f64::sin(TAU * 55.0 * t);
+            let melody = 0.1 * f64::sin(TAU * 440.0 * (1.0 + (n / 24_000 % 3) as f64 / 4.0) * t);
+            left.push(0.4 * (bass + melody + burst + 0.01));
+            right.push(0.4 * (bass + 0.5 * melody + burst));
+        }
+        AudioBuffer::new(sample_rate as u32, vec![left, right])
+    }
+
+    #[test]
+    fn test_master_to_target() {
+        let mix = make_mix(6);
+        let settings = MasteringSettings::new(-14.0, -1.0);
+        let (mastered, report) = master_audio(& mix, & settings).unwrap();
+        assert_eq!(mastered.get_num_frames(), mix.get_num_frames());
+        assert!(report.dc_blocker);
+        assert!(report.input_lufs < -20.0);
+        assert!((report.output_lufs + 14.0).abs() <= LOUDNESS_TOLERANCE_LU, "{:?}", report);
+        assert!(report.output_true_peak_db <= -1.0, "{:?}", report);
+        // The DC offset is removed.
+        let dc = mastered.channels[0][24_000..].iter().sum::<f64>() / (mastered.get_num_frames() - 24_000) as f64;
+        assert!(dc.abs() < 1e-3);
+
+        assert!(master_audio(& AudioBuffer::new(48_000, vec![vec![0.0; 48_000]]), & settings).is_err());
+    }
+
+    #[test]
+    fn test_master_file_with_dither() {
+        let (input_path, output_path) = (& temp_test_path("master_input.wav"), & temp_test_path("master_output.wav"));
+        let mut metadata = WavMetadata::new();
+        metadata.bext = Some(BextChunk::new("Mix 4"));
+        metadata.cue_points.push(CuePoint { id: 1, position: 48_000, label: Some("chorus".to_string()) });
+        write_wav_with_metadata(input_path, & make_mix(3), SampleFormat::Int16, & metadata).unwrap();
+        let report = master_file(input_path, output_path, & MasteringSettings::new(-16.0, -2.0)).unwrap();
+        let (output, sample_format) = read_wav(output_path).unwrap();
+        assert_eq!(sample_format, SampleFormat::Int16);
+        assert!((integrated_loudness(& output) - report.output_lufs).abs() < 0.05);
+        let (_, _, output_metadata) = read_wav_with_metadata(output_path).unwrap();
+        assert_eq!(output_metadata.cue_points, metadata.cue_points);
+        let bext = output_metadata.bext.unwrap();
+        assert_eq!(bext.description, "Mix 4");
+        assert!((bext.loudness_value.unwrap() - report.output_lufs).abs() <= 0.005);
+        let _ = std::fs::remove_file(input_path);
+        let _ = std::fs::remove_file(output_path);
+    }
+}
@@ -0,0 +1,190 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Multichannel processing, with a chain of processing blocks for each
+//!              channel.
+//!              Each channel has its own chain and its own filter state. The chains
+//!              can be the same, made by a factory, or different, to correct the
+//!              mismatch between the two drivers of a pair of headphones or between
+//!              the speakers of a room. The different chains of an EQ come from an
+//!              Equalizer APO configuration with Channel commands, see eq_profile.rs ,
+//!              and a channel without a chain is passed through unchanged, like the
+//!              LFE channel of the default processing mask of channel_layout.rs .
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::audio_buffer::AudioBuffer;
+use crate::channel_layout::ChannelLayout;
+use crate::eq_profile::parse_equalizer_apo_channels;
+
+
+pub struct MultichannelProcessor {
+    // None is a channel passed through.
+    chains: Vec<Option<Vec<Box<dyn ProcessingBlock>>>>,
+}
+
+impl MultichannelProcessor {
+    /// All the channels are passed through, until a chain is set.
+    pub fn new(num_channels: usize) -> Self {
+        assert!(num_channels >= 1);
+        MultichannelProcessor { chains: (0..num_channels).map(|_| None).collect() }
+    }
+
+    /// The chain of each channel from the factory, called with the index of the
+    /// channel.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let mut processor = MultichannelProcessor::from_factory(2,
+    ///    >>>     |_| vec![Box::new(make_highpass(80.0, 48_000, None)) as Box<dyn ProcessingBlock>]);
+    ///    ```
+    ///
+    pub fn from_factory<M>(num_channels: usize, mut make_chain: M) -> Self
+            where M: FnMut(usize) -> Vec<Box<dyn ProcessingBlock>> {
+        let mut processor = MultichannelProcessor::new(num_channels);
+        for channel in 0..num_channels {
+            processor.set_chain(channel, Some(make_chain(channel)));
+        }
+        processor
+    }
+
+    /// A ParametricEq for each channel, from an Equalizer APO configuration with
+    /// Channel commands. The channels of the layout outside of the default
+    /// processing mask, the LFE, are passed through when the configuration has no
+    /// Channel command for them.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let text = "Channel: L\nFilter: ON PK Fc 3100 Hz Gain -2 dB Q 2\n\
+    ///    >>>             Channel: R\nFilter: ON PK Fc 2900 Hz Gain -3.5 dB Q 1.8";
+    ///    >>> let mut processor = MultichannelProcessor::from_equalizer_apo_config(text, ChannelLayout::Stereo, 48_000)?;
+    ///    >>> let output = processor.process_audio(& audio)?;
+    ///    ```
+    ///
+    pub fn from_equalizer_apo_config(text: & str, layout: ChannelLayout, sample_rate: u32) -> Result<Self, String> {
+        let profiles = parse_equalizer_apo_channels(text, layout)?;
+        let mask = layout.default_processing_mask();
+        let has_channel_commands = text.lines().any(|line| line.trim().starts_with("Channel:"));
+        let mut processor = MultichannelProcessor::new(profiles.len());
+        for (channel, profile) in profiles.iter().enumerate() {
+            if mask[channel] || has_channel_commands {
+                processor.set_chain(channel, Some(vec![Box::new(profile.to_parametric_eq(sample_rate)?)]));
+            }
+        }
+        Ok(processor)
+    }
+
+    pub fn get_num_channels(& self) -> usize {
+        self.chains.len()
+    }
+
+    /// Whether the channel is processed, or passed through.
+    pub fn has_chain(& self, channel: usize) -> bool {
+        self.chains[channel].is_some()
+    }
+
+    /// Sets the chain of the channel, None passes it through. Returns the previous
+    /// chain.
+    pub fn set_chain(& mut self, channel: usize, chain: Option<Vec<Box<dyn ProcessingBlock>>>)
+            -> Option<Vec<Box<dyn ProcessingBlock>>> {
+        assert!(channel < self.chains.len());
+        std::mem::replace(& mut self.chains[channel], chain)
+    }
+
+    /// Processes one frame, a sample of each channel, in place.
+    pub fn process_frame(& mut self, frame: & mut [f64]) {
+        assert_eq!(frame.len(), self.chains.len());
+        for (sample, chain) in frame.iter_mut().zip(self.chains.iter_mut()) {
+            if let Some(chain) = chain {
+                *sample = chain.iter_mut().fold(*sample, |x, block| block.process(x));
+            }
+        }
+    }
+
+    /// Processes a buffer in place, like the block of an audio callback, that must
+    /// have the number of channels of the processor.
+    pub fn process_buffer(& mut self, buffer: & mut AudioBuffer) -> Result<(), String> {
+        if buffer.get_num_channels() != self.chains.len() {
+            return Err(format!("Error: the audio has {} channels, the processor has {}",
+                               buffer.get_num_channels(), self.chains.len()));
+        }
+        for (channel, chain) in buffer.channels.iter_mut().zip(self.chains.iter_mut()) {
+            // Block by block of the chain, the same output as sample by sample.
+            for block in chain.iter_mut().flatten() {
+                block.process_inplace(channel);
+            }
+        }
+        Ok(())
+    }
+
+    /// Processes the channels of the audio, that must have the number of channels
+    /// of the processor.
+    pub fn process_audio(& mut self, audio: & AudioBuffer) -> Result<AudioBuffer, String> {
+        let _span = trace_span!("multichannel_process", channels = self.chains.len());
+        let mut output = audio.clone();
+        self.process_buffer(& mut output)?;
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::TAU;
+    use crate::eq_profile::parse_equalizer_apo_config;
+
+    #[test]
+    fn test_different_eq_per_channel() {
+        let text = "Preamp: -2 dB\nChannel: L\nFilter: ON PK Fc 1000 Hz Gain -6 dB Q 1\n\
+                    Channel: R\nFilter: ON PK Fc 1000 Hz Gain 3 dB Q 1\n";
+        let mut processor = MultichannelProcessor::from_equalizer_apo_config(text, ChannelLayout::Stereo, 48_000).unwrap();
+        let tone: Vec<f64> = (0..48_000).map(|n| 0.1 * f64::sin(TAU * 1_000.0 * n as f64 / 48_000.0)).collect();
+        let output = processor.process_audio(& AudioBuffer::new(48_000, vec![tone.clone(), tone.clone()])).unwrap();
+        let level_db = |channel: & [f64]| 20.0 * f64::log10(channel[24_000..].iter().fold(0.0, |max: f64, y| max.max(y.abs())) / 0.1);
+        assert!((level_db(& output.channels[0]) + 8.0).abs() < 0.05);
+        assert!((level_db(& output.channels[1]) - 1.0).abs() < 0.05);
+
+        // The same chains, sample by sample.
+        let mut by_frame = MultichannelProcessor::from_equalizer_apo_config(text, ChannelLayout::Stereo, 48_000).unwrap();
+        for (n, x) in tone.iter().enumerate().take(1_000) {
+            let mut frame = [*x, *x];
+            by_frame.process_frame(& mut frame);
+            assert_eq!(frame, [output.channels[0][n], output.channels[1][n]]);
+        }
+        // And in blocks, like an audio callback.
+        let mut by_block = MultichannelProcessor::from_equalizer_apo_config(text, ChannelLayout::Stereo, 48_000).unwrap();
+        let mut blocks = AudioBuffer::new(48_000, vec![tone.clone(), tone.clone()]);
+        blocks.for_each_block(100, |block, _| by_block.process_buffer(block).unwrap());
+        assert_eq!(blocks, output);
+        assert!(processor.process_audio(& AudioBuffer::new(48_000, vec![tone])).is_err());
+    }
+
+    #[test]
+    fn test_lfe_passed_through() {
+        let profile = "Preamp: -6 dB\nFilter: ON HPQ Fc 100 Hz Q 0.7\n";
+        let processor = MultichannelProcessor::from_equalizer_apo_config(profile, ChannelLayout::Surround51, 48_000).unwrap();
+        let processed: Vec<bool> = (0..6).map(|channel| processor.has_chain(channel)).collect();
+        assert_eq!(processed, ChannelLayout::Surround51.default_processing_mask());
+        // A Channel command selects the LFE explicitly.
+        let text = "Channel: SUB\nFilter: ON LPQ Fc 120 Hz Q 0.7\n";
+        let processor = MultichannelProcessor::from_equalizer_apo_config(text, ChannelLayout::Surround51, 48_000).unwrap();
+        assert!((0..6).all(|channel| processor.has_chain(channel)));
+
+        let mut processor = MultichannelProcessor::from_factory(3, |channel| match channel {
+            1 => vec![Box::new(parse_equalizer_apo_config(profile).unwrap().to_parametric_eq(48_000).unwrap())],
+            _ => Vec::new(),
+        });
+        assert!(processor.set_chain(0, None).is_some());
+        let mut frame = [0.5, 0.5, 0.5];
+        processor.process_frame(& mut frame);
+        assert_eq!(frame[0], 0.5);
+        assert_eq!(frame[2], 0.5);
+        assert!(frame[1] < 0.5);
+    }
+}
@@ -0,0 +1,313 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: The same filter on each channel of stereo, 5.1 or any number of
+//!              channels, with a filter state of its own for each channel.
+//!              A filter has the state of one signal, and a stereo signal through a
+//!              single filter mixes the left and the right channels in the state.
+//!              The MultiChannelFilter holds a copy of the filter for each channel,
+//!              from a prototype or from a factory, and processes a frame, an
+//!              interleaved buffer, L R L R ..., or a planar buffer, one slice per
+//!              channel, of f64 or f32 samples. For a different chain of blocks on
+//!              each channel, or channels passed through, see multichannel.rs .
+//!
+//!              The channels that the default processing mask of the channel layout
+//!              excludes, the LFE of 5.1, see channel_layout.rs, are passed through:
+//!              those of the layout of the filter, from with_layout(), or in
+//!              process_buffer() of a filter without a layout, those of the layout
+//!              of the buffer. A full range EQ on a 5.1 file leaves the LFE channel
+//!              as it is. The mask is only the default, a channel set with
+//!              set_channel_processed() is processed or passed through as set, the
+//!              LFE with a filter of its own is processed after an opt-in.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use crate::iir_filter::Float; // Trait
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::audio_buffer::AudioBuffer;
+use crate::channel_layout::ChannelLayout;
+
+
+/// One filter for each channel.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut filter = MultiChannelFilter::new(make_lowpass(1_000.0, 48_000, None), 2);
+///    >>> filter.process_interleaved(& mut stereo_samples);
+///    ```
+///
+#[derive(Debug, Clone)]
+pub struct MultiChannelFilter<B> {
+    filters: Vec<B>,
+    // The channels set with set_channel_processed(), the others follow the layout.
+    explicit: Vec<Option<bool>>,
+    // The layout of the filter, from with_layout().
+    layout: Option<ChannelLayout>,
+    // The layout of the processing mask, computed once for each change of the
+    // layout, not for each block.
+    mask_layout: Option<ChannelLayout>,
+    // The channels that are processed, the others are passed through.
+    processed: Vec<bool>,
+}
+
+impl<B: Clone> MultiChannelFilter<B> {
+    /// A copy of the prototype for each channel, with its state.
+    pub fn new(prototype: B, num_channels: usize) -> Self {
+        assert!(num_channels >= 1);
+        MultiChannelFilter::from_filters(vec![prototype; num_channels])
+    }
+}
+
+impl<B> MultiChannelFilter<B> {
+    /// The filter of each channel from the factory, called with the index of the
+    /// channel, for the blocks that aren't Clone, like a Box<dyn ProcessingBlock>.
+    pub fn from_factory<M>(num_channels: usize, make_filter: M) -> Self
+            where M: FnMut(usize) -> B {
+        assert!(num_channels >= 1);
+        MultiChannelFilter::from_filters((0..num_channels).map(make_filter).collect())
+    }
+
+    fn from_filters(filters: Vec<B>) -> Self {
+        let num_channels = filters.len();
+        MultiChannelFilter { filters, explicit: vec![None; num_channels], layout: None, mask_layout: None,
+                             processed: vec![true; num_channels] }
+    }
+
+    /// Passes through the channels that the default processing mask of the layout
+    /// excludes, the LFE of 5.1, but those set with set_channel_processed(). The
+    /// layout of the filter is also the one of process_buffer().
+    pub fn with_layout(mut self, layout: ChannelLayout) -> Result<Self, String> {
+        if layout.channel_count() != self.filters.len() {
+            return Err(format!("Error: the layout {:?} has {} channels, the filter has {}",
+                               layout, layout.channel_count(), self.filters.len()));
+        }
+        self.layout = Some(layout);
+        self.update_mask(Some(layout));
+        Ok(self)
+    }
+
+    /// Processes or passes through the channel, whatever the layout, like the LFE
+    /// of 5.1 with a filter of its own.
+    pub fn set_channel_processed(& mut self, channel: usize, processed: bool) {
+        self.explicit[channel] = Some(processed);
+        self.processed[channel] = processed;
+    }
+
+    pub fn is_channel_processed(& self, channel: usize) -> bool {
+        self.processed[channel]
+    }
+
+    pub fn get_num_channels(& self) -> usize {
+        self.filters.len()
+    }
+
+    pub fn get_filter(& self, channel: usize) -> & B {
+        & self.filters[channel]
+    }
+
+    pub fn get_filter_mut(& mut self, channel: usize) -> & mut B {
+        & mut self.filters[channel]
+    }
+
+    /// Processes one frame, a sample of each channel, in place.
+    pub fn process_frame<T: Float>(& mut self, frame: & mut [T])
+            where B: ProcessingBlock<T> {
+        assert_eq!(frame.len(), self.filters.len());
+        self.update_mask(self.layout);
+        for ((sample, filter), processed) in frame.iter_mut().zip(self.filters.iter_mut()).zip(& self.processed) {
+            if *processed {
+                *sample = filter.process(*sample);
+            }
+        }
+    }
+
+    /// Processes the interleaved samples in place, frame by frame, L R L R ...,
+    /// that must be whole frames.
+    pub fn process_interleaved<T: Float>(& mut self, samples: & mut [T]) -> Result<(), String>
+            where B: ProcessingBlock<T> {
+        let num_channels = self.filters.len();
+        if !samples.len().is_multiple_of(num_channels) {
+            return Err(format!("Error: {} interleaved samples aren't whole frames of {} channels",
+                               samples.len(), num_channels));
+        }
+        for frame in samples.chunks_exact_mut(num_channels) {
+            self.process_frame(frame);
+        }
+        Ok(())
+    }
+
+    /// Processes the planar samples in place, one slice or Vec for each channel,
+    /// with the block processing of the filters.
+    pub fn process_planar<T: Float, C: AsMut<[T]>>(& mut self, channels: & mut [C]) -> Result<(), String>
+            where B: ProcessingBlock<T> {
+        self.process_channels(channels, self.layout)
+    }
+
+    /// Processes the channels of the buffer in place, like the block of an audio
+    /// callback. The channels that the layout of the filter, or without one the
+    /// layout of the buffer, excludes from the processing are passed through, but
+    /// those set with set_channel_processed().
+    pub fn process_buffer(& mut self, buffer: & mut AudioBuffer) -> Result<(), String>
+            where B: ProcessingBlock {
+        let layout = self.layout.unwrap_or(buffer.layout);
+        self.process_channels(& mut buffer.channels, Some(layout))
+    }
+
+    fn process_channels<T: Float, C: AsMut<[T]>>(& mut self, channels: & mut [C], layout: Option<ChannelLayout>)
+            -> Result<(), String>
+            where B: ProcessingBlock<T> {
+        if channels.len() != self.filters.len() {
+            return Err(format!("Error: the audio has {} channels, the filter has {}",
+                               channels.len(), self.filters.len()));
+        }
+        self.update_mask(layout);
+        for ((channel, filter), processed) in channels.iter_mut().zip(self.filters.iter_mut()).zip(& self.processed) {
+            if *processed {
+                filter.process_inplace(channel.as_mut());
+            }
+        }
+        Ok(())
+    }
+
+    // The processing mask of the layout, with the channels set with
+    // set_channel_processed(), only when the layout changes.
+    fn update_mask(& mut self, layout: Option<ChannelLayout>) {
+        if layout == self.mask_layout {
+            return;
+        }
+        let default = match layout {
+            Some(layout) => layout.default_processing_mask(),
+            None => vec![true; self.filters.len()],
+        };
+        self.processed = self.explicit.iter().zip(default)
+            .map(|(explicit, default)| explicit.unwrap_or(default))
+            .collect();
+        self.mask_layout = layout;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::iir_filter::IIRFilter;
+
+    #[test]
+    fn test_interleaved_and_planar() {
+        let left:  Vec<f64> = (0..1_000).map(|n| f64::sin(n as f64 * 0.3)).collect();
+        let right: Vec<f64> = (0..1_000).map(|n| if n % 50 < 25 { 0.5 } else { -0.5 }).collect();
+        let mut reference_filter = make_lowpass(2_000.0, 48_000, None);
+        let reference_left: Vec<f64> = left.iter().map(|x| reference_filter.process(*x)).collect();
+        let mut reference_filter = make_lowpass(2_000.0, 48_000, None);
+        let reference_right: Vec<f64> = right.iter().map(|x| reference_filter.process(*x)).collect();
+
+        let mut filter = MultiChannelFilter::new(make_lowpass(2_000.0, 48_000, None), 2);
+        let mut audio = AudioBuffer::new(48_000, vec![left.clone(), right.clone()]);
+        let mut interleaved = audio.to_interleaved();
+        let mut by_block = filter.clone();
+        filter.process_interleaved(& mut interleaved).unwrap();
+        assert_eq!(AudioBuffer::from_interleaved(48_000, 2, & interleaved).unwrap().channels,
+                   vec![reference_left.clone(), reference_right.clone()]);
+        assert!(filter.process_interleaved(& mut [0.0; 3]).is_err());
+
+        audio.for_each_block(100, |block, _| by_block.process_buffer(block).unwrap());
+        assert_eq!(audio.channels, vec![reference_left, reference_right]);
+        assert!(by_block.process_buffer(& mut AudioBuffer::zeros(48_000, 1, 10)).is_err());
+    }
+
+    #[test]
+    fn test_surround_f32_and_factory() {
+        // 5.1, the LFE with a lower cutoff, each channel an impulse.
+        let mut filter: MultiChannelFilter<IIRFilter<f32>> = MultiChannelFilter::from_factory(6, |channel| {
+            let cutoff = if channel == 3 { 120.0 } else { 8_000.0 };
+            make_lowpass(cutoff, 48_000, None).cast()
+        });
+        assert_eq!(filter.get_num_channels(), 6);
+        let mut channels = vec![vec![0.0_f32; 64]; 6];
+        for channel in channels.iter_mut() {
+            channel[0] = 1.0;
+        }
+        filter.process_planar(& mut channels).unwrap();
+        assert!(channels.iter().all(|channel| channel[..] == channels[0][..] || channel[..] == channels[3][..]));
+        assert!(channels[3][0] < channels[0][0]);
+
+        // Frame by frame, the same as planar.
+        let mut by_frame = MultiChannelFilter::new(make_lowpass(8_000.0, 48_000, None).cast::<f32>(), 6);
+        *by_frame.get_filter_mut(3) = make_lowpass(120.0, 48_000, None).cast();
+        for n in 0..64 {
+            let mut frame = [if n == 0 { 1.0_f32 } else { 0.0 }; 6];
+            by_frame.process_frame(& mut frame);
+            assert!(frame.iter().zip(& channels).all(|(y, channel)| *y == channel[n]));
+        }
+        assert!(filter.process_planar(& mut channels[..5]).is_err());
+    }
+
+    #[test]
+    fn test_layout_passes_lfe_through() {
+        let impulse: Vec<f64> = (0..64).map(|n| if n == 0 { 1.0 } else { 0.0 }).collect();
+        let mut reference = make_lowpass(1_000.0, 48_000, None);
+        let filtered: Vec<f64> = impulse.iter().map(|x| reference.process(*x)).collect();
+
+        // The layout of the buffer.
+        let mut filter = MultiChannelFilter::new(make_lowpass(1_000.0, 48_000, None), 6);
+        let mut audio = AudioBuffer::new(48_000, vec![impulse.clone(); 6]);
+        filter.process_buffer(& mut audio).unwrap();
+        for (channel, samples) in audio.channels.iter().enumerate() {
+            assert_eq!(samples, if channel == 3 { & impulse } else { & filtered });
+        }
+
+        // The layout of the filter, for the planar and interleaved samples.
+        let mut filter = MultiChannelFilter::new(make_lowpass(1_000.0, 48_000, None), 6)
+            .with_layout(ChannelLayout::Surround51).unwrap();
+        assert!(!filter.is_channel_processed(3));
+        let mut channels = vec![impulse.clone(); 6];
+        filter.process_planar(& mut channels).unwrap();
+        assert_eq!((& channels[3], & channels[4]), (& impulse, & filtered));
+        filter.set_channel_processed(3, true);
+        assert!(filter.is_channel_processed(3));
+        assert!(MultiChannelFilter::new(make_lowpass(1_000.0, 48_000, None), 2)
+            .with_layout(ChannelLayout::Surround51).is_err());
+    }
+
+    #[test]
+    fn test_lfe_opt_in() {
+        // A 5.1 buffer, the LFE with a 120 Hz low-pass of its own.
+        let impulse: Vec<f64> = (0..64).map(|n| if n == 0 { 1.0 } else { 0.0 }).collect();
+        let mut reference = make_lowpass(120.0, 48_000, None);
+        let lfe_filtered: Vec<f64> = impulse.iter().map(|x| reference.process(*x)).collect();
+        let make_filter = || MultiChannelFilter::from_factory(6, |channel| {
+            let cutoff = if channel == 3 { 120.0 } else { 8_000.0 };
+            make_lowpass(cutoff, 48_000, None)
+        });
+        let mut audio = AudioBuffer::new(48_000, vec![impulse.clone(); 6])
+            .with_layout(ChannelLayout::Surround51).unwrap();
+
+        // By default the LFE is passed through.
+        let mut by_default = audio.clone();
+        make_filter().process_buffer(& mut by_default).unwrap();
+        assert_eq!(by_default.channels[3], impulse);
+
+        // After the opt-in, block by block, it's filtered, and the other channels
+        // are the same.
+        let mut filter = make_filter();
+        filter.set_channel_processed(3, true);
+        audio.for_each_block(16, |block, _| filter.process_buffer(block).unwrap());
+        assert_eq!(audio.channels[3], lfe_filtered);
+        assert!((0..6).filter(|channel| *channel != 3)
+            .all(|channel| audio.channels[channel] == by_default.channels[channel]));
+
+        // And the opt-out of a channel the layout processes.
+        let mut filter = make_filter().with_layout(ChannelLayout::Surround51).unwrap();
+        filter.set_channel_processed(0, false);
+        filter.set_channel_processed(3, true);
+        let mut audio = AudioBuffer::new(48_000, vec![impulse.clone(); 6]);
+        filter.process_buffer(& mut audio).unwrap();
+        assert_eq!((& audio.channels[0], & audio.channels[3]), (& impulse, & lfe_filtered));
+        assert!((1..6).all(|channel| filter.is_channel_processed(channel)));
+    }
+}
@@ -0,0 +1,152 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Frequencies as musical note names.
+//!              A musician sets the notch of a resonance or the peak of a bass by its
+//!              pitch, not in Hz. A note name is the letter, the accidentals, # or b,
+//!              the octave of the scientific pitch notation, where C4 is the middle C,
+//!              and an optional offset in cents, 1/100 of a semitone:
+//!
+//!                 A4         440 Hz
+//!                 C#2+14c    69.86 Hz
+//!                 Bb0        29.14 Hz
+//!                 C-1        8.18 Hz, the MIDI note 0
+//!
+//!              In the equal temperament with A4 at 440 Hz, the MIDI note m, with
+//!              m = 12 (octave + 1) + semitone, is at
+//!
+//!                 f = 440 * 2^((m - 69 + cents / 100) / 12)
+//!
+//!              The format of a frequency is the nearest note, with sharps, and the
+//!              offset in whole cents, from -50c to +50c, like 1000 Hz is B5+21c.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Scientific pitch notation - Wikipedia
+//!       https://en.wikipedia.org/wiki/Scientific_pitch_notation
+//!
+
+
+/// The frequency of A4, the MIDI note 69.
+pub const A4_FREQUENCY: f64 = 440.0;
+const A4_MIDI_NOTE: f64 = 69.0;
+
+const NOTE_NAMES: [&str; 12] = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+
+/// The frequency of the note name, like "A4", "C#2+14c" or "Bb3-20c".
+///
+/// In Rust:
+///    ```text
+///    >>> let frequency = note_to_frequency("E1")?;   // 41.2 Hz, the low E of a bass
+///    >>> let notch = make_notch(frequency, 48_000, None);
+///    ```
+///
+pub fn note_to_frequency(note: & str) -> Result<f64, String> {
+    let error = || format!("Error: invalid note name \"{}\", like A4, C#2+14c or Bb3", note);
+    let mut chars = note.trim().chars().peekable();
+    let mut semitone: i32 = match chars.next().map(|c| c.to_ascii_uppercase()) {
+        Some('C') => 0,
+        Some('D') => 2,
+        Some('E') => 4,
+        Some('F') => 5,
+        Some('G') => 7,
+        Some('A') => 9,
+        Some('B') => 11,
+        _ => return Err(error()),
+    };
+    while let Some(accidental) = chars.peek() {
+        match accidental {
+            '#' => semitone += 1,
+            'b' => semitone -= 1,
+            _ => break,
+        }
+        chars.next();
+    }
+    let rest: String = chars.collect();
+    // The octave, with its sign, up to the sign of the cents.
+    let octave_end = rest.char_indices()
+        .find(|(index, c)| (*c == '+' || *c == '-') && *index > 0)
+        .map_or(rest.len(), |(index, _)| index);
+    let octave: i32 = rest[..octave_end].parse().map_err(|_| error())?;
+    let cents = match & rest[octave_end..] {
+        "" => 0.0,
+        offset => offset.strip_suffix('c')
+            .and_then(|cents| cents.parse::<f64>().ok())
+            .filter(|cents| cents.is_finite())
+            .ok_or_else(error)?,
+    };
+    let midi_note = 12 * (octave + 1) + semitone;
+    Ok(midi_to_frequency(midi_note as f64 + cents / 100.0))
+}
+
+/// The frequency of a MIDI note, a fraction is an offset in semitones.
+pub fn midi_to_frequency(midi_note: f64) -> f64 {
+    A4_FREQUENCY * f64::powf(2.0, (midi_note - A4_MIDI_NOTE) / 12.0)
+}
+
+/// The MIDI note of the frequency, with the fraction of a semitone.
+pub fn frequency_to_midi(frequency: f64) -> f64 {
+    A4_MIDI_NOTE + 12.0 * f64::log2(frequency / A4_FREQUENCY)
+}
+
+/// The nearest note of the frequency, and the offset in cents if it isn't 0,
+/// like "A4" or "B5+21c".
+pub fn frequency_to_note(frequency: f64) -> String {
+    assert!(frequency > 0.0 && frequency.is_finite());
+    let midi_note = frequency_to_midi(frequency);
+    let nearest = midi_note.round();
+    let cents = ((midi_note - nearest) * 100.0).round() as i64;
+    let nearest = nearest as i64;
+    let name = format!("{}{}", NOTE_NAMES[nearest.rem_euclid(12) as usize], nearest.div_euclid(12) - 1);
+    match cents {
+        0 => name,
+        _ => format!("{}{:+}c", name, cents),
+    }
+}
+
+/// A frequency in Hz, a number, or a note name.
+pub fn parse_frequency(text: & str) -> Result<f64, String> {
+    match text.trim().parse::<f64>() {
+        Ok(frequency) => Ok(frequency),
+        Err(_) => note_to_frequency(text)
+            .map_err(|_| format!("Error: invalid frequency \"{}\", a number in Hz or a note name like A4", text)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_to_frequency() {
+        assert_eq!(note_to_frequency("A4"), Ok(440.0));
+        assert_eq!(note_to_frequency("a5"), Ok(880.0));
+        assert!((note_to_frequency("C4").unwrap() - 261.625_565).abs() < 1e-6);
+        assert_eq!(note_to_frequency("C#2"), note_to_frequency("Db2"));
+        assert!((note_to_frequency("C#2+14c").unwrap() - 69.295_658 * f64::powf(2.0, 0.14 / 12.0)).abs() < 1e-5);
+        assert!((note_to_frequency("Bb3-20.5c").unwrap() - midi_to_frequency(58.0 - 0.205)).abs() < 1e-9);
+        assert!((note_to_frequency("C-1").unwrap() - 8.175_799).abs() < 1e-6);
+        assert!((note_to_frequency("C-1+50c").unwrap() - midi_to_frequency(0.5)).abs() < 1e-9);
+        for invalid in ["", "H4", "A", "A4+", "A4+14", "A#x", "C4-c"] {
+            assert!(note_to_frequency(invalid).is_err(), "{}", invalid);
+        }
+        assert_eq!(parse_frequency("1000"), Ok(1_000.0));
+        assert_eq!(parse_frequency("A3"), Ok(220.0));
+        assert!(parse_frequency("1 kHz").is_err());
+    }
+
+    #[test]
+    fn test_frequency_to_note() {
+        assert_eq!(frequency_to_note(440.0), "A4");
+        assert_eq!(frequency_to_note(1_000.0), "B5+21c");
+        assert_eq!(frequency_to_note(60.0), "B1-49c");
+        assert_eq!(frequency_to_note(8.0), "C-1-38c");
+        for name in ["C#2+14c", "G9", "D#0-7c"] {
+            assert_eq!(frequency_to_note(note_to_frequency(name).unwrap()), name);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: A parallel bank of processing blocks, the same input into each
+//!              branch and the sum of their outputs, each with its gain, that is
+//!              itself a ProcessingBlock:
+//!
+//!                          +->  branch 0      -> * gain 0      -+
+//!                 input  --+->  branch 1      -> * gain 1      -+->  sum  ->  output
+//!                          +->  branch n - 1  -> * gain n - 1  -+
+//!
+//!              It is the parallel of the ProcessingChain of processing_chain.rs,
+//!              the branches are boxed, Box<dyn ProcessingBlock>, and are added and
+//!              removed at run time. With it are built the parallel EQs, a dry
+//!              branch and the peaks in parallel, the multi-band compressors, the
+//!              bands of a crossover each through its compressor, and the comb
+//!              filters, a dry branch and a delayed one.
+//!              The gains are linear, 1.0 for a branch added with push(), and
+//!              are changed with set_gain() without a change of the state of the
+//!              branches.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use crate::iir_filter::Float; // Trait
+use crate::iir_filter::ProcessingBlock; // Trait
+
+
+/// The branches of a bank and their gains.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut bank = ParallelBank::new();
+///    >>> bank.push(DelayLine::new(0));
+///    >>> bank.push_with_gain(make_bandpass(1_000.0, 48_000, Some(4.0)), 0.5);
+///    >>> bank.process_inplace(& mut samples);
+///    ```
+///
+pub struct ParallelBank<T: Float = f64> {
+    branches: Vec<Box<dyn ProcessingBlock<T>>>,
+    gains:    Vec<T>,
+    // The input and the output of a branch, of process_inplace().
+    input:    Vec<T>,
+    output:   Vec<T>,
+}
+
+impl<T: Float> Default for ParallelBank<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Float> ParallelBank<T> {
+    /// An empty bank, that outputs silence.
+    pub fn new() -> Self {
+        ParallelBank { branches: Vec::new(), gains: Vec::new(), input: Vec::new(), output: Vec::new() }
+    }
+
+    pub fn get_num_branches(& self) -> usize {
+        self.branches.len()
+    }
+
+    pub fn is_empty(& self) -> bool {
+        self.branches.is_empty()
+    }
+
+    pub fn get_branch_mut(& mut self, index: usize) -> Option<& mut (dyn ProcessingBlock<T> + 'static)> {
+        self.branches.get_mut(index).map(|branch| branch.as_mut())
+    }
+
+    /// Adds the branch, with the gain of 1.0 .
+    pub fn push<B: ProcessingBlock<T> + 'static>(& mut self, branch: B) {
+        self.push_with_gain(branch, T::ONE);
+    }
+
+    /// Adds the branch, with its linear gain.
+    pub fn push_with_gain<B: ProcessingBlock<T> + 'static>(& mut self, branch: B, gain: T) {
+        self.branches.push(Box::new(branch));
+        self.gains.push(gain);
+    }
+
+    /// Removes the branch of the index, and returns it.
+    pub fn remove(& mut self, index: usize) -> Result<Box<dyn ProcessingBlock<T>>, String> {
+        self.check_index(index)?;
+        self.gains.remove(index);
+        Ok(self.branches.remove(index))
+    }
+
+    pub fn get_gain(& self, index: usize) -> Option<T> {
+        self.gains.get(index).copied()
+    }
+
+    pub fn set_gain(& mut self, index: usize, gain: T) -> Result<(), String> {
+        self.check_index(index)?;
+        self.gains[index] = gain;
+        Ok(())
+    }
+
+    fn check_index(& self, index: usize) -> Result<(), String> {
+        if index >= self.branches.len() {
+            return Err(format!("Error: no branch {} in a bank of {} branches", index, self.branches.len()));
+        }
+        Ok(())
+    }
+}
+
+impl<T: Float> ProcessingBlock<T> for ParallelBank<T> {
+    fn process(& mut self, sample: T) -> T {
+        let mut sum = T::ZERO;
+        for (branch, gain) in self.branches.iter_mut().zip(& self.gains) {
+            sum += *gain * branch.process(sample);
+        }
+        sum
+    }
+
+    fn process_buffer(& mut self, input: & [T], output: & mut [T]) {
+        assert_eq!(input.len(), output.len());
+        output.copy_from_slice(input);
+        self.process_inplace(output);
+    }
+
+    // The block through each branch, the same output as each sample through the
+    // bank. The buffers of the branches grow to the longest block, and are kept.
+    fn process_inplace(& mut self, samples: & mut [T]) {
+        self.input.clear();
+        self.input.extend_from_slice(samples);
+        self.output.resize(samples.len(), T::ZERO);
+        samples.fill(T::ZERO);
+        for (branch, gain) in self.branches.iter_mut().zip(& self.gains) {
+            branch.process_buffer(& self.input, & mut self.output[.. samples.len()]);
+            for (sum, y) in samples.iter_mut().zip(& self.output) {
+                *sum += *gain * *y;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_linkwitz_riley_highpass;
+    use crate::butterworth_filter::make_linkwitz_riley_lowpass;
+    use crate::delay_line::DelayLine;
+
+    struct Gain(f32);
+
+    impl ProcessingBlock<f32> for Gain {
+        fn process(& mut self, sample: f32) -> f32 {
+            self.0 * sample
+        }
+    }
+
+    #[test]
+    fn test_bank_sums_the_branches() {
+        // A comb, the dry branch and half of the branch delayed by 3 samples.
+        let mut comb = ParallelBank::new();
+        comb.push(DelayLine::new(0));
+        comb.push_with_gain(DelayLine::new(3), 0.5);
+        let mut impulse = vec![0.0; 6];
+        impulse[0] = 1.0;
+        comb.process_inplace(& mut impulse);
+        assert_eq!(impulse, vec![1.0, 0.0, 0.0, 0.5, 0.0, 0.0]);
+
+        // The bands of a crossover in parallel, the same output by sample and by
+        // block, and the sum of the LR4 bands is an allpass.
+        let signal: Vec<f64> = (0..4_000).map(|n| f64::sin(n as f64 * 0.05) + 0.5 * f64::sin(n as f64 * 0.9)).collect();
+        let make = || {
+            let mut bank = ParallelBank::new();
+            bank.push(make_linkwitz_riley_lowpass(1_000.0, 48_000, 4).unwrap());
+            bank.push(make_linkwitz_riley_highpass(1_000.0, 48_000, 4).unwrap());
+            bank
+        };
+        let (mut by_sample, mut by_block) = (make(), make());
+        let mut output = signal.clone();
+        by_block.process_inplace(& mut output[.. 1_000]);
+        by_block.process_inplace(& mut output[1_000 ..]);
+        assert!(signal.iter().zip(& output).all(|(x, y)| (by_sample.process(*x) - y).abs() < 1e-12));
+        let power = |x: & [f64]| x.iter().map(|x| x * x).sum::<f64>();
+        assert!((power(& output[2_000 ..]) / power(& signal[2_000 ..]) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_bank_edits() {
+        let mut bank = ParallelBank::<f32>::default();
+        assert!(bank.is_empty());
+        assert_eq!(bank.process(1.0), 0.0);
+        bank.push(Gain(1.0));
+        bank.push_with_gain(Gain(1.0), 2.0);
+        assert_eq!((bank.get_num_branches(), bank.process(1.0)), (2, 3.0));
+        bank.set_gain(0, -1.0).unwrap();
+        assert_eq!((bank.get_gain(0), bank.process(1.0)), (Some(-1.0), 1.0));
+        assert!(bank.set_gain(2, 1.0).is_err());
+        let mut removed = bank.remove(1).unwrap();
+        assert_eq!((removed.process(1.0), bank.process(1.0)), (1.0, -1.0));
+        assert!(bank.get_branch_mut(0).is_some());
+        assert!(bank.remove(1).is_err());
+    }
+}
@@ -0,0 +1,324 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Parametric equalizer.
+//!              Unlike the graphic Equalizer, where the band frequencies and Q are
+//!              fixed, each band of the parametric equalizer has its own filter type,
+//!              frequency, gain and Q. This is the kind of EQ that room correction
+//!              software and headphone correction profiles (AutoEq, Equalizer APO)
+//!              describe. All the bands use the WebAudio / Audio EQ Cookbook biquads
+//!              of this crate, the same that those tools use, designed with the
+//!              default DesignPolicy of design_policy.rs, so a band above Nyquist or
+//!              with a Q that puts its poles on the unit circle is an error.
+//!
+//!              A band can be parallel, like the "parallel" mode of analog consoles
+//!              (the airy Maag style boosts): instead of filtering the signal in
+//!              series, its wet path is summed with the dry signal,
+//!
+//!                 y = x + (G - 1) F(x)
+//!
+//!              where G is the linear gain of the band, and F is a band-pass of
+//!              0 dB peak for a peak band, a high-pass for a high shelf and a low-pass
+//!              for a low shelf. All the parallel bands take the output of the series
+//!              bands. The biquads have no latency, and F has a zero phase at its
+//!              center (and in the passband of the shelves), so the dry path needs no
+//!              delay, and the sum is in phase where the boost is.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. WebAudio - Cookbook formulae for audio equalizer biquad filter coefficients
+//!       https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::iir_filter::IIRFilter;
+use crate::exact_math;
+use crate::design_policy::DesignPolicy;
+use crate::design_policy::FilterKind;
+
+use rustfft::num_complex::Complex;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BandType {
+    Peak,
+    LowShelf,
+    HighShelf,
+    LowPass,
+    HighPass,
+}
+
+/// One band of the parametric equalizer.
+/// The gain is ignored by the low-pass and high-pass bands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParametricBand {
+    pub band_type: BandType,
+    pub frequency: f64,
+    pub gain_db:   f64,
+    pub q_factor:  f64,
+    pub enabled:   bool,
+    /// Summed with the dry signal instead of in series, only for the peak and the
+    /// shelf bands.
+    pub parallel:  bool,
+}
+
+impl ParametricBand {
+    pub fn new(band_type: BandType, frequency: f64, gain_db: f64, q_factor: f64) -> Self {
+        ParametricBand { band_type, frequency, gain_db, q_factor, enabled: true, parallel: false }
+    }
+
+    /// Designs the biquad of the band with the default DesignPolicy, an error for a
+    /// frequency, a Q or a gain out of its limits, or for a parallel low-pass or
+    /// high-pass. The biquad of a parallel band is its wet path, already scaled by
+    /// G - 1 .
+    pub fn make_filter(& self, sample_rate: u32) -> Result<IIRFilter, String> {
+        let policy = DesignPolicy::default();
+        let kind = match self.band_type {
+            BandType::Peak      => FilterKind::Peak,
+            BandType::LowShelf  => FilterKind::LowShelf,
+            BandType::HighShelf => FilterKind::HighShelf,
+            BandType::LowPass   => FilterKind::LowPass,
+            BandType::HighPass  => FilterKind::HighPass,
+        };
+        let design = policy.make(kind, self.frequency, sample_rate, self.gain_db, self.q_factor)?;
+        if !self.parallel {
+            return design.into_biquad();
+        }
+        let (wet_kind, peak_gain) = match self.band_type {
+            // The band-pass has a constant skirt gain, its peak gain is Q.
+            BandType::Peak      => (FilterKind::BandPass, self.q_factor),
+            BandType::LowShelf  => (FilterKind::LowPass, 1.0),
+            BandType::HighShelf => (FilterKind::HighPass, 1.0),
+            BandType::LowPass | BandType::HighPass =>
+                return Err(format!("Error: a {:?} band can't be parallel", self.band_type)),
+        };
+        let mut filter = policy.make(wet_kind, self.frequency, sample_rate, 0.0, self.q_factor)?.into_biquad()?;
+        let wet_gain = (exact_math::powf(10.0, self.gain_db / 20.0) - 1.0) / peak_gain;
+        filter.b_coeffs.iter_mut().for_each(|b| *b *= wet_gain);
+        Ok(filter)
+    }
+}
+
+#[derive(Clone)]
+pub struct ParametricEq {
+    sample_rate:     u32,
+    preamp_db:       f64,
+    preamp_gain:     f64,
+    bands_vec:       Vec<ParametricBand>,
+    iir_filters_vec: Vec<IIRFilter>,
+}
+
+impl ParametricEq {
+    pub fn new(sample_rate: u32) -> Self {
+        ParametricEq {
+            sample_rate,
+            preamp_db: 0.0,
+            preamp_gain: 1.0,
+            bands_vec: Vec::new(),
+            iir_filters_vec: Vec::new(),
+        }
+    }
+
+    /// Creates a parametric equalizer with a preamp and a list of bands.
+    pub fn from_bands(sample_rate: u32, preamp_db: f64, bands: & [ParametricBand]) -> Result<Self, String> {
+        let mut eq = ParametricEq::new(sample_rate);
+        eq.set_preamp_db(preamp_db);
+        for band in bands {
+            eq.add_band(*band)?;
+        }
+        Ok(eq)
+    }
+
+    pub fn get_sample_rate(& self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn get_preamp_db(& self) -> f64 {
+        self.preamp_db
+    }
+
+    /// Gain applied before the bands, usually negative to leave headroom for the boosts.
+    pub fn set_preamp_db(& mut self, preamp_db: f64) {
+        self.preamp_db = preamp_db;
+        self.preamp_gain = exact_math::powf(10.0, preamp_db / 20.0);
+    }
+
+    pub fn get_num_bands(& self) -> usize {
+        self.bands_vec.len()
+    }
+
+    pub fn get_bands(& self) -> & [ParametricBand] {
+        & self.bands_vec
+    }
+
+    pub fn get_band(& self, index: usize) -> ParametricBand {
+        assert!(index < self.bands_vec.len());
+        self.bands_vec[index]
+    }
+
+    pub fn add_band(& mut self, band: ParametricBand) -> Result<(), String> {
+        let filter = band.make_filter(self.sample_rate)?;
+        trace_event!(band = ?band, "parametric EQ band added");
+        self.iir_filters_vec.push(filter);
+        self.bands_vec.push(band);
+        Ok(())
+    }
+
+    /// Changes a band. Only the coefficients of the filter are changed, the internal
+    /// state is kept so that the sound doesn't have an abrupt change.
+    pub fn set_band(& mut self, index: usize, band: ParametricBand) -> Result<(), String> {
+        assert!(index < self.bands_vec.len());
+        let iir_filter_tmp = band.make_filter(self.sample_rate)?;
+        trace_event!(index, band = ?band, "parametric EQ band updated");
+        self.iir_filters_vec[index].set_coefficients(& iir_filter_tmp.a_coeffs, & iir_filter_tmp.b_coeffs)?;
+        self.bands_vec[index] = band;
+        Ok(())
+    }
+
+    pub fn remove_band(& mut self, index: usize) -> ParametricBand {
+        assert!(index < self.bands_vec.len());
+        self.iir_filters_vec.remove(index);
+        self.bands_vec.remove(index)
+    }
+
+    pub fn clear_bands(& mut self) {
+        self.bands_vec.clear();
+        self.iir_filters_vec.clear();
+    }
+
+    /// Frequency response of the preamp and all the enabled bands.
+    pub fn frequency_response(& self, frequency: f64) -> Complex<f64> {
+        let enabled = || self.bands_vec.iter().zip(& self.iir_filters_vec).filter(|(band, _)| band.enabled);
+        let series = enabled()
+            .filter(|(band, _)| !band.parallel)
+            .map(|(_, filter)| filter.frequency_response(frequency, self.sample_rate))
+            .product::<Complex<f64>>();
+        let parallel = enabled()
+            .filter(|(band, _)| band.parallel)
+            .map(|(_, filter)| filter.frequency_response(frequency, self.sample_rate))
+            .sum::<Complex<f64>>();
+        series * (1.0 + parallel) * self.preamp_gain
+    }
+
+    /// Gain in dB of the equalizer at a frequency.
+    pub fn magnitude_db(& self, frequency: f64) -> f64 {
+        20.0 * f64::log10(self.frequency_response(frequency).norm())
+    }
+}
+
+impl ProcessingBlock for ParametricEq {
+    fn process(& mut self, sample: f64) -> f64 {
+        let mut sample_t = sample * self.preamp_gain;
+        for (band, iir_filter) in self.bands_vec.iter().zip(& mut self.iir_filters_vec) {
+            if band.enabled && !band.parallel {
+                sample_t = iir_filter.process(sample_t);
+            }
+        }
+        // The wet paths of the parallel bands, summed with the dry signal.
+        let mut wet = 0.0;
+        for (band, iir_filter) in self.bands_vec.iter().zip(& mut self.iir_filters_vec) {
+            if band.enabled && band.parallel {
+                wet += iir_filter.process(sample_t);
+            }
+        }
+
+        sample_t + wet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parametric_eq_response() {
+        let sample_rate = 48_000;
+        let bands = [ParametricBand::new(BandType::Peak, 1_000.0, 6.0, 1.0),
+                     ParametricBand::new(BandType::HighPass, 20.0, 0.0, 0.707)];
+        let mut eq = ParametricEq::from_bands(sample_rate, -6.0, & bands).unwrap();
+        assert!(eq.magnitude_db(1_000.0).abs() < 0.01);
+        assert!((eq.magnitude_db(100.0) + 6.0).abs() < 0.5);
+
+        // Disabled bands are bypassed.
+        let mut band = eq.get_band(0);
+        band.enabled = false;
+        eq.set_band(0, band).unwrap();
+        assert!((eq.magnitude_db(1_000.0) + 6.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parametric_eq_process_matches_response() {
+        use std::f64::consts::TAU;
+
+        let sample_rate = 48_000;
+        let bands = [ParametricBand::new(BandType::LowShelf, 200.0, -4.0, 0.7)];
+        let mut eq = ParametricEq::from_bands(sample_rate, 0.0, & bands).unwrap();
+        let frequency = 50.0;
+        let mut max_output: f64 = 0.0;
+        for n in 0..sample_rate as usize {
+            let output = eq.process(f64::sin(TAU * frequency * n as f64 / sample_rate as f64));
+            if n > sample_rate as usize / 2 {
+                max_output = max_output.max(output.abs());
+            }
+        }
+        let gain_db = 20.0 * f64::log10(max_output);
+        assert!((gain_db - eq.magnitude_db(frequency)).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_parallel_bands() {
+        use std::f64::consts::TAU;
+
+        let sample_rate = 48_000;
+        let mut peak = ParametricBand::new(BandType::Peak, 1_000.0, 6.0, 2.0);
+        peak.parallel = true;
+        let mut air = ParametricBand::new(BandType::HighShelf, 12_000.0, 4.0, 0.707);
+        air.parallel = true;
+        let mut eq = ParametricEq::from_bands(sample_rate, 0.0, & [peak, air]).unwrap();
+        // In phase at the center, so the boost is exactly the gain.
+        assert!((eq.magnitude_db(1_000.0) - 6.0).abs() < 0.1);
+        assert!(eq.magnitude_db(100.0).abs() < 0.2);
+        assert!((eq.magnitude_db(20_000.0) - 4.0).abs() < 0.5);
+
+        let frequency = 1_000.0;
+        let mut max_output: f64 = 0.0;
+        for n in 0..sample_rate as usize {
+            let output = eq.process(f64::sin(TAU * frequency * n as f64 / sample_rate as f64));
+            if n > sample_rate as usize / 2 {
+                max_output = max_output.max(output.abs());
+            }
+        }
+        assert!((20.0 * f64::log10(max_output) - eq.magnitude_db(frequency)).abs() < 0.05);
+
+        let mut lowpass = ParametricBand::new(BandType::LowPass, 1_000.0, 0.0, 0.707);
+        lowpass.parallel = true;
+        assert!(eq.add_band(lowpass).is_err());
+    }
+
+    #[test]
+    fn test_parametric_eq_invalid_band() {
+        let mut eq = ParametricEq::new(44_100);
+        assert!(eq.add_band(ParametricBand::new(BandType::Peak, 30_000.0, 3.0, 1.0)).is_err());
+        assert!(eq.add_band(ParametricBand::new(BandType::Peak, 1_000.0, 3.0, 0.0)).is_err());
+        assert!(eq.add_band(ParametricBand::new(BandType::Peak, 1_000.0, f64::NAN, 1.0)).is_err());
+        // Beyond the limits of the default DesignPolicy.
+        assert!(eq.add_band(ParametricBand::new(BandType::LowShelf, 100.0, 30.0, 0.707)).is_err());
+        assert!(eq.add_band(ParametricBand::new(BandType::Peak, 50.0, 3.0, 1_000.0)).is_err());
+        assert_eq!(eq.get_num_bands(), 0);
+
+        // The fields are public, make_filter() checks them itself.
+        let mut band = ParametricBand::new(BandType::HighPass, 80.0, 0.0, 0.707);
+        assert!(band.make_filter(44_100).is_ok());
+        band.parallel = true;
+        assert!(band.make_filter(44_100).is_err());
+        band.band_type = BandType::LowShelf;
+        assert!(band.make_filter(44_100).is_ok());
+        band.q_factor = f64::NAN;
+        assert!(band.make_filter(44_100).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: A serial chain of processing blocks of any kind, filters, dynamics,
+//!              effects, or other chains, each sample through each stage in order,
+//!              that is itself a ProcessingBlock:
+//!
+//!                 input  ->  stage 0  ->  stage 1  -> ... ->  stage n - 1  ->  output
+//!
+//!              The stages are boxed, Box<dyn ProcessingBlock>, so the chain is built
+//!              and changed at run time, like the insert slots of a mixer channel,
+//!              with push(), insert(), remove() and replace(). A stage that is
+//!              inserted or replaced starts from its own state, the other stages
+//!              keep theirs.
+//!              The FilterChain of iir_filter.rs is the cascade of IIR sections of
+//!              a single design, with its transfer function and its frequency
+//!              response, a ProcessingChain has only the processing. The effect
+//!              presets of effects/presets.rs are ProcessingChains.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use crate::iir_filter::Float; // Trait
+use crate::iir_filter::ProcessingBlock; // Trait
+
+
+/// The stages of a chain, in the order of the processing.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut chain = ProcessingChain::new();
+///    >>> chain.push(make_highpass(80.0, 48_000, None));
+///    >>> chain.push(compressor);
+///    >>> chain.insert(1, make_peak(3_000.0, 48_000, -4.0, Some(2.0)))?;
+///    >>> chain.process_inplace(& mut samples);
+///    ```
+///
+pub struct ProcessingChain<T: Float = f64> {
+    stages: Vec<Box<dyn ProcessingBlock<T>>>,
+}
+
+impl<T: Float> Default for ProcessingChain<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Float> ProcessingChain<T> {
+    /// An empty chain, that passes the samples unchanged.
+    pub fn new() -> Self {
+        ProcessingChain { stages: Vec::new() }
+    }
+
+    pub fn from_stages(stages: Vec<Box<dyn ProcessingBlock<T>>>) -> Self {
+        ProcessingChain { stages }
+    }
+
+    pub fn get_num_stages(& self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(& self) -> bool {
+        self.stages.is_empty()
+    }
+
+    pub fn get_stage_mut(& mut self, index: usize) -> Option<& mut (dyn ProcessingBlock<T> + 'static)> {
+        self.stages.get_mut(index).map(|stage| stage.as_mut())
+    }
+
+    /// Adds the stage at the end of the chain.
+    pub fn push<B: ProcessingBlock<T> + 'static>(& mut self, stage: B) {
+        self.stages.push(Box::new(stage));
+    }
+
+    /// Inserts the stage before the stage of the index, or at the end for the
+    /// index of the number of stages.
+    pub fn insert<B: ProcessingBlock<T> + 'static>(& mut self, index: usize, stage: B) -> Result<(), String> {
+        if index > self.stages.len() {
+            return Err(format!("Error: can't insert at the stage {} of a chain of {} stages", index,
+                               self.stages.len()));
+        }
+        self.stages.insert(index, Box::new(stage));
+        Ok(())
+    }
+
+    /// Removes the stage of the index, and returns it.
+    pub fn remove(& mut self, index: usize) -> Result<Box<dyn ProcessingBlock<T>>, String> {
+        self.check_index(index)?;
+        Ok(self.stages.remove(index))
+    }
+
+    /// Replaces the stage of the index, and returns the previous one.
+    pub fn replace<B: ProcessingBlock<T> + 'static>(& mut self, index: usize, stage: B)
+            -> Result<Box<dyn ProcessingBlock<T>>, String> {
+        self.check_index(index)?;
+        Ok(std::mem::replace(& mut self.stages[index], Box::new(stage)))
+    }
+
+    fn check_index(& self, index: usize) -> Result<(), String> {
+        if index >= self.stages.len() {
+            return Err(format!("Error: no stage {} in a chain of {} stages", index, self.stages.len()));
+        }
+        Ok(())
+    }
+}
+
+impl<T: Float> ProcessingBlock<T> for ProcessingChain<T> {
+    fn process(& mut self, sample: T) -> T {
+        self.stages.iter_mut().fold(sample, |x, stage| stage.process(x))
+    }
+
+    fn process_buffer(& mut self, input: & [T], output: & mut [T]) {
+        assert_eq!(input.len(), output.len());
+        output.copy_from_slice(input);
+        self.process_inplace(output);
+    }
+
+    // The block through each stage, the same output as each sample through the chain.
+    fn process_inplace(& mut self, samples: & mut [T]) {
+        for stage in self.stages.iter_mut() {
+            stage.process_inplace(samples);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_highpass;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::butterworth_filter::make_peak;
+
+    struct Gain(f64);
+
+    impl ProcessingBlock for Gain {
+        fn process(& mut self, sample: f64) -> f64 {
+            self.0 * sample
+        }
+    }
+
+    #[test]
+    fn test_chain_is_serial() {
+        let signal: Vec<f64> = (0..2_000).map(|n| f64::sin(n as f64 * 0.07) + 0.5 * f64::sin(n as f64 * 1.3)).collect();
+        let mut chain = ProcessingChain::new();
+        chain.push(make_highpass(100.0, 48_000, None));
+        chain.push(Gain(0.5));
+        chain.push(make_lowpass(2_000.0, 48_000, None));
+
+        let mut highpass = make_highpass(100.0, 48_000, None);
+        let mut lowpass = make_lowpass(2_000.0, 48_000, None);
+        let expected: Vec<f64> = signal.iter().map(|x| lowpass.process(0.5 * highpass.process(*x))).collect();
+        let mut by_block = signal.clone();
+        chain.process_inplace(& mut by_block);
+        assert_eq!(by_block, expected);
+
+        // The chains of the same stages, by sample and by buffer, are the same.
+        let make = || ProcessingChain::from_stages(vec![Box::new(make_peak(1_000.0, 48_000, 6.0, None)) as Box<dyn ProcessingBlock>,
+                                                        Box::new(Gain(2.0))]);
+        let (mut by_sample, mut by_buffer) = (make(), make());
+        let mut output = vec![0.0; signal.len()];
+        by_buffer.process_buffer(& signal, & mut output);
+        assert!(signal.iter().zip(& output).all(|(x, y)| by_sample.process(*x) == *y));
+        let mut empty = ProcessingChain::<f32>::default();
+        assert!(empty.is_empty());
+        assert_eq!(empty.process(0.25), 0.25);
+    }
+
+    #[test]
+    fn test_chain_edits() {
+        let mut chain = ProcessingChain::new();
+        chain.push(Gain(2.0));
+        chain.push(Gain(3.0));
+        chain.insert(0, Gain(5.0)).unwrap();
+        chain.insert(3, Gain(7.0)).unwrap();
+        assert_eq!((chain.get_num_stages(), chain.process(1.0)), (4, 210.0));
+        assert!(chain.insert(5, Gain(1.0)).is_err());
+
+        let mut removed = chain.remove(1).unwrap();
+        assert_eq!((removed.process(1.0), chain.process(1.0)), (2.0, 105.0));
+        let mut replaced = chain.replace(2, Gain(-1.0)).unwrap();
+        assert_eq!((replaced.process(1.0), chain.process(1.0)), (7.0, -15.0));
+        assert_eq!(chain.get_stage_mut(0).unwrap().process(1.0), 5.0);
+        assert!(chain.get_stage_mut(3).is_none());
+        assert!(chain.remove(3).is_err());
+        assert!(chain.replace(3, Gain(1.0)).is_err());
+    }
+}
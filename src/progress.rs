@@ -0,0 +1,178 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Progress reporting and cancellation of the long offline jobs, like
+//!              the rendering of an album, see album.rs, for the progress bar and
+//!              the cancel button of a GUI or of a CLI.
+//!              A job takes a JobControl, with an optional progress callback and
+//!              an optional CancellationToken. The job calls update() after each
+//!              block of work, the callback gets the Progress, the work done and
+//!              the total, the percent and the estimated time to the end, and when
+//!              the token was cancelled update() returns an error, that the job
+//!              returns before the next block. The cancellation is cooperative, the
+//!              job stops at the end of a block, and the files of a job aren't
+//!              written when it is cancelled.
+//!              The CancellationToken is a shared atomic flag, its clones are moved
+//!              to the thread of the UI, or to a Ctrl-C handler, and cancel the job
+//!              running in another thread.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+
+/// The error of a job that was cancelled.
+pub const CANCELLED_ERROR: &str = "Error: the job was cancelled";
+
+// The progress callback of a job.
+type ProgressCallback<'a> = Box<dyn FnMut(& Progress) + 'a>;
+
+/// A flag shared by its clones, that cancels the jobs that have it.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken { cancelled: Arc::new(AtomicBool::new(false)) }
+    }
+
+    /// Cancels the jobs, from any thread.
+    pub fn cancel(& self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(& self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// The progress of a job, the work done of the total, in the units of the job,
+/// like the frames of an album, and the time since the start.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    pub done:    usize,
+    pub total:   usize,
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    /// The percent of the work done, 100 for a job without work.
+    pub fn get_percent(& self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            100.0 * self.done as f64 / self.total as f64
+        }
+    }
+
+    /// The estimated time to the end, at the average speed since the start, None
+    /// before the first work done.
+    pub fn get_eta(& self) -> Option<Duration> {
+        if self.done == 0 {
+            return None;
+        }
+        let remaining = self.total.saturating_sub(self.done) as f64 / self.done as f64;
+        Some(self.elapsed.mul_f64(remaining))
+    }
+}
+
+/// The progress callback and the cancellation token of a job.
+///
+/// In Rust:
+///    ```text
+///    >>> let token = CancellationToken::new();
+///    >>> let mut control = JobControl::new();
+///    >>> control.set_progress_callback(|progress| println!("{:.0} %", progress.get_percent()));
+///    >>> control.set_cancellation_token(Some(token.clone()));
+///    >>> // The UI thread calls token.cancel() to stop the job.
+///    >>> let rendered = process_album_with_control(& tracks, make_processor, & mut control)?;
+///    ```
+///
+pub struct JobControl<'a> {
+    on_progress: Option<ProgressCallback<'a>>,
+    token:       Option<CancellationToken>,
+    start:       Instant,
+}
+
+impl Default for JobControl<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> JobControl<'a> {
+    /// A control without a callback and without a token, the job runs to the end.
+    /// The clock of the ETA starts here.
+    pub fn new() -> Self {
+        JobControl { on_progress: None, token: None, start: Instant::now() }
+    }
+
+    pub fn set_progress_callback<F>(& mut self, on_progress: F)
+            where F: FnMut(& Progress) + 'a {
+        self.on_progress = Some(Box::new(on_progress));
+    }
+
+    pub fn set_cancellation_token(& mut self, token: Option<CancellationToken>) {
+        self.token = token;
+    }
+
+    pub fn is_cancelled(& self) -> bool {
+        self.token.as_ref().is_some_and(|token| token.is_cancelled())
+    }
+
+    /// Reports the work done of the total to the callback, and returns
+    /// CANCELLED_ERROR when the job was cancelled.
+    pub fn update(& mut self, done: usize, total: usize) -> Result<(), String> {
+        if let Some(on_progress) = self.on_progress.as_mut() {
+            on_progress(& Progress { done, total, elapsed: self.start.elapsed() });
+        }
+        if self.is_cancelled() {
+            return Err(CANCELLED_ERROR.to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        let progress = Progress { done: 25, total: 100, elapsed: Duration::from_secs(10) };
+        assert_eq!(progress.get_percent(), 25.0);
+        assert_eq!(progress.get_eta(), Some(Duration::from_secs(30)));
+        assert_eq!(Progress { done: 0, ..progress }.get_eta(), None);
+        assert_eq!(Progress { done: 0, total: 0, ..progress }.get_percent(), 100.0);
+    }
+
+    #[test]
+    fn test_job_control() {
+        let mut reports = Vec::new();
+        let token = CancellationToken::new();
+        {
+            let mut control = JobControl::new();
+            control.set_progress_callback(|progress| reports.push((progress.done, progress.total)));
+            control.set_cancellation_token(Some(token.clone()));
+            assert!(control.update(1, 3).is_ok());
+            // Cancelled from another thread.
+            let other = token.clone();
+            std::thread::spawn(move || other.cancel()).join().unwrap();
+            assert_eq!(control.update(2, 3), Err(CANCELLED_ERROR.to_string()));
+            assert!(control.is_cancelled());
+        }
+        assert_eq!(reports, vec![(1, 3), (2, 3)]);
+        assert!(JobControl::new().update(1, 1).is_ok());
+    }
+}
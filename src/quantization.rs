@@ -0,0 +1,322 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Impact of the quantization of the coefficients of a biquad cascade.
+//!              A filter designed in f64 runs on an embedded target with f32 or
+//!              fixed-point coefficients. The rounding moves the poles and the
+//!              zeros, most of all the poles near z = 1 of a low frequency filter:
+//!              the pole radius comes from a2 = r^2 and the angle from
+//!              a1 = -2 r cos(w), and for a small w a small change of a1 is a big
+//!              change of the frequency. analyze_quantization() rounds the
+//!              normalized coefficients, see coefficient_export.rs, and reports:
+//!
+//!                 - the largest deviation of the magnitude response in dB and of the
+//!                   phase in degrees, on a logarithmic frequency grid, where the
+//!                   magnitude is above -60 dB of its peak;
+//!                 - the largest displacement of a pole, and the largest radius of
+//!                   the quantized poles, a radius of 1 or more is unstable.
+//!
+//!              pair_and_order_sections() rebuilds the cascade from its poles and
+//!              zeros, like scipy.signal.zpk2sos: each pole pair, from the one
+//!              nearest the unit circle, takes the nearest zero pair, and the
+//!              sections are ordered with the poles nearest the unit circle last.
+//!              The gain is distributed so that each section, but the last, has a
+//!              peak gain of 0 dB. The b coefficients then use all the range of a
+//!              fixed-point format, and the intermediate signals don't overflow.
+//!              best_sections() keeps the cascade of smaller error.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Oppenheim, A. V., Schafer, R. W. - Discrete-Time Signal Processing.
+//!       Chapter 6.7, Effects of coefficient quantization
+//!
+//!    2. scipy.signal.zpk2sos
+//!       https://docs.scipy.org/doc/scipy/reference/generated/scipy.signal.zpk2sos.html
+//!
+
+
+use rustfft::num_complex::Complex;
+
+use crate::iir_filter::IIRFilter;
+use crate::coefficient_export::FixedPointFormat;
+use crate::coefficient_export::normalized_biquad_coefficients;
+use crate::filter_morph::quadratic_roots;
+
+
+// Points of the logarithmic frequency grid, from 10 Hz to Nyquist.
+const NUM_FREQUENCIES: usize = 512;
+const LOWEST_FREQUENCY: f64 = 10.0;
+// The errors are measured where the magnitude is above this, relative to the peak.
+const MIN_RELATIVE_MAGNITUDE: f64 = 1e-3;
+
+/// The precision of the coefficients on the target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoefficientPrecision {
+    Float32,
+    /// The coefficients are divided by 2^post_shift, see export_fixed_point_hex().
+    FixedPoint { format: FixedPointFormat, post_shift: u32 },
+}
+
+impl CoefficientPrecision {
+    /// The value rounded to the precision.
+    pub fn quantize(& self, value: f64) -> Result<f64, String> {
+        match self {
+            CoefficientPrecision::Float32 => Ok(value as f32 as f64),
+            CoefficientPrecision::FixedPoint { format, post_shift } => {
+                let shift = 2.0_f64.powi(*post_shift as i32);
+                let step = 2.0_f64.powi(-(format.fractional_bits as i32));
+                Ok(format.quantize(value / shift)? as f64 * step * shift)
+            },
+        }
+    }
+}
+
+/// The errors of the quantized cascade, against the f64 one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantizationReport {
+    pub max_magnitude_error_db:  f64,
+    pub max_phase_error_degrees: f64,
+    pub max_pole_displacement:   f64,
+    pub max_pole_radius:         f64,
+    /// The quantized {b0, b1, b2, a1, a2} of each section.
+    pub sections:                Vec<[f64; 5]>,
+}
+
+impl QuantizationReport {
+    pub fn is_stable(& self) -> bool {
+        self.max_pole_radius < 1.0
+    }
+}
+
+/// Quantizes the cascade of biquads and measures the errors.
+///
+/// In Rust:
+///    ```text
+///    >>> let highpass = make_highpass(20.0, 48_000, None);
+///    >>> let q31 = CoefficientPrecision::FixedPoint { format: FixedPointFormat::make_q31(), post_shift: 1 };
+///    >>> let report = analyze_quantization(& [& highpass], 48_000, q31)?;
+///    >>> println!("{:.6} dB, pole moved {:e}", report.max_magnitude_error_db, report.max_pole_displacement);
+///    ```
+///
+pub fn analyze_quantization(filters: & [& IIRFilter], sample_rate: u32, precision: CoefficientPrecision)
+        -> Result<QuantizationReport, String> {
+    if filters.is_empty() {
+        return Err("Error: no filters to analyze".to_string());
+    }
+    let reference: Vec<[f64; 5]> = filters.iter()
+        .map(|filter| normalized_biquad_coefficients(filter))
+        .collect::<Result<_, _>>()?;
+    let sections: Vec<[f64; 5]> = reference.iter()
+        .map(|section| {
+            let mut quantized = [0.0; 5];
+            for (q, c) in quantized.iter_mut().zip(section) {
+                *q = precision.quantize(*c)?;
+            }
+            Ok(quantized)
+        })
+        .collect::<Result<_, String>>()?;
+
+    let frequencies = frequency_grid(sample_rate);
+    let reference_response: Vec<Complex<f64>> = frequencies.iter()
+        .map(|f| cascade_response(& reference, *f, sample_rate))
+        .collect();
+    let quantized_response: Vec<Complex<f64>> = frequencies.iter()
+        .map(|f| cascade_response(& sections, *f, sample_rate))
+        .collect();
+    let peak = reference_response.iter().fold(0.0, |max: f64, h| max.max(h.norm()));
+    let (mut max_magnitude_error_db, mut max_phase_error_degrees) = (0.0_f64, 0.0_f64);
+    for (h, q) in reference_response.iter().zip(& quantized_response) {
+        if h.norm() < MIN_RELATIVE_MAGNITUDE * peak {
+            continue;
+        }
+        // The ratio gives both errors, without the wrapping of the phase.
+        let ratio = q / h;
+        max_magnitude_error_db = max_magnitude_error_db.max((20.0 * ratio.norm().log10()).abs());
+        max_phase_error_degrees = max_phase_error_degrees.max(ratio.arg().abs().to_degrees());
+    }
+
+    let (mut max_pole_displacement, mut max_pole_radius) = (0.0_f64, 0.0_f64);
+    for (r, q) in reference.iter().zip(& sections) {
+        let [r0, r1] = quadratic_roots(r[3], r[4]);
+        let [q0, q1] = quadratic_roots(q[3], q[4]);
+        // The nearest assignment of the quantized poles to the reference ones.
+        let displacement = f64::min(f64::max((r0 - q0).norm(), (r1 - q1).norm()),
+                                    f64::max((r0 - q1).norm(), (r1 - q0).norm()));
+        max_pole_displacement = max_pole_displacement.max(displacement);
+        max_pole_radius = max_pole_radius.max(q0.norm()).max(q1.norm());
+    }
+
+    Ok(QuantizationReport {
+        max_magnitude_error_db,
+        max_phase_error_degrees,
+        max_pole_displacement,
+        max_pole_radius,
+        sections,
+    })
+}
+
+/// Pairs the poles and the zeros of the cascade again and orders its sections,
+/// the cascade has the same response.
+pub fn pair_and_order_sections(filters: & [& IIRFilter], sample_rate: u32) -> Result<Vec<IIRFilter>, String> {
+    if filters.is_empty() {
+        return Err("Error: no filters to pair".to_string());
+    }
+    let mut gain = 1.0;
+    let mut pole_pairs = Vec::new();
+    let mut zero_pairs = Vec::new();
+    for filter in filters {
+        let [b0, b1, b2, a1, a2] = normalized_biquad_coefficients(filter)?;
+        if b0 == 0.0 {
+            return Err("Error: can't pair a biquad with b0 = 0, it has a zero at infinity".to_string());
+        }
+        gain *= b0;
+        pole_pairs.push(quadratic_roots(a1, a2));
+        zero_pairs.push(quadratic_roots(b1 / b0, b2 / b0));
+    }
+
+    let radius = |pair: & [Complex<f64>; 2]| f64::max(pair[0].norm(), pair[1].norm());
+    // From the poles nearest the unit circle, each takes the nearest zeros.
+    pole_pairs.sort_by(|p, q| radius(q).total_cmp(& radius(p)));
+    let mut pairs = Vec::new();
+    for poles in pole_pairs {
+        let distance = |zeros: & [Complex<f64>; 2]| f64::min((zeros[0] - poles[0]).norm(), (zeros[1] - poles[0]).norm());
+        let nearest = (0..zero_pairs.len())
+            .min_by(|i, j| distance(& zero_pairs[*i]).total_cmp(& distance(& zero_pairs[*j])))
+            .unwrap();
+        pairs.push((poles, zero_pairs.swap_remove(nearest)));
+    }
+    // The poles nearest the unit circle last.
+    pairs.reverse();
+
+    let frequencies = frequency_grid(sample_rate);
+    let num_sections = pairs.len();
+    let mut sections = Vec::with_capacity(num_sections);
+    for (index, (poles, zeros)) in pairs.iter().enumerate() {
+        if index + 1 == num_sections {
+            sections.push(make_section(poles, zeros, gain));
+            break;
+        }
+        // A peak gain of 0 dB.
+        let unit = make_section(poles, zeros, 1.0);
+        let peak = frequencies.iter()
+            .fold(0.0, |max: f64, f| max.max(unit.frequency_response(*f, sample_rate).norm()));
+        sections.push(make_section(poles, zeros, 1.0 / peak));
+        gain *= peak;
+    }
+    Ok(sections)
+}
+
+/// The cascade, original or paired and ordered, with the smaller magnitude error
+/// at the precision, and its report.
+pub fn best_sections(filters: & [& IIRFilter], sample_rate: u32, precision: CoefficientPrecision)
+        -> Result<(Vec<[f64; 5]>, QuantizationReport), String> {
+    let original = analyze_quantization(filters, sample_rate, precision);
+    let paired = pair_and_order_sections(filters, sample_rate)
+        .and_then(|sections| analyze_quantization(& sections.iter().collect::<Vec<_>>(), sample_rate, precision));
+    let report = match (original, paired) {
+        (Ok(original), Ok(paired)) => {
+            let is_better = (paired.is_stable() && !original.is_stable())
+                || (paired.is_stable() == original.is_stable()
+                    && paired.max_magnitude_error_db < original.max_magnitude_error_db);
+            if is_better { paired } else { original }
+        },
+        (Ok(report), Err(_)) | (Err(_), Ok(report)) => report,
+        (Err(error), Err(_)) => return Err(error),
+    };
+    Ok((report.sections.clone(), report))
+}
+
+fn make_section(poles: & [Complex<f64>; 2], zeros: & [Complex<f64>; 2], gain: f64) -> IIRFilter {
+    let [p0, p1] = poles;
+    let [z0, z1] = zeros;
+    let a = [1.0, -(p0 + p1).re, (p0 * p1).re];
+    let b = [gain, -gain * (z0 + z1).re, gain * (z0 * z1).re];
+    let mut filter = IIRFilter::new(2);
+    filter.set_coefficients(& a, & b).unwrap();
+    filter
+}
+
+/// The logarithmic frequency grid of the errors, from 10 Hz to Nyquist.
+pub fn frequency_grid(sample_rate: u32) -> Vec<f64> {
+    let highest = 0.999 * sample_rate as f64 / 2.0;
+    let ratio = highest / LOWEST_FREQUENCY;
+    (0..NUM_FREQUENCIES)
+        .map(|n| LOWEST_FREQUENCY * ratio.powf(n as f64 / (NUM_FREQUENCIES - 1) as f64))
+        .collect()
+}
+
+fn cascade_response(sections: & [[f64; 5]], frequency: f64, sample_rate: u32) -> Complex<f64> {
+    let z_inv = Complex::from_polar(1.0, -std::f64::consts::TAU * frequency / sample_rate as f64);
+    sections.iter().fold(Complex::new(1.0, 0.0), |h, [b0, b1, b2, a1, a2]| {
+        let numerator = *b0 + z_inv * (*b1 + z_inv * *b2);
+        let denominator = 1.0 + z_inv * (*a1 + z_inv * *a2);
+        h * numerator / denominator
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_highpass;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::butterworth_filter::make_peak;
+
+    #[test]
+    fn test_quantization_errors() {
+        let lowpass = make_lowpass(1_000.0, 48_000, None);
+        let report = analyze_quantization(& [& lowpass], 48_000, CoefficientPrecision::Float32).unwrap();
+        assert!(report.max_magnitude_error_db < 1e-4);
+        assert!(report.max_pole_displacement < 1e-6);
+        assert!(report.is_stable());
+        assert_eq!(report.sections[0][3], normalized_biquad_coefficients(& lowpass).unwrap()[3] as f32 as f64);
+
+        // A 20 Hz high-pass, its poles are near z = 1 .
+        let highpass = make_highpass(20.0, 48_000, None);
+        let error = |fractional_bits| {
+            let precision = CoefficientPrecision::FixedPoint {
+                format: FixedPointFormat::new(1, fractional_bits), post_shift: 1 };
+            analyze_quantization(& [& highpass], 48_000, precision).unwrap()
+        };
+        let (q15, q31) = (error(15), error(31));
+        assert!(q15.max_magnitude_error_db > 1.0, "{:?}", q15);
+        assert!(q31.max_magnitude_error_db < 0.01, "{:?}", q31);
+        assert!(q15.max_pole_displacement > 1000.0 * q31.max_pole_displacement);
+
+        // a1 is near -2, it needs the post shift.
+        let precision = CoefficientPrecision::FixedPoint { format: FixedPointFormat::make_q31(), post_shift: 0 };
+        assert!(analyze_quantization(& [& highpass], 48_000, precision).is_err());
+        assert!(analyze_quantization(& [], 48_000, CoefficientPrecision::Float32).is_err());
+    }
+
+    #[test]
+    fn test_pair_and_order_sections() {
+        let lowpass = make_lowpass(8_000.0, 48_000, Some(0.6));
+        let peak = make_peak(100.0, 48_000, 12.0, Some(8.0));
+        let filters = [& peak, & lowpass];
+        let sections = pair_and_order_sections(& filters, 48_000).unwrap();
+        assert_eq!(sections.len(), 2);
+
+        // The same response, the poles nearest the unit circle last.
+        let original: Vec<[f64; 5]> = filters.iter().map(|f| normalized_biquad_coefficients(f).unwrap()).collect();
+        let paired: Vec<[f64; 5]> = sections.iter().map(|f| normalized_biquad_coefficients(f).unwrap()).collect();
+        for f in frequency_grid(48_000) {
+            let (h, p) = (cascade_response(& original, f, 48_000), cascade_response(& paired, f, 48_000));
+            assert!((h - p).norm() < 1e-9 * h.norm().max(1.0));
+        }
+        let radius = |s: & [f64; 5]| quadratic_roots(s[3], s[4])[0].norm();
+        assert!(radius(& paired[0]) < radius(& paired[1]));
+        let peak_gain = frequency_grid(48_000).iter()
+            .fold(0.0, |max: f64, f| max.max(sections[0].frequency_response(*f, 48_000).norm()));
+        assert!((peak_gain - 1.0).abs() < 1e-12);
+
+        let q15 = CoefficientPrecision::FixedPoint { format: FixedPointFormat::new(1, 15), post_shift: 2 };
+        let (best, report) = best_sections(& filters, 48_000, q15).unwrap();
+        let original_report = analyze_quantization(& filters, 48_000, q15).unwrap();
+        assert_eq!(best.len(), 2);
+        assert!(report.max_magnitude_error_db <= original_report.max_magnitude_error_db);
+    }
+}
@@ -0,0 +1,218 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Sample rate negotiation of the file pipeline, and a resampler.
+//!              The coefficients of a filter are designed for a sample rate, a
+//!              1 kHz peak designed at 48 kHz is at 918.75 Hz on a 44.1 kHz file,
+//!              with a different Q. The file pipeline compares the rate of the file
+//!              with the design rate of the chain and follows a policy:
+//!
+//!                 - Reject: an error when the rates are different.
+//!                 - Resample: the file is resampled to the design rate on load, and
+//!                   the output is at the design rate.
+//!                 - Redesign: the chain is made again at the rate of the file, for
+//!                   the filters made from frequencies, like a ParametricEq.
+//!
+//!              The factory of the chain gets the sample rate to design at, the
+//!              design rate with Reject and Resample, the rate of the file with
+//!              Redesign.
+//!              The resampler is a band-limited interpolation, each output sample at
+//!              the time t, in samples of the input, is the sum of the input samples
+//!              weighted by a windowed sinc, with a cutoff r below the lower of the
+//!              two Nyquist frequencies:
+//!
+//!                 y(t) = sum_k x[k] r sinc(r (t - k)) w((t - k) r / ZERO_CROSSINGS)
+//!
+//!              With the Blackman window w and 32 zero crossings on each side, the
+//!              transition band is about 0.85 to 1.05 of the lower Nyquist frequency,
+//!              and the stopband is about 74 dB down.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Smith, J. O. - Digital Audio Resampling Home Page
+//!       https://ccrma.stanford.edu/~jos/resample/
+//!
+
+
+use std::f64::consts::PI;
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::audio_buffer::AudioBuffer;
+use crate::audio_io::read_wav;
+use crate::audio_io::write_wav;
+use crate::preview::process_range;
+
+
+// Zero crossings of the sinc on each side of the kernel, at the cutoff.
+const ZERO_CROSSINGS: f64 = 32.0;
+// The cutoff, as a fraction of the lower Nyquist frequency.
+const CUTOFF: f64 = 0.95;
+
+/// What the file pipeline does when the rate of a file isn't the design rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RatePolicy {
+    Reject,
+    Resample,
+    Redesign,
+}
+
+/// The policy with the name, "reject", "resample" or "redesign".
+pub fn parse_rate_policy(name: & str) -> Result<RatePolicy, String> {
+    match name.to_lowercase().as_str() {
+        "reject" => Ok(RatePolicy::Reject),
+        "resample" => Ok(RatePolicy::Resample),
+        "redesign" => Ok(RatePolicy::Redesign),
+        _ => Err(format!("Error: unknown rate policy \"{}\", the policies are reject, resample and redesign", name)),
+    }
+}
+
+/// The signal at the new sample rate, of length round(len * to_rate / from_rate).
+///
+/// In Rust:
+///    ```text
+///    >>> let resampled = resample(& cd_audio, 44_100, 48_000);
+///    ```
+///
+pub fn resample(signal: & [f64], from_rate: u32, to_rate: u32) -> Vec<f64> {
+    assert!(from_rate > 0 && to_rate > 0);
+    if from_rate == to_rate {
+        return signal.to_vec();
+    }
+    let ratio = from_rate as f64 / to_rate as f64;
+    let cutoff = CUTOFF * f64::min(1.0, 1.0 / ratio);
+    let half_length = ZERO_CROSSINGS / cutoff;
+    let num_output = (signal.len() as f64 / ratio).round() as usize;
+    let _span = trace_span!("resample", from_rate, to_rate, samples = signal.len());
+
+    (0..num_output)
+        .map(|n| {
+            let time = n as f64 * ratio;
+            let first = f64::max(0.0, (time - half_length).ceil()) as usize;
+            let last = usize::min(signal.len(), (time + half_length).floor() as usize + 1);
+            (first..last)
+                .map(|k| signal[k] * kernel(time - k as f64, cutoff, half_length))
+                .sum()
+        })
+        .collect()
+}
+
+// The windowed sinc at the distance, in samples of the input.
+fn kernel(distance: f64, cutoff: f64, half_length: f64) -> f64 {
+    let x = cutoff * distance;
+    let sinc = if x.abs() < 1e-12 { 1.0 } else { f64::sin(PI * x) / (PI * x) };
+    // Blackman window, from -half_length to half_length.
+    let phase = PI * distance / half_length;
+    let window = 0.42 + 0.5 * f64::cos(phase) + 0.08 * f64::cos(2.0 * phase);
+    cutoff * sinc * window
+}
+
+/// All the channels of the audio at the new sample rate.
+pub fn resample_audio(audio: & AudioBuffer, to_rate: u32) -> AudioBuffer {
+    let channels = audio.channels.iter().map(|channel| resample(channel, audio.sample_rate, to_rate)).collect();
+    audio.with_channels(to_rate, channels)
+}
+
+/// The audio to process with a chain designed at the design rate, resampled with
+/// the Resample policy. Its sample rate is the one to design the chain at.
+pub fn negotiate_rate(audio: AudioBuffer, design_rate: u32, policy: RatePolicy) -> Result<AudioBuffer, String> {
+    if audio.sample_rate == design_rate {
+        return Ok(audio);
+    }
+    match policy {
+        RatePolicy::Reject => Err(format!("Error: the audio is at {} Hz, the filters are designed at {} Hz",
+                                          audio.sample_rate, design_rate)),
+        RatePolicy::Resample => Ok(resample_audio(& audio, design_rate)),
+        RatePolicy::Redesign => Ok(audio),
+    }
+}
+
+/// Processes a WAV file with a processor for each channel, designed at the
+/// design rate or at the rate of the file, following the policy. The factory is
+/// called with the index of the channel and the sample rate to design at. Writes
+/// the output in the sample format of the input, and returns its sample rate.
+///
+/// In Rust:
+///    ```text
+///    >>> // An EQ tuned at 48 kHz, applied to a 44.1 kHz file.
+///    >>> let rate = process_file("in.wav", "out.wav", 48_000, RatePolicy::Redesign,
+///    >>>     |_, sample_rate| profile.to_parametric_eq(sample_rate).unwrap())?;
+///    ```
+///
+pub fn process_file<B, M>(input_path: & str, output_path: & str, design_rate: u32, policy: RatePolicy,
+                          mut make_processor: M) -> Result<u32, String>
+        where B: ProcessingBlock, M: FnMut(usize, u32) -> B {
+    let (audio, sample_format) = read_wav(input_path)?;
+    let audio = negotiate_rate(audio, design_rate, policy)?;
+    let sample_rate = audio.sample_rate;
+    let output = process_range(& audio, 0.0, None, 0.0, |channel| make_processor(channel, sample_rate))?;
+    write_wav(output_path, & output, sample_format)?;
+    Ok(sample_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::goertzel_amplitude;
+    use crate::audio_io::SampleFormat;
+    use crate::butterworth_filter::make_peak;
+    use crate::fixtures::temp_test_path;
+
+    fn sine(frequency: f64, sample_rate: u32, num_samples: usize) -> Vec<f64> {
+        (0..num_samples).map(|n| f64::sin(2.0 * PI * frequency * n as f64 / sample_rate as f64)).collect()
+    }
+
+    #[test]
+    fn test_resample() {
+        // 44.1 kHz to 48 kHz, away from the edges the sine is the same sine.
+        let output = resample(& sine(1_000.0, 44_100, 44_100), 44_100, 48_000);
+        assert_eq!(output.len(), 48_000);
+        let reference = sine(1_000.0, 48_000, 48_000);
+        let error = output[1_000..47_000].iter().zip(& reference[1_000..47_000])
+            .fold(0.0, |max: f64, (x, y)| max.max((x - y).abs()));
+        assert!(error < 1e-3, "{}", error);
+
+        // 48 kHz to 22.05 kHz, a tone above the new Nyquist frequency is removed.
+        let output = resample(& sine(15_000.0, 48_000, 48_000), 48_000, 22_050);
+        assert_eq!(output.len(), 22_050);
+        // Its alias would be at 22050 - 15000 = 7050 Hz.
+        assert!(goertzel_amplitude(& output[500..21_227], 7_050.0, 22_050) < 3e-4);
+        let output = resample(& sine(5_000.0, 48_000, 48_000), 48_000, 22_050);
+        assert!((goertzel_amplitude(& output[500..21_227], 5_000.0, 22_050) - 1.0).abs() < 2e-3);
+    }
+
+    #[test]
+    fn test_rate_policies() {
+        let audio = AudioBuffer::new(44_100, vec![sine(1_000.0, 44_100, 4_410)]);
+        assert!(negotiate_rate(audio.clone(), 48_000, RatePolicy::Reject).is_err());
+        assert_eq!(negotiate_rate(audio.clone(), 44_100, RatePolicy::Reject).unwrap().sample_rate, 44_100);
+        assert_eq!(negotiate_rate(audio.clone(), 48_000, RatePolicy::Resample).unwrap().get_num_frames(), 4_800);
+        assert_eq!(negotiate_rate(audio.clone(), 48_000, RatePolicy::Redesign).unwrap().sample_rate, 44_100);
+
+        let (input, output) = (& temp_test_path("rate_policy_input.wav"), & temp_test_path("rate_policy_output.wav"));
+        write_wav(input, & audio, SampleFormat::Float32).unwrap();
+        let mut rates = Vec::new();
+        let rate = process_file(input, output, 48_000, RatePolicy::Redesign, |_, sample_rate| {
+            rates.push(sample_rate);
+            make_peak(1_000.0, sample_rate, 6.0, None)
+        }).unwrap();
+        assert_eq!((rate, rates), (44_100, vec![44_100]));
+        // The peak is at the 1 kHz of the sine, +6 dB.
+        let (processed, _) = read_wav(output).unwrap();
+        assert!((goertzel_amplitude(& processed.channels[0][2_205..], 1_000.0, 44_100) - 2.0).abs() < 0.01);
+
+        let rate = process_file(input, output, 48_000, RatePolicy::Resample, |_, sample_rate| {
+            make_peak(1_000.0, sample_rate, 6.0, None)
+        }).unwrap();
+        assert_eq!(rate, 48_000);
+        assert_eq!(read_wav(output).unwrap().0.sample_rate, 48_000);
+        assert!(process_file(input, output, 48_000, RatePolicy::Reject, |_, sample_rate| {
+            make_peak(1_000.0, sample_rate, 6.0, None)
+        }).is_err());
+        assert_eq!(parse_rate_policy("Resample"), Ok(RatePolicy::Resample));
+        assert!(parse_rate_policy("drop").is_err());
+    }
+}
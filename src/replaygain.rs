@@ -0,0 +1,168 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: ReplayGain 2.0 scanning, the loudness tags of a music library.
+//!              ReplayGain 2.0 measures the loudness with the BS.1770 meter of
+//!              loudness.rs, and the gain of a track is the one that brings it to
+//!              the reference of -18 LUFS. The album gain is the gain of all the
+//!              tracks of the album as one program: the gating blocks of all the
+//!              tracks are gated together, so the quiet songs of an album stay
+//!              quieter than the loud ones. The peak is the true peak, linear, a
+//!              player lowers the gain when the gain times the peak is above 1.
+//!
+//!              The values are written in a sidecar text file next to each track,
+//!              with the names of the tags of the players:
+//!
+//!                 REPLAYGAIN_TRACK_GAIN=-4.27 dB
+//!                 REPLAYGAIN_TRACK_PEAK=0.988553
+//!                 REPLAYGAIN_ALBUM_GAIN=-3.90 dB
+//!                 REPLAYGAIN_ALBUM_PEAK=0.998012
+//!                 REPLAYGAIN_REFERENCE_LOUDNESS=-18.00 LUFS
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. ReplayGain 2.0 specification - Hydrogenaudio Knowledgebase
+//!       https://wiki.hydrogenaud.io/index.php?title=ReplayGain_2.0_specification
+//!
+
+
+use crate::audio_buffer::AudioBuffer;
+use crate::audio_io::read_wav;
+use crate::loudness::gated_loudness;
+use crate::loudness::gating_block_powers;
+use crate::loudness::true_peak;
+
+
+/// Loudness of the ReplayGain 2.0 reference, in LUFS.
+pub const REFERENCE_LUFS: f64 = -18.0;
+
+/// The gain and peak of a track or an album.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayGain {
+    pub loudness_lufs: f64,
+    pub gain_db:       f64,
+    /// True peak, linear.
+    pub peak:          f64,
+}
+
+impl ReplayGain {
+    fn from_blocks(block_powers: & [f64], peak: f64) -> Result<Self, String> {
+        let loudness_lufs = gated_loudness(block_powers);
+        if !loudness_lufs.is_finite() {
+            return Err("Error: the audio is silent or shorter than 400 ms, it has no loudness".to_string());
+        }
+        Ok(ReplayGain { loudness_lufs, gain_db: REFERENCE_LUFS - loudness_lufs, peak })
+    }
+}
+
+/// The ReplayGain of each track and of the album of all of them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayGainScan {
+    pub tracks: Vec<ReplayGain>,
+    pub album:  ReplayGain,
+}
+
+impl ReplayGainScan {
+    /// The tags of the track, in the sidecar format.
+    pub fn format_tags(& self, track: usize) -> String {
+        let track = & self.tracks[track];
+        format!("REPLAYGAIN_TRACK_GAIN={:.2} dB\nREPLAYGAIN_TRACK_PEAK={:.6}\n\
+                 REPLAYGAIN_ALBUM_GAIN={:.2} dB\nREPLAYGAIN_ALBUM_PEAK={:.6}\n\
+                 REPLAYGAIN_REFERENCE_LOUDNESS={:.2} LUFS\n",
+                track.gain_db, track.peak, self.album.gain_db, self.album.peak, REFERENCE_LUFS)
+    }
+}
+
+fn audio_true_peak(audio: & AudioBuffer) -> f64 {
+    audio.channels.iter().map(|channel| true_peak(channel)).fold(0.0, f64::max)
+}
+
+/// The ReplayGain of one track.
+pub fn track_gain(audio: & AudioBuffer) -> Result<ReplayGain, String> {
+    ReplayGain::from_blocks(& gating_block_powers(audio), audio_true_peak(audio))
+}
+
+/// The ReplayGain of the tracks and of the album, the tracks can have different
+/// sample rates.
+///
+/// In Rust:
+///    ```text
+///    >>> let scan = scan_album(& tracks)?;
+///    >>> println!("album gain {:.2} dB", scan.album.gain_db);
+///    ```
+///
+pub fn scan_album(tracks: & [AudioBuffer]) -> Result<ReplayGainScan, String> {
+    if tracks.is_empty() {
+        return Err("Error: the album has no tracks".to_string());
+    }
+    let mut album_blocks = Vec::new();
+    let mut album_peak: f64 = 0.0;
+    let mut results = Vec::with_capacity(tracks.len());
+    for (index, audio) in tracks.iter().enumerate() {
+        let blocks = gating_block_powers(audio);
+        let peak = audio_true_peak(audio);
+        results.push(ReplayGain::from_blocks(& blocks, peak).map_err(|error| format!("{} (track {})", error, index))?);
+        album_blocks.extend(blocks);
+        album_peak = album_peak.max(peak);
+    }
+    Ok(ReplayGainScan { tracks: results, album: ReplayGain::from_blocks(& album_blocks, album_peak)? })
+}
+
+/// Path of the sidecar file of a track.
+pub fn sidecar_path(path: & str) -> String {
+    format!("{}.replaygain.txt", path)
+}
+
+/// Scans the WAV files as an album and writes the sidecar file of each one.
+pub fn tag_files(paths: & [String]) -> Result<ReplayGainScan, String> {
+    let tracks = paths.iter()
+        .map(|path| read_wav(path).map(|(audio, _)| audio))
+        .collect::<Result<Vec<AudioBuffer>, String>>()?;
+    let scan = scan_album(& tracks).map_err(|error| format!("{} of {:?}", error, paths))?;
+    for (index, path) in paths.iter().enumerate() {
+        let sidecar = sidecar_path(path);
+        std::fs::write(& sidecar, scan.format_tags(index))
+            .map_err(|error| format!("Error: writing {}: {}", sidecar, error))?;
+    }
+    Ok(scan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::TAU;
+    use crate::loudness::integrated_loudness;
+
+    fn sine(amplitude: f64, seconds: usize) -> AudioBuffer {
+        let channel: Vec<f64> = (0..seconds * 48_000)
+            .map(|n| amplitude * f64::sin(TAU * 1_000.0 * n as f64 / 48_000.0))
+            .collect();
+        AudioBuffer::new(48_000, vec![channel.clone(), channel])
+    }
+
+    #[test]
+    fn test_track_and_album_gain() {
+        // Stereo 1 kHz sine at -23 dBFS is -23 LUFS, 5 dB under the reference.
+        let quiet = sine(f64::powf(10.0, -23.0 / 20.0), 4);
+        let loud = sine(f64::powf(10.0, -13.0 / 20.0), 12);
+        let scan = scan_album(& [quiet.clone(), loud.clone()]).unwrap();
+        assert!((scan.tracks[0].gain_db - 5.0).abs() < 0.05);
+        assert!((scan.tracks[1].gain_db + 5.0).abs() < 0.05);
+        assert_eq!(scan.tracks[0], track_gain(& quiet).unwrap());
+        // The album is the two tracks as one program.
+        let mut both = quiet.clone();
+        for (channel, other) in both.channels.iter_mut().zip(& loud.channels) {
+            channel.extend(other);
+        }
+        assert!((scan.album.loudness_lufs - integrated_loudness(& both)).abs() < 0.05);
+        assert!(scan.album.gain_db < -3.0 && scan.album.gain_db > -5.0);
+        assert_eq!(scan.album.peak, scan.tracks[1].peak);
+
+        assert!(track_gain(& sine(0.0, 1)).is_err());
+        assert!(scan_album(& []).is_err());
+    }
+}
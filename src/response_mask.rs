@@ -0,0 +1,140 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Frequency response tolerance mask checking.
+//!              A mask is a list of (frequency in Hz, lower bound in dB, upper bound
+//!              in dB) points, like the tolerance tables of the weighting curves
+//!              (IEC 61672 A-weighting, RIAA) or of a compliance spec. The check
+//!              gives every mask point where the magnitude response is outside of
+//!              the bounds, with by how much.
+//!
+//!              The response can be given as a function, for the filters with an
+//!              analytic response, or measured from the impulse response of any
+//!              ProcessingBlock, with the DFT evaluated at the exact mask frequencies:
+//!
+//!                 H(f) = sum_n h[n] e^(-j 2 pi f n / fs)
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use std::f64::consts::TAU;
+use rustfft::num_complex::Complex;
+
+use crate::iir_filter::ProcessingBlock; // Trait
+
+
+/// Length of the impulse response measured by check_against_mask().
+pub const MASK_IMPULSE_RESPONSE_LENGTH: usize = 65_536;
+
+/// A mask point where the response is out of the bounds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaskViolation {
+    pub frequency:   f64,
+    pub response_db: f64,
+    pub lower_db:    f64,
+    pub upper_db:    f64,
+}
+
+impl MaskViolation {
+    /// How far outside of the bounds the response is, in dB.
+    pub fn get_excess_db(& self) -> f64 {
+        if self.response_db > self.upper_db {
+            self.response_db - self.upper_db
+        } else {
+            self.lower_db - self.response_db
+        }
+    }
+
+    pub fn describe(& self) -> String {
+        let side = if self.response_db > self.upper_db { "above" } else { "below" };
+        format!("{} Hz: {:.3} dB is {:.3} dB {} the bounds [{}, {}] dB",
+                self.frequency, self.response_db, self.get_excess_db(), side, self.lower_db, self.upper_db)
+    }
+}
+
+/// Checks a response function, in dB, against the mask. Gives all the violations.
+pub fn check_response_against_mask(response_db: & dyn Fn(f64) -> f64, mask: & [(f64, f64, f64)])
+        -> Result<(), Vec<MaskViolation>> {
+    let violations: Vec<MaskViolation> = mask.iter()
+        .map(|(frequency, lower_db, upper_db)| {
+            assert!(lower_db <= upper_db);
+            MaskViolation { frequency: *frequency, response_db: response_db(*frequency), lower_db: *lower_db, upper_db: *upper_db }
+        })
+        // A NaN response fails too.
+        .filter(|v| !(v.response_db >= v.lower_db && v.response_db <= v.upper_db))
+        .collect();
+    if violations.is_empty() { Ok(()) } else { Err(violations) }
+}
+
+/// Checks the measured response of the filter against the mask. The filter is
+/// excited with an impulse, so its state changes, use a freshly made filter.
+///
+/// In Rust:
+///    ```text
+///    >>> // Flat within 0.5 dB up to 1 kHz, at least 20 dB down at 10 kHz.
+///    >>> let mask = [(100.0, -0.5, 0.5), (1_000.0, -0.5, 0.5), (10_000.0, -200.0, -20.0)];
+///    >>> if let Err(violations) = check_against_mask(& mut filter, 48_000, & mask) {
+///    >>>     for violation in & violations {
+///    >>>         println!("{}", violation.describe());
+///    >>>     }
+///    >>> }
+///    ```
+///
+pub fn check_against_mask(filter: & mut dyn ProcessingBlock, sample_rate: u32, mask: & [(f64, f64, f64)])
+        -> Result<(), Vec<MaskViolation>> {
+    let impulse_response: Vec<f64> = (0..MASK_IMPULSE_RESPONSE_LENGTH)
+        .map(|n| filter.process(if n == 0 { 1.0 } else { 0.0 }))
+        .collect();
+    let response_db = |frequency: f64| -> f64 {
+        let w = TAU * frequency / sample_rate as f64;
+        let response: Complex<f64> = impulse_response.iter().enumerate()
+            .map(|(n, h)| Complex::from_polar(*h, -w * n as f64))
+            .sum();
+        20.0 * f64::log10(response.norm())
+    };
+    check_response_against_mask(& response_db, mask)
+}
+
+/// Mask of a target curve of (frequency, dB) points, with a symmetric tolerance.
+pub fn make_mask_from_curve(curve: & [(f64, f64)], tolerance_db: f64) -> Vec<(f64, f64, f64)> {
+    assert!(tolerance_db >= 0.0);
+    curve.iter().map(|(frequency, db)| (*frequency, db - tolerance_db, db + tolerance_db)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_lowpass;
+
+    #[test]
+    fn test_lowpass_against_mask() {
+        let sample_rate = 48_000;
+        let mask = [(20.0, -0.1, 0.1), (1_000.0, -0.5, 0.5), (10_000.0, -200.0, -20.0)];
+        assert!(check_against_mask(& mut make_lowpass(2_000.0, sample_rate, None), sample_rate, & mask).is_ok());
+
+        // A 4 kHz cutoff is not 20 dB down at 10 kHz.
+        let violations = check_against_mask(& mut make_lowpass(4_000.0, sample_rate, None), sample_rate, & mask).unwrap_err();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].frequency, 10_000.0);
+        assert!(violations[0].get_excess_db() > 1.0);
+        assert!(violations[0].describe().contains("above"));
+    }
+
+    #[test]
+    fn test_measured_matches_analytic_response() {
+        let sample_rate = 48_000;
+        let filter = make_lowpass(1_000.0, sample_rate, Some(2.0));
+        let analytic = |frequency: f64| 20.0 * f64::log10(filter.frequency_response(frequency, sample_rate).norm());
+        let curve: Vec<(f64, f64)> = [50.0, 500.0, 1_000.0, 2_000.0, 15_000.0].iter().map(|f| (*f, analytic(*f))).collect();
+        let mask = make_mask_from_curve(& curve, 0.001);
+        assert!(check_response_against_mask(& analytic, & mask).is_ok());
+        assert!(check_against_mask(& mut make_lowpass(1_000.0, sample_rate, Some(2.0)), sample_rate, & mask).is_ok());
+
+        let violations = check_response_against_mask(& |_| f64::NAN, & mask).unwrap_err();
+        assert_eq!(violations.len(), 5);
+    }
+}
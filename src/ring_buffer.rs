@@ -0,0 +1,286 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Single producer, single consumer (SPSC) lock-free ring buffer, to
+//!              move samples between the audio callback thread and the other threads
+//!              (a file reader, a GUI meter, a network stream).
+//!              The audio thread can't wait on a lock, so the two sides only share
+//!              two atomic positions: the producer is the only one that writes the
+//!              write position, and the consumer the only one that writes the read
+//!              position. Neither side allocates or blocks after the creation.
+//!
+//!              The positions grow without limit (with wrap around of usize), the
+//!              capacity is a power of two and the slot of a position is
+//!              position & (capacity - 1). The number of samples in the buffer is
+//!              write - read.
+//!
+//!              The watermark callbacks are called when the fill level crosses a
+//!              level, in the thread that made it cross. A low watermark on the
+//!              consumer warns of an underrun before it happens, a high watermark on
+//!              the producer tells that the consumer is late. The callbacks run in
+//!              the audio thread, so they must be real-time safe too (set an atomic
+//!              flag, don't print or allocate).
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Circular buffer - Wikipedia
+//!       https://en.wikipedia.org/wiki/Circular_buffer
+//!
+//!    2. Real-time audio programming 101: time waits for nothing - Ross Bencina
+//!       http://www.rossbencina.com/code/real-time-audio-programming-101-time-waits-for-nothing
+//!
+
+
+use std::cell::UnsafeCell;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+
+/// Called with the fill level, when it crosses the watermark.
+pub type WatermarkCallback = Box<dyn FnMut(usize) + Send>;
+
+struct Shared<T> {
+    slots: Box<[UnsafeCell<T>]>,
+    mask:  usize,
+    // Total number of samples written and read.
+    write: AtomicUsize,
+    read:  AtomicUsize,
+}
+
+// The producer only writes the free slots and the consumer only reads the used
+// slots, the atomic positions with acquire / release order separate them.
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+/// The writing side of the ring buffer.
+pub struct RingProducer<T> {
+    shared:         Arc<Shared<T>>,
+    high_watermark: Option<(usize, WatermarkCallback)>,
+}
+
+/// The reading side of the ring buffer.
+pub struct RingConsumer<T> {
+    shared:        Arc<Shared<T>>,
+    low_watermark: Option<(usize, WatermarkCallback)>,
+}
+
+/// Creates a ring buffer with at least the capacity, rounded up to a power of two,
+/// and returns its two sides. Each side can be moved to a different thread.
+///
+/// In Rust:
+///    ```text
+///    >>> let (mut producer, mut consumer) = make_ring_buffer::<f64>(4_096);
+///    >>> std::thread::spawn(move || { producer.push_slice(& decoded_block); });
+///    >>> // In the audio callback.
+///    >>> let read = consumer.pop_slice(& mut output);
+///    >>> output[read..].fill(0.0);
+///    ```
+///
+pub fn make_ring_buffer<T: Copy + Default + Send>(capacity: usize) -> (RingProducer<T>, RingConsumer<T>) {
+    assert!(capacity > 0);
+    let capacity = capacity.next_power_of_two();
+    let shared = Arc::new(Shared {
+        slots: (0..capacity).map(|_| UnsafeCell::new(T::default())).collect(),
+        mask:  capacity - 1,
+        write: AtomicUsize::new(0),
+        read:  AtomicUsize::new(0),
+    });
+    (RingProducer { shared: shared.clone(), high_watermark: None },
+     RingConsumer { shared, low_watermark: None })
+}
+
+impl<T> Shared<T> {
+    fn get_capacity(& self) -> usize {
+        self.mask + 1
+    }
+}
+
+impl<T: Copy> RingProducer<T> {
+    pub fn get_capacity(& self) -> usize {
+        self.shared.get_capacity()
+    }
+
+    /// Number of samples that can be pushed now.
+    pub fn get_free_len(& self) -> usize {
+        let write = self.shared.write.load(Ordering::Relaxed);
+        let read = self.shared.read.load(Ordering::Acquire);
+        self.get_capacity() - write.wrapping_sub(read)
+    }
+
+    /// The callback is called when a push makes the fill level reach the level.
+    pub fn set_high_watermark(& mut self, level: usize, callback: WatermarkCallback) {
+        assert!(level > 0 && level <= self.get_capacity());
+        self.high_watermark = Some((level, callback));
+    }
+
+    /// Pushes one sample, or gives it back when the buffer is full.
+    pub fn push(& mut self, value: T) -> Result<(), T> {
+        if self.push_slice(& [value]) == 1 { Ok(()) } else { Err(value) }
+    }
+
+    /// Pushes as many samples of the block as fit, returns how many.
+    pub fn push_slice(& mut self, values: & [T]) -> usize {
+        let write = self.shared.write.load(Ordering::Relaxed);
+        let read = self.shared.read.load(Ordering::Acquire);
+        let used = write.wrapping_sub(read);
+        let count = values.len().min(self.get_capacity() - used);
+        for (i, value) in values[..count].iter().enumerate() {
+            let slot = & self.shared.slots[write.wrapping_add(i) & self.shared.mask];
+            // The slot is free, the consumer doesn't read it until the write position
+            // is published below.
+            unsafe { *slot.get() = *value; }
+        }
+        self.shared.write.store(write.wrapping_add(count), Ordering::Release);
+
+        if let Some((level, callback)) = & mut self.high_watermark {
+            if used < *level && used + count >= *level {
+                callback(used + count);
+            }
+        }
+        count
+    }
+}
+
+impl<T: Copy> RingConsumer<T> {
+    pub fn get_capacity(& self) -> usize {
+        self.shared.get_capacity()
+    }
+
+    /// Number of samples that can be popped now.
+    pub fn get_len(& self) -> usize {
+        let read = self.shared.read.load(Ordering::Relaxed);
+        let write = self.shared.write.load(Ordering::Acquire);
+        write.wrapping_sub(read)
+    }
+
+    pub fn is_empty(& self) -> bool {
+        self.get_len() == 0
+    }
+
+    /// The callback is called when a pop makes the fill level go below the level.
+    pub fn set_low_watermark(& mut self, level: usize, callback: WatermarkCallback) {
+        assert!(level > 0 && level <= self.get_capacity());
+        self.low_watermark = Some((level, callback));
+    }
+
+    /// Pops one sample, None when the buffer is empty.
+    pub fn pop(& mut self) -> Option<T> {
+        let read = self.shared.read.load(Ordering::Relaxed);
+        let write = self.shared.write.load(Ordering::Acquire);
+        if write == read {
+            return None;
+        }
+        let value = unsafe { *self.shared.slots[read & self.shared.mask].get() };
+        self.finish_pop(read, write.wrapping_sub(read), 1);
+        Some(value)
+    }
+
+    /// Pops samples to fill the block, or less when the buffer has less, returns how
+    /// many.
+    pub fn pop_slice(& mut self, output: & mut [T]) -> usize {
+        let read = self.shared.read.load(Ordering::Relaxed);
+        let write = self.shared.write.load(Ordering::Acquire);
+        let used = write.wrapping_sub(read);
+        let count = output.len().min(used);
+        for (i, value) in output[..count].iter_mut().enumerate() {
+            // The slot was published by the producer, it doesn't write it again until
+            // the read position is moved below.
+            *value = unsafe { *self.shared.slots[read.wrapping_add(i) & self.shared.mask].get() };
+        }
+        self.finish_pop(read, used, count);
+        count
+    }
+
+    // Publishes the new read position, and calls the low watermark.
+    fn finish_pop(& mut self, read: usize, used: usize, count: usize) {
+        self.shared.read.store(read.wrapping_add(count), Ordering::Release);
+        if let Some((level, callback)) = & mut self.low_watermark {
+            if used >= *level && used - count < *level {
+                callback(used - count);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_pop_and_wrap_around() {
+        let (mut producer, mut consumer) = make_ring_buffer::<f64>(6);
+        assert_eq!(producer.get_capacity(), 8);
+        assert_eq!(consumer.pop(), None);
+        assert_eq!(producer.push_slice(& [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]), 6);
+        let mut output = [0.0; 4];
+        assert_eq!(consumer.pop_slice(& mut output), 4);
+        assert_eq!(output, [1.0, 2.0, 3.0, 4.0]);
+        // Crosses the end of the slots.
+        assert_eq!(producer.push_slice(& [7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0]), 6);
+        assert_eq!(producer.push(13.0), Err(13.0));
+        assert_eq!(producer.get_free_len(), 0);
+        assert_eq!(consumer.get_len(), 8);
+        let mut output = [0.0; 10];
+        assert_eq!(consumer.pop_slice(& mut output), 8);
+        assert_eq!(output[..8], [5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0]);
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn test_watermarks() {
+        let high_count = Arc::new(AtomicUsize::new(0));
+        let low_count = Arc::new(AtomicUsize::new(0));
+        let (mut producer, mut consumer) = make_ring_buffer::<f64>(16);
+        let counter = high_count.clone();
+        producer.set_high_watermark(12, Box::new(move |_| { counter.fetch_add(1, Ordering::Relaxed); }));
+        let counter = low_count.clone();
+        consumer.set_low_watermark(4, Box::new(move |level| {
+            assert_eq!(level, 3);
+            counter.fetch_add(1, Ordering::Relaxed);
+        }));
+
+        producer.push_slice(& [0.0; 10]);
+        assert_eq!(high_count.load(Ordering::Relaxed), 0);
+        producer.push_slice(& [0.0; 4]);
+        producer.push_slice(& [0.0; 1]);
+        // Only the crossing calls it.
+        assert_eq!(high_count.load(Ordering::Relaxed), 1);
+
+        consumer.pop_slice(& mut [0.0; 11]);
+        assert_eq!(low_count.load(Ordering::Relaxed), 0);
+        consumer.pop();
+        consumer.pop();
+        assert_eq!(low_count.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_threads_keep_the_order() {
+        let total = 100_000;
+        let (mut producer, mut consumer) = make_ring_buffer::<f64>(256);
+        let writer = std::thread::spawn(move || {
+            let mut next = 0;
+            while next < total {
+                let block: Vec<f64> = (next..(next + 100).min(total)).map(|n| n as f64).collect();
+                next += producer.push_slice(& block);
+                std::thread::yield_now();
+            }
+        });
+
+        let mut expected = 0;
+        let mut block = [0.0; 64];
+        while expected < total {
+            let count = consumer.pop_slice(& mut block);
+            for value in & block[..count] {
+                assert_eq!(*value, expected as f64);
+                expected += 1;
+            }
+            std::thread::yield_now();
+        }
+        writer.join().unwrap();
+        assert!(consumer.is_empty());
+    }
+}
@@ -0,0 +1,240 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Round-off noise of the realizations of a biquad.
+//!              The same transfer function has many structures, and with a fixed-point
+//!              arithmetic each one rounds at other points of the signal flow. Each
+//!              rounding adds a white noise of power q^2 / 12, with q the step of the
+//!              format, and the noise goes to the output through the transfer function
+//!              from the point of the rounding. The noise gain is the output noise
+//!              power in units of q^2 / 12. The realizations, with an accumulator
+//!              wider than the samples, rounding once per sum:
+//!
+//!                 - DirectForm1: y = Q(b0 x + b1 x1 + b2 x2 - a1 y1 - a2 y2), the
+//!                   noise goes through 1 / A(z).
+//!                 - DirectForm2: w = Q(x - a1 w1 - a2 w2), y = Q(b0 w + b1 w1 + b2 w2),
+//!                   the noise of w goes through B(z) / A(z).
+//!                 - TransposedDirectForm2: y = Q(b0 x + s1), s1 = Q(b1 x - a1 y + s2),
+//!                   s2 = Q(b2 x - a2 y), three noises through 1 / A(z).
+//!                 - Lattice: the lattice-ladder of time_varying.rs, rounding the
+//!                   forward and backward signals of each stage and the ladder sum.
+//!
+//!              At a low cutoff frequency the poles are near z = 1, and 1 / A(z) has a
+//!              huge gain at low frequencies: a low-pass at 20 Hz and 48 kHz has a
+//!              DC gain of 1 / A(1) of about 10^6. The DF1 and the TDF2 amplify their
+//!              round-off noise by this gain, the DF2 filters it with the low-pass
+//!              B(z) / A(z), but its state w has the same huge gain, and overflows.
+//!              The noise gain is estimated by simulation: white noise through the
+//!              realization with rounded arithmetic and through the f64 IIRFilter, the
+//!              difference is the round-off noise.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Oppenheim, A. V., Schafer, R. W. - Discrete-Time Signal Processing.
+//!       Chapter 6.9, Effects of round-off noise in digital filters
+//!
+//!    2. Jackson, L. B. - Roundoff-Noise Analysis for Fixed-Point Digital Filters
+//!       Realized in Cascade or Parallel Form. IEEE Transactions on Audio and
+//!       Electroacoustics, 1970
+//!
+
+
+use crate::iir_filter::IIRFilter;
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::coefficient_export::normalized_biquad_coefficients;
+use crate::time_varying::LatticeCoefficients;
+use crate::generators::WhiteNoise;
+
+
+/// Samples of the white noise of the simulation.
+pub const DEFAULT_NUM_SAMPLES: usize = 1 << 16;
+
+/// A realization of a biquad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    DirectForm1,
+    DirectForm2,
+    TransposedDirectForm2,
+    Lattice,
+}
+
+pub const TOPOLOGIES: [Topology; 4] =
+    [Topology::DirectForm1, Topology::DirectForm2, Topology::TransposedDirectForm2, Topology::Lattice];
+
+/// The round-off noise of a realization.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoiseAnalysis {
+    pub topology:   Topology,
+    /// Output noise power over q^2 / 12 .
+    pub noise_gain: f64,
+    /// Output noise power in dBFS, for a full scale of 1.
+    pub noise_db:   f64,
+}
+
+impl NoiseAnalysis {
+    pub fn get_noise_gain_db(& self) -> f64 {
+        10.0 * f64::log10(self.noise_gain)
+    }
+}
+
+/// Round-off noise of the biquad in each topology, with the fractional bits of
+/// the fixed-point format.
+///
+/// In Rust:
+///    ```text
+///    >>> let lowpass = make_lowpass(50.0, 48_000, None);
+///    >>> for analysis in analyze_roundoff_noise(& lowpass, 23)? {
+///    >>>     println!("{:?}: {:.1} dB", analysis.topology, analysis.get_noise_gain_db());
+///    >>> }
+///    ```
+///
+pub fn analyze_roundoff_noise(filter: & IIRFilter, fractional_bits: u32) -> Result<Vec<NoiseAnalysis>, String> {
+    TOPOLOGIES.iter()
+        .map(|topology| {
+            let noise_gain = estimate_noise_gain(filter, *topology, fractional_bits, DEFAULT_NUM_SAMPLES)?;
+            let step = f64::powi(2.0, -(fractional_bits as i32));
+            let noise_db = 10.0 * f64::log10(noise_gain * step * step / 12.0);
+            Ok(NoiseAnalysis { topology: *topology, noise_gain, noise_db })
+        })
+        .collect()
+}
+
+/// The topology of lowest round-off noise.
+pub fn quietest_topology(filter: & IIRFilter, fractional_bits: u32) -> Result<NoiseAnalysis, String> {
+    let analyses = analyze_roundoff_noise(filter, fractional_bits)?;
+    Ok(analyses.into_iter().min_by(|a, b| a.noise_gain.total_cmp(& b.noise_gain)).unwrap())
+}
+
+/// Noise gain of the topology, from the simulation with num_samples of white noise.
+pub fn estimate_noise_gain(filter: & IIRFilter, topology: Topology, fractional_bits: u32, num_samples: usize)
+        -> Result<f64, String> {
+    if !(1..=52).contains(& fractional_bits) {
+        return Err(format!("Error: invalid number of fractional bits {}, must be in [1, 52]", fractional_bits));
+    }
+    let coefficients = normalized_biquad_coefficients(filter)?;
+    let step = f64::powi(2.0, -(fractional_bits as i32));
+    let quantize = |x: f64| (x / step).round() * step;
+    let mut realization = QuantizedBiquad::new(topology, coefficients, quantize)?;
+
+    let mut reference = IIRFilter::new(2);
+    let [b0, b1, b2, a1, a2] = coefficients;
+    reference.set_coefficients(& [1.0, a1, a2], & [b0, b1, b2])?;
+
+    let mut random = WhiteNoise::new(1);
+    let mut error_power = 0.0;
+    for _ in 0..num_samples {
+        // On the grid of the format, the input has no quantization noise.
+        let x = quantize(0.5 * (random.next_uniform() - 0.5));
+        let error = realization.process(x) - reference.process(x);
+        error_power += error * error;
+    }
+    Ok(error_power / num_samples as f64 / (step * step / 12.0))
+}
+
+// A biquad with rounded arithmetic.
+struct QuantizedBiquad<Q: Fn(f64) -> f64> {
+    topology:     Topology,
+    coefficients: [f64; 5],
+    lattice:      LatticeCoefficients,
+    quantize:     Q,
+    // The two states of each structure: x1, x2, y1, y2 for the DF1, w1, w2 for the
+    // DF2, s1, s2 for the TDF2 and g0, g1 for the lattice.
+    state:        [f64; 4],
+}
+
+impl<Q: Fn(f64) -> f64> QuantizedBiquad<Q> {
+    fn new(topology: Topology, coefficients: [f64; 5], quantize: Q) -> Result<Self, String> {
+        let [b0, b1, b2, a1, a2] = coefficients;
+        let mut filter = IIRFilter::new(2);
+        filter.set_coefficients(& [1.0, a1, a2], & [b0, b1, b2])?;
+        let lattice = LatticeCoefficients::from_iir_filter(& filter)?;
+        Ok(QuantizedBiquad { topology, coefficients, lattice, quantize, state: [0.0; 4] })
+    }
+
+    fn process(& mut self, x: f64) -> f64 {
+        let [b0, b1, b2, a1, a2] = self.coefficients;
+        let q = & self.quantize;
+        let s = & mut self.state;
+        match self.topology {
+            Topology::DirectForm1 => {
+                let y = q(b0 * x + b1 * s[0] + b2 * s[1] - a1 * s[2] - a2 * s[3]);
+                *s = [x, s[0], y, s[2]];
+                y
+            },
+            Topology::DirectForm2 => {
+                let w = q(x - a1 * s[0] - a2 * s[1]);
+                let y = q(b0 * w + b1 * s[0] + b2 * s[1]);
+                s[1] = s[0];
+                s[0] = w;
+                y
+            },
+            Topology::TransposedDirectForm2 => {
+                let y = q(b0 * x + s[0]);
+                s[0] = q(b1 * x - a1 * y + s[1]);
+                s[1] = q(b2 * x - a2 * y);
+                y
+            },
+            Topology::Lattice => {
+                let LatticeCoefficients { k1, k2, c0, c1, c2 } = self.lattice;
+                let f1 = q(x - k2 * s[1]);
+                let f0 = q(f1 - k1 * s[0]);
+                let g1 = q(k1 * f0 + s[0]);
+                let g2 = q(k2 * f1 + s[1]);
+                let y = q(c0 * f0 + c1 * g1 + c2 * g2);
+                s[0] = f0;
+                s[1] = g1;
+                y
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::butterworth_filter::make_highpass;
+
+    fn noise_gain(filter: & IIRFilter, topology: Topology) -> f64 {
+        estimate_noise_gain(filter, topology, 24, 1 << 15).unwrap()
+    }
+
+    #[test]
+    fn test_low_cutoff_noise() {
+        // The noise of the DF1 goes through 1 / A(z), the DF2 filters it.
+        let lowpass = make_lowpass(20.0, 48_000, None);
+        let df1 = noise_gain(& lowpass, Topology::DirectForm1);
+        let df2 = noise_gain(& lowpass, Topology::DirectForm2);
+        let tdf2 = noise_gain(& lowpass, Topology::TransposedDirectForm2);
+        assert!(df1 > 1_000.0 * df2, "DF1 {} DF2 {}", df1, df2);
+        assert!(tdf2 > 1_000.0 * df2, "TDF2 {} DF2 {}", tdf2, df2);
+        assert_eq!(quietest_topology(& lowpass, 24).unwrap().topology, Topology::DirectForm2);
+
+        // At a high cutoff frequency the realizations are about the same.
+        let lowpass = make_lowpass(8_000.0, 48_000, None);
+        for topology in TOPOLOGIES {
+            let gain = noise_gain(& lowpass, topology);
+            assert!(gain > 0.5 && gain < 10.0, "{:?} {}", topology, gain);
+        }
+    }
+
+    #[test]
+    fn test_noise_analysis() {
+        let highpass = make_highpass(50.0, 48_000, None);
+        let analyses = analyze_roundoff_noise(& highpass, 16).unwrap();
+        assert_eq!(analyses.len(), 4);
+        for analysis in & analyses {
+            // The gain of q^2 / 12 with q = 2^-16 .
+            let expected_db = analysis.get_noise_gain_db() - 10.0 * f64::log10(12.0) - 16.0 * 20.0 * f64::log10(2.0);
+            assert!((analysis.noise_db - expected_db).abs() < 1e-9);
+        }
+        assert!(estimate_noise_gain(& highpass, Topology::Lattice, 0, 1_000).is_err());
+        let mut unstable = IIRFilter::new(2);
+        unstable.set_coefficients(& [1.0, 0.0, 1.5], & [1.0, 0.0, 0.0]).unwrap();
+        assert!(estimate_noise_gain(& unstable, Topology::Lattice, 16, 1_000).is_err());
+    }
+}
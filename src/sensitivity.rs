@@ -0,0 +1,307 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Stability margin and coefficient sensitivity of an IIR filter.
+//!              The poles of a filter with a very low cutoff, relative to the
+//!              sample rate, are near z = 1, and there a small change of a
+//!              coefficient is a big change of the poles: for a biquad the
+//!              radius comes from a2 = r^2 and the angle from a1 = -2 r cos(w), for
+//!              a small w all the information is in the last digits of a1. The
+//!              same happens to the poles of a high order filter in direct form,
+//!              each coefficient moves all of them, and more for the clustered ones.
+//!              analyze_sensitivity() moves each coefficient, normalized by a0, by
+//!              +epsilon and -epsilon, and measures:
+//!
+//!                 - the largest displacement of a pole, and the largest radius of
+//!                   the moved poles, a radius of 1 or more is unstable;
+//!                 - the largest deviation of the magnitude response in dB, on a
+//!                   logarithmic frequency grid, where the magnitude is above -60 dB
+//!                   of its peak, like quantization.rs .
+//!
+//!              The default epsilon is the step of f32 between 1 and 2, the rounding
+//!              of the coefficients of a biquad in f32. The report has warnings
+//!              when the filter is near instability, or when its response changes by
+//!              more than 0.1 dB. Then the coefficients need f64, and a filter of
+//!              high order needs to be split in a cascade of second order sections,
+//!              like the biquads of butterworth_filter.rs .
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Oppenheim, A. V., Schafer, R. W. - Discrete-Time Signal Processing.
+//!       Chapter 6.8, Poles of quantized second order sections
+//!
+//!    2. Durand-Kerner method - Wikipedia
+//!       https://en.wikipedia.org/wiki/Durand%E2%80%93Kerner_method
+//!
+
+
+use std::f64::consts::TAU;
+use rustfft::num_complex::Complex;
+
+use crate::iir_filter::IIRFilter;
+use crate::filter_morph::quadratic_roots;
+use crate::quantization::frequency_grid;
+
+
+/// The step of f32 between 1 and 2, 2^-23.
+pub const DEFAULT_EPSILON: f64 = f32::EPSILON as f64;
+/// Poles nearer than this to the unit circle are near instability.
+pub const NEAR_INSTABILITY_MARGIN: f64 = 1e-3;
+/// The largest deviation of the response, in dB, of a robust design.
+pub const MAX_DEVIATION_DB: f64 = 0.1;
+
+// The deviation is measured where the magnitude is above this, relative to the peak.
+const MIN_RELATIVE_MAGNITUDE: f64 = 1e-3;
+const ROOT_ITERATIONS: usize = 500;
+const ROOT_TOLERANCE: f64 = 1e-15;
+
+/// The effect of moving one coefficient by +epsilon and by -epsilon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoefficientSensitivity {
+    /// "b0" ... "bN", "a1" ... "aN", normalized by a0.
+    pub name:                  String,
+    pub max_pole_displacement: f64,
+    pub max_pole_radius:       f64,
+    pub max_deviation_db:      f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensitivityReport {
+    pub epsilon:               f64,
+    /// Largest radius of the poles of the filter, and its distance to the unit circle.
+    pub pole_radius:           f64,
+    pub stability_margin:      f64,
+    /// The lowest frequency of a pole, from its angle, in Hz.
+    pub lowest_pole_frequency: f64,
+    pub coefficients:          Vec<CoefficientSensitivity>,
+    /// The worst of the coefficients.
+    pub max_pole_displacement: f64,
+    pub max_pole_radius:       f64,
+    pub max_deviation_db:      f64,
+    pub warnings:              Vec<String>,
+}
+
+impl SensitivityReport {
+    /// Whether the poles are near the unit circle, or a perturbation makes the
+    /// filter unstable.
+    pub fn is_near_instability(& self) -> bool {
+        self.stability_margin < NEAR_INSTABILITY_MARGIN || self.max_pole_radius >= 1.0
+    }
+
+    /// Whether the coefficients need more precision, or another structure.
+    pub fn needs_higher_precision(& self) -> bool {
+        self.is_near_instability() || self.max_deviation_db > MAX_DEVIATION_DB
+    }
+
+    /// The displacement of the poles per unit of change of a coefficient.
+    pub fn get_pole_sensitivity(& self) -> f64 {
+        self.max_pole_displacement / self.epsilon
+    }
+}
+
+/// Moves each coefficient of the filter by +epsilon and -epsilon, and measures the
+/// movement of the poles and the deviation of the response.
+///
+/// In Rust:
+///    ```text
+///    >>> let highpass = make_highpass(5.0, 96_000, None);
+///    >>> let report = analyze_sensitivity(& highpass, 96_000, DEFAULT_EPSILON)?;
+///    >>> for warning in & report.warnings {
+///    >>>     println!("{}", warning);
+///    >>> }
+///    ```
+///
+pub fn analyze_sensitivity(filter: & IIRFilter, sample_rate: u32, epsilon: f64) -> Result<SensitivityReport, String> {
+    if filter.order == 0 {
+        return Err("Error: a filter of order 0 has no poles".to_string());
+    }
+    if epsilon.is_nan() || epsilon <= 0.0 {
+        return Err(format!("Error: invalid epsilon {}", epsilon));
+    }
+    let a0 = filter.a_coeffs[0];
+    if a0 == 0.0 {
+        return Err("Error: the filter has a0 equal to 0".to_string());
+    }
+    let a: Vec<f64> = filter.a_coeffs.iter().map(|c| c / a0).collect();
+    let b: Vec<f64> = filter.b_coeffs.iter().map(|c| c / a0).collect();
+    let poles = polynomial_roots(& a);
+    let pole_radius = poles.iter().fold(0.0, |max: f64, pole| max.max(pole.norm()));
+    let lowest_pole_frequency = poles.iter()
+        .fold(f64::INFINITY, |min: f64, pole| min.min(pole.arg().abs() / TAU * sample_rate as f64));
+
+    let frequencies = frequency_grid(sample_rate);
+    let reference: Vec<f64> = frequencies.iter().map(|f| response(& b, & a, *f, sample_rate).norm()).collect();
+    let peak = reference.iter().fold(0.0, |max: f64, h| max.max(*h));
+
+    // The b coefficients, then a1 ... aN, a0 stays 1.
+    let names = (0..b.len()).map(|i| format!("b{}", i)).chain((1..a.len()).map(|i| format!("a{}", i)));
+    let mut coefficients = Vec::new();
+    for (index, name) in names.enumerate() {
+        let mut sensitivity = CoefficientSensitivity {
+            name, max_pole_displacement: 0.0, max_pole_radius: 0.0, max_deviation_db: 0.0 };
+        for delta in [epsilon, -epsilon] {
+            let (mut b, mut a) = (b.clone(), a.clone());
+            if index < b.len() {
+                b[index] += delta;
+            } else {
+                a[index - b.len() + 1] += delta;
+            }
+            let moved = polynomial_roots(& a);
+            for pole in & poles {
+                // The displacement of each pole to its nearest moved pole.
+                let nearest = moved.iter().fold(f64::INFINITY, |min: f64, m| min.min((m - pole).norm()));
+                sensitivity.max_pole_displacement = sensitivity.max_pole_displacement.max(nearest);
+            }
+            sensitivity.max_pole_radius = moved.iter().fold(sensitivity.max_pole_radius, |max, m| max.max(m.norm()));
+            for (f, h) in frequencies.iter().zip(& reference) {
+                if *h < MIN_RELATIVE_MAGNITUDE * peak {
+                    continue;
+                }
+                let deviation = (20.0 * f64::log10(response(& b, & a, *f, sample_rate).norm() / h)).abs();
+                // A NaN of an unstable filter is a deviation of infinity.
+                sensitivity.max_deviation_db = sensitivity.max_deviation_db.max(
+                    if deviation.is_nan() { f64::INFINITY } else { deviation });
+            }
+        }
+        coefficients.push(sensitivity);
+    }
+
+    let worst = |value: fn(& CoefficientSensitivity) -> f64| coefficients.iter().fold(0.0, |max: f64, c| max.max(value(c)));
+    let mut report = SensitivityReport {
+        epsilon,
+        pole_radius,
+        stability_margin: 1.0 - pole_radius,
+        lowest_pole_frequency,
+        max_pole_displacement: worst(|c| c.max_pole_displacement),
+        max_pole_radius: worst(|c| c.max_pole_radius),
+        max_deviation_db: worst(|c| c.max_deviation_db),
+        coefficients,
+        warnings: Vec::new(),
+    };
+    report.warnings = make_warnings(& report, filter.order);
+    Ok(report)
+}
+
+fn make_warnings(report: & SensitivityReport, order: usize) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if report.pole_radius >= 1.0 {
+        warnings.push(format!("Warning: the filter is unstable, a pole has radius {:.9}", report.pole_radius));
+    } else if report.stability_margin < NEAR_INSTABILITY_MARGIN {
+        warnings.push(format!("Warning: a pole is at {:.3e} of the unit circle, near instability",
+                              report.stability_margin));
+    }
+    if report.pole_radius < 1.0 && report.max_pole_radius >= 1.0 {
+        warnings.push(format!("Warning: a change of {:e} of a coefficient makes the filter unstable", report.epsilon));
+    }
+    if report.max_deviation_db > MAX_DEVIATION_DB {
+        let remedy = if order > 2 { "split it in second order sections, and use f64 coefficients" }
+                     else { "use f64 coefficients" };
+        warnings.push(format!("Warning: a change of {:e} of a coefficient changes the response by {:.3} dB, \
+                               the lowest pole is at {:.2} Hz, {}",
+                              report.epsilon, report.max_deviation_db, report.lowest_pole_frequency, remedy));
+    }
+    warnings
+}
+
+fn response(b: & [f64], a: & [f64], frequency: f64, sample_rate: u32) -> Complex<f64> {
+    let z_inv = Complex::from_polar(1.0, -TAU * frequency / sample_rate as f64);
+    // Horner in z^-1.
+    let polynomial = |c: & [f64]| c.iter().rev().fold(Complex::new(0.0, 0.0), |sum, c| sum * z_inv + c);
+    polynomial(b) / polynomial(a)
+}
+
+/// The roots of z^N + c1 z^(N-1) + ... + cN, from c = [1, c1, ..., cN]. Closed form
+/// for N <= 2, Durand-Kerner iterations above.
+pub fn polynomial_roots(c: & [f64]) -> Vec<Complex<f64>> {
+    match c.len() {
+        0 | 1 => Vec::new(),
+        2 => vec![Complex::new(-c[1], 0.0)],
+        3 => quadratic_roots(c[1], c[2]).to_vec(),
+        n => {
+            let evaluate = |z: Complex<f64>| c.iter().fold(Complex::new(0.0, 0.0), |sum, c| sum * z + c);
+            let seed = Complex::new(0.4, 0.9);
+            let mut roots: Vec<Complex<f64>> = (0..n - 1).map(|k| seed.powu(k as u32)).collect();
+            for _ in 0..ROOT_ITERATIONS {
+                let mut change: f64 = 0.0;
+                for i in 0..roots.len() {
+                    let denominator = (0..roots.len())
+                        .filter(|j| *j != i)
+                        .fold(Complex::new(1.0, 0.0), |product, j| product * (roots[i] - roots[j]));
+                    let step = evaluate(roots[i]) / denominator;
+                    roots[i] -= step;
+                    change = change.max(step.norm());
+                }
+                if change < ROOT_TOLERANCE {
+                    break;
+                }
+            }
+            roots
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_highpass;
+    use crate::butterworth_filter::make_lowpass;
+
+    // The filter of order 4 in direct form of the cascade of two biquads.
+    fn direct_form(first: & IIRFilter, second: & IIRFilter) -> IIRFilter {
+        let multiply = |p: & [f64], q: & [f64]| {
+            let mut product = vec![0.0; p.len() + q.len() - 1];
+            for (i, x) in p.iter().enumerate() {
+                for (j, y) in q.iter().enumerate() {
+                    product[i + j] += x * y;
+                }
+            }
+            product
+        };
+        let mut filter = IIRFilter::new(4);
+        filter.set_coefficients(& multiply(& first.a_coeffs, & second.a_coeffs),
+                                & multiply(& first.b_coeffs, & second.b_coeffs)).unwrap();
+        filter
+    }
+
+    #[test]
+    fn test_robust_and_sensitive_biquads() {
+        let lowpass = make_lowpass(1_000.0, 48_000, None);
+        let report = analyze_sensitivity(& lowpass, 48_000, DEFAULT_EPSILON).unwrap();
+        assert_eq!(report.coefficients.len(), 5);
+        assert_eq!(report.coefficients[3].name, "a1");
+        assert!(report.max_deviation_db < 1e-3, "{:?}", report);
+        assert!(!report.needs_higher_precision() && report.warnings.is_empty());
+        assert!((report.pole_radius - crate::design_policy::max_pole_radius(& lowpass)).abs() < 1e-12);
+
+        // A very low cutoff relative to the sample rate.
+        let highpass = make_highpass(2.0, 192_000, None);
+        let report = analyze_sensitivity(& highpass, 192_000, DEFAULT_EPSILON).unwrap();
+        assert!(report.stability_margin < NEAR_INSTABILITY_MARGIN);
+        assert!(report.is_near_instability() && report.needs_higher_precision());
+        assert!(report.get_pole_sensitivity() > 1_000.0, "{:?}", report);
+        assert!((report.lowest_pole_frequency - 2.0 / f64::sqrt(2.0)).abs() < 0.01, "{:?}", report);
+        assert!(!report.warnings.is_empty());
+
+        assert!(analyze_sensitivity(& lowpass, 48_000, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_direct_form_needs_sections() {
+        let first = make_lowpass(100.0, 48_000, Some(0.54));
+        let second = make_lowpass(100.0, 48_000, Some(1.31));
+        let sections: Vec<SensitivityReport> = [& first, & second].iter()
+            .map(|section| analyze_sensitivity(section, 48_000, DEFAULT_EPSILON).unwrap())
+            .collect();
+        let direct = analyze_sensitivity(& direct_form(& first, & second), 48_000, DEFAULT_EPSILON).unwrap();
+        // The same poles.
+        assert!((direct.pole_radius - sections[1].pole_radius).abs() < 1e-9, "{:?}", direct);
+        assert_eq!(direct.coefficients.len(), 9);
+        let worst_section = f64::max(sections[0].max_deviation_db, sections[1].max_deviation_db);
+        assert!(direct.max_deviation_db > 100.0 * worst_section, "{} {}", direct.max_deviation_db, worst_section);
+        assert!(direct.warnings.iter().any(|warning| warning.contains("second order sections")), "{:?}", direct.warnings);
+    }
+}
@@ -1,115 +1,128 @@
-/// Project: Audio filters in Rust
-/// Date:    2021.12.05
-/// Author of the port: João Nuno Carvalho
-///
-/// Description: Audio or DSP filters, allow you to attenuate or accentuate some frequencies
-///              or range of frequencies in a signal. The signal can be of any kind, but in
-///              here, we will focus on 1D signals. Like audio signals.
-///              There can also occur differences in the signal phases, that vary with the
-///              filter and the frequency components of the signal.  
-///              This is a port of Audio filters, from Python to Rust,
-///              from the Audio filter from TheAlgorithms GitHub in Python. That is by it
-///              self a port from WebAudio API implementation of the same common
-///              filters in the browsers.
-/// 
-///              The following filters are implemented over a BiQuad IIR filter:
-///                 -low-pass
-///                 -high-pass
-///                 -band-pass
-///                 -all-pass
-///                 -peak
-///                 -low-shelf
-///                 -high-shelf 
-///                 -notch
-///                 -10 band equalizer
-///  
-/// License: MIT Open Source License, like the original license from
-///    GitHub - TheAlgorithms / Python / audio_filters
-///    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
-///
-/// How to run the code. 
-/// 
-/// To make a project for this files do:
-///     -Install Rust your computer (Linux, Win, Mac, Raspberry Pi).
-///     
-///     cargo new audio_filters_in_rust
-///     cd audio_filters_in_rust
-///     
-///     -Copy the repository files to this directory and overlap them.
-/// 
-/// To compile do:
-///     cargo build --release
-/// 
-/// To run do:
-///     cargo run --release
-/// 
-/// to run the tests do:
-///     cargo test
-/// 
-/// References:
-///    1. GitHub - TheAlgorithms / Python / audio_filters
-///       https://github.com/TheAlgorithms/Python/tree/master/audio_filters
-///
-///    2. WebAudio - Cookbook formulae for audio equalizer biquad filter coefficients
-///       https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html 
-/// 
-///    3. Good resources on DSP – Digital Signal Programming
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_electronics#dsp--digital-signal-programming
-///
-///    4. Biquads - EarLevel
-///       http://www.earlevel.com/main/2003/02/28/biquads/
-///
-///    5. Biquad C++ source code - EarLevel
-///       https://www.earlevel.com/main/2012/11/26/biquad-c-source-code/
-///
-///    6. A biquad calculator V3 - EarLevel
-///       https://www.earlevel.com/main/2021/09/02/biquad-calculator-v3/
-/// 
-///    7. WebAudio API - Mozilla Docs
-///       https://developer.mozilla.org/en-US/docs/Web/API/Web_Audio_API
-/// 
-///    8. Audio Filters - Theory and Practice
-///       by Ethan Winer
-///       http://ethanwiner.com/filters.html
-/// 
-///    9. Audio filter - Wikipedia
-///       https://en.wikipedia.org/wiki/Audio_filter
-/// 
-///   10. Electronic filter - Wikipedia
-///       https://en.wikipedia.org/wiki/Electronic_filter
-///
-///   11. How to learn modern Rust
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
-///
-/// 
-/// 10 Band Equalizer
-/// 
-///   12. Making an EQ from cascading filters
-///       https://dsp.stackexchange.com/questions/10309/making-an-eq-from-cascading-filters
-/// 
-///   13. PEAK/NOTCH FILTER DESIGN
-///       https://www.dsprelated.com/showcode/169.php
-/// 
-///   14. The Equivalence of Various Methods of Computing
-///       Biquad Coefficients for Audio Parametric Equalizers
-///       http://www.thesounddesign.com/MIO/EQ-Coefficients.pdf
-///
-///   15. How to learn modern Rust
-///       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
-///
+//! Project: Audio filters in Rust
+//! Date:    2021.12.05
+//! Author of the port: João Nuno Carvalho
+//!
+//! Description: Audio or DSP filters, allow you to attenuate or accentuate some frequencies
+//!              or range of frequencies in a signal. The signal can be of any kind, but in
+//!              here, we will focus on 1D signals. Like audio signals.
+//!              There can also occur differences in the signal phases, that vary with the
+//!              filter and the frequency components of the signal.  
+//!              This is a port of Audio filters, from Python to Rust,
+//!              from the Audio filter from TheAlgorithms GitHub in Python. That is by it
+//!              self a port from WebAudio API implementation of the same common
+//!              filters in the browsers.
+//! 
+//!              The following filters are implemented over a BiQuad IIR filter:
+//!                 -low-pass
+//!                 -high-pass
+//!                 -band-pass
+//!                 -all-pass
+//!                 -peak
+//!                 -low-shelf
+//!                 -high-shelf 
+//!                 -notch
+//!                 -10 band equalizer
+//!  
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! How to run the code. 
+//! 
+//! To make a project for this files do:
+//!     -Install Rust your computer (Linux, Win, Mac, Raspberry Pi).
+//!     
+//!     cargo new audio_filters_in_rust
+//!     cd audio_filters_in_rust
+//!     
+//!     -Copy the repository files to this directory and overlap them.
+//! 
+//! To compile do:
+//!     cargo build --release
+//! 
+//! To run do:
+//!     cargo run --release
+//! 
+//! to run the tests do:
+//!     cargo test
+//! 
+//! References:
+//!    1. GitHub - TheAlgorithms / Python / audio_filters
+//!       https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//!    2. WebAudio - Cookbook formulae for audio equalizer biquad filter coefficients
+//!       https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html 
+//! 
+//!    3. Good resources on DSP – Digital Signal Programming
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_electronics#dsp--digital-signal-programming
+//!
+//!    4. Biquads - EarLevel
+//!       http://www.earlevel.com/main/2003/02/28/biquads/
+//!
+//!    5. Biquad C++ source code - EarLevel
+//!       https://www.earlevel.com/main/2012/11/26/biquad-c-source-code/
+//!
+//!    6. A biquad calculator V3 - EarLevel
+//!       https://www.earlevel.com/main/2021/09/02/biquad-calculator-v3/
+//! 
+//!    7. WebAudio API - Mozilla Docs
+//!       https://developer.mozilla.org/en-US/docs/Web/API/Web_Audio_API
+//! 
+//!    8. Audio Filters - Theory and Practice
+//!       by Ethan Winer
+//!       http://ethanwiner.com/filters.html
+//! 
+//!    9. Audio filter - Wikipedia
+//!       https://en.wikipedia.org/wiki/Audio_filter
+//! 
+//!   10. Electronic filter - Wikipedia
+//!       https://en.wikipedia.org/wiki/Electronic_filter
+//!
+//!   11. How to learn modern Rust
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
+//!
+//! 
+//! 10 Band Equalizer
+//! 
+//!   12. Making an EQ from cascading filters
+//!       https://dsp.stackexchange.com/questions/10309/making-an-eq-from-cascading-filters
+//! 
+//!   13. PEAK/NOTCH FILTER DESIGN
+//!       https://www.dsprelated.com/showcode/169.php
+//! 
+//!   14. The Equivalence of Various Methods of Computing
+//!       Biquad Coefficients for Audio Parametric Equalizers
+//!       http://www.thesounddesign.com/MIO/EQ-Coefficients.pdf
+//!
+//!   15. How to learn modern Rust
+//!       https://github.com/joaocarvalhoopen/How_to_learn_modern_Rust
+//!
 
 
 use crate::iir_filter::ProcessingBlock; // Trait
+use crate::analysis::goertzel_amplitude;
 use std::f32::consts::TAU as TAU_f32;
 
 
+// Lowest frequency of the ASCII plots, in Hz.
+const ASCII_MIN_FREQUENCY: f64 = 10.0;
+// Largest range of the ASCII plots, in dB.
+const ASCII_MAX_RANGE_DB: f64 = 60.0;
+// The dB per row of the ASCII plots.
+const ASCII_DB_STEPS: [f64; 11] = [0.5, 1.0, 2.0, 3.0, 5.0, 6.0, 10.0, 12.0, 20.0, 30.0, 60.0];
+// Levels of a sparkline, from the lowest.
+const SPARKLINE_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+
 /// Get bounds for printing fft results
 /// 
 /// In Python:
+///     ```text
 ///     >>> import numpy
 ///     >>> array = numpy.linspace(-20.0, 20.0, 1000)
 ///     >>> get_bounds(array, 1000)
 ///     (-20, 20)
+///     ```
 ///
 pub fn get_bounds(fft_results: & [f32], _sample_rate: usize, x_bound_max: usize) -> (f32, f32) {
     // let slice_upper_bound = (sample_rate / 2) - 1;
@@ -119,9 +132,9 @@ pub fn get_bounds(fft_results: & [f32], _sample_rate: usize, x_bound_max: usize)
     assert!(slice_upper_bound <= fft_results.len());
     let mut min_t = -20.0;  // f64::MAX;
     let mut max_t =  20.0;  // f64::MIN;
-    for i in 1..slice_upper_bound{
-        min_t = f32::min(fft_results[i], min_t);
-        max_t = f32::max(fft_results[i], max_t);
+    for value in & fft_results[1..slice_upper_bound] {
+        min_t = f32::min(*value, min_t);
+        max_t = f32::max(*value, max_t);
     }
     let lowest = min_t;
     let highest = max_t;
@@ -132,9 +145,11 @@ pub fn get_bounds(fft_results: & [f32], _sample_rate: usize, x_bound_max: usize)
 /// Show frequency response of a filter
 ///
 /// In Python:
+///     ```text
 ///     >>> from audio_filters.iir_filter import IIRFilter
 ///     >>> filt = IIRFilter(4)
 ///     >>> show_frequency_response(filt, 48000)
+///     ```
 ///
 pub fn show_frequency_response(processing_block: & mut dyn ProcessingBlock, sample_rate: usize, path: & str, line_name: & str) {
 
@@ -146,8 +161,8 @@ pub fn show_frequency_response(processing_block: & mut dyn ProcessingBlock, samp
                             inputs
                           };
     let mut outputs: Vec<f64> = Vec::with_capacity(size);
-    for i in 0..size {
-        outputs.push(processing_block.process(inputs[i]));
+    for input in & inputs {
+        outputs.push(processing_block.process(*input));
     }
     // zero-padding.
     let filler = vec![0.0; sample_rate - size];
@@ -176,7 +191,7 @@ pub fn show_frequency_response(processing_block: & mut dyn ProcessingBlock, samp
     // Display within reasonable bounds
     let (x_bound_min, x_bound_max) = (0_usize, sample_rate / 2 - 1 - 100 );
     let fft_db = & fft_db[x_bound_min..x_bound_max];
-    let bounds = get_bounds(& fft_db, sample_rate, x_bound_max);
+    let bounds = get_bounds(fft_db, sample_rate, x_bound_max);
     let (y_bound_min, y_bound_max) = (f32::max(-80.0, bounds.0), f32::min(80.0, bounds.1) );
 
     // Frequencies on log scale from 24 to nyquist frequency
@@ -200,21 +215,23 @@ pub fn show_frequency_response(processing_block: & mut dyn ProcessingBlock, samp
                 &BLUE,
             )).unwrap()
             .label(line_name)
-            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &BLUE));
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLUE));
     
         chart
             .configure_series_labels()
-            .background_style(&WHITE.mix(0.8))
-            .border_style(&BLACK)
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
             .draw().unwrap();
 }
 
 /// Show phase response of a filter
 /// 
 /// In Python:
+///     ```text
 ///     >>> from audio_filters.iir_filter import IIRFilter
 ///     >>> filt = IIRFilter(4)
 ///     >>> show_phase_response(filt, 48000)
+///     ```
 /// 
 pub fn show_phase_response(processing_block: & mut dyn ProcessingBlock, sample_rate: usize, path: & str, line_name: & str) {
 
@@ -226,8 +243,8 @@ pub fn show_phase_response(processing_block: & mut dyn ProcessingBlock, sample_r
                             inputs
                           };
     let mut outputs: Vec<f64> = Vec::with_capacity(size);
-    for i in 0..size {
-        outputs.push(processing_block.process(inputs[i]));
+    for input in & inputs {
+        outputs.push(processing_block.process(*input));
     }
     // zero-padding.
     let filler = vec![0.0; sample_rate - size];
@@ -254,7 +271,7 @@ pub fn show_phase_response(processing_block: & mut dyn ProcessingBlock, sample_r
     // Display within reasonable bounds
     let (x_bound_min, x_bound_max) = (0_usize, sample_rate / 2 - 1 - 150     );
     let fft_out = & fft_out[x_bound_min..x_bound_max];
-    let bounds = get_bounds(& fft_out, sample_rate, x_bound_max);
+    let bounds = get_bounds(fft_out, sample_rate, x_bound_max);
     // let (y_bound_min, y_bound_max) = (f32::max(-80.0, bounds.0), f32::min(80.0, bounds.1) );
     // NOTE: Remember that TAU = 2 * PI.
     let (y_bound_min, y_bound_max) = (f32::max(-TAU_f32, bounds.0), f32::min(TAU_f32, bounds.1) );
@@ -280,29 +297,260 @@ pub fn show_phase_response(processing_block: & mut dyn ProcessingBlock, sample_r
                 &BLUE,
             )).unwrap()
             .label(line_name)
-            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &BLUE));
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLUE));
     
         chart
             .configure_series_labels()
-            .background_style(&WHITE.mix(0.8))
-            .border_style(&BLACK)
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
             .draw().unwrap();
 }
 
+/// Show a spectrogram, the frames of dB magnitudes from analysis::spectrogram(),
+/// time on the x axis and frequency on the y axis, from -120 dB (black) to 0 dB (white).
+///
+/// In Rust:
+///     ```text
+///     >>> let frames = spectrogram(& signal, 1024, 512);
+///     >>> show_spectrogram(& frames, 48_000, 512, "plots/spectrogram.svg", "difference");
+///     ```
+///
+pub fn show_spectrogram(frames: & [Vec<f64>], sample_rate: usize, hop_size: usize, path: & str, title: & str) {
+    if frames.is_empty() {
+        return;
+    }
+    // Limits the number of rectangles in the SVG, by taking the max of groups of frames and bins.
+    let max_columns = 400;
+    let max_rows = 256;
+    let num_bins = frames[0].len();
+    let frame_step = frames.len().div_ceil(max_columns);
+    let bin_step = num_bins.div_ceil(max_rows);
+    let duration = (frames.len() * hop_size) as f64 / sample_rate as f64;
+    let nyquist = sample_rate as f64 / 2.0;
+    let frame_duration = hop_size as f64 / sample_rate as f64;
+    let bin_width = nyquist / (num_bins - 1) as f64;
+
+    use plotters::prelude::*;
+    let root = SVGBackend::new(path, (800, 400)).into_drawing_area();
+    root.fill(&WHITE).unwrap();
+    let mut chart = ChartBuilder::on(&root)
+        .caption(title.to_string() + " - Spectrogram, Freq(Hz) vs Time(s)", ("sans-serif", 25).into_font())
+        .margin(5)
+        .x_label_area_size(30)
+        .y_label_area_size(50)
+        .build_cartesian_2d(0.0..duration, 0.0..nyquist)
+        .unwrap();
+
+    chart.configure_mesh().disable_mesh().draw().unwrap();
+
+    let mut rectangles = Vec::new();
+    for frame_start in (0..frames.len()).step_by(frame_step) {
+        let frame_end = usize::min(frame_start + frame_step, frames.len());
+        for bin_start in (0..num_bins).step_by(bin_step) {
+            let bin_end = usize::min(bin_start + bin_step, num_bins);
+            let mut max_db = f64::NEG_INFINITY;
+            for frame in & frames[frame_start..frame_end] {
+                for value in & frame[bin_start..bin_end] {
+                    max_db = f64::max(max_db, *value);
+                }
+            }
+            let level = ((max_db + 120.0) / 120.0).clamp(0.0, 1.0);
+            let color = RGBColor((255.0 * level) as u8, (255.0 * level) as u8, (255.0 * level) as u8);
+            rectangles.push(Rectangle::new(
+                [(frame_start as f64 * frame_duration, bin_start as f64 * bin_width),
+                 (frame_end as f64 * frame_duration, bin_end as f64 * bin_width)],
+                color.filled()));
+        }
+    }
+    chart.draw_series(rectangles).unwrap();
+}
+
+/// Show the static curve of a dynamics processor, the (input dB, output dB) points
+/// of Compressor::get_static_curve(), with the 1:1 line of unity gain.
+///
+/// In Rust:
+///     ```text
+///     >>> let curve = compressor.get_static_curve(-60.0, 0.0, 241);
+///     >>> show_static_curve(& curve, "plots/compressor.svg", "compressor");
+///     ```
+///
+pub fn show_static_curve(points: & [(f64, f64)], path: & str, line_name: & str) {
+    if points.is_empty() {
+        return;
+    }
+    let (x_min, x_max) = points.iter().fold((f64::INFINITY, f64::NEG_INFINITY),
+        |(min, max), point| (min.min(point.0), max.max(point.0)));
+    let (y_min, y_max) = points.iter().fold((x_min, x_max),
+        |(min, max), point| (min.min(point.1), max.max(point.1)));
+
+    use plotters::prelude::*;
+    let root = SVGBackend::new(path, (400, 400)).into_drawing_area();
+    root.fill(&WHITE).unwrap();
+    let mut chart = ChartBuilder::on(&root)
+        .caption(line_name.to_string() + " - Output(dB) vs Input(dB)", ("sans-serif", 25).into_font())
+        .margin(5)
+        .x_label_area_size(30)
+        .y_label_area_size(30)
+        .build_cartesian_2d(x_min..x_max, y_min..y_max)
+        .unwrap();
+
+    chart.configure_mesh().draw().unwrap();
+
+    chart
+        .draw_series(LineSeries::new(vec![(x_min, x_min), (x_max, x_max)], &BLACK.mix(0.3)))
+        .unwrap();
+
+    chart
+        .draw_series(LineSeries::new(points.iter().copied(), &BLUE))
+        .unwrap()
+        .label(line_name)
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLUE));
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw().unwrap();
+}
+
+/// Magnitude response of a filter as text, for tests, logs and docs, without
+/// writing a file. The frequency axis is log, from 10 Hz to Nyquist, with a
+/// column per character, and the dB axis is fitted to the response, up to 60 dB.
+/// With a height of 1 it's a one line sparkline. The response is measured with
+/// the impulse response, so the state of the filter is changed.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut filter = make_peak(1_000.0, 48_000, 6.0, Some(2.0));
+///    >>> println!("{}", render_response_ascii(& mut filter, 48_000, 64, 9));
+///       +12 |
+///           |
+///        +6 |                                     **
+///           |                                  ***  **
+///        +0 |**********************************       ***********************
+///           |
+///        -6 |
+///           |
+///       -12 |
+///           +----------------------------------------------------------------
+///                               100               1k                 10k Hz
+///    ```
+///
+pub fn render_response_ascii(processing_block: & mut dyn ProcessingBlock, sample_rate: u32, width: usize,
+                             height: usize) -> String {
+    // 1 s of impulse response, a resolution of 1 Hz.
+    let impulse_response: Vec<f64> = (0..sample_rate as usize)
+        .map(|n| processing_block.process(if n == 0 { 1.0 } else { 0.0 }))
+        .collect();
+    let length = impulse_response.len() as f64;
+    render_magnitude_ascii(|frequency| {
+        // The Goertzel amplitude is 2 |H(f)| / N .
+        20.0 * f64::log10(goertzel_amplitude(& impulse_response, frequency, sample_rate) * length / 2.0)
+    }, sample_rate, width, height)
+}
+
+/// The text plot of render_response_ascii(), of a magnitude response in dB.
+pub fn render_magnitude_ascii<F: Fn(f64) -> f64>(magnitude_db: F, sample_rate: u32, width: usize, height: usize)
+        -> String {
+    assert!(width >= 2 && height >= 1);
+    let nyquist = sample_rate as f64 / 2.0;
+    let frequency = |column: usize| ASCII_MIN_FREQUENCY
+        * f64::powf(nyquist / ASCII_MIN_FREQUENCY, column as f64 / (width - 1) as f64);
+    let magnitudes: Vec<f64> = (0..width).map(|column| magnitude_db(frequency(column))).collect();
+    let max_db = magnitudes.iter().cloned().filter(|db| db.is_finite()).fold(0.0, f64::max);
+    let min_db = magnitudes.iter().cloned().filter(|db| db.is_finite()).fold(0.0, f64::min);
+    let top = 6.0 * f64::ceil((max_db + 1.0) / 6.0);
+    let bottom = f64::max(6.0 * f64::floor((min_db - 1.0) / 6.0), top - ASCII_MAX_RANGE_DB).min(top - 12.0);
+    if height == 1 {
+        return magnitudes.iter()
+            .map(|db| {
+                let level = ((db - bottom) / (top - bottom) * SPARKLINE_LEVELS.len() as f64).floor();
+                if level.is_nan() || level < 0.0 { ' ' }
+                else { SPARKLINE_LEVELS[(level as usize).min(SPARKLINE_LEVELS.len() - 1)] }
+            })
+            .collect();
+    }
+    // A round number of dB per row, for the labels.
+    let rows_range = (height - 1) as f64;
+    let step = ASCII_DB_STEPS.iter().copied().find(|step| step * rows_range >= top - bottom)
+        .unwrap_or((top - bottom) / rows_range);
+    let bottom = top - step * rows_range;
+    let mut rows = vec![vec![' '; width]; height];
+    for (column, db) in magnitudes.iter().enumerate() {
+        if *db >= bottom {
+            let row = ((top - db) / (top - bottom) * (height - 1) as f64).round() as usize;
+            rows[row.min(height - 1)][column] = '*';
+        }
+    }
+    let mut text = String::new();
+    for (index, row) in rows.iter().enumerate() {
+        let db = top - (top - bottom) * index as f64 / (height - 1) as f64;
+        let label = if index % 2 == 0 { format!("{:+5.0}", db) } else { String::new() };
+        text += & format!("{:>5} |{}\n", label, row.iter().collect::<String>().trim_end());
+    }
+    text += & format!("{:>5} +{}\n", "", "-".repeat(width));
+    // The labels of the decades, at their columns.
+    let mut axis = vec![' '; width + 10];
+    for (decade, label) in [(100.0, "100"), (1_000.0, "1k"), (10_000.0, "10k")] {
+        if decade < nyquist {
+            let column = (f64::ln(decade / ASCII_MIN_FREQUENCY) / f64::ln(nyquist / ASCII_MIN_FREQUENCY)
+                          * (width - 1) as f64).round() as usize;
+            for (i, c) in label.chars().enumerate() {
+                axis[7 + column + i] = c;
+            }
+        }
+    }
+    text += axis.iter().collect::<String>().trim_end();
+    text += " Hz\n";
+    text
+}
+
 #[cfg(test)]
 mod tests {
-    use super::*;
-
+    use super::show_static_curve;
+    use super::render_response_ascii;
+    use crate::butterworth_filter::make_peak;
     use crate::butterworth_filter::make_lowpass;
+    use crate::dynamics::Compressor;
+    use crate::fixtures::temp_test_path;
 
     #[test]
     fn test_show_frequency_response() {
         let frequency = 5_000.0;  // Hz
         let sample_rate = 48_000; // Samples
-        let mut filter = make_lowpass(frequency, sample_rate, None);
+        let _filter = make_lowpass(frequency, sample_rate, None);
         // show_frequency_response(& mut filter, sample_rate as usize, "plots/lowpass.svg", "lowpass");
         
         // assert_eq!(true, false);
     }
-}
 
+    #[test]
+    fn test_show_static_curve() {
+        let mut compressor = Compressor::new(48_000, -20.0, 4.0, 5.0, 100.0);
+        compressor.set_knee_db(6.0);
+        let path = & temp_test_path("static_curve.svg");
+        show_static_curve(& compressor.get_static_curve(-60.0, 0.0, 241), path, "compressor");
+        let svg = std::fs::read_to_string(path).unwrap();
+        assert!(svg.contains("<svg") && svg.contains("compressor"));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_render_response_ascii() {
+        let mut filter = make_peak(1_000.0, 48_000, 6.0, Some(2.0));
+        let plot = render_response_ascii(& mut filter, 48_000, 64, 9);
+        let lines: Vec<& str> = plot.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert!(lines[0].starts_with("  +12 |"));
+        // The peak is on the +6 dB row, around the 1k label, the rest on the 0 dB row.
+        let peak_column = lines[2].find('*').unwrap();
+        assert!(lines[10].find("1k").unwrap().abs_diff(peak_column) <= 2);
+        assert_eq!(lines[4].matches('*').count() + lines[3].matches('*').count() + 2, 64);
+        assert!(lines[10].ends_with("10k Hz"));
+
+        let sparkline = render_response_ascii(& mut make_lowpass(1_000.0, 48_000, None), 48_000, 32, 1);
+        assert_eq!(sparkline.chars().count(), 32);
+        // From 0 dB to below the bottom of the 60 dB range.
+        assert!(sparkline.starts_with('█') && sparkline.ends_with(' ') && !sparkline.contains('\n'));
+    }
+}
@@ -0,0 +1,275 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: The processing of a whole signal in one call, y = filter(x), like
+//!              the lfilter() of scipy or the filter() of Matlab / Octave, for the
+//!              offline analysis and the tests, where the sample by sample loop of
+//!              process() is only noise.
+//!              The ProcessSignal trait is implemented for all the processing
+//!              blocks, the filters, the equalizers and the chains, and
+//!              process_signal() is the same as a function. lfilter() takes the
+//!              b and a coefficients directly, of any order, and lfilter_with_state()
+//!              and lfilter_zi() carry the state explicitly, zi and zf, between the
+//!              chunks of a signal, with the same numbers as scipy.
+//!              With the feature "ndarray" the signals can also be the 1D and 2D
+//!              arrays of the ndarray crate, process_array() and process_lanes(),
+//!              a filter of its own on each lane, like the axis of lfilter().
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    scipy.signal.lfilter
+//!    https://docs.scipy.org/doc/scipy/reference/generated/scipy.signal.lfilter.html
+//!    scipy.signal.lfilter_zi
+//!    https://docs.scipy.org/doc/scipy/reference/generated/scipy.signal.lfilter_zi.html
+//!
+
+
+use crate::iir_filter::Float; // Trait
+use crate::iir_filter::IIRFilter;
+use crate::iir_filter::ProcessingBlock; // Trait
+
+#[cfg(feature = "ndarray")]
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+
+
+/// The processing of a whole signal in one call, for all the processing blocks.
+/// The state of the block continues from the previous call, like with process().
+///
+/// In Rust:
+///    ```text
+///    >>> let mut lowpass = make_lowpass(1_000.0, 48_000, None);
+///    >>> let y = lowpass.process_signal(& x);
+///    ```
+///
+pub trait ProcessSignal<T: Float = f64> {
+    /// The output of the signal, of the same length.
+    fn process_signal(& mut self, signal: & [T]) -> Vec<T>;
+
+    /// The output of the 1D array, of the same length. The array can be a view
+    /// with any stride, like a column of a 2D array.
+    #[cfg(feature = "ndarray")]
+    fn process_array(& mut self, signal: ArrayView1<T>) -> Array1<T>;
+}
+
+impl<T: Float, B: ProcessingBlock<T> + ?Sized> ProcessSignal<T> for B {
+    fn process_signal(& mut self, signal: & [T]) -> Vec<T> {
+        let mut output = vec![T::ZERO; signal.len()];
+        self.process_buffer(signal, & mut output);
+        output
+    }
+
+    #[cfg(feature = "ndarray")]
+    fn process_array(& mut self, signal: ArrayView1<T>) -> Array1<T> {
+        match signal.as_slice() {
+            Some(samples) => Array1::from_vec(self.process_signal(samples)),
+            None          => Array1::from_vec(self.process_signal(& signal.to_vec())),
+        }
+    }
+}
+
+/// The output of the signal through the block, y = block(x).
+pub fn process_signal<T: Float, B: ProcessingBlock<T> + ?Sized>(block: & mut B, signal: & [T]) -> Vec<T> {
+    block.process_signal(signal)
+}
+
+/// The output of the signal through the filter of the coefficients b and a, of
+/// any order, from the zero state, like lfilter(b, a, x) of scipy. The shorter of
+/// b and a is padded with zeros, and a[0] must not be zero.
+///
+/// In Rust:
+///    ```text
+///    >>> // The moving average of 4 samples.
+///    >>> let y = lfilter(& [0.25; 4], & [1.0], & x)?;
+///    ```
+///
+pub fn lfilter(b: & [f64], a: & [f64], signal: & [f64]) -> Result<Vec<f64>, String> {
+    let mut filter = make_filter(b, a)?;
+    if filter.order == 0 {
+        return Ok(signal.iter().map(|x| x * b[0] / a[0]).collect());
+    }
+    Ok(filter.process_signal(signal))
+}
+
+/// The output of the signal from the state zi, and the final state zf, like
+/// y, zf = lfilter(b, a, x, zi=zi) of scipy, for the chunks of an offline
+/// processing. The state has max(len(a), len(b)) - 1 values, see
+/// IIRFilter::process_with_state().
+///
+/// In Rust:
+///    ```text
+///    >>> let zi: Vec<f64> = lfilter_zi(& b, & a)?.iter().map(|z| z * x[0]).collect();
+///    >>> let (y, zf) = lfilter_with_state(& b, & a, & x, & zi)?;
+///    ```
+///
+pub fn lfilter_with_state(b: & [f64], a: & [f64], signal: & [f64], zi: & [f64])
+        -> Result<(Vec<f64>, Vec<f64>), String> {
+    make_filter(b, a)?.process_with_state(signal, zi)
+}
+
+/// The state zi of the steady state of the step response of the filter, like
+/// lfilter_zi(b, a) of scipy. With zi times the first sample, the output starts
+/// without the transient of a step from zero, for the filtfilt() of a signal with
+/// an offset. The filter must not have a pole at z = 1, the sum of a not zero.
+pub fn lfilter_zi(b: & [f64], a: & [f64]) -> Result<Vec<f64>, String> {
+    let filter = make_filter(b, a)?;
+    let order = filter.order;
+    let a: Vec<f64> = filter.a_coeffs.iter().map(|c| c / filter.a_coeffs[0]).collect();
+    let b: Vec<f64> = filter.b_coeffs.iter().map(|c| c / filter.a_coeffs[0]).collect();
+    let mut zi = vec![0.0; order];
+    if order == 0 {
+        return Ok(zi);
+    }
+    // The state of a constant input of 1 and output of the DC gain, y = b[0] + zi[0],
+    // is zi[k] = sum over j > k of b[j] - a[j] y .
+    let tail_sum = |coeffs: & [f64], k: usize| -> f64 { coeffs[(k + 1)..].iter().sum() };
+    let c: Vec<f64> = (0..=order).map(|j| b[j] - a[j] * b[0]).collect();
+    let a_sum = 1.0 + tail_sum(& a, 0);
+    if a_sum.abs() < 1e-12 {
+        return Err("Error: the filter has a pole at z = 1, without a steady state of a step".to_string());
+    }
+    zi[0] = tail_sum(& c, 0) / a_sum;
+    for k in 1..order {
+        zi[k] = tail_sum(& c, k) - tail_sum(& a, k) * zi[0];
+    }
+    Ok(zi)
+}
+
+// The filter of the coefficients, the shorter of b and a padded with zeros.
+fn make_filter(b: & [f64], a: & [f64]) -> Result<IIRFilter, String> {
+    if b.is_empty() || a.is_empty() {
+        return Err("Error: the b and a coefficients can't be empty".to_string());
+    }
+    if a[0] == 0.0 || !a[0].is_finite() {
+        return Err(format!("Error: the coefficient a[0] must be finite and not zero, got {}", a[0]));
+    }
+    if b.iter().chain(a).any(|c| !c.is_finite()) {
+        return Err("Error: the b and a coefficients must be finite".to_string());
+    }
+    let order = usize::max(b.len(), a.len()) - 1;
+    let padded = |coeffs: & [f64]| -> Vec<f64> {
+        let mut padded = coeffs.to_vec();
+        padded.resize(order + 1, 0.0);
+        padded
+    };
+    let mut filter = IIRFilter::new(order);
+    filter.set_coefficients(& padded(a), & padded(b))?;
+    Ok(filter)
+}
+
+/// The output of each lane of the 2D array along the axis, each one through a
+/// block of its own, from make_block(). With Axis(0) the lanes are the columns,
+/// the channels of a frames x channels array, with Axis(1) the rows.
+///
+/// In Rust:
+///    ```text
+///    >>> let y = process_lanes(x.view(), Axis(0), || make_lowpass(1_000.0, 48_000, None));
+///    ```
+///
+#[cfg(feature = "ndarray")]
+pub fn process_lanes<T, B, F>(signal: ArrayView2<T>, axis: Axis, mut make_block: F) -> Array2<T>
+        where T: Float, B: ProcessingBlock<T>, F: FnMut() -> B {
+    let mut output = signal.to_owned();
+    for mut lane in output.lanes_mut(axis) {
+        let filtered = make_block().process_array(lane.view());
+        lane.assign(& filtered);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_lowpass;
+
+    #[test]
+    fn test_process_signal() {
+        let signal: Vec<f64> = (0..500).map(|n| f64::sin(n as f64 * 0.7)).collect();
+        let mut reference_filter = make_lowpass(2_000.0, 48_000, None);
+        let reference: Vec<f64> = signal.iter().map(|x| reference_filter.process(*x)).collect();
+
+        assert_eq!(make_lowpass(2_000.0, 48_000, None).process_signal(& signal), reference);
+        let mut filter = make_lowpass(2_000.0, 48_000, None);
+        let mut output = process_signal(& mut filter, & signal[..200]);
+        output.extend(process_signal(& mut filter, & signal[200..]));
+        assert_eq!(output, reference);
+
+        // lfilter() with the coefficients of the biquad, a[0] not normalized.
+        let filter = make_lowpass(2_000.0, 48_000, None);
+        let b: Vec<f64> = filter.b_coeffs.iter().map(|c| c * 2.0).collect();
+        let a: Vec<f64> = filter.a_coeffs.iter().map(|c| c * 2.0).collect();
+        let output = lfilter(& b, & a, & signal).unwrap();
+        assert!(output.iter().zip(& reference).all(|(y, r)| (y - r).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_lfilter() {
+        // FIR, the moving average of 3 samples.
+        let y = lfilter(& [1.0, 1.0, 1.0], & [3.0], & [3.0, 6.0, 9.0, 0.0]).unwrap();
+        assert_eq!(y, vec![1.0, 3.0, 6.0, 5.0]);
+        // One pole, y[n] = x[n] + 0.5 y[n-1].
+        let y = lfilter(& [1.0], & [1.0, -0.5], & [1.0, 0.0, 0.0, 0.0]).unwrap();
+        assert_eq!(y, vec![1.0, 0.5, 0.25, 0.125]);
+        // Order 0, a gain.
+        assert_eq!(lfilter(& [2.0], & [4.0], & [1.0, -2.0]).unwrap(), vec![0.5, -1.0]);
+
+        assert!(lfilter(& [], & [1.0], & [1.0]).is_err());
+        assert!(lfilter(& [1.0], & [0.0, 1.0], & [1.0]).is_err());
+        assert!(lfilter(& [f64::NAN], & [1.0], & [1.0]).is_err());
+    }
+
+    #[test]
+    fn test_lfilter_with_state() {
+        // One pole, y[n] = x[n] + 0.5 y[n-1], the steady state of a step is 2.
+        assert_eq!(lfilter_zi(& [1.0], & [1.0, -0.5]).unwrap(), vec![1.0]);
+        let (y, zf) = lfilter_with_state(& [1.0], & [1.0, -0.5], & [1.0, 0.0], & [1.0]).unwrap();
+        assert_eq!((y, zf), (vec![2.0, 1.0], vec![0.5]));
+        // The moving average, the state of a step is the sum of the samples to come.
+        assert_eq!(lfilter_zi(& [1.0, 1.0, 1.0], & [3.0]).unwrap(), vec![2.0 / 3.0, 1.0 / 3.0]);
+        assert!(lfilter_zi(& [1.0], & [1.0, -1.0]).is_err());
+        assert!(lfilter_with_state(& [1.0], & [1.0, -0.5], & [1.0], & [0.0, 0.0]).is_err());
+
+        // The step from the steady state of the biquad is flat, at the DC gain.
+        let filter = make_lowpass(2_000.0, 48_000, None);
+        let (b, a) = (& filter.b_coeffs, & filter.a_coeffs);
+        let zi = lfilter_zi(b, a).unwrap();
+        let (y, _) = lfilter_with_state(b, a, & [0.8; 100], & zi.iter().map(|z| z * 0.8).collect::<Vec<f64>>()).unwrap();
+        assert!(y.iter().all(|y| (y - 0.8).abs() < 1e-12));
+
+        // From zero, the same as process(), and in chunks the same as in one call.
+        let signal: Vec<f64> = (0..1_000).map(|n| f64::sin(n as f64 * 0.3) + 0.2).collect();
+        let reference = make_lowpass(2_000.0, 48_000, None).process_signal(& signal);
+        let (y, zf) = filter.process_with_state(& signal, & [0.0, 0.0]).unwrap();
+        assert!(y.iter().zip(& reference).all(|(y, r)| (y - r).abs() < 1e-12));
+        let mut state = vec![0.0, 0.0];
+        let mut chunked = Vec::new();
+        for chunk in signal.chunks(333) {
+            let (y, zf) = lfilter_with_state(b, a, chunk, & state).unwrap();
+            chunked.extend(y);
+            state = zf;
+        }
+        assert_eq!(state, zf);
+        assert!(chunked.iter().zip(& reference).all(|(y, r)| (y - r).abs() < 1e-12));
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_process_lanes() {
+        let left:  Vec<f64> = (0..300).map(|n| f64::sin(n as f64 * 0.2)).collect();
+        let right: Vec<f64> = (0..300).map(|n| f64::cos(n as f64 * 0.9)).collect();
+        let frames = Array2::from_shape_fn((300, 2), |(n, c)| if c == 0 { left[n] } else { right[n] });
+        let make_filter = || make_lowpass(1_000.0, 48_000, None);
+
+        let output = process_lanes(frames.view(), Axis(0), make_filter);
+        assert_eq!(output.column(0).to_vec(), make_filter().process_signal(& left));
+        assert_eq!(output.column(1).to_vec(), make_filter().process_signal(& right));
+
+        let channels = frames.t().to_owned();
+        let output_rows = process_lanes(channels.view(), Axis(1), make_filter);
+        assert_eq!(output_rows, output.t());
+        assert_eq!(make_filter().process_array(frames.column(1)).to_vec(), output.column(1).to_vec());
+    }
+}
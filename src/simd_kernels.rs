@@ -0,0 +1,324 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Block processing kernels with the NEON instructions of the ARM
+//!              AArch64 CPUs, like the one of the Raspberry Pi 3, 4 and 5, where the
+//!              scalar f64 loops are slow. The NEON registers hold 2 f64, and the
+//!              backend is detected at run time, KernelBackend::detect(), with the
+//!              scalar kernels on the other CPUs:
+//!
+//!                 -dot_product(), the sum of the products of the time domain FIR
+//!                  convolution of DirectConvolver, in 4 lanes, 2 NEON registers.
+//!                 -BiquadCascade, a cascade of TDF-II biquads where the 2 lanes run
+//!                  2 sections at once, pipelined: the first section of a pair at the
+//!                  sample n and the second one at the sample n - 1, the output of
+//!                  the first one of the step before:
+//!
+//!                     [x[n], y1[n - 1]] -> [section 1, section 2] -> [y1[n], y2[n - 1]]
+//!
+//!              The biquad of a sample depends on the one before, so the lanes can't
+//!              run 2 samples of a section, but they run 2 sections. The NEON kernels
+//!              do the same multiplications and additions in the same order as the
+//!              scalar ones, without the fused multiply add, so the outputs are bit
+//!              identical on all the CPUs, and the same as the ones of the cascade
+//!              of BiquadTDF2, see the tests. The processing is the one of f64, the
+//!              f32 chains are in precision.rs .
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Arm - Neon Intrinsics Reference
+//!       https://developer.arm.com/architectures/instruction-sets/intrinsics/
+//!
+//!    2. Rust - std::arch::aarch64 and is_aarch64_feature_detected
+//!       https://doc.rust-lang.org/std/arch/aarch64/index.html
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::iir_filter::FilterChain;
+use crate::biquad_tdf2::BiquadTDF2;
+
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::float64x2_t;
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::vaddq_f64;
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::vaddvq_f64;
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::vdupq_n_f64;
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::vgetq_lane_f64;
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::vld1q_f64;
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::vmulq_f64;
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::vsetq_lane_f64;
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::vsubq_f64;
+
+
+/// The names of the kernel backends.
+pub const KERNEL_BACKEND_NAMES: [&str; 2] = ["scalar", "neon"];
+
+/// The instructions of the kernels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KernelBackend {
+    Scalar,
+    Neon,
+}
+
+impl KernelBackend {
+    /// The fastest backend of the CPU that runs the program.
+    pub fn detect() -> Self {
+        #[cfg(target_arch = "aarch64")]
+        {
+            if std::arch::is_aarch64_feature_detected!("neon") {
+                return KernelBackend::Neon;
+            }
+        }
+        KernelBackend::Scalar
+    }
+
+    pub fn is_available(& self) -> bool {
+        * self == KernelBackend::Scalar || * self == KernelBackend::detect()
+    }
+
+    /// The backend of a name of KERNEL_BACKEND_NAMES.
+    pub fn from_name(name: & str) -> Result<Self, String> {
+        match name {
+            "scalar" => Ok(KernelBackend::Scalar),
+            "neon"   => Ok(KernelBackend::Neon),
+            _ => Err(format!("Error: unknown kernel backend \"{}\", the backends are {}", name,
+                             KERNEL_BACKEND_NAMES.join(", "))),
+        }
+    }
+
+    pub fn get_name(& self) -> & 'static str {
+        match self {
+            KernelBackend::Scalar => KERNEL_BACKEND_NAMES[0],
+            KernelBackend::Neon   => KERNEL_BACKEND_NAMES[1],
+        }
+    }
+}
+
+/// sum_i a[i] b[i], of slices of the same length. A backend that isn't available
+/// runs the scalar kernel, of the same result.
+pub fn dot_product(a: & [f64], b: & [f64], backend: KernelBackend) -> f64 {
+    assert_eq!(a.len(), b.len());
+    if backend == KernelBackend::Neon && backend.is_available() {
+        // Safety: the CPU has the NEON instructions.
+        #[cfg(target_arch = "aarch64")]
+        return unsafe { dot_product_neon(a, b) };
+    }
+    dot_product_scalar(a, b)
+}
+
+// The lanes 0, 1, 2, 3 of the groups of 4 products, summed as (0 + 2) + (1 + 3),
+// then the products of the rest one by one.
+fn dot_product_scalar(a: & [f64], b: & [f64]) -> f64 {
+    let len = a.len() / 4 * 4;
+    let mut lanes = [0.0; 4];
+    for (x, y) in a[..len].chunks_exact(4).zip(b[..len].chunks_exact(4)) {
+        for lane in 0..4 {
+            lanes[lane] += x[lane] * y[lane];
+        }
+    }
+    let mut sum = (lanes[0] + lanes[2]) + (lanes[1] + lanes[3]);
+    for (x, y) in a[len..].iter().zip(& b[len..]) {
+        sum += x * y;
+    }
+    sum
+}
+
+// The lanes 0, 1 in one register and 2, 3 in the other.
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn dot_product_neon(a: & [f64], b: & [f64]) -> f64 {
+    let len = a.len() / 4 * 4;
+    let mut low = vdupq_n_f64(0.0);
+    let mut high = vdupq_n_f64(0.0);
+    for i in (0..len).step_by(4) {
+        let (x, y) = (a.as_ptr().add(i), b.as_ptr().add(i));
+        low = vaddq_f64(low, vmulq_f64(vld1q_f64(x), vld1q_f64(y)));
+        high = vaddq_f64(high, vmulq_f64(vld1q_f64(x.add(2)), vld1q_f64(y.add(2))));
+    }
+    let mut sum = vaddvq_f64(vaddq_f64(low, high));
+    for (x, y) in a[len..].iter().zip(& b[len..]) {
+        sum += x * y;
+    }
+    sum
+}
+
+/// A cascade of TDF-II biquads, with the kernel of the backend.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut cascade = BiquadCascade::from_chain(& make_butterworth_lowpass(1_000.0, 48_000, 8)?)?;
+///    >>> println!("kernel {}", cascade.get_backend().get_name());
+///    >>> cascade.process_inplace(& mut samples);
+///    ```
+///
+#[derive(Debug, Clone)]
+pub struct BiquadCascade {
+    sections: Vec<BiquadTDF2>,
+    backend:  KernelBackend,
+}
+
+impl BiquadCascade {
+    /// The cascade of the sections of the chain, of order 1 or 2, with the backend of
+    /// the CPU.
+    pub fn from_chain(chain: & FilterChain) -> Result<Self, String> {
+        BiquadCascade::with_backend(chain, KernelBackend::detect())
+    }
+
+    /// The cascade with a backend, an error if the CPU doesn't have it.
+    pub fn with_backend(chain: & FilterChain, backend: KernelBackend) -> Result<Self, String> {
+        if !backend.is_available() {
+            return Err(format!("Error: the kernel backend {} isn't available on this CPU", backend.get_name()));
+        }
+        Ok(BiquadCascade { sections: BiquadTDF2::from_chain(chain)?, backend })
+    }
+
+    pub fn get_backend(& self) -> KernelBackend {
+        self.backend
+    }
+
+    pub fn get_sections(& self) -> & [BiquadTDF2] {
+        & self.sections
+    }
+
+    pub fn reset(& mut self) {
+        for section in self.sections.iter_mut() {
+            section.reset();
+        }
+    }
+}
+
+impl ProcessingBlock for BiquadCascade {
+    fn process(& mut self, sample: f64) -> f64 {
+        self.sections.iter_mut().fold(sample, |x, section| section.process(x))
+    }
+
+    fn process_buffer(& mut self, input: & [f64], output: & mut [f64]) {
+        assert_eq!(input.len(), output.len());
+        output.copy_from_slice(input);
+        self.process_inplace(output);
+    }
+
+    /// With NEON the sections in pairs, and the last one alone for an odd number
+    /// of sections. The same output as process() on each sample.
+    fn process_inplace(& mut self, samples: & mut [f64]) {
+        let _span = trace_span!("biquad_cascade", samples = samples.len(), backend = self.backend.get_name());
+        #[cfg(target_arch = "aarch64")]
+        {
+            if self.backend == KernelBackend::Neon {
+                let mut pairs = self.sections.chunks_exact_mut(2);
+                for pair in pairs.by_ref() {
+                    let (first, second) = pair.split_at_mut(1);
+                    // Safety: the backend is Neon only on a CPU with the NEON
+                    // instructions, see with_backend().
+                    unsafe { process_pair_neon(& mut first[0], & mut second[0], samples) };
+                }
+                for section in pairs.into_remainder() {
+                    section.process_inplace(samples);
+                }
+                return;
+            }
+        }
+        for section in self.sections.iter_mut() {
+            section.process_inplace(samples);
+        }
+    }
+}
+
+// Two sections in the 2 lanes, the second one a sample behind. The first sample
+// goes through the first section alone and the last one through the second section
+// alone, so the state of the pair is the one of the end of the buffer.
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn process_pair_neon(first: & mut BiquadTDF2, second: & mut BiquadTDF2, samples: & mut [f64]) {
+    if samples.is_empty() {
+        return;
+    }
+    let mut carry = first.process(samples[0]);
+    let (a_first, b_first) = first.get_coefficients();
+    let (a_second, b_second) = second.get_coefficients();
+    let pair = |x: f64, y: f64| -> float64x2_t { vld1q_f64([x, y].as_ptr()) };
+    let (b0, b1, b2) = (pair(b_first[0], b_second[0]), pair(b_first[1], b_second[1]), pair(b_first[2], b_second[2]));
+    let (a1, a2) = (pair(a_first[1], a_second[1]), pair(a_first[2], a_second[2]));
+    let ([s1_first, s2_first], [s1_second, s2_second]) = (first.get_state(), second.get_state());
+    let mut s1 = pair(s1_first, s1_second);
+    let mut s2 = pair(s2_first, s2_second);
+    for n in 1..samples.len() {
+        let x = vsetq_lane_f64::<1>(carry, vdupq_n_f64(samples[n]));
+        // The operations of BiquadTDF2::process() in each lane.
+        let y = vaddq_f64(vmulq_f64(b0, x), s1);
+        s1 = vaddq_f64(vsubq_f64(vmulq_f64(b1, x), vmulq_f64(a1, y)), s2);
+        s2 = vsubq_f64(vmulq_f64(b2, x), vmulq_f64(a2, y));
+        carry = vgetq_lane_f64::<0>(y);
+        samples[n - 1] = vgetq_lane_f64::<1>(y);
+    }
+    first.set_state([vgetq_lane_f64::<0>(s1), vgetq_lane_f64::<0>(s2)]);
+    second.set_state([vgetq_lane_f64::<1>(s1), vgetq_lane_f64::<1>(s2)]);
+    let last = samples.len() - 1;
+    samples[last] = second.process(carry);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_butterworth_lowpass;
+    use crate::butterworth_filter::make_peak;
+    use crate::generators::make_white_noise;
+
+    #[test]
+    fn test_dot_product() {
+        // All the lengths of the tail, on all the backends of the CPU.
+        for length in [0, 1, 3, 4, 7, 64, 301] {
+            let (a, b) = (make_white_noise(length, 0.5, 1), make_white_noise(length, 0.5, 2));
+            let expected: f64 = a.iter().zip(& b).map(|(x, y)| x * y).sum();
+            let scalar = dot_product(& a, & b, KernelBackend::Scalar);
+            assert!((scalar - expected).abs() < 1e-12);
+            assert_eq!(dot_product(& a, & b, KernelBackend::detect()).to_bits(), scalar.to_bits());
+        }
+        assert!(KernelBackend::Scalar.is_available());
+        assert_eq!(KernelBackend::from_name("neon").unwrap().get_name(), "neon");
+        assert!(KernelBackend::from_name("sse").is_err());
+    }
+
+    #[test]
+    fn test_cascade_is_the_tdf2_chain() {
+        // 7 sections, 3 pairs and one alone, in buffers of odd lengths, of 1 sample
+        // and empty.
+        let mut filters = make_butterworth_lowpass(2_000.0, 48_000, 12).unwrap().get_filters().to_vec();
+        filters.push(make_peak(500.0, 48_000, 6.0, Some(2.0)));
+        let chain = FilterChain::new(filters);
+        let signal = make_white_noise(4_000, 0.5, 3);
+        let mut sections = BiquadTDF2::from_chain(& chain).unwrap();
+        let expected: Vec<f64> = signal.iter()
+            .map(|x| sections.iter_mut().fold(*x, |x, section| section.process(x)))
+            .collect();
+        for backend in [KernelBackend::Scalar, KernelBackend::detect()] {
+            let mut cascade = BiquadCascade::with_backend(& chain, backend).unwrap();
+            assert_eq!(cascade.get_sections().len(), 7);
+            let mut output = signal.clone();
+            let (mut start, mut length) = (0, 0);
+            while start < output.len() {
+                let end = (start + length).min(output.len());
+                cascade.process_inplace(& mut output[start..end]);
+                (start, length) = (end, 2 * length + 1);
+            }
+            assert!(output.iter().zip(& expected).all(|(y, expected)| y.to_bits() == expected.to_bits()));
+            cascade.reset();
+            assert_eq!(cascade.process(0.0), 0.0);
+        }
+        if !KernelBackend::Neon.is_available() {
+            assert!(BiquadCascade::with_backend(& chain, KernelBackend::Neon).is_err());
+        }
+    }
+}
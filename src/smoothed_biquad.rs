@@ -0,0 +1,248 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Smoothing of the changes of the parameters of a biquad, against the
+//!              zipper noise. New coefficients in a biquad that plays make a step in
+//!              its output, a click, and a gain knob turned by steps of 0.5 dB makes
+//!              a train of clicks, the zipper noise. SmoothedBiquad moves from the
+//!              current coefficients to the ones of a new design over a ramp time,
+//!              a few milliseconds, with a change of the coefficients at each sample.
+//!              Like in time_varying.rs the biquad runs as a lattice-ladder filter
+//!              and the ramp is on the lattice coefficients, so the filters of the
+//!              ramp are all stable, and the lattice state stays valid:
+//!
+//!                 c[n] = c_start + (c_target - c_start) t(n / N)    N samples of the ramp
+//!
+//!                 Linear         t(u) = u
+//!                 Exponential    t(u) = (1 - 0.001^u) / (1 - 0.001)
+//!
+//!              The exponential ramp is the one of a one pole smoother, fast at the
+//!              start, normalized to end at the target after the N samples, instead
+//!              of never. What is left of the click is in the corners of the ramp,
+//!              it falls 6 dB for each doubling of the ramp time, and the
+//!              exponential ramp, 7 times steeper at the start, needs a longer time
+//!              for the same click as the linear one, see the test. A new design in
+//!              the middle of a ramp starts a new ramp from the coefficients of that
+//!              sample. With a ramp of 0 samples the new coefficients apply at the
+//!              next sample, like in IIRFilter.
+//!              The Equalizer uses it for set_band_gain(), see set_smoothing().
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Zölzer, U. - DAFX: Digital Audio Effects, 2nd edition, Wiley, 2011
+//!
+//!    2. Lattice and ladder filters - Julius O. Smith III, Introduction to Digital Filters
+//!       https://ccrma.stanford.edu/~jos/filters/
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::iir_filter::IIRFilter;
+use crate::exact_math;
+use crate::time_varying::LatticeBiquad;
+use crate::time_varying::LatticeCoefficients;
+
+
+// The distance to the target left by the exponential ramp without its normalization,
+// -60 dB .
+const EXPONENTIAL_RAMP_FLOOR: f64 = 0.001;
+
+/// The shape of the ramp of the coefficients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RampShape {
+    Linear,
+    Exponential,
+}
+
+impl RampShape {
+    /// The fraction of the ramp done, of the fraction of the time u in [0, 1].
+    pub fn get_fraction(& self, u: f64) -> f64 {
+        match self {
+            RampShape::Linear      => u,
+            RampShape::Exponential => (1.0 - exact_math::powf(EXPONENTIAL_RAMP_FLOOR, u)) / (1.0 - EXPONENTIAL_RAMP_FLOOR),
+        }
+    }
+}
+
+/// A biquad with the changes of its design smoothed over a ramp.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut filter = SmoothedBiquad::new(& make_peak(1_000.0, 48_000, 0.0, None), 48_000, 20.0, RampShape::Linear)?;
+///    >>> filter.set_target(& make_peak(1_000.0, 48_000, gain_db, None))?;   // from the UI thread, at any time
+///    >>> filter.process_inplace(& mut buffer);
+///    ```
+///
+#[derive(Debug, Clone)]
+pub struct SmoothedBiquad {
+    sample_rate:  u32,
+    ramp_samples: usize,
+    shape:        RampShape,
+    lattice:      LatticeBiquad,
+    start:        LatticeCoefficients,
+    target:       LatticeCoefficients,
+    // Samples since the start of the ramp.
+    counter:      usize,
+}
+
+impl SmoothedBiquad {
+    /// The smoothed biquad of a design, with the ramp of ramp_ms milliseconds. Gives
+    /// an error if the design isn't a stable biquad or the ramp is negative.
+    pub fn new(filter: & IIRFilter, sample_rate: u32, ramp_ms: f64, shape: RampShape) -> Result<Self, String> {
+        let coefficients = LatticeCoefficients::from_iir_filter(filter)?;
+        let mut smoothed = SmoothedBiquad { sample_rate, ramp_samples: 0, shape, lattice: LatticeBiquad::new(coefficients),
+                                            start: coefficients, target: coefficients, counter: 0 };
+        smoothed.set_ramp(ramp_ms, shape)?;
+        Ok(smoothed)
+    }
+
+    /// Changes the ramp, a ramp in progress restarts with it from the current
+    /// coefficients.
+    pub fn set_ramp(& mut self, ramp_ms: f64, shape: RampShape) -> Result<(), String> {
+        if !(ramp_ms >= 0.0 && ramp_ms.is_finite()) {
+            return Err(format!("Error: the ramp time must be 0 ms or more, got {} ms", ramp_ms));
+        }
+        let ramp_samples = (ramp_ms / 1_000.0 * self.sample_rate as f64).round() as usize;
+        self.ramp_samples = ramp_samples;
+        self.shape = shape;
+        if self.is_ramping() {
+            self.start = self.lattice.get_coefficients();
+            self.counter = 0;
+            if ramp_samples == 0 {
+                self.lattice.set_coefficients(self.target);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_ramp_ms(& self) -> f64 {
+        self.ramp_samples as f64 / self.sample_rate as f64 * 1_000.0
+    }
+
+    pub fn get_ramp_shape(& self) -> RampShape {
+        self.shape
+    }
+
+    /// Starts a ramp from the current coefficients to the ones of a new design.
+    /// Gives an error, and keeps the current ramp, if the design isn't a stable
+    /// biquad.
+    pub fn set_target(& mut self, filter: & IIRFilter) -> Result<(), String> {
+        self.target = LatticeCoefficients::from_iir_filter(filter)?;
+        self.start = self.lattice.get_coefficients();
+        self.counter = 0;
+        if self.ramp_samples == 0 {
+            self.lattice.set_coefficients(self.target);
+        }
+        Ok(())
+    }
+
+    /// The coefficients of the next sample.
+    pub fn get_coefficients(& self) -> LatticeCoefficients {
+        self.lattice.get_coefficients()
+    }
+
+    /// The coefficients at the end of the ramp.
+    pub fn get_target(& self) -> LatticeCoefficients {
+        self.target
+    }
+
+    pub fn is_ramping(& self) -> bool {
+        self.lattice.get_coefficients() != self.target
+    }
+}
+
+impl ProcessingBlock for SmoothedBiquad {
+    fn process(& mut self, sample: f64) -> f64 {
+        if self.counter < self.ramp_samples {
+            self.counter += 1;
+            let coefficients = if self.counter == self.ramp_samples {
+                self.target
+            } else {
+                let u = self.counter as f64 / self.ramp_samples as f64;
+                self.start.interpolate(& self.target, self.shape.get_fraction(u))
+            };
+            self.lattice.set_coefficients(coefficients);
+        }
+        self.lattice.process(sample)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_peak;
+    use crate::butterworth_filter::make_butterworth_highpass;
+    use crate::equalizer::Equalizer;
+    use crate::analysis::peak;
+
+    #[test]
+    fn test_ramp_of_the_coefficients() {
+        let sample_rate = 48_000;
+        let flat = make_peak(1_000.0, sample_rate, 0.0, Some(2.0));
+        let boost = make_peak(1_000.0, sample_rate, 12.0, Some(2.0));
+        let target = LatticeCoefficients::from_iir_filter(& boost).unwrap();
+        for shape in [RampShape::Linear, RampShape::Exponential] {
+            let mut filter = SmoothedBiquad::new(& flat, sample_rate, 10.0, shape).unwrap();
+            assert_eq!((filter.get_ramp_ms(), filter.get_ramp_shape()), (10.0, shape));
+            filter.set_target(& boost).unwrap();
+            let mut last_distance = (filter.get_coefficients().k2 - target.k2).abs();
+            for _ in 0..480 {
+                assert!(filter.is_ramping());
+                filter.process(0.0);
+                // k2 goes to the target one way, the lattice stays stable.
+                let distance = (filter.get_coefficients().k2 - target.k2).abs();
+                assert!(distance <= last_distance && filter.get_coefficients().is_stable());
+                last_distance = distance;
+            }
+            assert!(!filter.is_ramping() && filter.get_coefficients() == target);
+        }
+        // At the middle of the time the exponential ramp is far ahead.
+        assert_eq!(RampShape::Linear.get_fraction(0.5), 0.5);
+        assert!(RampShape::Exponential.get_fraction(0.5) > 0.95);
+        assert_eq!(RampShape::Exponential.get_fraction(1.0), 1.0);
+
+        // Without a ramp, and after the ramp, it's the biquad of the design.
+        let mut filter = SmoothedBiquad::new(& flat, sample_rate, 0.0, RampShape::Linear).unwrap();
+        filter.set_target(& boost).unwrap();
+        let mut biquad = boost.clone();
+        for n in 0..500 {
+            let x = f64::sin(n as f64 * 0.37);
+            assert!((filter.process(x) - biquad.process(x)).abs() < 1e-12);
+        }
+        let mut unstable = IIRFilter::new(2);
+        unstable.set_coefficients(& [1.0, -2.0, 1.1], & [1.0, 0.0, 0.0]).unwrap();
+        assert!(filter.set_target(& unstable).is_err());
+        assert!(filter.set_ramp(-1.0, RampShape::Linear).is_err());
+    }
+
+    #[test]
+    fn test_equalizer_gain_change_without_click() {
+        // A 1 kHz sine, the 947 Hz band from 0 to -24 dB in the middle. The click
+        // is the peak above 12 kHz, where the sine isn't.
+        let sample_rate = 48_000;
+        let input: Vec<f64> = (0..9_600).map(|n| 0.5 * f64::sin(std::f64::consts::TAU * 1_000.0 * n as f64 / 48_000.0)).collect();
+        let click_db = |ramp_ms: f64, shape: RampShape| {
+            let mut eq = Equalizer::make_equalizer_10_band(sample_rate);
+            eq.set_smoothing(ramp_ms, shape).unwrap();
+            assert_eq!(eq.get_smoothing_ms(), ramp_ms);
+            let mut output = input.clone();
+            eq.process_inplace(& mut output[..4_800]);
+            eq.set_band_gain(5, -24.0).unwrap();
+            eq.process_inplace(& mut output[4_800..]);
+            let mut highpass = make_butterworth_highpass(12_000.0, sample_rate, 4).unwrap();
+            highpass.process_inplace(& mut output);
+            20.0 * f64::log10(peak(& output[4_800..]))
+        };
+        // About -47 dBFS without the smoothing, -65 dBFS with a linear ramp of 20 ms
+        // and -73 dBFS with 50 ms, the click falls 6 dB for each doubling of the
+        // ramp. The exponential ramp of the same time is steeper at its start.
+        let abrupt = click_db(0.0, RampShape::Linear);
+        assert!(click_db(20.0, RampShape::Linear) < abrupt - 15.0);
+        assert!(click_db(50.0, RampShape::Linear) < abrupt - 25.0);
+        assert!(click_db(50.0, RampShape::Exponential) < abrupt - 5.0);
+    }
+}
@@ -0,0 +1,220 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Fractional octave smoothing of measured magnitude responses.
+//!              The response of a loudspeaker in a room, measured with the FFT of
+//!              its impulse response, has many narrow peaks and dips, that aren't
+//!              heard and shouldn't be corrected. Each point of the smoothed response
+//!              is the power average of the points in a band centered on it, with a
+//!              width of a fraction of an octave, so the band is wider at high
+//!              frequencies, like the resolution of the ear.
+//!
+//!              The variable smoothing, as used in room measurement software, is
+//!              1/48 octave below 100 Hz, where the room modes are corrected, and
+//!              widens to 1/3 octave above 10 kHz. The width in octaves is
+//!              interpolated on the log of the frequency in between.
+//!
+//!              The levels of the ISO octave or third-octave bands, like the bars of
+//!              a real time analyzer, are the power average of the points in each
+//!              band, see iso_bands.rs .
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Hatziantoniou, P. D., Mourjopoulos, J. N., "Generalized Fractional-Octave
+//!       Smoothing of Audio and Acoustic Responses", J. Audio Eng. Soc. 2000.
+//!
+//!    2. REW - Graph smoothing
+//!       https://www.roomeqwizard.com/help/help_en-GB/html/graph.html
+//!
+
+
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+
+use crate::iso_bands::BandWidth;
+use crate::iso_bands::iso_bands;
+
+
+/// The width of the smoothing band.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Smoothing {
+    /// 1/n octave, like 3.0, 6.0 or 12.0 .
+    Octave(f64),
+    /// 1/48 octave below 100 Hz to 1/3 octave above 10 kHz.
+    Variable,
+}
+
+impl Smoothing {
+    /// Width of the band in octaves at the frequency.
+    pub fn get_bandwidth_octaves(& self, frequency: f64) -> f64 {
+        match self {
+            Smoothing::Octave(fraction) => 1.0 / fraction,
+            Smoothing::Variable => {
+                let (low, high) = (100.0, 10_000.0);
+                let t = (f64::log10(frequency / low) / f64::log10(high / low)).clamp(0.0, 1.0);
+                1.0 / 48.0 + (1.0 / 3.0 - 1.0 / 48.0) * t
+            }
+        }
+    }
+}
+
+/// Frequencies and magnitudes in dB of the FFT bins, from 0 Hz to the Nyquist
+/// frequency, of an impulse response.
+pub fn magnitude_response_db(impulse_response: & [f64], sample_rate: u32) -> (Vec<f64>, Vec<f64>) {
+    let length = impulse_response.len();
+    let mut spectrum: Vec<Complex<f64>> = impulse_response.iter().map(|x| Complex::new(*x, 0.0)).collect();
+    FftPlanner::<f64>::new().plan_fft_forward(length).process(& mut spectrum);
+    let num_bins = length / 2 + 1;
+    let frequencies = (0..num_bins).map(|k| k as f64 * sample_rate as f64 / length as f64).collect();
+    let magnitudes_db = spectrum[..num_bins].iter().map(|x| 20.0 * f64::log10(x.norm())).collect();
+    (frequencies, magnitudes_db)
+}
+
+/// Smooths the magnitudes, in dB, at the increasing frequencies (like the FFT bins).
+/// The points at 0 Hz are unchanged.
+///
+/// In Rust:
+///    ```text
+///    >>> let (frequencies, magnitudes_db) = magnitude_response_db(& impulse_response, 48_000);
+///    >>> let smoothed = smooth_response(& frequencies, & magnitudes_db, Smoothing::Octave(3.0))?;
+///    ```
+///
+pub fn smooth_response(frequencies: & [f64], magnitudes_db: & [f64], smoothing: Smoothing) -> Result<Vec<f64>, String> {
+    if frequencies.len() != magnitudes_db.len() {
+        return Err(format!("Error: {} frequencies and {} magnitudes", frequencies.len(), magnitudes_db.len()));
+    }
+    if frequencies.windows(2).any(|w| w[1] <= w[0]) || frequencies.first().is_some_and(|f| *f < 0.0) {
+        return Err("Error: the frequencies must be positive and increasing".to_string());
+    }
+    if let Smoothing::Octave(fraction) = smoothing {
+        if fraction <= 0.0 {
+            return Err(format!("Error: the octave fraction must be positive, got {}", fraction));
+        }
+    }
+    // Prefix sums of the power, the average of a band is a difference.
+    let mut power_sums = vec![0.0; frequencies.len() + 1];
+    for (i, db) in magnitudes_db.iter().enumerate() {
+        power_sums[i + 1] = power_sums[i] + f64::powf(10.0, db / 10.0);
+    }
+    let smoothed = frequencies.iter().zip(magnitudes_db).map(|(frequency, db)| {
+        if *frequency == 0.0 {
+            return *db;
+        }
+        let half_width = f64::powf(2.0, smoothing.get_bandwidth_octaves(*frequency) / 2.0);
+        let start = frequencies.partition_point(|f| *f < frequency / half_width);
+        let end = frequencies.partition_point(|f| *f <= frequency * half_width);
+        10.0 * f64::log10((power_sums[end] - power_sums[start]) / (end - start) as f64)
+    }).collect();
+    Ok(smoothed)
+}
+
+/// The level of each ISO band, the power average in dB of the magnitudes at the
+/// frequencies in the band, as (nominal frequency, level). The bands without a
+/// frequency are left out.
+///
+/// In Rust:
+///    ```text
+///    >>> let (frequencies, magnitudes_db) = magnitude_response_db(& impulse_response, 48_000);
+///    >>> for (frequency, level_db) in iso_band_levels_db(& frequencies, & magnitudes_db, BandWidth::ThirdOctave)? {
+///    >>>     println!("{:>7} Hz  {:6.1} dB", frequency, level_db);
+///    >>> }
+///    ```
+///
+pub fn iso_band_levels_db(frequencies: & [f64], magnitudes_db: & [f64], width: BandWidth) -> Result<Vec<(f64, f64)>, String> {
+    if frequencies.len() != magnitudes_db.len() {
+        return Err(format!("Error: {} frequencies and {} magnitudes", frequencies.len(), magnitudes_db.len()));
+    }
+    if frequencies.windows(2).any(|w| w[1] <= w[0]) {
+        return Err("Error: the frequencies must be increasing".to_string());
+    }
+    let levels = iso_bands(width).iter()
+        .filter_map(|band| {
+            let start = frequencies.partition_point(|f| *f < band.lower_frequency);
+            let end = frequencies.partition_point(|f| *f < band.upper_frequency);
+            if start == end {
+                return None;
+            }
+            let power: f64 = magnitudes_db[start..end].iter().map(|db| f64::powf(10.0, db / 10.0)).sum();
+            Some((band.nominal_frequency, 10.0 * f64::log10(power / (end - start) as f64)))
+        })
+        .collect();
+    Ok(levels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iir_filter::ProcessingBlock; // Trait
+    use crate::butterworth_filter::make_lowpass;
+
+    // Linear bins of 1 Hz, like an FFT.
+    fn make_frequencies() -> Vec<f64> {
+        (0..20_000).map(|n| n as f64).collect()
+    }
+
+    #[test]
+    fn test_octave_smoothing_averages_the_ripple() {
+        let frequencies = make_frequencies();
+        // +3 dB / -3 dB ripple, the power average is 10 log10((2 + 0.5) / 2) = 0.97 dB .
+        let magnitudes_db: Vec<f64> = (0..frequencies.len()).map(|n| if n % 2 == 0 { 3.0103 } else { -3.0103 }).collect();
+        let smoothed = smooth_response(& frequencies, & magnitudes_db, Smoothing::Octave(3.0)).unwrap();
+        for index in [1_000, 5_000, 15_000] {
+            assert!((smoothed[index] - 0.969).abs() < 0.01);
+        }
+        assert_eq!(smoothed[0], 3.0103);
+
+        let flat = smooth_response(& frequencies, & vec![-6.0; frequencies.len()], Smoothing::Octave(12.0)).unwrap();
+        assert!(flat.iter().all(|db| (db + 6.0).abs() < 1e-9));
+        assert!(smooth_response(& frequencies, & magnitudes_db[1..], Smoothing::Variable).is_err());
+        assert!(smooth_response(& [2.0, 1.0], & [0.0, 0.0], Smoothing::Variable).is_err());
+    }
+
+    #[test]
+    fn test_smoothed_filter_response() {
+        let mut filter = make_lowpass(1_000.0, 48_000, None);
+        let impulse_response: Vec<f64> = (0..4_800).map(|n| filter.process(if n == 0 { 1.0 } else { 0.0 })).collect();
+        let (frequencies, magnitudes_db) = magnitude_response_db(& impulse_response, 48_000);
+        assert_eq!(frequencies.len(), 2_401);
+        assert_eq!(frequencies[100], 1_000.0);
+        assert!((magnitudes_db[100] + 3.01).abs() < 0.01);
+        // The smoothing of a smooth response changes it little.
+        let smoothed = smooth_response(& frequencies, & magnitudes_db, Smoothing::Octave(6.0)).unwrap();
+        assert!((smoothed[100] - magnitudes_db[100]).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_variable_smoothing_keeps_the_low_frequency_detail() {
+        let frequencies = make_frequencies();
+        // A narrow dip at 60 Hz, and one at 12 kHz.
+        let magnitudes_db: Vec<f64> = frequencies.iter()
+            .map(|f| if (*f - 60.0).abs() < 1.0 || (*f - 12_000.0).abs() < 1.0 { -20.0 } else { 0.0 })
+            .collect();
+        let variable = smooth_response(& frequencies, & magnitudes_db, Smoothing::Variable).unwrap();
+        let third = smooth_response(& frequencies, & magnitudes_db, Smoothing::Octave(3.0)).unwrap();
+        assert!(variable[60] < -10.0);
+        assert!(third[60] > -3.0);
+        assert!(variable[12_000].abs() < 0.1);
+
+        assert_eq!(Smoothing::Variable.get_bandwidth_octaves(50.0), 1.0 / 48.0);
+        assert_eq!(Smoothing::Variable.get_bandwidth_octaves(20_000.0), 1.0 / 3.0);
+    }
+
+    #[test]
+    fn test_iso_band_levels() {
+        let frequencies = make_frequencies();
+        // -6 dB from 900 Hz to 1100 Hz, inside the third-octave band of 1 kHz.
+        let magnitudes_db: Vec<f64> = frequencies.iter().map(|f| if (900.0..1_100.0).contains(f) { -6.0 } else { 0.0 }).collect();
+        let levels = iso_band_levels_db(& frequencies, & magnitudes_db, BandWidth::ThirdOctave).unwrap();
+        assert_eq!(levels.len(), 31);
+        let (frequency, level_db) = levels[17];
+        assert_eq!(frequency, 1_000.0);
+        // 200 of the 231 points of the band at a quarter of the power.
+        assert!((level_db - 10.0 * f64::log10((200.0 * 0.251_189 + 31.0) / 231.0)).abs() < 0.01, "{}", level_db);
+        assert!(levels.iter().filter(|(f, _)| *f != 1_000.0).all(|(_, db)| db.abs() < 1e-9));
+        assert!(iso_band_levels_db(& frequencies, & magnitudes_db[1..], BandWidth::Octave).is_err());
+    }
+}
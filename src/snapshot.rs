@@ -0,0 +1,281 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Snapshots of the settings of equalizers and chains, with an undo /
+//!              redo history.
+//!              While tweaking an EQ live, every change can be captured as a
+//!              snapshot, and the history steps back and forward between them. A
+//!              processor that implements the Snapshot trait captures its settings
+//!              in a State value, and restores them later, without resetting the
+//!              filter state, so the sound doesn't click.
+//!
+//!              The snapshot of a ParametricEq is an EqProfile, so a list of
+//!              snapshots is saved in the Equalizer APO syntax of the presets, each
+//!              one after a "# Snapshot: <name>" comment line.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use crate::parametric_eq::ParametricEq;
+use crate::equalizer::Equalizer;
+use crate::eq_profile::EqProfile;
+use crate::eq_profile::parse_equalizer_apo_config;
+use crate::eq_profile::write_equalizer_apo_config;
+
+
+const SNAPSHOT_HEADER: &str = "# Snapshot:";
+
+/// A processor whose settings can be captured and restored.
+pub trait Snapshot {
+    type State: Clone;
+
+    /// The current settings.
+    fn capture(& self) -> Self::State;
+
+    /// Changes the settings to the state. On error the settings are unchanged.
+    fn restore(& mut self, state: & Self::State) -> Result<(), String>;
+}
+
+impl Snapshot for ParametricEq {
+    type State = EqProfile;
+
+    fn capture(& self) -> EqProfile {
+        EqProfile::from_parametric_eq(self)
+    }
+
+    fn restore(& mut self, state: & EqProfile) -> Result<(), String> {
+        // Validates all the bands before changing anything.
+        state.to_parametric_eq(self.get_sample_rate())?;
+        if self.get_num_bands() == state.bands.len() {
+            // Keeps the filter states.
+            for (index, band) in state.bands.iter().enumerate() {
+                self.set_band(index, *band)?;
+            }
+        } else {
+            self.clear_bands();
+            for band in & state.bands {
+                self.add_band(*band)?;
+            }
+        }
+        self.set_preamp_db(state.preamp_db);
+        Ok(())
+    }
+}
+
+impl Snapshot for Equalizer {
+    /// The gain in dB of each band.
+    type State = Vec<f64>;
+
+    fn capture(& self) -> Vec<f64> {
+        (0..self.get_num_bands()).map(|index| self.get_band_gain(index)).collect()
+    }
+
+    fn restore(& mut self, state: & Vec<f64>) -> Result<(), String> {
+        if state.len() != self.get_num_bands() {
+            return Err(format!("Error: the snapshot has {} band gains, the equalizer has {} bands",
+                               state.len(), self.get_num_bands()));
+        }
+        let previous = self.capture();
+        for (index, gain_db) in state.iter().enumerate() {
+            if let Err(error) = self.set_band_gain(index, *gain_db) {
+                // The previous gains are valid.
+                for (index, gain_db) in previous.iter().enumerate() {
+                    self.set_band_gain(index, *gain_db).unwrap();
+                }
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Linear undo / redo history of snapshots. Capturing a new snapshot after an undo
+/// drops the snapshots that could be redone.
+pub struct SnapshotHistory<S: Clone> {
+    states:   Vec<S>,
+    // Index of the current state.
+    position: usize,
+    max_len:  usize,
+}
+
+impl<S: Clone> SnapshotHistory<S> {
+    /// The history starts with the initial state, and keeps up to max_len states,
+    /// the oldest ones are dropped.
+    ///
+    /// In Rust:
+    ///    ```text
+    ///    >>> let mut history = SnapshotHistory::new(eq.capture(), 100);
+    ///    >>> eq.set_band(0, new_band)?;
+    ///    >>> history.capture(eq.capture());
+    ///    >>> if let Some(state) = history.undo() {
+    ///    >>>     eq.restore(state)?;
+    ///    >>> }
+    ///    ```
+    ///
+    pub fn new(initial: S, max_len: usize) -> Self {
+        assert!(max_len >= 1);
+        SnapshotHistory { states: vec![initial], position: 0, max_len }
+    }
+
+    pub fn get_current(& self) -> & S {
+        & self.states[self.position]
+    }
+
+    /// All the states, from the oldest, including the ones that can be redone.
+    pub fn get_states(& self) -> & [S] {
+        & self.states
+    }
+
+    pub fn get_position(& self) -> usize {
+        self.position
+    }
+
+    pub fn can_undo(& self) -> bool {
+        self.position > 0
+    }
+
+    pub fn can_redo(& self) -> bool {
+        self.position + 1 < self.states.len()
+    }
+
+    /// Adds the state after the current one, it becomes the current state.
+    pub fn capture(& mut self, state: S) {
+        self.states.truncate(self.position + 1);
+        self.states.push(state);
+        if self.states.len() > self.max_len {
+            self.states.remove(0);
+        }
+        self.position = self.states.len() - 1;
+    }
+
+    /// Steps back, returns the state to restore, or None at the oldest state.
+    pub fn undo(& mut self) -> Option<& S> {
+        if !self.can_undo() {
+            return None;
+        }
+        self.position -= 1;
+        Some(& self.states[self.position])
+    }
+
+    /// Steps forward, returns the state to restore, or None at the newest state.
+    pub fn redo(& mut self) -> Option<& S> {
+        if !self.can_redo() {
+            return None;
+        }
+        self.position += 1;
+        Some(& self.states[self.position])
+    }
+}
+
+/// Writes named EQ snapshots, each one in the Equalizer APO syntax after a
+/// "# Snapshot: <name>" line.
+pub fn write_eq_snapshots(snapshots: & [(String, EqProfile)]) -> String {
+    let mut text = String::new();
+    for (name, profile) in snapshots {
+        text += & format!("{} {}\n", SNAPSHOT_HEADER, name);
+        text += & write_equalizer_apo_config(profile);
+        text += "\n";
+    }
+    text
+}
+
+/// Parses the text of write_eq_snapshots().
+pub fn parse_eq_snapshots(text: & str) -> Result<Vec<(String, EqProfile)>, String> {
+    let mut snapshots = Vec::new();
+    let mut current: Option<(String, String)> = None;
+    for line in text.lines() {
+        if let Some(name) = line.trim().strip_prefix(SNAPSHOT_HEADER) {
+            if let Some((name, body)) = current.take() {
+                snapshots.push((name, parse_equalizer_apo_config(& body)?));
+            }
+            current = Some((name.trim().to_string(), String::new()));
+        } else if let Some((_, body)) = & mut current {
+            *body += line;
+            *body += "\n";
+        } else if !line.trim().is_empty() {
+            return Err(format!("Error: expected \"{} <name>\" before \"{}\"", SNAPSHOT_HEADER, line));
+        }
+    }
+    if let Some((name, body)) = current {
+        snapshots.push((name, parse_equalizer_apo_config(& body)?));
+    }
+    Ok(snapshots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parametric_eq::ParametricBand;
+    use crate::parametric_eq::BandType;
+
+    #[test]
+    fn test_undo_redo_parametric_eq() {
+        let mut eq = ParametricEq::new(48_000);
+        let mut history = SnapshotHistory::new(eq.capture(), 10);
+        eq.add_band(ParametricBand::new(BandType::Peak, 1_000.0, 3.0, 1.0)).unwrap();
+        history.capture(eq.capture());
+        eq.set_band(0, ParametricBand::new(BandType::Peak, 1_000.0, -3.0, 1.0)).unwrap();
+        eq.set_preamp_db(-2.0);
+        history.capture(eq.capture());
+
+        eq.restore(& history.undo().unwrap().clone()).unwrap();
+        assert_eq!(eq.get_band(0).gain_db, 3.0);
+        assert_eq!(eq.get_preamp_db(), 0.0);
+        eq.restore(& history.undo().unwrap().clone()).unwrap();
+        assert_eq!(eq.get_num_bands(), 0);
+        assert!(history.undo().is_none());
+        eq.restore(& history.redo().unwrap().clone()).unwrap();
+        eq.restore(& history.redo().unwrap().clone()).unwrap();
+        assert_eq!(eq.get_band(0).gain_db, -3.0);
+        assert!(history.redo().is_none());
+
+        // A new capture after an undo drops the redo.
+        history.undo();
+        history.capture(eq.capture());
+        assert!(!history.can_redo());
+        assert_eq!(history.get_states().len(), 3);
+
+        // An invalid state doesn't change the equalizer.
+        let invalid = EqProfile { preamp_db: 0.0, bands: vec![ParametricBand::new(BandType::Peak, 30_000.0, 1.0, 1.0)] };
+        assert!(eq.restore(& invalid).is_err());
+        assert_eq!(eq.get_band(0).gain_db, -3.0);
+    }
+
+    #[test]
+    fn test_history_max_len_and_equalizer() {
+        let mut eq = Equalizer::make_equalizer_10_band(48_000);
+        let mut history = SnapshotHistory::new(eq.capture(), 3);
+        for gain_db in [1.0, 2.0, 3.0, 4.0] {
+            eq.set_band_gain(0, gain_db).unwrap();
+            history.capture(eq.capture());
+        }
+        assert_eq!(history.get_states().len(), 3);
+        eq.restore(& history.undo().unwrap().clone()).unwrap();
+        eq.restore(& history.undo().unwrap().clone()).unwrap();
+        assert_eq!(eq.get_band_gain(0), 2.0);
+        assert!(history.undo().is_none());
+
+        assert!(eq.restore(& vec![0.0; 3]).is_err());
+        let mut out_of_range = vec![0.0; 10];
+        out_of_range[5] = 1_000.0;
+        assert!(eq.restore(& out_of_range).is_err());
+        assert_eq!(eq.capture()[0], 2.0);
+    }
+
+    #[test]
+    fn test_eq_snapshots_text_round_trip() {
+        let snapshots = vec![
+            ("flat".to_string(), EqProfile { preamp_db: 0.0, bands: vec![] }),
+            ("bass boost".to_string(), EqProfile { preamp_db: -4.0,
+                bands: vec![ParametricBand::new(BandType::LowShelf, 105.0, 4.0, 0.7)] }),
+        ];
+        let text = write_eq_snapshots(& snapshots);
+        assert!(text.starts_with("# Snapshot: flat\nPreamp: 0 dB\n"));
+        assert_eq!(parse_eq_snapshots(& text).unwrap(), snapshots);
+        assert!(parse_eq_snapshots("Preamp: 1 dB\n").is_err());
+    }
+}
@@ -0,0 +1,272 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Spectral tilt, its measurement and a one-knob tilt EQ.
+//!              The tonal balance of a recording, dark or bright, is mostly the
+//!              overall slope of its spectrum, in dB per octave. The episodes of a
+//!              podcast, recorded on different days, with different microphones,
+//!              are matched by their tilt long before any detail of their EQ.
+//!              The tilt is the slope of the least squares line of the third-octave
+//!              levels of the Welch PSD, see welch.rs and smoothing.rs, against the
+//!              octaves from the pivot frequency:
+//!
+//!                 L(f) = level + tilt * log2(f / pivot)
+//!
+//!              Each third-octave band has the same weight, so each octave has the
+//!              same weight, not each FFT bin. The PSD is a density, white noise has a
+//!              tilt of 0 dB/octave and pink noise of -3 dB/octave, and speech is
+//!              usually between -4 and -7 dB/octave.
+//!              The tilt EQ is a cascade of high shelves of Q 0.7071, one at each edge
+//!              between two ISO octave bands, see iso_bands.rs, each with a gain equal
+//!              to the tilt. The shelves are one octave apart and their sum is a
+//!              straight line, within 0.2 dB, from the first to the last octave band
+//!              below the Nyquist frequency, and flat outside. The preamp keeps the
+//!              gain at the pivot frequency at 0 dB, so the loudness of the voice
+//!              stays about the same. The one knob is the tilt, in dB/octave.
+//!              The corrector measures the tilt of a recording and sets the knob to
+//!              the difference to a target tilt, a fixed one or the tilt of a
+//!              reference episode.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use std::f64::consts::FRAC_1_SQRT_2;
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::audio_buffer::AudioBuffer;
+use crate::welch::WelchConfig;
+use crate::welch::welch_psd;
+use crate::smoothing::iso_band_levels_db;
+use crate::iso_bands::BandWidth;
+use crate::iso_bands::iso_bands_below;
+use crate::parametric_eq::ParametricEq;
+use crate::parametric_eq::ParametricBand;
+use crate::parametric_eq::BandType;
+use crate::preview::process_range;
+
+
+pub const DEFAULT_PIVOT_FREQUENCY: f64 = 1_000.0;
+/// The frequencies of the fit, the band of the voice and of most instruments.
+pub const DEFAULT_MIN_FREQUENCY: f64 = 50.0;
+pub const DEFAULT_MAX_FREQUENCY: f64 = 16_000.0;
+/// The range of the knob of the tilt EQ, in dB/octave.
+pub const MAX_TILT_DB_PER_OCTAVE: f64 = 6.0;
+
+// The segment length of the Welch PSD, about 6 Hz per bin at 48 kHz.
+const FFT_SIZE: usize = 8_192;
+
+/// The tilt of a recording, the least squares line of its third-octave levels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectralTilt {
+    pub tilt_db_per_octave: f64,
+    /// The level of the line at the pivot frequency, in dB of the PSD.
+    pub level_db:           f64,
+    pub pivot_frequency:    f64,
+    /// The RMS distance of the levels to the line, how far the spectrum is from
+    /// a plain tilt.
+    pub rms_error_db:       f64,
+}
+
+/// The tilt of the signal from the minimum to the maximum frequency.
+///
+/// In Rust:
+///    ```text
+///    >>> let tilt = measure_tilt(& episode.mixdown(), episode.sample_rate, DEFAULT_MIN_FREQUENCY,
+///    >>>                         DEFAULT_MAX_FREQUENCY)?;
+///    >>> println!("{:.2} dB/octave", tilt.tilt_db_per_octave);
+///    ```
+///
+pub fn measure_tilt(signal: & [f64], sample_rate: u32, min_frequency: f64, max_frequency: f64)
+        -> Result<SpectralTilt, String> {
+    if !(min_frequency > 0.0 && min_frequency < max_frequency) {
+        return Err(format!("Error: invalid tilt band from {} Hz to {} Hz", min_frequency, max_frequency));
+    }
+    let _span = trace_span!("measure_tilt", samples = signal.len(), sample_rate);
+    let psd = welch_psd(signal, & WelchConfig::new(sample_rate, FFT_SIZE))?;
+    let levels: Vec<(f64, f64)> = iso_band_levels_db(& psd.frequencies, & psd.get_psd_db(), BandWidth::ThirdOctave)?
+        .into_iter()
+        .filter(|(frequency, _)| *frequency >= min_frequency && *frequency <= max_frequency)
+        .filter(|(frequency, _)| *frequency < sample_rate as f64 / 2.0)
+        .collect();
+    if levels.len() < 2 {
+        return Err(format!("Error: less than 2 third-octave bands from {} Hz to {} Hz", min_frequency, max_frequency));
+    }
+
+    // The least squares line, x in octaves from the pivot.
+    let pivot_frequency = DEFAULT_PIVOT_FREQUENCY;
+    let n = levels.len() as f64;
+    let xs: Vec<f64> = levels.iter().map(|(frequency, _)| f64::log2(frequency / pivot_frequency)).collect();
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = levels.iter().map(|(_, level)| level).sum::<f64>() / n;
+    let covariance: f64 = xs.iter().zip(& levels).map(|(x, (_, y))| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = xs.iter().map(|x| (x - mean_x) * (x - mean_x)).sum();
+    let tilt_db_per_octave = covariance / variance;
+    let level_db = mean_y - tilt_db_per_octave * mean_x;
+    let squared_error: f64 = xs.iter().zip(& levels)
+        .map(|(x, (_, y))| f64::powi(y - (level_db + tilt_db_per_octave * x), 2))
+        .sum();
+    Ok(SpectralTilt {
+        tilt_db_per_octave,
+        level_db,
+        pivot_frequency,
+        rms_error_db: f64::sqrt(squared_error / n),
+    })
+}
+
+/// The tilt of the mixdown of the audio, from DEFAULT_MIN_FREQUENCY to
+/// DEFAULT_MAX_FREQUENCY.
+pub fn measure_audio_tilt(audio: & AudioBuffer) -> Result<SpectralTilt, String> {
+    measure_tilt(& audio.mixdown(), audio.sample_rate, DEFAULT_MIN_FREQUENCY, DEFAULT_MAX_FREQUENCY)
+}
+
+/// A one-knob EQ that tilts the spectrum around a pivot frequency.
+pub struct TiltEq {
+    pivot_frequency:    f64,
+    tilt_db_per_octave: f64,
+    eq:                 ParametricEq,
+}
+
+impl TiltEq {
+    /// A flat tilt EQ, the knob at 0 dB/octave.
+    pub fn new(sample_rate: u32, pivot_frequency: f64) -> Result<Self, String> {
+        let edges: Vec<f64> = iso_bands_below(BandWidth::Octave, sample_rate as f64 / 2.0).windows(2)
+            .map(|pair| pair[0].upper_frequency)
+            .collect();
+        if edges.is_empty() {
+            return Err(format!("Error: the sample rate {} Hz is too low for a tilt EQ", sample_rate));
+        }
+        if !(pivot_frequency >= edges[0] && pivot_frequency <= edges[edges.len() - 1]) {
+            return Err(format!("Error: invalid pivot frequency {} Hz, must be in the interval [{:.1}, {:.1}]",
+                               pivot_frequency, edges[0], edges[edges.len() - 1]));
+        }
+        let bands: Vec<ParametricBand> = edges.iter()
+            .map(|edge| ParametricBand::new(BandType::HighShelf, *edge, 0.0, FRAC_1_SQRT_2))
+            .collect();
+        let eq = ParametricEq::from_bands(sample_rate, 0.0, & bands)?;
+        Ok(TiltEq { pivot_frequency, tilt_db_per_octave: 0.0, eq })
+    }
+
+    pub fn get_pivot_frequency(& self) -> f64 {
+        self.pivot_frequency
+    }
+
+    pub fn get_tilt_db_per_octave(& self) -> f64 {
+        self.tilt_db_per_octave
+    }
+
+    /// Turns the knob, a positive tilt makes the sound brighter. The state of the
+    /// filters is kept, the knob can be turned while playing.
+    pub fn set_tilt_db_per_octave(& mut self, tilt_db_per_octave: f64) -> Result<(), String> {
+        if tilt_db_per_octave.is_nan() || tilt_db_per_octave.abs() > MAX_TILT_DB_PER_OCTAVE {
+            return Err(format!("Error: invalid tilt {} dB/octave, must be in the interval [-{}, {}]",
+                               tilt_db_per_octave, MAX_TILT_DB_PER_OCTAVE, MAX_TILT_DB_PER_OCTAVE));
+        }
+        for index in 0..self.eq.get_num_bands() {
+            let mut band = self.eq.get_band(index);
+            band.gain_db = tilt_db_per_octave;
+            self.eq.set_band(index, band)?;
+        }
+        // 0 dB at the pivot.
+        self.eq.set_preamp_db(0.0);
+        let preamp_db = -self.eq.magnitude_db(self.pivot_frequency);
+        self.eq.set_preamp_db(preamp_db);
+        self.tilt_db_per_octave = tilt_db_per_octave;
+        trace_event!(tilt_db_per_octave, preamp_db, "tilt EQ set");
+        Ok(())
+    }
+
+    /// The shelves and the preamp, to save as an Equalizer APO profile.
+    pub fn get_parametric_eq(& self) -> & ParametricEq {
+        & self.eq
+    }
+
+    /// Gain in dB of the tilt EQ at a frequency.
+    pub fn magnitude_db(& self, frequency: f64) -> f64 {
+        self.eq.magnitude_db(frequency)
+    }
+}
+
+impl ProcessingBlock for TiltEq {
+    fn process(& mut self, sample: f64) -> f64 {
+        self.eq.process(sample)
+    }
+}
+
+/// Tilts the audio to the target tilt, each channel with its own tilt EQ of pivot
+/// DEFAULT_PIVOT_FREQUENCY. Returns the output and the tilt measured before. The
+/// correction is limited to the range of the knob.
+///
+/// In Rust:
+///    ```text
+///    >>> // Each episode with the tonal balance of the first one.
+///    >>> let target = measure_audio_tilt(& first_episode)?.tilt_db_per_octave;
+///    >>> let (corrected, measured) = correct_tilt(& episode, target)?;
+///    ```
+///
+pub fn correct_tilt(audio: & AudioBuffer, target_tilt_db_per_octave: f64) -> Result<(AudioBuffer, SpectralTilt), String> {
+    let measured = measure_audio_tilt(audio)?;
+    let correction = (target_tilt_db_per_octave - measured.tilt_db_per_octave)
+        .clamp(-MAX_TILT_DB_PER_OCTAVE, MAX_TILT_DB_PER_OCTAVE);
+    let make_tilt_eq = || -> Result<TiltEq, String> {
+        let mut tilt_eq = TiltEq::new(audio.sample_rate, DEFAULT_PIVOT_FREQUENCY)?;
+        tilt_eq.set_tilt_db_per_octave(correction)?;
+        Ok(tilt_eq)
+    };
+    // Checks the tilt EQ once, before the processing.
+    make_tilt_eq()?;
+    let output = process_range(audio, 0.0, None, 0.0, |_| make_tilt_eq().unwrap())?;
+    Ok((output, measured))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::make_white_noise;
+
+    // Noise with a tilt, white noise through the tilt EQ.
+    fn tilted_noise(tilt_db_per_octave: f64, sample_rate: u32, num_samples: usize) -> Vec<f64> {
+        let mut tilt_eq = TiltEq::new(sample_rate, DEFAULT_PIVOT_FREQUENCY).unwrap();
+        tilt_eq.set_tilt_db_per_octave(tilt_db_per_octave).unwrap();
+        make_white_noise(num_samples, 0.05, 7).iter()
+            .map(|x| tilt_eq.process(*x))
+            .collect()
+    }
+
+    #[test]
+    fn test_tilt_eq() {
+        let mut tilt_eq = TiltEq::new(48_000, DEFAULT_PIVOT_FREQUENCY).unwrap();
+        assert_eq!(tilt_eq.get_parametric_eq().get_num_bands(), 9);
+        assert!(tilt_eq.magnitude_db(100.0).abs() < 1e-9);
+        tilt_eq.set_tilt_db_per_octave(-3.0).unwrap();
+        assert!(tilt_eq.magnitude_db(1_000.0).abs() < 1e-9);
+        // A straight line from 62.5 Hz to 8 kHz.
+        let max_error = (0..=70).map(|n| 62.5 * f64::powf(2.0, n as f64 / 10.0))
+            .map(|frequency| (tilt_eq.magnitude_db(frequency) + 3.0 * f64::log2(frequency / 1_000.0)).abs())
+            .fold(0.0, f64::max);
+        assert!(max_error < 0.2, "{}", max_error);
+        assert!(tilt_eq.set_tilt_db_per_octave(7.0).is_err());
+        assert!(TiltEq::new(48_000, 20.0).is_err());
+        assert_eq!(TiltEq::new(22_050, DEFAULT_PIVOT_FREQUENCY).unwrap().get_parametric_eq().get_num_bands(), 7);
+    }
+
+    #[test]
+    fn test_measure_and_correct_tilt() {
+        let white = tilted_noise(0.0, 48_000, 96_000);
+        let tilt = measure_tilt(& white, 48_000, DEFAULT_MIN_FREQUENCY, DEFAULT_MAX_FREQUENCY).unwrap();
+        assert!(tilt.tilt_db_per_octave.abs() < 0.2, "{:?}", tilt);
+        let dark = AudioBuffer::new(48_000, vec![tilted_noise(-4.0, 48_000, 96_000)]);
+        let tilt = measure_audio_tilt(& dark).unwrap();
+        assert!((tilt.tilt_db_per_octave + 4.0).abs() < 0.3, "{:?}", tilt);
+        assert!(tilt.rms_error_db < 1.5, "{:?}", tilt);
+
+        let (corrected, measured) = correct_tilt(& dark, -1.0).unwrap();
+        assert_eq!(measured, tilt);
+        let tilt = measure_audio_tilt(& corrected).unwrap();
+        assert!((tilt.tilt_db_per_octave + 1.0).abs() < 0.3, "{:?}", tilt);
+        assert!(measure_tilt(& white, 48_000, 1_000.0, 500.0).is_err());
+    }
+}
@@ -0,0 +1,231 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: State variable filter, SVF, the trapezoidal integrated SVF of Andrew
+//!              Simper (Cytomic), with the low-pass, high-pass, band-pass and notch
+//!              outputs from a single process_all() call.
+//!              The SVF is two integrators in a loop, and its state is the one of
+//!              the two integrators, the capacitors of the analog filter, not the
+//!              past samples of a direct form. With g = tan(pi fc / fs) and k = 1 / Q:
+//!
+//!                 v3 = x - ic2
+//!                 v1 = a1 ic1 + a2 v3          a1 = 1 / (1 + g (g + k))
+//!                 v2 = ic2 + a2 ic1 + a3 v3    a2 = g a1,  a3 = g a2
+//!                 ic1 = 2 v1 - ic1,  ic2 = 2 v2 - ic2
+//!
+//!                 low  = v2
+//!                 band = v1
+//!                 high = x - k v1 - v2
+//!                 notch = x - k v1 = low + high
+//!
+//!              For a fixed cutoff and Q the outputs are the same filters as the
+//!              cookbook biquads of make_lowpass(), make_highpass() and
+//!              make_bandpass(), with a peak gain of Q, and the notch is their
+//!              low-pass plus high-pass, the Q of make_notch() is a bandwidth. But
+//!              the cutoff and the Q can change at each sample with set_params(),
+//!              the state of the integrators stays valid, where the direct form
+//!              biquad with new coefficients at each sample can blow up, see the
+//!              test, a fast sweep of a high Q low-pass. For the automation of a
+//!              biquad at a slower rate see time_varying.rs .
+//!              As a ProcessingBlock the SVF outputs the one of its SvfMode.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Simper, A. - Linear Trapezoidal Integrated SVF, Cytomic, 2013
+//!       https://cytomic.com/files/dsp/SvfLinearTrapOptimised2.pdf
+//!
+//!    2. Chamberlin, H. - Musical Applications of Microprocessors, 1985
+//!
+//!    3. Zavalishin, V. - The Art of VA Filter Design, Native Instruments, 2018
+//!
+
+
+use std::f64::consts::PI;
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::exact_math;
+
+
+/// The output of the SVF as a ProcessingBlock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SvfMode {
+    LowPass,
+    HighPass,
+    BandPass,
+    Notch,
+}
+
+/// The outputs of a sample.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SvfOutputs {
+    pub low:   f64,
+    pub band:  f64,
+    pub high:  f64,
+    pub notch: f64,
+}
+
+/// The state variable filter.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut svf = StateVariableFilter::new(SvfMode::LowPass, 1_000.0, 48_000, 0.707)?;
+///    >>> for (n, x) in samples.iter_mut().enumerate() {
+///    >>>     svf.set_params(1_000.0 * f64::exp2(lfo[n]), 4.0)?;
+///    >>>     let outputs = svf.process_all(*x);
+///    >>>     *x = outputs.low - outputs.high;
+///    >>> }
+///    ```
+///
+#[derive(Debug, Clone)]
+pub struct StateVariableFilter {
+    mode:        SvfMode,
+    sample_rate: u32,
+    frequency:   f64,
+    q_factor:    f64,
+    // k = 1 / Q and the coefficients a1, a2, a3 .
+    k:           f64,
+    a1:          f64,
+    a2:          f64,
+    a3:          f64,
+    // The states of the two integrators.
+    ic1:         f64,
+    ic2:         f64,
+}
+
+impl StateVariableFilter {
+    pub fn new(mode: SvfMode, frequency: f64, sample_rate: u32, q_factor: f64) -> Result<Self, String> {
+        let mut filter = StateVariableFilter { mode, sample_rate, frequency, q_factor, k: 0.0, a1: 0.0, a2: 0.0,
+                                               a3: 0.0, ic1: 0.0, ic2: 0.0 };
+        filter.set_params(frequency, q_factor)?;
+        Ok(filter)
+    }
+
+    pub fn get_mode(& self) -> SvfMode {
+        self.mode
+    }
+
+    pub fn set_mode(& mut self, mode: SvfMode) {
+        self.mode = mode;
+    }
+
+    pub fn get_frequency(& self) -> f64 {
+        self.frequency
+    }
+
+    pub fn get_q_factor(& self) -> f64 {
+        self.q_factor
+    }
+
+    /// Changes the cutoff and the Q, keeping the state, at any sample. Gives an
+    /// error for a cutoff out of ]0, fs / 2[ or a Q not above 0 .
+    pub fn set_params(& mut self, frequency: f64, q_factor: f64) -> Result<(), String> {
+        let nyquist = self.sample_rate as f64 / 2.0;
+        if !(frequency > 0.0 && frequency < nyquist) {
+            return Err(format!("Error: the SVF cutoff must be between 0 and {} Hz, got {} Hz", nyquist, frequency));
+        }
+        if !(q_factor > 0.0 && q_factor.is_finite()) {
+            return Err(format!("Error: the SVF Q must be above 0, got {}", q_factor));
+        }
+        let g = exact_math::tan(PI * frequency / self.sample_rate as f64);
+        self.frequency = frequency;
+        self.q_factor = q_factor;
+        self.k = 1.0 / q_factor;
+        self.a1 = 1.0 / (1.0 + g * (g + self.k));
+        self.a2 = g * self.a1;
+        self.a3 = g * self.a2;
+        Ok(())
+    }
+
+    /// Clears the state of the integrators.
+    pub fn reset(& mut self) {
+        self.ic1 = 0.0;
+        self.ic2 = 0.0;
+    }
+
+    /// All the outputs of the sample.
+    pub fn process_all(& mut self, sample: f64) -> SvfOutputs {
+        let v3 = sample - self.ic2;
+        let v1 = self.a1 * self.ic1 + self.a2 * v3;
+        let v2 = self.ic2 + self.a2 * self.ic1 + self.a3 * v3;
+        self.ic1 = 2.0 * v1 - self.ic1;
+        self.ic2 = 2.0 * v2 - self.ic2;
+        let notch = sample - self.k * v1;
+        SvfOutputs { low: v2, band: v1, high: notch - v2, notch }
+    }
+}
+
+impl ProcessingBlock for StateVariableFilter {
+    fn process(& mut self, sample: f64) -> f64 {
+        let outputs = self.process_all(sample);
+        match self.mode {
+            SvfMode::LowPass  => outputs.low,
+            SvfMode::HighPass => outputs.high,
+            SvfMode::BandPass => outputs.band,
+            SvfMode::Notch    => outputs.notch,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iir_filter::IIRFilter;
+    use crate::butterworth_filter::make_bandpass;
+    use crate::butterworth_filter::make_highpass;
+    use crate::butterworth_filter::make_lowpass;
+
+    #[test]
+    fn test_outputs_are_the_cookbook_biquads() {
+        let signal: Vec<f64> = (0..2_000).map(|n| f64::sin(n as f64 * 0.05) + 0.5 * f64::sin(n as f64 * 1.7)).collect();
+        let mut svf = StateVariableFilter::new(SvfMode::BandPass, 2_000.0, 48_000, 3.0).unwrap();
+        let mut biquads = [make_lowpass(2_000.0, 48_000, Some(3.0)), make_highpass(2_000.0, 48_000, Some(3.0)),
+                           make_bandpass(2_000.0, 48_000, Some(3.0))];
+        for x in & signal {
+            let outputs = svf.process_all(*x);
+            let mut expected: Vec<f64> = biquads.iter_mut().map(|biquad| biquad.process(*x)).collect();
+            expected.push(expected[0] + expected[1]);
+            for (y, expected) in [outputs.low, outputs.high, outputs.band, outputs.notch].iter().zip(& expected) {
+                assert!((y - expected).abs() < 1e-9);
+            }
+            // The low-pass, the band-pass of gain 1 and the high-pass sum to the input.
+            assert!((outputs.low + outputs.band / 3.0 + outputs.high - x).abs() < 1e-12);
+        }
+        assert_eq!((svf.get_frequency(), svf.get_q_factor()), (2_000.0, 3.0));
+        let mut copy = svf.clone();
+        assert_eq!(svf.process(0.25), copy.process_all(0.25).band);
+        svf.set_mode(SvfMode::Notch);
+        svf.reset();
+        assert_eq!((svf.get_mode(), svf.process(0.0)), (SvfMode::Notch, 0.0));
+
+        assert!(StateVariableFilter::new(SvfMode::LowPass, 24_000.0, 48_000, 0.7).is_err());
+        assert!(StateVariableFilter::new(SvfMode::LowPass, 0.0, 48_000, 0.7).is_err());
+        assert!(svf.set_params(1_000.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_fast_modulation() {
+        // A low-pass of Q 5 swept by +/- 3.5 octaves at 1 kHz, new parameters at
+        // each sample. The direct form biquad blows up, the SVF doesn't.
+        let sample_rate = 48_000;
+        let cutoff = |n: usize| 1_000.0 * f64::exp2(3.5 * f64::sin(std::f64::consts::TAU * 1_000.0 * n as f64 / 48_000.0));
+        let input: Vec<f64> = (0..sample_rate as usize)
+            .map(|n| 0.5 * f64::sin(std::f64::consts::TAU * 110.0 * n as f64 / sample_rate as f64) + 0.3).collect();
+
+        let mut svf = StateVariableFilter::new(SvfMode::LowPass, cutoff(0), sample_rate, 5.0).unwrap();
+        let mut biquad = IIRFilter::new(2);
+        let (mut svf_peak, mut biquad_peak): (f64, f64) = (0.0, 0.0);
+        for (n, x) in input.iter().enumerate() {
+            svf.set_params(cutoff(n), 5.0).unwrap();
+            let design = make_lowpass(cutoff(n), sample_rate, Some(5.0));
+            biquad.set_coefficients(& design.a_coeffs, & design.b_coeffs).unwrap();
+            svf_peak = svf_peak.max(svf.process(*x).abs());
+            biquad_peak = biquad_peak.max(biquad.process(*x).abs());
+        }
+        assert!(svf_peak < 1.5, "{}", svf_peak);
+        assert!(biquad_peak > 1e6, "{}", biquad_peak);
+    }
+}
@@ -0,0 +1,281 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: A source of audio that decodes a file with the symphonia crate, with
+//!              the feature "symphonia", into a processing block for each channel,
+//!              like a chain or an equalizer, a packet at a time, when the samples
+//!              are pulled. The containers and the codecs are the default ones of
+//!              symphonia, WAV, FLAC, Ogg Vorbis, Matroska, ADPCM and PCM, and the
+//!              others, MP3, AAC, ALAC..., with the features of symphonia in the
+//!              Cargo.toml of the application.
+//!              The reader of an audio callback pulls the samples it needs, the
+//!              source decodes the next packets, about a thousand frames each, and
+//!              keeps the rest for the next call, so a file of any length plays
+//!              through an EQ to the speakers with the memory of a packet:
+//!
+//!                 file -> symphonia -> [block 0 (L), block 1 (R)] -> read_interleaved() -> speakers
+//!
+//!              The samples are interleaved, the f32 of the callback or any Sample
+//!              of convert.rs, rounded and clamped for the integer types. The
+//!              packets that don't decode are skipped, like a player does, and the
+//!              stream ends at the end of the file. The decoding and the blocks
+//!              allocate, so in a real-time callback the source runs in another
+//!              thread and sends the samples through the ring buffer of
+//!              ring_buffer.rs .
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    Symphonia - Pure Rust multimedia format demuxing, tag reading and audio decoding
+//!    https://github.com/pdeljanov/Symphonia
+//!
+
+
+use std::fs::File;
+use std::io::Cursor;
+use std::io::ErrorKind;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::Decoder;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::codecs::CODEC_TYPE_NULL;
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::formats::FormatReader;
+use symphonia::core::io::MediaSource;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::convert::Sample; // Trait
+use crate::audio_buffer::AudioBuffer;
+
+
+/// The decoded audio of a file through a block for each channel, pulled by the
+/// reader.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut source = DecodedSource::open("song.flac", |_| equalizer.clone())?;
+///    >>> // In the thread that feeds the audio callback, of 2 channels of f32.
+///    >>> let mut buffer = vec![0.0_f32; 2 * 512];
+///    >>> loop {
+///    >>>     let num_samples = source.read_interleaved(& mut buffer)?;
+///    >>>     producer.push_slice(& buffer[..num_samples]);
+///    >>>     if num_samples < buffer.len() { break; }
+///    >>> }
+///    ```
+///
+pub struct DecodedSource<B: ProcessingBlock> {
+    format:       Box<dyn FormatReader>,
+    decoder:      Box<dyn Decoder>,
+    track_id:     u32,
+    sample_rate:  u32,
+    blocks:       Vec<B>,
+    // The decoded packet, interleaved.
+    buffer:       Option<SampleBuffer<f64>>,
+    // The processed samples of the packet, interleaved, and the next one to read.
+    pending:      Vec<f64>,
+    position:     usize,
+    is_finished:  bool,
+}
+
+impl<B: ProcessingBlock> DecodedSource<B> {
+    /// Opens the file, of a container found by its content and its extension. The
+    /// factory is called with the index of each channel.
+    pub fn open<M: FnMut(usize) -> B>(path: & str, make_block: M) -> Result<Self, String> {
+        let _span = trace_span!("decoded_source_open", path);
+        let file = File::open(path).map_err(|error| format!("Error: can't open the file {}, {}", path, error))?;
+        let extension = std::path::Path::new(path).extension().and_then(|extension| extension.to_str());
+        DecodedSource::from_media_source(Box::new(file), extension, make_block)
+    }
+
+    /// The source of a file in memory, with the extension of its container if known.
+    pub fn from_bytes<M: FnMut(usize) -> B>(bytes: Vec<u8>, extension: Option<& str>, make_block: M)
+            -> Result<Self, String> {
+        DecodedSource::from_media_source(Box::new(Cursor::new(bytes)), extension, make_block)
+    }
+
+    fn from_media_source<M: FnMut(usize) -> B>(source: Box<dyn MediaSource>, extension: Option<& str>,
+                                               make_block: M) -> Result<Self, String> {
+        let mut hint = Hint::new();
+        if let Some(extension) = extension {
+            hint.with_extension(extension);
+        }
+        let stream = MediaSourceStream::new(source, Default::default());
+        let probed = symphonia::default::get_probe()
+            .format(& hint, stream, & FormatOptions::default(), & MetadataOptions::default())
+            .map_err(|error| format!("Error: the format of the audio isn't supported, {}", error))?;
+        let format = probed.format;
+        let track = format.tracks().iter()
+            .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or("Error: the file has no audio track")?;
+        let sample_rate = track.codec_params.sample_rate.ok_or("Error: the audio track has no sample rate")?;
+        let num_channels = track.codec_params.channels.ok_or("Error: the audio track has no channels")?.count();
+        let decoder = symphonia::default::get_codecs()
+            .make(& track.codec_params, & DecoderOptions::default())
+            .map_err(|error| format!("Error: the codec of the audio isn't supported, {}", error))?;
+        Ok(DecodedSource { track_id: track.id, format, decoder, sample_rate,
+                           blocks: (0..num_channels).map(make_block).collect(), buffer: None, pending: Vec::new(),
+                           position: 0, is_finished: false })
+    }
+
+    pub fn get_sample_rate(& self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn get_num_channels(& self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn get_block_mut(& mut self, channel: usize) -> Option<& mut B> {
+        self.blocks.get_mut(channel)
+    }
+
+    /// Fills the output with the next processed samples, interleaved, and returns
+    /// their number, less than the length of the output only at the end of the
+    /// stream.
+    pub fn read_interleaved<S: Sample>(& mut self, output: & mut [S]) -> Result<usize, String> {
+        let mut num_samples = 0;
+        while num_samples < output.len() {
+            if self.position == self.pending.len() && !self.decode_next_packet()? {
+                break;
+            }
+            let count = (self.pending.len() - self.position).min(output.len() - num_samples);
+            for (y, x) in output[num_samples..num_samples + count].iter_mut()
+                    .zip(& self.pending[self.position..self.position + count]) {
+                *y = S::from_f64(*x);
+            }
+            self.position += count;
+            num_samples += count;
+        }
+        Ok(num_samples)
+    }
+
+    /// The rest of the stream, processed.
+    pub fn read_to_end(& mut self) -> Result<AudioBuffer, String> {
+        let mut samples: Vec<f64> = self.pending.split_off(self.position);
+        self.pending.clear();
+        self.position = 0;
+        while self.decode_next_packet()? {
+            samples.append(& mut self.pending);
+        }
+        AudioBuffer::from_interleaved(self.sample_rate, self.get_num_channels(), & samples)
+    }
+
+    // Decodes and processes the next packet of the track into pending, false at the
+    // end of the stream.
+    fn decode_next_packet(& mut self) -> Result<bool, String> {
+        while !self.is_finished {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(SymphoniaError::IoError(error)) if error.kind() == ErrorKind::UnexpectedEof => {
+                    self.is_finished = true;
+                    break;
+                },
+                Err(error) => return Err(format!("Error: reading the audio, {}", error)),
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+            let decoded = match self.decoder.decode(& packet) {
+                Ok(decoded) => decoded,
+                Err(SymphoniaError::DecodeError(_)) => {
+                    trace_event!("packet skipped, it doesn't decode");
+                    continue;
+                },
+                Err(error) => return Err(format!("Error: decoding the audio, {}", error)),
+            };
+            let num_channels = self.blocks.len();
+            if decoded.spec().channels.count() != num_channels {
+                return Err(format!("Error: a packet of {} channels in a track of {} channels",
+                                   decoded.spec().channels.count(), num_channels));
+            }
+            if decoded.frames() == 0 {
+                continue;
+            }
+            if self.buffer.as_ref().is_none_or(|buffer| buffer.capacity() < decoded.capacity() * num_channels) {
+                self.buffer = Some(SampleBuffer::new(decoded.capacity() as u64, * decoded.spec()));
+            }
+            let buffer = self.buffer.as_mut().unwrap();
+            buffer.copy_interleaved_ref(decoded);
+            self.pending.clear();
+            self.position = 0;
+            for frame in buffer.samples().chunks_exact(num_channels) {
+                self.pending.extend(frame.iter().zip(self.blocks.iter_mut()).map(|(x, block)| block.process(*x)));
+            }
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_io::SampleFormat;
+    use crate::audio_io::decode_wav;
+    use crate::audio_io::encode_wav;
+    use crate::audio_io::write_wav;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::fixtures::temp_test_path;
+
+    fn make_audio(num_frames: usize) -> AudioBuffer {
+        let left:  Vec<f64> = (0..num_frames).map(|n| 0.5 * f64::sin(n as f64 * 0.03)).collect();
+        let right: Vec<f64> = (0..num_frames).map(|n| 0.5 * f64::sin(n as f64 * 1.3)).collect();
+        AudioBuffer::new(44_100, vec![left, right])
+    }
+
+    #[test]
+    fn test_pulled_in_small_reads() {
+        let bytes = encode_wav(& make_audio(10_000), SampleFormat::Int16).unwrap();
+        let (audio, _) = decode_wav(& bytes).unwrap();
+        let make = |channel: usize| make_lowpass(1_000.0 * (channel + 1) as f64, 44_100, None);
+        let mut expected = audio.clone();
+        expected.process_channels(& mut [make(0), make(1)]);
+        let expected = expected.to_interleaved();
+
+        let mut source = DecodedSource::from_bytes(bytes, Some("wav"), make).unwrap();
+        assert_eq!((source.get_sample_rate(), source.get_num_channels()), (44_100, 2));
+        // Reads of 333 samples, across the packets and the frames.
+        let mut output: Vec<f32> = Vec::new();
+        let mut buffer = [0.0_f32; 333];
+        loop {
+            let num_samples = source.read_interleaved(& mut buffer).unwrap();
+            output.extend_from_slice(& buffer[..num_samples]);
+            if num_samples < buffer.len() {
+                break;
+            }
+        }
+        assert_eq!(output.len(), expected.len());
+        assert!(output.iter().zip(& expected).all(|(y, x)| * y == * x as f32));
+        assert_eq!(source.read_interleaved(& mut buffer).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_read_file_to_end() {
+        let path = & temp_test_path("decoded_source.wav");
+        let audio = make_audio(5_000);
+        write_wav(path, & audio, SampleFormat::Float32).unwrap();
+
+        let mut source = DecodedSource::open(path, |_| make_lowpass(500.0, 44_100, None)).unwrap();
+        let mut head = [0_i16; 100];
+        assert_eq!(source.read_interleaved(& mut head).unwrap(), 100);
+        let rest = source.read_to_end().unwrap();
+        assert_eq!(rest.get_num_frames(), 5_000 - 50);
+        let mut expected = audio.clone();
+        expected.process_channels(& mut [make_lowpass(500.0, 44_100, None), make_lowpass(500.0, 44_100, None)]);
+        for (channel, expected) in rest.channels.iter().zip(& expected.channels) {
+            assert!(channel.iter().zip(& expected[50..]).all(|(x, y)| (x - y).abs() < 1e-6));
+        }
+        assert!(head.chunks_exact(2).zip(& expected.channels[0]).all(|(frame, x)| frame[0] == i16::from_f64(*x)));
+        let _ = std::fs::remove_file(path);
+
+        assert!(DecodedSource::open("audio_filters_test_missing.wav", |_| make_lowpass(500.0, 44_100, None)).is_err());
+        assert!(DecodedSource::from_bytes(vec![0; 1_000], None, |_| make_lowpass(500.0, 44_100, None)).is_err());
+    }
+}
@@ -0,0 +1,329 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Time-varying biquad filters, for parameter automation.
+//!              Sweeping a filter by crossfading the direct form coefficients between
+//!              two stable designs can pass through unstable coefficients, and a high
+//!              Q biquad can momentarily blow up. Here the biquad runs as a
+//!              lattice-ladder filter, and the interpolation is done on the reflection
+//!              coefficients k1 and k2 of the denominator. A biquad is stable if and
+//!              only if |k1| < 1 and |k2| < 1, that is a box, so any linear
+//!              interpolation between two stable designs is also stable. The
+//!              lattice structure also behaves well when the coefficients change.
+//!
+//!                 k2 = a2 ,  k1 = a1 / (1 + a2)       (with a0 = 1)
+//!
+//!              TimeVaryingBiquad calls a design function with the time, every
+//!              update interval samples, and interpolates the lattice coefficients
+//!              per sample from the current design to the new one.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Gray, A. H. and Markel, J. D. - Digital Lattice and Ladder Filter Synthesis.
+//!       IEEE Transactions on Audio and Electroacoustics, 1973
+//!
+//!    2. Lattice and ladder filters - Julius O. Smith III, Introduction to Digital Filters
+//!       https://ccrma.stanford.edu/~jos/filters/
+//!
+
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::iir_filter::IIRFilter;
+
+
+/// Coefficients of a 2nd order lattice-ladder filter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatticeCoefficients {
+    /// Reflection coefficients.
+    pub k1: f64,
+    pub k2: f64,
+    /// Ladder (tap) coefficients.
+    pub c0: f64,
+    pub c1: f64,
+    pub c2: f64,
+}
+
+impl LatticeCoefficients {
+    /// Converts the direct form coefficients of a biquad. Gives an error if the
+    /// filter isn't a biquad, or if it's unstable.
+    pub fn from_iir_filter(filter: & IIRFilter) -> Result<Self, String> {
+        if filter.order != 2 {
+            return Err(format!("Error: expected a biquad, got a filter of order {}", filter.order));
+        }
+        let a0 = filter.a_coeffs[0];
+        let (a1, a2) = (filter.a_coeffs[1] / a0, filter.a_coeffs[2] / a0);
+        let (b0, b1, b2) = (filter.b_coeffs[0] / a0, filter.b_coeffs[1] / a0, filter.b_coeffs[2] / a0);
+        let k2 = a2;
+        let k1 = a1 / (1.0 + a2);
+        let coefficients_t = LatticeCoefficients {
+            k1,
+            k2,
+            c2: b2,
+            c1: b1 - b2 * a1,
+            c0: b0 - (b1 - b2 * a1) * k1 - b2 * a2,
+        };
+        if !coefficients_t.is_stable() {
+            return Err(format!("Error: unstable biquad, reflection coefficients k1 = {} and k2 = {}", k1, k2));
+        }
+        Ok(coefficients_t)
+    }
+
+    pub fn is_stable(& self) -> bool {
+        self.k1.abs() < 1.0 && self.k2.abs() < 1.0
+    }
+
+    /// Linear interpolation, t = 0 is self and t = 1 is other.
+    pub fn interpolate(& self, other: & LatticeCoefficients, t: f64) -> LatticeCoefficients {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        LatticeCoefficients {
+            k1: lerp(self.k1, other.k1),
+            k2: lerp(self.k2, other.k2),
+            c0: lerp(self.c0, other.c0),
+            c1: lerp(self.c1, other.c1),
+            c2: lerp(self.c2, other.c2),
+        }
+    }
+}
+
+/// 2nd order lattice-ladder filter, with the same transfer function as the biquad
+/// it was made from.
+#[derive(Debug, Clone)]
+pub struct LatticeBiquad {
+    coefficients: LatticeCoefficients,
+    // g0[n-1] and g1[n-1], the backward outputs of the lattice stages.
+    g0_prev: f64,
+    g1_prev: f64,
+}
+
+impl LatticeBiquad {
+    pub fn new(coefficients: LatticeCoefficients) -> Self {
+        LatticeBiquad { coefficients, g0_prev: 0.0, g1_prev: 0.0 }
+    }
+
+    pub fn from_iir_filter(filter: & IIRFilter) -> Result<Self, String> {
+        Ok(LatticeBiquad::new(LatticeCoefficients::from_iir_filter(filter)?))
+    }
+
+    pub fn get_coefficients(& self) -> LatticeCoefficients {
+        self.coefficients
+    }
+
+    /// Changes the coefficients, keeping the state.
+    pub fn set_coefficients(& mut self, coefficients: LatticeCoefficients) {
+        self.coefficients = coefficients;
+    }
+}
+
+impl ProcessingBlock for LatticeBiquad {
+    fn process(& mut self, sample: f64) -> f64 {
+        let LatticeCoefficients { k1, k2, c0, c1, c2 } = self.coefficients;
+        let f1 = sample - k2 * self.g1_prev;
+        let f0 = f1 - k1 * self.g0_prev;
+        let g0 = f0;
+        let g1 = k1 * f0 + self.g0_prev;
+        let g2 = k2 * f1 + self.g1_prev;
+        self.g0_prev = g0;
+        self.g1_prev = g1;
+
+        c0 * g0 + c1 * g1 + c2 * g2
+    }
+}
+
+/// Automation curve, a parameter value over time from breakpoints.
+#[derive(Debug, Clone)]
+pub struct AutomationCurve {
+    // (time in seconds, value), sorted by time.
+    points:      Vec<(f64, f64)>,
+    logarithmic: bool,
+}
+
+impl AutomationCurve {
+    /// Linear interpolation between the breakpoints (time in seconds, value).
+    /// Before the first and after the last point the value is constant.
+    pub fn new(points: & [(f64, f64)]) -> Self {
+        assert!(!points.is_empty());
+        let mut points = points.to_vec();
+        points.sort_by(|a, b| a.0.total_cmp(& b.0));
+        AutomationCurve { points, logarithmic: false }
+    }
+
+    /// Interpolation in the logarithm of the values, a sweep at a constant number
+    /// of octaves per second for frequencies. All the values must be positive.
+    pub fn new_logarithmic(points: & [(f64, f64)]) -> Self {
+        assert!(points.iter().all(|(_, value)| *value > 0.0));
+        AutomationCurve { logarithmic: true, ..AutomationCurve::new(points) }
+    }
+
+    pub fn value_at(& self, time: f64) -> f64 {
+        let first = self.points[0];
+        let last = self.points[self.points.len() - 1];
+        if time <= first.0 {
+            return first.1;
+        }
+        if time >= last.0 {
+            return last.1;
+        }
+        let index = self.points.partition_point(|(t, _)| *t <= time);
+        let (t0, v0) = self.points[index - 1];
+        let (t1, v1) = self.points[index];
+        let fraction = (time - t0) / (t1 - t0);
+        if self.logarithmic {
+            v0 * (v1 / v0).powf(fraction)
+        } else {
+            v0 + (v1 - v0) * fraction
+        }
+    }
+}
+
+/// A biquad whose design follows the time, with stable per-sample interpolation.
+///
+/// In Rust:
+///    ```text
+///    >>> let frequency = AutomationCurve::new_logarithmic(& [(0.0, 200.0), (2.0, 8_000.0)]);
+///    >>> let mut filter = TimeVaryingBiquad::new(48_000, 32, move |time| {
+///    >>>     make_lowpass(frequency.value_at(time), 48_000, Some(10.0))
+///    >>> }).unwrap();
+///    >>> let output = filter.process(sample);
+///    ```
+///
+pub struct TimeVaryingBiquad<F: FnMut(f64) -> IIRFilter> {
+    design:          F,
+    sample_rate:     u32,
+    update_interval: usize,
+    lattice:         LatticeBiquad,
+    start:           LatticeCoefficients,
+    target:          LatticeCoefficients,
+    // Samples since the last design, and since the start.
+    counter:         usize,
+    time_samples:    u64,
+}
+
+impl<F: FnMut(f64) -> IIRFilter> TimeVaryingBiquad<F> {
+    /// The design function receives the time in seconds and returns a biquad, it's
+    /// called every update_interval samples (1 for every sample). Gives an error if
+    /// a design isn't a stable biquad.
+    pub fn new(sample_rate: u32, update_interval: usize, mut design: F) -> Result<Self, String> {
+        assert!(update_interval > 0);
+        let first = LatticeCoefficients::from_iir_filter(& design(0.0))?;
+        let target = LatticeCoefficients::from_iir_filter(& design(update_interval as f64 / sample_rate as f64))?;
+        Ok(TimeVaryingBiquad {
+            design,
+            sample_rate,
+            update_interval,
+            lattice: LatticeBiquad::new(first),
+            start: first,
+            target,
+            counter: 0,
+            time_samples: 0,
+        })
+    }
+
+    pub fn get_update_interval(& self) -> usize {
+        self.update_interval
+    }
+
+    /// Current lattice coefficients.
+    pub fn get_coefficients(& self) -> LatticeCoefficients {
+        self.lattice.get_coefficients()
+    }
+
+    /// Processes one sample. If the design at an update is an unstable biquad, it
+    /// returns an error, and the filter keeps moving to the last stable design.
+    pub fn try_process(& mut self, sample: f64) -> Result<f64, String> {
+        let (output, error) = self.step(sample);
+        match error {
+            None => Ok(output),
+            Some(error) => Err(error),
+        }
+    }
+
+    fn step(& mut self, sample: f64) -> (f64, Option<String>) {
+        let mut error = None;
+        if self.counter == self.update_interval {
+            self.counter = 0;
+            self.start = self.target;
+            let time = (self.time_samples + self.update_interval as u64) as f64 / self.sample_rate as f64;
+            match LatticeCoefficients::from_iir_filter(& (self.design)(time)) {
+                Ok(target) => self.target = target,
+                Err(e) => error = Some(e),
+            }
+        }
+        let t = self.counter as f64 / self.update_interval as f64;
+        self.lattice.set_coefficients(self.start.interpolate(& self.target, t));
+        self.counter += 1;
+        self.time_samples += 1;
+
+        (self.lattice.process(sample), error)
+    }
+}
+
+impl<F: FnMut(f64) -> IIRFilter> ProcessingBlock for TimeVaryingBiquad<F> {
+    // Unstable designs are ignored, the filter keeps the last stable one.
+    fn process(& mut self, sample: f64) -> f64 {
+        self.step(sample).0
+    }
+}
+
+/// Renders a signal through a time-varying biquad.
+pub fn render_time_varying<F: FnMut(f64) -> IIRFilter>(input: & [f64], sample_rate: u32, update_interval: usize,
+                                                       design: F) -> Result<Vec<f64>, String> {
+    let mut filter = TimeVaryingBiquad::new(sample_rate, update_interval, design)?;
+    input.iter().map(|x| filter.try_process(*x)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::butterworth_filter::make_lowpass;
+    use crate::butterworth_filter::make_peak;
+
+    #[test]
+    fn test_lattice_biquad_matches_direct_form() {
+        let mut filter = make_peak(1_000.0, 48_000, 9.0, Some(4.0));
+        let mut lattice = LatticeBiquad::from_iir_filter(& filter).unwrap();
+        for n in 0..500 {
+            let x = f64::sin(n as f64 * 0.37) + if n == 0 { 1.0 } else { 0.0 };
+            assert!((filter.process(x) - lattice.process(x)).abs() < 1e-12);
+        }
+        let mut unstable = IIRFilter::new(2);
+        unstable.set_coefficients(& [1.0, -2.0, 1.1], & [1.0, 0.0, 0.0]).unwrap();
+        assert!(LatticeBiquad::from_iir_filter(& unstable).is_err());
+    }
+
+    #[test]
+    fn test_automation_curve() {
+        let curve = AutomationCurve::new(& [(1.0, 10.0), (0.0, 0.0)]);
+        assert_eq!(curve.value_at(-1.0), 0.0);
+        assert_eq!(curve.value_at(0.25), 2.5);
+        assert_eq!(curve.value_at(5.0), 10.0);
+        let curve = AutomationCurve::new_logarithmic(& [(0.0, 100.0), (2.0, 10_000.0)]);
+        assert!((curve.value_at(1.0) - 1_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_time_varying_sweep_of_high_q_lowpass_is_stable() {
+        let sample_rate = 48_000;
+        // A fast sweep of a Q 30 resonant low-pass, down and up, over 0.1 s .
+        let frequency = AutomationCurve::new_logarithmic(& [(0.0, 20_000.0), (0.05, 30.0), (0.1, 20_000.0)]);
+        let input: Vec<f64> = (0..(sample_rate as usize / 10))
+            .map(|n| if n % 100 == 0 { 1.0 } else { 0.0 })
+            .collect();
+        for update_interval in [1, 64] {
+            let output = render_time_varying(& input, sample_rate, update_interval, |time| {
+                make_lowpass(frequency.value_at(time), sample_rate, Some(30.0))
+            }).unwrap();
+            assert!(output.iter().all(|y| y.is_finite() && y.abs() < 100.0));
+        }
+
+        // With constant parameters it's the same as the biquad.
+        let mut filter = TimeVaryingBiquad::new(sample_rate, 16, |_| make_lowpass(1_000.0, sample_rate, None)).unwrap();
+        let mut biquad = make_lowpass(1_000.0, sample_rate, None);
+        for x in & input {
+            assert!((filter.process(*x) - biquad.process(*x)).abs() < 1e-12);
+        }
+    }
+}
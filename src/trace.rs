@@ -0,0 +1,64 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Optional tracing instrumentation.
+//!              With the "tracing" feature, the crate emits spans of the tracing
+//!              crate around block processing and file I/O, and events on the
+//!              coefficient updates. An application that installs a subscriber
+//!              (for example tracing-subscriber) can then see what the filters are
+//!              doing and how long each block takes, when diagnosing glitches.
+//!
+//!                 cargo build --features tracing
+//!
+//!              Without the feature the macros expand to nothing, there is no
+//!              dependency and no cost.
+//!
+//!              trace_span!(name, fields...)  Debug level span, entered until the
+//!                                            returned guard is dropped.
+//!              trace_event!(fields..., msg)  Trace level event.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. tracing - Application-level tracing for Rust
+//!       https://docs.rs/tracing
+//!
+
+
+/// The guard of trace_span!() when the tracing feature is disabled.
+#[cfg(not(feature = "tracing"))]
+pub struct DisabledSpan;
+
+#[cfg(feature = "tracing")]
+macro_rules! trace_span {
+    ($($arg:tt)*) => { tracing::debug_span!($($arg)*).entered() };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_span {
+    ($($arg:tt)*) => { $crate::trace::DisabledSpan };
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! trace_event {
+    ($($arg:tt)*) => { tracing::trace!($($arg)*) };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_event {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_trace_macros() {
+        let block_size = 64;
+        let _span = trace_span!("test_block", block_size);
+        trace_event!(order = 2, "test coefficients updated");
+        assert_eq!(block_size, 64);
+    }
+}
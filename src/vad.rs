@@ -0,0 +1,291 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Voice activity detector (VAD).
+//!              The signal is cut in frames of 20 ms, and each frame is speech when:
+//!
+//!                 - its energy is well above the noise floor, tracked as the minimum
+//!                   of the frame energies, that rises slowly to follow a louder noise.
+//!                   The floor starts at the mean energy of the first 100 ms, that
+//!                   are never speech,
+//!                 - its spectrum in the voice band (300 Hz to 4 kHz) isn't flat. The
+//!                   spectral flatness, the geometric mean of the power spectrum over
+//!                   its arithmetic mean, is near 0 for the harmonics of a voice and
+//!                   about 0.56 for white noise.
+//!
+//!              After the last speech frame the decision is held for a hangover of
+//!              200 ms, so the pauses between words don't cut the speech segments.
+//!              The detector is a pass through ProcessingBlock, its decision is for
+//!              the last complete frame, and it can gate the learning of a noise
+//!              profile or drive an expander.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Voice activity detection - Wikipedia
+//!       https://en.wikipedia.org/wiki/Voice_activity_detection
+//!
+//!    2. Spectral flatness - Wikipedia
+//!       https://en.wikipedia.org/wiki/Spectral_flatness
+//!
+
+
+use std::f64::consts::TAU;
+use std::sync::Arc;
+use rustfft::Fft;
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+
+use crate::iir_filter::ProcessingBlock; // Trait
+
+
+const FRAME_MS: f64 = 20.0;
+const HANGOVER_MS: f64 = 200.0;
+const VOICE_BAND: (f64, f64) = (300.0, 4_000.0);
+// Frames quieter than this, in dBFS, are never speech.
+const MIN_SPEECH_LEVEL_DB: f64 = -70.0;
+// Rise of the noise floor per frame, 0.5 dB per second.
+const FLOOR_RISE_DB_PER_FRAME: f64 = 0.5 * FRAME_MS / 1_000.0;
+// Frames of the initial noise floor, 100 ms.
+const SEED_FRAMES: usize = 5;
+
+pub const DEFAULT_ENERGY_THRESHOLD_DB: f64 = 9.0;
+pub const DEFAULT_FLATNESS_THRESHOLD: f64 = 0.3;
+
+pub struct VoiceActivityDetector {
+    energy_threshold_db:  f64,
+    flatness_threshold:   f64,
+    frame:                Vec<f64>,
+    window:               Vec<f64>,
+    fft:                  Arc<dyn Fft<f64>>,
+    spectrum:             Vec<Complex<f64>>,
+    // The first and the last bins of the voice band.
+    voice_band_bins:      (usize, usize),
+    noise_floor_db:       Option<f64>,
+    // The sum of the energies of the first frames.
+    seed_energy:          f64,
+    frames_analyzed:      usize,
+    hangover_frames:      usize,
+    frames_since_speech:  usize,
+    speech:               bool,
+}
+
+impl VoiceActivityDetector {
+    /// The detector at the sample rate. Up to 600 Hz, twice the start of the voice
+    /// band, the band has at most one bin, its flatness is 1, and no frame is speech.
+    pub fn new(sample_rate: u32) -> Self {
+        // At least one sample, for a sample rate of a few Hz.
+        let frame_length = ((FRAME_MS * sample_rate as f64 / 1_000.0).round() as usize).max(1);
+        let fft_size = frame_length.next_power_of_two();
+        let bin_width = sample_rate as f64 / fft_size as f64;
+        let first = (VOICE_BAND.0 / bin_width).ceil() as usize;
+        let last = ((VOICE_BAND.1 / bin_width).floor() as usize).min(fft_size / 2);
+        VoiceActivityDetector {
+            energy_threshold_db: DEFAULT_ENERGY_THRESHOLD_DB,
+            flatness_threshold: DEFAULT_FLATNESS_THRESHOLD,
+            frame: Vec::with_capacity(frame_length),
+            window: (0..frame_length).map(|n| 0.5 - 0.5 * f64::cos(TAU * n as f64 / frame_length as f64)).collect(),
+            fft: FftPlanner::<f64>::new().plan_fft_forward(fft_size),
+            spectrum: vec![Complex::new(0.0, 0.0); fft_size],
+            voice_band_bins: (first, last),
+            noise_floor_db: None,
+            seed_energy: 0.0,
+            frames_analyzed: 0,
+            hangover_frames: (HANGOVER_MS / FRAME_MS) as usize,
+            frames_since_speech: usize::MAX,
+            speech: false,
+        }
+    }
+
+    /// How far, in dB, the energy of a speech frame is above the noise floor.
+    pub fn set_energy_threshold_db(& mut self, threshold_db: f64) {
+        assert!(threshold_db >= 0.0);
+        self.energy_threshold_db = threshold_db;
+    }
+
+    /// Maximum spectral flatness of a speech frame, from 0 to 1.
+    pub fn set_flatness_threshold(& mut self, threshold: f64) {
+        assert!(threshold > 0.0 && threshold <= 1.0);
+        self.flatness_threshold = threshold;
+    }
+
+    /// Number of samples of a frame.
+    pub fn get_frame_length(& self) -> usize {
+        self.window.len()
+    }
+
+    /// Decision for the last complete frame, with the hangover.
+    pub fn is_speech(& self) -> bool {
+        self.speech
+    }
+
+    /// The noise floor, in dBFS, None before the first frame.
+    pub fn get_noise_floor_db(& self) -> Option<f64> {
+        self.noise_floor_db
+    }
+
+    // Spectral flatness in the voice band, 1 for an empty band, like a flat spectrum.
+    fn voice_band_flatness(& mut self) -> f64 {
+        let (first, last) = self.voice_band_bins;
+        if first > last {
+            return 1.0;
+        }
+        for (n, value) in self.spectrum.iter_mut().enumerate() {
+            let sample = if n < self.frame.len() { self.frame[n] * self.window[n] } else { 0.0 };
+            *value = Complex::new(sample, 0.0);
+        }
+        self.fft.process(& mut self.spectrum);
+        // Avoids the log of 0 in digital silence.
+        let powers = self.spectrum[first..=last].iter().map(|x| x.norm_sqr() + 1e-30);
+        let count = (last - first + 1) as f64;
+        let (log_sum, sum) = powers.fold((0.0, 0.0), |(log_sum, sum), p| (log_sum + p.ln(), sum + p));
+        f64::exp(log_sum / count) / (sum / count)
+    }
+
+    fn analyze_frame(& mut self) {
+        let energy = self.frame.iter().map(|x| x * x).sum::<f64>() / self.frame.len() as f64;
+        let energy_db = 10.0 * f64::log10(energy + 1e-30);
+        self.frames_analyzed += 1;
+
+        let frame_is_speech = match self.noise_floor_db {
+            Some(noise_floor_db) if self.frames_analyzed > SEED_FRAMES => {
+                let frame_is_speech = energy_db > MIN_SPEECH_LEVEL_DB
+                    && energy_db > noise_floor_db + self.energy_threshold_db
+                    && self.voice_band_flatness() < self.flatness_threshold;
+                // The floor falls at once to a quieter frame, and rises slowly.
+                if energy_db < noise_floor_db {
+                    self.noise_floor_db = Some(energy_db);
+                } else if !frame_is_speech {
+                    self.noise_floor_db = Some(noise_floor_db + FLOOR_RISE_DB_PER_FRAME);
+                }
+                frame_is_speech
+            },
+            _ => {
+                // The first frames seed the floor with their mean energy.
+                self.seed_energy += energy;
+                let mean_energy = self.seed_energy / self.frames_analyzed as f64;
+                self.noise_floor_db = Some(10.0 * f64::log10(mean_energy + 1e-30));
+                false
+            },
+        };
+
+        if frame_is_speech {
+            self.frames_since_speech = 0;
+        } else {
+            self.frames_since_speech = self.frames_since_speech.saturating_add(1);
+        }
+        self.speech = self.frames_since_speech <= self.hangover_frames;
+        self.frame.clear();
+    }
+}
+
+impl ProcessingBlock for VoiceActivityDetector {
+    /// Pass through, the decision is updated at the end of each frame.
+    fn process(& mut self, sample: f64) -> f64 {
+        self.frame.push(sample);
+        if self.frame.len() == self.window.len() {
+            self.analyze_frame();
+        }
+        sample
+    }
+}
+
+/// The speech segments of the signal, as ranges of samples, on frame boundaries.
+///
+/// In Rust:
+///    ```text
+///    >>> for segment in detect_speech_segments(& recording, 16_000) {
+///    >>>     println!("speech from {} s to {} s", segment.start as f64 / 16_000.0,
+///    >>>              segment.end as f64 / 16_000.0);
+///    >>> }
+///    ```
+///
+pub fn detect_speech_segments(signal: & [f64], sample_rate: u32) -> Vec<std::ops::Range<usize>> {
+    let mut detector = VoiceActivityDetector::new(sample_rate);
+    let frame_length = detector.get_frame_length();
+    let mut segments: Vec<std::ops::Range<usize>> = Vec::new();
+    for (index, frame) in signal.chunks_exact(frame_length).enumerate() {
+        for x in frame {
+            detector.process(*x);
+        }
+        if detector.is_speech() {
+            let (start, end) = (index * frame_length, (index + 1) * frame_length);
+            match segments.last_mut() {
+                Some(segment) if segment.end == start => segment.end = end,
+                _ => segments.push(start..end),
+            }
+        }
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::make_white_noise;
+
+    // A voiced sound, harmonics of 150 Hz with a slow amplitude modulation.
+    fn voice(n: usize) -> f64 {
+        let t = n as f64 / 48_000.0;
+        let envelope = 0.6 + 0.4 * f64::sin(TAU * 4.0 * t);
+        envelope * (1..=20).map(|h| 0.1 / h as f64 * f64::sin(TAU * 150.0 * h as f64 * t)).sum::<f64>()
+    }
+
+    #[test]
+    fn test_speech_segments() {
+        let speech = [24_000..48_000, 72_000..105_600];
+        let signal: Vec<f64> = make_white_noise(144_000, 0.003, 987).iter().enumerate()
+            .map(|(n, x)| x + if speech.iter().any(|s| s.contains(& n)) { voice(n) } else { 0.0 })
+            .collect();
+        let segments = detect_speech_segments(& signal, 48_000);
+        assert_eq!(segments.len(), 2, "{:?}", segments);
+        for (segment, expected) in segments.iter().zip(& speech) {
+            // One frame at the start, and the hangover at the end.
+            assert!(segment.start >= expected.start && segment.start <= expected.start + 960);
+            assert!(segment.end >= expected.end && segment.end <= expected.end + 960 + 9_600 + 960);
+        }
+    }
+
+    #[test]
+    fn test_loud_noise_is_not_speech() {
+        let mut detector = VoiceActivityDetector::new(48_000);
+        let noise = make_white_noise(96_000, 0.5, 987);
+        let mut speech_frames = 0;
+        for chunk in noise.chunks(960) {
+            for x in chunk {
+                assert_eq!(detector.process(*x), *x);
+            }
+            speech_frames += detector.is_speech() as usize;
+        }
+        assert_eq!(speech_frames, 0);
+        // The power of an uniform noise of 0.5 is 0.25 / 3, or -10.8 dB .
+        assert!((detector.get_noise_floor_db().unwrap() + 10.8).abs() < 1.0);
+        assert!(detect_speech_segments(& vec![0.0; 48_000], 48_000).is_empty());
+    }
+
+    #[test]
+    fn test_noise_floor_seed_and_low_sample_rate() {
+        let mut detector = VoiceActivityDetector::new(48_000);
+        assert_eq!(detector.get_noise_floor_db(), None);
+        // A voice in the first frame only seeds the floor, a quieter frame after the
+        // seed brings it down.
+        for n in 0..960 {
+            detector.process(voice(n));
+        }
+        let voice_floor_db = detector.get_noise_floor_db().unwrap();
+        assert!(voice_floor_db.is_finite() && !detector.is_speech());
+        for x in make_white_noise(5 * 960, 0.003, 987) {
+            detector.process(x);
+        }
+        assert!(detector.get_noise_floor_db().unwrap() < voice_floor_db - 20.0);
+
+        // Without a voice band, nothing is speech and nothing panics.
+        for sample_rate in [0, 400, 600] {
+            let signal: Vec<f64> = (0..sample_rate as usize * 2).map(voice).collect();
+            assert!(detect_speech_segments(& signal, sample_rate).is_empty());
+        }
+    }
+}
@@ -0,0 +1,340 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Watch folder, the automatic processing of the new files of a
+//!              directory, for an ingest pipeline, like the uploads of a radio
+//!              station normalized to the loudness of the station.
+//!              The directory is scanned at an interval, without the notifications
+//!              of the OS, so it works the same on all the systems and on network
+//!              shares. A new WAV file is processed with the preset when it is
+//!              complete, when its size and its modification time didn't change
+//!              between two scans, so a file still being copied or uploaded isn't
+//!              read half written. The files already in the directory when the
+//!              watch starts aren't processed.
+//!              The output has the name of the input in the output directory, and
+//!              when a file of that name already exists, a number is added,
+//!              name_1.wav, name_2.wav ..., nothing is overwritten. The output is
+//!              written to name.wav.part and renamed when complete, for the next
+//!              step of the pipeline that watches the output directory.
+//!              The presets are the effect chains of effects/presets.rs, on each
+//!              channel, and "master", the mastering chain of mastering.rs . Both
+//!              keep the metadata of the input, its bext, cue and smpl chunks.
+//!              The output directory can't be the input directory or inside it, the
+//!              outputs would be processed again as new files, so the two paths are
+//!              resolved, with the symbolic links and the "..", and compared.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use crate::wav_metadata::read_wav_with_metadata;
+use crate::wav_metadata::write_wav_with_metadata;
+use crate::effects::presets::make_preset;
+use crate::effects::presets::PRESET_NAMES;
+use crate::mastering::MasteringSettings;
+use crate::mastering::master_file;
+use crate::multichannel_filter::MultiChannelFilter;
+
+
+/// Interval between the scans of the directory, in seconds.
+pub const DEFAULT_POLL_INTERVAL_SECONDS: f64 = 2.0;
+/// Name of the mastering preset, besides the effect presets of PRESET_NAMES.
+pub const MASTER_PRESET_NAME: &str = "master";
+// The suffix of an output file while it is written.
+const PARTIAL_SUFFIX: &str = ".part";
+
+// The size and the modification time of a file, unchanged when the file is complete.
+type FileState = (u64, SystemTime);
+
+/// The processing of the files of a watch folder.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchPreset {
+    /// An effect preset of PRESET_NAMES, on each channel.
+    Effect(String),
+    /// The mastering chain to the loudness and true peak targets.
+    Master(MasteringSettings),
+}
+
+impl WatchPreset {
+    /// The preset of a name of PRESET_NAMES, or "master" with the settings.
+    pub fn from_name(name: & str, master_settings: MasteringSettings) -> Result<Self, String> {
+        if name == MASTER_PRESET_NAME {
+            Ok(WatchPreset::Master(master_settings))
+        } else if PRESET_NAMES.contains(& name) {
+            Ok(WatchPreset::Effect(name.to_string()))
+        } else {
+            Err(format!("Error: unknown preset \"{}\", the presets are {}, {}", name, PRESET_NAMES.join(", "),
+                        MASTER_PRESET_NAME))
+        }
+    }
+
+    /// Processes the input file to the output file, in the sample format of the
+    /// input, with its metadata.
+    pub fn process_file(& self, input_path: & str, output_path: & str) -> Result<(), String> {
+        match self {
+            WatchPreset::Effect(name) => {
+                let (mut audio, sample_format, metadata) = read_wav_with_metadata(input_path)?;
+                let sample_rate = audio.sample_rate;
+                let mut presets = Vec::with_capacity(audio.get_num_channels());
+                for _ in 0..audio.get_num_channels() {
+                    presets.push(Some(make_preset(name, sample_rate)?));
+                }
+                let mut filter = MultiChannelFilter::from_factory(presets.len(), |channel| presets[channel].take().unwrap());
+                filter.process_buffer(& mut audio)?;
+                write_wav_with_metadata(output_path, & audio, sample_format.get_writable(), & metadata)
+            },
+            WatchPreset::Master(settings) => master_file(input_path, output_path, settings).map(|_| ()),
+        }
+    }
+}
+
+/// A file of the watch folder, processed or not.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchEvent {
+    pub input_path: PathBuf,
+    /// The output file, or the error of the processing.
+    pub result:     Result<PathBuf, String>,
+}
+
+/// The watch of an input directory, with the files seen and processed.
+pub struct WatchFolder {
+    input_dir:  PathBuf,
+    output_dir: PathBuf,
+    preset:     WatchPreset,
+    // The state of each new file at the last scan.
+    pending:    HashMap<PathBuf, FileState>,
+    // The files processed, or in the directory at the start.
+    done:       HashSet<PathBuf>,
+}
+
+impl WatchFolder {
+    /// Starts the watch of the input directory, the files already in it are
+    /// ignored. The output directory is created when it doesn't exist, an error
+    /// when it is the input directory or inside it.
+    pub fn new(input_dir: & Path, output_dir: & Path, preset: WatchPreset) -> Result<Self, String> {
+        if !input_dir.is_dir() {
+            return Err(format!("Error: the input directory {} doesn't exist", input_dir.display()));
+        }
+        if resolve_dir(output_dir)?.starts_with(resolve_dir(input_dir)?) {
+            return Err(format!("Error: the output directory {} is the input directory {} or inside it",
+                               output_dir.display(), input_dir.display()));
+        }
+        std::fs::create_dir_all(output_dir)
+            .map_err(|e| format!("Error: creating directory {}: {}", output_dir.display(), e))?;
+        let mut watch = WatchFolder {
+            input_dir: input_dir.to_path_buf(),
+            output_dir: output_dir.to_path_buf(),
+            preset,
+            pending: HashMap::new(),
+            done: HashSet::new(),
+        };
+        watch.done = watch.list_wav_files()?.into_iter().map(|(path, _)| path).collect();
+        Ok(watch)
+    }
+
+    /// Scans the directory once, and processes the new files that didn't change
+    /// since the previous scan. Returns an event for each file processed, an error
+    /// of a file isn't retried.
+    pub fn poll(& mut self) -> Result<Vec<WatchEvent>, String> {
+        let mut events = Vec::new();
+        let mut pending = HashMap::new();
+        for (path, state) in self.list_wav_files()? {
+            if self.done.contains(& path) {
+                continue;
+            }
+            if self.pending.get(& path) != Some(& state) {
+                pending.insert(path, state);
+                continue;
+            }
+            let result = self.process(& path);
+            trace_event!(input = %path.display(), ok = result.is_ok(), "watch folder file processed");
+            self.done.insert(path.clone());
+            events.push(WatchEvent { input_path: path, result });
+        }
+        self.pending = pending;
+        Ok(events)
+    }
+
+    /// Scans the directory at the interval, forever, and reports each event with
+    /// the function.
+    pub fn run<F>(& mut self, interval: Duration, mut report: F) -> Result<(), String>
+            where F: FnMut(& WatchEvent) {
+        loop {
+            for event in self.poll()? {
+                report(& event);
+            }
+            std::thread::sleep(interval);
+        }
+    }
+
+    fn process(& self, input_path: & Path) -> Result<PathBuf, String> {
+        let file_name = input_path.file_name().and_then(|name| name.to_str())
+            .ok_or(format!("Error: invalid file name {}", input_path.display()))?;
+        let output_path = collision_safe_path(& self.output_dir, file_name);
+        let partial_path = PathBuf::from(format!("{}{}", output_path.display(), PARTIAL_SUFFIX));
+        let to_str = |path: & Path| path.to_str().map(|s| s.to_string())
+            .ok_or(format!("Error: invalid path {}", path.display()));
+        let result = self.preset.process_file(& to_str(input_path)?, & to_str(& partial_path)?)
+            .and_then(|_| std::fs::rename(& partial_path, & output_path)
+                .map_err(|e| format!("Error: renaming {}: {}", partial_path.display(), e)));
+        if result.is_err() {
+            let _ = std::fs::remove_file(& partial_path);
+        }
+        result.map(|_| output_path)
+    }
+
+    // The WAV files of the input directory, with their size and modification time.
+    fn list_wav_files(& self) -> Result<Vec<(PathBuf, FileState)>, String> {
+        let entries = std::fs::read_dir(& self.input_dir)
+            .map_err(|e| format!("Error: reading directory {}: {}", self.input_dir.display(), e))?;
+        let mut files = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_wav = path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+            let is_hidden = path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with('.'));
+            if let (true, false, Ok(metadata)) = (is_wav, is_hidden, entry.metadata()) {
+                if metadata.is_file() {
+                    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    files.push((path, (metadata.len(), modified)));
+                }
+            }
+        }
+        files.sort_by(|a, b| a.0.cmp(& b.0));
+        Ok(files)
+    }
+}
+
+// The absolute path of a directory that may not exist yet, its nearest existing
+// ancestor canonicalized, with the symbolic links resolved, and the rest of the path.
+fn resolve_dir(dir: & Path) -> Result<PathBuf, String> {
+    let mut existing = dir.to_path_buf();
+    let mut missing = Vec::new();
+    while !existing.exists() {
+        let name = existing.file_name().ok_or(format!("Error: invalid directory {}", dir.display()))?;
+        missing.push(name.to_os_string());
+        existing = match existing.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+    }
+    let mut resolved = existing.canonicalize()
+        .map_err(|e| format!("Error: resolving directory {}: {}", dir.display(), e))?;
+    resolved.extend(missing.iter().rev());
+    Ok(resolved)
+}
+
+/// The path of the file name in the directory, with a number added before the
+/// extension when a file of that name, or its partial file, already exists.
+pub fn collision_safe_path(dir: & Path, file_name: & str) -> PathBuf {
+    let (stem, extension) = match file_name.rfind('.') {
+        Some(index) if index > 0 => (& file_name[..index], & file_name[index..]),
+        _                        => (file_name, ""),
+    };
+    let is_free = |path: & PathBuf| !path.exists()
+        && !PathBuf::from(format!("{}{}", path.display(), PARTIAL_SUFFIX)).exists();
+    let mut path = dir.join(file_name);
+    let mut number = 1;
+    while !is_free(& path) {
+        path = dir.join(format!("{}_{}{}", stem, number, extension));
+        number += 1;
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_buffer::AudioBuffer;
+    use crate::audio_io::SampleFormat;
+    use crate::audio_io::read_wav;
+    use crate::audio_io::write_wav;
+    use crate::wav_metadata::CuePoint;
+    use crate::wav_metadata::WavMetadata;
+    use crate::fixtures::temp_test_path;
+
+    fn make_dirs(name: & str) -> (PathBuf, PathBuf) {
+        let dir = PathBuf::from(temp_test_path(& format!("watch_{}", name)));
+        let _ = std::fs::remove_dir_all(& dir);
+        std::fs::create_dir_all(dir.join("input")).unwrap();
+        (dir.join("input"), dir.join("output"))
+    }
+
+    #[test]
+    fn test_watch_folder() {
+        let (input_dir, output_dir) = make_dirs("folder");
+        let tone: Vec<f64> = (0..4_800).map(|n| 0.5 * f64::sin(n as f64 * 0.1)).collect();
+        let audio = AudioBuffer::new(48_000, vec![tone.clone(), tone]);
+        let write = |name: & str| write_wav(input_dir.join(name).to_str().unwrap(), & audio, SampleFormat::Int16).unwrap();
+        write("old.wav");
+
+        let preset = WatchPreset::from_name("telephone", MasteringSettings::new(-14.0, -1.0)).unwrap();
+        let mut watch = WatchFolder::new(& input_dir, & output_dir, preset).unwrap();
+        write("upload.wav");
+        std::fs::write(input_dir.join("notes.txt"), "not audio").unwrap();
+        std::fs::write(input_dir.join("broken.wav"), "not a WAV file").unwrap();
+        // Seen once, and processed when it didn't change.
+        assert!(watch.poll().unwrap().is_empty());
+        let events = watch.poll().unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].result.is_err());
+        assert_eq!(events[1].result, Ok(output_dir.join("upload.wav")));
+        let (output, sample_format) = read_wav(output_dir.join("upload.wav").to_str().unwrap()).unwrap();
+        assert_eq!((output.get_num_channels(), sample_format), (2, SampleFormat::Int16));
+        assert!(watch.poll().unwrap().is_empty());
+
+        // The metadata of the input is kept.
+        let mut metadata = WavMetadata::new();
+        metadata.cue_points.push(CuePoint { id: 1, position: 2_400, label: Some("verse".to_string()) });
+        write_wav_with_metadata(input_dir.join("marked.wav").to_str().unwrap(), & audio, SampleFormat::Int16,
+                                & metadata).unwrap();
+        watch.poll().unwrap();
+        assert_eq!(watch.poll().unwrap()[0].result, Ok(output_dir.join("marked.wav")));
+        let (_, _, output_metadata) = read_wav_with_metadata(output_dir.join("marked.wav").to_str().unwrap()).unwrap();
+        assert_eq!(output_metadata.cue_points, metadata.cue_points);
+
+        // A file of the same name, uploaded again, doesn't overwrite the output.
+        write("take.wav");
+        std::fs::copy(output_dir.join("upload.wav"), output_dir.join("take.wav")).unwrap();
+        watch.poll().unwrap();
+        assert_eq!(watch.poll().unwrap()[0].result, Ok(output_dir.join("take_1.wav")));
+        assert!(!output_dir.join("old.wav").exists());
+        let names: Vec<String> = std::fs::read_dir(& output_dir).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_str().unwrap().to_string())
+            .collect();
+        assert!(names.iter().all(|name| !name.ends_with(PARTIAL_SUFFIX)));
+        let _ = std::fs::remove_dir_all(input_dir.parent().unwrap());
+    }
+
+    #[test]
+    fn test_presets_and_names() {
+        let (input_dir, output_dir) = make_dirs("names");
+        let settings = MasteringSettings::new(-16.0, -1.0);
+        assert_eq!(WatchPreset::from_name("master", settings.clone()).unwrap(), WatchPreset::Master(settings.clone()));
+        assert!(WatchPreset::from_name("loud", settings.clone()).is_err());
+        assert!(WatchFolder::new(& input_dir.join("missing"), & output_dir, WatchPreset::Master(settings.clone())).is_err());
+        // The outputs would be new files of the input directory.
+        let preset = WatchPreset::Master(settings);
+        for output in [input_dir.clone(), input_dir.join("."), input_dir.join("../input"), input_dir.join("out/mastered")] {
+            assert!(WatchFolder::new(& input_dir, & output, preset.clone()).is_err(), "{}", output.display());
+        }
+        assert!(!input_dir.join("out").exists());
+        assert!(WatchFolder::new(& input_dir, & input_dir.join("../input_out"), preset).is_ok());
+
+        std::fs::write(input_dir.join("a.wav"), "").unwrap();
+        std::fs::write(input_dir.join("a_1.wav.part"), "").unwrap();
+        assert_eq!(collision_safe_path(& input_dir, "b.wav"), input_dir.join("b.wav"));
+        assert_eq!(collision_safe_path(& input_dir, "a.wav"), input_dir.join("a_2.wav"));
+        assert_eq!(collision_safe_path(& input_dir, "README"), input_dir.join("README"));
+        let _ = std::fs::remove_dir_all(input_dir.parent().unwrap());
+    }
+}
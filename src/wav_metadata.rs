@@ -0,0 +1,533 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Metadata of WAV files, Broadcast WAV (BWF), cue points and loops.
+//!              A WAV file of a studio or a broadcaster carries more than the
+//!              samples: the bext chunk of the BWF, with the description, the
+//!              originator, the date and the time reference (the position of the
+//!              first sample in the timeline, in samples since midnight), the cue
+//!              points of the markers with their labels, and the loops of a
+//!              sampler. A file pipeline that drops them is not usable, so the
+//!              processing of files keeps them:
+//!
+//!                 bext          BextChunk, EBU Tech 3285 version 2, with the loudness.
+//!                 cue           CuePoint, the sample position of each marker.
+//!                 LIST adtl     The labl labels of the cue points.
+//!                 smpl          SamplerChunk, the MIDI note and the SampleLoop.
+//!
+//!              The other chunks (LIST INFO, iXML, ...) are kept as they are, and
+//!              written back after the data. The fields are public, to be edited
+//!              between the reading and the writing. The fact, JUNK and PAD chunks
+//!              are dropped, they describe the layout of the original file.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. EBU Tech 3285 - Specification of the Broadcast Wave Format (BWF), version 2
+//!       https://tech.ebu.ch/publications/tech3285
+//!
+//!    2. Multimedia Programming Interface and Data Specifications 1.0 - IBM and
+//!       Microsoft, the cue, LIST adtl and smpl chunks.
+//!
+
+
+use crate::audio_buffer::AudioBuffer;
+use crate::audio_io::SampleFormat;
+use crate::audio_io::ChunkSlice;
+use crate::audio_io::decode_wav;
+use crate::audio_io::encode_wav;
+use crate::audio_io::riff_chunks;
+use crate::audio_io::read_u16;
+use crate::audio_io::read_u32;
+
+
+// Size of the fixed part of the bext chunk, before the coding history.
+const BEXT_FIXED_SIZE: usize = 602;
+// Value of a loudness field of the bext chunk that wasn't measured.
+const BEXT_LOUDNESS_UNSET: i16 = 0x7FFF;
+
+/// The Broadcast Audio Extension chunk, the text fields are ASCII.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BextChunk {
+    /// At most 256 characters.
+    pub description:          String,
+    /// At most 32 characters.
+    pub originator:           String,
+    /// At most 32 characters.
+    pub originator_reference: String,
+    /// "yyyy-mm-dd".
+    pub origination_date:     String,
+    /// "hh:mm:ss".
+    pub origination_time:     String,
+    /// Position of the first sample, in samples since midnight.
+    pub time_reference:       u64,
+    pub version:              u16,
+    pub umid:                 [u8; 64],
+    /// Integrated loudness in LUFS, None when it wasn't measured.
+    pub loudness_value:       Option<f64>,
+    /// Loudness range in LU.
+    pub loudness_range:       Option<f64>,
+    /// Maximum true peak in dBTP.
+    pub max_true_peak_level:  Option<f64>,
+    pub max_momentary:        Option<f64>,
+    pub max_short_term:       Option<f64>,
+    /// The processing history, one line per step.
+    pub coding_history:       String,
+}
+
+impl BextChunk {
+    /// Empty fields, version 2 and the loudness not measured.
+    pub fn new(description: & str) -> Self {
+        BextChunk {
+            description: description.to_string(),
+            originator: String::new(),
+            originator_reference: String::new(),
+            origination_date: String::new(),
+            origination_time: String::new(),
+            time_reference: 0,
+            version: 2,
+            umid: [0; 64],
+            loudness_value: None,
+            loudness_range: None,
+            max_true_peak_level: None,
+            max_momentary: None,
+            max_short_term: None,
+            coding_history: String::new(),
+        }
+    }
+}
+
+/// A marker of the cue chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CuePoint {
+    pub id:       u32,
+    /// Sample frame of the marker.
+    pub position: u32,
+    /// The labl text of the cue point, in the LIST adtl chunk.
+    pub label:    Option<String>,
+}
+
+/// A loop of the smpl chunk, from the sample start to the sample end, included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleLoop {
+    pub cue_point_id: u32,
+    /// 0 forward, 1 alternating, 2 backward.
+    pub loop_type:    u32,
+    pub start:        u32,
+    pub end:          u32,
+    pub fraction:     u32,
+    /// 0 loops forever.
+    pub play_count:   u32,
+}
+
+/// The sampler chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SamplerChunk {
+    pub manufacturer:        u32,
+    pub product:             u32,
+    /// Duration of a sample in nanoseconds.
+    pub sample_period:       u32,
+    pub midi_unity_note:     u32,
+    pub midi_pitch_fraction: u32,
+    pub smpte_format:        u32,
+    pub smpte_offset:        u32,
+    pub loops:               Vec<SampleLoop>,
+    pub sampler_data:        Vec<u8>,
+}
+
+/// A chunk kept as it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RiffChunk {
+    pub id:   [u8; 4],
+    pub data: Vec<u8>,
+}
+
+/// The metadata of a WAV file, all but the fmt and data chunks.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WavMetadata {
+    pub bext:        Option<BextChunk>,
+    pub cue_points:  Vec<CuePoint>,
+    pub sampler:     Option<SamplerChunk>,
+    /// The sub chunks of LIST adtl other than labl, like note and ltxt.
+    pub adtl_chunks: Vec<RiffChunk>,
+    pub other:       Vec<RiffChunk>,
+}
+
+impl WavMetadata {
+    pub fn new() -> Self {
+        WavMetadata::default()
+    }
+
+    pub fn is_empty(& self) -> bool {
+        * self == WavMetadata::default()
+    }
+
+    /// Moves the sample positions, time reference, cue points and loops, to a new
+    /// sample rate, after a resampling.
+    pub fn rescale_positions(& mut self, from_sample_rate: u32, to_sample_rate: u32) {
+        let ratio = to_sample_rate as f64 / from_sample_rate as f64;
+        let scale = |position: u32| (position as f64 * ratio).round() as u32;
+        if let Some(bext) = & mut self.bext {
+            bext.time_reference = (bext.time_reference as f64 * ratio).round() as u64;
+        }
+        for cue in & mut self.cue_points {
+            cue.position = scale(cue.position);
+        }
+        if let Some(sampler) = & mut self.sampler {
+            sampler.sample_period = (sampler.sample_period as f64 / ratio).round() as u32;
+            for sample_loop in & mut sampler.loops {
+                sample_loop.start = scale(sample_loop.start);
+                sample_loop.end = scale(sample_loop.end);
+            }
+        }
+    }
+}
+
+/// Reads a WAV file with its metadata.
+///
+/// In Rust:
+///    ```text
+///    >>> let (audio, sample_format, mut metadata) = read_wav_with_metadata("take.wav")?;
+///    >>> if let Some(bext) = & mut metadata.bext {
+///    >>>     bext.coding_history.push_str("A=PCM,F=48000,W=24,M=stereo,T=audio_filters_in_rust\r\n");
+///    >>> }
+///    >>> write_wav_with_metadata("take_eq.wav", & processed, sample_format, & metadata)?;
+///    ```
+///
+pub fn read_wav_with_metadata(path: & str) -> Result<(AudioBuffer, SampleFormat, WavMetadata), String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Error: reading WAV file {}: {}", path, e))?;
+    let (audio, sample_format) = decode_wav(& bytes).map_err(|e| format!("{} ({})", e, path))?;
+    let metadata = decode_wav_metadata(& bytes).map_err(|e| format!("{} ({})", e, path))?;
+    Ok((audio, sample_format, metadata))
+}
+
+/// Writes a WAV file with the metadata.
+pub fn write_wav_with_metadata(path: & str, audio: & AudioBuffer, sample_format: SampleFormat,
+                               metadata: & WavMetadata) -> Result<(), String> {
+    let bytes = encode_wav_with_metadata(audio, sample_format, metadata)?;
+    std::fs::write(path, bytes).map_err(|e| format!("Error: writing WAV file {}: {}", path, e))
+}
+
+/// The metadata of the bytes of a WAV file.
+pub fn decode_wav_metadata(bytes: & [u8]) -> Result<WavMetadata, String> {
+    let mut metadata = WavMetadata::new();
+    let mut labels: Vec<(u32, String)> = Vec::new();
+    for (id, chunk) in riff_chunks(bytes)? {
+        match & id {
+            b"fmt " | b"data" | b"fact" | b"JUNK" | b"PAD " => {},
+            b"bext" => metadata.bext = Some(decode_bext(chunk)?),
+            b"cue " => metadata.cue_points = decode_cue(chunk)?,
+            b"smpl" => metadata.sampler = Some(decode_sampler(chunk)?),
+            b"LIST" if chunk.starts_with(b"adtl") => {
+                for (sub_id, sub_chunk) in list_sub_chunks(& chunk[4..]) {
+                    if & sub_id == b"labl" && sub_chunk.len() >= 4 {
+                        labels.push((read_u32(sub_chunk, 0), read_text(& sub_chunk[4..])));
+                    } else {
+                        metadata.adtl_chunks.push(RiffChunk { id: sub_id, data: sub_chunk.to_vec() });
+                    }
+                }
+            },
+            _ => metadata.other.push(RiffChunk { id, data: chunk.to_vec() }),
+        }
+    }
+    for (id, label) in labels {
+        if let Some(cue) = metadata.cue_points.iter_mut().find(|cue| cue.id == id) {
+            cue.label = Some(label);
+        }
+    }
+    Ok(metadata)
+}
+
+/// The bytes of a WAV file with the metadata, the bext chunk is before the data,
+/// like the BWF recommends, the other chunks after it.
+pub fn encode_wav_with_metadata(audio: & AudioBuffer, sample_format: SampleFormat, metadata: & WavMetadata)
+        -> Result<Vec<u8>, String> {
+    let plain = encode_wav(audio, sample_format)?;
+    // The RIFF header and the fmt chunk of encode_wav(), of 16 or 40 bytes.
+    let (header, data) = plain.split_at(20 + read_u32(& plain, 16) as usize);
+    let mut bytes = header.to_vec();
+    if let Some(bext) = & metadata.bext {
+        push_chunk(& mut bytes, b"bext", & encode_bext(bext)?);
+    }
+    bytes.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        bytes.push(0);
+    }
+    if !metadata.cue_points.is_empty() {
+        push_chunk(& mut bytes, b"cue ", & encode_cue(& metadata.cue_points));
+    }
+    let labelled: Vec<& CuePoint> = metadata.cue_points.iter().filter(|cue| cue.label.is_some()).collect();
+    if !labelled.is_empty() || !metadata.adtl_chunks.is_empty() {
+        let mut list = b"adtl".to_vec();
+        for cue in labelled {
+            let mut labl = cue.id.to_le_bytes().to_vec();
+            labl.extend_from_slice(cue.label.as_ref().unwrap().as_bytes());
+            labl.push(0);
+            push_chunk(& mut list, b"labl", & labl);
+        }
+        for chunk in & metadata.adtl_chunks {
+            push_chunk(& mut list, & chunk.id, & chunk.data);
+        }
+        push_chunk(& mut bytes, b"LIST", & list);
+    }
+    if let Some(sampler) = & metadata.sampler {
+        push_chunk(& mut bytes, b"smpl", & encode_sampler(sampler));
+    }
+    for chunk in & metadata.other {
+        push_chunk(& mut bytes, & chunk.id, & chunk.data);
+    }
+    if bytes.len() - 8 > u32::MAX as usize {
+        return Err("Error: the audio and metadata are too big for a WAV file".to_string());
+    }
+    let riff_size = (bytes.len() - 8) as u32;
+    bytes[4..8].copy_from_slice(& riff_size.to_le_bytes());
+    Ok(bytes)
+}
+
+// Appends a chunk, padded to an even size.
+fn push_chunk(bytes: & mut Vec<u8>, id: & [u8; 4], data: & [u8]) {
+    bytes.extend_from_slice(id);
+    bytes.extend_from_slice(& (data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        bytes.push(0);
+    }
+}
+
+// The sub chunks of a LIST chunk, after its type.
+fn list_sub_chunks(bytes: & [u8]) -> Vec<ChunkSlice<'_>> {
+    let mut chunks = Vec::new();
+    let mut position = 0;
+    while position + 8 <= bytes.len() {
+        let id = [bytes[position], bytes[position + 1], bytes[position + 2], bytes[position + 3]];
+        let size = read_u32(bytes, position + 4) as usize;
+        let end = usize::min(position + 8 + size, bytes.len());
+        chunks.push((id, & bytes[position + 8..end]));
+        position += 8 + size + (size & 1);
+    }
+    chunks
+}
+
+// The text up to the first 0.
+fn read_text(bytes: & [u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(& bytes[..end]).into_owned()
+}
+
+// The text in a field of fixed size, padded with 0.
+fn write_text(bytes: & mut Vec<u8>, text: & str, size: usize, name: & str) -> Result<(), String> {
+    if text.len() > size || !text.is_ascii() {
+        return Err(format!("Error: the bext {} must be ASCII of at most {} characters: \"{}\"", name, size, text));
+    }
+    bytes.extend_from_slice(text.as_bytes());
+    bytes.resize(bytes.len() + size - text.len(), 0);
+    Ok(())
+}
+
+fn decode_bext(chunk: & [u8]) -> Result<BextChunk, String> {
+    if chunk.len() < BEXT_FIXED_SIZE {
+        return Err("Error: WAV bext chunk too short".to_string());
+    }
+    let loudness = |position: usize| {
+        let value = read_u16(chunk, position) as i16;
+        if value == BEXT_LOUDNESS_UNSET { None } else { Some(value as f64 / 100.0) }
+    };
+    let version = read_u16(chunk, 346);
+    let mut umid = [0; 64];
+    umid.copy_from_slice(& chunk[348..412]);
+    Ok(BextChunk {
+        description: read_text(& chunk[0..256]),
+        originator: read_text(& chunk[256..288]),
+        originator_reference: read_text(& chunk[288..320]),
+        origination_date: read_text(& chunk[320..330]),
+        origination_time: read_text(& chunk[330..338]),
+        time_reference: read_u32(chunk, 338) as u64 | (read_u32(chunk, 342) as u64) << 32,
+        version,
+        umid,
+        // The loudness fields are in the version 2, before they are reserved zeros.
+        loudness_value: if version >= 2 { loudness(412) } else { None },
+        loudness_range: if version >= 2 { loudness(414) } else { None },
+        max_true_peak_level: if version >= 2 { loudness(416) } else { None },
+        max_momentary: if version >= 2 { loudness(418) } else { None },
+        max_short_term: if version >= 2 { loudness(420) } else { None },
+        coding_history: read_text(& chunk[BEXT_FIXED_SIZE..]),
+    })
+}
+
+fn encode_bext(bext: & BextChunk) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::with_capacity(BEXT_FIXED_SIZE + bext.coding_history.len());
+    write_text(& mut bytes, & bext.description, 256, "description")?;
+    write_text(& mut bytes, & bext.originator, 32, "originator")?;
+    write_text(& mut bytes, & bext.originator_reference, 32, "originator reference")?;
+    write_text(& mut bytes, & bext.origination_date, 10, "origination date")?;
+    write_text(& mut bytes, & bext.origination_time, 8, "origination time")?;
+    bytes.extend_from_slice(& bext.time_reference.to_le_bytes());
+    bytes.extend_from_slice(& bext.version.to_le_bytes());
+    bytes.extend_from_slice(& bext.umid);
+    for value in [bext.loudness_value, bext.loudness_range, bext.max_true_peak_level, bext.max_momentary,
+                  bext.max_short_term] {
+        let value = match value {
+            Some(value) => (value * 100.0).round().clamp(i16::MIN as f64, (BEXT_LOUDNESS_UNSET - 1) as f64) as i16,
+            None if bext.version >= 2 => BEXT_LOUDNESS_UNSET,
+            None => 0,
+        };
+        bytes.extend_from_slice(& value.to_le_bytes());
+    }
+    bytes.resize(BEXT_FIXED_SIZE, 0);
+    if !bext.coding_history.is_ascii() {
+        return Err("Error: the bext coding history must be ASCII".to_string());
+    }
+    bytes.extend_from_slice(bext.coding_history.as_bytes());
+    Ok(bytes)
+}
+
+fn decode_cue(chunk: & [u8]) -> Result<Vec<CuePoint>, String> {
+    if chunk.len() < 4 {
+        return Err("Error: WAV cue chunk too short".to_string());
+    }
+    let count = read_u32(chunk, 0) as usize;
+    if chunk.len() < 4 + 24 * count {
+        return Err(format!("Error: WAV cue chunk too short for {} cue points", count));
+    }
+    // The sample offset is the position of the marker in the data chunk.
+    Ok((0..count)
+        .map(|i| CuePoint { id: read_u32(chunk, 4 + 24 * i), position: read_u32(chunk, 4 + 24 * i + 20), label: None })
+        .collect())
+}
+
+fn encode_cue(cue_points: & [CuePoint]) -> Vec<u8> {
+    let mut bytes = (cue_points.len() as u32).to_le_bytes().to_vec();
+    for cue in cue_points {
+        bytes.extend_from_slice(& cue.id.to_le_bytes());
+        bytes.extend_from_slice(& cue.position.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(& [0; 8]);
+        bytes.extend_from_slice(& cue.position.to_le_bytes());
+    }
+    bytes
+}
+
+fn decode_sampler(chunk: & [u8]) -> Result<SamplerChunk, String> {
+    if chunk.len() < 36 {
+        return Err("Error: WAV smpl chunk too short".to_string());
+    }
+    let num_loops = read_u32(chunk, 28) as usize;
+    let data_size = read_u32(chunk, 32) as usize;
+    if chunk.len() < 36 + 24 * num_loops + data_size {
+        return Err(format!("Error: WAV smpl chunk too short for {} loops", num_loops));
+    }
+    let loops = (0..num_loops)
+        .map(|i| {
+            let field = |j: usize| read_u32(chunk, 36 + 24 * i + 4 * j);
+            SampleLoop { cue_point_id: field(0), loop_type: field(1), start: field(2), end: field(3),
+                         fraction: field(4), play_count: field(5) }
+        })
+        .collect();
+    let data_start = 36 + 24 * num_loops;
+    Ok(SamplerChunk {
+        manufacturer: read_u32(chunk, 0),
+        product: read_u32(chunk, 4),
+        sample_period: read_u32(chunk, 8),
+        midi_unity_note: read_u32(chunk, 12),
+        midi_pitch_fraction: read_u32(chunk, 16),
+        smpte_format: read_u32(chunk, 20),
+        smpte_offset: read_u32(chunk, 24),
+        loops,
+        sampler_data: chunk[data_start..data_start + data_size].to_vec(),
+    })
+}
+
+fn encode_sampler(sampler: & SamplerChunk) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(36 + 24 * sampler.loops.len() + sampler.sampler_data.len());
+    for value in [sampler.manufacturer, sampler.product, sampler.sample_period, sampler.midi_unity_note,
+                  sampler.midi_pitch_fraction, sampler.smpte_format, sampler.smpte_offset,
+                  sampler.loops.len() as u32, sampler.sampler_data.len() as u32] {
+        bytes.extend_from_slice(& value.to_le_bytes());
+    }
+    for sample_loop in & sampler.loops {
+        for value in [sample_loop.cue_point_id, sample_loop.loop_type, sample_loop.start, sample_loop.end,
+                      sample_loop.fraction, sample_loop.play_count] {
+            bytes.extend_from_slice(& value.to_le_bytes());
+        }
+    }
+    bytes.extend_from_slice(& sampler.sampler_data);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::temp_test_path;
+
+    fn make_metadata() -> WavMetadata {
+        let mut bext = BextChunk::new("Interview, take 3");
+        bext.originator = "Field recorder".to_string();
+        bext.origination_date = "2026-10-17".to_string();
+        bext.origination_time = "14:30:00".to_string();
+        bext.time_reference = 52 * 3_600 * 48_000 + 7;
+        bext.loudness_value = Some(-23.5);
+        bext.max_true_peak_level = Some(-1.25);
+        bext.coding_history = "A=PCM,F=48000,W=24,M=mono,T=recorder\r\n".to_string();
+        let mut metadata = WavMetadata::new();
+        metadata.bext = Some(bext);
+        metadata.cue_points = vec![CuePoint { id: 1, position: 10, label: Some("start".to_string()) },
+                                   CuePoint { id: 2, position: 75, label: None }];
+        metadata.sampler = Some(SamplerChunk {
+            manufacturer: 0, product: 0, sample_period: 20_833, midi_unity_note: 60, midi_pitch_fraction: 0,
+            smpte_format: 0, smpte_offset: 0,
+            loops: vec![SampleLoop { cue_point_id: 2, loop_type: 0, start: 20, end: 79, fraction: 0, play_count: 0 }],
+            sampler_data: vec![1, 2, 3],
+        });
+        metadata.adtl_chunks.push(RiffChunk { id: *b"note", data: vec![1, 0, 0, 0, b'x', 0] });
+        metadata.other.push(RiffChunk { id: *b"LIST", data: b"INFOISFT\x05\x00\x00\x00test\x00\x00".to_vec() });
+        metadata
+    }
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let audio = AudioBuffer::new(48_000, vec![(0..101).map(|n| n as f64 / 200.0).collect()]);
+        let metadata = make_metadata();
+        let bytes = encode_wav_with_metadata(& audio, SampleFormat::Int24, & metadata).unwrap();
+        assert_eq!(read_u32(& bytes, 4) as usize, bytes.len() - 8);
+        let chunk_ids: Vec<[u8; 4]> = riff_chunks(& bytes).unwrap().iter().map(|(id, _)| *id).collect();
+        assert_eq!(chunk_ids, vec![*b"fmt ", *b"bext", *b"data", *b"cue ", *b"LIST", *b"smpl", *b"LIST"]);
+        // The audio is unchanged and the plain reader skips the metadata.
+        assert_eq!(decode_wav(& bytes).unwrap(), decode_wav(& encode_wav(& audio, SampleFormat::Int24).unwrap()).unwrap());
+        assert_eq!(decode_wav_metadata(& bytes).unwrap(), metadata);
+        assert!(decode_wav_metadata(& encode_wav(& audio, SampleFormat::Int16).unwrap()).unwrap().is_empty());
+
+        // An edit, through a file.
+        let path = & temp_test_path("wav_metadata.wav");
+        let mut edited = metadata.clone();
+        edited.bext.as_mut().unwrap().description = "Interview, final".to_string();
+        edited.cue_points[1].label = Some("loop".to_string());
+        write_wav_with_metadata(path, & audio, SampleFormat::Float32, & edited).unwrap();
+        let (_, sample_format, read_back) = read_wav_with_metadata(path).unwrap();
+        assert_eq!(sample_format, SampleFormat::Float32);
+        assert_eq!(read_back, edited);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_invalid_metadata() {
+        let audio = AudioBuffer::new(48_000, vec![vec![0.0; 10]]);
+        let mut metadata = WavMetadata::new();
+        metadata.bext = Some(BextChunk::new(& "x".repeat(257)));
+        assert!(encode_wav_with_metadata(& audio, SampleFormat::Int16, & metadata).is_err());
+        metadata.bext = Some(BextChunk::new("café"));
+        assert!(encode_wav_with_metadata(& audio, SampleFormat::Int16, & metadata).is_err());
+
+        let mut metadata = make_metadata();
+        metadata.rescale_positions(48_000, 96_000);
+        assert_eq!(metadata.bext.as_ref().unwrap().time_reference, 2 * (52 * 3_600 * 48_000 + 7));
+        assert_eq!(metadata.cue_points[0].position, 20);
+        assert_eq!(metadata.sampler.as_ref().unwrap().loops[0].end, 158);
+
+        let mut bytes = encode_wav(& audio, SampleFormat::Int16).unwrap();
+        bytes.extend_from_slice(b"cue \x04\x00\x00\x00\x05\x00\x00\x00");
+        assert!(decode_wav_metadata(& bytes).is_err());
+    }
+}
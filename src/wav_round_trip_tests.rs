@@ -0,0 +1,135 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: End to end tests of WAV file -> filter chain -> WAV file.
+//!              A stereo file with test tones is written, read back, each channel
+//!              is processed by its own chain of known filters, the result is written
+//!              and read back again, and the level of each tone is measured, with a
+//!              single bin DFT over whole periods of the tone. The attenuation must be
+//!              the analytic response of the chain. This catches the bugs between the
+//!              pieces, like swapped channels, a wrong scaling of a sample format, or
+//!              a filter state shared between the channels, that the unit tests of
+//!              the coefficients don't see.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+
+
+use std::f64::consts::TAU;
+use rustfft::num_complex::Complex;
+
+use crate::iir_filter::ProcessingBlock; // Trait
+use crate::butterworth_filter::make_lowpass;
+use crate::butterworth_filter::make_highpass;
+use crate::butterworth_filter::make_peak;
+use crate::equalizer::Equalizer;
+use crate::audio_buffer::AudioBuffer;
+use crate::audio_io::SampleFormat;
+use crate::audio_io::read_wav;
+use crate::audio_io::write_wav;
+use crate::fixtures::temp_test_path;
+
+
+const SAMPLE_RATE: u32 = 48_000;
+// 1.2 s, the first 0.2 s are the transient of the filters.
+const NUM_FRAMES: usize = 57_600;
+const SETTLE_FRAMES: usize = 9_600;
+
+// Sum of the tones, each one with an amplitude of 0.2 .
+fn make_tones(frequencies: & [f64]) -> Vec<f64> {
+    (0..NUM_FRAMES)
+        .map(|n| frequencies.iter().map(|f| 0.2 * f64::sin(TAU * f * n as f64 / SAMPLE_RATE as f64)).sum())
+        .collect()
+}
+
+// Amplitude of the tone, a whole number of Hz, over one second after the transient.
+fn measure_tone(signal: & [f64], frequency: f64) -> f64 {
+    let window = & signal[SETTLE_FRAMES..SETTLE_FRAMES + SAMPLE_RATE as usize];
+    let sum: Complex<f64> = window.iter().enumerate()
+        .map(|(n, x)| Complex::from_polar(*x, -TAU * frequency * n as f64 / SAMPLE_RATE as f64))
+        .sum();
+    2.0 * sum.norm() / window.len() as f64
+}
+
+// Writes the audio, reads it back, processes each channel with its chain, and
+// writes and reads back the result.
+fn round_trip(name: & str, audio: & AudioBuffer, sample_format: SampleFormat,
+              chains: & mut [Vec<Box<dyn ProcessingBlock>>]) -> AudioBuffer {
+    let input_path = & temp_test_path(& format!("{}_input.wav", name));
+    let output_path = & temp_test_path(& format!("{}_output.wav", name));
+
+    write_wav(input_path, audio, sample_format).unwrap();
+    let (input, input_format) = read_wav(input_path).unwrap();
+    assert_eq!(input_format, sample_format);
+    assert_eq!(input.get_num_channels(), chains.len());
+    let channels = input.channels.iter().zip(chains.iter_mut())
+        .map(|(channel, chain)| channel.iter()
+            .map(|x| chain.iter_mut().fold(*x, |sample, block| block.process(sample)))
+            .collect())
+        .collect();
+    write_wav(output_path, & AudioBuffer::new(input.sample_rate, channels), SampleFormat::Float32).unwrap();
+    let (output, _) = read_wav(output_path).unwrap();
+
+    let _ = std::fs::remove_file(input_path);
+    let _ = std::fs::remove_file(output_path);
+    output
+}
+
+#[test]
+fn test_stereo_lowpass_highpass_round_trip() {
+    let audio = AudioBuffer::new(SAMPLE_RATE, vec![make_tones(& [100.0, 5_000.0]), make_tones(& [1_000.0, 10_000.0])]);
+    let mut chains: Vec<Vec<Box<dyn ProcessingBlock>>> = vec![
+        vec![Box::new(make_lowpass(1_000.0, SAMPLE_RATE, None))],
+        vec![Box::new(make_highpass(3_000.0, SAMPLE_RATE, None)), Box::new(make_peak(10_000.0, SAMPLE_RATE, -6.0, None))],
+    ];
+    let output = round_trip("stereo_chain", & audio, SampleFormat::Int24, & mut chains);
+    assert_eq!(output.sample_rate, SAMPLE_RATE);
+    assert_eq!(output.get_num_frames(), NUM_FRAMES);
+
+    let lowpass = make_lowpass(1_000.0, SAMPLE_RATE, None);
+    let highpass = make_highpass(3_000.0, SAMPLE_RATE, None);
+    let peak = make_peak(10_000.0, SAMPLE_RATE, -6.0, None);
+    let expected_left = |f: f64| 0.2 * lowpass.frequency_response(f, SAMPLE_RATE).norm();
+    let expected_right = |f: f64| 0.2 * (highpass.frequency_response(f, SAMPLE_RATE)
+                                         * peak.frequency_response(f, SAMPLE_RATE)).norm();
+    for frequency in [100.0, 5_000.0] {
+        let level = measure_tone(& output.channels[0], frequency);
+        assert!((level - expected_left(frequency)).abs() < 1e-4, "left {} Hz: {}", frequency, level);
+    }
+    for frequency in [1_000.0, 10_000.0] {
+        let level = measure_tone(& output.channels[1], frequency);
+        assert!((level - expected_right(frequency)).abs() < 1e-4, "right {} Hz: {}", frequency, level);
+    }
+    // No tone leaks to the other channel.
+    assert!(measure_tone(& output.channels[0], 1_000.0) < 1e-4);
+    assert!(measure_tone(& output.channels[1], 100.0) < 1e-4);
+}
+
+#[test]
+fn test_equalizer_round_trip_16_bit() {
+    let audio = AudioBuffer::new(SAMPLE_RATE, vec![make_tones(& [119.0, 3_770.0]), make_tones(& [119.0, 3_770.0])]);
+    let make_equalizer = || {
+        let mut equalizer = Equalizer::make_equalizer_10_band(SAMPLE_RATE);
+        for index in 0..equalizer.get_num_bands() {
+            if equalizer.get_bands_freq(index) == 3_770.0 {
+                equalizer.set_band_gain(index, -12.0).unwrap();
+            }
+        }
+        equalizer
+    };
+    let reference = make_equalizer();
+    let mut chains: Vec<Vec<Box<dyn ProcessingBlock>>> = vec![vec![Box::new(make_equalizer())], vec![Box::new(make_equalizer())]];
+    let output = round_trip("equalizer", & audio, SampleFormat::Int16, & mut chains);
+    for channel in & output.channels {
+        for frequency in [119.0, 3_770.0] {
+            let level = measure_tone(channel, frequency);
+            let expected = 0.2 * f64::powf(10.0, reference.magnitude_db(frequency) / 20.0);
+            // 16 bit quantization noise.
+            assert!((level - expected).abs() < 2e-4, "{} Hz: {} expected {}", frequency, level, expected);
+        }
+    }
+    assert!(reference.magnitude_db(3_770.0) < -10.0);
+}
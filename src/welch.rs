@@ -0,0 +1,333 @@
+//! Project: Audio filters in Rust
+//! Date:    2026.10.17
+//! Author:  João Nuno Carvalho
+//!
+//! Description: Power spectral density with the Welch method, and its confidence
+//!              intervals.
+//!              The periodogram of a whole noisy signal has a standard deviation
+//!              as large as its mean, at any length. Welch splits the signal in
+//!              overlapping segments, removes the trend of each one, applies a window,
+//!              and averages their periodograms, like scipy.signal.welch with the
+//!              "density" scaling:
+//!
+//!                 P[k] = 2 / (fs sum(w^2)) * mean over the segments of |FFT(w x)[k]|^2
+//!
+//!              one-sided, the DC and the Nyquist bins aren't doubled, in units^2 / Hz.
+//!              The sum of P[k] fs / L, over the bins, is the mean square of the
+//!              signal. The average of K segments is a chi-square variable with
+//!              about 2 K degrees of freedom, less with the overlap, the segments
+//!              are correlated. The equivalent degrees of freedom are
+//!
+//!                 v = 2 K / (1 + 2 sum_{l=1}^{K-1} (1 - l / K) rho(l D)^2)
+//!
+//!              with D the step between the segments and rho(s) the correlation of the
+//!              window with itself shifted by s samples. The confidence interval of a
+//!              level c is then
+//!
+//!                 [v P / chi2(v, (1 + c) / 2), v P / chi2(v, (1 - c) / 2)]
+//!
+//!              The quantiles of chi-square come from the Wilson-Hilferty
+//!              approximation, within 1 % for 3 or more degrees of freedom.
+//!
+//! License: MIT Open Source License, like the original license from
+//!    GitHub - TheAlgorithms / Python / audio_filters
+//!    https://github.com/TheAlgorithms/Python/tree/master/audio_filters
+//!
+//! References:
+//!    1. Welch, P. D. - The use of fast Fourier transform for the estimation of power
+//!       spectra. IEEE Transactions on Audio and Electroacoustics, 1967
+//!
+//!    2. Percival, D. B., Walden, A. T. - Spectral Analysis for Physical Applications.
+//!       Chapter 6.17, Welch's overlapped segment averaging
+//!
+//!    3. scipy.signal.welch
+//!       https://docs.scipy.org/doc/scipy/reference/generated/scipy.signal.welch.html
+//!
+
+
+use std::f64::consts::TAU;
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+
+use crate::analysis::remove_mean;
+use crate::analysis::remove_linear_trend;
+
+
+/// The window of the segments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WelchWindow {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+}
+
+impl WelchWindow {
+    /// The periodic window of the length, like scipy.signal.get_window.
+    pub fn make(& self, length: usize) -> Vec<f64> {
+        (0..length)
+            .map(|n| TAU * n as f64 / length as f64)
+            .map(|x| match self {
+                WelchWindow::Rectangular => 1.0,
+                WelchWindow::Hann        => 0.5 - 0.5 * f64::cos(x),
+                WelchWindow::Hamming     => 0.54 - 0.46 * f64::cos(x),
+                WelchWindow::Blackman    => 0.42 - 0.5 * f64::cos(x) + 0.08 * f64::cos(2.0 * x),
+            })
+            .collect()
+    }
+}
+
+/// The trend removed from each segment, before the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Detrend {
+    None,
+    /// The mean.
+    Constant,
+    /// The least squares line.
+    Linear,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WelchConfig {
+    pub sample_rate:    u32,
+    /// Samples of a segment, and the size of its FFT.
+    pub segment_length: usize,
+    /// Samples shared by two consecutive segments, below the segment length.
+    pub overlap:        usize,
+    pub window:         WelchWindow,
+    pub detrend:        Detrend,
+    /// The level of the confidence intervals, like 0.95, None for no intervals.
+    pub confidence:     Option<f64>,
+}
+
+impl WelchConfig {
+    /// A Hann window with 50 % overlap and the mean removed, like scipy, without
+    /// the confidence intervals.
+    pub fn new(sample_rate: u32, segment_length: usize) -> Self {
+        WelchConfig {
+            sample_rate,
+            segment_length,
+            overlap: segment_length / 2,
+            window: WelchWindow::Hann,
+            detrend: Detrend::Constant,
+            confidence: None,
+        }
+    }
+}
+
+/// The power spectral density, one-sided, in units^2 / Hz.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WelchPsd {
+    /// segment_length / 2 + 1 frequencies, from 0 Hz to Nyquist.
+    pub frequencies:        Vec<f64>,
+    pub psd:                Vec<f64>,
+    pub num_segments:       usize,
+    /// The equivalent degrees of freedom of each bin, with the overlap.
+    pub degrees_of_freedom: f64,
+    /// The lower and the upper bounds of each bin, with a confidence level.
+    pub confidence_bounds:  Option<(Vec<f64>, Vec<f64>)>,
+}
+
+impl WelchPsd {
+    /// The PSD in dB, 10 log10(P).
+    pub fn get_psd_db(& self) -> Vec<f64> {
+        self.psd.iter().map(|p| 10.0 * f64::log10(f64::max(* p, 1e-300))).collect()
+    }
+
+    /// The mean square of the signal in the band, the integral of the PSD.
+    pub fn get_band_power(& self, low_frequency: f64, high_frequency: f64) -> f64 {
+        let step = self.frequencies[1] - self.frequencies[0];
+        self.frequencies.iter().zip(& self.psd)
+            .filter(|(f, _)| **f >= low_frequency && **f <= high_frequency)
+            .map(|(_, p)| p * step)
+            .sum()
+    }
+}
+
+/// The Welch estimate of the power spectral density of the buffer.
+///
+/// In Rust:
+///    ```text
+///    >>> let mut config = WelchConfig::new(48_000, 4_096);
+///    >>> config.confidence = Some(0.95);
+///    >>> let estimate = welch_psd(& noise, & config)?;
+///    >>> let (lower, upper) = estimate.confidence_bounds.unwrap();
+///    ```
+///
+pub fn welch_psd(buffer: & [f64], config: & WelchConfig) -> Result<WelchPsd, String> {
+    let length = config.segment_length;
+    if length < 2 {
+        return Err(format!("Error: the segment length must be at least 2, got {}", length));
+    }
+    if config.overlap >= length {
+        return Err(format!("Error: the overlap of {} samples must be below the segment length {}",
+                           config.overlap, length));
+    }
+    if buffer.len() < length {
+        return Err(format!("Error: the buffer of {} samples is shorter than a segment of {}", buffer.len(), length));
+    }
+    if let Some(confidence) = config.confidence {
+        if confidence <= 0.0 || confidence >= 1.0 {
+            return Err(format!("Error: the confidence level must be between 0 and 1, got {}", confidence));
+        }
+    }
+    let _span = trace_span!("welch_psd", samples = buffer.len(), segment_length = length);
+    let window = config.window.make(length);
+    let window_power: f64 = window.iter().map(|w| w * w).sum();
+    let step = length - config.overlap;
+    let num_segments = (buffer.len() - length) / step + 1;
+    let num_bins = length / 2 + 1;
+
+    let fft = FftPlanner::<f64>::new().plan_fft_forward(length);
+    let mut psd = vec![0.0; num_bins];
+    let mut segment = vec![0.0; length];
+    let mut spectrum = vec![Complex::new(0.0, 0.0); length];
+    for index in 0..num_segments {
+        segment.copy_from_slice(& buffer[index * step..index * step + length]);
+        detrend(& mut segment, config.detrend);
+        for ((c, x), w) in spectrum.iter_mut().zip(& segment).zip(& window) {
+            *c = Complex::new(x * w, 0.0);
+        }
+        fft.process(& mut spectrum);
+        for (p, c) in psd.iter_mut().zip(& spectrum) {
+            *p += c.norm_sqr();
+        }
+    }
+    let scale = 1.0 / (config.sample_rate as f64 * window_power * num_segments as f64);
+    for (k, p) in psd.iter_mut().enumerate() {
+        // One-sided, the DC and Nyquist bins have no negative frequency.
+        let is_single = k == 0 || 2 * k == length;
+        *p *= if is_single { scale } else { 2.0 * scale };
+    }
+
+    let degrees_of_freedom = equivalent_degrees_of_freedom(& window, step, num_segments);
+    let confidence_bounds = config.confidence.map(|confidence| {
+        let lower_factor = degrees_of_freedom / chi_square_quantile((1.0 + confidence) / 2.0, degrees_of_freedom);
+        let upper_factor = degrees_of_freedom / chi_square_quantile((1.0 - confidence) / 2.0, degrees_of_freedom);
+        (psd.iter().map(|p| p * lower_factor).collect(), psd.iter().map(|p| p * upper_factor).collect())
+    });
+    Ok(WelchPsd {
+        frequencies: (0..num_bins).map(|k| k as f64 * config.sample_rate as f64 / length as f64).collect(),
+        psd,
+        num_segments,
+        degrees_of_freedom,
+        confidence_bounds,
+    })
+}
+
+fn detrend(segment: & mut [f64], detrend: Detrend) {
+    match detrend {
+        Detrend::None => (),
+        Detrend::Constant => { remove_mean(segment); },
+        Detrend::Linear => { remove_linear_trend(segment); },
+    }
+}
+
+// The degrees of freedom of the average of the overlapped segments.
+fn equivalent_degrees_of_freedom(window: & [f64], step: usize, num_segments: usize) -> f64 {
+    let window_power: f64 = window.iter().map(|w| w * w).sum();
+    let correlation = |shift: usize| -> f64 {
+        if shift >= window.len() {
+            return 0.0;
+        }
+        window.iter().zip(& window[shift..]).map(|(a, b)| a * b).sum::<f64>() / window_power
+    };
+    let k = num_segments as f64;
+    let sum: f64 = (1..num_segments)
+        .map(|l| (1.0 - l as f64 / k) * correlation(l * step).powi(2))
+        .sum();
+    2.0 * k / (1.0 + 2.0 * sum)
+}
+
+// The quantile of the standard normal distribution, Acklam's rational
+// approximation, with a relative error below 1.2e-9.
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e+01, 2.209460984245205e+02, -2.759285104469687e+02,
+                         1.38357751867269e+02, -3.066479806614716e+01, 2.506628277459239e+00];
+    const B: [f64; 5] = [-5.447609879822406e+01, 1.615858368580409e+02, -1.556989798598866e+02,
+                         6.680131188771972e+01, -1.328068155288572e+01];
+    const C: [f64; 6] = [-7.784894002430293e-03, -3.223964580411365e-01, -2.400758277161838e+00,
+                         -2.549732539343734e+00, 4.374664141464968e+00, 2.938163982698783e+00];
+    const D: [f64; 4] = [7.784695709041462e-03, 3.224671290700398e-01, 2.445134137142996e+00,
+                         3.754408661907416e+00];
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < 0.02425 {
+        tail(f64::sqrt(-2.0 * p.ln()))
+    } else if p > 1.0 - 0.02425 {
+        -tail(f64::sqrt(-2.0 * (1.0 - p).ln()))
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+// The quantile of chi-square, with the Wilson-Hilferty approximation.
+fn chi_square_quantile(p: f64, degrees_of_freedom: f64) -> f64 {
+    let a = 2.0 / (9.0 * degrees_of_freedom);
+    degrees_of_freedom * f64::max(1.0 - a + normal_quantile(p) * a.sqrt(), 0.0).powi(3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::make_white_noise;
+
+    #[test]
+    fn test_white_noise_density_and_intervals() {
+        // Uniform white noise on [-1, 1[, of variance 1 / 3.
+        let noise = make_white_noise(48_000 * 4, 1.0, 12_345);
+        let mut config = WelchConfig::new(48_000, 1_024);
+        config.confidence = Some(0.95);
+        let estimate = welch_psd(& noise, & config).unwrap();
+        assert_eq!(estimate.psd.len(), 513);
+        assert_eq!(estimate.num_segments, 374);
+        assert_eq!(estimate.frequencies[512], 24_000.0);
+        // The flat density 2 sigma^2 / fs, and the power of the whole band.
+        let expected = 2.0 / 3.0 / 48_000.0;
+        let mean = estimate.psd[1..512].iter().sum::<f64>() / 511.0;
+        assert!((mean / expected - 1.0).abs() < 0.01, "{} {}", mean, expected);
+        assert!((estimate.get_band_power(0.0, 24_000.0) * 3.0 - 1.0).abs() < 0.01);
+
+        // With the overlap of the Hann window, less than 2 K degrees of freedom.
+        let dof = estimate.degrees_of_freedom;
+        assert!(dof > 1.8 * 374.0 && dof < 2.0 * 374.0, "{}", dof);
+        let (lower, upper) = estimate.confidence_bounds.unwrap();
+        let inside = (1..512).filter(|k| lower[*k] <= expected && expected <= upper[*k]).count() as f64 / 511.0;
+        assert!(inside > 0.9 && inside < 0.99, "{}", inside);
+
+        // Without overlap and a rectangular window, 2 K exactly.
+        config.overlap = 0;
+        config.window = WelchWindow::Rectangular;
+        let estimate = welch_psd(& noise, & config).unwrap();
+        assert_eq!(estimate.degrees_of_freedom, 2.0 * estimate.num_segments as f64);
+        assert!((chi_square_quantile(0.975, 10.0) - 20.483).abs() < 0.1);
+        assert!((normal_quantile(0.975) - 1.959964).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_detrend_and_sine_power() {
+        // A sine of amplitude 0.5, power 0.125, on a ramp.
+        let signal: Vec<f64> = (0..48_000)
+            .map(|n| 0.5 * f64::sin(TAU * 1_000.0 * n as f64 / 48_000.0) + 0.3 + n as f64 * 1e-5)
+            .collect();
+        let mut config = WelchConfig::new(48_000, 2_048);
+        let estimate = welch_psd(& signal, & config).unwrap();
+        assert!((estimate.get_band_power(900.0, 1_100.0) / 0.125 - 1.0).abs() < 0.01);
+
+        config.detrend = Detrend::Linear;
+        let linear = welch_psd(& signal, & config).unwrap();
+        config.detrend = Detrend::None;
+        let none = welch_psd(& signal, & config).unwrap();
+        assert!(linear.get_band_power(0.0, 50.0) < 1e-4 * none.get_band_power(0.0, 50.0));
+        assert!(none.get_psd_db()[0] > linear.get_psd_db()[0] + 40.0);
+
+        assert!(welch_psd(& signal[..100], & config).is_err());
+        config.overlap = 2_048;
+        assert!(welch_psd(& signal, & config).is_err());
+    }
+}